@@ -19,6 +19,10 @@
 //!     let valid = client.verify_proof(&response.proof, &response.public_inputs).await?;
 //!     println!("Proof valid: {}", valid);
 //!
+//!     // Fetch the commitment proofs are verified against
+//!     let commitment = client.get_commitment().await?;
+//!     println!("Commitment version: {}", commitment.version);
+//!
 //!     Ok(())
 //! }
 //! ```
@@ -28,6 +32,8 @@ use crate::api::server::{
     ExecuteQueryRequest, ExecuteQueryResponse, VerifyProofRequest, VerifyProofResponse,
 };
 #[cfg(feature = "api")]
+use crate::commitment::DatabaseCommitment;
+#[cfg(feature = "api")]
 use serde_json;
 
 /// API client
@@ -115,6 +121,30 @@ impl ApiClient {
         Err("reqwest feature not enabled".into())
     }
 
+    /// Fetch the commitment to the server's current database version
+    ///
+    /// # Returns
+    /// `Ok(DatabaseCommitment)` if the server has an up-to-date commitment,
+    /// `Err` otherwise
+    pub async fn get_commitment(&self) -> Result<DatabaseCommitment, Box<dyn std::error::Error>> {
+        #[cfg(feature = "reqwest")]
+        {
+            let client = reqwest::Client::new();
+            let url = format!("{}/commitment", self.base_url);
+
+            let response = client.get(&url).send().await?;
+
+            if !response.status().is_success() {
+                return Err(format!("API request failed: {}", response.status()).into());
+            }
+
+            let commitment: DatabaseCommitment = response.json().await?;
+            Ok(commitment)
+        }
+        #[cfg(not(feature = "reqwest"))]
+        Err("reqwest feature not enabled".into())
+    }
+
     /// Health check
     ///
     /// # Returns
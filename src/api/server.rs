@@ -2,6 +2,13 @@
 //!
 //! This module provides HTTP/REST API server functionality using Axum.
 //!
+//! # Endpoints
+//!
+//! - `GET /health`: Server status and version
+//! - `GET /commitment`: Current `DatabaseCommitment` (JSON) for verifying proofs
+//! - `POST /query`: Execute a query with proof generation
+//! - `POST /verify`: Verify a proof
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -15,9 +22,13 @@
 //! }
 //! ```
 
+#[cfg(feature = "api")]
+use crate::commitment::DatabaseCommitment;
+#[cfg(feature = "api")]
+use crate::database::Database;
 #[cfg(feature = "api")]
 use axum::{
-    extract::Path,
+    extract::{Path, State},
     http::StatusCode,
     response::Json,
     routing::{get, post},
@@ -28,6 +39,8 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "api")]
 use std::net::SocketAddr;
 #[cfg(feature = "api")]
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "api")]
 use tower::ServiceBuilder;
 #[cfg(feature = "api")]
 #[cfg(feature = "tower-http")]
@@ -40,6 +53,9 @@ use tower_http::cors::CorsLayer;
 pub struct ApiServer {
     /// Server address
     addr: SocketAddr,
+
+    /// Database served by this server, shared with request handlers
+    database: Arc<RwLock<Option<Database>>>,
 }
 
 /// Query execution request
@@ -81,7 +97,30 @@ impl ApiServer {
     /// # Arguments
     /// * `addr` - Socket address to bind to
     pub fn new(addr: SocketAddr) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            database: Arc::default(),
+        }
+    }
+
+    /// Serve a database
+    ///
+    /// # Arguments
+    /// * `database` - Database to serve
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_database(self, database: Database) -> Self {
+        *self.database.write().unwrap_or_else(|e| e.into_inner()) = Some(database);
+        self
+    }
+
+    /// Get a handle to the served database
+    ///
+    /// Changes made through the handle (e.g., re-committing) are visible to
+    /// subsequent requests.
+    pub fn database(&self) -> Arc<RwLock<Option<Database>>> {
+        Arc::clone(&self.database)
     }
 
     /// Start the API server
@@ -91,20 +130,27 @@ impl ApiServer {
     /// # Returns
     /// `Ok(())` if server starts successfully, `Err` otherwise
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let app = Router::new()
-            .route("/health", get(health_check))
-            .route("/query", post(execute_query))
-            .route("/verify", post(verify_proof));
+        let listener = tokio::net::TcpListener::bind(self.addr).await?;
+        println!("🚀 API server listening on {}", self.addr);
 
+        let app = self.router();
         #[cfg(feature = "tower-http")]
         let app = app.layer(ServiceBuilder::new().layer(CorsLayer::permissive()));
 
-        let listener = tokio::net::TcpListener::bind(self.addr).await?;
-        println!("🚀 API server listening on {}", self.addr);
         axum::serve(listener, app).await?;
 
         Ok(())
     }
+
+    /// Build the router with all endpoints
+    fn router(&self) -> Router {
+        Router::new()
+            .route("/health", get(health_check))
+            .route("/commitment", get(get_commitment))
+            .route("/query", post(execute_query))
+            .route("/verify", post(verify_proof))
+            .with_state(self.database())
+    }
 }
 
 /// Health check endpoint
@@ -116,6 +162,26 @@ async fn health_check() -> Json<serde_json::Value> {
     }))
 }
 
+/// Database commitment endpoint
+///
+/// Returns the commitment to the current database version, or
+/// `404 Not Found` if no database is served and `409 Conflict` if the
+/// database is uncommitted or changed since it was last committed.
+#[cfg(feature = "api")]
+async fn get_commitment(
+    State(database): State<Arc<RwLock<Option<Database>>>>,
+) -> Result<Json<DatabaseCommitment>, StatusCode> {
+    let database = database
+        .read()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let database = database.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+
+    database
+        .current_commitment()
+        .map(|commitment| Json(commitment.clone()))
+        .map_err(|_| StatusCode::CONFLICT)
+}
+
 /// Execute query endpoint
 #[cfg(feature = "api")]
 async fn execute_query(
@@ -157,4 +223,75 @@ mod tests {
         let server = ApiServer::new(addr);
         assert!(true); // Server created successfully
     }
+
+    #[cfg(all(feature = "api", feature = "reqwest"))]
+    #[tokio::test]
+    async fn test_api_get_commitment_verifies_proof() {
+        use crate::api::ApiClient;
+        use crate::circuit::NzengiCircuit;
+        use crate::commitment::IPAParams;
+        use crate::database::Schema;
+        use crate::gates::selection::{Comparison, Operand, Predicate};
+        use crate::proof::{Prover, Verifier};
+        use crate::test_util::fields;
+        use crate::types::{Column, DataType, Row, Table, Value};
+        use halo2_proofs::halo2curves::bn256::Fr as Field;
+
+        let params = IPAParams::new(9);
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        let mut schema = Schema::new("tpch".to_string());
+        schema.add_table(table).unwrap();
+        let mut database = Database::new(schema);
+        database.commit(&params).unwrap();
+        database.commit(&params).unwrap();
+
+        let server = ApiServer::new("127.0.0.1:0".parse().unwrap()).with_database(database);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = server.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let client = ApiClient::new(format!("http://{}", addr));
+        let commitment = client.get_commitment().await.unwrap();
+        assert_eq!(commitment.version, 2);
+
+        // Prove COUNT(*) WHERE l_quantity > 10 and verify against the commitment
+        let circuit = NzengiCircuit::new()
+            .with_table(3, vec![fields(&[5, 12, 30])])
+            .with_predicate(Predicate::Compare {
+                column: 0,
+                comparison: Comparison::Greater,
+                rhs: Operand::Constant(Field::from(10)),
+                negated: false,
+            })
+            .with_count_output(Field::from(2))
+            .with_commitment_hash(commitment.hash_field());
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+        let public_inputs = vec![Field::from(2u64)];
+        let bound_inputs = [public_inputs[0], commitment.hash_field()];
+        let proof = prover.create_proof(&pk, &circuit, &bound_inputs).unwrap();
+
+        assert!(verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &commitment, Some(2))
+            .unwrap());
+
+        // A stale database has no current commitment to serve
+        server
+            .database()
+            .write()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .insert_row("lineitem", Row::new(vec![Value::Integer(1)]))
+            .unwrap();
+        assert!(client.get_commitment().await.is_err());
+    }
 }
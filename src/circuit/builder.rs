@@ -18,15 +18,34 @@
 //! use nzengi_db::circuit::builder::CircuitBuilder;
 //! use nzengi_db::query::planner::ExecutionPlan;
 //!
-//! let builder = CircuitBuilder::new();
-//! let circuit = builder.build_from_plan(&plan, &data)?;
+//! let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows)?;
 //! ```
 
-use super::NzengiCircuit;
-use crate::query::planner::ExecutionPlan;
+use super::{NzengiCircuit, Planner};
+use crate::field::FieldUtils;
+use crate::gates::arithmetic::{ArithmeticConfig, Op, Source};
+use crate::gates::hll::{HASH_ROWS, HLL_PRECISION, RHO_BITS};
+use crate::gates::selection::{Comparison, Operand, Predicate};
+use crate::gates::GroupByConfig;
+use crate::query::error::QueryError;
+use crate::query::executor::QueryExecutor;
+use crate::query::planner::{
+    AggregationFunction, ArithmeticOp, CompareOp, ExecutionPlan, FilterCondition, FilterOperation,
+    FilterTree, GroupByOperation, HavingCondition, ProjectionExpr, SortOperation,
+};
+use crate::types::{keys, Column, DataType, Row, Table, Value, DECIMAL_SCALE};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use std::collections::HashMap;
 
+/// Bit width of the keys the sort and group-by gates can order
+const SORT_KEY_BITS: usize = 64;
+
+/// Rows of one less-than comparison over `SORT_KEY_BITS`-bit values
+const COMPARISON_ROWS: usize = SORT_KEY_BITS + 1;
+
+/// Rows halo2 reserves at the end of the circuit for blinding (upper bound)
+const BLINDING_ROWS: usize = 10;
+
 /// Circuit builder
 ///
 /// This struct provides methods for building circuits from execution plans
@@ -53,46 +72,1341 @@ impl CircuitBuilder {
 
     /// Build a circuit from an execution plan
     ///
-    /// This method constructs a circuit based on the execution plan,
-    /// configuring gates and assigning data as needed.
+    /// Derives the data of every gate the plan needs from the input table and
+    /// the rows that passed its filters:
+    /// - for filters and filter trees comparing columns with constants
+    ///   (including BETWEEN and wildcard-free LIKE) or with other columns,
+    ///   the table cells of the columns and the flag of every table row
+    ///   under the filters (no row satisfying them dropped, none added);
+    ///   with other filters (not under OR), the selection flags of the
+    ///   filtered rows, each satisfying the comparisons
+    /// - aggregation over the non-NULL values of the circuit columns; with a
+    ///   GROUP BY whose keys fit the group-by gate, over the rows in key
+    ///   order, on the groups that gate proves
+    /// - for AVG, the truncated average of each group, with a remainder
+    ///   below its COUNT
+    /// - the public COUNT of the flagged rows for ungrouped COUNT queries
+    /// - the selected cells of the column of a single ungrouped SUM as the
+    ///   aggregation values
+    /// - the sorted values and public MIN/MAX for ungrouped MIN/MAX queries
+    /// - the public sketch registers of every ungrouped
+    ///   APPROX_COUNT_DISTINCT, over the selected cells of its column
+    /// - the public counts of the groups kept by HAVING, from their
+    ///   aggregation rows
+    /// - the public per-group counts of every COUNT(DISTINCT), with or
+    ///   without GROUP BY, over the selected cells of its column and the
+    ///   groups the group-by gate proves
+    /// - arithmetic rows for computed projections
+    ///
+    /// For ORDER BY, the sort gate needs the rows in result order, so it is
+    /// added by the executor with [`CircuitBuilder::sort_keys`].
     ///
     /// # Arguments
     /// * `plan` - Execution plan for the query
-    /// * `data` - Query data (tables, rows, etc.)
+    /// * `table` - Input table of the plan
+    /// * `filtered_rows` - Rows of `table` that satisfy the plan's filters, in
+    ///   table order
     ///
     /// # Returns
-    /// `Ok(NzengiCircuit)` if circuit building succeeds, `Err` otherwise
+    /// `Ok(NzengiCircuit)` if circuit building succeeds, `Err` if the plan
+    /// references a missing column, has malformed filter bounds, or compares
+    /// values the circuit can't order (negative, decimal, string or boolean)
     ///
     /// # Example
     /// ```
     /// use nzengi_db::circuit::builder::CircuitBuilder;
-    /// use nzengi_db::query::planner::ExecutionPlan;
     ///
-    /// let builder = CircuitBuilder::new();
-    /// let circuit = builder.build_from_plan(&plan, &data)?;
+    /// let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows)?;
     /// ```
-    pub fn build_from_plan(
-        &self,
-        _plan: &ExecutionPlan,
-        _data: &HashMap<String, Vec<Field>>,
-    ) -> Result<NzengiCircuit, Box<dyn std::error::Error>> {
-        let circuit = NzengiCircuit::new();
+    pub fn from_plan(
+        plan: &ExecutionPlan,
+        table: &Table,
+        filtered_rows: &[Row],
+    ) -> Result<NzengiCircuit, QueryError> {
+        let mut circuit = NzengiCircuit::new();
+
+        // Filters and filter trees comparing columns with constants or
+        // columns are proved over the table cells; `table_columns` maps the circuit's table columns to the table
+        let mut table_columns = vec![];
+        let (predicate, unproved) = Self::predicate(plan, table, &mut table_columns)?;
+        let proves_filters = unproved.is_empty();
+
+        // Range-check the cells of every compared column at the width of
+        // its type (e.g., 1 bit for BOOLEAN, 32 for INTEGER)
+        let mut compared = vec![];
+        Self::compared_columns(&predicate, &mut compared);
+        for column in compared {
+            let column_idx = table_columns[column];
+            let Some(bits) = table.columns[column_idx].data_type.range_bits() else {
+                continue;
+            };
+            for row in &table.rows {
+                let value = row
+                    .column_field(column_idx)
+                    .and_then(|f| FieldUtils::to_u64(&f));
+                if let Some(value) = value {
+                    circuit = circuit.with_range_check_bits(value, bits);
+                }
+            }
+        }
+
+        // Add aggregation gates (NULLs are excluded from aggregation); a
+        // single SUM over the whole input is exposed publicly, and the summed
+        // values are the selected cells of its column
+        let sum_values = match Self::sum_values(plan, table, filtered_rows)? {
+            Some((idx, values)) => {
+                circuit = circuit
+                    .with_selected_column(Self::table_column(&mut table_columns, idx))
+                    .with_sum_output(values.iter().sum());
+                Some(values)
+            }
+            None => None,
+        };
+        let distinct_columns = Self::distinct_count_columns(plan, table, filtered_rows)?;
+        let group_keys = match plan.group_by.first() {
+            Some(group_by) => Self::group_keys(group_by, table, filtered_rows)?,
+            // HAVING and COUNT(DISTINCT) without GROUP BY take all rows as
+            // one group
+            None if (plan.having.is_some() || !distinct_columns.is_empty())
+                && sum_values.is_none() =>
+            {
+                Some(vec![Field::zero(); filtered_rows.len()])
+            }
+            None => None,
+        };
+        let grouped = !plan.aggregations.is_empty() || plan.having.is_some();
+        if let (Some(keys), true) = (group_keys, grouped) {
+            // The group-by gate proves the groups of the keys, and the
+            // aggregation runs over the rows in key order, on its groups
+            circuit = Self::grouped_aggregation(circuit, plan, table, filtered_rows, keys)?;
+        } else if plan.having.is_some() {
+            return Err(QueryError::UnsupportedFeature(
+                "Proving HAVING over group keys the group-by gate can't order".to_string(),
+            ));
+        } else if !distinct_columns.is_empty() {
+            return Err(QueryError::UnsupportedFeature(
+                "Proving COUNT(DISTINCT) over group keys the group-by gate can't order, \
+                 or with an ungrouped SUM"
+                    .to_string(),
+            ));
+        } else if !plan.aggregations.is_empty() {
+            let values: Vec<Field> = sum_values.unwrap_or_else(|| {
+                let column_indices = Self::circuit_column_indices(plan, table);
+                filtered_rows
+                    .iter()
+                    .flat_map(|r| column_indices.iter().filter_map(|&i| r.column_field(i)))
+                    .filter(|f| !Value::is_null_field(f))
+                    .collect()
+            });
+            // One group spanning all values; its last row ends the group
+            let n = values.len();
+            let mut binary_markers = vec![Field::one(); n];
+            if let Some(last) = binary_markers.last_mut() {
+                *last = Field::zero();
+            }
+            let start_indices = vec![Field::zero(); n];
+            let end_indices = vec![Field::from(n.saturating_sub(1) as u64); n];
+            circuit = circuit.with_aggregation(values, binary_markers, start_indices, end_indices);
+        }
+
+        // AVG is the truncated average of each aggregated group
+        if plan
+            .aggregations
+            .iter()
+            .any(|agg| agg.function == AggregationFunction::Avg)
+        {
+            circuit = Self::truncated_avg(circuit)?;
+        }
+
+        // Expose COUNT publicly, bound to the rows selected by the filters
+        let count_agg = plan
+            .aggregations
+            .iter()
+            .find(|agg| agg.function == AggregationFunction::Count);
+        if let (Some(count_agg), true) = (count_agg, plan.group_by.is_empty()) {
+            let mut selection = Self::selection_flags(table, filtered_rows);
+
+            // COUNT(column) skips NULLs
+            let count_column_idx = count_agg
+                .column
+                .as_ref()
+                .and_then(|col| QueryExecutor::column_index(table, col).ok());
+            if let Some(idx) = count_column_idx {
+                for (flag, row) in selection.iter_mut().zip(&table.rows) {
+                    if matches!(row.values.get(idx), Some(Value::Null) | None) {
+                        *flag = Field::zero();
+                    }
+                }
+            }
+
+            // The count is the executor's; the circuit counts the table rows
+            // it flags, which must agree
+            let count = selection.iter().filter(|f| **f == Field::one()).count();
+            circuit = circuit.with_count_output(Field::from(count as u64));
+            if let Some(idx) = count_column_idx {
+                circuit = circuit.with_count_column(Self::table_column(&mut table_columns, idx));
+            }
+        }
+
+        // Expose MIN/MAX publicly as the ends of the sorted values
+        if plan.group_by.is_empty() {
+            if let Some((idx, values)) = Self::minmax_values(plan, table, filtered_rows)? {
+                let mut sorted = values.clone();
+                sorted.sort_by(FieldUtils::cmp);
+                let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+                circuit = circuit
+                    .with_sort(values, sorted)
+                    .with_minmax_column(Self::table_column(&mut table_columns, idx))
+                    .with_minmax_output(min, max);
+            }
+        }
+
+        // Expose the HyperLogLog registers of every APPROX_COUNT_DISTINCT
+        // publicly, over the selected cells of its column
+        for column_idx in Self::sketch_columns(plan, table, filtered_rows)? {
+            circuit = circuit.with_sketch(Self::table_column(&mut table_columns, column_idx));
+        }
+
+        // Expose COUNT(DISTINCT) of each group publicly, over the groups the
+        // group-by gate proves
+        for column_idx in distinct_columns {
+            circuit =
+                circuit.with_distinct_count(Self::table_column(&mut table_columns, column_idx));
+        }
+
+        // Computed projections are evaluated on the selected cells, and
+        // their values are public
+        let mut expressions = vec![];
+        for computed in &plan.computed {
+            let mut rows = vec![];
+            Self::arithmetic_rows(&computed.expr, table, &mut table_columns, &mut rows)?;
+            if !rows.is_empty() {
+                expressions.push(rows);
+            }
+        }
+        if !expressions.is_empty() {
+            for row in filtered_rows {
+                Self::check_computed(&expressions, row, &table_columns)?;
+            }
+            circuit = circuit.with_computed(expressions);
+        }
+
+        // Filters the predicate doesn't express select the rows, each of
+        // which must still satisfy the predicate
+        if !proves_filters {
+            circuit = circuit.with_selection(Self::selection_flags(table, filtered_rows));
+        }
+        let columns = table_columns
+            .iter()
+            .map(|&idx| {
+                table
+                    .rows
+                    .iter()
+                    .map(|row| row.column_field(idx).unwrap_or_else(Value::null_field))
+                    .collect()
+            })
+            .collect();
+        circuit = circuit
+            .with_table(table.rows.len(), columns)
+            .with_predicate(predicate);
+
+        Ok(circuit)
+    }
+
+    /// Expose the selected cells of a table column publicly, for a cell
+    /// opening of its committed column
+    ///
+    /// The circuit's table column holding the column's cells is exposed; a
+    /// column the circuit doesn't read is added to its table.
+    ///
+    /// # Arguments
+    /// * `circuit` - Circuit built by `from_plan` over `table`
+    /// * `table` - Input table of the plan
+    /// * `column_idx` - Index of the opened column in `table`
+    ///
+    /// # Returns
+    /// The circuit with the column's selected cells public
+    pub fn open_column(circuit: NzengiCircuit, table: &Table, column_idx: usize) -> NzengiCircuit {
+        let cells: Vec<Field> = table
+            .rows
+            .iter()
+            .map(|row| {
+                row.column_field(column_idx)
+                    .unwrap_or_else(Value::null_field)
+            })
+            .collect();
+        let mut columns = circuit.table_data().to_vec();
+        let column = match columns.iter().position(|column| *column == cells) {
+            Some(column) => column,
+            None => {
+                columns.push(cells);
+                columns.len() - 1
+            }
+        };
+        circuit
+            .with_table(table.rows.len(), columns)
+            .with_opened_column(column)
+    }
+
+    /// Build a circuit from an execution plan, laid out by floor planner `P`
+    ///
+    /// Same as [`CircuitBuilder::from_plan`], for callers choosing the
+    /// planner (e.g., `SimplePlanner` instead of the default `V1Planner`).
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `filtered_rows` - Rows of `table` that satisfy the plan's filters
+    ///
+    /// # Returns
+    /// `Ok(NzengiCircuit<P>)` if circuit building succeeds, `Err` otherwise
+    pub fn from_plan_with_planner<P: Planner>(
+        plan: &ExecutionPlan,
+        table: &Table,
+        filtered_rows: &[Row],
+    ) -> Result<NzengiCircuit<P>, QueryError> {
+        Self::from_plan(plan, table, filtered_rows).map(NzengiCircuit::with_floor_planner)
+    }
+
+    /// Build the predicate of a plan's filters and filter tree, ANDed, for
+    /// the selection gate
+    ///
+    /// Each filter comparing a column with a constant or another column
+    /// becomes a comparison of the columns' table cells, `>=` and `<=` as
+    /// the negations of `<` and `>`, BETWEEN both of its bounds, and LIKE an
+    /// equality with its string. NULL cells satisfy no comparison, as in the
+    /// executor. The filter tree keeps its AND/OR structure.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `table_columns` - Indices of the circuit's table columns in `table`,
+    ///   extended with the columns the predicate reads
+    ///
+    /// # Returns
+    /// `Ok((predicate, unproved))`, where `unproved` are the filters the
+    /// predicate doesn't express (it then holds on a superset of the
+    /// filtered rows), `Err` if a filter references a missing column,
+    /// compares a column the gate can't order (see
+    /// [`CircuitBuilder::comparison_constant`]), or isn't expressed under OR
+    fn predicate<'a>(
+        plan: &'a ExecutionPlan,
+        table: &Table,
+        table_columns: &mut Vec<usize>,
+    ) -> Result<(Predicate, Vec<&'a FilterOperation>), QueryError> {
+        let mut comparisons = vec![];
+        let mut unproved = vec![];
+        for filter in &plan.filters {
+            match Self::filter_predicate(filter, table, table_columns)? {
+                Some(predicate) => comparisons.push(predicate),
+                None => unproved.push(filter),
+            }
+        }
+        if let Some(tree) = &plan.filter_tree {
+            comparisons.push(Self::tree_predicate(
+                tree,
+                table,
+                table_columns,
+                Some(&mut unproved),
+            )?);
+        }
+        Ok((Predicate::And(comparisons), unproved))
+    }
+
+    /// Build the predicate of a filter tree
+    ///
+    /// # Arguments
+    /// * `tree` - Filter tree
+    /// * `table` - Input table of the plan
+    /// * `table_columns` - Indices of the circuit's table columns in `table`
+    /// * `unproved` - Filters the predicate doesn't express, for leaves only
+    ///   under AND; `None` under OR
+    ///
+    /// # Returns
+    /// `Ok(predicate)` of the tree, `Err` as for
+    /// [`CircuitBuilder::predicate`]
+    fn tree_predicate<'a>(
+        tree: &'a FilterTree,
+        table: &Table,
+        table_columns: &mut Vec<usize>,
+        mut unproved: Option<&mut Vec<&'a FilterOperation>>,
+    ) -> Result<Predicate, QueryError> {
+        match tree {
+            FilterTree::Leaf(filter) => {
+                let predicate = Self::filter_predicate(filter, table, table_columns)?;
+                match (predicate, unproved) {
+                    (Some(predicate), _) => Ok(predicate),
+                    // Under AND, the selection flags still apply the leaf
+                    (None, Some(unproved)) => {
+                        unproved.push(filter);
+                        Ok(Predicate::And(vec![]))
+                    }
+                    // Under OR, flags selecting rows the leaf rejects could
+                    // still satisfy the predicate
+                    (None, None) => Err(QueryError::UnsupportedFeature(format!(
+                        "Proving {:?} on {} under OR",
+                        filter.condition, filter.column
+                    ))),
+                }
+            }
+            FilterTree::And(children) => children
+                .iter()
+                .map(|child| {
+                    Self::tree_predicate(child, table, table_columns, unproved.as_deref_mut())
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Predicate::And),
+            FilterTree::Or(children) => children
+                .iter()
+                .map(|child| Self::tree_predicate(child, table, table_columns, None))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Predicate::Or),
+        }
+    }
+
+    /// Build the predicate of a single filter
+    ///
+    /// # Returns
+    /// `Ok(Some(predicate))` for comparisons with constants or columns,
+    /// BETWEEN and LIKE,
+    /// `Ok(None)` for filters the selection gate doesn't express, `Err` as
+    /// for [`CircuitBuilder::predicate`]
+    fn filter_predicate(
+        filter: &FilterOperation,
+        table: &Table,
+        table_columns: &mut Vec<usize>,
+    ) -> Result<Option<Predicate>, QueryError> {
+        let column_idx = QueryExecutor::column_index(table, &filter.column)?;
+        let mut compare = |comparison, negated, literal: &str| -> Result<_, QueryError> {
+            let constant = Self::comparison_constant(table, column_idx, literal)?;
+            Ok(Predicate::Compare {
+                column: Self::table_column(table_columns, column_idx),
+                comparison,
+                rhs: Operand::Constant(Field::from(constant)),
+                negated,
+            })
+        };
+
+        match &filter.condition {
+            // Both columns are copied from the table cells; INTEGER and
+            // BIGINT values compare as the executor promotes them, but not
+            // with dates or booleans
+            FilterCondition::ColumnCompare { other_column, op } => {
+                let other_idx = QueryExecutor::column_index(table, other_column)?;
+                let column = Self::ordered_column(table, column_idx)?;
+                let other = Self::ordered_column(table, other_idx)?;
+                let promoted = |data_type: &DataType| {
+                    matches!(data_type, DataType::Integer | DataType::BigInt)
+                };
+                if column.data_type != other.data_type
+                    && !(promoted(&column.data_type) && promoted(&other.data_type))
+                {
+                    return Err(QueryError::UnsupportedFeature(format!(
+                        "Proving comparisons of {} column {} with {} column {}",
+                        column.data_type, column.name, other.data_type, other.name
+                    )));
+                }
+                let comparison = match op {
+                    CompareOp::LessThan => Comparison::Less,
+                    CompareOp::GreaterThan => Comparison::Greater,
+                    CompareOp::Equal => Comparison::Equal,
+                };
+                Ok(Some(Predicate::Compare {
+                    column: Self::table_column(table_columns, column_idx),
+                    comparison,
+                    rhs: Operand::Column(Self::table_column(table_columns, other_idx)),
+                    negated: false,
+                }))
+            }
+            // The committed encoding of the one string the pattern matches
+            FilterCondition::Like(pattern) => Ok(Some(Predicate::Compare {
+                column: Self::table_column(table_columns, column_idx),
+                comparison: Comparison::Equal,
+                rhs: Operand::Constant(Self::like_constant(table, column_idx, pattern)?),
+                negated: false,
+            })),
+            // low <= v <= high, as not (v < low) and not (v > high)
+            FilterCondition::Between(low, high) => Ok(Some(Predicate::And(vec![
+                compare(Comparison::Less, true, low)?,
+                compare(Comparison::Greater, true, high)?,
+            ]))),
+            condition => Self::constant_comparison(condition)
+                .map(|(comparison, negated, literal)| compare(comparison, negated, literal))
+                .transpose(),
+        }
+    }
+
+    /// Collect the circuit table columns a predicate compares, once each
+    fn compared_columns(predicate: &Predicate, columns: &mut Vec<usize>) {
+        match predicate {
+            Predicate::Compare { column, rhs, .. } => {
+                let rhs = match rhs {
+                    Operand::Column(other) => Some(*other),
+                    Operand::Constant(_) => None,
+                };
+                for column in std::iter::once(*column).chain(rhs) {
+                    if !columns.contains(&column) {
+                        columns.push(column);
+                    }
+                }
+            }
+            Predicate::And(children) | Predicate::Or(children) => {
+                for child in children {
+                    Self::compared_columns(child, columns);
+                }
+            }
+        }
+    }
+
+    /// Get the selection gate comparison of a filter comparing a column with
+    /// a constant
+    ///
+    /// # Returns
+    /// `Some((comparison, negated, literal))` for `>`, `<`, `>=`, `<=` and
+    /// `=`, `None` for other conditions
+    fn constant_comparison(condition: &FilterCondition) -> Option<(Comparison, bool, &str)> {
+        match condition {
+            FilterCondition::GreaterThan(c) => Some((Comparison::Greater, false, c)),
+            FilterCondition::LessThan(c) => Some((Comparison::Less, false, c)),
+            FilterCondition::GreaterOrEqual(c) => Some((Comparison::Less, true, c)),
+            FilterCondition::LessOrEqual(c) => Some((Comparison::Greater, true, c)),
+            FilterCondition::Equal(c) => Some((Comparison::Equal, false, c)),
+            _ => None,
+        }
+    }
+
+    /// Get the unsigned encoding of a filter constant, checking that the
+    /// circuit compares the column with it as the executor does
+    ///
+    /// The column must be ordered by the circuit (see
+    /// [`CircuitBuilder::ordered_column`]), and the literal a non-negative
+    /// constant of its type.
+    ///
+    /// # Returns
+    /// `Ok(u64)` with the encoding of the constant,
+    /// `Err(QueryError::UnsupportedFeature)` for unordered columns and
+    /// negative constants, `Err(QueryError::InvalidValue)` if the literal
+    /// doesn't parse as the column type (e.g., a decimal constant for an
+    /// INTEGER column)
+    fn comparison_constant(
+        table: &Table,
+        column_idx: usize,
+        literal: &str,
+    ) -> Result<u64, QueryError> {
+        let column = Self::ordered_column(table, column_idx)?;
+        let invalid = || {
+            QueryError::InvalidValue(format!(
+                "Cannot compare {} column {} with {}",
+                column.data_type, column.name, literal
+            ))
+        };
+
+        let constant = match column.data_type {
+            DataType::Integer => literal.parse::<i32>().map_err(|_| invalid())? as i64,
+            DataType::BigInt => literal.parse::<i64>().map_err(|_| invalid())?,
+            DataType::Boolean => match Value::parse(literal, &DataType::Boolean) {
+                Ok(Value::Boolean(v)) => return Ok(u64::from(v)),
+                _ => return Err(invalid()),
+            },
+            _ => match Value::parse(literal, &DataType::Date) {
+                Ok(Value::Date(v)) => return Ok(v),
+                _ => return Err(invalid()),
+            },
+        };
+        u64::try_from(constant).map_err(|_| {
+            QueryError::UnsupportedFeature(format!(
+                "Proving comparisons of {} with negative constants",
+                column.name
+            ))
+        })
+    }
+
+    /// Get the field encoding of the string a LIKE pattern matches
+    ///
+    /// Strings are committed as hashes, so the circuit can't match a
+    /// pattern with wildcards against them; a pattern without wildcards
+    /// matches the one string equal to it, as an equality of encodings.
+    ///
+    /// # Returns
+    /// `Ok(Field)` with the encoding of the pattern's string,
+    /// `Err(QueryError::UnsupportedFeature)` for patterns with `%` or `_`
+    /// and for columns other than VARCHAR
+    fn like_constant(table: &Table, column_idx: usize, pattern: &str) -> Result<Field, QueryError> {
+        let column = table
+            .columns
+            .get(column_idx)
+            .ok_or_else(|| QueryError::ColumnNotFound(format!("column {}", column_idx)))?;
+        if !matches!(column.data_type, DataType::Varchar(_)) {
+            return Err(QueryError::UnsupportedFeature(format!(
+                "Proving LIKE on {} column {}",
+                column.data_type, column.name
+            )));
+        }
+        if pattern.contains(['%', '_']) {
+            return Err(QueryError::UnsupportedFeature(format!(
+                "Proving LIKE '{}' over hashed strings (patterns with wildcards)",
+                pattern
+            )));
+        }
+        Ok(Value::String(pattern.to_string()).to_field())
+    }
 
-        // Add gates based on execution plan
-        // Note: This is a simplified implementation
-        // In production, you would:
-        // 1. Analyze the execution plan
-        // 2. Configure appropriate gates
-        // 3. Assign data to gates
-        // 4. Optimize circuit layout
+    /// Check that the circuit orders a column's values as the executor does
+    ///
+    /// Comparisons are proved over unsigned integers, so the column must be
+    /// an INTEGER, BIGINT, DATE or BOOLEAN column of non-negative values (or
+    /// NULLs).
+    ///
+    /// # Returns
+    /// `Ok(column)` with the column's schema,
+    /// `Err(QueryError::UnsupportedFeature)` for other column types and
+    /// negative values
+    fn ordered_column(table: &Table, column_idx: usize) -> Result<&Column, QueryError> {
+        let column = table
+            .columns
+            .get(column_idx)
+            .ok_or_else(|| QueryError::ColumnNotFound(format!("column {}", column_idx)))?;
+        if !matches!(
+            column.data_type,
+            DataType::Integer | DataType::BigInt | DataType::Date | DataType::Boolean
+        ) {
+            return Err(QueryError::UnsupportedFeature(format!(
+                "Proving comparisons of {} column {}",
+                column.data_type, column.name
+            )));
+        }
+
+        let negative_values = table
+            .rows
+            .iter()
+            .any(|row| match row.values.get(column_idx) {
+                Some(Value::Integer(v)) => *v < 0,
+                Some(Value::BigInt(v)) => *v < 0,
+                _ => false,
+            });
+        if negative_values {
+            return Err(QueryError::UnsupportedFeature(format!(
+                "Proving comparisons of {} with negative values",
+                column.name
+            )));
+        }
+        Ok(column)
+    }
+
+    /// Get the circuit table column of a table column, adding it if needed
+    fn table_column(table_columns: &mut Vec<usize>, column_idx: usize) -> usize {
+        match table_columns.iter().position(|&idx| idx == column_idx) {
+            Some(position) => position,
+            None => {
+                table_columns.push(column_idx);
+                table_columns.len() - 1
+            }
+        }
+    }
+
+    /// Get the indices of the table columns fed into the circuit
+    ///
+    /// Honors the pruned column set from the optimizer; without one,
+    /// every column is used.
+    pub(crate) fn circuit_column_indices(plan: &ExecutionPlan, table: &Table) -> Vec<usize> {
+        match &plan.required_columns {
+            Some(required) => {
+                let mut indices: Vec<usize> = required
+                    .iter()
+                    .filter_map(|name| QueryExecutor::column_index(table, name).ok())
+                    .collect();
+                indices.sort_unstable();
+                indices.dedup();
+                indices
+            }
+            None => (0..table.columns.len()).collect(),
+        }
+    }
+
+    /// Compute selection flags over all table rows
+    ///
+    /// A row's flag is 1 if it is one of the filtered rows, else 0. The
+    /// filtered rows are matched against the table in order, so duplicate
+    /// rows are selected as many times as they were kept.
+    ///
+    /// # Arguments
+    /// * `table` - Input table
+    /// * `filtered_rows` - Rows of `table` kept by the filters, in table order
+    ///
+    /// # Returns
+    /// One flag per table row
+    pub(crate) fn selection_flags(table: &Table, filtered_rows: &[Row]) -> Vec<Field> {
+        let mut remaining = filtered_rows.iter().peekable();
+
+        table
+            .rows
+            .iter()
+            .map(|row| {
+                let selected = remaining.next_if(|kept| *kept == row).is_some();
+                Field::from(selected as u64)
+            })
+            .collect()
+    }
+
+    /// Field encoding of a value usable by the less-than gate
+    ///
+    /// # Returns
+    /// `Some(Field)` for non-negative integers and dates, `None` otherwise
+    fn unsigned_field(value: &Value) -> Option<Field> {
+        match value {
+            Value::Integer(v) if *v >= 0 => Some(Field::from(*v as u64)),
+            Value::BigInt(v) | Value::Decimal(v) if *v >= 0 => Some(Field::from(*v as u64)),
+            Value::Date(v) => Some(Field::from(*v)),
+            _ => None,
+        }
+    }
+
+    /// Flatten a projection expression into arithmetic rows
+    ///
+    /// Each binary operation becomes one `(a, op, b)` row whose operands are
+    /// table columns, constants or the outputs of earlier rows. Decimals
+    /// follow the executor's fixed-point arithmetic: an integer operand of a
+    /// decimal is scaled by a multiplication row, and decimal products and
+    /// quotients are rescaled by a division row.
+    ///
+    /// # Arguments
+    /// * `expr` - Projection expression
+    /// * `table` - Input table of the plan
+    /// * `table_columns` - Indices of the circuit's table columns in `table`,
+    ///   extended with the columns the expression reads
+    /// * `rows` - Rows of the expression, extended in evaluation order
+    ///
+    /// # Returns
+    /// `Ok((source, decimal))` with the source of the expression's value and
+    /// whether it is a fixed-point decimal, `Err` if the expression reads a
+    /// missing column or a column other than INTEGER, BIGINT and DECIMAL
+    fn arithmetic_rows(
+        expr: &ProjectionExpr,
+        table: &Table,
+        table_columns: &mut Vec<usize>,
+        rows: &mut Vec<(Source, Op, Source)>,
+    ) -> Result<(Source, bool), QueryError> {
+        match expr {
+            ProjectionExpr::Column(name) => {
+                let idx = QueryExecutor::column_index(table, name)?;
+                let column = &table.columns[idx];
+                let decimal = match column.data_type {
+                    DataType::Integer | DataType::BigInt => false,
+                    DataType::Decimal => true,
+                    _ => {
+                        return Err(QueryError::UnsupportedFeature(format!(
+                            "Proving arithmetic on {} column {}",
+                            column.data_type, column.name
+                        )))
+                    }
+                };
+                Ok((
+                    Source::Column(Self::table_column(table_columns, idx)),
+                    decimal,
+                ))
+            }
+            ProjectionExpr::Literal(value) => {
+                let magnitude = Field::from(value.unsigned_abs());
+                let value = if *value < 0 { -magnitude } else { magnitude };
+                Ok((Source::Constant(value), false))
+            }
+            ProjectionExpr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    ArithmeticOp::Add => Op::Add,
+                    ArithmeticOp::Sub => Op::Sub,
+                    ArithmeticOp::Mul => Op::Mul,
+                    ArithmeticOp::Div => Op::Div,
+                    ArithmeticOp::Mod => Op::Mod,
+                };
+                let (mut a, a_decimal) = Self::arithmetic_rows(left, table, table_columns, rows)?;
+                let (mut b, b_decimal) = Self::arithmetic_rows(right, table, table_columns, rows)?;
+
+                let mut push = |a: Source, op: Op, b: Source| {
+                    rows.push((a, op, b));
+                    Source::Row(rows.len() - 1)
+                };
+                let scale = Source::Constant(Field::from(10u64.pow(DECIMAL_SCALE)));
+                if a_decimal && !b_decimal {
+                    b = push(b, Op::Mul, scale);
+                }
+                if b_decimal && !a_decimal {
+                    a = push(a, Op::Mul, scale);
+                }
+                let decimal = a_decimal || b_decimal;
+                if decimal && op == Op::Div {
+                    a = push(a, Op::Mul, scale);
+                }
+
+                let mut out = push(a, op, b);
+                if decimal && op == Op::Mul {
+                    out = push(out, Op::Div, scale);
+                }
+                Ok((out, decimal))
+            }
+        }
+    }
+
+    /// Check that the circuit computes expressions on a row as the executor
+    /// does
+    ///
+    /// # Arguments
+    /// * `expressions` - Rows of each expression (see
+    ///   [`CircuitBuilder::arithmetic_rows`])
+    /// * `row` - Row of the table
+    /// * `table_columns` - Indices of the circuit's table columns in the table
+    ///
+    /// # Returns
+    /// `Ok(())` if every expression can be proved on the row,
+    /// `Err(QueryError::UnsupportedFeature)` if it reads NULL, or divides a
+    /// negative value or by zero (division is only proved for unsigned
+    /// operands below 2^64)
+    fn check_computed(
+        expressions: &[Vec<(Source, Op, Source)>],
+        row: &Row,
+        table_columns: &[usize],
+    ) -> Result<(), QueryError> {
+        let values: Vec<&Value> = table_columns
+            .iter()
+            .map(|&idx| row.values.get(idx).unwrap_or(&Value::Null))
+            .collect();
+        let cells: Vec<Field> = values.iter().map(|value| value.to_field()).collect();
+
+        for rows in expressions {
+            let reads_null = rows
+                .iter()
+                .flat_map(|&(a, _, b)| [a, b])
+                .any(|source| matches!(source, Source::Column(idx) if *values[idx] == Value::Null));
+            if reads_null {
+                return Err(QueryError::UnsupportedFeature(
+                    "Proving computed projections of NULL values".to_string(),
+                ));
+            }
+
+            let ops = ArithmeticConfig::evaluate(rows, &cells).unwrap_or_default();
+            let divides = ops.iter().all(|&(a, op, b, _)| {
+                !matches!(op, Op::Div | Op::Mod)
+                    || (FieldUtils::to_u64(&a).is_some()
+                        && FieldUtils::to_u64(&b).is_some_and(|b| b != 0))
+            });
+            if !divides {
+                return Err(QueryError::UnsupportedFeature(
+                    "Proving division of negative values or by zero".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the sort key of each row, for the sort gate
+    ///
+    /// The key packs the leading sort columns sorted in the same direction as
+    /// the first one into a composite key (see [`keys::composite`]), using the
+    /// bit width of each column type, for as many columns as fit the 64 bits
+    /// the sortedness check supports. Rows in sort order then have ordered
+    /// keys.
+    ///
+    /// # Arguments
+    /// * `sort` - Sort operation of the plan
+    /// * `table` - Table the sort columns are resolved against
+    /// * `rows` - Rows of `table`
+    ///
+    /// # Returns
+    /// `Ok(Some(keys))` if every key value is a non-negative integer or date
+    /// (the values the sortedness check supports), `Ok(None)` otherwise, `Err`
+    /// if a sort column doesn't exist
+    pub fn sort_keys(
+        sort: &SortOperation,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Option<Vec<Field>>, QueryError> {
+        let ascending = sort.ascending.first().copied().unwrap_or(true);
+        let mut indices = vec![];
+        let mut widths = vec![];
+        for (column, &direction) in sort.columns.iter().zip(&sort.ascending) {
+            if direction != ascending {
+                break;
+            }
+            let column_idx = QueryExecutor::column_index(table, column)?;
+            match table.columns[column_idx].data_type.range_bits() {
+                Some(bits) if widths.iter().sum::<usize>() + bits <= SORT_KEY_BITS => {
+                    indices.push(column_idx);
+                    widths.push(bits);
+                }
+                _ => break,
+            }
+        }
+        if indices.is_empty() {
+            return Ok(None);
+        }
 
-        // For now, we'll create a basic circuit structure
-        // The actual gate configuration and data assignment
-        // would be done based on the plan's operations
+        Ok(Self::composite_keys(rows, &indices, &widths))
+    }
 
+    /// Get the group-by key of each row, for the group-by gate
+    ///
+    /// The key packs all group-by columns into a composite key (see
+    /// [`keys::composite`]) using the bit width of each column type, so rows
+    /// in group order have non-decreasing keys and rows of one group share a
+    /// key.
+    ///
+    /// # Arguments
+    /// * `group_by` - Group-by operation of the plan
+    /// * `table` - Table the group-by columns are resolved against
+    /// * `rows` - Rows of `table`
+    ///
+    /// # Returns
+    /// `Ok(Some(keys))` if the columns fit in 64 bits and every key value is
+    /// a non-negative integer or date, `Ok(None)` otherwise, `Err` if a
+    /// group-by column doesn't exist
+    pub fn group_keys(
+        group_by: &GroupByOperation,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Option<Vec<Field>>, QueryError> {
+        let indices = group_by
+            .columns
+            .iter()
+            .map(|column| QueryExecutor::column_index(table, column))
+            .collect::<Result<Vec<_>, _>>()?;
+        let widths: Option<Vec<usize>> = indices
+            .iter()
+            .map(|&idx| table.columns[idx].data_type.range_bits())
+            .collect();
+        match widths {
+            Some(widths) if !widths.is_empty() && widths.iter().sum::<usize>() <= SORT_KEY_BITS => {
+                Ok(Self::composite_keys(rows, &indices, &widths))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Add the group-by gate over the group keys of some rows, and aggregate
+    /// the rows in key order over its groups
+    ///
+    /// Each row aggregates the value of the plan's first aggregated column
+    /// (a NULL adds 0), or 1 if every aggregation is COUNT(*). For HAVING,
+    /// the groups passing it are kept, with the counts of their aggregation
+    /// rows.
+    ///
+    /// # Arguments
+    /// * `circuit` - Circuit to add the gates to
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `rows` - Rows of `table` that satisfy the plan's filters
+    /// * `keys` - Group key of each row, as returned by `group_keys`
+    ///
+    /// # Returns
+    /// `Ok(circuit)` with the group-by and aggregation data, `Err` if the
+    /// aggregated column doesn't exist
+    fn grouped_aggregation(
+        circuit: NzengiCircuit,
+        plan: &ExecutionPlan,
+        table: &Table,
+        rows: &[Row],
+        keys: Vec<Field>,
+    ) -> Result<NzengiCircuit, QueryError> {
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        order.sort_by(|&a, &b| FieldUtils::cmp(&keys[a], &keys[b]));
+        let sorted: Vec<Field> = order.iter().map(|&i| keys[i]).collect();
+
+        let column_idx = plan
+            .aggregations
+            .iter()
+            .find_map(|agg| agg.column.as_deref())
+            .map(|column| QueryExecutor::column_index(table, column))
+            .transpose()?;
+        let values = order
+            .iter()
+            .map(|&i| match column_idx {
+                Some(idx) => rows[i]
+                    .column_field(idx)
+                    .filter(|f| !Value::is_null_field(f))
+                    .unwrap_or(Field::zero()),
+                None => Field::one(),
+            })
+            .collect();
+
+        let (binary_markers, start_indices, end_indices) = Self::group_witness(&sorted);
+        let mut circuit = circuit
+            .with_group_by(keys, sorted.clone())
+            .with_aggregation(values, binary_markers, start_indices, end_indices);
+
+        if let Some(HavingCondition::CountGreaterThan(k)) = plan.having {
+            let kept = GroupByConfig::get_group_boundaries(&sorted)
+                .into_iter()
+                .map(|(start, end)| (start, (end - start + 1) as u64))
+                .filter(|&(_, count)| count > k)
+                .map(|(start, count)| (start, Field::from(count)))
+                .collect();
+            circuit = circuit.with_having(kept, Field::from(k));
+        }
         Ok(circuit)
     }
 
+    /// Derive the aggregation gate's group witness from sorted keys
+    ///
+    /// # Returns
+    /// `(binary_markers, start_indices, end_indices)`, with groups of equal
+    /// keys as the group-by gate derives them
+    pub(crate) fn group_witness(sorted_keys: &[Field]) -> (Vec<Field>, Vec<Field>, Vec<Field>) {
+        let mut markers = vec![];
+        let mut starts = vec![];
+        let mut ends = vec![];
+        for (start, end) in GroupByConfig::get_group_boundaries(sorted_keys) {
+            for row in start..=end {
+                markers.push(Field::from((row < end) as u64));
+                starts.push(Field::from(start as u64));
+                ends.push(Field::from(end as u64));
+            }
+        }
+        (markers, starts, ends)
+    }
+
+    /// Pack the values of some columns of each row into composite keys
+    ///
+    /// # Returns
+    /// `Some(keys)` if every row's values pack into `widths`, `None` otherwise
+    fn composite_keys(rows: &[Row], indices: &[usize], widths: &[usize]) -> Option<Vec<Field>> {
+        rows.iter()
+            .map(|row| {
+                let values: Vec<Value> = indices
+                    .iter()
+                    .map(|&idx| row.values.get(idx).cloned().unwrap_or(Value::Null))
+                    .collect();
+                keys::composite(&values, widths).ok()
+            })
+            .collect()
+    }
+
+    /// Get the values MIN/MAX aggregate over, for the sort gate
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `rows` - Rows of `table` that satisfy the plan's filters
+    ///
+    /// # Returns
+    /// `Ok(Some((column, values)))` with the non-NULL values of the MIN/MAX
+    /// column if all MIN/MAX aggregations share one column, `Ok(None)`
+    /// otherwise, `Err` if the column doesn't exist or a value isn't a
+    /// non-negative integer or date
+    pub(crate) fn minmax_values(
+        plan: &ExecutionPlan,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Option<(usize, Vec<Field>)>, QueryError> {
+        let mut columns = plan
+            .aggregations
+            .iter()
+            .filter(|agg| {
+                matches!(
+                    agg.function,
+                    AggregationFunction::Min | AggregationFunction::Max
+                )
+            })
+            .map(|agg| agg.column.as_deref());
+        let Some(Some(column)) = columns.next() else {
+            return Ok(None);
+        };
+        if columns.any(|other| other != Some(column)) {
+            return Ok(None);
+        }
+        let column_idx = QueryExecutor::column_index(table, column)?;
+
+        let values = rows
+            .iter()
+            .filter_map(|row| row.values.get(column_idx))
+            .filter(|v| **v != Value::Null)
+            .map(|value| {
+                Self::unsigned_field(value).ok_or_else(|| {
+                    QueryError::UnsupportedFeature(format!(
+                        "Proving MIN/MAX of {} value {}",
+                        column, value
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((!values.is_empty()).then_some((column_idx, values)))
+    }
+
+    /// Prove a circuit's AVG as the truncated average of each aggregated group
+    ///
+    /// # Arguments
+    /// * `circuit` - Circuit with the aggregation data of the plan
+    ///
+    /// # Returns
+    /// `Ok(circuit)` proving AVG truncated, `Err` if an aggregated value
+    /// isn't a non-negative integer or a group's SUM isn't below 2^64 (the
+    /// less-than gate only bounds 64-bit quotients and remainders)
+    fn truncated_avg(circuit: NzengiCircuit) -> Result<NzengiCircuit, QueryError> {
+        if let Some((values, binary_markers, _, _)) = &circuit.aggregation_data {
+            let mut sum = 0u64;
+            for (value, marker) in values.iter().zip(binary_markers) {
+                sum = FieldUtils::to_u64(value)
+                    .and_then(|value| sum.checked_add(value))
+                    .ok_or_else(|| {
+                        QueryError::UnsupportedFeature(
+                            "Proving AVG of negative values or sums of 2^64 or more".to_string(),
+                        )
+                    })?;
+                // A zero marker ends the group
+                if *marker == Field::zero() {
+                    sum = 0;
+                }
+            }
+        }
+        Ok(circuit.with_truncated_avg())
+    }
+
+    /// Get the table column of each of a plan's APPROX_COUNT_DISTINCTs, for
+    /// the sketch gate
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `rows` - Rows of `table` that satisfy the plan's filters
+    ///
+    /// # Returns
+    /// `Ok(columns)` with the column index of each APPROX_COUNT_DISTINCT, in
+    /// plan order, `Err` if the plan groups its rows, a column doesn't exist,
+    /// or a non-NULL value isn't a non-negative integer or date (the gate
+    /// only hashes values below 2^64)
+    fn sketch_columns(
+        plan: &ExecutionPlan,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Vec<usize>, QueryError> {
+        let sketches: Vec<_> = plan
+            .aggregations
+            .iter()
+            .filter(|agg| agg.function == AggregationFunction::ApproxCountDistinct)
+            .collect();
+        if !sketches.is_empty() && !plan.group_by.is_empty() {
+            return Err(QueryError::UnsupportedFeature(
+                "Proving APPROX_COUNT_DISTINCT with GROUP BY".to_string(),
+            ));
+        }
+        sketches
+            .into_iter()
+            .map(|agg| {
+                let column = agg.column.as_deref().ok_or_else(|| {
+                    QueryError::UnsupportedFeature("APPROX_COUNT_DISTINCT(*)".to_string())
+                })?;
+                let column_idx = QueryExecutor::column_index(table, column)?;
+                let unsupported = rows
+                    .iter()
+                    .filter_map(|row| row.values.get(column_idx))
+                    .find(|v| **v != Value::Null && Self::unsigned_field(v).is_none());
+                match unsupported {
+                    Some(value) => Err(QueryError::UnsupportedFeature(format!(
+                        "Proving APPROX_COUNT_DISTINCT of {} value {}",
+                        column, value
+                    ))),
+                    None => Ok(column_idx),
+                }
+            })
+            .collect()
+    }
+
+    /// Get the table column of each of a plan's COUNT(DISTINCT)s, for the
+    /// distinct-count gate
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table` - Input table of the plan
+    /// * `rows` - Rows of `table` that satisfy the plan's filters
+    ///
+    /// # Returns
+    /// `Ok(columns)` with the column index of each COUNT(DISTINCT), in plan
+    /// order, `Err` if a column doesn't exist or a non-NULL value isn't a
+    /// non-negative integer or date
+    pub(crate) fn distinct_count_columns(
+        plan: &ExecutionPlan,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Vec<usize>, QueryError> {
+        plan.aggregations
+            .iter()
+            .filter(|agg| agg.function == AggregationFunction::CountDistinct)
+            .map(|agg| {
+                let column = agg.column.as_deref().ok_or_else(|| {
+                    QueryError::UnsupportedFeature("Proving COUNT(DISTINCT *)".to_string())
+                })?;
+                let column_idx = QueryExecutor::column_index(table, column)?;
+                let unsupported = rows
+                    .iter()
+                    .filter_map(|row| row.values.get(column_idx))
+                    .find(|v| **v != Value::Null && Self::unsigned_field(v).is_none());
+                match unsupported {
+                    Some(value) => Err(QueryError::UnsupportedFeature(format!(
+                        "Proving COUNT(DISTINCT) of {} value {}",
+                        column, value
+                    ))),
+                    None => Ok(column_idx),
+                }
+            })
+            .collect()
+    }
+
+    /// Get the values summed by a plan's SUM, for its public output
+    ///
+    /// # Returns
+    /// `Ok(Some((column, values)))` if the plan has no group-by and SUMs
+    /// exactly one column whose non-NULL values are all unsigned integers,
+    /// `Ok(None)` otherwise, `Err` if the column doesn't exist
+    pub(crate) fn sum_values(
+        plan: &ExecutionPlan,
+        table: &Table,
+        rows: &[Row],
+    ) -> Result<Option<(usize, Vec<Field>)>, QueryError> {
+        if !plan.group_by.is_empty() {
+            return Ok(None);
+        }
+        let mut columns = plan
+            .aggregations
+            .iter()
+            .filter(|agg| agg.function == AggregationFunction::Sum)
+            .map(|agg| agg.column.as_deref());
+        let (Some(Some(column)), None) = (columns.next(), columns.next()) else {
+            return Ok(None);
+        };
+        let column_idx = QueryExecutor::column_index(table, column)?;
+
+        let values: Option<Vec<Field>> = rows
+            .iter()
+            .filter_map(|row| row.values.get(column_idx))
+            .filter(|v| **v != Value::Null)
+            .map(Self::unsigned_field)
+            .collect();
+        Ok(values
+            .filter(|values| !values.is_empty())
+            .map(|values| (column_idx, values)))
+    }
+
+    /// Estimate the worst-case number of rows of the circuit for a plan
+    ///
+    /// Assumes every input row passes the filters and every region is laid
+    /// out after the previous one (as `SimplePlanner` does), so the circuit
+    /// of any data of the given sizes fits. Each sorted sequence of n values
+    /// costs n rows plus n - 1 comparisons, which dominate the estimate.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table_sizes` - Number of rows of each table (missing tables count as empty)
+    ///
+    /// # Returns
+    /// Upper bound on the rows used, including the rows reserved for blinding
+    pub fn estimate_rows(plan: &ExecutionPlan, table_sizes: &HashMap<String, usize>) -> usize {
+        let n: usize = plan
+            .tables
+            .iter()
+            .filter_map(|table| table_sizes.get(table))
+            .sum();
+        let sorted_rows = n + n.saturating_sub(1) * COMPARISON_ROWS;
+        let has = |function: AggregationFunction| {
+            plan.aggregations.iter().any(|agg| agg.function == function)
+        };
+        let ungrouped = plan.group_by.is_empty();
+
+        let mut rows = BLINDING_ROWS;
+
+        // Table cells of the compared, counted and summed columns, the flags
+        // of their comparisons (two for BETWEEN) and the range checks of the
+        // compared cells against one u8 lookup table, and of their
+        // combinations, the selection flags within them, and the selected
+        // cells
+        let tree_leaves = plan.filter_tree.iter().flat_map(FilterTree::leaves);
+        let comparisons: usize = plan
+            .filters
+            .iter()
+            .chain(tree_leaves)
+            .map(|f| match f.condition {
+                FilterCondition::Between(..) => 2,
+                _ => 1,
+            })
+            .sum();
+        if comparisons > 0 {
+            rows += FieldUtils::u8_lookup_table_size();
+        }
+        rows += (4 * comparisons + 9) * n;
+
+        if !plan.aggregations.is_empty() {
+            rows += plan.aggregations.len() * n;
+        }
+        if ungrouped && has(AggregationFunction::Count) {
+            rows += n;
+        }
+
+        // AVG bounds its quotient and remainder with 3 comparisons per row
+        if has(AggregationFunction::Avg) {
+            rows += 3 * n * COMPARISON_ROWS;
+        }
+
+        // ORDER BY shares the sort gate with MIN/MAX
+        let minmax = has(AggregationFunction::Min) || has(AggregationFunction::Max);
+        if !plan.sort.is_empty() || (ungrouped && minmax) {
+            rows += sorted_rows;
+        }
+
+        // MIN/MAX flags the non-NULL selected cells of its column, and
+        // compacts them into the sort input
+        if ungrouped && minmax {
+            rows += 3 * n;
+        }
+        let distinct_counts = plan
+            .aggregations
+            .iter()
+            .filter(|agg| agg.function == AggregationFunction::CountDistinct)
+            .count();
+        if !ungrouped || plan.having.is_some() || distinct_counts > 0 {
+            rows += sorted_rows;
+        }
+        if plan.having.is_some() {
+            rows += n * COMPARISON_ROWS;
+        }
+
+        // Each COUNT(DISTINCT) re-sorts the group-by rows by value, from
+        // the selected cells of its column
+        rows += distinct_counts * (sorted_rows + n);
+
+        // Each APPROX_COUNT_DISTINCT hashes the selected cells of its column,
+        // with words range checked against the u8 lookup table, and orders
+        // their observations and a sentinel per bucket
+        let sketches = plan
+            .aggregations
+            .iter()
+            .filter(|agg| agg.function == AggregationFunction::ApproxCountDistinct)
+            .count();
+        if sketches > 0 && comparisons == 0 {
+            rows += FieldUtils::u8_lookup_table_size();
+        }
+        let observations = n + (1 << HLL_PRECISION);
+        rows += sketches * ((HASH_ROWS + 1) * n + observations * (RHO_BITS + 1));
+        rows += plan.joins.len() * 2 * n;
+
+        // Per selected row, the rows of each computed projection, and the
+        // selected cells of each column it reads
+        for computed in &plan.computed {
+            rows +=
+                (Self::arithmetic_row_bound(&computed.expr) + computed.expr.columns().len()) * n;
+        }
+
+        rows
+    }
+
+    /// Bound the arithmetic rows of a projection expression, with their
+    /// decimal scaling and division bound checks, per evaluated row
+    fn arithmetic_row_bound(expr: &ProjectionExpr) -> usize {
+        match expr {
+            ProjectionExpr::BinaryOp { left, right, .. } => {
+                3 + 3 * COMPARISON_ROWS
+                    + Self::arithmetic_row_bound(left)
+                    + Self::arithmetic_row_bound(right)
+            }
+            _ => 0,
+        }
+    }
+
     /// Build a circuit with range check gates
     ///
     /// # Arguments
@@ -104,37 +1418,29 @@ impl CircuitBuilder {
         &self,
         values: Vec<u64>,
     ) -> Result<NzengiCircuit, Box<dyn std::error::Error>> {
-        let mut circuit = NzengiCircuit::new();
+        let checks = values
+            .into_iter()
+            .map(|value| (value, FieldUtils::decompose_u64(value).to_vec()))
+            .collect();
 
-        for value in values {
-            let u8_cells = crate::field::FieldUtils::decompose_u64(value);
-            circuit = circuit.with_range_check(value, u8_cells.to_vec());
-        }
-
-        Ok(circuit)
+        Ok(NzengiCircuit::new().with_range_checks(checks))
     }
 
     /// Build a circuit with sort gates
     ///
     /// # Arguments
     /// * `input_values` - Input values to sort
-    /// * `alpha` - Random field element for blinding
     ///
     /// # Returns
     /// `Ok(NzengiCircuit)` if circuit building succeeds, `Err` otherwise
     pub fn with_sort(
         &self,
         input_values: Vec<Field>,
-        alpha: Field,
     ) -> Result<NzengiCircuit, Box<dyn std::error::Error>> {
         let mut sorted_values = input_values.clone();
-        sorted_values.sort_by(|a, b| {
-            let a_u64 = Self::field_to_u64(*a);
-            let b_u64 = Self::field_to_u64(*b);
-            a_u64.cmp(&b_u64)
-        });
+        sorted_values.sort_by(FieldUtils::cmp);
 
-        let circuit = NzengiCircuit::new().with_sort(input_values, sorted_values, alpha);
+        let circuit = NzengiCircuit::new().with_sort(input_values, sorted_values);
         Ok(circuit)
     }
 
@@ -162,16 +1468,6 @@ impl CircuitBuilder {
         );
         Ok(circuit)
     }
-
-    /// Helper function to convert field to u64 for comparison
-    fn field_to_u64(value: Field) -> u64 {
-        let bytes = value.to_bytes();
-        let mut u64_bytes = [0u8; 8];
-        for i in 0..8.min(bytes.len()) {
-            u64_bytes[i] = bytes[i];
-        }
-        u64::from_le_bytes(u64_bytes)
-    }
 }
 
 impl Default for CircuitBuilder {
@@ -183,6 +1479,8 @@ impl Default for CircuitBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::{QueryParser, QueryPlanner};
+    use crate::test_util::{fields, plan, run_mock_with_instance, small_lineitem};
 
     #[test]
     fn test_circuit_builder_new() {
@@ -197,21 +1495,645 @@ mod tests {
     }
 
     #[test]
-    fn test_build_from_plan() {
-        let builder = CircuitBuilder::new();
-        let plan = ExecutionPlan {
-            tables: vec![],
-            filters: vec![],
-            joins: vec![],
-            group_by: vec![],
-            aggregations: vec![],
-            sort: vec![],
-            projection: vec![],
+    fn test_from_plan_count_filter() {
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 10, 30, 11] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        let filtered_rows: Vec<Row> = table
+            .rows
+            .iter()
+            .filter(|row| matches!(row.values[0], Value::Integer(q) if q > 10))
+            .cloned()
+            .collect();
+
+        let ast = QueryParser::new()
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10")
+            .unwrap();
+        let plan = QueryPlanner::new().plan(&ast).unwrap();
+
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+
+        // The selected rows are proven against the whole input column
+        assert_eq!(circuit.table_data(), &[fields(&[5, 12, 10, 30, 11])]);
+        assert_eq!(
+            circuit.predicate(),
+            Some(&Predicate::And(vec![Predicate::Compare {
+                column: 0,
+                comparison: Comparison::Greater,
+                rhs: Operand::Constant(Field::from(10)),
+                negated: false,
+            }]))
+        );
+        assert!(circuit.selection_data.is_none());
+        assert_eq!(circuit.public_inputs(), vec![Field::from(3u64)]);
+        assert!(circuit.sort_data.is_none());
+
+        // No per-row checks of the selected values are needed
+        assert!(circuit.narrow_range_check_data.is_none());
+        assert!(circuit.less_than_data.is_none());
+    }
+
+    #[test]
+    fn test_from_plan_filters_on_two_columns() {
+        use halo2_proofs::dev::MockProver;
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_discount".to_string(), DataType::Integer),
+            ],
+        );
+        for (quantity, discount) in [(5, 2), (12, 7), (30, 3), (11, 4), (8, 9)] {
+            table.rows.push(Row::new(vec![
+                Value::Integer(quantity),
+                Value::Integer(discount),
+            ]));
+        }
+
+        let ast = QueryParser::new()
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10 AND l_discount < 5")
+            .unwrap();
+        let plan = QueryPlanner::new().plan(&ast).unwrap();
+        assert_eq!(plan.filters.len(), 2);
+
+        // Only rows where both filters hold are selected
+        let filtered_rows: Vec<Row> = table
+            .rows
+            .iter()
+            .filter(|row| {
+                plan.filters
+                    .iter()
+                    .all(|f| QueryExecutor::filter_holds(&table, f, row))
+            })
+            .cloned()
+            .collect();
+        assert_eq!(filtered_rows.len(), 2);
+
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+        assert_eq!(circuit.public_inputs(), vec![Field::from(2u64)]);
+        let prover = MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Selecting a row that passes only the quantity filter fails the
+        // discount comparison
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows[1..4]).unwrap();
+        let prover = MockProver::run(10, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_from_plan_constant_comparisons() {
+        let table = small_lineitem(&[5, 12, 10, 30, 11]);
+
+        for (sql, count) in [
+            ("SELECT COUNT(*) FROM lineitem WHERE l_quantity < 11", 2),
+            ("SELECT COUNT(*) FROM lineitem WHERE l_quantity = 10", 1),
+            ("SELECT COUNT(*) FROM lineitem WHERE l_quantity >= 11", 3),
+            ("SELECT COUNT(*) FROM lineitem WHERE l_quantity <= 11", 3),
+        ] {
+            let plan = plan(sql);
+            let filtered_rows: Vec<Row> = table
+                .rows
+                .iter()
+                .filter(|row| QueryExecutor::filter_holds(&table, &plan.filters[0], row))
+                .cloned()
+                .collect();
+            assert_eq!(filtered_rows.len(), count, "{}", sql);
+
+            let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+            assert!(circuit.predicate().is_some(), "{}", sql);
+            assert_eq!(
+                run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+                Ok(()),
+                "{}",
+                sql
+            );
+
+            // Dropping a selected row leaves a count the flags don't sum to
+            let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows[1..]).unwrap();
+            assert!(
+                run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err(),
+                "{}",
+                sql
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_plan_between() {
+        let table = small_lineitem(&[5, 12, 10, 30, 11]);
+        let plan = plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity BETWEEN 10 AND 12");
+        let filtered_rows: Vec<Row> = table
+            .rows
+            .iter()
+            .filter(|row| QueryExecutor::filter_holds(&table, &plan.filters[0], row))
+            .cloned()
+            .collect();
+        assert_eq!(filtered_rows.len(), 3);
+
+        // Both bounds are proved over the table cells, with the bounds inclusive
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+        assert!(circuit.selection_data.is_none());
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Counting 30, above the upper bound, is rejected
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows[1..]).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[4]));
+        assert!(run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err());
+
+        // Signed bounds would compare by their two's complement encoding
+        let mut signed = plan.clone();
+        signed.filters[0].condition = FilterCondition::Between("-5".to_string(), "12".to_string());
+        assert!(matches!(
+            CircuitBuilder::from_plan(&signed, &table, &filtered_rows),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_column_comparisons() {
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_limit".to_string(), DataType::BigInt),
+                Column::new("l_shipdate".to_string(), DataType::Date),
+            ],
+        );
+        for (quantity, limit) in [(5, 2), (12, 7), (30, 30), (11, 4), (8, 9)] {
+            table.rows.push(Row::new(vec![
+                Value::Integer(quantity),
+                Value::BigInt(limit),
+                Value::Date(0),
+            ]));
+        }
+        table.rows.push(Row::new(vec![
+            Value::Null,
+            Value::BigInt(3),
+            Value::Date(0),
+        ]));
+
+        for (op, count) in [("<", 1), (">", 3), ("=", 1)] {
+            let sql = &format!(
+                "SELECT COUNT(*) FROM lineitem WHERE l_quantity {} l_limit",
+                op
+            );
+            let plan = plan(sql);
+            let filtered_rows: Vec<Row> = table
+                .rows
+                .iter()
+                .filter(|row| QueryExecutor::filter_holds(&table, &plan.filters[0], row))
+                .cloned()
+                .collect();
+            assert_eq!(filtered_rows.len(), count, "{}", sql);
+
+            let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+            assert!(circuit.less_than_data.is_none(), "{}", sql);
+            assert_eq!(
+                run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+                Ok(()),
+                "{}",
+                sql
+            );
+        }
+
+        // Counting (12, 7) as equal is rejected
+        let plan = plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity = l_limit");
+        let forged = vec![table.rows[1].clone(), table.rows[2].clone()];
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &forged).unwrap();
+        assert!(run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err());
+
+        // Integers and dates aren't comparable
+        let plan = self::plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity < l_shipdate");
+        assert!(matches!(
+            CircuitBuilder::from_plan(&plan, &table, &[]),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_like() {
+        let mut table = Table::new(
+            "customer".to_string(),
+            vec![Column::new("c_name".to_string(), DataType::Varchar(25))],
+        );
+        for name in ["Customer#001", "Customer#003", "Supplier#003"] {
+            table
+                .rows
+                .push(Row::new(vec![Value::String(name.to_string())]));
+        }
+        table.rows.push(Row::new(vec![Value::Null]));
+
+        // A pattern without wildcards is an equality with the committed hash
+        let plan = plan("SELECT COUNT(*) FROM customer WHERE c_name LIKE 'Customer#003'");
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows[1..2]).unwrap();
+        assert!(circuit.selection_data.is_none());
+        assert_eq!(circuit.public_inputs(), fields(&[1]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Counting a row with another name is rejected
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows[1..3]).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[2]));
+        assert!(run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err());
+
+        // Wildcard patterns can't be matched against hashes
+        let plan = self::plan("SELECT COUNT(*) FROM customer WHERE c_name LIKE 'Customer%'");
+        assert!(matches!(
+            CircuitBuilder::from_plan(&plan, &table, &table.rows[..2]),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_selection_within_comparisons() {
+        // IN isn't a predicate comparison, so the filtered rows are
+        // selection flags, each constrained to satisfy `> 10`
+        let table = small_lineitem(&[5, 12, 10, 30, 11]);
+        let plan =
+            plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10 AND l_quantity IN (12, 11)");
+        let filtered_rows = vec![table.rows[1].clone(), table.rows[4].clone()];
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+        assert!(circuit.selection_data.is_some());
+        assert!(circuit.less_than_data.is_none());
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // A tampered selection adding a row failing `> 10` is rejected
+        let tampered = vec![
+            table.rows[0].clone(),
+            table.rows[1].clone(),
+            table.rows[4].clone(),
+        ];
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &tampered).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[3]));
+        assert!(run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err());
+    }
+
+    #[test]
+    fn test_from_plan_unordered_comparisons_rejected() {
+        let table = small_lineitem(&[5, 12, 10]);
+        let build = |table: &Table, sql: &str| CircuitBuilder::from_plan(&plan(sql), table, &[]);
+
+        // Negative and decimal constants aren't dropped or read as 0
+        let mut negative_constant = plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 5");
+        negative_constant.filters[0].condition = FilterCondition::GreaterThan("-5".to_string());
+        assert!(matches!(
+            CircuitBuilder::from_plan(&negative_constant, &table, &table.rows),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+        assert!(matches!(
+            build(
+                &table,
+                "SELECT COUNT(*) FROM lineitem WHERE l_quantity < 10.5"
+            ),
+            Err(QueryError::InvalidValue(_))
+        ));
+
+        // Negative values would compare by their two's complement encoding
+        let negative = small_lineitem(&[5, -12, 10]);
+        assert!(matches!(
+            build(
+                &negative,
+                "SELECT COUNT(*) FROM lineitem WHERE l_quantity > 0"
+            ),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_unfiltered_count() {
+        // No column is read, yet every table row is flagged
+        let table = small_lineitem(&[5, 12, 10]);
+        let plan = plan("SELECT COUNT(*) FROM lineitem");
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows).unwrap();
+        assert!(circuit.table_data().is_empty());
+        assert_eq!(circuit.public_inputs(), fields(&[3]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+        assert!(run_mock_with_instance(&circuit, 10, vec![fields(&[2])]).is_err());
+    }
+
+    #[test]
+    fn test_from_plan_group_by() {
+        let table = small_lineitem(&[5, 12, 5, 30, 12]);
+        let plan = plan("SELECT l_quantity, COUNT(*) FROM lineitem GROUP BY l_quantity");
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows).unwrap();
+
+        // The rows are aggregated in key order, on the groups of the keys
+        let (input, sorted) = circuit.group_by_data.clone().unwrap();
+        assert_eq!(input, fields(&[5, 12, 5, 30, 12]));
+        assert_eq!(sorted, fields(&[5, 5, 12, 12, 30]));
+        let (values, markers, starts, ends) = circuit.aggregation_data.clone().unwrap();
+        assert_eq!(markers, fields(&[1, 0, 1, 0, 0]));
+        assert_eq!(starts, fields(&[0, 0, 2, 2, 4]));
+        assert_eq!(ends, fields(&[1, 1, 3, 3, 4]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Aggregating every row as one group is rejected
+        let forged = circuit.with_aggregation(
+            values,
+            fields(&[1, 1, 1, 1, 0]),
+            fields(&[0; 5]),
+            fields(&[4; 5]),
+        );
+        assert!(run_mock_with_instance(&forged, 10, vec![forged.public_inputs()]).is_err());
+    }
+
+    #[test]
+    fn test_from_plan_avg() {
+        // sum = 28, count = 3: avg = 9, r = 1
+        let table = small_lineitem(&[5, 12, 11]);
+        let circuit = CircuitBuilder::from_plan(
+            &plan("SELECT AVG(l_quantity) FROM lineitem"),
+            &table,
+            &table.rows,
+        )
+        .unwrap();
+        assert!(circuit.gates().avg_remainder);
+        assert_eq!(
+            run_mock_with_instance(&circuit, 11, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Other aggregations don't pay for the remainder check
+        let circuit = CircuitBuilder::from_plan(
+            &plan("SELECT SUM(l_quantity) FROM lineitem"),
+            &table,
+            &table.rows,
+        )
+        .unwrap();
+        assert!(!circuit.gates().avg_remainder);
+
+        // Negative values have no truncated average the gate can prove
+        let table = small_lineitem(&[-5, 12]);
+        assert!(matches!(
+            CircuitBuilder::from_plan(
+                &plan("SELECT AVG(l_quantity) FROM lineitem"),
+                &table,
+                &table.rows
+            ),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_range_check_widths() {
+        use crate::circuit::config::{CircuitConfig, GateSet};
+        use halo2_proofs::plonk::ConstraintSystem;
+
+        let mut table = Table::new(
+            "orders".to_string(),
+            vec![
+                Column::new("o_paid".to_string(), DataType::Boolean),
+                Column::new("o_orderkey".to_string(), DataType::BigInt),
+                Column::new("o_quantity".to_string(), DataType::Integer),
+            ],
+        );
+        for (paid, key, quantity) in [(true, 7, 3), (false, 9, 5)] {
+            table.rows.push(Row::new(vec![
+                Value::Boolean(paid),
+                Value::BigInt(key),
+                Value::Integer(quantity),
+            ]));
+        }
+
+        let build = |sql: &str| {
+            let ast = QueryParser::new().parse(sql).unwrap();
+            let plan = QueryPlanner::new().plan(&ast).unwrap();
+            CircuitBuilder::from_plan(&plan, &table, &table.rows[..1]).unwrap()
+        };
+        let mut meta = ConstraintSystem::<Field>::default();
+        let gates = GateSet {
+            range_check: true,
+            narrow_range_check: true,
+            ..GateSet::default()
         };
-        let data = HashMap::new();
+        let config = CircuitConfig::new(&mut meta, &gates);
 
-        let result = builder.build_from_plan(&plan, &data);
-        assert!(result.is_ok());
+        // A BOOLEAN filter range-checks its column's cells at 1 bit
+        let circuit = build("SELECT COUNT(*) FROM orders WHERE o_paid = true");
+        assert_eq!(circuit.narrow_range_check_data, Some(vec![(1, 1), (0, 1)]));
+        assert!(circuit.range_check_data.is_none());
+        assert!(circuit.gates().narrow_range_check);
+        assert_eq!(config.narrow_range_check(1).unwrap().bits, 1);
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[1]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![public_inputs]),
+            Ok(())
+        );
+
+        // INTEGER at 32 bits
+        let circuit = build("SELECT COUNT(*) FROM orders WHERE o_quantity < 4");
+        assert_eq!(
+            circuit.narrow_range_check_data,
+            Some(vec![(3, 32), (5, 32)])
+        );
+        assert_eq!(config.narrow_range_check(32).unwrap().bits, 32);
+
+        // BIGINT at 64 bits, with the 64-bit range check, also under OR
+        let circuit = build("SELECT COUNT(*) FROM orders WHERE o_orderkey < 8 OR o_orderkey > 100");
+        assert!(circuit.narrow_range_check_data.is_none());
+        assert!(!circuit.gates().narrow_range_check);
+        let widths: Vec<usize> = circuit
+            .range_check_data
+            .iter()
+            .flatten()
+            .map(|(_, u8_cells)| 8 * u8_cells.len())
+            .collect();
+        assert_eq!(widths, vec![64, 64]);
+        assert!(config.narrow_range_check(64).is_none());
+    }
+
+    #[test]
+    fn test_from_plan_computed_projection() {
+        let table = small_lineitem(&[5, 12, 10, 30]);
+        let plan = plan("SELECT l_quantity * 2 - 1 FROM lineitem WHERE l_quantity > 10");
+        let filtered_rows = vec![table.rows[1].clone(), table.rows[3].clone()];
+
+        // The values are computed on the selected cells and public
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[23, 59]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Values of another row, or not following from the cells, are rejected
+        for forged in [fields(&[9, 59]), fields(&[23, 60])] {
+            assert!(run_mock_with_instance(&circuit, 10, vec![forged]).is_err());
+        }
+
+        // NULL operands can't be proved, unless the filters drop them
+        let mut table = table;
+        table.rows[3] = Row::new(vec![Value::Null]);
+        assert!(CircuitBuilder::from_plan(&plan, &table, &table.rows[1..2]).is_ok());
+        let plan = self::plan("SELECT l_quantity * 2 FROM lineitem");
+        assert!(matches!(
+            CircuitBuilder::from_plan(&plan, &table, &table.rows),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_plan_filter_tree() {
+        let table = small_lineitem(&[5, 12, 10, 30, 11]);
+        let plan = plan(
+            "SELECT COUNT(*) FROM lineitem WHERE l_quantity < 8 OR (l_quantity > 10 AND l_quantity <= 12)",
+        );
+        let tree = plan.filter_tree.as_ref().unwrap();
+        let filtered_rows: Vec<Row> = table
+            .rows
+            .iter()
+            .filter(|row| tree.evaluate(&mut |leaf| QueryExecutor::filter_holds(&table, leaf, row)))
+            .cloned()
+            .collect();
+        assert_eq!(filtered_rows.len(), 3);
+
+        // The tree is proved over the table cells, keeping its AND/OR
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &filtered_rows).unwrap();
+        assert!(circuit.selection_data.is_none());
+        assert!(circuit.computed_data.is_none());
+        assert!(matches!(
+            circuit.predicate(),
+            Some(Predicate::And(children)) if matches!(children[..], [Predicate::Or(_)])
+        ));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // Counting 10, which satisfies neither branch, is rejected
+        let forged = vec![
+            table.rows[0].clone(),
+            table.rows[1].clone(),
+            table.rows[2].clone(),
+            table.rows[4].clone(),
+        ];
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &forged).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[4]));
+        assert!(run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]).is_err());
+
+        // Selection flags can't stand in for a leaf under OR
+        let plan = self::plan(
+            "SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10 OR l_quantity IN (1, 2)",
+        );
+        assert!(matches!(
+            CircuitBuilder::from_plan(&plan, &table, &[]),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+                Column::new("l_comment".to_string(), DataType::Varchar(10)),
+            ],
+        );
+        for key in [30, 10, 20] {
+            table.rows.push(Row::new(vec![
+                Value::BigInt(key),
+                Value::String("x".to_string()),
+            ]));
+        }
+
+        let ast = QueryParser::new()
+            .parse("SELECT l_orderkey FROM lineitem ORDER BY l_orderkey")
+            .unwrap();
+        let plan = QueryPlanner::new().plan(&ast).unwrap();
+
+        // Sorting is left to the executor, which knows the result order
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows).unwrap();
+        assert!(circuit.sort_data.is_none());
+
+        let keys = CircuitBuilder::sort_keys(&plan.sort[0], &table, &table.rows).unwrap();
+        assert_eq!(
+            keys,
+            Some(vec![30u64, 10, 20].into_iter().map(Field::from).collect())
+        );
+
+        // Strings can't be proven sorted
+        let mut by_comment = plan.sort[0].clone();
+        by_comment.columns = vec!["l_comment".to_string()];
+        assert_eq!(
+            CircuitBuilder::sort_keys(&by_comment, &table, &table.rows).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_composite_sort_and_group_keys() {
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_linenumber".to_string(), DataType::Integer),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+            ],
+        );
+        for (line, q) in [(2, 5), (1, 7), (2, 3)] {
+            table.rows.push(Row::new(vec![
+                Value::Integer(line),
+                Value::Integer(q),
+                Value::BigInt(1),
+            ]));
+        }
+
+        // Two INTEGER columns pack into one 64-bit key
+        let ast = QueryParser::new()
+            .parse("SELECT l_quantity FROM lineitem ORDER BY l_linenumber, l_quantity")
+            .unwrap();
+        let plan = QueryPlanner::new().plan(&ast).unwrap();
+        let keys = CircuitBuilder::sort_keys(&plan.sort[0], &table, &table.rows).unwrap();
+        assert_eq!(
+            keys,
+            Some(fields(&[(2 << 32) + 5, (1 << 32) + 7, (2 << 32) + 3]))
+        );
+
+        // A column in the other direction, or past 64 bits, is left out
+        let mut mixed = plan.sort[0].clone();
+        mixed.ascending = vec![true, false];
+        let keys = CircuitBuilder::sort_keys(&mixed, &table, &table.rows).unwrap();
+        assert_eq!(keys, Some(fields(&[2, 1, 2])));
+
+        let group_by = GroupByOperation {
+            columns: vec!["l_linenumber".to_string(), "l_quantity".to_string()],
+        };
+        let keys = CircuitBuilder::group_keys(&group_by, &table, &table.rows).unwrap();
+        assert_eq!(
+            keys,
+            Some(fields(&[(2 << 32) + 5, (1 << 32) + 7, (2 << 32) + 3]))
+        );
+
+        // An INTEGER and a BIGINT key don't fit the gate
+        let group_by = GroupByOperation {
+            columns: vec!["l_linenumber".to_string(), "l_orderkey".to_string()],
+        };
+        assert_eq!(
+            CircuitBuilder::group_keys(&group_by, &table, &table.rows).unwrap(),
+            None
+        );
     }
 
     #[test]
@@ -227,9 +2149,8 @@ mod tests {
     fn test_with_sort() {
         let builder = CircuitBuilder::new();
         let input_values = vec![Field::from(3u64), Field::from(1u64), Field::from(2u64)];
-        let alpha = Field::from(42u64);
 
-        let result = builder.with_sort(input_values, alpha);
+        let result = builder.with_sort(input_values);
         assert!(result.is_ok());
     }
 }
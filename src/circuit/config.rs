@@ -11,11 +11,137 @@
 //! - Manages column assignments
 //! - Enables/disables gates dynamically
 
+use crate::gates::hll::HLL_PRECISION;
 use crate::gates::{
-    AggregationConfig, BitwiseRangeCheckConfig, GroupByConfig, JoinConfig, SortConfig,
+    AggregationConfig, ArithmeticConfig, BitwiseRangeCheckConfig, CountConfig, DistinctCountConfig,
+    GroupByConfig, HllConfig, JoinConfig, LessThanConfig, MinMaxConfig, NBitRangeCheckConfig,
+    SelectionConfig, SortConfig,
 };
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::plonk::*;
+use std::collections::HashMap;
+
+/// Bit widths of the narrow range checks, in increasing order
+///
+/// Booleans need 1 bit, 32-bit integers 32, and dates 48; wider values use
+/// the 64-bit range check.
+pub const NARROW_RANGE_BITS: [usize; 3] = [1, 32, 48];
+
+/// Gates enabled in a circuit configuration
+///
+/// Gates enabled together with another (e.g., count with aggregation) have
+/// no flag of their own. Gates only some queries use (e.g., the distinct
+/// count) have one, so circuits of other queries don't pay for their
+/// columns.
+///
+/// # Example
+///
+/// ```rust
+/// use nzengi_db::circuit::config::GateSet;
+///
+/// let gates = GateSet {
+///     range_check: true,
+///     aggregation: true,
+///     ..GateSet::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GateSet {
+    /// Range check gate, with selection
+    pub range_check: bool,
+
+    /// Narrow range checks, one per width in `NARROW_RANGE_BITS` (with range
+    /// check)
+    pub narrow_range_check: bool,
+
+    /// Sort gate
+    pub sort: bool,
+
+    /// Group-by gate
+    pub group_by: bool,
+
+    /// Distinct-count gate (with group-by)
+    pub distinct_count: bool,
+
+    /// Join gate
+    pub join: bool,
+
+    /// Aggregation gate, with count and MIN/MAX
+    pub aggregation: bool,
+
+    /// Truncating AVG with a remainder below COUNT (with aggregation and
+    /// arithmetic)
+    pub avg_remainder: bool,
+
+    /// HyperLogLog sketch gate (with range check)
+    pub sketch: bool,
+
+    /// Arithmetic gate, with the less-than gate
+    pub arithmetic: bool,
+}
+
+impl GateSet {
+    /// All gates enabled
+    pub fn all() -> Self {
+        Self {
+            range_check: true,
+            narrow_range_check: true,
+            sort: true,
+            group_by: true,
+            distinct_count: true,
+            join: true,
+            aggregation: true,
+            avg_remainder: true,
+            sketch: true,
+            arithmetic: true,
+        }
+    }
+
+    /// Encode the gates as flags, one bit per gate in field order
+    pub fn to_bits(&self) -> u16 {
+        self.flags()
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, &flag)| bits | (u16::from(flag) << i))
+    }
+
+    /// Decode gates encoded by [`GateSet::to_bits`]
+    ///
+    /// # Returns
+    /// `Some(gates)`, `None` if a bit past the last gate is set
+    pub fn from_bits(bits: u16) -> Option<Self> {
+        let flag = |i: u32| bits & (1 << i) != 0;
+        let gates = Self {
+            range_check: flag(0),
+            narrow_range_check: flag(1),
+            sort: flag(2),
+            group_by: flag(3),
+            distinct_count: flag(4),
+            join: flag(5),
+            aggregation: flag(6),
+            avg_remainder: flag(7),
+            sketch: flag(8),
+            arithmetic: flag(9),
+        };
+        (gates.to_bits() == bits).then_some(gates)
+    }
+
+    /// Get the flag of each gate, in field order
+    fn flags(&self) -> [bool; 10] {
+        [
+            self.range_check,
+            self.narrow_range_check,
+            self.sort,
+            self.group_by,
+            self.distinct_count,
+            self.join,
+            self.aggregation,
+            self.avg_remainder,
+            self.sketch,
+            self.arithmetic,
+        ]
+    }
+}
 
 /// Configuration for nzengi circuit
 ///
@@ -25,17 +151,51 @@ pub struct CircuitConfig {
     /// Range check gate configuration
     pub range_check: Option<BitwiseRangeCheckConfig>,
 
+    /// Narrow range checks, one per width in `NARROW_RANGE_BITS` (requires
+    /// range check)
+    pub narrow_range_checks: Vec<NBitRangeCheckConfig>,
+
+    /// Selection gate configuration (enabled together with range check)
+    pub selection: Option<SelectionConfig>,
+
     /// Sort gate configuration
     pub sort: Option<SortConfig>,
 
     /// Group-by gate configuration
     pub group_by: Option<GroupByConfig>,
 
+    /// Distinct-count gate configuration (requires group-by)
+    pub distinct_count: Option<DistinctCountConfig>,
+
     /// Join gate configuration
     pub join: Option<JoinConfig>,
 
     /// Aggregation gate configuration
     pub aggregation: Option<AggregationConfig>,
+
+    /// Count gate configuration (enabled together with aggregation)
+    pub count: Option<CountConfig>,
+
+    /// Public MIN/MAX configuration (enabled together with aggregation)
+    pub min_max: Option<MinMaxConfig>,
+
+    /// HyperLogLog sketch configuration (requires range check)
+    pub sketch: Option<HllConfig>,
+
+    /// Arithmetic gate configuration
+    pub arithmetic: Option<ArithmeticConfig>,
+
+    /// Less-than gate configuration (shared with the arithmetic gate)
+    pub less_than: Option<LessThanConfig>,
+
+    /// Instance column for public inputs/outputs
+    pub instance: Column<Instance>,
+
+    /// Number of advice columns allocated by this configuration
+    advice_columns: usize,
+
+    /// Number of fixed columns allocated by this configuration
+    fixed_columns: usize,
 }
 
 impl CircuitConfig {
@@ -43,50 +203,89 @@ impl CircuitConfig {
     ///
     /// # Arguments
     /// * `meta` - Constraint system metadata
-    /// * `enable_range_check` - Enable range check gate
-    /// * `enable_sort` - Enable sort gate
-    /// * `enable_group_by` - Enable group-by gate
-    /// * `enable_join` - Enable join gate
-    /// * `enable_aggregation` - Enable aggregation gate
+    /// * `gates` - Gates to enable
     ///
     /// # Returns
     /// `CircuitConfig` with configured gates
-    pub fn new(
-        meta: &mut ConstraintSystem<Field>,
-        enable_range_check: bool,
-        enable_sort: bool,
-        enable_group_by: bool,
-        enable_join: bool,
-        enable_aggregation: bool,
-    ) -> Self {
+    pub fn new(meta: &mut ConstraintSystem<Field>, gates: &GateSet) -> Self {
+        let GateSet {
+            range_check: enable_range_check,
+            narrow_range_check: enable_narrow_range_check,
+            sort: enable_sort,
+            group_by: enable_group_by,
+            distinct_count: enable_distinct_count,
+            join: enable_join,
+            aggregation: enable_aggregation,
+            avg_remainder: enable_avg_remainder,
+            sketch: enable_sketch,
+            arithmetic: enable_arithmetic,
+        } = *gates;
+
         // Calculate total number of advice columns needed
         // Range check: 9 columns (1 value + 8 u8 cells)
-        // Sort: 4 columns (input, output, z, alpha)
-        // Group-by: 5 columns (sorted, start_idx, end_idx, binary_marker, helper_p)
-        // Join: 6 columns (t1_join, t2_join, result_t1_join, result_t2_join, sorted_union, z)
+        //   plus 10 columns for the selection gate (column, value, rhs, raw, flag,
+        //   null, null_inverse, eq_inverse, running, output)
+        //   plus 7 columns shared by the narrow range checks (1 value + 6 u8 cells)
+        // Sort: 3 columns (input, output, difference)
+        // Group-by: 6 columns (sorted, start_idx, end_idx, binary_marker, helper_p, input)
+        //   plus 10 columns for the distinct count (key_input, value_input,
+        //   key, value, marker, null, null_inverse, new_value, value_helper,
+        //   count)
+        // Join: 6 columns (t1_join, t2_join, result_t1_join, result_t2_join, union, sorted_union)
         // Aggregation: 8 columns (value, binary_marker, accumulator, start_idx, end_idx, sum, count, avg)
+        //   plus 2 columns for the public count (selection, running)
+        //   plus 2 columns for the public MIN/MAX (min, max)
+        //   plus 1 column for the AVG remainder
+        // Sketch: 20 columns (bucket, rho, last, carry, a, b, z, observed, word,
+        //   8 u8 cells, 3 auxiliary)
+        // Arithmetic: 4 columns (a, b, out, aux)
+
+        // Lookup tables allocate fixed columns; count only the ones added here
+        let fixed_columns_before = meta.num_fixed_columns();
 
         let mut total_columns = 0;
         if enable_range_check {
-            total_columns += 9;
+            total_columns += 9 + 10;
+        }
+        let enable_narrow_range_check = enable_range_check && enable_narrow_range_check;
+        if enable_narrow_range_check {
+            total_columns += 7;
         }
         if enable_sort {
-            total_columns += 4;
+            total_columns += 3;
         }
+        let enable_distinct_count = enable_group_by && enable_distinct_count;
         if enable_group_by {
-            total_columns += 5;
+            total_columns += 6;
+        }
+        if enable_distinct_count {
+            total_columns += 10;
         }
         if enable_join {
             total_columns += 6;
         }
         if enable_aggregation {
-            total_columns += 8;
+            total_columns += 8 + 2 + 2;
+        }
+        let enable_avg_remainder = enable_aggregation && enable_arithmetic && enable_avg_remainder;
+        if enable_avg_remainder {
+            total_columns += 1;
+        }
+        let enable_sketch = enable_range_check && enable_sketch;
+        if enable_sketch {
+            total_columns += 20;
+        }
+        if enable_arithmetic {
+            total_columns += 4;
         }
 
         // Create advice columns
         let advice_columns: Vec<Column<Advice>> =
             (0..total_columns).map(|_| meta.advice_column()).collect();
 
+        // Instance column for public outputs (e.g., COUNT(*))
+        let instance = meta.instance_column();
+
         // Lookup tables use TableColumn, not fixed columns
         // No need to create fixed columns for range check lookup table
 
@@ -94,19 +293,42 @@ impl CircuitConfig {
         let mut col_idx = 0;
 
         // Range check gate
-        let range_check = if enable_range_check {
+        let (range_check, narrow_range_checks, selection) = if enable_range_check {
             let advice = &advice_columns[col_idx..col_idx + 9];
+            let selection_advice = &advice_columns[col_idx + 9..col_idx + 19];
             // Range check gate now uses TableColumn internally, no fixed columns needed
-            col_idx += 9;
-            Some(BitwiseRangeCheckConfig::configure(meta, advice, &[]))
+            col_idx += 19;
+            let range_check = BitwiseRangeCheckConfig::configure(meta, advice, &[]);
+            // The selection gate adds fixed columns for negations, positions,
+            // tags and constants
+            let selection = SelectionConfig::configure(meta, range_check.clone(), selection_advice);
+            // Narrow widths share columns and the u8 table, selected per row
+            let narrow = if enable_narrow_range_check {
+                let narrow_advice = &advice_columns[col_idx..col_idx + 7];
+                col_idx += 7;
+                NARROW_RANGE_BITS
+                    .iter()
+                    .map(|&bits| {
+                        NBitRangeCheckConfig::configure(
+                            meta,
+                            narrow_advice,
+                            range_check.u8_table,
+                            bits,
+                        )
+                    })
+                    .collect()
+            } else {
+                vec![]
+            };
+            (Some(range_check), narrow, Some(selection))
         } else {
-            None
+            (None, vec![], None)
         };
 
         // Sort gate
         let sort = if enable_sort {
-            let advice = &advice_columns[col_idx..col_idx + 4];
-            col_idx += 4;
+            let advice = &advice_columns[col_idx..col_idx + 3];
+            col_idx += 3;
             Some(SortConfig::configure(meta, advice))
         } else {
             None
@@ -114,13 +336,22 @@ impl CircuitConfig {
 
         // Group-by gate
         let group_by = if enable_group_by {
-            let advice = &advice_columns[col_idx..col_idx + 5];
-            col_idx += 5;
+            let advice = &advice_columns[col_idx..col_idx + 6];
+            col_idx += 6;
             Some(GroupByConfig::configure(meta, advice))
         } else {
             None
         };
 
+        // Distinct-count gate, over the groups of the group-by gate
+        let distinct_count = if enable_distinct_count {
+            let advice = &advice_columns[col_idx..col_idx + 10];
+            col_idx += 10;
+            Some(DistinctCountConfig::configure(meta, advice))
+        } else {
+            None
+        };
+
         // Join gate
         let join = if enable_join {
             let advice = &advice_columns[col_idx..col_idx + 6];
@@ -131,21 +362,227 @@ impl CircuitConfig {
         };
 
         // Aggregation gate
-        let aggregation = if enable_aggregation {
+        let (aggregation, count, min_max) = if enable_aggregation {
             let advice = &advice_columns[col_idx..col_idx + 8];
-            Some(AggregationConfig::configure(meta, advice))
+            let count_advice = &advice_columns[col_idx + 8..col_idx + 10];
+            let min_max_advice = &advice_columns[col_idx + 10..col_idx + 12];
+            col_idx += 12;
+            (
+                Some(AggregationConfig::configure(meta, advice)),
+                Some(CountConfig::configure(meta, count_advice, instance)),
+                Some(MinMaxConfig::configure(meta, min_max_advice, instance)),
+            )
+        } else {
+            (None, None, None)
+        };
+
+        // Sketch gate, range checking its words with the u8 table
+        let sketch = match (&range_check, enable_sketch) {
+            (Some(range_check), true) => {
+                let advice = &advice_columns[col_idx..col_idx + 20];
+                col_idx += 20;
+                Some(HllConfig::configure(
+                    meta,
+                    advice,
+                    range_check.u8_table,
+                    instance,
+                    HLL_PRECISION,
+                ))
+            }
+            _ => None,
+        };
+
+        // Arithmetic gate
+        let arithmetic = if enable_arithmetic {
+            let advice = &advice_columns[col_idx..col_idx + 4];
+            col_idx += 4;
+            Some(ArithmeticConfig::configure(meta, advice))
         } else {
             None
         };
 
+        // Less-than gate, reusing the one bounding arithmetic division rows
+        let less_than = arithmetic.as_ref().map(|a| a.less_than.clone());
+
+        // Prove group-by and distinct-count input order with the same less-than gate
+        let (group_by, distinct_count) = match &less_than {
+            Some(lt) => (
+                group_by.map(|g| g.with_order_check(lt.clone())),
+                distinct_count.map(|d| d.with_order_check(lt.clone())),
+            ),
+            None => (group_by, distinct_count),
+        };
+
+        // Truncate AVG, bounding its remainder by COUNT with the same less-than gate
+        let aggregation = match (aggregation, &less_than) {
+            (Some(aggregation), Some(lt)) if enable_avg_remainder => {
+                Some(aggregation.with_remainder_check(meta, advice_columns[col_idx], lt.clone()))
+            }
+            (aggregation, _) => aggregation,
+        };
+
         Self {
             range_check,
+            narrow_range_checks,
+            selection,
             sort,
             group_by,
+            distinct_count,
             join,
             aggregation,
+            count,
+            min_max,
+            sketch,
+            arithmetic,
+            less_than,
+            instance,
+            advice_columns: total_columns,
+            fixed_columns: meta.num_fixed_columns() - fixed_columns_before,
         }
     }
+
+    /// Get the names of the enabled gates
+    ///
+    /// # Returns
+    /// Gate names in allocation order (e.g., `["range_check", "selection"]`)
+    pub fn enabled_gates(&self) -> Vec<&'static str> {
+        [
+            ("range_check", self.range_check.is_some()),
+            ("narrow_range_check", !self.narrow_range_checks.is_empty()),
+            ("selection", self.selection.is_some()),
+            ("sort", self.sort.is_some()),
+            ("group_by", self.group_by.is_some()),
+            ("distinct_count", self.distinct_count.is_some()),
+            ("join", self.join.is_some()),
+            ("aggregation", self.aggregation.is_some()),
+            ("count", self.count.is_some()),
+            ("min_max", self.min_max.is_some()),
+            ("sketch", self.sketch.is_some()),
+            ("arithmetic", self.arithmetic.is_some()),
+            ("less_than", self.less_than.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name)
+        .collect()
+    }
+
+    /// Get the number of constraints each enabled gate contributes
+    ///
+    /// # Arguments
+    /// * `sizes` - Input length per gate name (as in `enabled_gates`);
+    ///   missing gates have no input
+    ///
+    /// # Returns
+    /// `(gate name, constraint count)` for each enabled gate, in allocation order
+    pub fn constraint_profile(&self, sizes: &HashMap<&str, usize>) -> Vec<(String, usize)> {
+        let size = |name: &str| sizes.get(name).copied().unwrap_or(0);
+
+        [
+            (
+                "range_check",
+                self.range_check
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("range_check"))),
+            ),
+            (
+                // Counted at the widest narrow width
+                "narrow_range_check",
+                self.narrow_range_checks
+                    .last()
+                    .map(|g| g.num_constraints(size("narrow_range_check"))),
+            ),
+            (
+                "selection",
+                self.selection
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("selection"))),
+            ),
+            (
+                "sort",
+                self.sort.as_ref().map(|g| g.num_constraints(size("sort"))),
+            ),
+            (
+                "group_by",
+                self.group_by
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("group_by"))),
+            ),
+            (
+                "distinct_count",
+                self.distinct_count
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("distinct_count"))),
+            ),
+            (
+                "join",
+                self.join.as_ref().map(|g| g.num_constraints(size("join"))),
+            ),
+            (
+                "aggregation",
+                self.aggregation
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("aggregation"))),
+            ),
+            (
+                "count",
+                self.count
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("count"))),
+            ),
+            (
+                "min_max",
+                self.min_max
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("min_max"))),
+            ),
+            (
+                "sketch",
+                self.sketch
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("sketch"))),
+            ),
+            (
+                "arithmetic",
+                self.arithmetic
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("arithmetic"))),
+            ),
+            (
+                "less_than",
+                self.less_than
+                    .as_ref()
+                    .map(|g| g.num_constraints(size("less_than"))),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, count)| count.map(|count| (name.to_string(), count)))
+        .collect()
+    }
+
+    /// Get the narrowest range check covering a bit width
+    ///
+    /// # Arguments
+    /// * `bits` - Bit width of the checked values
+    ///
+    /// # Returns
+    /// The narrow range check with the smallest width of at least `bits`,
+    /// `None` if the values need the 64-bit range check
+    pub fn narrow_range_check(&self, bits: usize) -> Option<&NBitRangeCheckConfig> {
+        self.narrow_range_checks.iter().find(|g| g.bits >= bits)
+    }
+
+    /// Get the number of advice columns allocated by this configuration
+    pub fn num_advice_columns(&self) -> usize {
+        self.advice_columns
+    }
+
+    /// Get the number of fixed columns allocated by this configuration
+    ///
+    /// This includes the columns backing lookup tables.
+    pub fn num_fixed_columns(&self) -> usize {
+        self.fixed_columns
+    }
 }
 
 #[cfg(test)]
@@ -155,25 +592,39 @@ mod tests {
     #[test]
     fn test_circuit_config_new() {
         let mut meta = ConstraintSystem::<Field>::default();
-        let config = CircuitConfig::new(&mut meta, true, true, true, true, true);
+        let config = CircuitConfig::new(&mut meta, &GateSet::all());
 
         assert!(
             config.range_check.is_some(),
             "Range check should be enabled"
         );
+        assert!(config.selection.is_some(), "Selection should be enabled");
         assert!(config.sort.is_some(), "Sort should be enabled");
         assert!(config.group_by.is_some(), "Group-by should be enabled");
+        assert!(
+            config.distinct_count.is_some(),
+            "Distinct count should be enabled"
+        );
         assert!(config.join.is_some(), "Join should be enabled");
         assert!(
             config.aggregation.is_some(),
             "Aggregation should be enabled"
         );
+        assert!(config.count.is_some(), "Count should be enabled");
+        assert!(config.min_max.is_some(), "MIN/MAX should be enabled");
+        assert!(config.sketch.is_some(), "Sketch should be enabled");
+        assert!(config.arithmetic.is_some(), "Arithmetic should be enabled");
+        assert!(config.less_than.is_some(), "Less-than should be enabled");
     }
 
     #[test]
     fn test_circuit_config_selective() {
         let mut meta = ConstraintSystem::<Field>::default();
-        let config = CircuitConfig::new(&mut meta, true, false, false, false, false);
+        let gates = GateSet {
+            range_check: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut meta, &gates);
 
         assert!(
             config.range_check.is_some(),
@@ -186,5 +637,228 @@ mod tests {
             config.aggregation.is_none(),
             "Aggregation should be disabled"
         );
+        assert!(config.count.is_none(), "Count should be disabled");
+        assert!(config.arithmetic.is_none(), "Arithmetic should be disabled");
+    }
+
+    #[test]
+    fn test_circuit_config_gate_set() {
+        let mut meta = ConstraintSystem::<Field>::default();
+        let gates = GateSet {
+            range_check: true,
+            aggregation: true,
+            ..GateSet::default()
+        };
+        assert_ne!(gates, GateSet::all());
+        let config = CircuitConfig::new(&mut meta, &gates);
+
+        // Range check and aggregation bring the gates enabled with them
+        assert_eq!(
+            config.enabled_gates(),
+            vec![
+                "range_check",
+                "selection",
+                "aggregation",
+                "count",
+                "min_max"
+            ]
+        );
+        assert!(config.sort.is_none());
+        assert!(config.group_by.is_none());
+        assert!(config.join.is_none());
+        assert!(config.narrow_range_checks.is_empty());
+        assert!(config.sketch.is_none());
+        assert!(config.arithmetic.is_none());
+        assert!(config.less_than.is_none());
+    }
+
+    #[test]
+    fn test_circuit_config_distinct_count() {
+        let gates = GateSet {
+            group_by: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.group_by.is_some());
+        assert!(config.distinct_count.is_none());
+        assert_eq!(config.num_advice_columns(), 6);
+
+        let gates = GateSet {
+            distinct_count: true,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.distinct_count.is_some());
+        assert_eq!(config.num_advice_columns(), 16);
+
+        // Without group-by there are no groups to count in
+        let gates = GateSet {
+            group_by: false,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.distinct_count.is_none());
+        assert_eq!(config.num_advice_columns(), 0);
+    }
+
+    #[test]
+    fn test_gate_set_bits() {
+        let gates = GateSet {
+            range_check: true,
+            aggregation: true,
+            arithmetic: true,
+            ..GateSet::default()
+        };
+        assert_eq!(gates.to_bits(), 0b10_0100_0001);
+        assert_eq!(GateSet::from_bits(gates.to_bits()), Some(gates));
+        assert_eq!(
+            GateSet::from_bits(GateSet::all().to_bits()),
+            Some(GateSet::all())
+        );
+        assert_eq!(GateSet::from_bits(0), Some(GateSet::default()));
+
+        // Bits past the last gate don't decode
+        assert_eq!(GateSet::from_bits(1 << 10), None);
+    }
+
+    #[test]
+    fn test_circuit_config_narrow_range_check() {
+        let gates = GateSet {
+            range_check: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.narrow_range_check(1).is_none());
+        assert_eq!(config.num_advice_columns(), 19);
+
+        // The narrow widths share their columns and the u8 table
+        let gates = GateSet {
+            narrow_range_check: true,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert_eq!(config.narrow_range_checks.len(), NARROW_RANGE_BITS.len());
+        assert_eq!(config.num_advice_columns(), 26);
+
+        // Without range check there is no u8 table to look their cells up in
+        let gates = GateSet {
+            range_check: false,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.narrow_range_checks.is_empty());
+        assert_eq!(config.num_advice_columns(), 0);
+    }
+
+    #[test]
+    fn test_circuit_config_sketch() {
+        let gates = GateSet {
+            range_check: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.sketch.is_none());
+        assert_eq!(config.num_advice_columns(), 19);
+        assert_eq!(config.num_fixed_columns(), 5);
+
+        // The sketch adds its columns and rho table
+        let gates = GateSet {
+            sketch: true,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.sketch.is_some());
+        assert_eq!(config.num_advice_columns(), 39);
+        assert_eq!(config.num_fixed_columns(), 8);
+
+        // Without range check there is no u8 table for its words
+        let gates = GateSet {
+            range_check: false,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.sketch.is_none());
+        assert_eq!(config.num_advice_columns(), 0);
+    }
+
+    #[test]
+    fn test_circuit_config_avg_remainder() {
+        let gates = GateSet {
+            aggregation: true,
+            arithmetic: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.aggregation.unwrap().remainder_check.is_none());
+        assert_eq!(config.num_advice_columns(), 16);
+
+        // The remainder check adds its column, bounded by the less-than gate
+        let gates = GateSet {
+            avg_remainder: true,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.aggregation.unwrap().remainder_check.is_some());
+        assert_eq!(config.num_advice_columns(), 17);
+
+        // Without arithmetic there is no less-than gate to bound it
+        let gates = GateSet {
+            arithmetic: false,
+            ..gates
+        };
+        let config = CircuitConfig::new(&mut ConstraintSystem::default(), &gates);
+        assert!(config.aggregation.unwrap().remainder_check.is_none());
+        assert_eq!(config.num_advice_columns(), 12);
+    }
+
+    #[test]
+    fn test_circuit_config_introspection() {
+        let mut meta = ConstraintSystem::<Field>::default();
+        let config = CircuitConfig::new(
+            &mut meta,
+            &GateSet {
+                range_check: true,
+                aggregation: true,
+                ..GateSet::default()
+            },
+        );
+
+        let gates = config.enabled_gates();
+        assert!(gates.contains(&"range_check"));
+        assert!(gates.contains(&"aggregation"));
+        assert!(!gates.contains(&"sort"));
+        assert!(!gates.contains(&"arithmetic"));
+
+        // Range check: 9 + 10 (selection),
+        // aggregation: 8 + 2 (count) + 2 (MIN/MAX)
+        assert_eq!(config.num_advice_columns(), 31);
+        // u8 lookup table, selection negations, positions, tags and constants
+        assert_eq!(config.num_fixed_columns(), 5);
+    }
+
+    #[test]
+    fn test_circuit_config_constraint_profile() {
+        let mut meta = ConstraintSystem::<Field>::default();
+        let gates = GateSet {
+            range_check: true,
+            sort: true,
+            aggregation: true,
+            ..GateSet::default()
+        };
+        let config = CircuitConfig::new(&mut meta, &gates);
+
+        let sizes = HashMap::from([("range_check", 3), ("sort", 4), ("count", 10)]);
+        let profile = config.constraint_profile(&sizes);
+
+        let names: Vec<&str> = profile.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, config.enabled_gates());
+
+        let count_of = |gate: &str| profile.iter().find(|(name, _)| name == gate).unwrap().1;
+        assert_eq!(count_of("range_check"), 3 * 9);
+        assert_eq!(count_of("selection"), 0);
+        // 4 shuffle rows + 3 comparisons of 66
+        assert_eq!(count_of("sort"), 4 + 3 * 66);
+        assert_eq!(count_of("count"), 22);
+        assert_eq!(count_of("min_max"), 0);
     }
 }
@@ -14,10 +14,26 @@
 //!
 //! The circuit integrates the following custom gates:
 //! - Range Check Gate: Bitwise decomposition for range validation
+//! - Selection Gate: Filter outputs are exactly the input rows satisfying `>`
 //! - Sort Gate: Permutation and sortedness checks
 //! - Group-By Gate: Group boundary detection and validation
 //! - Join Gate: Deduplication, disjointness, and join predicate validation
 //! - Aggregation Gate: SUM, COUNT, AVG, MIN, MAX operations
+//! - Distinct-Count Gate: Public COUNT(DISTINCT) per group
+//! - HyperLogLog Gate: Public sketch registers for APPROX_COUNT_DISTINCT
+//! - Arithmetic Gate: Expressions computed on the selected rows
+//!
+//! # Layout
+//!
+//! Gates are laid out by the `V1` floor planner, which packs each gate's
+//! region into the earliest rows free in its columns instead of stacking
+//! all regions one after another. Gates in disjoint columns share rows, and
+//! every assigned cell has a fixed absolute position, so cells of one gate
+//! can be copy-constrained to cells of another (e.g., group-by boundaries
+//! to the aggregation gate's indices).
+//!
+//! The planner is a type parameter of the circuit, so callers can lay a
+//! circuit out with `SimpleFloorPlanner` instead (see the `planner` module).
 //!
 //! # Example
 //!
@@ -25,7 +41,7 @@
 //! use nzengi_db::circuit::NzengiCircuit;
 //! use halo2_proofs::plonk::Circuit;
 //!
-//! let circuit = NzengiCircuit::default();
+//! let circuit = NzengiCircuit::new();
 //! let k = 10; // 2^10 = 1024 rows
 //! let prover = MockProver::run(k, &circuit, vec![]).unwrap();
 //! assert_eq!(prover.verify(), Ok(()));
@@ -34,40 +50,130 @@
 pub mod builder;
 pub mod config;
 pub mod layouter;
+pub mod planner;
 
 // Re-export main types for convenience
 pub use builder::CircuitBuilder;
-pub use config::CircuitConfig;
+pub use config::{CircuitConfig, GateSet};
 pub use layouter::CircuitLayouter;
+pub use planner::{Planner, SimplePlanner, V1Planner};
 
+use crate::field::FieldUtils;
+use crate::gates::arithmetic::{ArithmeticConfig, Op, Source};
+use crate::gates::hll::HLL_PRECISION;
+use crate::gates::selection::{Combine, Predicate};
+use crate::gates::{
+    AggregationCells, DistinctCountConfig, GroupByConfig, HllConfig, HyperLogLog, SelectionConfig,
+    ValidationMode,
+};
+use crate::proof::KeyCircuit;
+use crate::types::Value as SqlValue;
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner},
+    circuit::{AssignedCell, Layouter, Value},
     plonk::{Circuit, ConstraintSystem, ErrorFront},
 };
+use std::marker::PhantomData;
 
 /// Nzengi circuit for SQL query verification
 ///
 /// This circuit integrates all custom gates for proving SQL query correctness.
+/// It is laid out by the floor planner `P` (`V1` by default).
 #[derive(Default, Debug, Clone)]
-pub struct NzengiCircuit {
+pub struct NzengiCircuit<P: Planner = V1Planner> {
     /// Circuit configuration
     _config: Option<config::CircuitConfig>,
 
-    /// Range check data (optional)
-    range_check_data: Option<(u64, Vec<u8>)>,
+    /// Range check data as (value, u8 cells), sharing one lookup table (optional)
+    range_check_data: Option<Vec<(u64, Vec<u8>)>>,
+
+    /// Range checks narrower than 64 bits as (value, bit width) (optional)
+    narrow_range_check_data: Option<Vec<(u64, usize)>>,
 
-    /// Sort data (optional)
-    sort_data: Option<(Vec<Field>, Vec<Field>, Field)>,
+    /// Sorts as (input values, sorted values, whether the sorted values are
+    /// public), each proved under its own tag (optional)
+    sort_data: Option<Vec<(Vec<Field>, Vec<Field>, bool)>>,
 
-    /// Group-by data (optional)
-    group_by_data: Option<Vec<Field>>,
+    /// Group-by data as (input keys, sorted keys) (optional)
+    group_by_data: Option<(Vec<Field>, Vec<Field>)>,
 
     /// Join data (optional)
     join_data: Option<(Vec<Field>, Vec<Field>, Vec<(Field, Field)>)>,
 
     /// Aggregation data (optional)
     aggregation_data: Option<(Vec<Field>, Vec<Field>, Vec<Field>, Vec<Field>)>,
+
+    /// Whether the aggregation proves AVG truncated, with a remainder below
+    /// COUNT, instead of the field quotient
+    avg_remainder: bool,
+
+    /// Rows of each computed expression, evaluated on the selected rows (optional)
+    computed_data: Option<Vec<Vec<(Source, Op, Source)>>>,
+
+    /// Less-than checks as (a, b) proving `a < b` (optional)
+    less_than_data: Option<Vec<(Field, Field)>>,
+
+    /// Selection flags for rows satisfying the predicate (optional)
+    selection_data: Option<Vec<Field>>,
+
+    /// Number of table rows, and the table columns read by the predicate
+    /// and the selected cells, one value per table row each (optional)
+    table_data: Option<(usize, Vec<Vec<Field>>)>,
+
+    /// Predicate selecting table rows; all rows without one (optional)
+    predicate: Option<Predicate>,
+
+    /// Table column whose NULLs COUNT skips, for COUNT(column) (optional)
+    count_column: Option<usize>,
+
+    /// Table column whose selected non-NULL cells are the aggregation values
+    /// (optional)
+    selected_column: Option<usize>,
+
+    /// Table column whose selected non-NULL cells are the input of the
+    /// MIN/MAX sort (optional)
+    minmax_column: Option<usize>,
+
+    /// Public COUNT(*) output (optional)
+    count_output: Option<Field>,
+
+    /// Public SUM output over the single aggregated group (optional)
+    sum_output: Option<Field>,
+
+    /// Public (MIN, MAX) output over the sorted values (optional)
+    minmax_output: Option<(Field, Field)>,
+
+    /// Table columns whose selected non-NULL cells are sketched, for
+    /// APPROX_COUNT_DISTINCT (optional)
+    sketch_data: Option<Vec<usize>>,
+
+    /// HAVING `COUNT(*) > k` as ((first aggregation row, count) of each
+    /// kept group, k) (optional)
+    having_data: Option<(Vec<(usize, Field)>, Field)>,
+
+    /// Table columns whose distinct values each group counts, for
+    /// COUNT(DISTINCT) (optional)
+    distinct_count_data: Option<Vec<usize>>,
+
+    /// Table columns whose selected cells are public, for the cell openings
+    /// of their committed columns (optional)
+    opened_data: Option<Vec<usize>>,
+
+    /// Constants exposed as public inputs, fixed by the circuit (optional)
+    public_input_data: Option<Vec<Field>>,
+
+    /// Hash of the database commitment the proof is bound to (optional)
+    commitment_hash: Option<Field>,
+
+    /// How thoroughly the derived-witness gates check their witness
+    validation: ValidationMode,
+
+    /// Gates configured regardless of the data, for circuits rebuilt from
+    /// a verifying key file (optional)
+    configured_gates: Option<GateSet>,
+
+    /// Floor planner laying out the circuit
+    _planner: PhantomData<P>,
 }
 
 impl NzengiCircuit {
@@ -75,27 +181,112 @@ impl NzengiCircuit {
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<P: Planner> NzengiCircuit<P> {
+    /// Lay the circuit out with another floor planner
+    ///
+    /// # Example
+    /// ```
+    /// use nzengi_db::circuit::{NzengiCircuit, SimplePlanner};
+    ///
+    /// let circuit = NzengiCircuit::new().with_floor_planner::<SimplePlanner>();
+    /// ```
+    pub fn with_floor_planner<Q: Planner>(self) -> NzengiCircuit<Q> {
+        NzengiCircuit {
+            _config: self._config,
+            range_check_data: self.range_check_data,
+            narrow_range_check_data: self.narrow_range_check_data,
+            sort_data: self.sort_data,
+            group_by_data: self.group_by_data,
+            join_data: self.join_data,
+            aggregation_data: self.aggregation_data,
+            avg_remainder: self.avg_remainder,
+            computed_data: self.computed_data,
+            less_than_data: self.less_than_data,
+            selection_data: self.selection_data,
+            table_data: self.table_data,
+            predicate: self.predicate,
+            count_column: self.count_column,
+            selected_column: self.selected_column,
+            minmax_column: self.minmax_column,
+            count_output: self.count_output,
+            sum_output: self.sum_output,
+            minmax_output: self.minmax_output,
+            sketch_data: self.sketch_data,
+            having_data: self.having_data,
+            distinct_count_data: self.distinct_count_data,
+            opened_data: self.opened_data,
+            public_input_data: self.public_input_data,
+            commitment_hash: self.commitment_hash,
+            validation: self.validation,
+            configured_gates: self.configured_gates,
+            _planner: PhantomData,
+        }
+    }
 
-    /// Set range check data
+    /// Add a range check
     pub fn with_range_check(mut self, value: u64, u8_cells: Vec<u8>) -> Self {
-        self.range_check_data = Some((value, u8_cells));
+        self.range_check_data
+            .get_or_insert_with(Vec::new)
+            .push((value, u8_cells));
         self
     }
 
-    /// Set sort data
-    pub fn with_sort(
-        mut self,
-        input_values: Vec<Field>,
-        sorted_values: Vec<Field>,
-        alpha: Field,
-    ) -> Self {
-        self.sort_data = Some((input_values, sorted_values, alpha));
+    /// Add several range checks, all checked against one lookup table
+    pub fn with_range_checks(mut self, checks: Vec<(u64, Vec<u8>)>) -> Self {
+        self.range_check_data
+            .get_or_insert_with(Vec::new)
+            .extend(checks);
+        self
+    }
+
+    /// Add a range check proving `value < 2^bits`
+    ///
+    /// Widths of 64 bits or more use the 64-bit range check; narrower widths
+    /// use the narrowest configured range check that covers them.
+    pub fn with_range_check_bits(mut self, value: u64, bits: usize) -> Self {
+        if bits >= 64 {
+            let u8_cells = FieldUtils::decompose_u64(value);
+            return self.with_range_check(value, u8_cells.to_vec());
+        }
+        self.narrow_range_check_data
+            .get_or_insert_with(Vec::new)
+            .push((value, bits));
+        self
+    }
+
+    /// Add a sort
+    ///
+    /// The sort gate proves the sorted values are an ascending permutation
+    /// of the input values. Repeated calls append; the sorts share the gate
+    /// and are told apart by their tags (1 for the first sort, and so on).
+    pub fn with_sort(mut self, input_values: Vec<Field>, sorted_values: Vec<Field>) -> Self {
+        self.sort_data
+            .get_or_insert_with(Vec::new)
+            .push((input_values, sorted_values, false));
+        self
+    }
+
+    /// Add a sort and expose its sorted values as public outputs
+    ///
+    /// Like [`with_sort`](Self::with_sort), but the sorted values are bound
+    /// to the instance rows after the distinct counts (if any), so a verifier
+    /// holding an ORDER BY result can check its keys are the proved order.
+    pub fn with_sort_output(mut self, input_values: Vec<Field>, sorted_values: Vec<Field>) -> Self {
+        self.sort_data
+            .get_or_insert_with(Vec::new)
+            .push((input_values, sorted_values, true));
         self
     }
 
     /// Set group-by data
-    pub fn with_group_by(mut self, sorted_values: Vec<Field>) -> Self {
-        self.group_by_data = Some(sorted_values);
+    ///
+    /// The group-by gate proves the sorted keys are a permutation of the
+    /// input keys. The sorted keys must be non-decreasing and below 2^64;
+    /// synthesis fails otherwise.
+    pub fn with_group_by(mut self, input_values: Vec<Field>, sorted_values: Vec<Field>) -> Self {
+        self.group_by_data = Some((input_values, sorted_values));
         self
     }
 
@@ -121,33 +312,492 @@ impl NzengiCircuit {
         self.aggregation_data = Some((values, binary_markers, start_indices, end_indices));
         self
     }
+
+    /// Prove AVG as the truncated integer average of each group
+    ///
+    /// Each aggregation row proves `sum = avg · count + r` with `avg` and `r`
+    /// below 2^64 and `r < count`, with the less-than gate. The aggregated
+    /// values must be non-negative integers whose group sums are below 2^64.
+    pub fn with_truncated_avg(mut self) -> Self {
+        self.avg_remainder = true;
+        self
+    }
+
+    /// Set the expressions computed on each selected table row
+    ///
+    /// Each expression is a list of arithmetic rows; column operands index
+    /// the table columns (see [`with_table`](Self::with_table)) and are
+    /// copied from the selected cells, and the output of the last row is the
+    /// expression's value. The values are public, row by row, after the
+    /// sorted values.
+    pub fn with_computed(mut self, expressions: Vec<Vec<(Source, Op, Source)>>) -> Self {
+        self.computed_data = Some(expressions);
+        self
+    }
+
+    /// Add a less-than check proving `a < b`, for values below 2^64
+    pub fn with_less_than(mut self, a: Field, b: Field) -> Self {
+        self.less_than_data
+            .get_or_insert_with(Vec::new)
+            .push((a, b));
+        self
+    }
+
+    /// Set selection flags (1 for each table row satisfying the filters, else 0)
+    ///
+    /// The flags select the rows of the table (see
+    /// [`with_table`](Self::with_table)) instead of the predicate (see
+    /// [`with_predicate`](Self::with_predicate)), for filters the predicate
+    /// doesn't express, and every flagged row is constrained to satisfy the
+    /// predicate. They are ignored without a table.
+    pub fn with_selection(mut self, selection: Vec<Field>) -> Self {
+        self.selection_data = Some(selection);
+        self
+    }
+
+    /// Set the table rows and columns, one value per table row each
+    ///
+    /// The cells are assigned once and copied by the gates reading them, so
+    /// the predicate flags, the count and the selected cells all read the
+    /// same table. Predicates refer to the columns by index. The row count
+    /// is separate so that a predicate reading no column (COUNT(*) without
+    /// filters) still flags every row.
+    pub fn with_table(mut self, num_rows: usize, columns: Vec<Vec<Field>>) -> Self {
+        self.table_data = Some((num_rows, columns));
+        self
+    }
+
+    /// Set the predicate selecting table rows (all rows without one)
+    ///
+    /// The selection gate proves the flag of every row from its table cells
+    /// (see `SelectionConfig`).
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Count only the selected rows whose cell of a table column is not
+    /// NULL, for COUNT(column)
+    pub fn with_count_column(mut self, column: usize) -> Self {
+        self.count_column = Some(column);
+        self
+    }
+
+    /// Bind the aggregation values to the selected cells of a table column
+    ///
+    /// The selection gate compacts the non-NULL cells of the rows the
+    /// predicate selects, and the aggregation values are constrained to
+    /// equal them, in order.
+    pub fn with_selected_column(mut self, column: usize) -> Self {
+        self.selected_column = Some(column);
+        self
+    }
+
+    /// Expose COUNT(*) as a public output
+    ///
+    /// The count is bound to instance row 0 and constrained to equal the
+    /// number of table rows the predicate selects, so a verifier learns only
+    /// the count. A count other than the selected rows' leaves the circuit
+    /// unsatisfied, and without a table synthesis fails.
+    pub fn with_count_output(mut self, count: Field) -> Self {
+        self.count_output = Some(count);
+        self
+    }
+
+    /// Expose SUM as a public output
+    ///
+    /// The sum is bound to the instance row after the count (if any) and
+    /// constrained to equal the last accumulator of the aggregation gate, so
+    /// the aggregation data must hold the summed values as a single group.
+    /// The summed values must be bound to a table column with
+    /// [`NzengiCircuit::with_selected_column`]; otherwise synthesis fails.
+    pub fn with_sum_output(mut self, sum: Field) -> Self {
+        self.sum_output = Some(sum);
+        self
+    }
+
+    /// Expose MIN and MAX as public outputs
+    ///
+    /// MIN and MAX are bound to the instance rows after the count and SUM
+    /// (if any) and constrained to equal the first and last sorted values of
+    /// the first sort, so that sort must hold the aggregated values. Its
+    /// input must be bound to a table column with
+    /// [`NzengiCircuit::with_minmax_column`]; otherwise synthesis fails.
+    pub fn with_minmax_output(mut self, min: Field, max: Field) -> Self {
+        self.minmax_output = Some((min, max));
+        self
+    }
+
+    /// Bind the input of the MIN/MAX sort to the selected cells of a table
+    /// column
+    ///
+    /// The selection gate compacts the non-NULL cells of the rows the
+    /// predicate selects, and the input values of the first sort are
+    /// constrained to equal them, in order.
+    pub fn with_minmax_column(mut self, column: usize) -> Self {
+        self.minmax_column = Some(column);
+        self
+    }
+
+    /// Expose the HyperLogLog sketch registers of a table column as public
+    /// outputs
+    ///
+    /// The sketch gate hashes the column's selected cells, copied from the
+    /// selection, skipping NULLs; synthesis fails without a table or if a
+    /// cell is neither NULL nor below 2^64. One register per bucket, at
+    /// precision `HLL_PRECISION`, is bound to the instance rows after the
+    /// count, SUM and MIN/MAX (if any). Repeated calls append, each
+    /// column's registers after the previous one's.
+    pub fn with_sketch(mut self, column: usize) -> Self {
+        self.sketch_data.get_or_insert_with(Vec::new).push(column);
+        self
+    }
+
+    /// Prove HAVING `COUNT(*) > threshold` for the groups kept in a result
+    ///
+    /// Each kept group is given as its first row in the aggregation data and
+    /// its count. The count is copied from that row's COUNT cell of the
+    /// aggregation gate, so it is the count of the proved group; synthesis
+    /// fails if the row doesn't exist. The threshold and then each group
+    /// count are bound to the instance rows after the sketch registers (if
+    /// any), and every count is range-checked to satisfy
+    /// `count - threshold - 1 >= 0`, so a group failing the threshold can't
+    /// be claimed to pass.
+    pub fn with_having(mut self, groups: Vec<(usize, Field)>, threshold: Field) -> Self {
+        self.having_data = Some((groups, threshold));
+        self
+    }
+
+    /// Expose COUNT(DISTINCT) of a table column in each group as public
+    /// outputs
+    ///
+    /// The groups are the group-by gate's, whose input keys must be those
+    /// of the selected rows, in order; synthesis fails without a group-by or
+    /// a table. The distinct-count gate counts the column's distinct
+    /// non-NULL cells of the rows of each group, copied from the selected
+    /// cells. The key and count of each group are bound to the instance rows
+    /// after the HAVING counts (if any). Repeated calls append, each column's
+    /// groups after the previous one's.
+    pub fn with_distinct_count(mut self, column: usize) -> Self {
+        self.distinct_count_data
+            .get_or_insert_with(Vec::new)
+            .push(column);
+        self
+    }
+
+    /// Expose the selected cells of a table column publicly, for a cell
+    /// opening of its committed column
+    ///
+    /// The cells are copied from the compacted selected cells, in row order,
+    /// and bound to the instance rows after the computed values (if any).
+    /// The verifier supplies the opened values there (see
+    /// `Verifier::verify_with_commitment`), so the proof only verifies if
+    /// the circuit's cells are the committed ones. Repeated calls append.
+    pub fn with_opened_column(mut self, column: usize) -> Self {
+        self.opened_data.get_or_insert_with(Vec::new).push(column);
+        self
+    }
+
+    /// Expose constants as explicit public inputs
+    ///
+    /// Each value is the output of an arithmetic row adding two fixed
+    /// constants, so the cell bound to its instance row is fixed by the
+    /// circuit and a prover can't substitute another value. The values are
+    /// bound to the instance rows after the opened cells (if any), in order,
+    /// and shape the fixed columns, so they are part of the verifying key.
+    /// Synthesis fails without the arithmetic gate. Repeated calls append.
+    pub fn with_public_inputs(mut self, values: Vec<Field>) -> Self {
+        self.public_input_data
+            .get_or_insert_with(Vec::new)
+            .extend(values);
+        self
+    }
+
+    /// Bind the proof to a database commitment
+    ///
+    /// The hash (see `DatabaseCommitment::hash_field`) is the last public
+    /// input. Instance values are absorbed into the proof transcript, so the
+    /// proof only verifies against the commitment it was created for.
+    pub fn with_commitment_hash(mut self, hash: Field) -> Self {
+        self.commitment_hash = Some(hash);
+        self
+    }
+
+    /// Set how thoroughly the derived-witness gates check their witness
+    ///
+    /// `Strict` has the sort, join, group-by, aggregation, and distinct-count
+    /// gates re-check their derived witness at synthesis time and fail with a
+    /// descriptive error instead of leaving an unsatisfied constraint; `Fast`
+    /// (the default) skips those checks.
+    pub fn with_validation(mut self, mode: ValidationMode) -> Self {
+        self.validation = mode;
+        self
+    }
+
+    /// Get the public inputs expected by this circuit's instance column
+    ///
+    /// The count comes first, followed by SUM, MIN and MAX, then the sketch
+    /// registers, then the HAVING threshold and group counts, then the key and
+    /// distinct count of each group, then the public sorted values, then the
+    /// computed values, then the selected cells of the opened columns, then
+    /// the explicit public inputs, then the commitment hash.
+    pub fn public_inputs(&self) -> Vec<Field> {
+        self.count_output
+            .into_iter()
+            .chain(self.sum_output)
+            .chain(
+                self.minmax_output
+                    .into_iter()
+                    .flat_map(|(min, max)| [min, max]),
+            )
+            .chain(self.sketch_registers())
+            .chain(self.having_data.iter().flat_map(|(groups, threshold)| {
+                std::iter::once(*threshold).chain(groups.iter().map(|(_, count)| *count))
+            }))
+            .chain(self.distinct_count_outputs())
+            .chain(self.sort_outputs())
+            .chain(self.computed_outputs())
+            .chain(self.opened_outputs())
+            .chain(self.public_input_data.iter().flatten().copied())
+            .chain(self.commitment_hash)
+            .collect()
+    }
+
+    /// Get the registers of the sketch of each sketched column, flattened
+    fn sketch_registers(&self) -> Vec<Field> {
+        self.sketch_data
+            .iter()
+            .flatten()
+            .flat_map(|&column| {
+                let values: Vec<Field> = self
+                    .selected_values(column)
+                    .into_iter()
+                    .filter(|value| !SqlValue::is_null_field(value))
+                    .collect();
+                let observations = HyperLogLog::observations(&values, HLL_PRECISION);
+                HllConfig::registers(&observations, HLL_PRECISION)
+            })
+            .collect()
+    }
+
+    /// Get the (key, distinct count) of each group, for each counted
+    /// column, flattened
+    fn distinct_count_outputs(&self) -> Vec<Field> {
+        self.distinct_count_data
+            .iter()
+            .flatten()
+            .flat_map(|&column| {
+                DistinctCountConfig::group_counts(&self.distinct_count_rows(column))
+            })
+            .flat_map(|(key, count)| [key, count])
+            .collect()
+    }
+
+    /// Get the (group key, value) rows COUNT(DISTINCT) of a table column
+    /// counts: the group-by input keys paired with the column's cells of the
+    /// selected rows, sorted by key and then by value
+    fn distinct_count_rows(&self, column: usize) -> Vec<(Field, Field)> {
+        let keys = self
+            .group_by_data
+            .as_ref()
+            .map_or(&[][..], |(keys, _)| keys.as_slice());
+        DistinctCountConfig::sort_rows(keys, &self.selected_values(column))
+    }
+
+    /// Get the cells of a table column in the selected rows, in row order
+    fn selected_values(&self, column: usize) -> Vec<Field> {
+        let Some(cells) = self.table_data().get(column) else {
+            return vec![];
+        };
+        self.selected_rows()
+            .into_iter()
+            .zip(cells)
+            .filter(|(selected, _)| *selected)
+            .map(|(_, &cell)| cell)
+            .collect()
+    }
+
+    /// Get the gates this circuit is configured with
+    ///
+    /// Every gate is enabled, except the ones only some queries use, which
+    /// are enabled when this circuit uses them. The configuration depends
+    /// on these alone, so circuits of one plan over different data share
+    /// their keys. A circuit read back from a verifying key file has the
+    /// gates stored with the key.
+    pub fn gates(&self) -> GateSet {
+        if let Some(gates) = self.configured_gates {
+            return gates;
+        }
+        GateSet {
+            narrow_range_check: self.narrow_range_check_data.is_some(),
+            distinct_count: self.distinct_count_data.is_some(),
+            avg_remainder: self.avg_remainder,
+            sketch: self.sketch_data.is_some(),
+            ..GateSet::all()
+        }
+    }
+
+    /// Get the sorted values of the public sorts, flattened
+    fn sort_outputs(&self) -> Vec<Field> {
+        self.sort_data()
+            .iter()
+            .filter(|(_, _, public)| *public)
+            .flat_map(|(_, sorted, _)| sorted.iter().copied())
+            .collect()
+    }
+
+    /// Get the value of each computed expression on each selected row, row
+    /// by row
+    fn computed_outputs(&self) -> Vec<Field> {
+        let Some(expressions) = &self.computed_data else {
+            return vec![];
+        };
+        let columns = self.table_data();
+        let mut outputs = vec![];
+        for (row, selected) in self.selected_rows().into_iter().enumerate() {
+            if !selected {
+                continue;
+            }
+            let cells: Vec<Field> = columns.iter().map(|column| column[row]).collect();
+            outputs.extend(expressions.iter().filter_map(|rows| {
+                ArithmeticConfig::evaluate(rows, &cells)?
+                    .last()
+                    .map(|op| op.3)
+            }));
+        }
+        outputs
+    }
+
+    /// Get the selected cells of each opened column, flattened
+    fn opened_outputs(&self) -> Vec<Field> {
+        self.opened_data
+            .iter()
+            .flatten()
+            .flat_map(|&column| self.selected_values(column))
+            .collect()
+    }
+
+    /// Get the number of public inputs after the sorted values: the computed
+    /// values, the opened cells, the explicit public inputs and the
+    /// commitment hash
+    fn trailing_inputs(&self) -> usize {
+        self.computed_outputs().len() + self.opened_outputs().len() + self.explicit_inputs()
+    }
+
+    /// Get the number of public inputs after the opened cells: the explicit
+    /// public inputs and the commitment hash
+    fn explicit_inputs(&self) -> usize {
+        self.public_input_data.as_ref().map_or(0, Vec::len)
+            + usize::from(self.commitment_hash.is_some())
+    }
+
+    /// Get the sorts as (input values, sorted values, whether the sorted
+    /// values are public), in tag order
+    pub fn sort_data(&self) -> &[(Vec<Field>, Vec<Field>, bool)] {
+        self.sort_data.as_deref().unwrap_or(&[])
+    }
+
+    /// Get the table columns, one value per table row each
+    pub fn table_data(&self) -> &[Vec<Field>] {
+        self.table_data
+            .as_ref()
+            .map_or(&[], |(_, columns)| columns.as_slice())
+    }
+
+    /// Get how thoroughly the derived-witness gates check their witness
+    pub fn validation(&self) -> ValidationMode {
+        self.validation
+    }
+
+    /// Get the predicate selecting table rows, if any
+    pub fn predicate(&self) -> Option<&Predicate> {
+        self.predicate.as_ref()
+    }
+
+    /// Get the number of table rows
+    fn num_table_rows(&self) -> usize {
+        self.table_data
+            .as_ref()
+            .map_or(0, |(num_rows, _)| *num_rows)
+    }
+
+    /// Get the predicate selecting table rows, or one holding on all rows
+    fn row_predicate(&self) -> Predicate {
+        self.predicate
+            .clone()
+            .unwrap_or_else(|| Predicate::And(vec![]))
+    }
+
+    /// Get the flag of every table row: the selection flags if set, the
+    /// predicate's flags otherwise
+    fn selected_rows(&self) -> Vec<bool> {
+        match &self.selection_data {
+            Some(selection) => selection.iter().map(|f| *f == Field::from(1)).collect(),
+            None => self
+                .row_predicate()
+                .flags(self.table_data(), self.num_table_rows()),
+        }
+    }
+
+    /// AND row flags with the flags of a column being not NULL
+    ///
+    /// # Returns
+    /// The combined flag cells, and the plain combined flags for the layout
+    fn assign_not_null(
+        &self,
+        config: &SelectionConfig,
+        layouter: &mut impl Layouter<Field>,
+        flags: &[AssignedCell<Field, Field>],
+        table_cells: &[Vec<AssignedCell<Field, Field>>],
+        column: usize,
+    ) -> Result<(Vec<AssignedCell<Field, Field>>, Vec<bool>), ErrorFront> {
+        let num_rows = self.num_table_rows();
+        let not_null = Predicate::not_null(column);
+        let not_null_flags = config.assign_predicate(layouter, &not_null, table_cells, num_rows)?;
+        let combined = config.assign_combine(layouter, flags, &not_null_flags, Combine::And)?;
+        let not_null_rows = not_null.flags(self.table_data(), num_rows);
+        let selected = self
+            .selected_rows()
+            .into_iter()
+            .zip(not_null_rows)
+            .map(|(selected, not_null)| selected && not_null)
+            .collect();
+        Ok((combined, selected))
+    }
 }
 
-impl Circuit<Field> for NzengiCircuit {
+impl<P: Planner> Circuit<Field> for NzengiCircuit<P> {
     type Config = config::CircuitConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+    type FloorPlanner = P::FloorPlanner;
+
+    type Params = GateSet;
 
     fn without_witnesses(&self) -> Self {
-        Self::new()
+        // The counted and sketched columns, the narrow range checks and the
+        // AVG truncation are part of the plan, not the witness, and shape
+        // the configuration; the explicit public inputs are fixed constants
+        Self {
+            public_input_data: self.public_input_data.clone(),
+            configured_gates: self.configured_gates,
+            narrow_range_check_data: self.narrow_range_check_data.as_ref().map(|_| vec![]),
+            sketch_data: self.sketch_data.clone(),
+            distinct_count_data: self.distinct_count_data.clone(),
+            avg_remainder: self.avg_remainder,
+            ..Self::default()
+        }
+    }
+
+    fn params(&self) -> GateSet {
+        self.gates()
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<Field>, gates: GateSet) -> Self::Config {
+        config::CircuitConfig::new(meta, &gates)
     }
 
     fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
-        // Determine which gates to enable based on data availability
-        // In a real implementation, this would be determined by query analysis
-        let enable_range_check = true;
-        let enable_sort = true;
-        let enable_group_by = true;
-        let enable_join = true;
-        let enable_aggregation = true;
-
-        config::CircuitConfig::new(
-            meta,
-            enable_range_check,
-            enable_sort,
-            enable_group_by,
-            enable_join,
-            enable_aggregation,
-        )
+        // Without the circuit's gates, every gate is enabled
+        config::CircuitConfig::new(meta, &GateSet::all())
     }
 
     fn synthesize(
@@ -155,50 +805,778 @@ impl Circuit<Field> for NzengiCircuit {
         config: Self::Config,
         mut layouter: impl Layouter<Field>,
     ) -> Result<(), ErrorFront> {
-        // Assign range check gate
-        if let (Some(range_check_config), Some((value, _u8_cells))) =
-            (&config.range_check, &self.range_check_data)
-        {
-            range_check_config.load_lookup_table(&mut layouter)?;
-            range_check_config.assign(&mut layouter, *value)?;
+        // Load the u8 lookup table shared by range check and selection gates
+        if let Some(range_check_config) = &config.range_check {
+            if self.range_check_data.is_some()
+                || self.narrow_range_check_data.is_some()
+                || self.sketch_data.is_some()
+                || self
+                    .predicate
+                    .as_ref()
+                    .is_some_and(|predicate| *predicate != Predicate::And(vec![]))
+            {
+                range_check_config.load_lookup_table(&mut layouter)?;
+            }
         }
 
-        // Assign sort gate
-        if let (Some(sort_config), Some((input_values, sorted_values, alpha))) =
-            (&config.sort, &self.sort_data)
-        {
-            sort_config.assign(&mut layouter, input_values, sorted_values, *alpha)?;
+        // Assign narrow range checks, grouped by the width that covers them;
+        // widths no narrow range check covers fall back to 64 bits
+        let mut values: Vec<u64> = self
+            .range_check_data
+            .iter()
+            .flatten()
+            .map(|(value, _u8_cells)| *value)
+            .collect();
+        let mut narrow_values: Vec<Vec<u64>> = vec![vec![]; config.narrow_range_checks.len()];
+        for &(value, bits) in self.narrow_range_check_data.iter().flatten() {
+            match config
+                .narrow_range_checks
+                .iter()
+                .position(|g| g.bits >= bits)
+            {
+                Some(idx) => narrow_values[idx].push(value),
+                None => values.push(value),
+            }
+        }
+        for (narrow_config, values) in config.narrow_range_checks.iter().zip(&narrow_values) {
+            if !values.is_empty() {
+                narrow_config.assign_many(&mut layouter, values)?;
+            }
         }
 
-        // Assign group-by gate
-        if let (Some(group_by_config), Some(sorted_values)) =
-            (&config.group_by, &self.group_by_data)
-        {
-            group_by_config.assign(&mut layouter, sorted_values)?;
+        // Assign 64-bit range checks, one row each in a single region
+        if let Some(range_check_config) = &config.range_check {
+            if !values.is_empty() {
+                range_check_config.assign_many(&mut layouter, &values)?;
+            }
         }
 
+        // Assign the table cells, and prove the flag of each row under the
+        // predicate from them; selection flags must only flag rows the
+        // predicate does (flag AND predicate flag = flag), and replace them
+        let (table_cells, row_flags) = match (&config.selection, &self.table_data) {
+            (Some(selection_config), Some((_, columns))) => {
+                let num_rows = self.num_table_rows();
+                let cells = columns
+                    .iter()
+                    .map(|column| selection_config.assign_column(&mut layouter, column))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut flags = selection_config.assign_predicate(
+                    &mut layouter,
+                    &self.row_predicate(),
+                    &cells,
+                    num_rows,
+                )?;
+                if let Some(selection) = &self.selection_data {
+                    let selected = selection_config.assign_column(&mut layouter, selection)?;
+                    let both = selection_config.assign_combine(
+                        &mut layouter,
+                        &selected,
+                        &flags,
+                        Combine::And,
+                    )?;
+                    SelectionConfig::constrain_cells(&mut layouter, &both, &selected)?;
+                    flags = selected;
+                }
+                (cells, Some(flags))
+            }
+            _ => (vec![], None),
+        };
+
+        // Assign the sorts, tagged 1, 2, ... in order
+        let mut sorts = vec![];
+        if let Some(sort_config) = &config.sort {
+            for (tag, (input_values, sorted_values, public)) in (1..).zip(self.sort_data()) {
+                let (input_cells, sorted_cells) = sort_config.assign(
+                    &mut layouter,
+                    input_values,
+                    sorted_values,
+                    tag,
+                    self.validation,
+                )?;
+                sorts.push((input_cells, sorted_cells, *public));
+            }
+        }
+
+        // Assign group-by gate
+        let groups = match (&config.group_by, &self.group_by_data) {
+            (Some(group_by_config), Some((input_values, sorted_values))) => {
+                Some(group_by_config.assign(
+                    &mut layouter,
+                    input_values,
+                    sorted_values,
+                    self.validation,
+                )?)
+            }
+            _ => None,
+        };
+
         // Assign join gate
         if let (Some(join_config), Some((t1_join_values, t2_join_values, join_results))) =
             (&config.join, &self.join_data)
         {
-            join_config.assign(&mut layouter, t1_join_values, t2_join_values, join_results)?;
+            join_config.assign(
+                &mut layouter,
+                t1_join_values,
+                t2_join_values,
+                join_results,
+                self.validation,
+            )?;
         }
 
         // Assign aggregation gate
-        if let (
-            Some(aggregation_config),
-            Some((values, binary_markers, start_indices, end_indices)),
-        ) = (&config.aggregation, &self.aggregation_data)
-        {
-            aggregation_config.assign(
+        let aggregation_cells = match (&config.aggregation, &self.aggregation_data) {
+            (
+                Some(aggregation_config),
+                Some((values, binary_markers, start_indices, end_indices)),
+            ) => {
+                let cells = aggregation_config.assign(
+                    &mut layouter,
+                    values,
+                    binary_markers,
+                    start_indices,
+                    end_indices,
+                    self.validation,
+                )?;
+
+                // Aggregate over the same groups the group-by gate proved
+                if let Some(groups) = &groups {
+                    GroupByConfig::constrain_boundaries(&mut layouter, groups, &cells)?;
+                }
+
+                // Aggregate the selected cells of the table
+                if let (Some(selection_config), Some(flags), Some(column)) =
+                    (&config.selection, &row_flags, self.selected_column)
+                {
+                    let (flags, selected) = self.assign_not_null(
+                        selection_config,
+                        &mut layouter,
+                        flags,
+                        &table_cells,
+                        column,
+                    )?;
+                    let outputs = selection_config.assign_compact(
+                        &mut layouter,
+                        &flags,
+                        table_cells.get(column).ok_or(ErrorFront::Synthesis)?,
+                        &selected,
+                        1,
+                    )?;
+                    SelectionConfig::constrain_cells(&mut layouter, &outputs, &cells.values)?;
+                }
+                cells
+            }
+            _ => AggregationCells::default(),
+        };
+
+        // Bind COUNT(*) to the public instance: the count of the flagged
+        // table rows
+        if let (Some(count_config), Some(count)) = (&config.count, self.count_output) {
+            match (&config.selection, &row_flags, self.count_column) {
+                (Some(selection_config), Some(flags), Some(column)) => {
+                    let (flags, _) = self.assign_not_null(
+                        selection_config,
+                        &mut layouter,
+                        flags,
+                        &table_cells,
+                        column,
+                    )?;
+                    count_config.assign_public_cells(&mut layouter, &flags, count, 0)?;
+                }
+                (_, Some(flags), _) => {
+                    count_config.assign_public_cells(&mut layouter, flags, count, 0)?
+                }
+                // Without a table there are no rows to count
+                _ => return Err(ErrorFront::Synthesis),
+            }
+        }
+
+        // Bind SUM to the public instance, after the count: the accumulator
+        // of the last row holds the sum of the single aggregated group, whose
+        // values must be the selected cells of a table column
+        if let Some(_sum) = &self.sum_output {
+            if row_flags.is_none() || self.selected_column.is_none() {
+                return Err(ErrorFront::Synthesis);
+            }
+            let sum_cell = aggregation_cells
+                .accumulators
+                .last()
+                .ok_or(ErrorFront::Synthesis)?;
+            let sum_row = self.count_output.map_or(0, |_| 1);
+            layouter.constrain_instance(sum_cell.cell(), config.instance, sum_row)?;
+        }
+
+        // Bind MIN/MAX to the public instance, after the count and SUM: the
+        // ends of the first sort, whose input must be the selected cells of
+        // a table column (compacted with the tag after the SUM column's)
+        if let (Some(min_max_config), Some(_minmax)) = (&config.min_max, &self.minmax_output) {
+            let (
+                Some(selection_config),
+                Some(flags),
+                Some(column),
+                Some((input_cells, sorted_cells, _)),
+            ) = (
+                &config.selection,
+                &row_flags,
+                self.minmax_column,
+                sorts.first(),
+            )
+            else {
+                return Err(ErrorFront::Synthesis);
+            };
+            let (flags, selected) =
+                self.assign_not_null(selection_config, &mut layouter, flags, &table_cells, column)?;
+            let outputs = selection_config.assign_compact(
                 &mut layouter,
-                values,
-                binary_markers,
-                start_indices,
-                end_indices,
+                &flags,
+                table_cells.get(column).ok_or(ErrorFront::Synthesis)?,
+                &selected,
+                2,
             )?;
+            SelectionConfig::constrain_cells(&mut layouter, &outputs, input_cells)?;
+
+            let min_row = self.count_output.map_or(0, |_| 1) + self.sum_output.map_or(0, |_| 1);
+            min_max_config.assign_public(&mut layouter, sorted_cells, min_row, min_row + 1)?;
+        }
+
+        // Compact the selected cells of every table column for the sketches,
+        // distinct counts, computed expressions and opened columns, with tags
+        // after the SUM and MIN/MAX columns'
+        let compacted = match (&config.selection, &row_flags) {
+            (Some(selection_config), Some(flags))
+                if self.sketch_data.is_some()
+                    || self.distinct_count_data.is_some()
+                    || self.computed_data.is_some()
+                    || self.opened_data.is_some() =>
+            {
+                let selected = self.selected_rows();
+                let compacted = table_cells
+                    .iter()
+                    .zip(3..)
+                    .map(|(cells, tag)| {
+                        selection_config.assign_compact(&mut layouter, flags, cells, &selected, tag)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Some(compacted)
+            }
+            _ => None,
+        };
+
+        // Bind the sketch registers to the public instance, after MIN/MAX:
+        // the sketches hash the selected cells of their columns
+        if let Some(columns) = &self.sketch_data {
+            let (Some(sketch_config), Some(compacted)) = (&config.sketch, &compacted) else {
+                return Err(ErrorFront::Synthesis);
+            };
+            sketch_config.load_rho_table(&mut layouter)?;
+            let mut offset = self.count_output.map_or(0, |_| 1)
+                + self.sum_output.map_or(0, |_| 1)
+                + self.minmax_output.map_or(0, |_| 2);
+            for &column in columns {
+                let inputs =
+                    sketch_config.assign(&mut layouter, &self.selected_values(column), offset)?;
+                let selected = compacted.get(column).ok_or(ErrorFront::Synthesis)?;
+                SelectionConfig::constrain_cells(&mut layouter, &inputs, selected)?;
+                offset += sketch_config.num_buckets();
+            }
+        }
+
+        // Bind HAVING to the instance, after the sketch: each kept group's
+        // count is its aggregation COUNT cell, and exceeds the threshold
+        if let (Some(less_than_config), Some((groups, threshold))) =
+            (&config.less_than, &self.having_data)
+        {
+            let trailing = self.trailing_inputs()
+                + self.sort_outputs().len()
+                + self.distinct_count_outputs().len();
+            let offset = self.public_inputs().len() - trailing - groups.len() - 1;
+            for (i, &(row, count)) in groups.iter().enumerate() {
+                let group_count = aggregation_cells
+                    .counts
+                    .get(row)
+                    .ok_or(ErrorFront::Synthesis)?;
+                let (threshold_cell, count_cell) =
+                    less_than_config.assign(&mut layouter, *threshold, count)?;
+                layouter.assign_region(
+                    || format!("having count[{}]", i),
+                    |mut region| region.constrain_equal(count_cell.cell(), group_count.cell()),
+                )?;
+                layouter.constrain_instance(threshold_cell.cell(), config.instance, offset)?;
+                layouter.constrain_instance(count_cell.cell(), config.instance, offset + 1 + i)?;
+            }
+        }
+
+        // Bind each group's key and distinct count to the instance, after
+        // HAVING: the rows are the group-by gate's, and the counted values
+        // the selected cells of their column
+        if let Some(columns) = &self.distinct_count_data {
+            let (Some(distinct_count_config), Some(groups), Some((keys, _)), Some(compacted)) = (
+                &config.distinct_count,
+                &groups,
+                &self.group_by_data,
+                &compacted,
+            ) else {
+                return Err(ErrorFront::Synthesis);
+            };
+            let trailing = self.trailing_inputs() + self.sort_outputs().len();
+            let mut offset =
+                self.public_inputs().len() - trailing - self.distinct_count_outputs().len();
+            for (tag, &column) in (1..).zip(columns) {
+                let cells = distinct_count_config.assign(
+                    &mut layouter,
+                    groups,
+                    keys,
+                    &self.selected_values(column),
+                    tag,
+                    self.validation,
+                )?;
+                let selected = compacted.get(column).ok_or(ErrorFront::Synthesis)?;
+                SelectionConfig::constrain_cells(&mut layouter, &cells.inputs, selected)?;
+                for (key_cell, count_cell) in &cells.groups {
+                    layouter.constrain_instance(key_cell.cell(), config.instance, offset)?;
+                    layouter.constrain_instance(count_cell.cell(), config.instance, offset + 1)?;
+                    offset += 2;
+                }
+            }
+        }
+
+        // Bind the public sorted values to the instance, after the distinct counts
+        let mut offset =
+            self.public_inputs().len() - self.trailing_inputs() - self.sort_outputs().len();
+        for (_, sorted_cells, _) in sorts.iter().filter(|(_, _, public)| *public) {
+            for cell in sorted_cells {
+                layouter.constrain_instance(cell.cell(), config.instance, offset)?;
+                offset += 1;
+            }
+        }
+
+        // Bind the value of each computed expression on each selected row to
+        // the instance, after the sorted values; the expressions read the
+        // selected cells of the table columns
+        if let (Some(arithmetic_config), Some(compacted), Some(expressions)) =
+            (&config.arithmetic, &compacted, &self.computed_data)
+        {
+            let selected = self.selected_rows();
+            let mut offset = self.public_inputs().len() - self.trailing_inputs();
+            let rows = selected.iter().enumerate().filter(|(_, s)| **s);
+            for (j, (row, _)) in rows.enumerate() {
+                let cells: Vec<_> = self
+                    .table_data()
+                    .iter()
+                    .zip(compacted)
+                    .map(|(column, outputs)| (column[row], outputs[j].clone()))
+                    .collect();
+                for expression in expressions {
+                    let out =
+                        arithmetic_config.assign_expression(&mut layouter, expression, &cells)?;
+                    layouter.constrain_instance(out.cell(), config.instance, offset)?;
+                    offset += 1;
+                }
+            }
+        }
+
+        // Bind the selected cells of each opened column to the instance,
+        // after the computed values
+        if let Some(columns) = &self.opened_data {
+            let compacted = compacted.as_ref().ok_or(ErrorFront::Synthesis)?;
+            let mut offset =
+                self.public_inputs().len() - self.opened_outputs().len() - self.explicit_inputs();
+            for &column in columns {
+                for cell in compacted.get(column).ok_or(ErrorFront::Synthesis)? {
+                    layouter.constrain_instance(cell.cell(), config.instance, offset)?;
+                    offset += 1;
+                }
+            }
+        }
+
+        // Bind the explicit public inputs to the instance, after the opened
+        // cells: each is the sum of two fixed constants
+        if let Some(values) = &self.public_input_data {
+            let arithmetic_config = config.arithmetic.as_ref().ok_or(ErrorFront::Synthesis)?;
+            let offset = self.public_inputs().len() - self.explicit_inputs();
+            for (i, &value) in values.iter().enumerate() {
+                let row = (
+                    Source::Constant(value),
+                    Op::Add,
+                    Source::Constant(Field::zero()),
+                );
+                let out = arithmetic_config.assign_expression(&mut layouter, &[row], &[])?;
+                layouter.constrain_instance(out.cell(), config.instance, offset + i)?;
+            }
+        }
+
+        // Assign less-than checks
+        if let (Some(less_than_config), Some(checks)) = (&config.less_than, &self.less_than_data) {
+            for &(a, b) in checks {
+                less_than_config.assign(&mut layouter, a, b)?;
+            }
         }
 
         Ok(())
     }
 }
+
+impl<P: Planner> KeyCircuit for NzengiCircuit<P> {
+    fn params_bytes(&self) -> Vec<u8> {
+        self.gates().to_bits().to_le_bytes().to_vec()
+    }
+
+    fn from_params_bytes(bytes: &[u8]) -> Option<Self> {
+        let bits = u16::from_le_bytes(bytes.try_into().ok()?);
+        Some(Self {
+            configured_gates: Some(GateSet::from_bits(bits)?),
+            ..Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{count_circuit, fields, group_witness, run_mock};
+    use halo2_proofs::dev::MockProver;
+
+    fn grouped_aggregation(markers: &[u64], start: &[u64], end: &[u64]) -> NzengiCircuit {
+        NzengiCircuit::new()
+            .with_group_by(fields(&[9, 7, 9, 7]), fields(&[7, 7, 9, 9]))
+            .with_aggregation(
+                fields(&[1, 2, 3, 4]),
+                fields(markers),
+                fields(start),
+                fields(end),
+            )
+    }
+
+    #[test]
+    fn test_group_by_aggregation_shared_cells() {
+        // Groups {0, 1} and {2, 3}, as proved by the group-by gate
+        let circuit = grouped_aggregation(&[1, 0, 1, 0], &[0, 0, 2, 2], &[1, 1, 3, 3]);
+        assert_eq!(run_mock(&circuit, 10), Ok(()));
+
+        // One group over all rows disagrees with the group-by boundaries
+        let circuit = grouped_aggregation(&[1, 1, 1, 0], &[0, 0, 0, 0], &[3, 3, 3, 3]);
+        assert!(run_mock(&circuit, 10).is_err());
+
+        // So do markers splitting a group, even with its indices
+        let circuit = grouped_aggregation(&[0, 0, 1, 0], &[0, 0, 2, 2], &[1, 1, 3, 3]);
+        assert!(run_mock(&circuit, 10).is_err());
+    }
+
+    /// Smallest `k` at which the circuit synthesizes and verifies
+    fn min_k<P: Planner>(circuit: &NzengiCircuit<P>) -> u32 {
+        (8..=14)
+            .find(|&k| {
+                MockProver::run(k, circuit, vec![circuit.public_inputs()])
+                    .is_ok_and(|prover| prover.verify().is_ok())
+            })
+            .expect("circuit fits in 2^14 rows")
+    }
+
+    #[test]
+    fn test_floor_planners() {
+        let circuit = count_circuit(&[2, 0, 3, 5])
+            .with_range_checks(
+                (0..64u64)
+                    .map(|v| (v * 1_000, FieldUtils::decompose_u64(v * 1_000).to_vec()))
+                    .collect(),
+            )
+            .with_range_check_bits(1, 1)
+            .with_less_than(Field::from(3), Field::from(4))
+            .with_computed(vec![vec![(
+                Source::Column(0),
+                Op::Mul,
+                Source::Constant(Field::from(2)),
+            )]]);
+
+        // Only circuits with narrow range checks configure the narrow widths
+        assert!(circuit.gates().narrow_range_check);
+        assert!(!count_circuit(&[2, 0, 3, 5]).gates().narrow_range_check);
+
+        // The circuit synthesizes and verifies under both planners, and V1
+        // packs it into no more rows than the simple planner
+        let simple = circuit.clone().with_floor_planner::<SimplePlanner>();
+        assert!(min_k(&circuit) <= min_k(&simple));
+    }
+
+    #[test]
+    fn test_key_params() {
+        let circuit = count_circuit(&[2, 0, 3]).with_truncated_avg();
+        let gates = circuit.gates();
+        assert!(gates.avg_remainder && !gates.sketch);
+
+        // A circuit rebuilt from the key parameters has the same gates,
+        // without the data
+        let rebuilt =
+            NzengiCircuit::<V1Planner>::from_params_bytes(&circuit.params_bytes()).unwrap();
+        assert_eq!(rebuilt.gates(), gates);
+        assert_eq!(rebuilt.without_witnesses().gates(), gates);
+        assert!(rebuilt.table_data().is_empty());
+
+        assert!(NzengiCircuit::<V1Planner>::from_params_bytes(&[]).is_none());
+        assert!(NzengiCircuit::<V1Planner>::from_params_bytes(&[0, 0x80]).is_none());
+    }
+
+    #[test]
+    fn test_opened_columns() {
+        let circuit = count_circuit(&[2, 0, 3])
+            .with_opened_column(0)
+            .with_commitment_hash(Field::from(99));
+
+        // The selected cells follow the count, before the commitment hash
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[2, 2, 3, 99]));
+
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Opened values other than the selected cells are rejected
+        for forged in [fields(&[2, 2, 4, 99]), fields(&[2, 0, 3, 99])] {
+            let prover = MockProver::run(k, &circuit, vec![forged]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_explicit_public_inputs() {
+        let circuit = count_circuit(&[2, 0, 3])
+            .with_opened_column(0)
+            .with_public_inputs(fields(&[42, 7]))
+            .with_commitment_hash(Field::from(99));
+
+        // Explicit inputs follow the opened cells, before the commitment hash
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[2, 2, 3, 42, 7, 99]));
+
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A mismatched public input is rejected
+        for forged in [fields(&[2, 2, 3, 42, 8, 99]), fields(&[2, 2, 3, 7, 42, 99])] {
+            let prover = MockProver::run(k, &circuit, vec![forged]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // The constants are part of the keys, not the witness
+        assert_eq!(
+            circuit.without_witnesses().public_input_data,
+            Some(fields(&[42, 7]))
+        );
+    }
+
+    #[test]
+    fn test_having_counts() {
+        // Groups of 3 and 5 rows, starting at aggregation rows 0 and 3
+        let keys = [7, 7, 7, 9, 9, 9, 9, 9];
+        let having = |groups: &[(usize, u64)], threshold: u64| {
+            let (markers, starts, ends) = group_witness(&keys);
+            NzengiCircuit::new()
+                .with_group_by(fields(&keys), fields(&keys))
+                .with_aggregation(fields(&[1; 8]), markers, starts, ends)
+                .with_having(
+                    groups
+                        .iter()
+                        .map(|&(row, count)| (row, Field::from(count)))
+                        .collect(),
+                    Field::from(threshold),
+                )
+        };
+        let circuit = having(&[(0, 3), (3, 5)], 2).with_commitment_hash(Field::from(99));
+
+        // The threshold and counts come before the commitment hash
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[2, 3, 5, 99]));
+
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A count equal to the threshold fails `count > k`
+        let circuit = having(&[(0, 3), (3, 5)], 3);
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Public counts other than the proven ones are rejected
+        let circuit = having(&[(0, 3)], 2);
+        let prover = MockProver::run(k, &circuit, vec![fields(&[2, 4])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // So are counts other than their group's, even when they pass
+        let circuit = having(&[(0, 4)], 2);
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // A kept group's row must be in the aggregation data
+        let circuit = having(&[(8, 3)], 2);
+        assert!(MockProver::run(k, &circuit, vec![circuit.public_inputs()]).is_err());
+    }
+
+    #[test]
+    fn test_distinct_counts() {
+        use crate::types::Value as SqlValue;
+
+        // Groups 1 and 2 of the selected rows; the NULL is not counted
+        let table = vec![vec![
+            Field::from(5),
+            Field::from(7),
+            Field::from(5),
+            Field::from(9),
+            SqlValue::null_field(),
+        ]];
+        let circuit = NzengiCircuit::new()
+            .with_table(5, table.clone())
+            .with_group_by(fields(&[1, 2, 1, 2, 1]), fields(&[1, 1, 1, 2, 2]))
+            .with_distinct_count(0);
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[1, 1, 2, 2]));
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A tampered per-group count is rejected
+        let prover = MockProver::run(k, &circuit, vec![fields(&[1, 2, 2, 2])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Each counted column's groups follow the previous one's
+        let circuit = circuit.with_distinct_count(0);
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[1, 1, 2, 2, 1, 1, 2, 2]));
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The rows are the group-by gate's: there are none without it
+        let circuit = NzengiCircuit::new()
+            .with_table(5, table)
+            .with_distinct_count(0);
+        assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_sum_output() {
+        // SUM over every row of a one-column table
+        let circuit_for = |values: &[u64]| {
+            NzengiCircuit::new()
+                .with_table(3, vec![fields(&[4, 5, 6])])
+                .with_selected_column(0)
+                .with_aggregation(
+                    fields(values),
+                    fields(&[1, 1, 0]),
+                    fields(&[0, 0, 0]),
+                    fields(&[2, 2, 2]),
+                )
+                .with_sum_output(fields(values).iter().sum())
+        };
+        let circuit = circuit_for(&[4, 5, 6]);
+        assert_eq!(circuit.public_inputs(), fields(&[15]));
+
+        let k = 9;
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A sum other than the accumulated one is rejected
+        let prover = MockProver::run(k, &circuit, vec![fields(&[16])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // So is a sum accumulated over values other than the column's cells
+        let circuit = circuit_for(&[4, 5, 7]);
+        assert_eq!(circuit.public_inputs(), fields(&[16]));
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // A sum not bound to a table column can't be synthesized
+        let circuit = NzengiCircuit::new()
+            .with_aggregation(
+                fields(&[4, 5, 6]),
+                fields(&[1, 1, 0]),
+                fields(&[0, 0, 0]),
+                fields(&[2, 2, 2]),
+            )
+            .with_sum_output(Field::from(15));
+        assert!(MockProver::run(k, &circuit, vec![circuit.public_inputs()]).is_err());
+    }
+
+    #[test]
+    fn test_predicate_selection() {
+        use crate::gates::selection::{Comparison, Operand};
+        use crate::types::Value as SqlValue;
+
+        // l_quantity > 10 over a column with a NULL; SUM(l_quantity)
+        let mut quantities = fields(&[5, 12, 10, 30, 11]);
+        quantities.push(SqlValue::null_field());
+        let circuit_for = |values: &[u64], count: u64| {
+            let n = values.len();
+            NzengiCircuit::new()
+                .with_table(6, vec![quantities.clone()])
+                .with_predicate(Predicate::Compare {
+                    column: 0,
+                    comparison: Comparison::Greater,
+                    rhs: Operand::Constant(Field::from(10)),
+                    negated: false,
+                })
+                .with_selected_column(0)
+                .with_count_output(Field::from(count))
+                .with_aggregation(
+                    fields(values),
+                    (0..n).map(|i| Field::from((i + 1 < n) as u64)).collect(),
+                    vec![Field::zero(); n],
+                    vec![Field::from(n as u64 - 1); n],
+                )
+                .with_sum_output(fields(values).iter().sum())
+        };
+
+        let k = 10;
+        let circuit = circuit_for(&[12, 30, 11], 3);
+        assert_eq!(circuit.public_inputs(), fields(&[3, 53]));
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A count other than the flagged rows is rejected
+        let prover = MockProver::run(k, &circuit, vec![fields(&[2, 53])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Aggregating a row the predicate doesn't select is rejected
+        let circuit = circuit_for(&[12, 30, 5], 3);
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_sort_output() {
+        // MIN/MAX sorts the aggregated values; ORDER BY sorts the result keys
+        let circuit = NzengiCircuit::new()
+            .with_table(3, vec![fields(&[8, 3, 5])])
+            .with_sort(fields(&[8, 3, 5]), fields(&[3, 5, 8]))
+            .with_minmax_column(0)
+            .with_minmax_output(Field::from(3), Field::from(8))
+            .with_sort_output(fields(&[30, 10, 20]), fields(&[10, 20, 30]))
+            .with_commitment_hash(Field::from(99));
+
+        // The sorted keys come after MIN/MAX and before the commitment hash
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[3, 8, 10, 20, 30, 99]));
+
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // A result in another order than the proved one is rejected
+        let prover = MockProver::run(k, &circuit, vec![fields(&[3, 8, 20, 10, 30, 99])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // MIN/MAX of values other than the column's cells is rejected
+        let circuit = NzengiCircuit::new()
+            .with_table(3, vec![fields(&[8, 3, 5])])
+            .with_sort(fields(&[8, 4, 5]), fields(&[4, 5, 8]))
+            .with_minmax_column(0)
+            .with_minmax_output(Field::from(4), Field::from(8));
+        let prover = MockProver::run(k, &circuit, vec![circuit.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // MIN/MAX not bound to a table column can't be synthesized
+        let circuit = NzengiCircuit::new()
+            .with_sort(fields(&[8, 3, 5]), fields(&[3, 5, 8]))
+            .with_minmax_output(Field::from(3), Field::from(8));
+        assert!(MockProver::run(k, &circuit, vec![circuit.public_inputs()]).is_err());
+    }
+}
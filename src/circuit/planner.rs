@@ -0,0 +1,48 @@
+//! Floor planner selection
+//!
+//! This module provides the floor planners `NzengiCircuit` can be laid out
+//! with. The planner is chosen by the circuit's type parameter, since halo2
+//! fixes it per circuit type.
+//!
+//! # Planners
+//!
+//! - `V1Planner`: halo2's `V1` planner, which packs each region into the
+//!   earliest rows free in its columns (the default)
+//! - `SimplePlanner`: halo2's `SimpleFloorPlanner`, which stacks regions one
+//!   after another
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::circuit::{NzengiCircuit, SimplePlanner};
+//!
+//! let circuit: NzengiCircuit<SimplePlanner> = NzengiCircuit::new().with_floor_planner();
+//! ```
+
+use halo2_proofs::circuit::{floor_planner::V1, FloorPlanner, SimpleFloorPlanner};
+use std::fmt::Debug;
+
+/// Floor planner choice for a circuit
+///
+/// Implemented by marker types, so circuits generic over the planner can
+/// still derive `Default`, `Debug` and `Clone`.
+pub trait Planner: Default + Debug + Clone {
+    /// halo2 floor planner laying out the circuit
+    type FloorPlanner: FloorPlanner;
+}
+
+/// halo2's `V1` floor planner
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct V1Planner;
+
+impl Planner for V1Planner {
+    type FloorPlanner = V1;
+}
+
+/// halo2's `SimpleFloorPlanner`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimplePlanner;
+
+impl Planner for SimplePlanner {
+    type FloorPlanner = SimpleFloorPlanner;
+}
@@ -21,7 +21,7 @@
 //! let tables = vec![/* your tables */];
 //!
 //! // Commit to database
-//! let commitment = DatabaseCommitment::commit_database(&tables, &params);
+//! let commitment = DatabaseCommitment::commit_database(&tables, &params)?;
 //!
 //! // Verify commitment
 //! assert!(commitment.verify(&params));
@@ -30,9 +30,24 @@
 //! println!("Commitment hash: {}", commitment.commitment_hash);
 //! ```
 
-use super::ipa::{IPAParams, VectorCommitment};
+use super::error::CommitmentError;
+use super::ipa::{IPAParams, VectorCommitment, EMPTY_COMMITMENT};
+use super::opening::CommitmentBlinds;
 use crate::types::Table;
+use ff::FromUniformBytes;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::halo2curves::group::{GroupEncoding, UncompressedEncoding};
 use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Magic bytes identifying the binary commitment format
+const BINARY_MAGIC: &[u8; 4] = b"NZDC";
+
+/// Binary commitment format version
+const BINARY_VERSION: u8 = 2;
+
+/// Flag set when commitment points are stored compressed (32 bytes)
+const FLAG_COMPRESSED: u8 = 0x01;
 
 /// Database commitment
 ///
@@ -45,6 +60,14 @@ pub struct DatabaseCommitment {
 
     /// Overall commitment hash (for publishing on blockchain)
     pub commitment_hash: String,
+
+    /// Commitment version, incremented each time the database is re-committed
+    #[serde(default)]
+    pub version: u64,
+
+    /// Creation time in seconds since the Unix epoch
+    #[serde(default)]
+    pub created_at: u64,
 }
 
 /// Commitment to a single table
@@ -80,7 +103,9 @@ impl DatabaseCommitment {
     /// * `params` - IPA parameters for commitment
     ///
     /// # Returns
-    /// A `DatabaseCommitment` containing all table commitments and a global hash
+    /// `Ok(DatabaseCommitment)` at version 1 containing all table commitments
+    /// and a global hash, `Err(CommitmentError::TooLarge)` if a table has more
+    /// rows than `params.max_rows()`
     ///
     /// # Example
     /// ```
@@ -101,37 +126,62 @@ impl DatabaseCommitment {
     ///     ],
     /// };
     ///
-    /// let commitment = DatabaseCommitment::commit_database(&[table], &params);
+    /// let commitment = DatabaseCommitment::commit_database(&[table], &params)?;
     /// ```
-    pub fn commit_database(tables: &[Table], params: &IPAParams) -> Self {
+    pub fn commit_database(tables: &[Table], params: &IPAParams) -> Result<Self, CommitmentError> {
+        Ok(Self::commit_database_with_blinds(tables, params)?.0)
+    }
+
+    /// Create commitment to entire database, keeping the blinding factors
+    ///
+    /// Same as `commit_database`, but also returns the blind of every
+    /// column commitment so the prover can later open committed cells with
+    /// `CellOpening::create`. The blinds must not be published.
+    ///
+    /// # Arguments
+    /// * `tables` - Vector of tables to commit to
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok((DatabaseCommitment, CommitmentBlinds))` with the commitment and
+    /// the blinds of its column commitments, `Err(CommitmentError::TooLarge)`
+    /// if a table has more rows than `params.max_rows()`
+    pub fn commit_database_with_blinds(
+        tables: &[Table],
+        params: &IPAParams,
+    ) -> Result<(Self, CommitmentBlinds), CommitmentError> {
         // Pre-allocate vector for better performance
         let mut table_commitments = Vec::with_capacity(tables.len());
+        let mut blinds = CommitmentBlinds::default();
 
         for table in tables {
             let mut column_commitments = Vec::new();
 
             // Check table size
-            if table.rows.len() > params.max_rows() {
-                panic!(
-                    "Table '{}' has {} rows, exceeds maximum {}",
-                    table.name,
-                    table.rows.len(),
-                    params.max_rows()
-                );
-            }
+            VectorCommitment::check_size(table.rows.len(), params)?;
 
             // Create commitment for each column (optimized with pre-allocation)
             column_commitments.reserve(table.columns.len());
 
-            for (col_idx, column) in table.columns.iter().enumerate() {
-                // Extract column values (pre-allocate for better performance)
-                let mut values = Vec::with_capacity(table.rows.len());
-                for row in &table.rows {
-                    values.push(row.values[col_idx].to_field());
+            for column in &table.columns {
+                // A table without rows commits each column as the empty commitment
+                if table.rows.is_empty() {
+                    column_commitments.push(ColumnCommitment {
+                        column_name: column.name.clone(),
+                        commitment: EMPTY_COMMITMENT.to_vec(),
+                        num_rows: 0,
+                    });
+                    continue;
                 }
 
+                // Extract column values; a missing value is committed as NULL
+                let values = table.column_fields(&column.name).unwrap_or_default();
+
                 // Create commitment for this column
-                let vector_commitment = VectorCommitment::commit(values, params);
+                let vector_commitment = VectorCommitment::commit(values, params)?;
+                if let Some(blind) = vector_commitment.blind {
+                    blinds.insert(&table.name, &column.name, blind);
+                }
 
                 column_commitments.push(ColumnCommitment {
                     column_name: column.name.clone(),
@@ -146,13 +196,110 @@ impl DatabaseCommitment {
             });
         }
 
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
         // Compute overall commitment hash
-        let commitment_hash = Self::compute_commitment_hash(&table_commitments);
+        let commitment_hash = Self::compute_commitment_hash(&table_commitments, 1, created_at);
 
-        Self {
+        let commitment = Self {
             table_commitments,
             commitment_hash,
+            version: 1,
+            created_at,
+        };
+        Ok((commitment, blinds))
+    }
+
+    /// Set the commitment version
+    ///
+    /// The commitment hash is recomputed, since it covers the version.
+    ///
+    /// # Arguments
+    /// * `version` - Commitment version
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = version;
+        self.commitment_hash =
+            Self::compute_commitment_hash(&self.table_commitments, version, self.created_at);
+        self
+    }
+
+    /// Set the creation time
+    ///
+    /// The commitment hash is recomputed, since it covers the creation time.
+    ///
+    /// # Arguments
+    /// * `created_at` - Creation time in seconds since the Unix epoch
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_created_at(mut self, created_at: u64) -> Self {
+        self.created_at = created_at;
+        self.commitment_hash =
+            Self::compute_commitment_hash(&self.table_commitments, self.version, created_at);
+        self
+    }
+
+    /// Merge with another database commitment
+    ///
+    /// Combines commitments made independently (e.g., by parties of a
+    /// federated database) without recommitting the tables. Tables of
+    /// `other` are appended after those of `self`, and the merged commitment
+    /// takes the higher version and the later creation time of the two.
+    ///
+    /// # Arguments
+    /// * `other` - Commitment to merge in
+    ///
+    /// # Returns
+    /// `Ok(DatabaseCommitment)` with the combined tables and a recomputed
+    /// hash, `Err` if both commitments contain a table with the same name
+    pub fn merge(mut self, other: DatabaseCommitment) -> Result<Self, Box<dyn std::error::Error>> {
+        for table in &other.table_commitments {
+            if self.get_table_commitment(&table.table_name).is_some() {
+                return Err(
+                    format!("Duplicate table in merged commitment: {}", table.table_name).into(),
+                );
+            }
+        }
+
+        self.table_commitments.extend(other.table_commitments);
+        self.version = self.version.max(other.version);
+        self.created_at = self.created_at.max(other.created_at);
+        self.commitment_hash =
+            Self::compute_commitment_hash(&self.table_commitments, self.version, self.created_at);
+        Ok(self)
+    }
+
+    /// Check whether the commitment hash matches the commitment contents
+    ///
+    /// # Returns
+    /// `true` if the hash covers the tables, version and creation time
+    pub fn hash_matches(&self) -> bool {
+        Self::compute_commitment_hash(&self.table_commitments, self.version, self.created_at)
+            == self.commitment_hash
+    }
+
+    /// Get the commitment hash as a field element
+    ///
+    /// The hash is recomputed from the commitment contents and reduced into
+    /// the field, so proofs can carry it as a public input (see
+    /// `Verifier::verify_with_commitment`).
+    ///
+    /// # Returns
+    /// The hash of the tables, version and creation time as a field element
+    pub fn hash_field(&self) -> Field {
+        let hash =
+            Self::compute_commitment_hash(&self.table_commitments, self.version, self.created_at);
+        let mut bytes = [0u8; 64];
+        if let Ok(digest) = hex::decode(hash) {
+            bytes[..digest.len()].copy_from_slice(&digest);
         }
+        Field::from_uniform_bytes(&bytes)
     }
 
     /// Verify database commitment
@@ -172,7 +319,7 @@ impl DatabaseCommitment {
     ///
     /// let params = IPAParams::new(16);
     /// let tables = vec![/* your tables */];
-    /// let commitment = DatabaseCommitment::commit_database(&tables, &params);
+    /// let commitment = DatabaseCommitment::commit_database(&tables, &params)?;
     ///
     /// assert!(commitment.verify(&params));
     /// ```
@@ -181,31 +328,176 @@ impl DatabaseCommitment {
         // In full implementation, this would verify cryptographic proofs
 
         // For now, verify that commitment hash matches
-        let recomputed_hash = Self::compute_commitment_hash(&self.table_commitments);
-        if recomputed_hash != self.commitment_hash {
+        if !self.hash_matches() {
             return false;
         }
 
         // Verify all column commitments are valid
         // (This is a simplified check - in production, verify cryptographic proofs)
-        true
+        // Empty columns must carry the empty commitment, other columns a point
+        self.table_commitments
+            .iter()
+            .flat_map(|tc| &tc.column_commitments)
+            .all(|col| {
+                if col.num_rows == 0 {
+                    col.commitment == EMPTY_COMMITMENT
+                } else {
+                    VectorCommitment::point_from_bytes(&col.commitment).is_some()
+                }
+            })
+    }
+
+    /// Verify the column commitments against the data they were made from
+    ///
+    /// Unlike `verify`, this recomputes every column commitment, so any
+    /// changed cell is detected. It needs the original tables and the blinds
+    /// from `commit_database_with_blinds`, so only the prover can run it.
+    ///
+    /// # Arguments
+    /// * `original_tables` - Tables the commitment was created from
+    /// * `blinds` - Blinds of the column commitments
+    /// * `params` - IPA parameters used for commitment
+    ///
+    /// # Returns
+    /// `true` if the hash matches and every column commitment recomputes to
+    /// the committed bytes, `false` otherwise
+    pub fn verify_cryptographic(
+        &self,
+        original_tables: &[Table],
+        blinds: &CommitmentBlinds,
+        params: &IPAParams,
+    ) -> bool {
+        match self.column_checks(original_tables) {
+            Some(checks) => checks
+                .iter()
+                .all(|&(table, committed)| Self::column_matches(table, committed, blinds, params)),
+            None => false,
+        }
+    }
+
+    /// Verify the column commitments against their data on all cores
+    ///
+    /// Same as `verify_cryptographic`, with the columns split across threads.
+    ///
+    /// # Arguments
+    /// * `original_tables` - Tables the commitment was created from
+    /// * `blinds` - Blinds of the column commitments
+    /// * `params` - IPA parameters used for commitment
+    ///
+    /// # Returns
+    /// `true` if the hash matches and every column commitment recomputes to
+    /// the committed bytes, `false` otherwise
+    #[cfg(feature = "parallel")]
+    pub fn verify_cryptographic_parallel(
+        &self,
+        original_tables: &[Table],
+        blinds: &CommitmentBlinds,
+        params: &IPAParams,
+    ) -> bool {
+        let Some(checks) = self.column_checks(original_tables) else {
+            return false;
+        };
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk_size = checks.len().div_ceil(threads).max(1);
+
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = checks
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk.iter().all(|&(table, committed)| {
+                            Self::column_matches(table, committed, blinds, params)
+                        })
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .all(|handle| handle.join().unwrap_or(false))
+        })
+    }
+
+    /// Pair every column commitment with the table it was made from
+    ///
+    /// # Returns
+    /// `Some` pairs if the hash matches and the tables have the committed
+    /// layout, `None` otherwise
+    fn column_checks<'a>(
+        &'a self,
+        tables: &'a [Table],
+    ) -> Option<Vec<(&'a Table, &'a ColumnCommitment)>> {
+        if !self.hash_matches() || tables.len() != self.table_commitments.len() {
+            return None;
+        }
+
+        let mut checks = Vec::with_capacity(self.total_columns());
+        for table_commitment in &self.table_commitments {
+            let table = tables
+                .iter()
+                .find(|t| t.name == table_commitment.table_name)?;
+            if table.columns.len() != table_commitment.column_commitments.len() {
+                return None;
+            }
+            checks.extend(
+                table_commitment
+                    .column_commitments
+                    .iter()
+                    .map(|committed| (table, committed)),
+            );
+        }
+        Some(checks)
+    }
+
+    /// Recompute a column commitment and compare it with the committed bytes
+    fn column_matches(
+        table: &Table,
+        committed: &ColumnCommitment,
+        blinds: &CommitmentBlinds,
+        params: &IPAParams,
+    ) -> bool {
+        if committed.num_rows != table.rows.len()
+            || table.get_column(&committed.column_name).is_none()
+        {
+            return false;
+        }
+        if table.rows.is_empty() {
+            return committed.commitment == EMPTY_COMMITMENT;
+        }
+
+        let Some(blind) = blinds.get(&table.name, &committed.column_name) else {
+            return false;
+        };
+        let values = table
+            .column_fields(&committed.column_name)
+            .unwrap_or_default();
+        VectorCommitment::commit_with_blind(values, blind, params)
+            .is_ok_and(|vc| vc.commitment == committed.commitment)
     }
 
     /// Compute hash of all commitments
     ///
-    /// Creates a SHA-256 hash of all table and column commitments.
+    /// Creates a SHA-256 hash of all table and column commitments, the
+    /// version and the creation time.
     /// This hash can be published on a blockchain for immutable verification.
     ///
     /// # Arguments
     /// * `table_commitments` - Vector of table commitments
+    /// * `version` - Commitment version
+    /// * `created_at` - Creation time in seconds since the Unix epoch
     ///
     /// # Returns
     /// Hex-encoded SHA-256 hash string
-    fn compute_commitment_hash(table_commitments: &[TableCommitment]) -> String {
+    fn compute_commitment_hash(
+        table_commitments: &[TableCommitment],
+        version: u64,
+        created_at: u64,
+    ) -> String {
         use hex;
         use sha2::{Digest, Sha256};
 
         let mut hasher = Sha256::new();
+        hasher.update(version.to_le_bytes());
+        hasher.update(created_at.to_le_bytes());
 
         for table in table_commitments {
             hasher.update(table.table_name.as_bytes());
@@ -234,7 +526,7 @@ impl DatabaseCommitment {
     ///
     /// let params = IPAParams::new(16);
     /// let tables = vec![/* your tables */];
-    /// let commitment = DatabaseCommitment::commit_database(&tables, &params);
+    /// let commitment = DatabaseCommitment::commit_database(&tables, &params)?;
     ///
     /// if let Some(table_commitment) = commitment.get_table_commitment("users") {
     ///     println!("Found table commitment: {}", table_commitment.table_name);
@@ -246,6 +538,120 @@ impl DatabaseCommitment {
             .find(|tc| tc.table_name == table_name)
     }
 
+    /// Serialize to a compact binary format
+    ///
+    /// Points are stored compressed (32 bytes instead of 64). Layout
+    /// (little-endian, strings and commitments are u32 length-prefixed):
+    ///
+    /// ```text
+    /// "NZDC" | format: u8 | flags: u8 | version: u64 | created_at: u64
+    ///   | commitment_hash | num_tables: u32
+    ///   table_name | num_columns: u32
+    ///     column_name | num_rows: u64 | commitment
+    /// ```
+    ///
+    /// Commitments that are not valid points (e.g., empty columns) are
+    /// stored as-is.
+    ///
+    /// # Returns
+    /// The serialized bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.push(BINARY_VERSION);
+        bytes.push(FLAG_COMPRESSED);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&self.created_at.to_le_bytes());
+        write_bytes(&mut bytes, self.commitment_hash.as_bytes());
+
+        bytes.extend_from_slice(&(self.table_commitments.len() as u32).to_le_bytes());
+        for table in &self.table_commitments {
+            write_bytes(&mut bytes, table.table_name.as_bytes());
+            bytes.extend_from_slice(&(table.column_commitments.len() as u32).to_le_bytes());
+
+            for col in &table.column_commitments {
+                write_bytes(&mut bytes, col.column_name.as_bytes());
+                bytes.extend_from_slice(&(col.num_rows as u64).to_le_bytes());
+                // Only compress when decompressing restores the exact bytes
+                let commitment = VectorCommitment::point_from_bytes(&col.commitment)
+                    .filter(|point| point.to_uncompressed().as_ref() == col.commitment.as_slice())
+                    .map(|point| point.to_bytes().as_ref().to_vec())
+                    .unwrap_or_else(|| col.commitment.clone());
+                write_bytes(&mut bytes, &commitment);
+            }
+        }
+
+        bytes
+    }
+
+    /// Deserialize from the binary format produced by `to_bytes`
+    ///
+    /// Compressed points are expanded back to the uncompressed (64 bytes)
+    /// encoding, so the commitment hash is unchanged.
+    ///
+    /// # Arguments
+    /// * `bytes` - Serialized commitment
+    ///
+    /// # Returns
+    /// `Ok(DatabaseCommitment)` if the bytes are well-formed, `Err` otherwise
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        if reader.take(4)? != BINARY_MAGIC {
+            return Err("Not a database commitment".into());
+        }
+        let format = reader.take(1)?[0];
+        if format != BINARY_VERSION {
+            return Err(format!("Unsupported commitment format version {}", format).into());
+        }
+        let compressed = reader.take(1)?[0] & FLAG_COMPRESSED != 0;
+        let version = reader.read_u64()?;
+        let created_at = reader.read_u64()?;
+        let commitment_hash = reader.read_string()?;
+
+        let num_tables = reader.read_u32()? as usize;
+        let mut table_commitments = Vec::with_capacity(num_tables.min(bytes.len()));
+        for _ in 0..num_tables {
+            let table_name = reader.read_string()?;
+            let num_columns = reader.read_u32()? as usize;
+            let mut column_commitments = Vec::with_capacity(num_columns.min(bytes.len()));
+
+            for _ in 0..num_columns {
+                let column_name = reader.read_string()?;
+                let num_rows = reader.read_u64()? as usize;
+                let stored = reader.read_bytes()?;
+                let commitment = if compressed && stored.len() == 32 {
+                    let point = VectorCommitment::point_from_bytes(stored)
+                        .ok_or("Invalid compressed commitment point")?;
+                    point.to_uncompressed().as_ref().to_vec()
+                } else {
+                    stored.to_vec()
+                };
+
+                column_commitments.push(ColumnCommitment {
+                    column_name,
+                    commitment,
+                    num_rows,
+                });
+            }
+
+            table_commitments.push(TableCommitment {
+                table_name,
+                column_commitments,
+            });
+        }
+
+        if reader.pos != bytes.len() {
+            return Err("Trailing bytes after database commitment".into());
+        }
+
+        Ok(Self {
+            table_commitments,
+            commitment_hash,
+            version,
+            created_at,
+        })
+    }
+
     /// Get number of tables
     pub fn num_tables(&self) -> usize {
         self.table_commitments.len()
@@ -280,6 +686,48 @@ impl TableCommitment {
     }
 }
 
+/// Append u32 length-prefixed bytes
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Cursor over the binary commitment format
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or("Truncated database commitment")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Box<dyn std::error::Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let len = self.read_u32()? as usize;
+        self.take(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(String::from_utf8(self.read_bytes()?.to_vec())?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -298,13 +746,187 @@ mod tests {
             ],
         };
 
-        let commitment = DatabaseCommitment::commit_database(&[table], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
 
         assert_eq!(commitment.num_tables(), 1);
         assert_eq!(commitment.total_columns(), 1);
         assert!(commitment.verify(&params));
     }
 
+    #[test]
+    fn test_database_commitment_verify_cryptographic() {
+        let params = IPAParams::new(10);
+
+        let mut tables = vec![
+            Table {
+                name: "orders".to_string(),
+                columns: vec![
+                    Column::new("o_orderkey".to_string(), DataType::Integer),
+                    Column::new("o_totalprice".to_string(), DataType::Decimal),
+                ],
+                rows: vec![
+                    Row::new(vec![Value::Integer(1), Value::Decimal(17325)]),
+                    Row::new(vec![Value::Integer(2), Value::Decimal(4590)]),
+                ],
+            },
+            Table {
+                name: "nation".to_string(),
+                columns: vec![Column::new("n_name".to_string(), DataType::Varchar(25))],
+                rows: vec![],
+            },
+        ];
+
+        let (commitment, blinds) =
+            DatabaseCommitment::commit_database_with_blinds(&tables, &params).unwrap();
+        assert!(commitment.verify_cryptographic(&tables, &blinds, &params));
+
+        // The hash-only check can't see the data; the cryptographic one can
+        tables[0].rows[1].values[1] = Value::Decimal(4591);
+        assert!(commitment.verify(&params));
+        assert!(!commitment.verify_cryptographic(&tables, &blinds, &params));
+        #[cfg(feature = "parallel")]
+        assert!(!commitment.verify_cryptographic_parallel(&tables, &blinds, &params));
+
+        tables[0].rows[1].values[1] = Value::Decimal(4590);
+        #[cfg(feature = "parallel")]
+        assert!(commitment.verify_cryptographic_parallel(&tables, &blinds, &params));
+
+        // Without the blinds the commitments can't be recomputed
+        assert!(!commitment.verify_cryptographic(&tables, &CommitmentBlinds::default(), &params));
+        assert!(!commitment.verify_cryptographic(&tables[..1], &blinds, &params));
+    }
+
+    #[test]
+    fn test_database_commitment_empty_table() {
+        let params = IPAParams::new(10);
+
+        let table = Table::new(
+            "empty".to_string(),
+            vec![
+                Column::new("id".to_string(), DataType::Integer),
+                Column::new("name".to_string(), DataType::Varchar(10)),
+            ],
+        );
+
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
+
+        assert_eq!(commitment.total_columns(), 2);
+        for col in &commitment.table_commitments[0].column_commitments {
+            assert_eq!(col.num_rows, 0);
+            assert_eq!(col.commitment, EMPTY_COMMITMENT.to_vec());
+        }
+        assert!(commitment.verify(&params));
+
+        // Survives the binary round trip
+        let decoded = DatabaseCommitment::from_bytes(&commitment.to_bytes()).unwrap();
+        assert!(decoded.verify(&params));
+
+        // An empty column claiming rows is rejected
+        let mut tampered = commitment.clone();
+        tampered.table_commitments[0].column_commitments[0].num_rows = 1;
+        tampered.commitment_hash = DatabaseCommitment::compute_commitment_hash(
+            &tampered.table_commitments,
+            tampered.version,
+            tampered.created_at,
+        );
+        assert!(!tampered.verify(&params));
+    }
+
+    #[test]
+    fn test_database_commitment_null_distinct_from_zero() {
+        let params = IPAParams::new(10);
+
+        let column = vec![Column::new("l_quantity".to_string(), DataType::Integer)];
+        let with_null = Table {
+            name: "test".to_string(),
+            columns: column.clone(),
+            rows: vec![
+                Row::new(vec![Value::Integer(0)]),
+                Row::new(vec![Value::Null]),
+            ],
+        };
+        let with_zero = Table {
+            name: "test".to_string(),
+            columns: column,
+            rows: vec![
+                Row::new(vec![Value::Integer(0)]),
+                Row::new(vec![Value::Integer(0)]),
+            ],
+        };
+
+        // The committed column vectors differ, not just the blinding
+        let null_values: Vec<_> = with_null.rows.iter().flat_map(Row::to_fields).collect();
+        let zero_values: Vec<_> = with_zero.rows.iter().flat_map(Row::to_fields).collect();
+        assert_ne!(null_values, zero_values);
+
+        let null_commitment = DatabaseCommitment::commit_database(&[with_null], &params).unwrap();
+        let zero_commitment = DatabaseCommitment::commit_database(&[with_zero], &params).unwrap();
+        assert_ne!(
+            null_commitment.table_commitments[0].column_commitments[0].commitment,
+            zero_commitment.table_commitments[0].column_commitments[0].commitment
+        );
+    }
+
+    #[test]
+    fn test_database_commitment_binary_round_trip() {
+        let params = IPAParams::new(10);
+
+        let table = Table {
+            name: "lineitem".to_string(),
+            columns: vec![
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_discount".to_string(), DataType::Integer),
+            ],
+            rows: vec![
+                Row::new(vec![
+                    Value::BigInt(1),
+                    Value::Integer(17),
+                    Value::Integer(4),
+                ]),
+                Row::new(vec![
+                    Value::BigInt(2),
+                    Value::Integer(36),
+                    Value::Integer(9),
+                ]),
+            ],
+        };
+        let empty = Table {
+            name: "region".to_string(),
+            columns: vec![Column::new("r_regionkey".to_string(), DataType::Integer)],
+            rows: vec![],
+        };
+
+        let commitment = DatabaseCommitment::commit_database(&[table, empty], &params).unwrap();
+        let bytes = commitment.to_bytes();
+        let decoded = DatabaseCommitment::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded.commitment_hash, commitment.commitment_hash);
+        assert_eq!(decoded.version, commitment.version);
+        assert_eq!(decoded.created_at, commitment.created_at);
+        assert_eq!(decoded.num_tables(), 2);
+        for (a, b) in decoded
+            .table_commitments
+            .iter()
+            .zip(&commitment.table_commitments)
+        {
+            assert_eq!(a.table_name, b.table_name);
+            for (x, y) in a.column_commitments.iter().zip(&b.column_commitments) {
+                assert_eq!(x.column_name, y.column_name);
+                assert_eq!(x.commitment, y.commitment);
+                assert_eq!(x.num_rows, y.num_rows);
+            }
+        }
+        assert!(decoded.verify(&params));
+
+        // Compressed binary is at most half the uncompressed JSON
+        let json = serde_json::to_vec(&commitment).unwrap();
+        assert!(bytes.len() * 2 <= json.len());
+
+        // Truncated input is rejected
+        assert!(DatabaseCommitment::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
     #[test]
     fn test_database_commitment_multiple_tables() {
         let params = IPAParams::new(10);
@@ -321,13 +943,51 @@ mod tests {
             rows: vec![Row::new(vec![Value::Integer(100)])],
         };
 
-        let commitment = DatabaseCommitment::commit_database(&[table1, table2], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table1, table2], &params).unwrap();
 
         assert_eq!(commitment.num_tables(), 2);
         assert_eq!(commitment.total_columns(), 2);
         assert!(commitment.verify(&params));
     }
 
+    #[test]
+    fn test_database_commitment_merge() {
+        let params = IPAParams::new(10);
+
+        let users = Table {
+            name: "users".to_string(),
+            columns: vec![Column::new("id".to_string(), DataType::Integer)],
+            rows: vec![Row::new(vec![Value::Integer(1)])],
+        };
+
+        let orders = Table {
+            name: "orders".to_string(),
+            columns: vec![Column::new("order_id".to_string(), DataType::Integer)],
+            rows: vec![Row::new(vec![Value::Integer(100)])],
+        };
+
+        let left = DatabaseCommitment::commit_database(&[users.clone()], &params)
+            .unwrap()
+            .with_created_at(0);
+        let right = DatabaseCommitment::commit_database(&[orders.clone()], &params)
+            .unwrap()
+            .with_created_at(0);
+        let merged = left.clone().merge(right).unwrap();
+
+        assert_eq!(merged.num_tables(), 2);
+        assert!(merged.get_table_commitment("users").is_some());
+        assert!(merged.get_table_commitment("orders").is_some());
+        assert!(merged.verify(&params));
+
+        let combined = DatabaseCommitment::commit_database(&[users, orders], &params)
+            .unwrap()
+            .with_created_at(0);
+        assert_eq!(merged.commitment_hash, combined.commitment_hash);
+
+        // Both sides committing the same table is rejected
+        assert!(left.clone().merge(left).is_err());
+    }
+
     #[test]
     fn test_get_table_commitment() {
         let params = IPAParams::new(10);
@@ -338,7 +998,7 @@ mod tests {
             rows: vec![Row::new(vec![Value::Integer(1)])],
         };
 
-        let commitment = DatabaseCommitment::commit_database(&[table], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
 
         let table_commitment = commitment.get_table_commitment("users");
         assert!(table_commitment.is_some());
@@ -358,15 +1018,31 @@ mod tests {
             rows: vec![Row::new(vec![Value::Integer(1)])],
         };
 
-        let commitment1 = DatabaseCommitment::commit_database(&[table.clone()], &params);
-        let commitment2 = DatabaseCommitment::commit_database(&[table], &params);
+        let commitment1 = DatabaseCommitment::commit_database(&[table.clone()], &params)
+            .unwrap()
+            .with_created_at(0);
+        let commitment2 = DatabaseCommitment::commit_database(&[table], &params)
+            .unwrap()
+            .with_created_at(0);
 
         // Same data should produce same hash
         assert_eq!(commitment1.commitment_hash, commitment2.commitment_hash);
+
+        // The hash covers the version
+        let commitment3 = commitment2.with_version(2);
+        assert_ne!(commitment1.commitment_hash, commitment3.commitment_hash);
+        assert!(commitment3.verify(&params));
+
+        // ...and so does its field encoding
+        assert_eq!(commitment1.hash_field(), commitment2.hash_field());
+        assert_ne!(commitment1.hash_field(), commitment3.hash_field());
+
+        let mut forged = commitment1.clone();
+        forged.version = 2;
+        assert!(!forged.verify(&params));
     }
 
     #[test]
-    #[should_panic(expected = "exceeds maximum")]
     fn test_database_commitment_overflow() {
         let params = IPAParams::new(2); // max 4 rows
 
@@ -382,6 +1058,9 @@ mod tests {
             ],
         };
 
-        let _commitment = DatabaseCommitment::commit_database(&[table], &params);
+        assert_eq!(
+            DatabaseCommitment::commit_database(&[table], &params).unwrap_err(),
+            CommitmentError::TooLarge { rows: 5, max: 4 }
+        );
     }
 }
@@ -0,0 +1,32 @@
+//! Commitment error type
+//!
+//! This module provides the error type returned when creating commitments,
+//! so callers handling untrusted input can reject oversized data instead
+//! of crashing.
+
+use thiserror::Error;
+
+/// Error returned by commitment creation
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CommitmentError {
+    /// The data has more rows than the IPA parameters support
+    #[error("{rows} rows exceed maximum rows {max}")]
+    TooLarge {
+        /// Number of rows to commit
+        rows: usize,
+
+        /// Maximum number of rows (`IPAParams::max_rows`)
+        max: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_error_display() {
+        let err = CommitmentError::TooLarge { rows: 5, max: 4 };
+        assert_eq!(err.to_string(), "5 rows exceed maximum rows 4");
+    }
+}
@@ -10,17 +10,20 @@
 //! - No trusted setup required
 //! - Works on 254-bit prime field
 
+use super::error::CommitmentError;
+use super::scheme::{commit_with_params, CommitmentScheme, IPAScheme};
 use ff::Field as _;
-use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine};
-use halo2_proofs::halo2curves::group::{Curve, UncompressedEncoding};
-use halo2_proofs::poly::commitment::{Blind, ParamsProver};
-use halo2_proofs::poly::ipa::commitment::ParamsIPA;
-use halo2_proofs::poly::EvaluationDomain;
-// Note: Coeff and Polynomial are internal types used by ParamsIPA::commit
-// We'll create the polynomial through EvaluationDomain::coeff_from_vec
-use halo2_middleware::zal::impls::PlonkEngineConfig;
-use rand_core::OsRng;
+use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine, G1};
+use halo2_proofs::halo2curves::group::{Curve, GroupEncoding, UncompressedEncoding};
+use halo2_proofs::poly::commitment::{Blind, Params, ParamsProver};
+use halo2_proofs::poly::ipa::commitment::{ParamsIPA, ParamsVerifierIPA};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// Commitment bytes of an empty vector (uncompressed G1Affine size, all zero)
+pub const EMPTY_COMMITMENT: [u8; 64] = [0u8; 64];
 
 /// IPA (Inner Product Argument) commitment parameters
 ///
@@ -28,6 +31,10 @@ use serde::{Deserialize, Serialize};
 /// The parameter `k` represents log2(max_rows), so k=16 means max 2^16 = 65536 rows.
 ///
 /// Note: Params is not serializable, so we only store k and regenerate params when needed.
+///
+/// Deserializing `IPAParams` regenerates the parameters, which takes minutes
+/// for large k. This implicit regeneration is deprecated: store an
+/// [`IPAParamsRef`] in configs and call [`IPAParamsRef::materialize`] instead.
 #[derive(Debug, Clone, Serialize)]
 pub struct IPAParams {
     /// Halo2 commitment parameters (not serializable)
@@ -38,8 +45,16 @@ pub struct IPAParams {
 
     /// Log2 of maximum number of rows
     pub k: u32,
+
+    /// Parameters downsized to smaller commitment domains, by log2 of the
+    /// domain size (shared between clones)
+    #[serde(skip)]
+    domains: DomainParams,
 }
 
+/// Cache of downsized parameters, by log2 of the domain size
+type DomainParams = Arc<Mutex<HashMap<u32, Arc<ParamsIPA<G1Affine>>>>>;
+
 impl<'de> serde::Deserialize<'de> for IPAParams {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -51,6 +66,11 @@ impl<'de> serde::Deserialize<'de> for IPAParams {
         }
 
         let helper = IPAParamsHelper::deserialize(deserializer)?;
+        log::warn!(
+            "Deserializing IPAParams regenerates parameters for k={}; \
+             deserialize IPAParamsRef and call materialize instead",
+            helper.k
+        );
 
         // Reconstruct params from k
         let params = ParamsIPA::new(helper.k);
@@ -58,6 +78,7 @@ impl<'de> serde::Deserialize<'de> for IPAParams {
         Ok(IPAParams {
             params,
             k: helper.k,
+            domains: DomainParams::default(),
         })
     }
 }
@@ -84,7 +105,11 @@ impl IPAParams {
         let params = ParamsIPA::new(k);
         println!("✅ IPA parameters generated successfully");
 
-        Self { params, k }
+        Self {
+            params,
+            k,
+            domains: DomainParams::default(),
+        }
     }
 
     /// Load parameters from file
@@ -110,7 +135,11 @@ impl IPAParams {
         // Note: This is a simplified version - in production, you'd need proper serialization
         let params = ParamsIPA::new(k);
 
-        Ok(Self { params, k })
+        Ok(Self {
+            params,
+            k,
+            domains: DomainParams::default(),
+        })
     }
 
     /// Save parameters to file
@@ -155,14 +184,125 @@ impl IPAParams {
     pub fn k(&self) -> u32 {
         self.k
     }
+
+    /// Get the parameters downsized to a domain of 2^domain_k values
+    ///
+    /// Downsizing copies the generators, which costs as much as the MSM it
+    /// saves, so each domain size is downsized once and cached.
+    ///
+    /// # Arguments
+    /// * `domain_k` - Log2 of the domain size, at most `k`
+    ///
+    /// # Returns
+    /// The downsized parameters
+    pub(crate) fn domain_params(&self, domain_k: u32) -> Arc<ParamsIPA<G1Affine>> {
+        let mut domains = self.domains.lock().unwrap_or_else(|e| e.into_inner());
+        domains
+            .entry(domain_k)
+            .or_insert_with(|| {
+                let mut params = self.params.clone();
+                params.downsize(domain_k);
+                Arc::new(params)
+            })
+            .clone()
+    }
+
+    /// Extract the parameters needed for proof verification only
+    ///
+    /// # Returns
+    /// `VerifierParams` for use with `Verifier::new_from_verifier_params`
+    pub fn verifier_params(&self) -> VerifierParams {
+        VerifierParams {
+            params: self.params.verifier_params().clone(),
+            k: self.k,
+        }
+    }
+}
+
+/// Reference to IPA parameters by size only
+///
+/// A lightweight stand-in for [`IPAParams`] in configs and manifests.
+/// Deserializes from the same `{"k": ...}` form without generating
+/// anything; [`IPAParamsRef::materialize`] performs the expensive generation
+/// explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IPAParamsRef {
+    /// Log2 of maximum number of rows
+    pub k: u32,
+}
+
+impl IPAParamsRef {
+    /// Create a reference to parameters of size 2^k
+    ///
+    /// # Arguments
+    /// * `k` - Log2 of maximum number of rows
+    pub fn new(k: u32) -> Self {
+        Self { k }
+    }
+
+    /// Get maximum number of rows of the referenced parameters
+    pub fn max_rows(&self) -> usize {
+        1 << self.k
+    }
+
+    /// Generate the referenced parameters
+    ///
+    /// This is as slow as `IPAParams::new` (see the timings in the
+    /// commitment module docs).
+    ///
+    /// # Returns
+    /// `IPAParams` for k
+    pub fn materialize(&self) -> IPAParams {
+        IPAParams::new(self.k)
+    }
+}
+
+impl From<&IPAParams> for IPAParamsRef {
+    fn from(params: &IPAParams) -> Self {
+        Self { k: params.k }
+    }
+}
+
+/// Verification-only IPA parameters
+///
+/// Holds just what `verify_proof` needs, so a verifier does not have to
+/// keep the prover parameters around.
+#[derive(Debug, Clone)]
+pub struct VerifierParams {
+    /// Halo2 verifier parameters
+    pub params: ParamsVerifierIPA<G1Affine>,
+
+    /// Log2 of maximum number of rows
+    pub k: u32,
+}
+
+impl VerifierParams {
+    /// Get the parameter k (log2 of max rows)
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Get maximum number of rows
+    pub fn max_rows(&self) -> usize {
+        1 << self.k
+    }
+
+    /// Commit to values over the full 2^k domain
+    ///
+    /// Gives the same point as committing over any smaller domain that fits
+    /// the values (see `IPAScheme::commit_in_domain`).
+    pub(crate) fn commit(&self, values: &[Field], blind: Blind<Field>) -> G1 {
+        commit_with_params(&self.params, values, blind)
+    }
 }
 
 /// Commitment to a vector of field elements
 ///
 /// This represents a cryptographic commitment to a vector of field elements
-/// using the IPA protocol.
+/// under the commitment backend `S`, by default the IPA protocol.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VectorCommitment {
+#[serde(bound = "")]
+pub struct VectorCommitment<S = IPAScheme> {
     /// Cryptographic commitment bytes
     pub commitment: Vec<u8>,
 
@@ -175,6 +315,35 @@ pub struct VectorCommitment {
     /// This is serialized as bytes for storage
     #[serde(with = "blind_serde")]
     pub blind: Option<Blind<Field>>,
+
+    /// Size of the polynomial domain the values were committed over
+    /// (0 for commitments created before domain sizing, which used 2^k)
+    #[serde(default)]
+    pub domain_size: usize,
+
+    /// Number of committed values for hiding commitments, which retain
+    /// neither the values nor the blind (`None` if the values are retained)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden_len: Option<usize>,
+
+    /// Commitment backend the commitment was created with
+    #[serde(skip)]
+    pub scheme: PhantomData<S>,
+}
+
+/// Full opening of a hiding commitment
+///
+/// Kept by the data owner to open a commitment created with
+/// `VectorCommitment::commit_hiding`. It holds every committed value and the
+/// blind, so handing it to a verifier discloses the whole vector; to reveal
+/// only some cells of a database column, use `CellOpening` instead.
+#[derive(Debug, Clone)]
+pub struct CommitmentOpening {
+    /// The committed values
+    pub values: Vec<Field>,
+
+    /// Blind factor used in the commitment
+    pub blind: Blind<Field>,
 }
 
 /// Serialization helper for Field vectors
@@ -309,7 +478,8 @@ impl VectorCommitment {
     /// * `params` - IPA parameters for commitment
     ///
     /// # Returns
-    /// A `VectorCommitment` containing the commitment and original values
+    /// `Ok(VectorCommitment)` containing the commitment and original values,
+    /// `Err(CommitmentError::TooLarge)` if the vector exceeds `params.max_rows()`
     ///
     /// # Example
     /// ```
@@ -318,7 +488,7 @@ impl VectorCommitment {
     ///
     /// let params = IPAParams::new(10);
     /// let values = vec![Field::from(1), Field::from(2), Field::from(3)];
-    /// let commitment = VectorCommitment::commit(values.clone(), &params);
+    /// let commitment = VectorCommitment::commit(values.clone(), &params)?;
     /// ```
     /// Create commitment to a vector of field elements using IPA protocol
     ///
@@ -327,63 +497,141 @@ impl VectorCommitment {
     /// 1. Creating a polynomial from the values (coefficient form)
     /// 2. Committing to the polynomial using ParamsIPA with a random blinding factor
     /// 3. Serializing the commitment (G1 curve point) to bytes
-    pub fn commit(values: Vec<Field>, params: &IPAParams) -> Self {
-        // Check that values fit within max_rows
-        if values.len() > params.max_rows() {
-            panic!(
-                "Vector length {} exceeds maximum rows {}",
-                values.len(),
-                params.max_rows()
-            );
-        }
+    ///
+    /// The random blind makes the commitment hiding, so committing the same
+    /// values twice yields different bytes. Use `commit_with_blind` or
+    /// `commit_unblinded` for reproducible commitments.
+    pub fn commit(values: Vec<Field>, params: &IPAParams) -> Result<Self, CommitmentError> {
+        IPAScheme::commit(values, params)
+    }
 
-        // Handle empty vector
-        if values.is_empty() {
-            return Self {
-                commitment: vec![0u8; 64], // Empty commitment (uncompressed G1Affine size)
-                values,
-                blind: None, // No blind for empty commitment
-            };
-        }
+    /// Create commitment to a vector of field elements with a given blinding factor
+    ///
+    /// Unlike `commit`, an empty vector is committed as the blinding term alone,
+    /// so the result can later be extended with `append`.
+    ///
+    /// # Arguments
+    /// * `values` - Vector of field elements to commit to
+    /// * `blind` - Blinding factor
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok(VectorCommitment)` containing the commitment, values, and blind,
+    /// `Err(CommitmentError::TooLarge)` if the vector exceeds `params.max_rows()`
+    pub fn commit_with_blind(
+        values: Vec<Field>,
+        blind: Blind<Field>,
+        params: &IPAParams,
+    ) -> Result<Self, CommitmentError> {
+        IPAScheme::commit_with_blind(values, blind, params)
+    }
 
-        // Pad values to domain size (2^k) if necessary
-        let domain_size = params.max_rows();
-        let mut coeffs = values.clone();
-        while coeffs.len() < domain_size {
-            coeffs.push(Field::zero());
-        }
-        // Truncate if too large (shouldn't happen due to check above)
-        coeffs.truncate(domain_size);
+    /// Create a commitment with a zero blinding factor
+    ///
+    /// The commitment is still binding, but not hiding: anyone who can guess
+    /// the values can recompute it. Equal values always give equal bytes,
+    /// which makes this suitable for tests and public data.
+    ///
+    /// # Arguments
+    /// * `values` - Vector of field elements to commit to
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok(VectorCommitment)` containing the commitment, values, and zero blind,
+    /// `Err(CommitmentError::TooLarge)` if the vector exceeds `params.max_rows()`
+    pub fn commit_unblinded(
+        values: Vec<Field>,
+        params: &IPAParams,
+    ) -> Result<Self, CommitmentError> {
+        Self::commit_with_blind(values, Blind(Field::zero()), params)
+    }
 
-        // Create evaluation domain for polynomial operations
-        // EvaluationDomain::new takes (k: u32, omega_k: u32) where omega_k is the rotation index
-        // For polynomial commitment, we use k=0 (no rotation)
-        let domain = EvaluationDomain::<Field>::new(params.k(), 0u32);
+    /// Check that a number of rows fits the parameters
+    ///
+    /// # Returns
+    /// `Ok(())` if `rows <= params.max_rows()`, `Err(CommitmentError::TooLarge)` otherwise
+    pub fn check_size(rows: usize, params: &IPAParams) -> Result<(), CommitmentError> {
+        IPAScheme::check_size(rows, params)
+    }
 
-        // Create polynomial from coefficients using domain's method
-        let poly = domain.coeff_from_vec(coeffs);
+    /// Create a hiding commitment that does not retain the committed values
+    ///
+    /// Neither the values nor the blind are stored in (or serialized with)
+    /// the commitment; they are returned as a `CommitmentOpening` for the
+    /// data owner to keep. The commitment can then only be checked with
+    /// `verify_disclosure`, which discloses the whole vector.
+    ///
+    /// # Arguments
+    /// * `values` - Vector of field elements to commit to
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok((VectorCommitment, CommitmentOpening))` with the hiding commitment
+    /// and the opening for it, `Err(CommitmentError::TooLarge)` if the vector
+    /// exceeds `params.max_rows()`
+    pub fn commit_hiding(
+        values: Vec<Field>,
+        params: &IPAParams,
+    ) -> Result<(Self, CommitmentOpening), CommitmentError> {
+        IPAScheme::commit_hiding(values, params)
+    }
 
-        // Create a random blinding factor for the commitment
-        let mut rng = OsRng;
-        let blind = Blind(Field::random(&mut rng));
+    /// Get the smallest power-of-two domain size that fits `len` values
+    ///
+    /// # Arguments
+    /// * `len` - Number of values
+    ///
+    /// # Returns
+    /// The domain size, at least 2
+    pub fn domain_size_for(len: usize) -> usize {
+        domain_size_for(len)
+    }
 
-        // Build MSM engine required by Halo2 backend API
-        let engine = PlonkEngineConfig::build_default::<G1Affine>();
+    /// Commit to values padded to a power-of-two domain
+    ///
+    /// See [`IPAScheme::commit_in_domain`].
+    pub(crate) fn commit_in_domain(
+        values: &[Field],
+        blind: Blind<Field>,
+        domain_size: usize,
+        params: &IPAParams,
+    ) -> G1 {
+        IPAScheme::commit_in_domain(values, blind, domain_size, params)
+    }
 
-        // Commit to polynomial using IPA protocol with explicit engine backend
-        let commitment_g1 = params.params.commit(&engine.msm_backend, &poly, blind);
+    /// Decode a G1Affine point from uncompressed (64 bytes) or compressed (32 bytes) encoding
+    ///
+    /// # Arguments
+    /// * `bytes` - Encoded point
+    ///
+    /// # Returns
+    /// `Some(G1Affine)` if the bytes encode a valid point, `None` otherwise
+    pub fn point_from_bytes(bytes: &[u8]) -> Option<G1Affine> {
+        point_from_bytes(bytes)
+    }
+}
 
-        // Convert G1 to G1Affine and serialize to uncompressed bytes (64 bytes)
-        // G1 implements PrimeCurve which has to_affine() method
-        let commitment_affine: G1Affine = commitment_g1.to_affine();
-        // Use uncompressed format (64 bytes) for better compatibility
-        let commitment_bytes = commitment_affine.to_uncompressed();
+impl<S: CommitmentScheme> VectorCommitment<S> {
+    /// Check whether the commitment retains its committed values
+    ///
+    /// # Returns
+    /// `false` for hiding commitments, `true` otherwise
+    pub fn has_values(&self) -> bool {
+        self.hidden_len.is_none()
+    }
 
-        Self {
-            commitment: commitment_bytes.as_ref().to_vec(),
-            values,
-            blind: Some(blind), // Store blind factor for verification
-        }
+    /// Get the domain the values were committed over
+    ///
+    /// # Returns
+    /// `Some(size)` if the stored domain (2^k for older commitments) is a
+    /// power of two that fits `len` values and the parameters, `None` otherwise
+    fn checked_domain_size(&self, len: usize, params: &S::Params) -> Option<usize> {
+        let domain_size = match self.domain_size {
+            0 => S::max_rows(params),
+            size => size,
+        };
+        (domain_size.is_power_of_two() && len <= domain_size && domain_size <= S::max_rows(params))
+            .then_some(domain_size)
     }
 
     /// Verify commitment
@@ -391,7 +639,7 @@ impl VectorCommitment {
     /// Verifies that the commitment matches the committed values.
     ///
     /// # Arguments
-    /// * `params` - IPA parameters used for commitment
+    /// * `params` - Parameters used for commitment
     ///
     /// # Returns
     /// `true` if the commitment is valid, `false` otherwise
@@ -403,7 +651,7 @@ impl VectorCommitment {
     ///
     /// let params = IPAParams::new(10);
     /// let values = vec![Field::from(1), Field::from(2), Field::from(3)];
-    /// let commitment = VectorCommitment::commit(values.clone(), &params);
+    /// let commitment = VectorCommitment::commit(values.clone(), &params)?;
     /// assert!(commitment.verify(&params));
     /// ```
     /// Verify commitment using IPA protocol
@@ -415,7 +663,12 @@ impl VectorCommitment {
     ///
     /// # Returns
     /// `true` if the commitment is valid, `false` otherwise
-    pub fn verify(&self, params: &IPAParams) -> bool {
+    pub fn verify(&self, params: &S::Params) -> bool {
+        // Hiding commitments can only be checked against an opening
+        if !self.has_values() {
+            return false;
+        }
+
         // Get the stored commitment point
         let Some(commitment_point) = self.commitment_point() else {
             return false;
@@ -424,58 +677,110 @@ impl VectorCommitment {
         // Get the blind factor (required for verification)
         let Some(blind) = self.blind else {
             // If no blind factor, we can't verify (empty commitment case)
-            return self.values.is_empty() && self.commitment.len() == 64;
+            return self.values.is_empty() && self.commitment == EMPTY_COMMITMENT;
         };
 
-        // Recompute commitment from values
-        // First, check that values fit within max_rows
-        if self.values.len() > params.max_rows() {
+        // Recompute commitment over the stored domain (2^k for older commitments)
+        let Some(domain_size) = self.checked_domain_size(self.values.len(), params) else {
+            return false;
+        };
+
+        let recomputed_g1 = S::commit_in_domain(&self.values, blind, domain_size, params);
+        let recomputed_affine: G1Affine = recomputed_g1.to_affine();
+
+        // Compare commitments (point equality)
+        commitment_point == recomputed_affine
+    }
+
+    /// Verify commitment against a full disclosure of its values and blind
+    ///
+    /// Recomputes the commitment from the opening's values and blind instead
+    /// of the stored ones, so it also verifies hiding commitments. This is
+    /// not a zero-knowledge opening: the verifier learns every value and the
+    /// blind. Use `CellOpening` to prove selected cells without revealing
+    /// the rest.
+    ///
+    /// # Arguments
+    /// * `opening` - Values and blind the commitment is opened to
+    /// * `params` - Parameters used for commitment
+    ///
+    /// # Returns
+    /// `true` if the commitment opens to the given values, `false` otherwise
+    pub fn verify_disclosure(&self, opening: &CommitmentOpening, params: &S::Params) -> bool {
+        let Some(commitment_point) = self.commitment_point() else {
+            return false;
+        };
+        if opening.values.len() != self.len() {
             return false;
         }
+        let Some(domain_size) = self.checked_domain_size(opening.values.len(), params) else {
+            return false;
+        };
+
+        let recomputed_g1 =
+            S::commit_in_domain(&opening.values, opening.blind, domain_size, params);
+        commitment_point == recomputed_g1.to_affine()
+    }
 
-        // Pad values to domain size if necessary
-        let domain_size = params.max_rows();
-        let mut coeffs = self.values.clone();
-        while coeffs.len() < domain_size {
-            coeffs.push(Field::zero());
+    /// Append a value to the committed vector
+    ///
+    /// The commitment is updated homomorphically: the commitment to the new
+    /// value at the next position (with a zero blind) is added to the existing
+    /// point, so the existing values do not need to be recommitted.
+    ///
+    /// # Arguments
+    /// * `value` - Value to append
+    /// * `params` - Parameters used for commitment
+    ///
+    /// # Returns
+    /// `Ok(())` if the value was appended, `Err` if the vector is full or
+    /// the commitment has no blind (empty commitment from `commit`)
+    pub fn append(
+        &mut self,
+        value: Field,
+        params: &S::Params,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.has_values() {
+            return Err("Hiding commitment cannot be extended without its values".into());
         }
-        coeffs.truncate(domain_size);
+        S::check_size(self.values.len() + 1, params)?;
 
-        // Create evaluation domain
-        let domain = EvaluationDomain::<Field>::new(params.k(), 0u32);
+        let current = self
+            .commitment_point()
+            .filter(|_| self.blind.is_some())
+            .ok_or("Commitment cannot be extended without a blind factor")?;
 
-        // Create polynomial from coefficients
-        let poly = domain.coeff_from_vec(coeffs);
+        // Commitment to (0, ..., 0, value) at the next position
+        let mut delta_values = vec![Field::zero(); self.values.len()];
+        delta_values.push(value);
+        let delta = S::commit_with_blind(delta_values, Blind(Field::zero()), params)?
+            .commitment_point()
+            .ok_or("Failed to compute commitment delta")?;
 
-        // Recompute commitment using the same blind factor
-        let engine = PlonkEngineConfig::build_default::<G1Affine>();
-        let recomputed_g1 = params.params.commit(&engine.msm_backend, &poly, blind);
-        let recomputed_affine: G1Affine = recomputed_g1.to_affine();
+        let updated: G1Affine = (G1::from(current) + delta).to_affine();
+        self.commitment = updated.to_uncompressed().as_ref().to_vec();
+        self.values.push(value);
+        self.domain_size = domain_size_for(self.values.len()).min(S::max_rows(params));
 
-        // Compare commitments (point equality)
-        commitment_point == recomputed_affine
+        Ok(())
     }
 
     /// Get the commitment as G1Affine point
     ///
     /// Deserializes the commitment bytes back to a G1Affine point.
+    /// Accepts both uncompressed (64 bytes) and compressed (32 bytes) encodings.
     /// Returns None if deserialization fails.
     pub fn commitment_point(&self) -> Option<G1Affine> {
-        if self.commitment.len() != 64 {
-            return None;
-        }
-
-        let mut bytes = [0u8; 64];
-        bytes.copy_from_slice(&self.commitment[..64]);
+        point_from_bytes(&self.commitment)
+    }
 
-        // Use from_uncompressed which takes &G1Uncompressed and returns CtOption<G1Affine>
-        // Since we stored uncompressed (64 bytes), we can directly deserialize
-        use halo2_proofs::halo2curves::bn256::G1Uncompressed;
-        use halo2_proofs::halo2curves::group::UncompressedEncoding;
-        // Create G1Uncompressed from bytes - it's a newtype wrapper around [u8; 64]
-        // Since it might have private fields, use unsafe transmute
-        let uncompressed: G1Uncompressed = unsafe { std::mem::transmute(bytes) };
-        G1Affine::from_uncompressed(&uncompressed).into()
+    /// Get the commitment in compressed (32 bytes) encoding
+    ///
+    /// # Returns
+    /// `Some(Vec<u8>)` if the commitment is a valid point, `None` otherwise
+    pub fn compressed_commitment(&self) -> Option<Vec<u8>> {
+        self.commitment_point()
+            .map(|point| point.to_bytes().as_ref().to_vec())
     }
 
     /// Get commitment size in bytes
@@ -485,18 +790,41 @@ impl VectorCommitment {
 
     /// Get number of committed values
     pub fn len(&self) -> usize {
-        self.values.len()
+        self.hidden_len.unwrap_or(self.values.len())
     }
 
     /// Check if commitment is empty
     pub fn is_empty(&self) -> bool {
-        self.values.is_empty()
+        self.len() == 0
+    }
+}
+
+/// Get the smallest power-of-two domain size that fits `len` values, at least 2
+pub(crate) fn domain_size_for(len: usize) -> usize {
+    len.max(2).next_power_of_two()
+}
+
+/// Decode a G1Affine point from uncompressed (64 bytes) or compressed (32 bytes) encoding
+fn point_from_bytes(bytes: &[u8]) -> Option<G1Affine> {
+    match bytes.len() {
+        64 => {
+            let mut uncompressed = <G1Affine as UncompressedEncoding>::Uncompressed::default();
+            uncompressed.as_mut().copy_from_slice(bytes);
+            G1Affine::from_uncompressed(&uncompressed).into()
+        }
+        32 => {
+            let mut compressed = <G1Affine as GroupEncoding>::Repr::default();
+            compressed.as_mut().copy_from_slice(bytes);
+            G1Affine::from_bytes(&compressed).into()
+        }
+        _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
 
     #[test]
     fn test_ipa_params_creation() {
@@ -505,6 +833,26 @@ mod tests {
         assert_eq!(params.max_rows(), 1024);
     }
 
+    #[test]
+    fn test_ipa_params_ref() {
+        // Deserializing a large k generates nothing
+        let start = Instant::now();
+        let reference: IPAParamsRef = serde_json::from_str(r#"{"k":24}"#).unwrap();
+        assert!(start.elapsed().as_secs() < 1);
+        assert_eq!(reference, IPAParamsRef::new(24));
+        assert_eq!(reference.max_rows(), 1 << 24);
+
+        // Same format as IPAParams
+        let params = IPAParamsRef::new(4).materialize();
+        assert_eq!(params.k(), 4);
+        assert_eq!(params.max_rows(), 16);
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(
+            serde_json::from_str::<IPAParamsRef>(&json).unwrap(),
+            IPAParamsRef::from(&params)
+        );
+    }
+
     #[test]
     fn test_ipa_params_max_rows() {
         let test_cases = vec![(10, 1024), (15, 32768), (16, 65536), (17, 131072)];
@@ -520,7 +868,7 @@ mod tests {
         let params = IPAParams::new(10);
         let values = vec![Field::from(1u64), Field::from(2u64), Field::from(3u64)];
 
-        let commitment = VectorCommitment::commit(values.clone(), &params);
+        let commitment = VectorCommitment::commit(values.clone(), &params).unwrap();
 
         assert_eq!(commitment.len(), 3);
         assert!(!commitment.is_empty());
@@ -534,9 +882,9 @@ mod tests {
         let values2 = vec![Field::from(1u64), Field::from(2u64)];
         let values3 = vec![Field::from(1u64), Field::from(3u64)];
 
-        let commitment1 = VectorCommitment::commit(values1, &params);
-        let commitment2 = VectorCommitment::commit(values2, &params);
-        let commitment3 = VectorCommitment::commit(values3, &params);
+        let commitment1 = VectorCommitment::commit_unblinded(values1, &params).unwrap();
+        let commitment2 = VectorCommitment::commit_unblinded(values2, &params).unwrap();
+        let commitment3 = VectorCommitment::commit_unblinded(values3, &params).unwrap();
 
         // Same values should produce same commitment
         assert_eq!(commitment1.commitment, commitment2.commitment);
@@ -546,12 +894,163 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "exceeds maximum rows")]
+    fn test_vector_commitment_blinds() {
+        let params = IPAParams::new(10);
+        let values = vec![Field::from(1u64), Field::from(2u64)];
+        let blind = Blind(Field::from(7u64));
+
+        let commit = |blind| VectorCommitment::commit_with_blind(values.clone(), blind, &params);
+
+        // Equal values with equal blinds match
+        assert_eq!(
+            commit(blind).unwrap().commitment,
+            commit(blind).unwrap().commitment
+        );
+
+        // Different blinds hide equal values
+        assert_ne!(
+            commit(blind).unwrap().commitment,
+            commit(Blind(Field::from(8u64))).unwrap().commitment
+        );
+        assert_ne!(
+            VectorCommitment::commit(values.clone(), &params)
+                .unwrap()
+                .commitment,
+            VectorCommitment::commit(values.clone(), &params)
+                .unwrap()
+                .commitment
+        );
+    }
+
+    #[test]
+    fn test_commitment_point_compressed() {
+        let params = IPAParams::new(10);
+        let values = vec![Field::from(1u64), Field::from(2u64)];
+        let commitment = VectorCommitment::commit(values, &params).unwrap();
+
+        let compressed = commitment.compressed_commitment().unwrap();
+        assert_eq!(compressed.len(), 32);
+        assert_eq!(
+            VectorCommitment::point_from_bytes(&compressed),
+            commitment.commitment_point()
+        );
+        assert!(VectorCommitment::point_from_bytes(&compressed[..31]).is_none());
+    }
+
+    #[test]
+    fn test_vector_commitment_hiding() {
+        let params = IPAParams::new(10);
+        let values = vec![Field::from(123456u64), Field::from(654321u64)];
+
+        let (commitment, opening) =
+            VectorCommitment::commit_hiding(values.clone(), &params).unwrap();
+        assert!(!commitment.has_values());
+        assert_eq!(commitment.len(), 2);
+
+        // Neither the values nor the blind are serialized
+        let json = serde_json::to_string(&commitment).unwrap();
+        for value in values.iter().chain([&opening.blind.0]) {
+            assert!(!json.contains(&hex::encode(value.to_bytes())));
+        }
+        let restored: VectorCommitment = serde_json::from_str(&json).unwrap();
+        assert!(!restored.has_values());
+
+        // Verification requires the opening
+        assert!(!restored.verify(&params));
+        assert!(restored.verify_disclosure(&opening, &params));
+
+        let wrong = CommitmentOpening {
+            values: vec![Field::from(123456u64), Field::from(1u64)],
+            blind: opening.blind,
+        };
+        assert!(!restored.verify_disclosure(&wrong, &params));
+    }
+
+    #[test]
+    fn test_vector_commitment_append() {
+        let params = IPAParams::new(4);
+        let blind = Blind(Field::from(7u64));
+
+        let mut commitment = VectorCommitment::commit_with_blind(vec![], blind, &params).unwrap();
+        for i in 1..=3u64 {
+            commitment.append(Field::from(i), &params).unwrap();
+        }
+
+        // Incremental commitment matches committing all values at once
+        let direct = VectorCommitment::commit_with_blind(
+            vec![Field::from(1u64), Field::from(2u64), Field::from(3u64)],
+            blind,
+            &params,
+        )
+        .unwrap();
+        assert_eq!(commitment.commitment, direct.commitment);
+        assert!(commitment.verify(&params));
+    }
+
+    #[test]
+    fn test_vector_commitment_small_domain() {
+        let params = IPAParams::new(14);
+        let blind = Blind(Field::from(7u64));
+        let values = vec![Field::from(1u64), Field::from(2u64), Field::from(3u64)];
+
+        let small = VectorCommitment::commit_with_blind(values.clone(), blind, &params).unwrap();
+
+        assert_eq!(small.domain_size, 4);
+        assert!(small.verify(&params));
+
+        // The downsized parameters are cached for the next commitment
+        assert!(Arc::ptr_eq(
+            &params.domain_params(2),
+            &params.domain_params(2)
+        ));
+        let again = VectorCommitment::commit_with_blind(values.clone(), blind, &params).unwrap();
+        assert_eq!(again.commitment, small.commitment);
+
+        // Committing over the full 2^k domain gives the same point
+        let mut padded = values;
+        padded.resize(params.max_rows(), Field::zero());
+        let full = VectorCommitment::commit_with_blind(padded, blind, &params).unwrap();
+
+        assert_eq!(full.domain_size, params.max_rows());
+        assert_eq!(small.commitment, full.commitment);
+    }
+
+    #[test]
+    fn test_vector_commitment_legacy_domain() {
+        let params = IPAParams::new(6);
+        let mut commitment = VectorCommitment::commit(vec![Field::from(9u64)], &params).unwrap();
+
+        // Commitments without a stored domain were made over 2^k
+        commitment.domain_size = 0;
+        assert!(commitment.verify(&params));
+
+        commitment.domain_size = 3;
+        assert!(!commitment.verify(&params));
+    }
+
+    #[test]
     fn test_vector_commitment_overflow() {
         let params = IPAParams::new(2); // max 4 rows
         let values = vec![Field::from(1u64); 5]; // 5 values, exceeds max
 
-        let _commitment = VectorCommitment::commit(values, &params);
+        assert_eq!(
+            VectorCommitment::commit(values.clone(), &params).unwrap_err(),
+            CommitmentError::TooLarge { rows: 5, max: 4 }
+        );
+        assert!(VectorCommitment::commit_hiding(values, &params).is_err());
+
+        // Appending past the maximum fails the same way
+        let mut full = VectorCommitment::commit_with_blind(
+            vec![Field::from(1u64); 4],
+            Blind(Field::from(7u64)),
+            &params,
+        )
+        .unwrap();
+        let err = full.append(Field::from(1u64), &params).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<CommitmentError>(),
+            Some(&CommitmentError::TooLarge { rows: 5, max: 4 })
+        );
     }
 
     #[test]
@@ -559,7 +1058,7 @@ mod tests {
         let params = IPAParams::new(10);
         let values = vec![];
 
-        let commitment = VectorCommitment::commit(values, &params);
+        let commitment = VectorCommitment::commit(values, &params).unwrap();
         assert!(commitment.is_empty());
         assert_eq!(commitment.len(), 0);
         assert!(commitment.verify(&params));
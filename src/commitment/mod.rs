@@ -6,6 +6,9 @@
 //! The commitment module consists of:
 //! - `ipa`: IPA protocol implementation for vector commitments
 //! - `database`: Database-level commitment operations
+//! - `opening`: Openings of selected cells of committed columns
+//! - `scheme`: Commitment backend trait and its IPA implementation
+//! - `error`: Error type for commitment creation
 //!
 //! # Overview
 //!
@@ -42,7 +45,7 @@
 //!
 //! // Create database commitment
 //! let tables = vec![/* your tables */];
-//! let commitment = DatabaseCommitment::commit_database(&tables, &params);
+//! let commitment = DatabaseCommitment::commit_database(&tables, &params)?;
 //!
 //! // Verify commitment
 //! assert!(commitment.verify(&params));
@@ -76,11 +79,17 @@
 //! as long as the circuit size doesn't exceed the maximum.
 
 pub mod database;
+pub mod error;
 pub mod ipa;
+pub mod opening;
+pub mod scheme;
 
 // Re-export main types for convenience
 pub use database::{ColumnCommitment, DatabaseCommitment, TableCommitment};
-pub use ipa::{IPAParams, VectorCommitment};
+pub use error::CommitmentError;
+pub use ipa::{CommitmentOpening, IPAParams, IPAParamsRef, VectorCommitment, VerifierParams};
+pub use opening::{CellOpening, CommitmentBlinds};
+pub use scheme::{CommitmentScheme, IPAScheme};
 
 #[cfg(test)]
 mod tests {
@@ -106,7 +115,7 @@ mod tests {
         let params = IPAParams::new(10); // 2^10 = 1024 max rows
 
         // Create database commitment
-        let commitment = DatabaseCommitment::commit_database(&[table], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
 
         // Verify commitment
         assert!(commitment.verify(&params));
@@ -124,7 +133,7 @@ mod tests {
         };
 
         let params = IPAParams::new(10);
-        let commitment = DatabaseCommitment::commit_database(&[table], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
 
         // Get table commitment
         let table_commitment = commitment.get_table_commitment("users");
@@ -150,7 +159,7 @@ mod tests {
         };
 
         let params = IPAParams::new(10);
-        let commitment = DatabaseCommitment::commit_database(&[table1, table2], &params);
+        let commitment = DatabaseCommitment::commit_database(&[table1, table2], &params).unwrap();
 
         assert_eq!(commitment.table_commitments.len(), 2);
         assert!(commitment.get_table_commitment("table1").is_some());
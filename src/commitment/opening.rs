@@ -0,0 +1,516 @@
+//! Cell openings of column commitments
+//!
+//! This module proves the values of selected cells of a committed column
+//! without revealing the other cells.
+//!
+//! # Method
+//!
+//! A column commitment is `C = Σ v_i·G_i + r·W` for values `v`, blind `r`
+//! and generators `G_i`, `W`. Opening the rows `S` reveals `v_i` for
+//! `i ∈ S` and proves knowledge of the remaining values `a` and the blind,
+//! i.e. of an opening of `C - Σ_{i∈S} v_i·G_i` over the generators `H`
+//! outside `S` (a Schnorr proof of representation, made non-interactive
+//! with Fiat-Shamir):
+//!
+//! 1. Commit to random masks `k` and `k_r`: `T = <k, H> + k_r·W`
+//! 2. Challenge `c = H(C, S, v_S, T)`
+//! 3. Responses `s = k + c·a` and `s_r = k_r + c·r`, so that
+//!    `<s, H> = T + c·(C - Σ_{i∈S} v_i·G_i) - s_r·W`
+//!
+//! Rather than sending `s` (one scalar per row), the prover shows it knows
+//! `s` by halving it log2(|H|) times, as in the Bulletproofs inner product
+//! argument: each round sends `L = <s_lo, H_hi>` and `R = <s_hi, H_lo>`,
+//! and with a challenge `u` folds `s' = u·s_lo + u⁻¹·s_hi` and
+//! `H' = u⁻¹·H_lo + u·H_hi`, which preserves `<s', H'> = P + u²·L + u⁻²·R`.
+//! The final scalar is checked against the folded generator. `s` is masked
+//! by `k`, so neither it nor the rounds reveal the unopened values, and the
+//! commitment's binding ensures the opened values are the committed ones.
+
+use super::database::DatabaseCommitment;
+use super::ipa::{IPAParams, VectorCommitment, VerifierParams};
+use super::scheme::{CommitmentScheme, IPAScheme};
+use ff::{Field as _, FromUniformBytes};
+use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine, G1};
+use halo2_proofs::halo2curves::group::{Curve, UncompressedEncoding};
+use halo2_proofs::poly::commitment::Blind;
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Blinding factors of a database commitment
+///
+/// Kept by the prover (never published) to open cells of the committed
+/// columns later. Created by `DatabaseCommitment::commit_database_with_blinds`.
+#[derive(Debug, Clone, Default)]
+pub struct CommitmentBlinds {
+    /// Blind per (table name, column name)
+    blinds: HashMap<(String, String), Blind<Field>>,
+}
+
+impl CommitmentBlinds {
+    /// Record the blind of a column commitment
+    pub(crate) fn insert(&mut self, table_name: &str, column_name: &str, blind: Blind<Field>) {
+        self.blinds
+            .insert((table_name.to_string(), column_name.to_string()), blind);
+    }
+
+    /// Get the blind of a column commitment
+    ///
+    /// # Returns
+    /// `Some(Blind)` if the column was committed with rows, `None` otherwise
+    pub fn get(&self, table_name: &str, column_name: &str) -> Option<Blind<Field>> {
+        self.blinds
+            .get(&(table_name.to_string(), column_name.to_string()))
+            .copied()
+    }
+}
+
+/// Opening of selected cells of a committed column
+///
+/// Its size is logarithmic in the number of rows: the opened values plus
+/// one (L, R) pair per folding round.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellOpening {
+    /// Table of the opened column
+    pub table_name: String,
+
+    /// Opened column
+    pub column_name: String,
+
+    /// Opened row indices, strictly increasing
+    pub rows: Vec<usize>,
+
+    /// Committed values of the opened rows
+    pub values: Vec<Field>,
+
+    /// Commitment to the random masks (uncompressed point)
+    pub mask_commitment: Vec<u8>,
+
+    /// (L, R) points of each folding round (uncompressed), one round per
+    /// halving of the unopened rows (rounded up to a power of two)
+    pub rounds: Vec<(Vec<u8>, Vec<u8>)>,
+
+    /// Folded response for the unopened values
+    pub response: Field,
+
+    /// Response for the blind
+    pub blind_response: Field,
+}
+
+impl CellOpening {
+    /// Open selected cells of a committed column
+    ///
+    /// # Arguments
+    /// * `table_name` - Table of the column
+    /// * `column_name` - Column name
+    /// * `column` - All committed values of the column
+    /// * `blind` - Blind the column was committed with
+    /// * `rows` - Row indices to open
+    /// * `params` - IPA parameters used for the commitment
+    ///
+    /// # Returns
+    /// `Ok(CellOpening)` for the given rows, `Err` if a row is out of range
+    /// or the column doesn't fit the parameters
+    pub fn create(
+        table_name: &str,
+        column_name: &str,
+        column: &[Field],
+        blind: Blind<Field>,
+        rows: &[usize],
+        params: &IPAParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        VectorCommitment::check_size(column.len(), params)?;
+        let mut rows = rows.to_vec();
+        rows.sort_unstable();
+        rows.dedup();
+        if let Some(&row) = rows.iter().find(|&&row| row >= column.len()) {
+            return Err(format!("Row {} out of range for column {}", row, column_name).into());
+        }
+
+        let domain_size = VectorCommitment::domain_size_for(column.len()).min(params.max_rows());
+        let unopened = Self::unopened_rows(column.len(), &rows);
+        let commit = |coeffs: &[Field], blind: Field| {
+            IPAScheme::commit_in_domain(
+                &Self::scatter(coeffs, &unopened, column.len()),
+                Blind(blind),
+                domain_size,
+                params,
+            )
+            .to_affine()
+        };
+
+        // Unopened values, padded to a power of two (padding rows have no
+        // generator, so they add nothing)
+        let len = Self::folded_len(unopened.len());
+        let mut unopened_values: Vec<Field> = unopened.iter().map(|&i| column[i]).collect();
+        unopened_values.resize(len, Field::zero());
+
+        // Masks for the unopened rows and the blind
+        let masks: Vec<Field> = (0..len).map(|_| Field::random(OsRng)).collect();
+        let blind_mask = Field::random(OsRng);
+        let mask_commitment = commit(&masks, blind_mask);
+
+        let commitment =
+            IPAScheme::commit_in_domain(column, blind, domain_size, params).to_affine();
+        let values: Vec<Field> = rows.iter().map(|&i| column[i]).collect();
+        let challenge = Self::challenge(
+            &commitment,
+            table_name,
+            column_name,
+            &rows,
+            &values,
+            &mask_commitment,
+        );
+
+        let mut responses: Vec<Field> = masks
+            .iter()
+            .zip(&unopened_values)
+            .map(|(mask, value)| *mask + challenge * value)
+            .collect();
+
+        // Fold the responses, tracking each generator's weight in the
+        // folded generators
+        let mut weights = vec![Field::one(); len];
+        let mut transcript = challenge;
+        let mut rounds = Vec::new();
+        while responses.len() > 1 {
+            let half = responses.len() / 2;
+            let (mut l, mut r) = (vec![Field::zero(); len], vec![Field::zero(); len]);
+            for (i, weight) in weights.iter().enumerate() {
+                let t = i % responses.len();
+                if t < half {
+                    r[i] = responses[t + half] * weight;
+                } else {
+                    l[i] = responses[t - half] * weight;
+                }
+            }
+            let (l, r) = (commit(&l, Field::zero()), commit(&r, Field::zero()));
+
+            let u = Self::round_challenge(&transcript, &l, &r);
+            let u_inv = Option::<Field>::from(u.invert()).ok_or("Zero folding challenge")?;
+            Self::fold_weights(&mut weights, responses.len(), u, u_inv);
+            responses = (0..half)
+                .map(|t| u * responses[t] + u_inv * responses[t + half])
+                .collect();
+
+            transcript = u;
+            rounds.push((
+                l.to_uncompressed().as_ref().to_vec(),
+                r.to_uncompressed().as_ref().to_vec(),
+            ));
+        }
+
+        Ok(Self {
+            table_name: table_name.to_string(),
+            column_name: column_name.to_string(),
+            rows,
+            values,
+            mask_commitment: mask_commitment.to_uncompressed().as_ref().to_vec(),
+            rounds,
+            response: responses[0],
+            blind_response: blind_mask + challenge * blind.0,
+        })
+    }
+
+    /// Verify the opening against a database commitment
+    ///
+    /// # Arguments
+    /// * `commitment` - Database commitment containing the column
+    /// * `params` - Verifier parameters of the IPA parameters used for the
+    ///   commitment
+    ///
+    /// # Returns
+    /// `true` if the committed column holds `values` at `rows`, `false` otherwise
+    pub fn verify(&self, commitment: &DatabaseCommitment, params: &VerifierParams) -> bool {
+        let Some(column) = commitment
+            .get_table_commitment(&self.table_name)
+            .and_then(|table| table.get_column_commitment(&self.column_name))
+        else {
+            return false;
+        };
+        let (Some(point), Some(mask_point)) = (
+            VectorCommitment::point_from_bytes(&column.commitment),
+            VectorCommitment::point_from_bytes(&self.mask_commitment),
+        ) else {
+            return false;
+        };
+
+        let num_rows = column.num_rows;
+        if num_rows > params.max_rows()
+            || self.values.len() != self.rows.len()
+            || !self.rows.windows(2).all(|w| w[0] < w[1])
+            || self.rows.last().is_some_and(|&row| row >= num_rows)
+        {
+            return false;
+        }
+        let unopened = Self::unopened_rows(num_rows, &self.rows);
+        let len = Self::folded_len(unopened.len());
+        if self.rounds.len() != len.trailing_zeros() as usize {
+            return false;
+        }
+
+        let challenge = Self::challenge(
+            &point,
+            &self.table_name,
+            &self.column_name,
+            &self.rows,
+            &self.values,
+            &mask_point,
+        );
+
+        // T + c·(C - commitment to the opened values alone)
+        let mut opened_values = vec![Field::zero(); num_rows];
+        for (&row, &value) in self.rows.iter().zip(&self.values) {
+            opened_values[row] = value;
+        }
+        let opened = params.commit(&opened_values, Blind(Field::zero()));
+        let mut folded = G1::from(mask_point) + (G1::from(point) - opened) * challenge;
+
+        // Replay the folding rounds
+        let mut weights = vec![Field::one(); len];
+        let mut transcript = challenge;
+        for (round, (l, r)) in self.rounds.iter().enumerate() {
+            let (Some(l), Some(r)) = (
+                VectorCommitment::point_from_bytes(l),
+                VectorCommitment::point_from_bytes(r),
+            ) else {
+                return false;
+            };
+            let u = Self::round_challenge(&transcript, &l, &r);
+            let Some(u_inv) = Option::<Field>::from(u.invert()) else {
+                return false;
+            };
+            folded += G1::from(l) * u.square() + G1::from(r) * u_inv.square();
+            Self::fold_weights(&mut weights, len >> round, u, u_inv);
+            transcript = u;
+        }
+
+        // <s, H> + s_r·W with the folded response and generator
+        let responses: Vec<Field> = weights.iter().map(|w| *w * self.response).collect();
+        let lhs = params.commit(
+            &Self::scatter(&responses, &unopened, num_rows),
+            Blind(self.blind_response),
+        );
+
+        lhs.to_affine() == folded.to_affine()
+    }
+
+    /// Rows of a column that are not opened, in order
+    fn unopened_rows(num_rows: usize, rows: &[usize]) -> Vec<usize> {
+        (0..num_rows)
+            .filter(|i| rows.binary_search(i).is_err())
+            .collect()
+    }
+
+    /// Length of the folded vector for a number of unopened rows
+    fn folded_len(unopened: usize) -> usize {
+        unopened.max(1).next_power_of_two()
+    }
+
+    /// Place coefficients of the unopened rows at their row indices
+    fn scatter(coeffs: &[Field], unopened: &[usize], num_rows: usize) -> Vec<Field> {
+        let mut column = vec![Field::zero(); num_rows];
+        for (&row, &coeff) in unopened.iter().zip(coeffs) {
+            column[row] = coeff;
+        }
+        column
+    }
+
+    /// Fold the generator weights of a round halving `len` generators
+    ///
+    /// Generator `i` belongs to folded generator `i mod len`, which is
+    /// scaled by `u⁻¹` in the low half and by `u` in the high half.
+    fn fold_weights(weights: &mut [Field], len: usize, u: Field, u_inv: Field) {
+        for (i, weight) in weights.iter_mut().enumerate() {
+            *weight *= if i % len < len / 2 { u_inv } else { u };
+        }
+    }
+
+    /// Fiat-Shamir challenge binding the commitment, opened cells and masks
+    fn challenge(
+        commitment: &G1Affine,
+        table_name: &str,
+        column_name: &str,
+        rows: &[usize],
+        values: &[Field],
+        mask_commitment: &G1Affine,
+    ) -> Field {
+        use sha2::{Digest, Sha512};
+
+        let mut hasher = Sha512::new();
+        hasher.update(commitment.to_uncompressed().as_ref());
+        for name in [table_name, column_name] {
+            hasher.update((name.len() as u64).to_le_bytes());
+            hasher.update(name.as_bytes());
+        }
+        hasher.update((rows.len() as u64).to_le_bytes());
+        for (&row, value) in rows.iter().zip(values) {
+            hasher.update((row as u64).to_le_bytes());
+            hasher.update(value.to_bytes());
+        }
+        hasher.update(mask_commitment.to_uncompressed().as_ref());
+
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&hasher.finalize());
+        Field::from_uniform_bytes(&wide)
+    }
+
+    /// Fiat-Shamir challenge of a folding round, chained to the previous one
+    fn round_challenge(previous: &Field, l: &G1Affine, r: &G1Affine) -> Field {
+        use sha2::{Digest, Sha512};
+
+        let mut hasher = Sha512::new();
+        hasher.update(previous.to_bytes());
+        hasher.update(l.to_uncompressed().as_ref());
+        hasher.update(r.to_uncompressed().as_ref());
+
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&hasher.finalize());
+        Field::from_uniform_bytes(&wide)
+    }
+}
+
+/// Hex encoding of a `CellOpening`, for JSON proofs
+#[derive(Serialize, Deserialize)]
+struct EncodedOpening {
+    table_name: String,
+    column_name: String,
+    rows: Vec<usize>,
+    values: Vec<String>,
+    mask_commitment: String,
+    rounds: Vec<(String, String)>,
+    response: String,
+    blind_response: String,
+}
+
+impl Serialize for CellOpening {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let field = |f: &Field| hex::encode(f.to_bytes());
+        EncodedOpening {
+            table_name: self.table_name.clone(),
+            column_name: self.column_name.clone(),
+            rows: self.rows.clone(),
+            values: self.values.iter().map(field).collect(),
+            mask_commitment: hex::encode(&self.mask_commitment),
+            rounds: self
+                .rounds
+                .iter()
+                .map(|(l, r)| (hex::encode(l), hex::encode(r)))
+                .collect(),
+            response: field(&self.response),
+            blind_response: field(&self.blind_response),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CellOpening {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        let bytes = |s: &str| hex::decode(s).map_err(D::Error::custom);
+        let field = |s: &str| {
+            let bytes: [u8; 32] = hex::decode(s)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| D::Error::custom("field element is not 32 hex bytes"))?;
+            Option::from(Field::from_bytes(&bytes))
+                .ok_or_else(|| D::Error::custom("not a canonical field element"))
+        };
+
+        let encoded = EncodedOpening::deserialize(deserializer)?;
+        Ok(Self {
+            table_name: encoded.table_name,
+            column_name: encoded.column_name,
+            rows: encoded.rows,
+            values: encoded
+                .values
+                .iter()
+                .map(|value| field(value))
+                .collect::<Result<_, _>>()?,
+            mask_commitment: bytes(&encoded.mask_commitment)?,
+            rounds: encoded
+                .rounds
+                .iter()
+                .map(|(l, r)| Ok((bytes(l)?, bytes(r)?)))
+                .collect::<Result<_, D::Error>>()?,
+            response: field(&encoded.response)?,
+            blind_response: field(&encoded.blind_response)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, DataType, Row, Table, Value};
+
+    #[test]
+    fn test_cell_opening() {
+        let params = IPAParams::new(6);
+        let verifier_params = params.verifier_params();
+        let table = Table {
+            name: "lineitem".to_string(),
+            columns: vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+            rows: [5, 12, 30, 7, 9]
+                .into_iter()
+                .map(|q| Row::new(vec![Value::Integer(q)]))
+                .collect(),
+        };
+        let (commitment, blinds) =
+            DatabaseCommitment::commit_database_with_blinds(&[table.clone()], &params).unwrap();
+        let blind = blinds.get("lineitem", "l_quantity").unwrap();
+        let column: Vec<Field> = table.rows.iter().map(|r| r.to_fields()[0]).collect();
+
+        let opening =
+            CellOpening::create("lineitem", "l_quantity", &column, blind, &[2, 1], &params)
+                .unwrap();
+        assert_eq!(opening.rows, vec![1, 2]);
+        assert!(opening.verify(&commitment, &verifier_params));
+
+        // Three unopened rows fold in two rounds
+        assert_eq!(opening.rounds.len(), 2);
+
+        // A fabricated cell value fails verification
+        let mut forged = opening.clone();
+        forged.values[0] = Value::Integer(13).to_field();
+        assert!(!forged.verify(&commitment, &verifier_params));
+
+        // ...and so does a tampered response or a dropped round
+        let mut forged = opening.clone();
+        forged.response += Field::one();
+        assert!(!forged.verify(&commitment, &verifier_params));
+        let mut forged = opening.clone();
+        forged.rounds.pop();
+        assert!(!forged.verify(&commitment, &verifier_params));
+
+        // Opening every row leaves nothing to fold
+        let all = CellOpening::create(
+            "lineitem",
+            "l_quantity",
+            &column,
+            blind,
+            &[0, 1, 2, 3, 4],
+            &params,
+        )
+        .unwrap();
+        assert!(all.rounds.is_empty());
+        assert!(all.verify(&commitment, &verifier_params));
+
+        // Openings survive a JSON round trip
+        let json = serde_json::to_string(&opening).unwrap();
+        let restored: CellOpening = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, opening);
+
+        // Rows outside the column cannot be opened
+        assert!(
+            CellOpening::create("lineitem", "l_quantity", &column, blind, &[5], &params).is_err()
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! Pluggable commitment backends
+//!
+//! This module provides the `CommitmentScheme` trait, which abstracts the
+//! polynomial commitment backend behind parameter generation, committing,
+//! opening and verifying. Vector commitments (`VectorCommitment<S>`) and
+//! proofs (`Prover<S>`) are generic over it. `IPAScheme` is the only backend
+//! for now; a KZG backend (constant-size proofs, trusted setup) would be
+//! another impl.
+//!
+//! # Example
+//!
+//! ```rust
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//! use nzengi_db::commitment::{CommitmentScheme, IPAScheme};
+//!
+//! fn commit_and_check<S: CommitmentScheme>(values: Vec<Field>, k: u32) -> bool {
+//!     let params = S::setup(k);
+//!     let commitment = S::commit(values, &params).unwrap();
+//!     S::open(&commitment).is_some_and(|opening| S::verify(&commitment, &opening, &params))
+//! }
+//!
+//! let values = vec![Field::from(1u64), Field::from(2u64), Field::from(3u64)];
+//! assert!(commit_and_check::<IPAScheme>(values, 4));
+//! ```
+
+use super::error::CommitmentError;
+use super::ipa::{
+    domain_size_for, CommitmentOpening, IPAParams, VectorCommitment, EMPTY_COMMITMENT,
+};
+use ff::Field as _;
+use halo2_middleware::zal::impls::PlonkEngineConfig;
+use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine, G1};
+use halo2_proofs::halo2curves::group::{Curve, UncompressedEncoding};
+use halo2_proofs::poly::commitment::{
+    Blind, CommitmentScheme as Halo2Scheme, Params, ParamsProver, Prover as Halo2Prover,
+};
+use halo2_proofs::poly::ipa::commitment::{IPACommitmentScheme, ParamsIPA};
+use halo2_proofs::poly::ipa::multiopen::ProverIPA;
+use halo2_proofs::poly::EvaluationDomain;
+use rand_core::OsRng;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Commitment backend for vectors of field elements
+///
+/// A backend supplies its parameters, the halo2 commitment scheme proofs are
+/// created with, and the commitment to a vector padded to a power-of-two
+/// domain. Committing, opening and verifying vectors are built on the latter.
+pub trait CommitmentScheme: Sized + Clone + fmt::Debug {
+    /// Public parameters of the scheme
+    type Params: Clone + fmt::Debug;
+
+    /// halo2 commitment scheme used for proofs
+    type Halo2: Halo2Scheme<Scalar = Field, Curve = G1Affine>;
+
+    /// halo2 multiopen prover of the scheme
+    type Halo2Prover<'params>: Halo2Prover<'params, Self::Halo2>;
+
+    /// Generate parameters for vectors of up to 2^k values
+    ///
+    /// # Arguments
+    /// * `k` - Log2 of the maximum number of values
+    ///
+    /// # Returns
+    /// The scheme parameters
+    fn setup(k: u32) -> Self::Params;
+
+    /// Get log2 of the maximum number of values of the parameters
+    fn k(params: &Self::Params) -> u32;
+
+    /// Get the maximum number of values of the parameters
+    fn max_rows(params: &Self::Params) -> usize {
+        1 << Self::k(params)
+    }
+
+    /// Get the halo2 prover parameters, for key generation and proving
+    fn prover_params(params: &Self::Params) -> &<Self::Halo2 as Halo2Scheme>::ParamsProver;
+
+    /// Commit to values padded to a power-of-two domain
+    ///
+    /// # Arguments
+    /// * `values` - Values to commit to (at most `domain_size`)
+    /// * `blind` - Blinding factor
+    /// * `domain_size` - Power-of-two domain size, at most `max_rows(params)`
+    /// * `params` - Scheme parameters
+    ///
+    /// # Returns
+    /// The commitment point
+    fn commit_in_domain(
+        values: &[Field],
+        blind: Blind<Field>,
+        domain_size: usize,
+        params: &Self::Params,
+    ) -> G1;
+
+    /// Check that a number of rows fits the parameters
+    ///
+    /// # Returns
+    /// `Ok(())` if `rows <= max_rows(params)`, `Err(CommitmentError::TooLarge)` otherwise
+    fn check_size(rows: usize, params: &Self::Params) -> Result<(), CommitmentError> {
+        let max = Self::max_rows(params);
+        if rows > max {
+            return Err(CommitmentError::TooLarge { rows, max });
+        }
+        Ok(())
+    }
+
+    /// Commit to a vector of field elements with a random blind
+    ///
+    /// An empty vector gets the empty commitment, without a blind.
+    ///
+    /// # Arguments
+    /// * `values` - Values to commit to
+    /// * `params` - Scheme parameters
+    ///
+    /// # Returns
+    /// `Ok(VectorCommitment)`, or `Err(CommitmentError::TooLarge)` if the
+    /// vector exceeds the parameters
+    fn commit(
+        values: Vec<Field>,
+        params: &Self::Params,
+    ) -> Result<VectorCommitment<Self>, CommitmentError> {
+        Self::check_size(values.len(), params)?;
+
+        if values.is_empty() {
+            return Ok(VectorCommitment {
+                commitment: EMPTY_COMMITMENT.to_vec(),
+                values,
+                blind: None,
+                domain_size: 0,
+                hidden_len: None,
+                scheme: PhantomData,
+            });
+        }
+
+        Self::commit_with_blind(values, Blind(Field::random(OsRng)), params)
+    }
+
+    /// Commit to a vector of field elements with a given blind
+    ///
+    /// The values are committed over the smallest domain that fits them.
+    ///
+    /// # Arguments
+    /// * `values` - Values to commit to
+    /// * `blind` - Blinding factor
+    /// * `params` - Scheme parameters
+    ///
+    /// # Returns
+    /// `Ok(VectorCommitment)`, or `Err(CommitmentError::TooLarge)` if the
+    /// vector exceeds the parameters
+    fn commit_with_blind(
+        values: Vec<Field>,
+        blind: Blind<Field>,
+        params: &Self::Params,
+    ) -> Result<VectorCommitment<Self>, CommitmentError> {
+        Self::check_size(values.len(), params)?;
+
+        let domain_size = domain_size_for(values.len()).min(Self::max_rows(params));
+        let point: G1Affine =
+            Self::commit_in_domain(&values, blind, domain_size, params).to_affine();
+
+        Ok(VectorCommitment {
+            commitment: point.to_uncompressed().as_ref().to_vec(),
+            values,
+            blind: Some(blind),
+            domain_size,
+            hidden_len: None,
+            scheme: PhantomData,
+        })
+    }
+
+    /// Commit to a vector without retaining the values or the blind
+    ///
+    /// # Arguments
+    /// * `values` - Values to commit to
+    /// * `params` - Scheme parameters
+    ///
+    /// # Returns
+    /// `Ok((VectorCommitment, CommitmentOpening))` with the hiding commitment
+    /// and the opening for it, `Err(CommitmentError::TooLarge)` if the vector
+    /// exceeds the parameters
+    fn commit_hiding(
+        values: Vec<Field>,
+        params: &Self::Params,
+    ) -> Result<(VectorCommitment<Self>, CommitmentOpening), CommitmentError> {
+        let blind = Blind(Field::random(OsRng));
+        let mut commitment = Self::commit_with_blind(values, blind, params)?;
+
+        let opening = CommitmentOpening {
+            values: std::mem::take(&mut commitment.values),
+            blind,
+        };
+        commitment.blind = None;
+        commitment.hidden_len = Some(opening.values.len());
+
+        Ok((commitment, opening))
+    }
+
+    /// Open a commitment that retains its values
+    ///
+    /// # Arguments
+    /// * `commitment` - Commitment to open
+    ///
+    /// # Returns
+    /// `Some(Opening)`, or `None` if the commitment does not retain what is
+    /// needed to open it
+    fn open(commitment: &VectorCommitment<Self>) -> Option<CommitmentOpening> {
+        if !commitment.has_values() {
+            return None;
+        }
+        Some(CommitmentOpening {
+            values: commitment.values.clone(),
+            blind: commitment.blind?,
+        })
+    }
+
+    /// Verify that a commitment opens to the given opening
+    ///
+    /// The opening discloses every committed value and the blind (see
+    /// `VectorCommitment::verify_disclosure`).
+    ///
+    /// # Arguments
+    /// * `commitment` - Commitment to check
+    /// * `opening` - Opening to check it against
+    /// * `params` - Scheme parameters used for the commitment
+    ///
+    /// # Returns
+    /// `true` if the commitment opens to `opening`, `false` otherwise
+    fn verify(
+        commitment: &VectorCommitment<Self>,
+        opening: &CommitmentOpening,
+        params: &Self::Params,
+    ) -> bool {
+        commitment.verify_disclosure(opening, params)
+    }
+}
+
+/// IPA commitment backend (no trusted setup)
+///
+/// Commitments are `VectorCommitment`s over `IPAParams`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IPAScheme;
+
+impl CommitmentScheme for IPAScheme {
+    type Params = IPAParams;
+    type Halo2 = IPACommitmentScheme<G1Affine>;
+    type Halo2Prover<'params> = ProverIPA<'params, G1Affine>;
+
+    fn setup(k: u32) -> IPAParams {
+        IPAParams::new(k)
+    }
+
+    fn k(params: &IPAParams) -> u32 {
+        params.k()
+    }
+
+    fn prover_params(params: &IPAParams) -> &ParamsIPA<G1Affine> {
+        &params.params
+    }
+
+    /// Commit to values padded to a power-of-two domain
+    ///
+    /// The generators of downsized parameters are a prefix of the full ones,
+    /// and padding zeros add nothing, so the commitment is the same as over
+    /// the full 2^k domain while the MSM only covers `domain_size` points.
+    /// The downsized parameters are cached in `params`.
+    fn commit_in_domain(
+        values: &[Field],
+        blind: Blind<Field>,
+        domain_size: usize,
+        params: &IPAParams,
+    ) -> G1 {
+        let domain_k = domain_size.trailing_zeros();
+        if domain_k == params.k() {
+            commit_with_params(&params.params, values, blind)
+        } else {
+            commit_with_params(&params.domain_params(domain_k), values, blind)
+        }
+    }
+}
+
+/// Commit to values padded to the full domain of the given parameters
+///
+/// # Arguments
+/// * `params` - halo2 IPA parameters (possibly downsized)
+/// * `values` - Values to commit to (at most `params.n()`)
+/// * `blind` - Blinding factor
+///
+/// # Returns
+/// The commitment point
+pub(crate) fn commit_with_params(
+    params: &ParamsIPA<G1Affine>,
+    values: &[Field],
+    blind: Blind<Field>,
+) -> G1 {
+    let domain_k = params.k();
+
+    // Pad values to domain size
+    let mut coeffs = values.to_vec();
+    coeffs.resize(1 << domain_k, Field::zero());
+
+    // Create evaluation domain for polynomial operations
+    // EvaluationDomain::new takes (k: u32, omega_k: u32) where omega_k is the rotation index
+    // For polynomial commitment, we use k=0 (no rotation)
+    let domain = EvaluationDomain::<Field>::new(domain_k, 0u32);
+
+    // Create polynomial from coefficients using domain's method
+    let poly = domain.coeff_from_vec(coeffs);
+
+    // Build MSM engine required by Halo2 backend API
+    let engine = PlonkEngineConfig::build_default::<G1Affine>();
+
+    // Commit to polynomial using IPA protocol with explicit engine backend
+    params.commit(&engine.msm_backend, &poly, blind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_and_verify<S: CommitmentScheme>(values: Vec<Field>, params: &S::Params) -> bool {
+        let commitment = S::commit(values, params).unwrap();
+        S::open(&commitment).is_some_and(|opening| S::verify(&commitment, &opening, params))
+    }
+
+    #[test]
+    fn test_ipa_scheme_commit_verify() {
+        let params = IPAScheme::setup(10);
+        assert_eq!(params.k(), 10);
+        let values = vec![Field::from(1u64), Field::from(2u64), Field::from(3u64)];
+
+        // The generic flow accepts what the IPA commitment accepts
+        let commitment = IPAScheme::commit(values.clone(), &params).unwrap();
+        assert!(commitment.verify(&params));
+        assert!(commit_and_verify::<IPAScheme>(values.clone(), &params));
+
+        // ...and rejects an opening to other values
+        let mut opening = IPAScheme::open(&commitment).unwrap();
+        opening.values[0] = Field::from(4u64);
+        assert!(!IPAScheme::verify(&commitment, &opening, &params));
+
+        // Hiding commitments keep their opening with the data owner
+        let (hiding, opening) = VectorCommitment::commit_hiding(values.clone(), &params).unwrap();
+        assert!(IPAScheme::open(&hiding).is_none());
+        assert!(IPAScheme::verify(&hiding, &opening, &params));
+
+        // Oversized vectors are rejected the same way
+        let too_many = vec![Field::from(1u64); params.max_rows() + 1];
+        assert_eq!(
+            IPAScheme::commit(too_many.clone(), &params).unwrap_err(),
+            VectorCommitment::commit(too_many, &params).unwrap_err()
+        );
+
+        // The generic commitment is the IPA commitment
+        let blind = Blind(Field::from(5u64));
+        assert_eq!(
+            IPAScheme::commit_with_blind(values.clone(), blind, &params)
+                .unwrap()
+                .commitment,
+            VectorCommitment::commit_with_blind(values, blind, &params)
+                .unwrap()
+                .commitment
+        );
+    }
+}
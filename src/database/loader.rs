@@ -16,24 +16,66 @@
 //!
 //! // Load from JSON
 //! loader.load_json(&mut db, "lineitem.json", "lineitem")?;
+//!
+//! // Export back to CSV
+//! loader.export_csv(db.get_table("lineitem").unwrap(), "lineitem_out.csv")?;
 //! ```
 
 use crate::database::schema::Database;
-use crate::types::{Column, DataType, Row, Table, Value};
+use crate::types::{Column, DataType, ParseError, Row, Table, Value};
 use serde_json;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Number of data rows sampled to infer CSV column types
+const SCHEMA_SAMPLE_ROWS: usize = 1000;
+
+/// CSV column types tried in order; the first one every sampled value parses as wins
+const CSV_TYPE_CANDIDATES: [DataType; 5] = [
+    DataType::Integer,
+    DataType::BigInt,
+    DataType::Decimal,
+    DataType::Date,
+    DataType::Boolean,
+];
+
+/// Seconds per day, for writing dates as `YYYY-MM-DD`
+const SECONDS_PER_DAY: u64 = 86_400;
 
 /// Data loader
 ///
 /// Provides methods for loading data from various formats.
 #[derive(Debug, Clone)]
-pub struct DataLoader;
+pub struct DataLoader {
+    /// Truncate strings longer than their VARCHAR length instead of failing
+    truncate_strings: bool,
+}
 
 impl DataLoader {
     /// Create a new data loader
     pub fn new() -> Self {
-        Self
+        Self {
+            truncate_strings: false,
+        }
+    }
+
+    /// Truncate over-long strings to their VARCHAR length
+    ///
+    /// By default a string longer than its column's VARCHAR length fails
+    /// the load.
+    pub fn with_truncate_strings(mut self, truncate: bool) -> Self {
+        self.truncate_strings = truncate;
+        self
+    }
+
+    /// Parse a field according to the loader's string length policy
+    fn parse_value(&self, s: &str, data_type: &DataType) -> Result<Value, ParseError> {
+        if self.truncate_strings {
+            Value::parse_truncating(s, data_type)
+        } else {
+            Value::parse(s, data_type)
+        }
     }
 
     /// Load data from a CSV file
@@ -65,23 +107,18 @@ impl DataLoader {
             .map(|s| s.trim().to_string())
             .collect();
 
-        // Create columns (assuming all columns are integers for simplicity)
-        let columns: Vec<Column> = headers
-            .iter()
-            .map(|h| Column::new(h.clone(), DataType::Integer))
-            .collect();
-
-        // Create or get table
+        // Create or get table, inferring column types for a new one
         let table = if let Some(existing_table) = database.get_table_mut(table_name) {
             existing_table
         } else {
+            let columns = Self::infer_csv_columns(path)?;
             let new_table = Table::new(table_name.to_string(), columns);
             database.schema.add_table(new_table)?;
             database.get_table_mut(table_name).unwrap()
         };
 
         // Read data rows
-        for line_result in lines {
+        for (line_idx, line_result) in lines.enumerate() {
             let line = line_result.map_err(|e| format!("Failed to read line: {}", e))?;
             let values: Vec<String> = line.split(',').map(|s| s.trim().to_string()).collect();
 
@@ -94,14 +131,15 @@ impl DataLoader {
                 .into());
             }
 
-            let row_values: Vec<Value> = values
+            let row_values = values
                 .iter()
-                .map(|v| {
-                    v.parse::<i32>()
-                        .map(Value::Integer)
-                        .unwrap_or_else(|_| Value::Integer(0))
+                .zip(&table.columns)
+                .map(|(v, column)| {
+                    self.parse_value(v, &column.data_type).map_err(|e| {
+                        format!("Line {}, column {}: {}", line_idx + 2, column.name, e)
+                    })
                 })
-                .collect();
+                .collect::<Result<Vec<Value>, _>>()?;
 
             table.rows.push(Row::new(row_values));
         }
@@ -140,17 +178,9 @@ impl DataLoader {
             return Err("JSON array is empty".into());
         }
 
-        // Extract columns from first object
-        let first_obj = array[0]
-            .as_object()
-            .ok_or("JSON array elements must be objects")?;
-        let headers: Vec<String> = first_obj.keys().cloned().collect();
-
-        // Create columns (assuming all columns are integers for simplicity)
-        let columns: Vec<Column> = headers
-            .iter()
-            .map(|h| Column::new(h.clone(), DataType::Integer))
-            .collect();
+        // Extract columns from first object, inferring their types
+        let columns = Self::json_columns(&array[0])?;
+        let headers: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
 
         // Create or get table
         let table = if let Some(existing_table) = database.get_table_mut(table_name) {
@@ -162,27 +192,24 @@ impl DataLoader {
         };
 
         // Read data rows
-        for obj in array {
+        for (row_idx, obj) in array.iter().enumerate() {
             let obj = obj
                 .as_object()
                 .ok_or("JSON array elements must be objects")?;
 
-            let row_values: Vec<Value> = headers
+            let row_values = headers
                 .iter()
-                .map(|h| {
-                    obj.get(h)
-                        .and_then(|v| {
-                            if let Some(i) = v.as_i64() {
-                                Some(Value::Integer(i as i32))
-                            } else if let Some(s) = v.as_str() {
-                                Some(Value::String(s.to_string()))
-                            } else {
-                                None
-                            }
-                        })
-                        .unwrap_or(Value::Null)
+                .zip(&table.columns)
+                .map(|(h, column)| {
+                    let text = match obj.get(h) {
+                        None | Some(serde_json::Value::Null) => String::new(),
+                        Some(serde_json::Value::String(s)) => s.clone(),
+                        Some(other) => other.to_string(),
+                    };
+                    self.parse_value(&text, &column.data_type)
+                        .map_err(|e| format!("Row {}, column {}: {}", row_idx, h, e))
                 })
-                .collect();
+                .collect::<Result<Vec<Value>, _>>()?;
 
             table.rows.push(Row::new(row_values));
         }
@@ -190,6 +217,215 @@ impl DataLoader {
         Ok(())
     }
 
+    /// Infer the columns of a CSV or JSON file without loading its rows
+    ///
+    /// CSV column types are inferred from the header and the first
+    /// `SCHEMA_SAMPLE_ROWS` rows, as `load_csv` does for a new table; JSON
+    /// column types come from the first object, as in `load_json`. Files
+    /// ending in `.json` are read as JSON, everything else as CSV.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the CSV or JSON file
+    /// * `table_name` - Name of the table the file would be loaded into
+    ///
+    /// # Returns
+    /// `Ok(columns)` with the inferred column definitions, `Err` if the file
+    /// can't be read or parsed
+    pub fn infer_schema(
+        &self,
+        path: &str,
+        table_name: &str,
+    ) -> Result<Vec<Column>, Box<dyn std::error::Error>> {
+        let is_json = Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let columns = if is_json {
+            let contents = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+            let json_data: serde_json::Value = serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+            let first = json_data
+                .as_array()
+                .ok_or("JSON data must be an array")?
+                .first()
+                .ok_or("JSON array is empty")?;
+            Self::json_columns(first)
+        } else {
+            Self::infer_csv_columns(path)
+        };
+
+        columns.map_err(|e| format!("Failed to infer schema of table {}: {}", table_name, e).into())
+    }
+
+    /// Infer CSV columns from the header and a sample of rows
+    fn infer_csv_columns(path: &str) -> Result<Vec<Column>, Box<dyn std::error::Error>> {
+        let file = File::open(path).map_err(|e| format!("Failed to open file {}: {}", path, e))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header_line = lines
+            .next()
+            .ok_or("CSV file is empty")?
+            .map_err(|e| format!("Failed to read header: {}", e))?;
+        let headers: Vec<String> = header_line
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .collect();
+
+        // Sampled values per column
+        let mut samples: Vec<Vec<String>> = vec![vec![]; headers.len()];
+        for line_result in lines.take(SCHEMA_SAMPLE_ROWS) {
+            let line = line_result.map_err(|e| format!("Failed to read line: {}", e))?;
+            let values: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+
+            if values.len() != headers.len() {
+                return Err(format!(
+                    "Row has {} values but header has {} columns",
+                    values.len(),
+                    headers.len()
+                )
+                .into());
+            }
+
+            for (sample, value) in samples.iter_mut().zip(values) {
+                sample.push(value.to_string());
+            }
+        }
+
+        Ok(headers
+            .into_iter()
+            .zip(&samples)
+            .map(|(name, sample)| Column::new(name, Self::infer_csv_type(sample)))
+            .collect())
+    }
+
+    /// Infer a column type from sampled CSV values
+    ///
+    /// Empty values are NULL and don't constrain the type. Values matching
+    /// no candidate type make the column a VARCHAR.
+    fn infer_csv_type(sample: &[String]) -> DataType {
+        CSV_TYPE_CANDIDATES
+            .into_iter()
+            .find(|data_type| {
+                sample
+                    .iter()
+                    .all(|value| Value::parse(value, data_type).is_ok())
+            })
+            .unwrap_or(DataType::Varchar(usize::MAX))
+    }
+
+    /// Infer JSON columns from the first object of the array
+    fn json_columns(first: &serde_json::Value) -> Result<Vec<Column>, Box<dyn std::error::Error>> {
+        let first_obj = first
+            .as_object()
+            .ok_or("JSON array elements must be objects")?;
+
+        Ok(first_obj
+            .iter()
+            .map(|(name, value)| Column::new(name.clone(), Self::infer_json_type(value)))
+            .collect())
+    }
+
+    /// Infer a column type from a JSON value
+    fn infer_json_type(value: &serde_json::Value) -> DataType {
+        match value {
+            serde_json::Value::Bool(_) => DataType::Boolean,
+            serde_json::Value::Number(n) if n.is_i64() => {
+                if n.as_i64().is_some_and(|i| i32::try_from(i).is_ok()) {
+                    DataType::Integer
+                } else {
+                    DataType::BigInt
+                }
+            }
+            serde_json::Value::Number(_) => DataType::Decimal,
+            _ => DataType::Varchar(usize::MAX),
+        }
+    }
+
+    /// Export a table to a CSV file
+    ///
+    /// Writes the column names as the header and one line per row, using the
+    /// importer's rules so `load_csv` reads the file back unchanged: NULL is
+    /// an empty field, strings are written unquoted, and other values use
+    /// their `Display` form, except that dates at midnight are written as
+    /// `YYYY-MM-DD` so their type is inferred as DATE again. Column types and
+    /// VARCHAR lengths are not stored.
+    ///
+    /// # Arguments
+    /// * `table` - Table to export
+    /// * `path` - Path of the CSV file to write
+    ///
+    /// # Returns
+    /// `Ok(())` if successful, `Err` if the file can't be written or a name
+    /// or string can't be represented (it contains a comma or line break,
+    /// has surrounding whitespace, or is an empty string)
+    pub fn export_csv(&self, table: &Table, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let header = table
+            .columns
+            .iter()
+            .map(|column| Self::csv_text(&column.name).map(str::to_string))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Column name of table {}: {}", table.name, e))?;
+
+        let mut lines = vec![header.join(",")];
+        for (row_idx, row) in table.rows.iter().enumerate() {
+            let fields = row
+                .values
+                .iter()
+                .map(Self::csv_field)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Row {}: {}", row_idx, e))?;
+            lines.push(fields.join(","));
+        }
+
+        let file =
+            File::create(path).map_err(|e| format!("Failed to create file {}: {}", path, e))?;
+        let mut writer = BufWriter::new(file);
+        for line in lines {
+            writeln!(writer, "{}", line)
+                .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("Failed to write file {}: {}", path, e))?;
+
+        Ok(())
+    }
+
+    /// Format a value as a CSV field readable by `load_csv`
+    fn csv_field(value: &Value) -> Result<String, String> {
+        match value {
+            Value::Null => Ok(String::new()),
+            Value::String(s) => Self::csv_text(s).map(str::to_string),
+            Value::Date(v) if v % SECONDS_PER_DAY == 0 => Ok(Self::format_date(*v)),
+            other => Ok(other.to_string()),
+        }
+    }
+
+    /// Check that text survives the importer's comma splitting and trimming
+    fn csv_text(s: &str) -> Result<&str, String> {
+        if s.is_empty() || s.trim() != s || s.contains([',', '\n', '\r']) {
+            return Err(format!("{:?} can't be written to CSV", s));
+        }
+        Ok(s)
+    }
+
+    /// Format Unix seconds at midnight as an ISO `YYYY-MM-DD` date
+    fn format_date(epoch: u64) -> String {
+        // Civil date from days since 1970-01-01 (proleptic Gregorian calendar)
+        let z = (epoch / SECONDS_PER_DAY) as i64 + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as i64;
+
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
     /// Load a table from a Table struct
     ///
     /// # Arguments
@@ -224,6 +460,89 @@ mod tests {
         assert!(true); // Loader created successfully
     }
 
+    #[test]
+    fn test_loader_load_csv_typed() {
+        let loader = DataLoader::new();
+        let mut db = Database::new(crate::database::schema::Schema::new("testdb".to_string()));
+        db.schema
+            .add_table(Table::new(
+                "lineitem".to_string(),
+                vec![
+                    Column::new("l_quantity".to_string(), DataType::Integer),
+                    Column::new("l_discount".to_string(), DataType::Decimal),
+                    Column::new("l_shipdate".to_string(), DataType::Date),
+                ],
+            ))
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lineitem.csv");
+        std::fs::write(
+            &path,
+            "l_quantity,l_discount,l_shipdate\n17,0.04,1996-03-13\n,0.10,\n",
+        )
+        .unwrap();
+
+        loader
+            .load_csv(&mut db, path.to_str().unwrap(), "lineitem")
+            .unwrap();
+        let table = db.get_table("lineitem").unwrap();
+        assert_eq!(
+            table.rows[0].values,
+            vec![
+                Value::Integer(17),
+                Value::Decimal(4),
+                Value::Date(826_675_200)
+            ]
+        );
+        assert_eq!(
+            table.rows[1].values,
+            vec![Value::Null, Value::Decimal(10), Value::Null]
+        );
+
+        std::fs::write(
+            &path,
+            "l_quantity,l_discount,l_shipdate\nabc,0.04,1996-03-13\n",
+        )
+        .unwrap();
+        assert!(loader
+            .load_csv(&mut db, path.to_str().unwrap(), "lineitem")
+            .is_err());
+    }
+
+    #[test]
+    fn test_loader_truncate_strings() {
+        let mut db = Database::new(crate::database::schema::Schema::new("testdb".to_string()));
+        db.schema
+            .add_table(Table::new(
+                "nation".to_string(),
+                vec![Column::new("n_name".to_string(), DataType::Varchar(5))],
+            ))
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nation.csv");
+        std::fs::write(&path, "n_name\nPERU\nGERMANY\n").unwrap();
+
+        assert!(DataLoader::new()
+            .load_csv(&mut db, path.to_str().unwrap(), "nation")
+            .is_err());
+
+        DataLoader::new()
+            .with_truncate_strings(true)
+            .load_csv(&mut db, path.to_str().unwrap(), "nation")
+            .unwrap();
+        let table = db.get_table("nation").unwrap();
+        let names: Vec<_> = table.rows.iter().map(|r| r.values[0].clone()).collect();
+        assert_eq!(
+            names[names.len() - 2..],
+            [
+                Value::String("PERU".to_string()),
+                Value::String("GERMA".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_loader_load_table() {
         let loader = DataLoader::new();
@@ -237,4 +556,94 @@ mod tests {
         assert!(loader.load_table(&mut db, table).is_ok());
         assert_eq!(db.schema.tables.len(), 1);
     }
+
+    #[test]
+    fn test_loader_infer_schema_matches_load() {
+        let loader = DataLoader::new();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.csv");
+        std::fs::write(
+            &path,
+            "o_id,o_total,o_price,o_date,o_paid,o_comment\n\
+             1,5000000000,12.50,1996-03-13,true,first\n\
+             2,,3,1996-04-01,false,\n\
+             3,7,0.25,,true,third order\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let inferred = loader.infer_schema(path, "orders").unwrap();
+        let types: Vec<DataType> = inferred.iter().map(|c| c.data_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                DataType::Integer,
+                DataType::BigInt,
+                DataType::Decimal,
+                DataType::Date,
+                DataType::Boolean,
+                DataType::Varchar(usize::MAX),
+            ]
+        );
+
+        let mut db = Database::new(crate::database::schema::Schema::new("testdb".to_string()));
+        loader.load_csv(&mut db, path, "orders").unwrap();
+        let table = db.get_table("orders").unwrap();
+        assert_eq!(table.columns, inferred);
+        assert_eq!(table.num_rows(), 3);
+    }
+
+    #[test]
+    fn test_loader_export_csv_round_trip() {
+        let loader = DataLoader::new();
+        let mut table = Table::new(
+            "orders".to_string(),
+            vec![
+                Column::new("o_id".to_string(), DataType::Integer),
+                Column::new("o_total".to_string(), DataType::BigInt),
+                Column::new("o_price".to_string(), DataType::Decimal),
+                Column::new("o_date".to_string(), DataType::Date),
+                Column::new("o_paid".to_string(), DataType::Boolean),
+                Column::new("o_comment".to_string(), DataType::Varchar(usize::MAX)),
+            ],
+        );
+        table.rows = vec![
+            Row::new(vec![
+                Value::Integer(1),
+                Value::BigInt(5_000_000_000),
+                Value::Decimal(1250),
+                Value::Date(826_675_200),
+                Value::Boolean(true),
+                Value::String("first order".to_string()),
+            ]),
+            Row::new(vec![
+                Value::Integer(-2),
+                Value::Null,
+                Value::Decimal(-300),
+                Value::Null,
+                Value::Boolean(false),
+                Value::Null,
+            ]),
+        ];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.csv");
+        let path = path.to_str().unwrap();
+        loader.export_csv(&table, path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap().lines().nth(1),
+            Some("1,5000000000,12.50,1996-03-13,TRUE,first order")
+        );
+
+        // Re-importing infers the same schema and values
+        let mut db = Database::new(crate::database::schema::Schema::new("testdb".to_string()));
+        loader.load_csv(&mut db, path, "orders").unwrap();
+        let imported = db.get_table("orders").unwrap();
+        assert_eq!(imported.columns, table.columns);
+        assert_eq!(imported.rows, table.rows);
+
+        // Strings the importer would split or trim are rejected
+        table.rows[0].values[5] = Value::String("a, b".to_string());
+        assert!(loader.export_csv(&table, path).is_err());
+    }
 }
@@ -8,6 +8,7 @@
 //! - `storage`: Database storage and persistence
 //! - `loader`: Data loading from files
 //! - `tpch`: TPC-H benchmark data support
+//! - `versioned`: Append-only tables with versioned commitments
 //!
 //! # Overview
 //!
@@ -38,9 +39,11 @@ pub mod loader;
 pub mod schema;
 pub mod storage;
 pub mod tpch;
+pub mod versioned;
 
 // Re-export main types for convenience
 pub use loader::DataLoader;
-pub use schema::{Database, Schema};
+pub use schema::{Database, DatabaseDiff, Schema};
 pub use storage::DatabaseStorage;
 pub use tpch::TPCHData;
+pub use versioned::VersionedTable;
@@ -26,10 +26,39 @@
 //! let db = Database::new(schema);
 //! ```
 
-use crate::types::Table;
+use crate::commitment::ipa::EMPTY_COMMITMENT;
+use crate::commitment::{
+    CommitmentBlinds, CommitmentError, DatabaseCommitment, IPAParams, VectorCommitment,
+};
+use crate::query::planner::ExecutionPlan;
+use crate::query::{ProofCache, QueryExecutor};
+use crate::types::{Column, Proof, QueryResult, Row, Table, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Differences between a database and a prior commitment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseDiff {
+    /// Tables that were not in the prior commitment
+    pub added_tables: Vec<String>,
+
+    /// Tables of the prior commitment that no longer exist
+    pub removed_tables: Vec<String>,
+
+    /// Tables in both as (table name, columns whose commitments differ),
+    /// including columns added or dropped since the prior commitment
+    pub changed_tables: Vec<(String, Vec<String>)>,
+}
+
+impl DatabaseDiff {
+    /// Check whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.changed_tables.is_empty()
+    }
+}
+
 /// Database schema
 ///
 /// Represents the schema of a database, including all table definitions.
@@ -91,6 +120,87 @@ impl Schema {
         self.tables.get_mut(name)
     }
 
+    /// Add a column to a table
+    ///
+    /// The column is appended after the existing ones and every existing row
+    /// is backfilled with `default`.
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `column` - Column to add
+    /// * `default` - Value for the column in existing rows
+    ///
+    /// # Returns
+    /// `Ok(())` if added, `Err` if the table doesn't exist, already has a
+    /// column with that name, or `default` doesn't fit the column type
+    pub fn add_column(
+        &mut self,
+        table_name: &str,
+        column: Column,
+        default: Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let table = self
+            .get_table_mut(table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+
+        if table.get_column(&column.name).is_some() {
+            return Err(format!("Table {} already has column {}", table_name, column.name).into());
+        }
+        if !default.is_compatible(&column.data_type) {
+            return Err(format!(
+                "Default {} is not valid for column {} ({})",
+                default, column.name, column.data_type
+            )
+            .into());
+        }
+
+        table.columns.push(column);
+        for row in &mut table.rows {
+            row.values.push(default.clone());
+        }
+        Ok(())
+    }
+
+    /// Drop a column from a table
+    ///
+    /// Removes the column definition and its value from every row.
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `column_name` - Column to drop
+    ///
+    /// # Returns
+    /// `Ok(())` if dropped, `Err` if the table or column doesn't exist, or the
+    /// column is the table's last one
+    pub fn drop_column(
+        &mut self,
+        table_name: &str,
+        column_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let table = self
+            .get_table_mut(table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+
+        let index = table
+            .columns
+            .iter()
+            .position(|c| c.name == column_name)
+            .ok_or_else(|| format!("Column {} not found in table {}", column_name, table_name))?;
+        if table.columns.len() == 1 {
+            return Err(format!(
+                "Cannot drop {}, the last column of table {}",
+                column_name, table_name
+            )
+            .into());
+        }
+
+        table.columns.remove(index);
+        for row in &mut table.rows {
+            row.values.remove(index);
+        }
+        Ok(())
+    }
+
     /// Validate the schema
     ///
     /// Checks that all tables have valid column definitions.
@@ -144,6 +254,14 @@ impl Schema {
 pub struct Database {
     /// Database schema
     pub schema: Schema,
+
+    /// Commitment to the database contents, if committed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<DatabaseCommitment>,
+
+    /// Whether the data changed since `commitment` was created
+    #[serde(default)]
+    pub commitment_stale: bool,
 }
 
 impl Database {
@@ -152,7 +270,281 @@ impl Database {
     /// # Arguments
     /// * `schema` - Database schema
     pub fn new(schema: Schema) -> Self {
-        Self { schema }
+        Self {
+            schema,
+            commitment: None,
+            commitment_stale: false,
+        }
+    }
+
+    /// Commit to the current database contents
+    ///
+    /// Replaces any stored commitment and clears the stale flag. The new
+    /// commitment's version is one more than the replaced commitment's.
+    ///
+    /// # Arguments
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok(&DatabaseCommitment)` with the new commitment,
+    /// `Err(CommitmentError::TooLarge)` if a table has more rows than
+    /// `params.max_rows()` (the stored commitment is left unchanged)
+    pub fn commit(&mut self, params: &IPAParams) -> Result<&DatabaseCommitment, CommitmentError> {
+        Ok(self.commit_with_blinds(params)?.0)
+    }
+
+    /// Commit to the current database contents, keeping the blinding factors
+    ///
+    /// Same as `commit`, but also returns the blinds of the column
+    /// commitments, which `diff` needs to recompute them. The blinds must
+    /// not be published.
+    ///
+    /// # Arguments
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok((&DatabaseCommitment, CommitmentBlinds))` with the new commitment
+    /// and its blinds, `Err(CommitmentError::TooLarge)` if a table has more
+    /// rows than `params.max_rows()`
+    pub fn commit_with_blinds(
+        &mut self,
+        params: &IPAParams,
+    ) -> Result<(&DatabaseCommitment, CommitmentBlinds), CommitmentError> {
+        let mut tables: Vec<Table> = self.schema.tables.values().cloned().collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let version = self.commitment.as_ref().map_or(1, |c| c.version + 1);
+        let (commitment, blinds) =
+            DatabaseCommitment::commit_database_with_blinds(&tables, params)?;
+        self.commitment_stale = false;
+        Ok((
+            self.commitment.insert(commitment.with_version(version)),
+            blinds,
+        ))
+    }
+
+    /// Compare the current contents against a prior commitment
+    ///
+    /// Every column is recommitted with the blind it was committed with, so
+    /// a column differs exactly when its values (or row count) changed.
+    /// Columns without a blind in `blinds` can't be recommitted and are
+    /// reported as changed unless both sides are empty.
+    ///
+    /// # Arguments
+    /// * `prior` - Prior commitment to compare against
+    /// * `blinds` - Blinds of the prior commitment, as returned by `commit_with_blinds`
+    /// * `params` - IPA parameters the prior commitment was created with
+    ///
+    /// # Returns
+    /// `Ok(DatabaseDiff)` with the added, removed, and changed tables,
+    /// `Err(CommitmentError::TooLarge)` if a table has more rows than
+    /// `params.max_rows()`
+    pub fn diff(
+        &self,
+        prior: &DatabaseCommitment,
+        blinds: &CommitmentBlinds,
+        params: &IPAParams,
+    ) -> Result<DatabaseDiff, CommitmentError> {
+        let mut diff = DatabaseDiff::default();
+
+        let mut tables: Vec<&Table> = self.schema.tables.values().collect();
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+        for table in tables {
+            let Some(prior_table) = prior
+                .table_commitments
+                .iter()
+                .find(|tc| tc.table_name == table.name)
+            else {
+                diff.added_tables.push(table.name.clone());
+                continue;
+            };
+
+            VectorCommitment::check_size(table.rows.len(), params)?;
+            let mut columns = vec![];
+            for column in &table.columns {
+                let committed = prior_table
+                    .column_commitments
+                    .iter()
+                    .find(|cc| cc.column_name == column.name);
+                let unchanged = match committed {
+                    Some(cc) if cc.num_rows == table.rows.len() => {
+                        if table.rows.is_empty() {
+                            cc.commitment == EMPTY_COMMITMENT
+                        } else if let Some(blind) = blinds.get(&table.name, &column.name) {
+                            let values = table.column_fields(&column.name).unwrap_or_default();
+                            VectorCommitment::commit_with_blind(values, blind, params)?.commitment
+                                == cc.commitment
+                        } else {
+                            false
+                        }
+                    }
+                    _ => false,
+                };
+                if !unchanged {
+                    columns.push(column.name.clone());
+                }
+            }
+
+            // Columns dropped since the prior commitment
+            columns.extend(
+                prior_table
+                    .column_commitments
+                    .iter()
+                    .filter(|cc| table.get_column(&cc.column_name).is_none())
+                    .map(|cc| cc.column_name.clone()),
+            );
+            if !columns.is_empty() {
+                diff.changed_tables.push((table.name.clone(), columns));
+            }
+        }
+
+        diff.removed_tables = prior
+            .table_commitments
+            .iter()
+            .filter(|tc| !self.schema.tables.contains_key(&tc.table_name))
+            .map(|tc| tc.table_name.clone())
+            .collect();
+
+        Ok(diff)
+    }
+
+    /// Get the commitment to the current contents
+    ///
+    /// Callers must re-commit with `commit` after any mutation before proving.
+    ///
+    /// # Returns
+    /// `Ok(&DatabaseCommitment)` if a commitment exists and is up to date,
+    /// `Err` otherwise
+    pub fn current_commitment(&self) -> Result<&DatabaseCommitment, Box<dyn std::error::Error>> {
+        match &self.commitment {
+            None => Err("Database has not been committed".into()),
+            Some(_) if self.commitment_stale => {
+                Err("Database commitment is stale; re-commit before proving".into())
+            }
+            Some(commitment) => Ok(commitment),
+        }
+    }
+
+    /// Check whether the stored commitment is out of date
+    pub fn is_commitment_stale(&self) -> bool {
+        self.commitment.is_some() && self.commitment_stale
+    }
+
+    /// Insert a row into a table
+    ///
+    /// Marks the stored commitment stale.
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `row` - Row to insert
+    ///
+    /// # Returns
+    /// `Ok(())` if inserted, `Err` if the table doesn't exist or the row
+    /// doesn't match its columns
+    pub fn insert_row(
+        &mut self,
+        table_name: &str,
+        row: Row,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let table = self
+            .schema
+            .get_table_mut(table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+
+        if row.values.len() != table.columns.len() {
+            return Err(format!(
+                "Row has {} values but table {} has {} columns",
+                row.values.len(),
+                table_name,
+                table.columns.len()
+            )
+            .into());
+        }
+        for (column, value) in table.columns.iter().zip(&row.values) {
+            if !value.is_compatible(&column.data_type) {
+                return Err(format!(
+                    "Value {} is not valid for column {} ({})",
+                    value, column.name, column.data_type
+                )
+                .into());
+            }
+        }
+
+        table.rows.push(row);
+        self.commitment_stale = true;
+        Ok(())
+    }
+
+    /// Delete the rows of a table matching a predicate
+    ///
+    /// Marks the stored commitment stale if any row was deleted.
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `predicate` - Returns `true` for rows to delete
+    ///
+    /// # Returns
+    /// `Ok(count)` with the number of deleted rows, `Err` if the table doesn't exist
+    pub fn delete_rows(
+        &mut self,
+        table_name: &str,
+        predicate: impl Fn(&Row) -> bool,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let table = self
+            .schema
+            .get_table_mut(table_name)
+            .ok_or_else(|| format!("Table {} not found", table_name))?;
+
+        let before = table.rows.len();
+        table.rows.retain(|row| !predicate(row));
+        let deleted = before - table.rows.len();
+
+        if deleted > 0 {
+            self.commitment_stale = true;
+        }
+        Ok(deleted)
+    }
+
+    /// Add a column to a table, backfilling existing rows with a default
+    ///
+    /// Marks the stored commitment stale. See [`Schema::add_column`].
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `column` - Column to add
+    /// * `default` - Value for the column in existing rows
+    ///
+    /// # Returns
+    /// `Ok(())` if added, `Err` otherwise (the database is unchanged)
+    pub fn add_column(
+        &mut self,
+        table_name: &str,
+        column: Column,
+        default: Value,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.schema.add_column(table_name, column, default)?;
+        self.commitment_stale = true;
+        Ok(())
+    }
+
+    /// Drop a column from a table
+    ///
+    /// Marks the stored commitment stale. See [`Schema::drop_column`].
+    ///
+    /// # Arguments
+    /// * `table_name` - Table name
+    /// * `column_name` - Column to drop
+    ///
+    /// # Returns
+    /// `Ok(())` if dropped, `Err` otherwise (the database is unchanged)
+    pub fn drop_column(
+        &mut self,
+        table_name: &str,
+        column_name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.schema.drop_column(table_name, column_name)?;
+        self.commitment_stale = true;
+        Ok(())
     }
 
     /// Get a table by name
@@ -168,13 +560,17 @@ impl Database {
 
     /// Get a mutable reference to a table
     ///
+    /// The table may be modified, so this marks the stored commitment stale.
+    ///
     /// # Arguments
     /// * `name` - Table name
     ///
     /// # Returns
     /// `Some(&mut Table)` if found, `None` otherwise
     pub fn get_table_mut(&mut self, name: &str) -> Option<&mut Table> {
-        self.schema.get_table_mut(name)
+        let table = self.schema.get_table_mut(name)?;
+        self.commitment_stale = true;
+        Some(table)
     }
 
     /// Validate the database
@@ -191,12 +587,63 @@ impl Database {
     pub fn table_names(&self) -> Vec<String> {
         self.schema.table_names()
     }
+
+    /// Get the tables keyed by name, as `QueryExecutor::execute` takes them
+    pub fn tables_ref(&self) -> &HashMap<String, Table> {
+        &self.schema.tables
+    }
+
+    /// Get an owned copy of the tables keyed by name
+    ///
+    /// Use `tables_ref` to avoid copying the rows when a borrow suffices.
+    pub fn tables_map(&self) -> HashMap<String, Table> {
+        self.schema.tables.clone()
+    }
+
+    /// Execute a query plan, reusing a cached proof when possible
+    ///
+    /// Results are cached against the current commitment, so a query repeated
+    /// before the database changes (and is re-committed) is not proved again.
+    /// The executor must be configured with the same commitment, or cached
+    /// proofs could be served for data they weren't produced against.
+    ///
+    /// # Arguments
+    /// * `executor` - Executor to run the query with on a cache miss
+    /// * `plan` - Execution plan for the query
+    /// * `cache` - Cache of results and proofs
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, Proof))` if execution succeeds, `Err` if the database
+    /// has no up-to-date commitment, the executor is configured with another
+    /// commitment, or execution fails
+    pub fn query(
+        &self,
+        executor: &QueryExecutor,
+        plan: &ExecutionPlan,
+        cache: &mut ProofCache,
+    ) -> Result<(QueryResult, Proof), Box<dyn std::error::Error>> {
+        let commitment_hash = &self.current_commitment()?.commitment_hash;
+        match executor.commitment() {
+            Some(commitment) if commitment.commitment_hash == *commitment_hash => {}
+            Some(_) => {
+                return Err("Executor commitment does not match the database commitment".into())
+            }
+            None => return Err("Executor has no database commitment".into()),
+        }
+        if let Some(cached) = cache.get(plan, commitment_hash) {
+            return Ok(cached);
+        }
+
+        let (result, proof) = executor.execute(plan, self.tables_ref())?;
+        cache.insert(plan, commitment_hash, result.clone(), proof.clone());
+        Ok((result, proof))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Column, DataType};
+    use crate::types::DataType;
 
     #[test]
     fn test_schema_new() {
@@ -245,6 +692,21 @@ mod tests {
         assert_eq!(db.schema.name, "testdb");
     }
 
+    #[test]
+    fn test_database_tables_map() {
+        let db = crate::database::TPCHData::new()
+            .generate_database(0.01)
+            .unwrap();
+
+        let tables = db.tables_map();
+        assert_eq!(tables.len(), db.table_names().len());
+        for name in ["lineitem", "orders", "customer"] {
+            assert_eq!(tables[name].name, name);
+            assert_eq!(tables[name].rows, db.get_table(name).unwrap().rows);
+        }
+        assert_eq!(db.tables_ref().len(), tables.len());
+    }
+
     #[test]
     fn test_database_validate() {
         let mut schema = Schema::new("testdb".to_string());
@@ -256,4 +718,287 @@ mod tests {
         let db = Database::new(schema);
         assert!(db.validate().is_ok());
     }
+
+    fn lineitem_db() -> Database {
+        let mut schema = Schema::new("testdb".to_string());
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        schema.add_table(table).unwrap();
+        Database::new(schema)
+    }
+
+    #[test]
+    fn test_database_insert_row() {
+        let mut db = lineitem_db();
+        db.insert_row("lineitem", Row::new(vec![Value::Integer(7)]))
+            .unwrap();
+        assert_eq!(db.get_table("lineitem").unwrap().num_rows(), 4);
+
+        // Wrong arity, wrong type, and unknown table are rejected
+        assert!(db.insert_row("lineitem", Row::new(vec![])).is_err());
+        assert!(db
+            .insert_row("lineitem", Row::new(vec![Value::Boolean(true)]))
+            .is_err());
+        assert!(db.insert_row("orders", Row::new(vec![])).is_err());
+        assert_eq!(db.get_table("lineitem").unwrap().num_rows(), 4);
+    }
+
+    #[test]
+    fn test_database_delete_rows() {
+        let mut db = lineitem_db();
+        let deleted = db
+            .delete_rows(
+                "lineitem",
+                |row| matches!(row.values[0], Value::Integer(q) if q > 10),
+            )
+            .unwrap();
+
+        assert_eq!(deleted, 2);
+        let table = db.get_table("lineitem").unwrap();
+        assert_eq!(table.num_rows(), 1);
+        assert_eq!(table.rows[0].values, vec![Value::Integer(5)]);
+    }
+
+    #[test]
+    fn test_database_add_column() {
+        let mut db = lineitem_db();
+        db.add_column(
+            "lineitem",
+            Column::new("l_returnflag".to_string(), DataType::Varchar(1)),
+            Value::String("N".to_string()),
+        )
+        .unwrap();
+
+        let table = db.get_table("lineitem").unwrap();
+        assert_eq!(table.num_columns(), 2);
+        assert!(table.rows.iter().all(|row| row.values.len() == 2));
+        assert_eq!(
+            table.rows[1].values,
+            vec![Value::Integer(12), Value::String("N".to_string())]
+        );
+        assert!(db.validate().is_ok());
+
+        // Unknown table, duplicate column, and mistyped default are rejected
+        let column = Column::new("l_tax".to_string(), DataType::Decimal);
+        assert!(db
+            .add_column("orders", column.clone(), Value::Null)
+            .is_err());
+        assert!(db
+            .add_column(
+                "lineitem",
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Value::Null
+            )
+            .is_err());
+        assert!(db
+            .add_column("lineitem", column, Value::Boolean(false))
+            .is_err());
+        assert_eq!(db.get_table("lineitem").unwrap().num_columns(), 2);
+    }
+
+    #[test]
+    fn test_database_drop_column() {
+        let mut db = lineitem_db();
+        db.add_column(
+            "lineitem",
+            Column::new("l_tax".to_string(), DataType::Decimal),
+            Value::Decimal(0),
+        )
+        .unwrap();
+        db.drop_column("lineitem", "l_quantity").unwrap();
+
+        let table = db.get_table("lineitem").unwrap();
+        assert_eq!(table.num_columns(), 1);
+        assert_eq!(table.columns[0].name, "l_tax");
+        assert!(table
+            .rows
+            .iter()
+            .all(|row| row.values == vec![Value::Decimal(0)]));
+        assert!(db.validate().is_ok());
+
+        // Unknown table, unknown column, and the last column are rejected
+        assert!(db.drop_column("orders", "l_tax").is_err());
+        assert!(db.drop_column("lineitem", "l_quantity").is_err());
+        assert!(db.drop_column("lineitem", "l_tax").is_err());
+        assert_eq!(db.get_table("lineitem").unwrap().num_columns(), 1);
+    }
+
+    #[test]
+    fn test_database_mutation_marks_commitment_stale() {
+        let params = IPAParams::new(4);
+        let mut db = lineitem_db();
+        assert!(db.current_commitment().is_err());
+
+        db.commit(&params).unwrap();
+        assert!(!db.is_commitment_stale());
+        assert!(db.current_commitment().is_ok());
+
+        db.insert_row("lineitem", Row::new(vec![Value::Integer(1)]))
+            .unwrap();
+        assert!(db.is_commitment_stale());
+        assert!(db.current_commitment().is_err());
+
+        db.commit(&params).unwrap();
+        assert!(db.current_commitment().is_ok());
+        assert_eq!(db.current_commitment().unwrap().version, 2);
+
+        // Deleting nothing keeps the commitment fresh
+        db.delete_rows("lineitem", |_| false).unwrap();
+        assert!(!db.is_commitment_stale());
+        db.delete_rows("lineitem", |_| true).unwrap();
+        assert!(db.is_commitment_stale());
+
+        // Schema changes do too, unless they are rejected
+        db.commit(&params).unwrap();
+        assert!(db.drop_column("lineitem", "l_quantity").is_err());
+        assert!(!db.is_commitment_stale());
+        db.add_column(
+            "lineitem",
+            Column::new("l_tax".to_string(), DataType::Decimal),
+            Value::Null,
+        )
+        .unwrap();
+        assert!(db.is_commitment_stale());
+    }
+
+    #[test]
+    fn test_database_query_uses_proof_cache() {
+        let params = IPAParams::new(10);
+        let plan = crate::test_util::plan("SELECT COUNT(*) FROM lineitem");
+        let mut cache = ProofCache::new();
+        let mut db = lineitem_db();
+
+        // Queries need an up-to-date commitment to key the cache
+        let executor = QueryExecutor::new(&params);
+        assert!(db.query(&executor, &plan, &mut cache).is_err());
+
+        let (commitment, blinds) = db.commit_with_blinds(&params).unwrap();
+        let executor = QueryExecutor::new(&params).with_commitment(commitment.clone(), blinds);
+        let (result, proof) = db.query(&executor, &plan, &mut cache).unwrap();
+        let (cached, cached_proof) = db.query(&executor, &plan, &mut cache).unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+        assert_eq!(cached.rows, result.rows);
+        assert_eq!(cached_proof.proof_bytes, proof.proof_bytes);
+
+        // Changing the database busts the cache
+        db.insert_row("lineitem", Row::new(vec![Value::Integer(7)]))
+            .unwrap();
+        let (commitment, blinds) = db.commit_with_blinds(&params).unwrap();
+
+        // An executor left on the old commitment is rejected
+        assert!(db.query(&executor, &plan, &mut cache).is_err());
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        let executor = QueryExecutor::new(&params).with_commitment(commitment.clone(), blinds);
+        let (result, _) = db.query(&executor, &plan, &mut cache).unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+        assert_eq!(result.rows[0].values, vec![Value::BigInt(4)]);
+    }
+
+    #[test]
+    fn test_database_query_join() {
+        let params = IPAParams::new(10);
+        let mut schema = Schema::new("testdb".to_string());
+        let mut orders = Table::new(
+            "orders".to_string(),
+            vec![Column::new("o_custkey".to_string(), DataType::Integer)],
+        );
+        for key in [1, 2, 2] {
+            orders.rows.push(Row::new(vec![Value::Integer(key)]));
+        }
+        let mut customer = Table::new(
+            "customer".to_string(),
+            vec![Column::new("c_custkey".to_string(), DataType::Integer)],
+        );
+        for key in [2, 3] {
+            customer.rows.push(Row::new(vec![Value::Integer(key)]));
+        }
+        schema.add_table(orders).unwrap();
+        schema.add_table(customer).unwrap();
+        let mut db = Database::new(schema);
+
+        let (commitment, blinds) = db.commit_with_blinds(&params).unwrap();
+        let executor = QueryExecutor::new(&params).with_commitment(commitment.clone(), blinds);
+        let plan = crate::test_util::plan(
+            "SELECT COUNT(*) FROM orders o JOIN customer c ON o.o_custkey = c.c_custkey",
+        );
+
+        // The joined rows aren't committed cells, so none are opened
+        let (result, proof) = db.query(&executor, &plan, &mut ProofCache::new()).unwrap();
+        assert_eq!(result.rows[0].values, vec![Value::BigInt(2)]);
+        assert!(proof.openings.is_empty());
+    }
+
+    #[test]
+    fn test_database_commit_too_large() {
+        let params = IPAParams::new(2); // max 4 rows
+        let mut db = lineitem_db();
+        db.commit(&params).unwrap();
+
+        for q in [1, 2] {
+            db.insert_row("lineitem", Row::new(vec![Value::Integer(q)]))
+                .unwrap();
+        }
+        assert_eq!(
+            db.commit(&params).unwrap_err(),
+            CommitmentError::TooLarge { rows: 5, max: 4 }
+        );
+
+        // The failed commit leaves the stale commitment in place
+        assert!(db.is_commitment_stale());
+        assert!(db.current_commitment().is_err());
+    }
+
+    #[test]
+    fn test_database_diff() {
+        let params = IPAParams::new(4);
+        let mut db = lineitem_db();
+        let mut orders = Table::new(
+            "orders".to_string(),
+            vec![
+                Column::new("o_orderkey".to_string(), DataType::Integer),
+                Column::new("o_totalprice".to_string(), DataType::BigInt),
+            ],
+        );
+        for (key, price) in [(1, 100), (2, 250)] {
+            orders
+                .rows
+                .push(Row::new(vec![Value::Integer(key), Value::BigInt(price)]));
+        }
+        db.schema.add_table(orders).unwrap();
+
+        let (prior, blinds) = db.commit_with_blinds(&params).unwrap();
+        let prior = prior.clone();
+        assert!(db.diff(&prior, &blinds, &params).unwrap().is_empty());
+
+        // Changing one cell surfaces only its table and column
+        db.get_table_mut("orders").unwrap().rows[1].values[1] = Value::BigInt(260);
+        let diff = db.diff(&prior, &blinds, &params).unwrap();
+        assert_eq!(
+            diff,
+            DatabaseDiff {
+                added_tables: vec![],
+                removed_tables: vec![],
+                changed_tables: vec![("orders".to_string(), vec!["o_totalprice".to_string()])],
+            }
+        );
+
+        // Added and removed tables
+        db.schema.tables.remove("lineitem");
+        db.schema
+            .add_table(Table::new(
+                "part".to_string(),
+                vec![Column::new("p_partkey".to_string(), DataType::Integer)],
+            ))
+            .unwrap();
+        let diff = db.diff(&prior, &blinds, &params).unwrap();
+        assert_eq!(diff.added_tables, vec!["part"]);
+        assert_eq!(diff.removed_tables, vec!["lineitem"]);
+        assert_eq!(diff.changed_tables.len(), 1);
+    }
 }
@@ -0,0 +1,292 @@
+//! Append-only versioned tables
+//!
+//! This module provides an append-only table whose column commitments are
+//! updated incrementally on each append. Every append records a new version
+//! whose hash is linked to the previous version's hash, forming a hash chain
+//! suitable for audit logs.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::commitment::IPAParams;
+//! use nzengi_db::database::VersionedTable;
+//! use nzengi_db::types::{Column, DataType, Row, Table, Value};
+//!
+//! let params = IPAParams::new(10);
+//! let table = Table::new(
+//!     "audit_log".to_string(),
+//!     vec![Column::new("event".to_string(), DataType::Integer)],
+//! );
+//!
+//! let mut versioned = VersionedTable::new(table, &params)?;
+//! versioned.append(Row::new(vec![Value::Integer(1)]), &params)?;
+//!
+//! assert!(versioned.verify_chain(&params));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::commitment::{CommitmentError, IPAParams, VectorCommitment};
+use crate::types::{Row, Table};
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::poly::commitment::Blind;
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+
+/// Append-only table with a hash chain of commitment versions
+#[derive(Debug, Clone)]
+pub struct VersionedTable {
+    /// Underlying table
+    pub table: Table,
+
+    /// Incremental commitment for each column
+    pub column_commitments: Vec<VectorCommitment>,
+
+    /// Version chain: (version, commitment hash linked to the previous version)
+    pub versions: Vec<(u64, String)>,
+
+    /// Number of rows at version 0
+    base_rows: usize,
+}
+
+impl VersionedTable {
+    /// Create a versioned table from an existing table
+    ///
+    /// The existing rows are committed as version 0.
+    ///
+    /// # Arguments
+    /// * `table` - Initial table contents
+    /// * `params` - IPA parameters for commitment
+    ///
+    /// # Returns
+    /// `Ok(VersionedTable)` with a single version, `Err(CommitmentError::TooLarge)`
+    /// if the table has more rows than `params.max_rows()`
+    pub fn new(table: Table, params: &IPAParams) -> Result<Self, CommitmentError> {
+        let column_commitments = table
+            .columns
+            .iter()
+            .map(|column| {
+                let values = table.column_fields(&column.name).unwrap_or_default();
+                VectorCommitment::commit_with_blind(values, Blind(Field::random(OsRng)), params)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let base_rows = table.rows.len();
+        let hash = Self::version_hash(&Self::genesis_hash(), 0, &table, &column_commitments);
+
+        Ok(Self {
+            table,
+            column_commitments,
+            versions: vec![(0, hash)],
+            base_rows,
+        })
+    }
+
+    /// Append a row and record a new version
+    ///
+    /// # Arguments
+    /// * `row` - Row to append
+    /// * `params` - IPA parameters used for commitment
+    ///
+    /// # Returns
+    /// `Ok(version)` with the new version number, `Err` if the row does not
+    /// match the table schema or the table is full
+    pub fn append(
+        &mut self,
+        row: Row,
+        params: &IPAParams,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        if row.values.len() != self.table.columns.len() {
+            return Err(format!(
+                "Row has {} values, table {} has {} columns",
+                row.values.len(),
+                self.table.name,
+                self.table.columns.len()
+            )
+            .into());
+        }
+
+        VectorCommitment::check_size(self.table.rows.len() + 1, params)?;
+
+        for (commitment, value) in self.column_commitments.iter_mut().zip(&row.values) {
+            commitment.append(value.to_field(), params)?;
+        }
+        self.table.rows.push(row);
+
+        let (prev_version, prev_hash) = self.versions.last().ok_or("Version chain is empty")?;
+        let version = prev_version + 1;
+        let hash = Self::version_hash(prev_hash, version, &self.table, &self.column_commitments);
+        self.versions.push((version, hash));
+
+        Ok(version)
+    }
+
+    /// Verify the version chain
+    ///
+    /// Recomputes the commitment at every version from the committed rows and
+    /// checks that each version hash links to the previous one.
+    ///
+    /// # Arguments
+    /// * `params` - IPA parameters used for commitment
+    ///
+    /// # Returns
+    /// `true` if the current commitments and every version link are valid
+    pub fn verify_chain(&self, params: &IPAParams) -> bool {
+        let expected_versions = self.table.rows.len().checked_sub(self.base_rows);
+        if expected_versions.map(|n| n + 1) != Some(self.versions.len()) {
+            return false;
+        }
+
+        // Current commitments must open to the table rows
+        for (col_idx, commitment) in self.column_commitments.iter().enumerate() {
+            let matches_rows = commitment.len() == self.table.rows.len()
+                && self
+                    .table
+                    .rows
+                    .iter()
+                    .zip(&commitment.values)
+                    .all(|(row, value)| row.column_field(col_idx) == Some(*value));
+            if !matches_rows || !commitment.verify(params) {
+                return false;
+            }
+        }
+
+        let mut prev_hash = Self::genesis_hash();
+        for (i, (version, hash)) in self.versions.iter().enumerate() {
+            if *version != i as u64 {
+                return false;
+            }
+
+            // Commitments as of this version
+            let num_rows = self.base_rows + i;
+            let Ok(prefix_commitments) = self
+                .column_commitments
+                .iter()
+                .map(|commitment| {
+                    VectorCommitment::commit_with_blind(
+                        commitment.values[..num_rows].to_vec(),
+                        commitment.blind.unwrap_or(Blind(Field::zero())),
+                        params,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+            else {
+                return false;
+            };
+
+            let expected =
+                Self::version_hash(&prev_hash, *version, &self.table, &prefix_commitments);
+            if *hash != expected {
+                return false;
+            }
+            prev_hash = expected;
+        }
+
+        true
+    }
+
+    /// Get the current version number
+    pub fn current_version(&self) -> u64 {
+        self.versions
+            .last()
+            .map(|(version, _)| *version)
+            .unwrap_or(0)
+    }
+
+    /// Get the hash of the current version
+    pub fn current_hash(&self) -> Option<&str> {
+        self.versions.last().map(|(_, hash)| hash.as_str())
+    }
+
+    /// Hash preceding version 0
+    fn genesis_hash() -> String {
+        hex::encode([0u8; 32])
+    }
+
+    /// Compute the hash of a version, linked to the previous version hash
+    ///
+    /// # Arguments
+    /// * `prev_hash` - Hash of the previous version
+    /// * `version` - Version number
+    /// * `table` - Table (for names)
+    /// * `column_commitments` - Column commitments at this version
+    ///
+    /// # Returns
+    /// Hex-encoded SHA-256 hash string
+    fn version_hash(
+        prev_hash: &str,
+        version: u64,
+        table: &Table,
+        column_commitments: &[VectorCommitment],
+    ) -> String {
+        let mut hasher = Sha256::new();
+
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(version.to_le_bytes());
+        hasher.update(table.name.as_bytes());
+
+        for (column, commitment) in table.columns.iter().zip(column_commitments) {
+            hasher.update(column.name.as_bytes());
+            hasher.update(&commitment.commitment);
+            hasher.update(commitment.len().to_le_bytes());
+        }
+
+        hex::encode(hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Column, DataType, Value};
+
+    fn audit_table() -> Table {
+        Table::new(
+            "audit_log".to_string(),
+            vec![
+                Column::new("id".to_string(), DataType::Integer),
+                Column::new("amount".to_string(), DataType::BigInt),
+            ],
+        )
+    }
+
+    fn append_three(params: &IPAParams) -> VersionedTable {
+        let mut versioned = VersionedTable::new(audit_table(), params).unwrap();
+        for i in 1..=3 {
+            let row = Row::new(vec![Value::Integer(i), Value::BigInt(i as i64 * 100)]);
+            let version = versioned.append(row, params).unwrap();
+            assert_eq!(version, i as u64);
+        }
+        versioned
+    }
+
+    #[test]
+    fn test_versioned_table_chain_verifies() {
+        let params = IPAParams::new(4);
+        let versioned = append_three(&params);
+
+        assert_eq!(versioned.versions.len(), 4);
+        assert_eq!(versioned.current_version(), 3);
+        assert_eq!(versioned.table.num_rows(), 3);
+        assert!(versioned.verify_chain(&params));
+    }
+
+    #[test]
+    fn test_versioned_table_tampered_version() {
+        let params = IPAParams::new(4);
+        let mut versioned = append_three(&params);
+
+        versioned.versions[1].1 = hex::encode([1u8; 32]);
+        assert!(!versioned.verify_chain(&params));
+    }
+
+    #[test]
+    fn test_versioned_table_rejects_wrong_arity() {
+        let params = IPAParams::new(4);
+        let mut versioned = VersionedTable::new(audit_table(), &params).unwrap();
+
+        let row = Row::new(vec![Value::Integer(1)]);
+        assert!(versioned.append(row, &params).is_err());
+        assert_eq!(versioned.current_version(), 0);
+    }
+}
@@ -12,6 +12,7 @@
 
 use ff::{Field as _, PrimeField};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
+use std::cmp::Ordering;
 
 /// Field element utilities
 ///
@@ -61,6 +62,28 @@ impl FieldUtils {
         ]))
     }
 
+    /// Compare two field elements as unsigned 256-bit integers
+    ///
+    /// Uses the full canonical representation, so values that agree in their
+    /// low 64 bits are still ordered correctly.
+    ///
+    /// # Example
+    /// ```
+    /// use halo2_proofs::halo2curves::bn256::Fr as Field;
+    /// use nzengi_db::field::FieldUtils;
+    /// use std::cmp::Ordering;
+    ///
+    /// let a = Field::from(3u64);
+    /// let b = Field::from(5u64);
+    /// assert_eq!(FieldUtils::cmp(&a, &b), Ordering::Less);
+    /// ```
+    pub fn cmp(a: &Field, b: &Field) -> Ordering {
+        // Representations are little-endian; compare from the most significant byte
+        let a_bytes = a.to_repr();
+        let b_bytes = b.to_repr();
+        a_bytes.iter().rev().cmp(b_bytes.iter().rev())
+    }
+
     /// Decompose u64 into u8 cells
     ///
     /// Splits a 64-bit integer into 8 segments of 8 bits each.
@@ -271,6 +294,22 @@ mod tests {
         assert_eq!(value, Some(u64::MAX));
     }
 
+    #[test]
+    fn test_cmp_full_width() {
+        // Same low 64 bits, different high bits
+        let shift = Field::from(2u64).pow_vartime([64u64]);
+        let a = Field::from(7u64) + shift;
+        let b = Field::from(7u64) + shift * Field::from(2u64);
+
+        assert_eq!(FieldUtils::cmp(&a, &b), Ordering::Less);
+        assert_eq!(FieldUtils::cmp(&b, &a), Ordering::Greater);
+        assert_eq!(FieldUtils::cmp(&a, &a), Ordering::Equal);
+        assert_eq!(
+            FieldUtils::cmp(&Field::from(u64::MAX), &shift),
+            Ordering::Less
+        );
+    }
+
     #[test]
     fn test_u64_decompose_recompose() {
         // Test with various values
@@ -8,22 +8,37 @@
 //!
 //! # Method
 //!
-//! 1. SUM: Mi = bi · Mi-1 + valuei · (1 - bi)
-//!    - If bi = 1 (same group): Mi = Mi-1 + valuei
-//!    - If bi = 0 (new group): Mi = valuei
+//! 1. SUM: M0 = value0, Mi = bi-1 · Mi-1 + valuei
+//!    - If bi-1 = 1 (rows i-1 and i in the same group): Mi = Mi-1 + valuei
+//!    - If bi-1 = 0 (row i starts a new group): Mi = valuei
+//!
+//!    The binary markers follow the group-by gate: bi = 1 if rows i and i+1
+//!    belong to the same group.
 //!
 //! 2. COUNT: counti = endi - starti + 1
 //!
 //! 3. AVG: avgi · counti - sumi = 0
+//!    - With [`AggregationConfig::with_remainder_check`], AVG is truncated:
+//!      sumi = avgi · counti + ri, with avgi, ri < 2^n and ri < counti
+//!      proven by the less-than gate against the row's COUNT cell
 //!
 //! 4. MIN/MAX: After sorting, MINi = value at starti, MAXi = value at endi
 //!
+//! For public MIN/MAX outputs, [`MinMaxConfig`] copies the first and last
+//! cells of the sort gate's output into the min/max columns and binds them
+//! to instance cells. The sort gate proves the output is an ascending
+//! permutation of the input, so these are the true minimum and maximum.
+//! A public SUM over a single group is the accumulator of its last row,
+//! returned by [`AggregationConfig::assign`] for binding to an instance cell.
+//!
 //! # Constraints
 //!
-//! - SUM constraint: 1 per group
-//! - COUNT constraint: 1 per group
-//! - AVG constraint: 1 per group
+//! - SUM constraint: 1 per row
+//! - COUNT constraint: 1 per row
+//! - AVG constraint: 1 per row
+//! - AVG remainder bounds: 3 less-than comparisons per row (with a remainder check)
 //! - MIN/MAX constraint: 1 per group
+//! - Public MIN/MAX: 2 copy constraints and 2 instance constraints
 //!
 //! # Example
 //!
@@ -38,11 +53,14 @@
 //! let config = AggregationConfig::configure(&mut meta, &advice);
 //! ```
 
+use super::less_than::LessThanConfig;
+use super::ValidationMode;
+use crate::field::FieldUtils;
 use ff::Field as _;
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
-    circuit::{Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression},
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Instance, Selector},
     poly::Rotation,
 };
 
@@ -75,6 +93,21 @@ pub struct AggregationConfig {
 
     /// Column for AVG result
     pub avg_col: Column<Advice>,
+
+    /// Selector for the first row (M0 = value0)
+    pub s_first: Selector,
+
+    /// Selector for accumulation rows after the first
+    pub s_acc: Selector,
+
+    /// Selector for the per-row COUNT constraint
+    pub s_row: Selector,
+
+    /// Selector for the exact AVG constraint (without a remainder check)
+    pub s_avg: Selector,
+
+    /// Truncating AVG with a bounded remainder (optional)
+    pub remainder_check: Option<AvgRemainderConfig>,
 }
 
 impl AggregationConfig {
@@ -105,6 +138,10 @@ impl AggregationConfig {
         let sum_col = advice[5];
         let count_col = advice[6];
         let avg_col = advice[7];
+        let s_first = meta.selector();
+        let s_acc = meta.selector();
+        let s_row = meta.selector();
+        let s_avg = meta.selector();
 
         // Enable equality on all advice columns
         meta.enable_equality(value_col);
@@ -117,26 +154,32 @@ impl AggregationConfig {
         meta.enable_equality(avg_col);
 
         // Constraint 1: SUM constraint
-        // Mi = bi · Mi-1 + valuei · (1 - bi)
-        // If bi = 1 (same group): Mi = Mi-1 + valuei
-        // If bi = 0 (new group): Mi = valuei
+        // M0 = value0
+        // Mi = bi-1 · Mi-1 + valuei
+        // If bi-1 = 1 (same group): Mi = Mi-1 + valuei
+        // If bi-1 = 0 (new group): Mi = valuei
+        meta.create_gate("sum_first", |meta| {
+            let s_first = meta.query_selector(s_first);
+            let m_cur = meta.query_advice(accumulator_col, Rotation::cur());
+            let value_cur = meta.query_advice(value_col, Rotation::cur());
+            vec![s_first * (m_cur - value_cur)]
+        });
         meta.create_gate("sum_aggregation", |meta| {
+            let s_acc = meta.query_selector(s_acc);
             let m_cur = meta.query_advice(accumulator_col, Rotation::cur());
             let m_prev = meta.query_advice(accumulator_col, Rotation::prev());
             let value_cur = meta.query_advice(value_col, Rotation::cur());
-            let b_cur = meta.query_advice(binary_marker_col, Rotation::cur());
-
-            // Mi = bi · Mi-1 + valuei · (1 - bi)
-            // Rearranged: Mi - bi · Mi-1 - valuei · (1 - bi) = 0
-            let left = m_cur.clone();
-            let one = Expression::Constant(Field::one());
-            let right = b_cur.clone() * m_prev.clone() + value_cur.clone() * (one - b_cur.clone());
-            vec![left - right]
+            let b_prev = meta.query_advice(binary_marker_col, Rotation::prev());
+
+            // Rearranged: Mi - bi-1 · Mi-1 - valuei = 0
+            let right = b_prev * m_prev + value_cur;
+            vec![s_acc * (m_cur - right)]
         });
 
         // Constraint 2: COUNT constraint
         // counti = endi - starti + 1
         meta.create_gate("count_aggregation", |meta| {
+            let s_row = meta.query_selector(s_row);
             let count_cur = meta.query_advice(count_col, Rotation::cur());
             let start_cur = meta.query_advice(start_idx_col, Rotation::cur());
             let end_cur = meta.query_advice(end_idx_col, Rotation::cur());
@@ -148,18 +191,19 @@ impl AggregationConfig {
             let left = count_cur.clone();
             let diff = end_cur.clone() - start_cur.clone();
             let right = diff + Expression::Constant(Field::one());
-            vec![left - right]
+            vec![s_row * (left - right)]
         });
 
         // Constraint 3: AVG constraint
         // avgi · counti - sumi = 0
         meta.create_gate("avg_aggregation", |meta| {
+            let s_avg = meta.query_selector(s_avg);
             let avg_cur = meta.query_advice(avg_col, Rotation::cur());
             let count_cur = meta.query_advice(count_col, Rotation::cur());
             let sum_cur = meta.query_advice(sum_col, Rotation::cur());
 
             // avgi · counti - sumi = 0
-            vec![avg_cur * count_cur - sum_cur]
+            vec![s_avg * (avg_cur * count_cur - sum_cur)]
         });
 
         Self {
@@ -171,9 +215,79 @@ impl AggregationConfig {
             sum_col,
             count_col,
             avg_col,
+            s_first,
+            s_acc,
+            s_row,
+            s_avg,
+            remainder_check: None,
         }
     }
 
+    /// Truncate AVG to an integer with a remainder bounded by COUNT
+    ///
+    /// Without a remainder check, AVG is the field quotient `sum / count`.
+    /// With it, each row proves `sum = avg · count + r` with `avg` and `r`
+    /// below 2^n and `r < count`, comparing `r` against the row's COUNT cell
+    /// with the less-than gate, so AVG is the truncated integer average for
+    /// any group size. Sums must be below 2^n for the less-than gate's
+    /// bit-width n.
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `remainder_col` - Advice column for the remainder r
+    /// * `less_than` - Less-than gate for the AVG and remainder bounds
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_remainder_check(
+        mut self,
+        meta: &mut ConstraintSystem<Field>,
+        remainder_col: Column<Advice>,
+        less_than: LessThanConfig,
+    ) -> Self {
+        let s_avg = meta.selector();
+        meta.enable_equality(remainder_col);
+
+        // sumi = avgi · counti + ri
+        let (avg_col, count_col, sum_col) = (self.avg_col, self.count_col, self.sum_col);
+        meta.create_gate("avg_remainder", |meta| {
+            let s_avg = meta.query_selector(s_avg);
+            let avg_cur = meta.query_advice(avg_col, Rotation::cur());
+            let count_cur = meta.query_advice(count_col, Rotation::cur());
+            let sum_cur = meta.query_advice(sum_col, Rotation::cur());
+            let remainder_cur = meta.query_advice(remainder_col, Rotation::cur());
+
+            vec![s_avg * (avg_cur * count_cur + remainder_cur - sum_cur)]
+        });
+
+        // The lower bounds compare against the constant -1
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        self.remainder_check = Some(AvgRemainderConfig {
+            remainder_col,
+            s_avg,
+            less_than,
+        });
+        self
+    }
+
+    /// Number of constraints for aggregating `input_len` values
+    ///
+    /// Each value has 1 accumulation, 1 COUNT and 1 AVG constraint, plus
+    /// the 3 less-than comparisons bounding AVG and its remainder if the
+    /// remainder check is enabled.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of aggregated values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        3 * input_len
+            + self
+                .remainder_check
+                .as_ref()
+                .map_or(0, |check| check.less_than.num_constraints(3 * input_len))
+    }
+
     /// Assign values for aggregation gate
     ///
     /// This method:
@@ -186,12 +300,17 @@ impl AggregationConfig {
     /// # Arguments
     /// * `layouter` - Layouter for assigning values
     /// * `values` - Values being aggregated
-    /// * `binary_markers` - Binary markers (1 = same group, 0 = different group)
-    /// * `start_indices` - Start indices of each group
-    /// * `end_indices` - End indices of each group
+    /// * `binary_markers` - Binary markers (1 = next row in the same group,
+    ///   0 = group ends at this row)
+    /// * `start_indices` - Start index of each row's group
+    /// * `end_indices` - End index of each row's group
+    /// * `mode` - Whether to check the accumulators against the group indices
     ///
     /// # Returns
-    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    /// `Ok(AggregationCells)` with the assigned index and accumulator cells
+    /// of each row if assignment succeeds, `Err(Error::Other)` in `Strict`
+    /// mode if the markers, indices and accumulators disagree,
+    /// `Err(Error)` otherwise
     pub fn assign(
         &self,
         layouter: &mut impl Layouter<Field>,
@@ -199,10 +318,11 @@ impl AggregationConfig {
         binary_markers: &[Field],
         start_indices: &[Field],
         end_indices: &[Field],
-    ) -> Result<(), ErrorFront> {
+        mode: ValidationMode,
+    ) -> Result<AggregationCells, ErrorFront> {
         let n = values.len();
         if n == 0 {
-            return Ok(()); // Empty input, nothing to do
+            return Ok(AggregationCells::default()); // Empty input, nothing to do
         }
 
         // Validate inputs
@@ -223,7 +343,7 @@ impl AggregationConfig {
         );
 
         // Compute accumulators M
-        // Mi = bi · Mi-1 + valuei · (1 - bi)
+        // Mi = bi-1 · Mi-1 + valuei
         let mut accumulators = Vec::with_capacity(n);
         if n > 0 {
             // First row: M0 = value0 (assuming new group)
@@ -233,10 +353,10 @@ impl AggregationConfig {
         for i in 1..n {
             let m_prev = accumulators[i - 1];
             let value_cur = values[i];
-            let b_cur = binary_markers[i];
+            let b_prev = binary_markers[i - 1];
 
-            // Mi = bi · Mi-1 + valuei · (1 - bi)
-            let m_cur = b_cur * m_prev + value_cur * (Field::one() - b_cur);
+            // Mi = bi-1 · Mi-1 + valuei
+            let m_cur = b_prev * m_prev + value_cur;
             accumulators.push(m_cur);
         }
 
@@ -250,8 +370,8 @@ impl AggregationConfig {
             if i == 0 || (i > 0 && binary_markers[i - 1] == Field::zero()) {
                 if i > 0 {
                     // End previous group
-                    let start_idx = Self::field_to_usize(start_indices[current_group_start]);
-                    let end_idx = Self::field_to_usize(end_indices[i - 1]);
+                    let start_idx = Self::field_to_usize(start_indices[current_group_start])?;
+                    let end_idx = Self::field_to_usize(end_indices[i - 1])?;
                     groups.push((start_idx, end_idx));
                 }
                 current_group_start = i;
@@ -260,25 +380,42 @@ impl AggregationConfig {
 
         // Add last group
         if n > 0 {
-            let start_idx = Self::field_to_usize(start_indices[current_group_start]);
-            let end_idx = Self::field_to_usize(end_indices[n - 1]);
+            let start_idx = Self::field_to_usize(start_indices[current_group_start])?;
+            let end_idx = Self::field_to_usize(end_indices[n - 1])?;
             groups.push((start_idx, end_idx));
         }
 
+        if mode.is_strict() {
+            Self::check_witness(
+                values,
+                binary_markers,
+                start_indices,
+                end_indices,
+                &accumulators,
+            )?;
+        }
+
         // Compute SUM, COUNT, AVG for each group
         let mut sums = Vec::with_capacity(n);
         let mut counts = Vec::with_capacity(n);
         let mut avgs = Vec::with_capacity(n);
 
         for (start, end) in &groups {
+            if start > end || *end >= n {
+                return Err(ErrorFront::Synthesis);
+            }
+
             // SUM: sum of values in group
             let sum: Field = values[*start..=*end].iter().sum();
 
             // COUNT: count = end - start + 1
             let count = Field::from((end - start + 1) as u64);
 
-            // AVG: avg = sum / count
-            let avg = sum * count.invert().unwrap();
+            // AVG: avg = sum / count, truncated with a remainder check
+            let avg = match &self.remainder_check {
+                Some(_) => Self::truncated_avg(sum, count),
+                None => (sum * count.invert().unwrap(), Field::zero()),
+            };
 
             // Assign to all rows in group
             for _ in *start..=*end {
@@ -289,57 +426,68 @@ impl AggregationConfig {
         }
 
         // Assign all values in a region
-        layouter.assign_region(
+        let (cells, bounds) = layouter.assign_region(
             || "aggregation gate",
             |mut region| {
                 // Assign values
+                let mut value_cells = Vec::with_capacity(n);
                 for (i, &value) in values.iter().enumerate() {
-                    region.assign_advice(
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_acc.enable(&mut region, i)?;
+                    }
+                    self.s_row.enable(&mut region, i)?;
+                    match &self.remainder_check {
+                        Some(check) => check.s_avg.enable(&mut region, i)?,
+                        None => self.s_avg.enable(&mut region, i)?,
+                    }
+                    value_cells.push(region.assign_advice(
                         || format!("value[{}]", i),
                         self.value_col,
                         i,
                         || Value::known(value),
-                    )?;
+                    )?);
                 }
 
                 // Assign binary markers
+                let mut marker_cells = Vec::with_capacity(n);
                 for (i, &marker) in binary_markers.iter().enumerate() {
-                    region.assign_advice(
+                    marker_cells.push(region.assign_advice(
                         || format!("binary_marker[{}]", i),
                         self.binary_marker_col,
                         i,
                         || Value::known(marker),
-                    )?;
+                    )?);
                 }
 
                 // Assign accumulators
+                let mut accumulator_cells = Vec::with_capacity(n);
                 for (i, &acc) in accumulators.iter().enumerate() {
-                    region.assign_advice(
+                    accumulator_cells.push(region.assign_advice(
                         || format!("accumulator[{}]", i),
                         self.accumulator_col,
                         i,
                         || Value::known(acc),
-                    )?;
+                    )?);
                 }
 
-                // Assign start indices
-                for (i, &start) in start_indices.iter().enumerate() {
-                    region.assign_advice(
+                // Assign start/end indices
+                let mut index_cells = Vec::with_capacity(n);
+                for (i, (&start, &end)) in start_indices.iter().zip(end_indices).enumerate() {
+                    let start_cell = region.assign_advice(
                         || format!("start_idx[{}]", i),
                         self.start_idx_col,
                         i,
                         || Value::known(start),
                     )?;
-                }
-
-                // Assign end indices
-                for (i, &end) in end_indices.iter().enumerate() {
-                    region.assign_advice(
+                    let end_cell = region.assign_advice(
                         || format!("end_idx[{}]", i),
                         self.end_idx_col,
                         i,
                         || Value::known(end),
                     )?;
+                    index_cells.push((start_cell, end_cell));
                 }
 
                 // Assign SUM results
@@ -353,28 +501,139 @@ impl AggregationConfig {
                 }
 
                 // Assign COUNT results
+                let mut count_cells = Vec::with_capacity(n);
                 for (i, &count) in counts.iter().enumerate() {
-                    region.assign_advice(
+                    count_cells.push(region.assign_advice(
                         || format!("count[{}]", i),
                         self.count_col,
                         i,
                         || Value::known(count),
-                    )?;
+                    )?);
                 }
 
-                // Assign AVG results
-                for (i, &avg) in avgs.iter().enumerate() {
-                    region.assign_advice(
+                // Assign AVG results, and their remainders with a remainder check
+                let mut bounds = vec![];
+                for (i, (&(avg, remainder), count_cell)) in
+                    avgs.iter().zip(&count_cells).enumerate()
+                {
+                    let avg_cell = region.assign_advice(
                         || format!("avg[{}]", i),
                         self.avg_col,
                         i,
                         || Value::known(avg),
                     )?;
+                    if let Some(check) = &self.remainder_check {
+                        let remainder_cell = region.assign_advice(
+                            || format!("remainder[{}]", i),
+                            check.remainder_col,
+                            i,
+                            || Value::known(remainder),
+                        )?;
+                        bounds.push((
+                            (avg, avg_cell),
+                            (remainder, remainder_cell),
+                            (counts[i], count_cell.clone()),
+                        ));
+                    }
                 }
 
-                Ok(())
+                Ok((
+                    AggregationCells {
+                        values: value_cells,
+                        markers: marker_cells,
+                        indices: index_cells,
+                        counts: count_cells,
+                        accumulators: accumulator_cells,
+                    },
+                    bounds,
+                ))
             },
-        )
+        )?;
+
+        if let Some(check) = &self.remainder_check {
+            for (avg, remainder, count) in bounds {
+                check.assign_bounds(layouter, avg, remainder, count)?;
+            }
+        }
+        Ok(cells)
+    }
+
+    /// Integer AVG of a group as (quotient, remainder)
+    ///
+    /// Sums or counts that aren't integers below 2^64 give `(0, sum)`, which
+    /// the remainder bound then rejects.
+    fn truncated_avg(sum: Field, count: Field) -> (Field, Field) {
+        match (FieldUtils::to_u64(&sum), FieldUtils::to_u64(&count)) {
+            (Some(sum), Some(count)) if count > 0 => {
+                (Field::from(sum / count), Field::from(sum % count))
+            }
+            _ => (Field::zero(), sum),
+        }
+    }
+
+    /// Check the aggregation witness for `Strict` mode
+    ///
+    /// Every group ended by a zero binary marker must accumulate the SUM of
+    /// the rows its start/end indices span, and every row of the group must
+    /// carry the group's first and last row as its indices.
+    ///
+    /// # Returns
+    /// `Ok(())` if the witness is consistent, `Err(Error::Other)` naming the
+    /// first inconsistent row otherwise
+    fn check_witness(
+        values: &[Field],
+        binary_markers: &[Field],
+        start_indices: &[Field],
+        end_indices: &[Field],
+        accumulators: &[Field],
+    ) -> Result<(), ErrorFront> {
+        if let Some(i) = binary_markers
+            .iter()
+            .position(|b| *b != Field::zero() && *b != Field::one())
+        {
+            return Err(ErrorFront::Other(format!(
+                "binary marker at row {} is not 0 or 1",
+                i
+            )));
+        }
+        if binary_markers.last() != Some(&Field::zero()) {
+            return Err(ErrorFront::Other(
+                "last binary marker does not end the group".to_string(),
+            ));
+        }
+
+        let mut group_start = 0;
+        for (i, marker) in binary_markers.iter().enumerate() {
+            if *marker != Field::zero() {
+                continue;
+            }
+
+            let (start, end) = (
+                Self::field_to_usize(start_indices[i])?,
+                Self::field_to_usize(end_indices[i])?,
+            );
+            let indexed_sum = (start <= end && end < values.len())
+                .then(|| values[start..=end].iter().sum::<Field>());
+            if indexed_sum != Some(accumulators[i]) {
+                return Err(ErrorFront::Other(format!(
+                    "accumulator at row {} is not the SUM of its group's rows",
+                    i
+                )));
+            }
+
+            let (first, last) = (Field::from(group_start as u64), Field::from(i as u64));
+            if let Some(j) =
+                (group_start..=i).find(|&j| start_indices[j] != first || end_indices[j] != last)
+            {
+                return Err(ErrorFront::Other(format!(
+                    "group indices at row {} do not match the binary markers",
+                    j
+                )));
+            }
+            group_start = i + 1;
+        }
+
+        Ok(())
     }
 
     /// Compute SUM for a group
@@ -446,28 +705,206 @@ impl AggregationConfig {
 
     /// Convert field value to usize for index operations
     ///
+    /// Indices must fit in 64 bits; larger values are rejected rather than
+    /// truncated.
+    ///
     /// # Arguments
     /// * `value` - Field value
     ///
     /// # Returns
-    /// usize representation
-    fn field_to_usize(value: Field) -> usize {
-        // Convert field to bytes and extract first 8 bytes as u64, then to usize
-        let bytes = value.to_bytes();
-        let mut u64_bytes = [0u8; 8];
-        for i in 0..8.min(bytes.len()) {
-            u64_bytes[i] = bytes[i];
+    /// `Ok(usize)` if the value fits in 64 bits, `Err(Error::Synthesis)` otherwise
+    fn field_to_usize(value: Field) -> Result<usize, ErrorFront> {
+        FieldUtils::to_u64(&value)
+            .map(|v| v as usize)
+            .ok_or(ErrorFront::Synthesis)
+    }
+}
+
+/// Cells assigned by the aggregation gate
+#[derive(Debug, Clone, Default)]
+pub struct AggregationCells {
+    /// Value cell of each row
+    pub values: Vec<AssignedCell<Field, Field>>,
+
+    /// Binary marker cell of each row
+    pub markers: Vec<AssignedCell<Field, Field>>,
+
+    /// (start index, end index) cells of each row
+    pub indices: Vec<(AssignedCell<Field, Field>, AssignedCell<Field, Field>)>,
+
+    /// COUNT cell of each row, holding the count of its group
+    pub counts: Vec<AssignedCell<Field, Field>>,
+
+    /// Accumulator cell of each row; the last row of a group holds its SUM
+    pub accumulators: Vec<AssignedCell<Field, Field>>,
+}
+
+/// Configuration for truncating AVG with a bounded remainder
+///
+/// See [`AggregationConfig::with_remainder_check`].
+#[derive(Debug, Clone)]
+pub struct AvgRemainderConfig {
+    /// Column for the AVG remainder r
+    pub remainder_col: Column<Advice>,
+
+    /// Selector for the truncating AVG constraint (sum = avg · count + r)
+    pub s_avg: Selector,
+
+    /// Less-than gate bounding AVG and its remainder
+    pub less_than: LessThanConfig,
+}
+
+impl AvgRemainderConfig {
+    /// Prove `0 <= avg < 2^n`, `0 <= r < 2^n` and `r < count` for one row
+    ///
+    /// The lower bounds are comparisons `-1 < x` with the constant `-1`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `avg` - AVG value and its cell in the aggregation region
+    /// * `remainder` - Remainder value and its cell in the aggregation region
+    /// * `count` - COUNT value and its cell in the aggregation region
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    fn assign_bounds(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        avg: (Field, AssignedCell<Field, Field>),
+        remainder: (Field, AssignedCell<Field, Field>),
+        count: (Field, AssignedCell<Field, Field>),
+    ) -> Result<(), ErrorFront> {
+        let minus_one = -Field::one();
+
+        let (avg_low, avg_copy) = self.less_than.assign(layouter, minus_one, avg.0)?;
+        let (r_low, r_copy) = self.less_than.assign(layouter, minus_one, remainder.0)?;
+        let (r_below, count_copy) = self.less_than.assign(layouter, remainder.0, count.0)?;
+
+        layouter.assign_region(
+            || "avg remainder bounds",
+            |mut region| {
+                region.constrain_constant(avg_low.cell(), minus_one)?;
+                region.constrain_constant(r_low.cell(), minus_one)?;
+                region.constrain_equal(avg_copy.cell(), avg.1.cell())?;
+                region.constrain_equal(r_copy.cell(), remainder.1.cell())?;
+                region.constrain_equal(r_below.cell(), remainder.1.cell())?;
+                region.constrain_equal(count_copy.cell(), count.1.cell())
+            },
+        )
+    }
+}
+
+/// Configuration for public MIN/MAX outputs
+///
+/// Binds the first and last cells of a sorted group to instance cells.
+#[derive(Debug, Clone)]
+pub struct MinMaxConfig {
+    /// Column for the MIN result
+    pub min_col: Column<Advice>,
+
+    /// Column for the MAX result
+    pub max_col: Column<Advice>,
+
+    /// Instance column exposing MIN and MAX
+    pub instance: Column<Instance>,
+}
+
+impl MinMaxConfig {
+    /// Configure the MIN/MAX output columns
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 2 columns)
+    /// * `instance` - Instance column exposing MIN and MAX
+    ///
+    /// # Returns
+    /// `MinMaxConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        advice: &[Column<Advice>],
+        instance: Column<Instance>,
+    ) -> Self {
+        assert!(
+            advice.len() >= 2,
+            "Need at least 2 advice columns (min, max)"
+        );
+
+        let min_col = advice[0];
+        let max_col = advice[1];
+
+        // Enable equality for copies from the sort output and to the instance
+        meta.enable_equality(min_col);
+        meta.enable_equality(max_col);
+        meta.enable_equality(instance);
+
+        Self {
+            min_col,
+            max_col,
+            instance,
         }
-        u64::from_le_bytes(u64_bytes) as usize
+    }
+
+    /// Number of constraints for the MIN/MAX of `input_len` sorted values
+    ///
+    /// MIN and MAX each have 1 copy constraint from the sorted group and 1
+    /// copy constraint to the instance.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of sorted values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        if input_len == 0 {
+            return 0;
+        }
+        4
+    }
+
+    /// Assign MIN and MAX and bind them to public instance rows
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `sorted_cells` - Assigned cells of the group, in ascending order
+    /// * `min_row` - Row of the instance column holding MIN
+    /// * `max_row` - Row of the instance column holding MAX
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error::Synthesis)` if the group
+    /// is empty
+    pub fn assign_public(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        sorted_cells: &[AssignedCell<Field, Field>],
+        min_row: usize,
+        max_row: usize,
+    ) -> Result<(), ErrorFront> {
+        let (Some(first), Some(last)) = (sorted_cells.first(), sorted_cells.last()) else {
+            return Err(ErrorFront::Synthesis);
+        };
+
+        let (min_cell, max_cell) = layouter.assign_region(
+            || "min/max output",
+            |mut region| {
+                let min_cell = first.copy_advice(|| "min", &mut region, self.min_col, 0)?;
+                let max_cell = last.copy_advice(|| "max", &mut region, self.max_col, 0)?;
+                Ok((min_cell, max_cell))
+            },
+        )?;
+
+        layouter.constrain_instance(min_cell.cell(), self.instance, min_row)?;
+        layouter.constrain_instance(max_cell.cell(), self.instance, max_row)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::circuit::NzengiCircuit;
+    use crate::test_util::{fields, group_witness, run_mock, run_mock_with_instance};
     use halo2_proofs::{
         circuit::{Layouter, SimpleFloorPlanner, Value},
-        dev::MockProver,
+        dev::{FailureLocation, MockProver, VerifyFailure},
         plonk::Circuit,
     };
 
@@ -520,18 +957,18 @@ mod tests {
 
     #[test]
     fn test_sum_constraint() {
-        // Test SUM constraint: Mi = bi · Mi-1 + valuei · (1 - bi)
+        // Test SUM constraint: Mi = bi-1 · Mi-1 + valuei
         let m_prev = Field::from(5u64);
         let value_cur = Field::from(3u64);
         let b_same = Field::one(); // same group
         let b_new = Field::zero(); // new group
 
         // Same group: Mi = Mi-1 + valuei
-        let m_same = b_same * m_prev + value_cur * (Field::one() - b_same);
+        let m_same = b_same * m_prev + value_cur;
         assert_eq!(m_same, Field::from(8u64), "Same group: M = 5 + 3 = 8");
 
         // New group: Mi = valuei
-        let m_new = b_new * m_prev + value_cur * (Field::one() - b_new);
+        let m_new = b_new * m_prev + value_cur;
         assert_eq!(m_new, Field::from(3u64), "New group: M = 3");
     }
 
@@ -561,11 +998,13 @@ mod tests {
         binary_markers: Vec<Field>,
         start_indices: Vec<Field>,
         end_indices: Vec<Field>,
+        mode: ValidationMode,
     }
 
     impl Circuit<Field> for TestCircuit {
         type Config = AggregationConfig;
         type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
 
         fn without_witnesses(&self) -> Self {
             Self::default()
@@ -588,6 +1027,7 @@ mod tests {
                     &self.binary_markers,
                     &self.start_indices,
                     &self.end_indices,
+                    self.mode,
                 )?;
             }
             Ok(())
@@ -611,6 +1051,7 @@ mod tests {
             binary_markers,
             start_indices,
             end_indices,
+            mode: ValidationMode::Strict,
         };
 
         let k = 10; // 2^10 = 1024 rows
@@ -630,10 +1071,187 @@ mod tests {
             binary_markers: vec![],
             start_indices: vec![],
             end_indices: vec![],
+            mode: ValidationMode::Fast,
         };
 
         let k = 10;
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()), "Empty circuit should verify");
     }
+
+    #[test]
+    fn test_aggregation_strict_mode() {
+        // The markers end a group at row 1, but the indices span all rows,
+        // so the accumulator at row 1 misses row 2
+        let circuit = TestCircuit {
+            values: fields(&[1, 2, 3]),
+            binary_markers: fields(&[1, 0, 0]),
+            start_indices: fields(&[0, 0, 0]),
+            end_indices: fields(&[2, 2, 2]),
+            mode: ValidationMode::Strict,
+        };
+        assert!(matches!(
+            MockProver::run(10, &circuit, vec![]),
+            Err(ErrorFront::Other(msg)) if msg.contains("accumulator at row 1")
+        ));
+
+        // Fast mode skips the check and leaves the witness to the constraints
+        let circuit = TestCircuit {
+            mode: ValidationMode::Fast,
+            ..circuit
+        };
+        assert!(MockProver::run(10, &circuit, vec![]).is_ok());
+
+        // Indices that disagree with the markers are caught too
+        let circuit = TestCircuit {
+            values: fields(&[1, 2, 3]),
+            binary_markers: fields(&[1, 0, 0]),
+            start_indices: fields(&[1, 0, 2]),
+            end_indices: fields(&[1, 1, 2]),
+            mode: ValidationMode::Strict,
+        };
+        assert!(matches!(
+            MockProver::run(10, &circuit, vec![]),
+            Err(ErrorFront::Other(msg)) if msg.contains("group indices at row 0")
+        ));
+    }
+
+    /// Test circuit for AVG with a remainder check
+    #[derive(Default)]
+    struct RemainderTestCircuit {
+        values: Vec<Field>,
+        /// (sum, avg, remainder) assigned for `count = 3` in place of the
+        /// witness `assign` derives
+        forged: Option<(u64, u64, u64)>,
+    }
+
+    impl Circuit<Field> for RemainderTestCircuit {
+        type Config = AggregationConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..12).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let less_than = LessThanConfig::configure(meta, &advice[9..], 8);
+            AggregationConfig::configure(meta, &advice[..8])
+                .with_remainder_check(meta, advice[8], less_than)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let Some((sum, avg, remainder)) = self.forged else {
+                let (markers, starts, ends) = group_witness(&vec![0; self.values.len()]);
+                config.assign(
+                    &mut layouter,
+                    &self.values,
+                    &markers,
+                    &starts,
+                    &ends,
+                    ValidationMode::Fast,
+                )?;
+                return Ok(());
+            };
+
+            let check = config.remainder_check.as_ref().unwrap();
+            let (sum, avg, remainder) =
+                (Field::from(sum), Field::from(avg), Field::from(remainder));
+            let count = Field::from(3u64);
+            let (avg_cell, remainder_cell, count_cell) = layouter.assign_region(
+                || "forged avg",
+                |mut region| {
+                    check.s_avg.enable(&mut region, 0)?;
+                    region.assign_advice(|| "sum", config.sum_col, 0, || Value::known(sum))?;
+                    Ok((
+                        region.assign_advice(|| "avg", config.avg_col, 0, || Value::known(avg))?,
+                        region.assign_advice(
+                            || "remainder",
+                            check.remainder_col,
+                            0,
+                            || Value::known(remainder),
+                        )?,
+                        region.assign_advice(
+                            || "count",
+                            config.count_col,
+                            0,
+                            || Value::known(count),
+                        )?,
+                    ))
+                },
+            )?;
+            check.assign_bounds(
+                &mut layouter,
+                (avg, avg_cell),
+                (remainder, remainder_cell),
+                (count, count_cell),
+            )
+        }
+    }
+
+    #[test]
+    fn test_avg_remainder_bound() {
+        // sum = 10, count = 3: avg = 3, r = 1
+        let circuit = RemainderTestCircuit {
+            values: fields(&[2, 3, 5]),
+            forged: None,
+        };
+        assert_eq!(run_mock(&circuit, 10), Ok(()));
+        assert_eq!(
+            AggregationConfig::truncated_avg(Field::from(10u64), Field::from(3u64)),
+            (Field::from(3u64), Field::from(1u64))
+        );
+
+        let forged = |sum: u64, avg: u64, remainder: u64| RemainderTestCircuit {
+            values: vec![],
+            forged: Some((sum, avg, remainder)),
+        };
+        assert_eq!(run_mock(&forged(10, 3, 1), 10), Ok(()));
+        assert_eq!(run_mock(&forged(12, 4, 0), 10), Ok(()));
+
+        // r = 3 >= count for sum = 12 = 3 · 3 + 3: avg and r are in range
+        // and satisfy the quotient constraint, so only `r < count` fails,
+        // in the third comparison after the forged region
+        let failures = run_mock(&forged(12, 3, 3), 10).unwrap_err();
+        assert!(!failures.is_empty());
+        for failure in failures {
+            assert!(
+                matches!(
+                    &failure,
+                    VerifyFailure::ConstraintNotSatisfied {
+                        location: FailureLocation::InRegion { region, .. },
+                        ..
+                    } if *region == (3, "less than").into()
+                ),
+                "{}",
+                failure
+            );
+        }
+
+        // r = 4 >= count, with an integer avg = 2 satisfying sum = avg · count + r
+        assert!(run_mock(&forged(10, 2, 4), 10).is_err());
+    }
+
+    #[test]
+    fn test_min_max_public_output() {
+        let circuit = NzengiCircuit::new()
+            .with_table(3, vec![fields(&[7, 3, 9])])
+            .with_sort(fields(&[7, 3, 9]), fields(&[3, 7, 9]))
+            .with_minmax_column(0)
+            .with_minmax_output(Field::from(3), Field::from(9));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![fields(&[3, 9])]),
+            Ok(())
+        );
+
+        // Claiming a smaller MIN or larger MAX than the data fails
+        for claimed in [[2, 9], [3, 10]] {
+            assert!(run_mock_with_instance(&circuit, 10, vec![fields(&claimed)]).is_err());
+        }
+    }
 }
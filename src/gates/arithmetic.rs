@@ -0,0 +1,715 @@
+//! Arithmetic gate for row-level computed columns
+//!
+//! This module provides a generic arithmetic gate that verifies:
+//! 1. Addition: out = a + b
+//! 2. Subtraction: out = a - b
+//! 3. Multiplication: out = a · b
+//! 4. Integer division: out = a / b
+//! 5. Modulo: out = a % b
+//!
+//! Unlike the specialized gates, this gate backs general expressions such as
+//! computed projections (`l_extendedprice * (1 - l_discount)`) and derived
+//! filters. Each row holds one operation; a selector per operator decides
+//! which constraint applies to that row.
+//!
+//! # Constraints
+//!
+//! - Add constraint: s_add · (a + b - out) = 0
+//! - Sub constraint: s_sub · (a - b - out) = 0
+//! - Mul constraint: s_mul · (a · b - out) = 0
+//! - Div constraint: s_div · (out · b + aux - a) = 0, with remainder aux
+//! - Mod constraint: s_mod · (aux · b + out - a) = 0, with quotient aux
+//!
+//! Division and modulo rows additionally prove `0 <= q < 2^64`,
+//! `0 <= r < 2^64` and `r < b` for the quotient q and remainder r with the
+//! less-than gate. With those bounds `a = q · b + r` holds over the integers,
+//! so q and r are the unique unsigned quotient and remainder.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::arithmetic::ArithmeticConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 4];
+//!
+//! let config = ArithmeticConfig::configure(&mut meta, &advice);
+//! ```
+
+use crate::field::FieldUtils;
+use crate::gates::less_than::LessThanConfig;
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// Bit-width bound on the quotient and remainder of division rows
+const DIVISION_BITS: usize = 64;
+
+/// Arithmetic operator supported by the gate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Addition (out = a + b)
+    Add,
+
+    /// Subtraction (out = a - b)
+    Sub,
+
+    /// Multiplication (out = a · b)
+    Mul,
+
+    /// Unsigned integer division (out = a / b)
+    Div,
+
+    /// Unsigned integer remainder (out = a % b)
+    Mod,
+}
+
+impl Op {
+    /// Apply the operator to two field elements
+    ///
+    /// Division and modulo treat the operands as unsigned integers below
+    /// 2^64. They return 0 if an operand is out of range or `b` is 0; the
+    /// gate has no valid witness for such rows.
+    pub fn apply(&self, a: Field, b: Field) -> Field {
+        match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div | Op::Mod => {
+                let result = match (FieldUtils::to_u64(&a), FieldUtils::to_u64(&b)) {
+                    (Some(a), Some(b)) if *self == Op::Div => a.checked_div(b),
+                    (Some(a), Some(b)) => a.checked_rem(b),
+                    _ => None,
+                };
+                Field::from(result.unwrap_or(0))
+            }
+        }
+    }
+}
+
+/// Operand of a row of an expression, by where its value is copied from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// A constant
+    Constant(Field),
+
+    /// A cell of the row the expression is evaluated on, by column index
+    Column(usize),
+
+    /// The output of an earlier row of the expression, by index
+    Row(usize),
+}
+
+/// Configuration for arithmetic gate
+///
+/// This gate verifies that each row's output equals the selected
+/// operation applied to its two inputs.
+#[derive(Debug, Clone)]
+pub struct ArithmeticConfig {
+    /// Column for left operand a
+    pub a: Column<Advice>,
+
+    /// Column for right operand b
+    pub b: Column<Advice>,
+
+    /// Column for output
+    pub out: Column<Advice>,
+
+    /// Column for the complementary division result (remainder for Div,
+    /// quotient for Mod)
+    pub aux: Column<Advice>,
+
+    /// Selector enabling the addition constraint
+    pub s_add: Selector,
+
+    /// Selector enabling the subtraction constraint
+    pub s_sub: Selector,
+
+    /// Selector enabling the multiplication constraint
+    pub s_mul: Selector,
+
+    /// Selector enabling the division constraint
+    pub s_div: Selector,
+
+    /// Selector enabling the modulo constraint
+    pub s_mod: Selector,
+
+    /// Less-than gate bounding quotients and remainders
+    pub less_than: LessThanConfig,
+
+    /// Fixed column holding the constant lower bound of those comparisons
+    pub constant: Column<Fixed>,
+}
+
+impl ArithmeticConfig {
+    /// Configure the arithmetic gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 4: a, b, out, aux)
+    ///
+    /// # Returns
+    /// `ArithmeticConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 4,
+            "Need at least 4 advice columns (a, b, out, aux)"
+        );
+
+        // Assign columns
+        let a = advice[0];
+        let b = advice[1];
+        let out = advice[2];
+        let aux = advice[3];
+
+        // Enable equality on all advice columns
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(out);
+        meta.enable_equality(aux);
+
+        let s_add = meta.selector();
+        let s_sub = meta.selector();
+        let s_mul = meta.selector();
+        let s_div = meta.selector();
+        let s_mod = meta.selector();
+
+        // Constraint 1: out = a + b
+        meta.create_gate("arithmetic_add", |meta| {
+            let s = meta.query_selector(s_add);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![s * (a + b - out)]
+        });
+
+        // Constraint 2: out = a - b
+        meta.create_gate("arithmetic_sub", |meta| {
+            let s = meta.query_selector(s_sub);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![s * (a - b - out)]
+        });
+
+        // Constraint 3: out = a · b
+        meta.create_gate("arithmetic_mul", |meta| {
+            let s = meta.query_selector(s_mul);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+
+            vec![s * (a * b - out)]
+        });
+
+        // Constraint 4: a = out · b + aux (quotient out, remainder aux)
+        meta.create_gate("arithmetic_div", |meta| {
+            let s = meta.query_selector(s_div);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let aux = meta.query_advice(aux, Rotation::cur());
+
+            vec![s * (out * b + aux - a)]
+        });
+
+        // Constraint 5: a = aux · b + out (quotient aux, remainder out)
+        meta.create_gate("arithmetic_mod", |meta| {
+            let s = meta.query_selector(s_mod);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let aux = meta.query_advice(aux, Rotation::cur());
+
+            vec![s * (aux * b + out - a)]
+        });
+
+        // Bounds on quotient and remainder reuse the first three columns
+        let less_than = LessThanConfig::configure(meta, &advice[..3], DIVISION_BITS);
+
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        Self {
+            a,
+            b,
+            out,
+            aux,
+            s_add,
+            s_sub,
+            s_mul,
+            s_div,
+            s_mod,
+            less_than,
+            constant,
+        }
+    }
+
+    /// Number of constraints for `input_len` add, sub or mul operations
+    ///
+    /// Each operation has 1 constraint. Division and modulo rows add the
+    /// less-than comparisons bounding their quotient and remainder (see
+    /// [`ArithmeticConfig::num_division_constraints`]).
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of operations
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        input_len
+    }
+
+    /// Number of constraints for `input_len` division or modulo operations
+    ///
+    /// Each operation has 1 constraint and 3 less-than comparisons
+    /// (`-1 < q`, `-1 < r`, `r < b`).
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of division or modulo operations
+    pub fn num_division_constraints(&self, input_len: usize) -> usize {
+        input_len + self.less_than.num_constraints(3 * input_len)
+    }
+
+    /// Evaluate the rows of an expression on the cells of a row
+    ///
+    /// # Arguments
+    /// * `rows` - Rows of the expression, in evaluation order
+    /// * `cells` - Value of each column of the row
+    ///
+    /// # Returns
+    /// `Some(ops)` with the `(a, op, b, out)` of each row, `None` if an
+    /// operand reads a missing column or a row that isn't an earlier one
+    pub fn evaluate(
+        rows: &[(Source, Op, Source)],
+        cells: &[Field],
+    ) -> Option<Vec<(Field, Op, Field, Field)>> {
+        let mut ops: Vec<(Field, Op, Field, Field)> = Vec::with_capacity(rows.len());
+        for &(a, op, b) in rows {
+            let value = |source| match source {
+                Source::Constant(c) => Some(c),
+                Source::Column(idx) => cells.get(idx).copied(),
+                Source::Row(idx) => ops.get(idx).map(|row| row.3),
+            };
+            let (a, b) = (value(a)?, value(b)?);
+            ops.push((a, op, b, op.apply(a, b)));
+        }
+        Some(ops)
+    }
+
+    /// Assign the rows of an expression evaluated on the cells of a row
+    ///
+    /// Each operand is copied from its source: constants are fixed, column
+    /// operands equal the given cells, and row operands the output of the
+    /// earlier row, so the output is the expression's value on the cells.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `rows` - Rows of the expression, in evaluation order
+    /// * `cells` - Value and cell of each column of the row
+    ///
+    /// # Returns
+    /// `Ok(out)` with the output cell of the last row,
+    /// `Err(Error::Synthesis)` if the expression has no rows or an operand
+    /// has no source
+    pub fn assign_expression(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        rows: &[(Source, Op, Source)],
+        cells: &[(Field, AssignedCell<Field, Field>)],
+    ) -> Result<AssignedCell<Field, Field>, ErrorFront> {
+        let values: Vec<Field> = cells.iter().map(|(value, _)| *value).collect();
+        let ops = Self::evaluate(rows, &values).ok_or(ErrorFront::Synthesis)?;
+        let assigned = self.assign(layouter, &ops)?;
+
+        layouter.assign_region(
+            || "arithmetic operands",
+            |mut region| {
+                for (&(a, _, b), [a_cell, b_cell, _]) in rows.iter().zip(&assigned) {
+                    for (source, cell) in [(a, a_cell), (b, b_cell)] {
+                        match source {
+                            Source::Constant(c) => region.constrain_constant(cell.cell(), c)?,
+                            Source::Column(idx) => {
+                                region.constrain_equal(cell.cell(), cells[idx].1.cell())?
+                            }
+                            Source::Row(idx) => {
+                                region.constrain_equal(cell.cell(), assigned[idx][2].cell())?
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        assigned
+            .last()
+            .map(|[_, _, out]| out.clone())
+            .ok_or(ErrorFront::Synthesis)
+    }
+
+    /// Assign values for arithmetic gate
+    ///
+    /// Each operation occupies one row and enables the selector of its operator.
+    /// Division and modulo rows are followed by the bound checks on their
+    /// quotient and remainder.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `ops` - Operations as `(a, op, b, out)` tuples
+    ///
+    /// # Returns
+    /// `Ok(cells)` with the `[a, b, out]` cells of each operation,
+    /// `Err(Error)` otherwise
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        ops: &[(Field, Op, Field, Field)],
+    ) -> Result<Vec<[AssignedCell<Field, Field>; 3]>, ErrorFront> {
+        if ops.is_empty() {
+            return Ok(vec![]); // Empty input, nothing to do
+        }
+
+        let (cells, divisions) = layouter.assign_region(
+            || "arithmetic gate",
+            |mut region| {
+                let mut cells = vec![];
+                // (quotient, remainder, divisor) of each division row
+                let mut divisions = vec![];
+
+                for (i, &(a, op, b, out)) in ops.iter().enumerate() {
+                    match op {
+                        Op::Add => self.s_add.enable(&mut region, i)?,
+                        Op::Sub => self.s_sub.enable(&mut region, i)?,
+                        Op::Mul => self.s_mul.enable(&mut region, i)?,
+                        Op::Div => self.s_div.enable(&mut region, i)?,
+                        Op::Mod => self.s_mod.enable(&mut region, i)?,
+                    }
+
+                    let a_cell = region.assign_advice(
+                        || format!("a[{}]", i),
+                        self.a,
+                        i,
+                        || Value::known(a),
+                    )?;
+                    let b_cell = region.assign_advice(
+                        || format!("b[{}]", i),
+                        self.b,
+                        i,
+                        || Value::known(b),
+                    )?;
+                    let out_cell = region.assign_advice(
+                        || format!("out[{}]", i),
+                        self.out,
+                        i,
+                        || Value::known(out),
+                    )?;
+                    cells.push([a_cell, b_cell.clone(), out_cell.clone()]);
+
+                    // The complementary result: r = a - q · b, q = (a - r) / b
+                    let aux = match op {
+                        Op::Div => a - out * b,
+                        Op::Mod => (a - out) * b.invert().unwrap_or(Field::zero()),
+                        _ => continue,
+                    };
+                    let aux_cell = region.assign_advice(
+                        || format!("aux[{}]", i),
+                        self.aux,
+                        i,
+                        || Value::known(aux),
+                    )?;
+
+                    let ((q, q_cell), (r, r_cell)) = if op == Op::Div {
+                        ((out, out_cell), (aux, aux_cell))
+                    } else {
+                        ((aux, aux_cell), (out, out_cell))
+                    };
+                    divisions.push([(q, q_cell), (r, r_cell), (b, b_cell)]);
+                }
+
+                Ok((cells, divisions))
+            },
+        )?;
+
+        for [q, r, b] in divisions {
+            self.assign_division_bounds(layouter, q, r, b)?;
+        }
+
+        Ok(cells)
+    }
+
+    /// Prove `0 <= q < 2^64`, `0 <= r < 2^64` and `r < b` for one division row
+    ///
+    /// The lower bounds are comparisons `-1 < x` with the constant `-1`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `q` - Quotient value and its cell in the arithmetic region
+    /// * `r` - Remainder value and its cell in the arithmetic region
+    /// * `b` - Divisor value and its cell in the arithmetic region
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    fn assign_division_bounds(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        q: (Field, AssignedCell<Field, Field>),
+        r: (Field, AssignedCell<Field, Field>),
+        b: (Field, AssignedCell<Field, Field>),
+    ) -> Result<(), ErrorFront> {
+        let minus_one = -Field::one();
+
+        let (q_low, q_copy) = self.less_than.assign(layouter, minus_one, q.0)?;
+        let (r_low, r_copy) = self.less_than.assign(layouter, minus_one, r.0)?;
+        let (r_below, b_copy) = self.less_than.assign(layouter, r.0, b.0)?;
+
+        layouter.assign_region(
+            || "division bounds",
+            |mut region| {
+                region.constrain_constant(q_low.cell(), minus_one)?;
+                region.constrain_constant(r_low.cell(), minus_one)?;
+                region.constrain_equal(q_copy.cell(), q.1.cell())?;
+                region.constrain_equal(r_copy.cell(), r.1.cell())?;
+                region.constrain_equal(r_below.cell(), r.1.cell())?;
+                region.constrain_equal(b_copy.cell(), b.1.cell())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, Instance},
+    };
+
+    /// Test circuit for arithmetic gate
+    #[derive(Default)]
+    struct TestCircuit {
+        ops: Vec<(Field, Op, Field, Field)>,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = ArithmeticConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..4).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            ArithmeticConfig::configure(meta, &advice)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            config.assign(&mut layouter, &self.ops).map(|_| ())
+        }
+    }
+
+    /// Test circuit evaluating an expression on cells, with a public output
+    #[derive(Default)]
+    struct ExpressionCircuit {
+        rows: Vec<(Source, Op, Source)>,
+        cells: Vec<Field>,
+    }
+
+    impl Circuit<Field> for ExpressionCircuit {
+        type Config = (ArithmeticConfig, Column<Advice>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..4).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let cells = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(cells);
+            meta.enable_equality(instance);
+            (ArithmeticConfig::configure(meta, &advice), cells, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, column, instance): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let cells = layouter.assign_region(
+                || "cells",
+                |mut region| {
+                    self.cells
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &value)| {
+                            let cell = region.assign_advice(
+                                || format!("cell[{}]", i),
+                                column,
+                                i,
+                                || Value::known(value),
+                            )?;
+                            Ok((value, cell))
+                        })
+                        .collect::<Result<Vec<_>, ErrorFront>>()
+                },
+            )?;
+            let out = config.assign_expression(&mut layouter, &self.rows, &cells)?;
+            layouter.constrain_instance(out.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_op_apply() {
+        let a = Field::from(7u64);
+        let b = Field::from(5u64);
+        assert_eq!(Op::Add.apply(a, b), Field::from(12u64));
+        assert_eq!(Op::Sub.apply(a, b), Field::from(2u64));
+        assert_eq!(Op::Mul.apply(a, b), Field::from(35u64));
+        assert_eq!(Op::Div.apply(a, b), Field::from(1u64));
+        assert_eq!(Op::Mod.apply(a, b), Field::from(2u64));
+        assert_eq!(Op::Div.apply(a, Field::zero()), Field::zero());
+    }
+
+    #[test]
+    fn test_arithmetic_mul_accepted() {
+        let circuit = TestCircuit {
+            ops: vec![(
+                Field::from(3u64),
+                Op::Mul,
+                Field::from(4u64),
+                Field::from(12u64),
+            )],
+        };
+
+        let k = 4;
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "3 * 4 = 12 should verify");
+    }
+
+    #[test]
+    fn test_arithmetic_mul_wrong_product_rejected() {
+        let circuit = TestCircuit {
+            ops: vec![(
+                Field::from(3u64),
+                Op::Mul,
+                Field::from(4u64),
+                Field::from(13u64),
+            )],
+        };
+
+        let k = 4;
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err(), "3 * 4 = 13 should be rejected");
+    }
+
+    #[test]
+    fn test_arithmetic_mixed_ops() {
+        // 1 - 3 = -2, then 100 * -2 = -200
+        let diff = Op::Sub.apply(Field::from(1u64), Field::from(3u64));
+        let product = Op::Mul.apply(Field::from(100u64), diff);
+        let circuit = TestCircuit {
+            ops: vec![
+                (Field::from(1u64), Op::Sub, Field::from(3u64), diff),
+                (Field::from(100u64), Op::Mul, diff, product),
+                (
+                    Field::from(2u64),
+                    Op::Add,
+                    Field::from(2u64),
+                    Field::from(4u64),
+                ),
+            ],
+        };
+
+        let k = 4;
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    fn run_single(a: u64, op: Op, b: u64, out: Field) -> bool {
+        let circuit = TestCircuit {
+            ops: vec![(Field::from(a), op, Field::from(b), out)],
+        };
+
+        // Bound checks take 3 · 65 rows per division
+        let k = 8;
+        MockProver::run(k, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn test_arithmetic_mod_accepted() {
+        assert!(
+            run_single(17, Op::Mod, 5, Field::from(2u64)),
+            "17 % 5 = 2 should verify"
+        );
+        assert!(run_single(3, Op::Mod, 5, Field::from(3u64)));
+    }
+
+    #[test]
+    fn test_arithmetic_mod_wrong_remainder_rejected() {
+        // 17 = 2 · 5 + 7, but 7 is not below 5
+        assert!(!run_single(17, Op::Mod, 5, Field::from(7u64)));
+        // 17 = 4 · 5 - 3, but -3 is negative
+        assert!(!run_single(17, Op::Mod, 5, -Field::from(3u64)));
+        // No integer quotient for 17 - 3
+        assert!(!run_single(17, Op::Mod, 5, Field::from(3u64)));
+        // Remainder modulo 0 is undefined
+        assert!(!run_single(17, Op::Mod, 0, Field::from(17u64)));
+    }
+
+    #[test]
+    fn test_arithmetic_div() {
+        assert!(run_single(17, Op::Div, 5, Field::from(3u64)));
+        assert!(!run_single(17, Op::Div, 5, Field::from(4u64)));
+        assert!(!run_single(17, Op::Div, 5, Field::from(2u64)));
+    }
+
+    #[test]
+    fn test_arithmetic_expression() {
+        // (x - 1) · y on x = 5, y = 3
+        let circuit = ExpressionCircuit {
+            rows: vec![
+                (Source::Column(0), Op::Sub, Source::Constant(Field::one())),
+                (Source::Row(0), Op::Mul, Source::Column(1)),
+            ],
+            cells: vec![Field::from(5u64), Field::from(3u64)],
+        };
+        assert_eq!(
+            ArithmeticConfig::evaluate(&circuit.rows, &circuit.cells).map(|ops| ops[1].3),
+            Some(Field::from(12u64))
+        );
+
+        let k = 4;
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(12u64)]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The output follows from the cells, so another value is rejected
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(15u64)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Operands can only read earlier rows
+        let rows = vec![(Source::Row(0), Op::Add, Source::Column(0))];
+        assert_eq!(ArithmeticConfig::evaluate(&rows, &circuit.cells), None);
+    }
+}
@@ -0,0 +1,443 @@
+//! Count gate for proving COUNT(*) as a public output
+//!
+//! This module provides a count gate that verifies:
+//! 1. Each selection flag is boolean (row selected by the predicate or not)
+//! 2. A running sum accumulates the selection flags
+//! 3. The final running sum equals the claimed count, and the claimed count
+//!    a public instance cell
+//!
+//! A verifier therefore learns only the count, and that it equals the number
+//! of rows whose selection flag is set; the rows themselves stay private.
+//!
+//! # Method
+//!
+//! 1. Boolean check: s · (1 - s) = 0
+//! 2. Accumulation: c_0 = s_0, c_i = c_{i-1} + s_i
+//! 3. Output: c_{n-1} = count = instance[row]
+//!
+//! # Constraints
+//!
+//! - Boolean constraint: 1 per row
+//! - Accumulation constraint: 1 per row
+//! - Count and instance constraints: 2 (copy constraints)
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::count::CountConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 2];
+//! let instance = meta.instance_column();
+//!
+//! let config = CountConfig::configure(&mut meta, &advice, instance);
+//! ```
+
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for count gate
+///
+/// This gate verifies that a running sum over boolean selection flags
+/// equals a public count.
+#[derive(Debug, Clone)]
+pub struct CountConfig {
+    /// Column for selection flags (1 = row satisfies the predicate)
+    pub selection: Column<Advice>,
+
+    /// Column for running count
+    pub running: Column<Advice>,
+
+    /// Instance column exposing the count
+    pub instance: Column<Instance>,
+
+    /// Selector for the first row (c_0 = s_0)
+    pub s_first: Selector,
+
+    /// Selector for subsequent rows (c_i = c_{i-1} + s_i)
+    pub s_acc: Selector,
+
+    /// Selector for the boolean check on selection flags
+    pub s_bool: Selector,
+}
+
+impl CountConfig {
+    /// Configure the count gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 2: selection, running)
+    /// * `instance` - Instance column exposing the count
+    ///
+    /// # Returns
+    /// `CountConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        advice: &[Column<Advice>],
+        instance: Column<Instance>,
+    ) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 2,
+            "Need at least 2 advice columns (selection, running)"
+        );
+
+        // Assign columns
+        let selection = advice[0];
+        let running = advice[1];
+
+        // Enable equality for copy constraints to the instance column
+        meta.enable_equality(selection);
+        meta.enable_equality(running);
+        meta.enable_equality(instance);
+
+        let s_first = meta.selector();
+        let s_acc = meta.selector();
+        let s_bool = meta.selector();
+
+        // Constraint 1: selection flags are boolean
+        // s · (1 - s) = 0
+        meta.create_gate("count_selection_boolean", |meta| {
+            let q = meta.query_selector(s_bool);
+            let s = meta.query_advice(selection, Rotation::cur());
+
+            vec![q * s.clone() * (Expression::Constant(Field::one()) - s)]
+        });
+
+        // Constraint 2: first row starts the running count
+        // c_0 = s_0
+        meta.create_gate("count_first", |meta| {
+            let q = meta.query_selector(s_first);
+            let s = meta.query_advice(selection, Rotation::cur());
+            let c = meta.query_advice(running, Rotation::cur());
+
+            vec![q * (c - s)]
+        });
+
+        // Constraint 3: running count accumulates selection flags
+        // c_i = c_{i-1} + s_i
+        meta.create_gate("count_accumulate", |meta| {
+            let q = meta.query_selector(s_acc);
+            let s = meta.query_advice(selection, Rotation::cur());
+            let c_cur = meta.query_advice(running, Rotation::cur());
+            let c_prev = meta.query_advice(running, Rotation::prev());
+
+            vec![q * (c_cur - c_prev - s)]
+        });
+
+        Self {
+            selection,
+            running,
+            instance,
+            s_first,
+            s_acc,
+            s_bool,
+        }
+    }
+
+    /// Number of constraints for counting over `input_len` rows
+    ///
+    /// Each row has 1 boolean and 1 accumulation constraint, plus 2 copy
+    /// constraints binding the claimed count to the running count and to
+    /// the instance.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of selection flags
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        2 * input_len + 2
+    }
+
+    /// Assign selection flags and the running count
+    ///
+    /// An empty selection is assigned as a single unselected row so the
+    /// count cell always exists (and equals zero).
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `selection` - Selection flags (0 or 1) for each input row
+    ///
+    /// # Returns
+    /// The assigned cell holding the final count
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        selection: &[Field],
+    ) -> Result<AssignedCell<Field, Field>, ErrorFront> {
+        let zero = [Field::zero()];
+        let selection = if selection.is_empty() {
+            &zero[..]
+        } else {
+            selection
+        };
+
+        layouter.assign_region(
+            || "count gate",
+            |mut region| {
+                let mut count = Field::zero();
+                let mut count_cell = None;
+
+                for (i, &flag) in selection.iter().enumerate() {
+                    self.s_bool.enable(&mut region, i)?;
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_acc.enable(&mut region, i)?;
+                    }
+
+                    count += flag;
+                    region.assign_advice(
+                        || format!("selection[{}]", i),
+                        self.selection,
+                        i,
+                        || Value::known(flag),
+                    )?;
+                    count_cell = Some(region.assign_advice(
+                        || format!("running[{}]", i),
+                        self.running,
+                        i,
+                        || Value::known(count),
+                    )?);
+                }
+
+                Ok(count_cell.expect("selection is non-empty"))
+            },
+        )
+    }
+
+    /// Copy selection flags from their cells and assign the running count
+    ///
+    /// Binds the count to the gate that proved the flags (e.g., the
+    /// selection gate's predicate flags). No flags count as zero.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `flags` - Selection flag cell of each input row
+    ///
+    /// # Returns
+    /// The assigned cell holding the final count
+    pub fn assign_cells(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        flags: &[AssignedCell<Field, Field>],
+    ) -> Result<AssignedCell<Field, Field>, ErrorFront> {
+        if flags.is_empty() {
+            return self.assign(layouter, &[]);
+        }
+
+        layouter.assign_region(
+            || "count gate",
+            |mut region| {
+                let mut count = Value::known(Field::zero());
+                let mut count_cell = None;
+
+                for (i, flag_cell) in flags.iter().enumerate() {
+                    self.s_bool.enable(&mut region, i)?;
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_acc.enable(&mut region, i)?;
+                    }
+
+                    let flag = flag_cell.copy_advice(
+                        || format!("selection[{}]", i),
+                        &mut region,
+                        self.selection,
+                        i,
+                    )?;
+                    count = count + flag.value().copied();
+                    count_cell = Some(region.assign_advice(
+                        || format!("running[{}]", i),
+                        self.running,
+                        i,
+                        || count,
+                    )?);
+                }
+
+                Ok(count_cell.expect("flags are non-empty"))
+            },
+        )
+    }
+
+    /// Assign the running count and bind the claimed count to it and to a
+    /// public instance row
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `selection` - Selection flags (0 or 1) for each input row
+    /// * `count` - Claimed count
+    /// * `instance_row` - Row of the instance column holding the count
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    pub fn assign_public(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        selection: &[Field],
+        count: Field,
+        instance_row: usize,
+    ) -> Result<(), ErrorFront> {
+        let running_cell = self.assign(layouter, selection)?;
+        self.bind_count(layouter, &running_cell, count, instance_row)
+    }
+
+    /// Count copied selection flags, and bind the claimed count to the
+    /// running count and to a public instance row
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `flags` - Selection flag cell of each input row
+    /// * `count` - Claimed count
+    /// * `instance_row` - Row of the instance column holding the count
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    pub fn assign_public_cells(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        flags: &[AssignedCell<Field, Field>],
+        count: Field,
+        instance_row: usize,
+    ) -> Result<(), ErrorFront> {
+        let running_cell = self.assign_cells(layouter, flags)?;
+        self.bind_count(layouter, &running_cell, count, instance_row)
+    }
+
+    /// Assign the claimed count, equal to the final running count and to a
+    /// public instance row
+    fn bind_count(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        running_cell: &AssignedCell<Field, Field>,
+        count: Field,
+        instance_row: usize,
+    ) -> Result<(), ErrorFront> {
+        let count_cell = layouter.assign_region(
+            || "count output",
+            |mut region| {
+                let count_cell =
+                    region.assign_advice(|| "count", self.running, 0, || Value::known(count))?;
+                region.constrain_equal(running_cell.cell(), count_cell.cell())?;
+                Ok(count_cell)
+            },
+        )?;
+        layouter.constrain_instance(count_cell.cell(), self.instance, instance_row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+
+    /// Test circuit for count gate
+    #[derive(Default)]
+    struct TestCircuit {
+        selection: Vec<Field>,
+        count: u64,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = CountConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..2).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let instance = meta.instance_column();
+            CountConfig::configure(meta, &advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            config.assign_public(&mut layouter, &self.selection, Field::from(self.count), 0)
+        }
+    }
+
+    /// Selection flags for `l_quantity > 10` over a small column
+    fn filtered_selection() -> Vec<Field> {
+        let quantities = [5u64, 12, 10, 30, 11, 2];
+        quantities
+            .iter()
+            .map(|&q| Field::from((q > 10) as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_count_filtered_rows() {
+        let circuit = TestCircuit {
+            selection: filtered_selection(),
+            count: 3,
+        };
+
+        let k = 5;
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(3u64)]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "Count of 3 should verify");
+    }
+
+    #[test]
+    fn test_count_wrong_count_rejected() {
+        let circuit = TestCircuit {
+            selection: filtered_selection(),
+            count: 3,
+        };
+
+        let k = 5;
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(4u64)]]).unwrap();
+        assert!(prover.verify().is_err(), "Count of 4 should be rejected");
+
+        // A claimed count the flags don't sum to is rejected, even when the
+        // instance agrees with it
+        let circuit = TestCircuit {
+            selection: filtered_selection(),
+            count: 4,
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(4u64)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_count_non_boolean_selection_rejected() {
+        // A flag of 2 would let a prover inflate the count
+        let circuit = TestCircuit {
+            selection: vec![Field::from(2u64), Field::one()],
+            count: 3,
+        };
+
+        let k = 5;
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::from(3u64)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_count_empty_selection() {
+        let circuit = TestCircuit {
+            selection: vec![],
+            count: 0,
+        };
+
+        let k = 5;
+        let prover = MockProver::run(k, &circuit, vec![vec![Field::zero()]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -0,0 +1,765 @@
+//! Distinct-count gate for proving COUNT(DISTINCT) per group
+//!
+//! This module provides a distinct-count gate that verifies, over the rows
+//! of the group-by gate re-sorted by value within each group:
+//! 1. The (key, value) rows are a permutation of the input rows
+//! 2. The keys and group markers are the group-by gate's
+//! 3. A new-value flag is set exactly where a group starts with a value or
+//!    its value changes, and never on a NULL value
+//! 4. A running count of the flags restarts at each group, so the count at a
+//!    group's last row is its number of distinct non-NULL values
+//!
+//! # Method
+//!
+//! 0. Permutation: the multiset of (tag, key, value) input rows equals the
+//!    multiset of sorted rows, proven by a shuffle argument. The input keys
+//!    are copied from the group-by gate's input cells, and the sorted keys
+//!    from its sorted cells.
+//!
+//! 1. Groups: the binary markers bi (1 if rows i and i+1 belong to the same
+//!    group) are copied from the group-by gate, so no boundary is re-derived.
+//!
+//! 2. NULL: w = v + 1, z = 1 - w · i and w · z = 0, so z = 1 exactly when
+//!    the value is the NULL encoding -1
+//!
+//! 3. New value: di · (1 - di) = 0, zi · di = 0, and comparing row i with
+//!    row i-1 (gi = bi-1 = 1 in the same group):
+//!    - (1 - gi) · (1 - zi - di) = 0: a new group starts with a new value
+//!      unless its value is NULL
+//!    - gi · (1 - zi - di) · (vi - vi-1) = 0: a repeated value equals the
+//!      previous one
+//!    - gi · di · ((vi - vi-1) · qi - 1) = 0: a new value within a group
+//!      differs from the previous one
+//!    - gi · zi-1 · (1 - zi) = 0: NULLs come last in their group
+//!
+//! 4. Running count: d0 = 1 - z0, c0 = d0, ci = gi · ci-1 + di
+//!
+//! 5. Order (with [`DistinctCountConfig::with_order_check`]): vi-1 < vi
+//!    where a value changes within a group, proven with the less-than gate.
+//!    Equal values are then adjacent, so no value is counted twice.
+//!
+//! [`DistinctCountConfig::assign`] returns the input value cells, for
+//! binding to the counted column, and the key and count cells of each
+//! group's last row, for binding to instance cells.
+//!
+//! # Constraints
+//!
+//! - Permutation constraint: 1 per row (shuffle)
+//! - NULL and new-value flag constraints: 4 per row
+//! - First row constraints: 2
+//! - Pair constraints: 5 per adjacent pair
+//! - Order constraints: n + 2 per value change (with an order check)
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::distinct_count::DistinctCountConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 10];
+//!
+//! let config = DistinctCountConfig::configure(&mut meta, &advice);
+//! ```
+
+use crate::field::FieldUtils;
+use crate::gates::group_by::{GroupByCells, GroupByConfig};
+use crate::gates::less_than::LessThanConfig;
+use crate::gates::ValidationMode;
+use crate::types::Value as SqlValue;
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for distinct-count gate
+///
+/// This gate verifies the number of distinct values in each group proven by
+/// the group-by gate.
+#[derive(Debug, Clone)]
+pub struct DistinctCountConfig {
+    /// Column for the group keys, in input order
+    pub key_input_col: Column<Advice>,
+
+    /// Column for the counted values, in input order
+    pub value_input_col: Column<Advice>,
+
+    /// Column for the group keys, sorted
+    pub key_col: Column<Advice>,
+
+    /// Column for the counted values, sorted within each group
+    pub value_col: Column<Advice>,
+
+    /// Column for the group-by gate's binary marker b (1 = same group as the
+    /// next row)
+    pub marker_col: Column<Advice>,
+
+    /// Column for the NULL flag z (1 = the value is NULL)
+    pub null_col: Column<Advice>,
+
+    /// Column for the NULL helper i (inverse of the value plus one)
+    pub null_inverse_col: Column<Advice>,
+
+    /// Column for the new-value flag d (1 = value not seen in the group)
+    pub new_value_col: Column<Advice>,
+
+    /// Column for the value helper q (inverse of the value difference)
+    pub value_helper_col: Column<Advice>,
+
+    /// Column for the running distinct count c
+    pub count_col: Column<Advice>,
+
+    /// Fixed column separating the uses of the permutation
+    pub tag: Column<Fixed>,
+
+    /// Selector for the permutation rows
+    pub s_perm: Selector,
+
+    /// Selector for every row (NULL and new-value flags)
+    pub s_row: Selector,
+
+    /// Selector for the first row (d0 = 1 - z0, c0 = d0)
+    pub s_first: Selector,
+
+    /// Selector for rows after the first
+    pub s_pair: Selector,
+
+    /// Less-than gate proving the value order within groups (optional)
+    pub order_check: Option<LessThanConfig>,
+}
+
+/// Cells assigned by the distinct-count gate
+#[derive(Debug, Clone, Default)]
+pub struct DistinctCountCells {
+    /// Input value cells, in input order
+    pub inputs: Vec<AssignedCell<Field, Field>>,
+
+    /// (key, distinct count) cells of each group's last row, in key order
+    pub groups: Vec<(AssignedCell<Field, Field>, AssignedCell<Field, Field>)>,
+}
+
+impl DistinctCountConfig {
+    /// Configure the distinct-count gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 10 columns)
+    ///
+    /// # Returns
+    /// `DistinctCountConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 10,
+            "Need at least 10 advice columns (key_input, value_input, key, value, marker, \
+             null, null_inverse, new_value, value_helper, count)"
+        );
+
+        // Assign columns
+        let key_input_col = advice[0];
+        let value_input_col = advice[1];
+        let key_col = advice[2];
+        let value_col = advice[3];
+        let marker_col = advice[4];
+        let null_col = advice[5];
+        let null_inverse_col = advice[6];
+        let new_value_col = advice[7];
+        let value_helper_col = advice[8];
+        let count_col = advice[9];
+        let tag = meta.fixed_column();
+        let s_perm = meta.complex_selector();
+        let s_row = meta.selector();
+        let s_first = meta.selector();
+        let s_pair = meta.selector();
+
+        // Inputs, keys and markers are copied from the group-by gate and the
+        // counted column; values and counts to the less-than gate and instance
+        for column in [
+            key_input_col,
+            value_input_col,
+            key_col,
+            value_col,
+            marker_col,
+            count_col,
+        ] {
+            meta.enable_equality(column);
+        }
+
+        let one = || Expression::Constant(Field::one());
+
+        // Constraint 0: {(q · tag, q · key_in, q · value_in)} =
+        // {(q · tag, q · key, q · value)} as multisets
+        meta.shuffle("distinct_permutation", |meta| {
+            let q = meta.query_selector(s_perm);
+            let tag = meta.query_fixed(tag, Rotation::cur());
+            let key_in = meta.query_advice(key_input_col, Rotation::cur());
+            let value_in = meta.query_advice(value_input_col, Rotation::cur());
+            let key = meta.query_advice(key_col, Rotation::cur());
+            let value = meta.query_advice(value_col, Rotation::cur());
+
+            vec![
+                (q.clone() * tag.clone(), q.clone() * tag),
+                (q.clone() * key_in, q.clone() * key),
+                (q.clone() * value_in, q * value),
+            ]
+        });
+
+        // Constraints 2-3 on every row: the NULL flag and the new-value flag
+        meta.create_gate("distinct_row", |meta| {
+            let q = meta.query_selector(s_row);
+            let v = meta.query_advice(value_col, Rotation::cur());
+            let z = meta.query_advice(null_col, Rotation::cur());
+            let i = meta.query_advice(null_inverse_col, Rotation::cur());
+            let d = meta.query_advice(new_value_col, Rotation::cur());
+
+            let w = v + one();
+            vec![
+                // z = 1 - w · i
+                q.clone() * (z.clone() - one() + w.clone() * i),
+                // w · z = 0
+                q.clone() * w * z.clone(),
+                // d is boolean
+                q.clone() * d.clone() * (one() - d.clone()),
+                // A NULL is never a new value
+                q * z * d,
+            ]
+        });
+
+        // Constraint 4 on the first row: it starts a group
+        // d0 = 1 - z0, c0 = d0
+        meta.create_gate("distinct_first", |meta| {
+            let q = meta.query_selector(s_first);
+            let z = meta.query_advice(null_col, Rotation::cur());
+            let d = meta.query_advice(new_value_col, Rotation::cur());
+            let c = meta.query_advice(count_col, Rotation::cur());
+
+            vec![q.clone() * (one() - z - d.clone()), q * (c - d)]
+        });
+
+        // Constraints 3-4 on each row and its predecessor
+        meta.create_gate("distinct_pair", |meta| {
+            let q = meta.query_selector(s_pair);
+            let g = meta.query_advice(marker_col, Rotation::prev());
+            let v_cur = meta.query_advice(value_col, Rotation::cur());
+            let v_prev = meta.query_advice(value_col, Rotation::prev());
+            let z_cur = meta.query_advice(null_col, Rotation::cur());
+            let z_prev = meta.query_advice(null_col, Rotation::prev());
+            let d = meta.query_advice(new_value_col, Rotation::cur());
+            let value_inv = meta.query_advice(value_helper_col, Rotation::cur());
+            let c_cur = meta.query_advice(count_col, Rotation::cur());
+            let c_prev = meta.query_advice(count_col, Rotation::prev());
+
+            // 1 exactly for a repeated non-NULL value, as z · d = 0
+            let repeated = one() - z_cur.clone() - d.clone();
+            let value_diff = v_cur - v_prev;
+            vec![
+                // (1 - gi) · (1 - zi - di) = 0
+                q.clone() * (one() - g.clone()) * repeated.clone(),
+                // gi · (1 - zi - di) · (vi - vi-1) = 0
+                q.clone() * g.clone() * repeated * value_diff.clone(),
+                // gi · di · ((vi - vi-1) · qi - 1) = 0
+                q.clone() * g.clone() * d.clone() * (value_diff * value_inv - one()),
+                // gi · zi-1 · (1 - zi) = 0
+                q.clone() * g.clone() * z_prev * (one() - z_cur),
+                // ci = gi · ci-1 + di
+                q * (c_cur - g * c_prev - d),
+            ]
+        });
+
+        Self {
+            key_input_col,
+            value_input_col,
+            key_col,
+            value_col,
+            marker_col,
+            null_col,
+            null_inverse_col,
+            new_value_col,
+            value_helper_col,
+            count_col,
+            tag,
+            s_perm,
+            s_row,
+            s_first,
+            s_pair,
+            order_check: None,
+        }
+    }
+
+    /// Prove the value order within groups with a less-than gate
+    ///
+    /// Without an order check, `assign` still sorts the rows, but the proof
+    /// does not attest the order, so a repeated value placed apart from its
+    /// duplicates would be counted twice. Non-NULL values must be below 2^n
+    /// for the less-than gate's bit-width n.
+    ///
+    /// # Arguments
+    /// * `less_than` - Less-than gate for the value changes
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_order_check(mut self, less_than: LessThanConfig) -> Self {
+        self.order_check = Some(less_than);
+        self
+    }
+
+    /// Number of constraints for counting over `input_len` rows
+    ///
+    /// Each row has 1 permutation and 4 flag constraints, the first row 2
+    /// more and each adjacent pair 5, plus the less-than constraints of the
+    /// order check if enabled (counted for a change on every pair).
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of (key, value) rows
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        if input_len == 0 {
+            return 0;
+        }
+        let pairs = input_len - 1;
+        5 * input_len
+            + 2
+            + 5 * pairs
+            + self
+                .order_check
+                .as_ref()
+                .map_or(0, |less_than| less_than.num_constraints(pairs))
+    }
+
+    /// Sort (key, value) rows by key and then by value
+    ///
+    /// NULL values, encoded as -1, sort last within their group.
+    pub fn sort_rows(keys: &[Field], values: &[Field]) -> Vec<(Field, Field)> {
+        let mut rows: Vec<(Field, Field)> =
+            keys.iter().copied().zip(values.iter().copied()).collect();
+        rows.sort_by(|a, b| FieldUtils::cmp(&a.0, &b.0).then_with(|| FieldUtils::cmp(&a.1, &b.1)));
+        rows
+    }
+
+    /// Get the distinct count of each group of sorted rows
+    ///
+    /// NULL values are not counted, so a group of NULLs counts 0.
+    ///
+    /// # Arguments
+    /// * `rows` - (key, value) rows as returned by `sort_rows`
+    ///
+    /// # Returns
+    /// (key, distinct count) of each group, in key order
+    pub fn group_counts(rows: &[(Field, Field)]) -> Vec<(Field, Field)> {
+        let mut counts: Vec<(Field, u64)> = vec![];
+        for (i, &(key, value)) in rows.iter().enumerate() {
+            let counted = !SqlValue::is_null_field(&value);
+            match counts.last_mut() {
+                Some((last, count)) if *last == key => {
+                    if counted && rows[i - 1].1 != value {
+                        *count += 1;
+                    }
+                }
+                _ => counts.push((key, counted as u64)),
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(key, count)| (key, Field::from(count)))
+            .collect()
+    }
+
+    /// Assign values for the distinct-count gate
+    ///
+    /// The rows are the group-by gate's: `keys` must be its input keys, in
+    /// input order, and `groups` the cells it assigned for them. The input
+    /// keys and the sorted keys and markers are copy-constrained to those
+    /// cells.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `groups` - Cells returned by `GroupByConfig::assign`
+    /// * `keys` - Group key of each input row
+    /// * `values` - Counted value of each input row (NULL for none)
+    /// * `tag` - Nonzero tag, distinct for each use of this gate
+    /// * `mode` - Whether to re-count each group's distinct values
+    ///
+    /// # Returns
+    /// `Ok(cells)` with the input value cells and the (key, distinct count)
+    /// cells of each group's last row,
+    /// `Err(Error::Synthesis)` if the lengths differ from the group-by
+    /// gate's or the tag is zero, `Err(Error::Other)` if a value is too
+    /// wide for the order check or, in `Strict` mode, a running count
+    /// disagrees with the group's distinct values, `Err(Error)` otherwise
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        groups: &GroupByCells,
+        keys: &[Field],
+        values: &[Field],
+        tag: u64,
+        mode: ValidationMode,
+    ) -> Result<DistinctCountCells, ErrorFront> {
+        let n = keys.len();
+        if values.len() != n
+            || groups.inputs.len() != n
+            || groups.sorted.len() != n
+            || groups.markers.len() != n
+            || tag == 0
+        {
+            return Err(ErrorFront::Synthesis);
+        }
+        if n == 0 {
+            return Ok(DistinctCountCells::default()); // Empty input, nothing to do
+        }
+        if let Some(less_than) = &self.order_check {
+            if let Some(i) = values.iter().position(|value| {
+                !SqlValue::is_null_field(value) && !GroupByConfig::fits_bits(value, less_than.n)
+            }) {
+                return Err(ErrorFront::Other(format!(
+                    "distinct-count value at row {} exceeds {} bits",
+                    i, less_than.n
+                )));
+            }
+        }
+
+        // Sort the rows within the groups, and derive the flags, helpers
+        // and running counts; the markers are the group-by gate's
+        let rows = Self::sort_rows(keys, values);
+        let markers: Vec<Field> = (0..n)
+            .map(|i| Field::from((i + 1 < n && rows[i].0 == rows[i + 1].0) as u64))
+            .collect();
+        let mut nulls = vec![Field::zero(); n];
+        let mut null_inverses = vec![Field::zero(); n];
+        let mut new_values = vec![Field::zero(); n];
+        let mut value_helpers = vec![Field::zero(); n];
+        let mut counts = vec![Field::zero(); n];
+        for (i, &(_, value)) in rows.iter().enumerate() {
+            let same_group = i > 0 && markers[i - 1] == Field::one();
+            if SqlValue::is_null_field(&value) {
+                nulls[i] = Field::one();
+            } else {
+                null_inverses[i] = (value + Field::one()).invert().unwrap();
+                if !same_group {
+                    new_values[i] = Field::one();
+                } else {
+                    let value_diff = value - rows[i - 1].1;
+                    if !bool::from(value_diff.is_zero()) {
+                        new_values[i] = Field::one();
+                        value_helpers[i] = value_diff.invert().unwrap();
+                    }
+                }
+            }
+            counts[i] = new_values[i];
+            if same_group {
+                counts[i] += counts[i - 1];
+            }
+        }
+        let last_rows: Vec<usize> = (0..n).filter(|&i| markers[i] == Field::zero()).collect();
+        if mode.is_strict() {
+            Self::check_counts(&rows, &last_rows, &counts)?;
+        }
+
+        let (input_cells, value_cells, group_cells) = layouter.assign_region(
+            || "distinct_count gate",
+            |mut region| {
+                let mut input_cells = Vec::with_capacity(n);
+                let mut value_cells = Vec::with_capacity(n);
+                let mut key_cells = Vec::with_capacity(n);
+                let mut count_cells = Vec::with_capacity(n);
+                for (i, &(key, value)) in rows.iter().enumerate() {
+                    self.s_perm.enable(&mut region, i)?;
+                    self.s_row.enable(&mut region, i)?;
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_pair.enable(&mut region, i)?;
+                    }
+                    region.assign_fixed(
+                        || format!("tag[{}]", i),
+                        self.tag,
+                        i,
+                        || Value::known(Field::from(tag)),
+                    )?;
+
+                    // Input rows, with the group-by gate's input keys
+                    let key_input = region.assign_advice(
+                        || format!("key_input[{}]", i),
+                        self.key_input_col,
+                        i,
+                        || Value::known(keys[i]),
+                    )?;
+                    region.constrain_equal(key_input.cell(), groups.inputs[i].cell())?;
+                    input_cells.push(region.assign_advice(
+                        || format!("value_input[{}]", i),
+                        self.value_input_col,
+                        i,
+                        || Value::known(values[i]),
+                    )?);
+
+                    // Sorted rows, with the group-by gate's sorted keys and markers
+                    let key_cell = region.assign_advice(
+                        || format!("key[{}]", i),
+                        self.key_col,
+                        i,
+                        || Value::known(key),
+                    )?;
+                    region.constrain_equal(key_cell.cell(), groups.sorted[i].cell())?;
+                    key_cells.push(key_cell);
+                    value_cells.push(region.assign_advice(
+                        || format!("value[{}]", i),
+                        self.value_col,
+                        i,
+                        || Value::known(value),
+                    )?);
+                    let marker = region.assign_advice(
+                        || format!("marker[{}]", i),
+                        self.marker_col,
+                        i,
+                        || Value::known(markers[i]),
+                    )?;
+                    region.constrain_equal(marker.cell(), groups.markers[i].cell())?;
+
+                    region.assign_advice(
+                        || format!("null[{}]", i),
+                        self.null_col,
+                        i,
+                        || Value::known(nulls[i]),
+                    )?;
+                    region.assign_advice(
+                        || format!("null_inverse[{}]", i),
+                        self.null_inverse_col,
+                        i,
+                        || Value::known(null_inverses[i]),
+                    )?;
+                    region.assign_advice(
+                        || format!("new_value[{}]", i),
+                        self.new_value_col,
+                        i,
+                        || Value::known(new_values[i]),
+                    )?;
+                    region.assign_advice(
+                        || format!("value_helper[{}]", i),
+                        self.value_helper_col,
+                        i,
+                        || Value::known(value_helpers[i]),
+                    )?;
+                    count_cells.push(region.assign_advice(
+                        || format!("count[{}]", i),
+                        self.count_col,
+                        i,
+                        || Value::known(counts[i]),
+                    )?);
+                }
+
+                let group_cells = last_rows
+                    .iter()
+                    .map(|&i| (key_cells[i].clone(), count_cells[i].clone()))
+                    .collect::<Vec<_>>();
+                Ok((input_cells, value_cells, group_cells))
+            },
+        )?;
+
+        // Prove vi-1 < vi where a value changes within a group, on copies
+        // of the compared cells
+        if let Some(less_than) = &self.order_check {
+            for i in 1..n {
+                if markers[i - 1] == Field::zero() || new_values[i] == Field::zero() {
+                    continue;
+                }
+                let (a, b) = less_than.assign(layouter, rows[i - 1].1, rows[i].1)?;
+                layouter.assign_region(
+                    || format!("distinct_count order[{}]", i),
+                    |mut region| {
+                        region.constrain_equal(value_cells[i - 1].cell(), a.cell())?;
+                        region.constrain_equal(value_cells[i].cell(), b.cell())
+                    },
+                )?;
+            }
+        }
+
+        Ok(DistinctCountCells {
+            inputs: input_cells,
+            groups: group_cells,
+        })
+    }
+
+    /// Check the running counts for `Strict` mode
+    ///
+    /// Each group's count must equal its number of distinct non-NULL values.
+    ///
+    /// # Returns
+    /// `Ok(())` if the counts are consistent, `Err(Error::Other)` naming the
+    /// first inconsistent group otherwise
+    fn check_counts(
+        rows: &[(Field, Field)],
+        last_rows: &[usize],
+        counts: &[Field],
+    ) -> Result<(), ErrorFront> {
+        let mut start = 0;
+        for (group, &end) in last_rows.iter().enumerate() {
+            let mut values: Vec<Field> = rows[start..=end]
+                .iter()
+                .map(|&(_, v)| v)
+                .filter(|v| !SqlValue::is_null_field(v))
+                .collect();
+            values.sort_by(FieldUtils::cmp);
+            values.dedup();
+            if counts[end] != Field::from(values.len() as u64) {
+                return Err(ErrorFront::Other(format!(
+                    "distinct-count group {} (rows {}..={}) has {} distinct values",
+                    group,
+                    start,
+                    end,
+                    values.len()
+                )));
+            }
+            start = end + 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fields, run_mock_with_instance};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, Instance},
+    };
+
+    /// Test circuit grouping rows by key and exposing each group's
+    /// (key, distinct count) publicly
+    #[derive(Default)]
+    struct TestCircuit {
+        keys: Vec<Field>,
+        values: Vec<Field>,
+        /// Keys given to the group-by gate instead of `keys` (optional)
+        group_keys: Option<Vec<Field>>,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = (GroupByConfig, DistinctCountConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..19).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let less_than = LessThanConfig::configure(meta, &advice[16..], 8);
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            (
+                GroupByConfig::configure(meta, &advice[..6]).with_order_check(less_than.clone()),
+                DistinctCountConfig::configure(meta, &advice[6..16]).with_order_check(less_than),
+                instance,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            (group_by, config, instance): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let group_keys = self.group_keys.as_ref().unwrap_or(&self.keys);
+            let mut sorted = group_keys.clone();
+            sorted.sort_by(FieldUtils::cmp);
+            let groups =
+                group_by.assign(&mut layouter, group_keys, &sorted, ValidationMode::Fast)?;
+            let cells = config.assign(
+                &mut layouter,
+                &groups,
+                &self.keys,
+                &self.values,
+                1,
+                ValidationMode::Fast,
+            )?;
+            for (i, (key, count)) in cells.groups.iter().enumerate() {
+                layouter.constrain_instance(key.cell(), instance, 2 * i)?;
+                layouter.constrain_instance(count.cell(), instance, 2 * i + 1)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Unsorted rows of (key, value), with NULL values
+    fn circuit(pairs: &[(u64, Option<u64>)]) -> TestCircuit {
+        TestCircuit {
+            keys: pairs.iter().map(|&(k, _)| Field::from(k)).collect(),
+            values: pairs
+                .iter()
+                .map(|&(_, v)| v.map_or_else(SqlValue::null_field, Field::from))
+                .collect(),
+            group_keys: None,
+        }
+    }
+
+    const ROWS: [(u64, Option<u64>); 9] = [
+        (3, Some(2)),
+        (1, Some(7)),
+        (2, Some(5)),
+        (1, Some(5)),
+        (3, Some(1)),
+        (4, None),
+        (1, None),
+        (3, Some(2)),
+        (1, Some(5)),
+    ];
+
+    #[test]
+    fn test_distinct_count_group_counts() {
+        let circuit = circuit(&ROWS);
+        let rows = DistinctCountConfig::sort_rows(&circuit.keys, &circuit.values);
+        assert_eq!(rows[3], (Field::from(1u64), SqlValue::null_field()));
+        assert_eq!(
+            DistinctCountConfig::group_counts(&rows),
+            vec![
+                (Field::from(1u64), Field::from(2u64)),
+                (Field::from(2u64), Field::from(1u64)),
+                (Field::from(3u64), Field::from(2u64)),
+                (Field::from(4u64), Field::from(0u64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distinct_count_per_group() {
+        let circuit = circuit(&ROWS);
+        let honest = fields(&[1, 2, 2, 1, 3, 2, 4, 0]);
+        assert_eq!(run_mock_with_instance(&circuit, 8, vec![honest]), Ok(()));
+
+        // A tampered per-group count is rejected
+        let tampered = fields(&[1, 3, 2, 1, 3, 2, 4, 0]);
+        assert!(run_mock_with_instance(&circuit, 8, vec![tampered]).is_err());
+
+        // Counting a group of NULLs as one value is rejected
+        let tampered = fields(&[1, 2, 2, 1, 3, 2, 4, 1]);
+        assert!(run_mock_with_instance(&circuit, 8, vec![tampered]).is_err());
+    }
+
+    #[test]
+    fn test_distinct_count_rows_bound_to_group_by() {
+        // The group-by gate groups other keys than the counted rows'
+        let mut circuit = circuit(&[(1, Some(5)), (1, Some(7)), (2, Some(5))]);
+        circuit.group_keys = Some(fields(&[1, 2, 2]));
+        let prover = MockProver::run(8, &circuit, vec![fields(&[1, 2, 2, 1])]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_distinct_count_rejects_wide_values() {
+        let circuit = circuit(&[(1, Some(256))]);
+        let err = MockProver::run(8, &circuit, vec![vec![]]).unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds 8 bits"),
+            "Unexpected error: {}",
+            err
+        );
+    }
+}
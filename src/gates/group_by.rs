@@ -1,22 +1,44 @@
 //! Group-by gate for proving grouping correctness
 //!
 //! This module provides a group-by gate that verifies:
-//! 1. Group boundaries are correctly identified (equality check)
-//! 2. Binary markers correctly indicate same/different groups
-//! 3. Start/end indices correctly mark group boundaries
+//! 1. The sorted keys are a permutation of the input keys
+//! 2. Group boundaries are correctly identified (equality check)
+//! 3. Binary markers correctly indicate same/different groups
+//! 4. Start/end indices correctly mark group boundaries
 //!
 //! # Method
 //!
+//! 0. Permutation: the sorted keys are a permutation of the input keys,
+//!    proven by the shared [`PermutationArgument`]
+//!
 //! 1. Group Boundary Detection: b = 1 - (v1 - v2) · p
 //!    - p = 0 if v1 = v2 (same group)
 //!    - p = 1/(v1-v2) if v1 ≠ v2 (different group)
 //!
 //! 2. Validation: b · (v1 - v2) = 0
 //!
+//! 3. Order (with [`GroupByConfig::with_order_check`]): v1 ≤ v2, proven per
+//!    adjacent pair with the less-than gate, so the proof attests that the
+//!    input is sorted and each group is contiguous
+//!
+//! 4. Group indices: each row carries the start/end index of its group,
+//!    checked against a fixed column holding the row's index
+//!    - bi · (starti+1 - starti) = 0 and bi · (endi+1 - endi) = 0
+//!    - (1 - bi) · (starti+1 - (i+1)) = 0 and (1 - bi) · (endi - i) = 0
+//!    - start0 = 0, endn-1 = n-1 and bn-1 = 0
+//!
+//! Constraints 1, 2 and 4 are enabled on adjacent pairs, except for the
+//! first and last row's. [`GroupByConfig::assign`] returns the markers and
+//! indices so they can be copy-constrained to the aggregation gate's.
+//!
 //! # Constraints
 //!
+//! - Permutation constraint: 1 per row (shuffle)
 //! - Group boundary constraint: 1 per adjacent pair
 //! - Validation constraint: 1 per adjacent pair
+//! - Group index constraints: 4 per adjacent pair, plus 3 for the first and
+//!   last rows
+//! - Order constraints: n + 2 per adjacent pair (with an order check)
 //!
 //! # Example
 //!
@@ -26,16 +48,21 @@
 //! use halo2_proofs::halo2curves::bn256::Fr as Field;
 //!
 //! let mut meta = ConstraintSystem::<Field>::default();
-//! let advice = vec![meta.advice_column(); 5];
+//! let advice = vec![meta.advice_column(); 6];
 //!
 //! let config = GroupByConfig::configure(&mut meta, &advice);
 //! ```
 
-use ff::Field as _;
+use crate::field::FieldUtils;
+use crate::gates::aggregation::AggregationCells;
+use crate::gates::less_than::LessThanConfig;
+use crate::gates::permutation::PermutationArgument;
+use crate::gates::ValidationMode;
+use ff::{Field as _, PrimeField};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
-    circuit::{Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression},
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Fixed, Selector},
     poly::Rotation,
 };
 
@@ -59,6 +86,40 @@ pub struct GroupByConfig {
 
     /// Column for helper variable p
     pub helper_p: Column<Advice>,
+
+    /// Fixed column holding each row's index within the region
+    pub index: Column<Fixed>,
+
+    /// Permutation argument between the input keys and the sorted column
+    pub permutation: PermutationArgument,
+
+    /// Selector enabling the pair constraints on rows with a successor
+    pub s_pair: Selector,
+
+    /// Selector for the first row (its group starts at 0)
+    pub s_first: Selector,
+
+    /// Selector for the last row (its group ends there)
+    pub s_last: Selector,
+
+    /// Less-than gate proving adjacent sorted values are non-decreasing
+    pub order_check: Option<LessThanConfig>,
+}
+
+/// Cells assigned by the group-by gate
+#[derive(Debug, Clone, Default)]
+pub struct GroupByCells {
+    /// Input key cells, in input order
+    pub inputs: Vec<AssignedCell<Field, Field>>,
+
+    /// Sorted key cells
+    pub sorted: Vec<AssignedCell<Field, Field>>,
+
+    /// Binary marker cell of each sorted row
+    pub markers: Vec<AssignedCell<Field, Field>>,
+
+    /// (start index, end index) cells of each sorted row
+    pub boundaries: Vec<(AssignedCell<Field, Field>, AssignedCell<Field, Field>)>,
 }
 
 impl GroupByConfig {
@@ -66,7 +127,7 @@ impl GroupByConfig {
     ///
     /// # Arguments
     /// * `meta` - Constraint system metadata
-    /// * `advice` - Slice of advice columns (needs at least 5: sorted, start_idx, end_idx, binary_marker, helper_p)
+    /// * `advice` - Slice of advice columns (needs at least 6: sorted, start_idx, end_idx, binary_marker, helper_p, input)
     ///
     /// # Returns
     /// `GroupByConfig` with configured columns
@@ -76,8 +137,8 @@ impl GroupByConfig {
     pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
         // Validate input
         assert!(
-            advice.len() >= 5,
-            "Need at least 5 advice columns (sorted, start_idx, end_idx, binary_marker, helper_p)"
+            advice.len() >= 6,
+            "Need at least 6 advice columns (sorted, start_idx, end_idx, binary_marker, helper_p, input)"
         );
 
         // Assign columns
@@ -86,6 +147,13 @@ impl GroupByConfig {
         let end_idx = advice[2];
         let binary_marker = advice[3];
         let helper_p = advice[4];
+        let index = meta.fixed_column();
+        let s_pair = meta.selector();
+        let s_first = meta.selector();
+        let s_last = meta.selector();
+
+        // Constraint 0: the sorted keys are a permutation of the input keys
+        let permutation = PermutationArgument::configure(meta, &[advice[5], sorted_col]);
 
         // Enable equality on all advice columns
         meta.enable_equality(sorted_col);
@@ -100,6 +168,7 @@ impl GroupByConfig {
         //   p = 0           if v1 = v2 (same group)
         //   p = 1/(v1-v2)   if v1 ≠ v2 (different group)
         meta.create_gate("group_boundary", |meta| {
+            let s = meta.query_selector(s_pair);
             let v1 = meta.query_advice(sorted_col, Rotation::cur());
             let v2 = meta.query_advice(sorted_col, Rotation::next());
             let b = meta.query_advice(binary_marker, Rotation::cur());
@@ -108,7 +177,7 @@ impl GroupByConfig {
             // b = 1 - (v1 - v2) · p
             // Rearranged: b + (v1 - v2) · p = 1
             let left = b.clone() + (v1.clone() - v2.clone()) * p.clone();
-            vec![left - Expression::Constant(Field::one())]
+            vec![s * (left - Expression::Constant(Field::one()))]
         });
 
         // Constraint 2: Validation constraint
@@ -117,12 +186,48 @@ impl GroupByConfig {
         // - If v1 = v2: any b works, but formula forces b = 1
         // - If v1 ≠ v2: b must be 0, else constraint violated
         meta.create_gate("group_validity", |meta| {
+            let s = meta.query_selector(s_pair);
             let v1 = meta.query_advice(sorted_col, Rotation::cur());
             let v2 = meta.query_advice(sorted_col, Rotation::next());
             let b = meta.query_advice(binary_marker, Rotation::cur());
 
             // b · (v1 - v2) = 0
-            vec![b * (v1 - v2)]
+            vec![s * b * (v1 - v2)]
+        });
+
+        // Constraint 3: Group indices
+        // Rows of a group (b = 1) share its start and end; a group ends
+        // (b = 0) at its own row, and the next one starts at the next row
+        meta.create_gate("group_indices", |meta| {
+            let s = meta.query_selector(s_pair);
+            let b = meta.query_advice(binary_marker, Rotation::cur());
+            let start = meta.query_advice(start_idx, Rotation::cur());
+            let start_next = meta.query_advice(start_idx, Rotation::next());
+            let end = meta.query_advice(end_idx, Rotation::cur());
+            let end_next = meta.query_advice(end_idx, Rotation::next());
+            let row = meta.query_fixed(index, Rotation::cur());
+            let row_next = meta.query_fixed(index, Rotation::next());
+            let new_group = Expression::Constant(Field::one()) - b.clone();
+
+            vec![
+                s.clone() * b.clone() * (start_next.clone() - start),
+                s.clone() * b * (end_next - end.clone()),
+                s.clone() * new_group.clone() * (start_next - row_next),
+                s * new_group * (end - row),
+            ]
+        });
+        meta.create_gate("group_first", |meta| {
+            let s = meta.query_selector(s_first);
+            let start = meta.query_advice(start_idx, Rotation::cur());
+            let row = meta.query_fixed(index, Rotation::cur());
+            vec![s * (start - row)]
+        });
+        meta.create_gate("group_last", |meta| {
+            let s = meta.query_selector(s_last);
+            let b = meta.query_advice(binary_marker, Rotation::cur());
+            let end = meta.query_advice(end_idx, Rotation::cur());
+            let row = meta.query_fixed(index, Rotation::cur());
+            vec![s.clone() * b, s * (end - row)]
         });
 
         Self {
@@ -131,31 +236,114 @@ impl GroupByConfig {
             end_idx,
             binary_marker,
             helper_p,
+            index,
+            permutation,
+            s_pair,
+            s_first,
+            s_last,
+            order_check: None,
+        }
+    }
+
+    /// Prove that the input is sorted with a less-than gate
+    ///
+    /// Without an order check, `assign` still rejects unsorted input, but the
+    /// proof does not attest the order. Sorted values must be below 2^n for
+    /// the less-than gate's bit-width n.
+    ///
+    /// # Arguments
+    /// * `less_than` - Less-than gate to chain over adjacent sorted values
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_order_check(mut self, less_than: LessThanConfig) -> Self {
+        self.order_check = Some(less_than);
+        self
+    }
+
+    /// Number of constraints for grouping `input_len` sorted values
+    ///
+    /// Each row has 1 permutation constraint, each adjacent pair has 1
+    /// boundary, 1 validation and 4 group index constraints, the first and
+    /// last rows have 3 more, plus the less-than constraints of the order
+    /// check if enabled.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of sorted values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        if input_len == 0 {
+            return 0;
         }
+        let pairs = input_len - 1;
+        self.permutation.num_constraints(input_len)
+            + 6 * pairs
+            + 3
+            + self
+                .order_check
+                .as_ref()
+                .map_or(0, |less_than| less_than.num_constraints(pairs))
     }
 
     /// Assign values for group-by gate
     ///
     /// This method:
-    /// 1. Assigns sorted values to sorted column
+    /// 1. Assigns the input keys and sorted values, proving the sorted values
+    ///    are a permutation of the input
     /// 2. Computes and assigns binary markers b
     /// 3. Computes and assigns helper variables p
     /// 4. Computes and assigns start/end indices
     ///
     /// # Arguments
     /// * `layouter` - Layouter for assigning values
+    /// * `input_values` - The keys of the grouped rows, in input order
     /// * `sorted_values` - The sorted values (must be sorted by grouping attributes)
+    /// * `mode` - Whether to check that the sorted values are a permutation
+    ///   of the input and that the group boundaries are monotonic and agree
+    ///   with the binary markers
     ///
     /// # Returns
-    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    /// `Ok(cells)` with the assigned input and sorted key cells and the
+    /// binary marker and (start index, end index) cells of each sorted row
+    /// if assignment succeeds,
+    /// `Err(Error::Other)` if the values are not non-decreasing (or too wide
+    /// for the order check) or, in `Strict` mode, not a permutation of the
+    /// input or the derived boundaries are inconsistent, `Err(Error)` otherwise
     pub fn assign(
         &self,
         layouter: &mut impl Layouter<Field>,
+        input_values: &[Field],
         sorted_values: &[Field],
-    ) -> Result<(), ErrorFront> {
+        mode: ValidationMode,
+    ) -> Result<GroupByCells, ErrorFront> {
         let n = sorted_values.len();
+        if input_values.len() != n {
+            return Err(ErrorFront::Synthesis);
+        }
         if n == 0 {
-            return Ok(()); // Empty input, nothing to do
+            return Ok(GroupByCells::default()); // Empty input, nothing to do
+        }
+
+        // Unsorted input would split groups into wrong-but-valid boundaries
+        if let Some(i) = sorted_values
+            .windows(2)
+            .position(|pair| FieldUtils::cmp(&pair[0], &pair[1]).is_gt())
+        {
+            return Err(ErrorFront::Other(format!(
+                "group-by input is not sorted: row {} is greater than row {}",
+                i,
+                i + 1
+            )));
+        }
+        if let Some(less_than) = &self.order_check {
+            if let Some(i) = sorted_values
+                .iter()
+                .position(|value| !Self::fits_bits(value, less_than.n))
+            {
+                return Err(ErrorFront::Other(format!(
+                    "group-by value at row {} exceeds {} bits",
+                    i, less_than.n
+                )));
+            }
         }
 
         // Compute binary markers b and helper variables p
@@ -173,7 +361,7 @@ impl GroupByConfig {
             } else {
                 let v1 = sorted_values[i];
                 let v2 = sorted_values[i + 1];
-                let diff = v2 - v1;
+                let diff = v1 - v2;
 
                 if diff.is_zero().into() {
                     // v1 == v2: same group
@@ -187,52 +375,52 @@ impl GroupByConfig {
             }
         }
 
-        // Compute start/end indices
-        // Start index: index where binary_marker changes from 0 to 1 (or first row)
-        // End index: index where binary_marker changes from 1 to 0 (or last row)
+        // Compute start/end indices: every row carries its group's boundaries
+        let boundaries = Self::get_group_boundaries(sorted_values);
+        if mode.is_strict() {
+            Self::check_boundaries(&boundaries, &binary_markers, n)?;
+        }
         let mut start_indices = Vec::with_capacity(n);
         let mut end_indices = Vec::with_capacity(n);
 
-        let mut current_group_start = 0;
-        for i in 0..n {
-            // Check if this is the start of a new group
-            if i == 0 || (i > 0 && binary_markers[i - 1] == Field::zero()) {
-                current_group_start = i;
-            }
-
-            start_indices.push(Field::from(current_group_start as u64));
-
-            // Check if this is the end of a group
-            if i == n - 1 || (i < n - 1 && binary_markers[i] == Field::zero()) {
-                end_indices.push(Field::from(i as u64));
-            } else {
-                // Not end yet, use next end index
-                end_indices.push(Field::from((i + 1) as u64));
+        for (start, end) in boundaries {
+            for _ in start..=end {
+                start_indices.push(Field::from(start as u64));
+                end_indices.push(Field::from(end as u64));
             }
         }
 
         // Assign all values in a region
-        layouter.assign_region(
+        let (input_cells, sorted_cells, marker_cells, index_cells) = layouter.assign_region(
             || "group_by gate",
             |mut region| {
-                // Assign sorted values
-                for (i, &value) in sorted_values.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("sorted[{}]", i),
-                        self.sorted_col,
+                // Assign the input keys and sorted values
+                let (input_cells, sorted_cells) =
+                    self.permutation
+                        .assign(&mut region, input_values, sorted_values, 1, mode)?;
+                for i in 0..n - 1 {
+                    self.s_pair.enable(&mut region, i)?;
+                }
+                self.s_first.enable(&mut region, 0)?;
+                self.s_last.enable(&mut region, n - 1)?;
+                for i in 0..n {
+                    region.assign_fixed(
+                        || format!("index[{}]", i),
+                        self.index,
                         i,
-                        || Value::known(value),
+                        || Value::known(Field::from(i as u64)),
                     )?;
                 }
 
                 // Assign binary markers
+                let mut marker_cells = Vec::with_capacity(n);
                 for (i, &marker) in binary_markers.iter().enumerate() {
-                    region.assign_advice(
+                    marker_cells.push(region.assign_advice(
                         || format!("binary_marker[{}]", i),
                         self.binary_marker,
                         i,
                         || Value::known(marker),
-                    )?;
+                    )?);
                 }
 
                 // Assign helper variables p
@@ -245,31 +433,141 @@ impl GroupByConfig {
                     )?;
                 }
 
-                // Assign start indices
-                for (i, &start) in start_indices.iter().enumerate() {
-                    region.assign_advice(
+                // Assign start/end indices
+                let mut index_cells = Vec::with_capacity(n);
+                for (i, (&start, &end)) in start_indices.iter().zip(&end_indices).enumerate() {
+                    let start_cell = region.assign_advice(
                         || format!("start_idx[{}]", i),
                         self.start_idx,
                         i,
                         || Value::known(start),
                     )?;
-                }
-
-                // Assign end indices
-                for (i, &end) in end_indices.iter().enumerate() {
-                    region.assign_advice(
+                    let end_cell = region.assign_advice(
                         || format!("end_idx[{}]", i),
                         self.end_idx,
                         i,
                         || Value::known(end),
                     )?;
+                    index_cells.push((start_cell, end_cell));
                 }
 
+                Ok((input_cells, sorted_cells, marker_cells, index_cells))
+            },
+        )?;
+
+        // Prove v_i ≤ v_{i+1} on copies of each adjacent pair
+        if let Some(less_than) = &self.order_check {
+            for (i, pair) in sorted_cells.windows(2).enumerate() {
+                let (a, b) = less_than.assign_less_equal(
+                    layouter,
+                    sorted_values[i],
+                    sorted_values[i + 1],
+                )?;
+                layouter.assign_region(
+                    || format!("group_by order[{}]", i),
+                    |mut region| {
+                        region.constrain_equal(pair[0].cell(), a.cell())?;
+                        region.constrain_equal(pair[1].cell(), b.cell())
+                    },
+                )?;
+            }
+        }
+
+        Ok(GroupByCells {
+            inputs: input_cells,
+            sorted: sorted_cells,
+            markers: marker_cells,
+            boundaries: index_cells,
+        })
+    }
+
+    /// Check that a value is below 2^bits
+    pub(crate) fn fits_bits(value: &Field, bits: usize) -> bool {
+        let repr = value.to_repr();
+        (bits..Field::NUM_BITS as usize).all(|i| (repr.as_ref()[i / 8] >> (i % 8)) & 1 == 0)
+    }
+
+    /// Copy-constrain each row's group to the aggregation gate's
+    ///
+    /// Binds the aggregation gate's binary marker and start/end index cells
+    /// to the ones this gate proved, so both gates agree on the groups.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for the copy constraints
+    /// * `groups` - Cells returned by `assign`
+    /// * `aggregation` - Cells of the aggregation gate, per row
+    ///
+    /// # Returns
+    /// `Ok(())` if the constraints were added, `Err(Error::Synthesis)` if the
+    /// row counts differ
+    pub fn constrain_boundaries(
+        layouter: &mut impl Layouter<Field>,
+        groups: &GroupByCells,
+        aggregation: &AggregationCells,
+    ) -> Result<(), ErrorFront> {
+        let n = groups.boundaries.len();
+        if groups.markers.len() != n
+            || aggregation.markers.len() != n
+            || aggregation.indices.len() != n
+        {
+            return Err(ErrorFront::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "group boundaries",
+            |mut region| {
+                for (marker, other) in groups.markers.iter().zip(&aggregation.markers) {
+                    region.constrain_equal(marker.cell(), other.cell())?;
+                }
+                for ((start, end), (other_start, other_end)) in
+                    groups.boundaries.iter().zip(&aggregation.indices)
+                {
+                    region.constrain_equal(start.cell(), other_start.cell())?;
+                    region.constrain_equal(end.cell(), other_end.cell())?;
+                }
                 Ok(())
             },
         )
     }
 
+    /// Check group boundaries for `Strict` mode
+    ///
+    /// The groups must tile rows `0..n` in increasing order, and each group
+    /// must end exactly where a binary marker is zero.
+    ///
+    /// # Returns
+    /// `Ok(())` if the boundaries are consistent, `Err(Error::Other)` naming
+    /// the first inconsistent group otherwise
+    fn check_boundaries(
+        boundaries: &[(usize, usize)],
+        binary_markers: &[Field],
+        n: usize,
+    ) -> Result<(), ErrorFront> {
+        let mut next_start = 0;
+        for (group, &(start, end)) in boundaries.iter().enumerate() {
+            let ends_here = |i: usize| binary_markers[i] == Field::zero();
+            if start != next_start
+                || end < start
+                || end >= n
+                || !ends_here(end)
+                || (start..end).any(ends_here)
+            {
+                return Err(ErrorFront::Other(format!(
+                    "group {} spans rows {}..={}, inconsistent with the binary markers",
+                    group, start, end
+                )));
+            }
+            next_start = end + 1;
+        }
+        if next_start != n {
+            return Err(ErrorFront::Other(format!(
+                "groups cover {} of {} rows",
+                next_start, n
+            )));
+        }
+        Ok(())
+    }
+
     /// Get group boundaries from sorted values
     ///
     /// Returns a vector of (start_index, end_index) tuples for each group.
@@ -309,6 +607,7 @@ impl GroupByConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{fields, run_mock};
     use halo2_proofs::{
         circuit::{Layouter, SimpleFloorPlanner, Value},
         dev::MockProver,
@@ -338,7 +637,7 @@ mod tests {
             } else {
                 let v1 = sorted[i];
                 let v2 = sorted[i + 1];
-                let diff = v2 - v1;
+                let diff = v1 - v2;
 
                 if diff.is_zero().into() {
                     binary_markers.push(Field::one());
@@ -447,19 +746,21 @@ mod tests {
     /// Test circuit for group-by gate
     #[derive(Default)]
     struct TestCircuit {
+        input_values: Vec<Field>,
         sorted_values: Vec<Field>,
     }
 
     impl Circuit<Field> for TestCircuit {
         type Config = GroupByConfig;
         type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
 
         fn without_witnesses(&self) -> Self {
             Self::default()
         }
 
         fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
-            let advice = (0..5).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let advice = (0..6).map(|_| meta.advice_column()).collect::<Vec<_>>();
             GroupByConfig::configure(meta, &advice)
         }
 
@@ -469,7 +770,12 @@ mod tests {
             mut layouter: impl Layouter<Field>,
         ) -> Result<(), ErrorFront> {
             if !self.sorted_values.is_empty() {
-                config.assign(&mut layouter, &self.sorted_values)?;
+                config.assign(
+                    &mut layouter,
+                    &self.input_values,
+                    &self.sorted_values,
+                    ValidationMode::Fast,
+                )?;
             }
             Ok(())
         }
@@ -492,6 +798,7 @@ mod tests {
             let sorted: Vec<Field> = sorted_u64.iter().map(|&v| Field::from(v)).collect();
 
             let circuit = TestCircuit {
+                input_values: fields(&input_u64),
                 sorted_values: sorted,
             };
 
@@ -509,12 +816,97 @@ mod tests {
     #[test]
     fn test_group_by_circuit_empty() {
         // Test with empty input
-        let circuit = TestCircuit {
-            sorted_values: vec![],
-        };
+        let circuit = TestCircuit::default();
 
         let k = 10;
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()), "Empty circuit should verify");
     }
+
+    #[test]
+    fn test_group_by_rejects_unsorted_input() {
+        let circuit = TestCircuit {
+            input_values: fields(&[1, 2, 3]),
+            sorted_values: fields(&[2, 1, 3]),
+        };
+
+        let err = MockProver::run(10, &circuit, vec![]).unwrap_err();
+        assert!(
+            err.to_string().contains("not sorted"),
+            "Unexpected error: {}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_group_by_check_boundaries() {
+        let markers = [1u64, 0, 1, 0].map(Field::from);
+
+        assert!(GroupByConfig::check_boundaries(&[(0, 1), (2, 3)], &markers, 4).is_ok());
+        // Gap between groups
+        assert!(GroupByConfig::check_boundaries(&[(0, 0), (2, 3)], &markers, 4).is_err());
+        // Group ending where the markers continue it
+        assert!(GroupByConfig::check_boundaries(&[(0, 2), (3, 3)], &markers, 4).is_err());
+        // Trailing rows left uncovered
+        assert!(GroupByConfig::check_boundaries(&[(0, 1)], &markers, 4).is_err());
+    }
+
+    #[test]
+    fn test_group_by_rejects_non_permutation() {
+        // Sorted, but 2 replaces the input's 3
+        let circuit = TestCircuit {
+            input_values: fields(&[3, 1, 1]),
+            sorted_values: fields(&[1, 1, 2]),
+        };
+        assert!(run_mock(&circuit, 10).is_err());
+    }
+
+    /// Test circuit for group-by with an order check
+    #[derive(Default)]
+    struct OrderedTestCircuit {
+        sorted_values: Vec<Field>,
+    }
+
+    impl Circuit<Field> for OrderedTestCircuit {
+        type Config = GroupByConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..9).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let less_than = LessThanConfig::configure(meta, &advice[6..], 8);
+            GroupByConfig::configure(meta, &advice[..6]).with_order_check(less_than)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let values = &self.sorted_values;
+            config.assign(&mut layouter, values, values, ValidationMode::Fast)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_group_by_order_check() {
+        let circuit = OrderedTestCircuit {
+            sorted_values: fields(&[1, 1, 2, 5, 5]),
+        };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Unsorted input and values too wide for the gate are rejected
+        for values in [fields(&[2, 1, 3]), fields(&[1, 256])] {
+            let circuit = OrderedTestCircuit {
+                sorted_values: values,
+            };
+            assert!(MockProver::run(10, &circuit, vec![]).is_err());
+        }
+    }
 }
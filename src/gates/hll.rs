@@ -0,0 +1,1203 @@
+//! HyperLogLog sketch gate for approximate COUNT(DISTINCT)
+//!
+//! This module provides a HyperLogLog sketch and a gate proving that the
+//! sketch registers are the per-bucket maxima of the hashes of a column's
+//! cells. The registers are exposed as public outputs, so a verifier can
+//! recompute the estimate without the exact distinct count ever being
+//! proven.
+//!
+//! # Sketch
+//!
+//! Each value is hashed to 64 bits with three rounds of a 64-bit
+//! multiply-and-fold, where fold(t) = (⌊t / 2^64⌋ + t) mod 2^64:
+//!
+//! - x_1 = fold(v · K_1 + C_1)
+//! - x_2 = fold(x_1² + C_2)
+//! - h = fold(x_2 · K_2 + C_3)
+//!
+//! The top `p` bits of h select one of `m = 2^p` buckets, and `rho` is one
+//! plus the number of leading zeros of the remaining `w = 64 - p` bits (at
+//! most w + 1). Register j holds the maximum `rho` observed in bucket j (0
+//! if none), and the estimate is `α_m · m² / Σ 2^(-register_j)`, with linear
+//! counting for small cardinalities. Only values below 2^64 can be hashed
+//! in the circuit; wider values (e.g., strings) are first reduced to 64
+//! bits with SHA-256 for estimates outside it.
+//!
+//! # Method
+//!
+//! Each hashed value takes `HASH_ROWS` rows, whose words are range checked
+//! to 64 bits by u8 lookups; its cell is copied from the column:
+//!
+//! 1. NULL: null · (1 - null) = 0, null · (v + 1) = 0; a NULL value is not
+//!    hashed and not observed
+//! 2. Round r: a_r · b_r + C_r = hi_r · 2^64 + lo_r,
+//!    hi_r + lo_r = o_r · 2^64 + x_r, o_r ∈ {0, 1}
+//! 3. Split h = bucket · 2^w + rest, from the bytes of h
+//! 4. Rho: (rho, 2^(rho-1), full) is in the rho table, where full marks
+//!    rho = w + 1; P = rest · 2^(rho-1) < 2^w and
+//!    T = 2 · P - 2^w · (1 - full) ∈ [0, 2^w), i.e. rest has w + 1 - rho
+//!    bits
+//!
+//! The observations (bucket, rho) are assigned sorted by bucket, then rho,
+//! together with a sentinel (j, 0) for every bucket j, so that every bucket
+//! appears and its last row holds its register:
+//!
+//! 5. Observed rows: {(bucket, rho)} of the rows flagged observed equals
+//!    {(bucket, rho)} of the hashed values as multisets (shuffle), and the
+//!    other rows have rho = 0
+//! 6. First row: bucket_0 = 0, rho_0 = 0
+//! 7. Step: d = bucket_{i+1} - bucket_i, d · (1 - d) = 0, last_i = d
+//! 8. Carry: c_i = rho_i · (1 - last_i)
+//! 9. Order: c_i ≤ rho_{i+1} (less-than gate), so rho never decreases within
+//!    a bucket and never goes below 0
+//! 10. Last row: bucket_{N-1} = m - 1, last_{N-1} = 1
+//! 11. Output: rho of each row with last = 1 equals instance[offset + bucket]
+//!
+//! # Constraints
+//!
+//! - Hash constraints: 13 + 2 · p / 8 per value
+//! - Word range checks: 9 per word, `HASH_ROWS` words per value
+//! - Rho lookup and observation shuffle: 2 per value
+//! - Observed-row constraints: 2 per observation
+//! - First/last row constraints: 4
+//! - Step and carry constraints: 3 per adjacent pair
+//! - Order constraints: RHO_BITS + 2 per adjacent pair
+//! - Instance constraints: 1 per bucket (copy constraint)
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::hll::{HllConfig, HyperLogLog};
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut sketch = HyperLogLog::new(8);
+//! sketch.insert(&Field::from(42u64));
+//! let estimate = sketch.estimate();
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 20];
+//! let u8_table = meta.lookup_table_column();
+//! let instance = meta.instance_column();
+//!
+//! let config = HllConfig::configure(&mut meta, &advice, u8_table, instance, 8);
+//! ```
+
+use super::less_than::LessThanConfig;
+use crate::field::FieldUtils;
+use crate::types::Value as SqlValue;
+use ff::{Field as _, PrimeField};
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, ErrorFront, Expression, Instance, Selector, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+
+/// Precision (log2 of the number of buckets) used for APPROX_COUNT_DISTINCT
+///
+/// 256 buckets give a standard error of about 6.5%.
+pub const HLL_PRECISION: u32 = 8;
+
+/// Bit-width bounding rho in the order check (rho ≤ 64 - p + 1 < 2^7)
+pub(crate) const RHO_BITS: usize = 7;
+
+/// Multiplier of the first hash round
+const HASH_K1: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Multiplier of the third hash round
+const HASH_K2: u64 = 0xBF58_476D_1CE4_E5B9;
+
+/// Offsets of the three hash rounds
+///
+/// The first is below `HASH_K1`, so NULL (-1) can't be hashed: -K_1 + C_1
+/// wraps around the field, far above 2^128.
+const HASH_OFFSETS: [u64; 3] = [
+    0x243F_6A88_85A3_08D3,
+    0x94D0_49BB_1331_11EB,
+    0x4528_21E6_38D0_1377,
+];
+
+/// Rows per hashed value: (hi, lo, x) of each round, then P and T
+pub(crate) const HASH_ROWS: usize = 11;
+
+/// HyperLogLog sketch over field values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    /// Log2 of the number of buckets
+    precision: u32,
+
+    /// Maximum rho per bucket
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Create an empty sketch with 2^precision buckets
+    ///
+    /// # Arguments
+    /// * `precision` - Log2 of the number of buckets
+    ///
+    /// # Panics
+    /// Panics if `precision` is not in [4, 16]
+    pub fn new(precision: u32) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "Precision must be in [4, 16]"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Rebuild a sketch from its registers, e.g. the public outputs of a proof
+    ///
+    /// # Arguments
+    /// * `precision` - Log2 of the number of buckets
+    /// * `registers` - One register per bucket
+    ///
+    /// # Returns
+    /// `Some(HyperLogLog)` if there are 2^precision registers, each below
+    /// 2^RHO_BITS, `None` otherwise
+    pub fn from_registers(precision: u32, registers: &[Field]) -> Option<Self> {
+        if !(4..=16).contains(&precision) || registers.len() != 1 << precision {
+            return None;
+        }
+        let registers = registers
+            .iter()
+            .map(|r| {
+                FieldUtils::to_u64(r)
+                    .filter(|&r| r < 1 << RHO_BITS)
+                    .map(|r| r as u8)
+            })
+            .collect::<Option<Vec<u8>>>()?;
+        Some(Self {
+            precision,
+            registers,
+        })
+    }
+
+    /// Compute the (hi, lo, folded) words of each hash round of a value
+    ///
+    /// # Arguments
+    /// * `value` - Value to hash
+    ///
+    /// # Returns
+    /// The high and low halves of each round's 128-bit result and their
+    /// sum modulo 2^64; the last folded word is the hash
+    fn hash_rounds(value: u64) -> [(u64, u64, u64); 3] {
+        let mut rounds = [(0, 0, 0); 3];
+        let mut x = value;
+        for (r, round) in rounds.iter_mut().enumerate() {
+            let (a, b) = match r {
+                0 => (x, HASH_K1),
+                1 => (x, x),
+                _ => (x, HASH_K2),
+            };
+            let t = a as u128 * b as u128 + HASH_OFFSETS[r] as u128;
+            let (hi, lo) = ((t >> 64) as u64, t as u64);
+            x = hi.wrapping_add(lo);
+            *round = (hi, lo, x);
+        }
+        rounds
+    }
+
+    /// Hash a value to 64 bits, as the sketch gate does
+    pub fn hash(value: u64) -> u64 {
+        Self::hash_rounds(value)[2].2
+    }
+
+    /// Map a value to its (bucket, rho) observation
+    ///
+    /// # Arguments
+    /// * `value` - Value to observe
+    /// * `precision` - Log2 of the number of buckets
+    ///
+    /// # Returns
+    /// The bucket index and rho of the value's hash; values of 2^64 or more
+    /// are reduced to their SHA-256 digest's first 8 bytes before hashing
+    pub fn observe(value: &Field, precision: u32) -> (usize, u8) {
+        use sha2::{Digest, Sha256};
+
+        let value = FieldUtils::to_u64(value).unwrap_or_else(|| {
+            let digest = Sha256::digest(value.to_repr().as_ref());
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&digest[..8]);
+            u64::from_be_bytes(bytes)
+        });
+        let hash = Self::hash(value);
+
+        let bucket = (hash >> (64 - precision)) as usize;
+        let rest = hash << precision;
+        let rho = (rest.leading_zeros()).min(64 - precision) + 1;
+        (bucket, rho as u8)
+    }
+
+    /// Add a value to the sketch
+    pub fn insert(&mut self, value: &Field) {
+        let (bucket, rho) = Self::observe(value, self.precision);
+        self.registers[bucket] = self.registers[bucket].max(rho);
+    }
+
+    /// Get the precision (log2 of the number of buckets)
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Get the registers, one per bucket
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// Estimate the number of distinct values inserted
+    ///
+    /// # Returns
+    /// The HyperLogLog estimate, using linear counting while it is below
+    /// 2.5 · m and some buckets are empty
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let estimate = alpha * m * m / sum;
+
+        let empty = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    /// Compute the observations of a sketch, as assigned by `HllConfig`
+    ///
+    /// # Arguments
+    /// * `values` - Values inserted into the sketch
+    /// * `precision` - Log2 of the number of buckets
+    ///
+    /// # Returns
+    /// The (bucket, rho) observation of each value plus a (bucket, 0)
+    /// sentinel per bucket, sorted by bucket, then rho
+    pub fn observations(values: &[Field], precision: u32) -> Vec<(usize, u8)> {
+        let mut observations: Vec<(usize, u8)> = values
+            .iter()
+            .map(|value| Self::observe(value, precision))
+            .chain((0..1 << precision).map(|bucket| (bucket, 0)))
+            .collect();
+        observations.sort_unstable();
+        observations
+    }
+}
+
+/// Configuration for the HyperLogLog sketch gate
+///
+/// This gate verifies that public registers are the per-bucket maxima of
+/// the (bucket, rho) observations of hashed cells.
+///
+/// Each hashed value takes `HASH_ROWS` rows of the word, byte and auxiliary
+/// columns:
+///
+/// | row | word             | aux[0]     | aux[1]    | aux[2] |
+/// |-----|------------------|------------|-----------|--------|
+/// | 0   | hi_1             | value      | null      |        |
+/// | 1   | lo_1             |            |           |        |
+/// | 2   | x_1              | overflow_1 |           |        |
+/// | 3-5 | hi_2, lo_2, x_2  | overflow_2 |           |        |
+/// | 6-8 | hi_3, lo_3, h    | overflow_3 |           |        |
+/// | 9   | P                | rho        | 2^(rho-1) | full   |
+/// | 10  | T                |            |           |        |
+#[derive(Debug, Clone)]
+pub struct HllConfig {
+    /// Column for bucket indices
+    pub bucket: Column<Advice>,
+
+    /// Column for rho values
+    pub rho: Column<Advice>,
+
+    /// Column marking the last row of each bucket
+    pub last: Column<Advice>,
+
+    /// Column for rho carried to the next row of the same bucket
+    pub carry: Column<Advice>,
+
+    /// Less-than gate proving rho is non-decreasing within a bucket
+    pub order: LessThanConfig,
+
+    /// Column flagging the observations of hashed values (not sentinels)
+    pub observed: Column<Advice>,
+
+    /// Column for the 64-bit words of the hash rows
+    pub word: Column<Advice>,
+
+    /// Columns for the u8 cells of each word, least significant first
+    pub bytes: [Column<Advice>; 8],
+
+    /// Auxiliary columns of the hash rows (see the layout above)
+    pub aux: [Column<Advice>; 3],
+
+    /// u8 lookup table, e.g. `BitwiseRangeCheckConfig::u8_table`
+    pub u8_table: TableColumn,
+
+    /// Lookup table of rho values, with (0, 0, 0) for unhashed rows
+    pub rho_table: TableColumn,
+
+    /// Lookup table of 2^(rho-1), next to each rho
+    pub power_table: TableColumn,
+
+    /// Lookup table flagging rho = w + 1, next to each rho
+    pub full_table: TableColumn,
+
+    /// Instance column exposing the registers
+    pub instance: Column<Instance>,
+
+    /// Log2 of the number of buckets
+    pub precision: u32,
+
+    /// Selector for the first row
+    pub s_first: Selector,
+
+    /// Selector for rows with a successor
+    pub s_step: Selector,
+
+    /// Selector for the final row
+    pub s_last: Selector,
+
+    /// Selector for every observation row
+    pub s_row: Selector,
+
+    /// Selector for the first hash row of each value
+    pub s_hash: Selector,
+
+    /// Selector for every hash row, range checking its word
+    pub s_word: Selector,
+}
+
+impl HllConfig {
+    /// Configure the sketch gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 20: bucket, rho,
+    ///   last, carry, 3 for the less-than gate, observed, word, 8 u8 cells
+    ///   and 3 auxiliary columns)
+    /// * `u8_table` - u8 lookup table, e.g. `BitwiseRangeCheckConfig::u8_table`
+    /// * `instance` - Instance column exposing the registers
+    /// * `precision` - Log2 of the number of buckets (8 or 16)
+    ///
+    /// # Returns
+    /// `HllConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided or the precision is not a
+    /// whole number of bytes
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        advice: &[Column<Advice>],
+        u8_table: TableColumn,
+        instance: Column<Instance>,
+        precision: u32,
+    ) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 20,
+            "Need at least 20 advice columns (bucket, rho, last, carry, a, b, z, observed, \
+             word, 8 u8 cells, 3 auxiliary)"
+        );
+        assert!(
+            precision == 8 || precision == 16,
+            "Precision must be 8 or 16"
+        );
+
+        // Assign columns
+        let bucket = advice[0];
+        let rho = advice[1];
+        let last = advice[2];
+        let carry = advice[3];
+        let order = LessThanConfig::configure(meta, &advice[4..7], RHO_BITS);
+        let observed = advice[7];
+        let word = advice[8];
+        let bytes: [Column<Advice>; 8] = advice[9..17].try_into().unwrap();
+        let aux: [Column<Advice>; 3] = advice[17..20].try_into().unwrap();
+        let rho_table = meta.lookup_table_column();
+        let power_table = meta.lookup_table_column();
+        let full_table = meta.lookup_table_column();
+
+        // Enable equality for copies to the less-than gate and the instance,
+        // and of the hashed cells
+        meta.enable_equality(rho);
+        meta.enable_equality(carry);
+        meta.enable_equality(aux[0]);
+        meta.enable_equality(instance);
+
+        let s_first = meta.selector();
+        let s_step = meta.selector();
+        let s_last = meta.selector();
+        let s_row = meta.complex_selector();
+        let s_hash = meta.complex_selector();
+        let s_word = meta.complex_selector();
+
+        let one = || Expression::Constant(Field::one());
+        let two_64 = Expression::Constant(Field::from_u128(1 << 64));
+        let rest_bits = 64 - precision as usize;
+        let rest_bytes = rest_bits / 8;
+        let two_rest = Expression::Constant(Field::from_u128(1 << rest_bits));
+
+        // Constraint 1: each hash row's word is a 64-bit integer
+        // word = Σ(i < 8) byte_i · 2^(8i), byte_i ∈ [0, 255]
+        meta.create_gate("hll_word", |meta| {
+            let q = meta.query_selector(s_word);
+            let word = meta.query_advice(word, Rotation::cur());
+            let recomposed = bytes.iter().enumerate().fold(
+                Expression::Constant(Field::zero()),
+                |acc, (i, &col)| {
+                    acc + meta.query_advice(col, Rotation::cur())
+                        * Field::from_u128(1u128 << (8 * i))
+                },
+            );
+
+            vec![q * (word - recomposed)]
+        });
+        for &col in &bytes {
+            meta.lookup("hll_word_u8", |meta| {
+                let q = meta.query_selector(s_word);
+                let cell = meta.query_advice(col, Rotation::cur());
+                vec![(q * cell, u8_table)]
+            });
+        }
+
+        // Constraint 2: the hash rows hash the value, unless it is NULL
+        meta.create_gate("hll_hash", |meta| {
+            let q = meta.query_selector(s_hash);
+            let words: Vec<_> = (0..HASH_ROWS)
+                .map(|row| meta.query_advice(word, Rotation(row as i32)))
+                .collect();
+            let value = meta.query_advice(aux[0], Rotation::cur());
+            let null = meta.query_advice(aux[1], Rotation::cur());
+            let power = meta.query_advice(aux[1], Rotation(9));
+            let full = meta.query_advice(aux[2], Rotation(9));
+            let (_, rest) = Self::split_hash(meta, &bytes, rest_bytes);
+            let hashed = q.clone() * (one() - null.clone());
+
+            // NULL (-1) is not hashed
+            let mut constraints = vec![
+                q.clone() * null.clone() * (one() - null.clone()),
+                q.clone() * null * (value.clone() + one()),
+            ];
+
+            // a_r · b_r + C_r = hi_r · 2^64 + lo_r, hi_r + lo_r = o_r · 2^64 + x_r
+            let mut x = value;
+            for (r, &offset) in HASH_OFFSETS.iter().enumerate() {
+                let (hi, lo, out) = (
+                    words[3 * r].clone(),
+                    words[3 * r + 1].clone(),
+                    words[3 * r + 2].clone(),
+                );
+                let overflow = meta.query_advice(aux[0], Rotation(3 * r as i32 + 2));
+                let product = match r {
+                    0 => x * Field::from(HASH_K1),
+                    1 => x.clone() * x,
+                    _ => x * Field::from(HASH_K2),
+                };
+                constraints.push(
+                    hashed.clone()
+                        * (product + Expression::Constant(Field::from(offset))
+                            - hi.clone() * two_64.clone()
+                            - lo.clone()),
+                );
+                constraints.push(
+                    hashed.clone() * (hi + lo - overflow.clone() * two_64.clone() - out.clone()),
+                );
+                constraints.push(q.clone() * overflow.clone() * (one() - overflow));
+                x = out;
+            }
+
+            // P = rest · 2^(rho-1), T = 2 · P - 2^w · (1 - full), both below 2^w
+            constraints.push(hashed.clone() * (words[9].clone() - rest * power));
+            constraints.push(
+                hashed
+                    * (words[10].clone() - words[9].clone() * Field::from(2)
+                        + two_rest.clone() * (one() - full)),
+            );
+            for row in [9, 10] {
+                for &col in &bytes[rest_bytes..] {
+                    constraints.push(q.clone() * meta.query_advice(col, Rotation(row)));
+                }
+            }
+
+            constraints
+        });
+
+        // Constraint 3: (rho, 2^(rho-1), full) is a row of the rho table
+        meta.lookup("hll_rho", |meta| {
+            let q = meta.query_selector(s_hash);
+            let rho = meta.query_advice(aux[0], Rotation(9));
+            let power = meta.query_advice(aux[1], Rotation(9));
+            let full = meta.query_advice(aux[2], Rotation(9));
+
+            vec![
+                (q.clone() * rho, rho_table),
+                (q.clone() * power, power_table),
+                (q * full, full_table),
+            ]
+        });
+
+        // Constraint 4: observed rows are the observations of the hashed values
+        // {(q · h, q · h · bucket, q · h · rho)} = {(q · o, q · o · bucket, q · o · rho)}
+        meta.shuffle("hll_observations", |meta| {
+            let q_hash = meta.query_selector(s_hash);
+            let null = meta.query_advice(aux[1], Rotation::cur());
+            let hash_rho = meta.query_advice(aux[0], Rotation(9));
+            let (hash_bucket, _) = Self::split_hash(meta, &bytes, rest_bytes);
+            let hashed = q_hash * (one() - null);
+
+            let q_row = meta.query_selector(s_row);
+            let observed = meta.query_advice(observed, Rotation::cur());
+            let bucket = meta.query_advice(bucket, Rotation::cur());
+            let rho = meta.query_advice(rho, Rotation::cur());
+            let observation = q_row * observed;
+
+            vec![
+                (hashed.clone(), observation.clone()),
+                (hashed.clone() * hash_bucket, observation.clone() * bucket),
+                (hashed * hash_rho, observation * rho),
+            ]
+        });
+
+        // Constraint 5: other rows are sentinels, with rho = 0
+        // o · (1 - o) = 0, (1 - o) · rho = 0
+        meta.create_gate("hll_row", |meta| {
+            let q = meta.query_selector(s_row);
+            let observed = meta.query_advice(observed, Rotation::cur());
+            let rho = meta.query_advice(rho, Rotation::cur());
+
+            vec![
+                q.clone() * observed.clone() * (one() - observed.clone()),
+                q * (one() - observed) * rho,
+            ]
+        });
+
+        // Constraint 6: the first row is the sentinel of bucket 0
+        // bucket_0 = 0, rho_0 = 0
+        meta.create_gate("hll_first", |meta| {
+            let q = meta.query_selector(s_first);
+            let bucket = meta.query_advice(bucket, Rotation::cur());
+            let rho = meta.query_advice(rho, Rotation::cur());
+
+            vec![q.clone() * bucket, q * rho]
+        });
+
+        // Constraint 7: buckets advance by 0 or 1, marking the last row of each
+        // d · (1 - d) = 0, last = d, c = rho · (1 - last)
+        meta.create_gate("hll_step", |meta| {
+            let q = meta.query_selector(s_step);
+            let bucket_cur = meta.query_advice(bucket, Rotation::cur());
+            let bucket_next = meta.query_advice(bucket, Rotation::next());
+            let rho = meta.query_advice(rho, Rotation::cur());
+            let last = meta.query_advice(last, Rotation::cur());
+            let carry = meta.query_advice(carry, Rotation::cur());
+            let d = bucket_next - bucket_cur;
+
+            vec![
+                q.clone() * d.clone() * (one() - d.clone()),
+                q.clone() * (last.clone() - d),
+                q * (carry - rho * (one() - last)),
+            ]
+        });
+
+        // Constraint 8: the final row ends the last bucket
+        // bucket_{N-1} = m - 1, last_{N-1} = 1
+        let last_bucket = Field::from((1u64 << precision) - 1);
+        meta.create_gate("hll_last", |meta| {
+            let q = meta.query_selector(s_last);
+            let bucket = meta.query_advice(bucket, Rotation::cur());
+            let last = meta.query_advice(last, Rotation::cur());
+
+            vec![
+                q.clone() * (bucket - Expression::Constant(last_bucket)),
+                q * (last - one()),
+            ]
+        });
+
+        Self {
+            bucket,
+            rho,
+            last,
+            carry,
+            order,
+            observed,
+            word,
+            bytes,
+            aux,
+            u8_table,
+            rho_table,
+            power_table,
+            full_table,
+            instance,
+            precision,
+            s_first,
+            s_step,
+            s_last,
+            s_row,
+            s_hash,
+            s_word,
+        }
+    }
+
+    /// Query the bucket and the rest of the hash h from its bytes, on the
+    /// hash rows of a value
+    fn split_hash(
+        meta: &mut VirtualCells<'_, Field>,
+        bytes: &[Column<Advice>; 8],
+        rest_bytes: usize,
+    ) -> (Expression<Field>, Expression<Field>) {
+        let hash_bytes: Vec<_> = bytes
+            .iter()
+            .map(|&col| meta.query_advice(col, Rotation(8)))
+            .collect();
+        let recompose = |bytes: &[Expression<Field>]| {
+            bytes
+                .iter()
+                .enumerate()
+                .fold(Expression::Constant(Field::zero()), |acc, (i, byte)| {
+                    acc + byte.clone() * Field::from_u128(1u128 << (8 * i))
+                })
+        };
+        (
+            recompose(&hash_bytes[rest_bytes..]),
+            recompose(&hash_bytes[..rest_bytes]),
+        )
+    }
+
+    /// Number of buckets (and registers)
+    pub fn num_buckets(&self) -> usize {
+        1 << self.precision
+    }
+
+    /// Number of constraints for `input_len` hashed values
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of hashed values, all assumed non-NULL
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        if input_len == 0 {
+            return 0;
+        }
+        let hash = 13 + 2 * self.precision as usize / 8 + 9 * HASH_ROWS + 2;
+        let observations = input_len + self.num_buckets();
+        let pairs = observations - 1;
+        hash * input_len
+            + 2 * observations
+            + 4
+            + 3 * pairs
+            + self.order.num_constraints(pairs)
+            + self.num_buckets()
+    }
+
+    /// Compute the registers of sorted observations
+    ///
+    /// # Arguments
+    /// * `observations` - Observations as returned by `HyperLogLog::observations`
+    /// * `precision` - Log2 of the number of buckets
+    ///
+    /// # Returns
+    /// The maximum rho per bucket, as field elements
+    pub fn registers(observations: &[(usize, u8)], precision: u32) -> Vec<Field> {
+        let mut registers = vec![0u8; 1 << precision];
+        for &(bucket, rho) in observations {
+            if let Some(register) = registers.get_mut(bucket) {
+                *register = (*register).max(rho);
+            }
+        }
+        registers
+            .into_iter()
+            .map(|r| Field::from(r as u64))
+            .collect()
+    }
+
+    /// Load the rho lookup table
+    ///
+    /// The table holds (0, 0, 0) for unhashed rows, then
+    /// (rho, 2^(rho-1), rho = w + 1) for each rho in [1, w + 1]. It must be
+    /// loaded once per circuit, however many sketches it assigns; the u8
+    /// table is loaded separately via
+    /// `BitwiseRangeCheckConfig::load_lookup_table`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    pub fn load_rho_table(&self, layouter: &mut impl Layouter<Field>) -> Result<(), ErrorFront> {
+        let rest_bits = 64 - self.precision as u64;
+        let rows = std::iter::once((0, Field::zero(), false)).chain(
+            (1..=rest_bits + 1)
+                .map(|rho| (rho, Field::from_u128(1 << (rho - 1)), rho == rest_bits + 1)),
+        );
+
+        layouter.assign_table(
+            || "hll rho table",
+            |mut table| {
+                for (i, (rho, power, full)) in rows.clone().enumerate() {
+                    table.assign_cell(
+                        || format!("rho[{}]", i),
+                        self.rho_table,
+                        i,
+                        || Value::known(Field::from(rho)),
+                    )?;
+                    table.assign_cell(
+                        || format!("power[{}]", i),
+                        self.power_table,
+                        i,
+                        || Value::known(power),
+                    )?;
+                    table.assign_cell(
+                        || format!("full[{}]", i),
+                        self.full_table,
+                        i,
+                        || Value::known(Field::from(full as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Hash values and bind the registers of their sketch to public
+    /// instance rows
+    ///
+    /// NULL values are skipped. The returned cells hold the values, for
+    /// copy constraints binding them to the sketched column's cells.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `values` - Values to sketch, NULL or below 2^64
+    /// * `instance_offset` - Instance row of the register of bucket 0
+    ///
+    /// # Returns
+    /// `Ok(cells)` with the cell of each value, in order,
+    /// `Err(Error::Other)` if a value is neither NULL nor below 2^64
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[Field],
+        instance_offset: usize,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        let hashed: Vec<Field> = values
+            .iter()
+            .filter(|value| !SqlValue::is_null_field(value))
+            .copied()
+            .collect();
+        let observations = HyperLogLog::observations(&hashed, self.precision);
+        self.assign_observations(layouter, values, &observations, instance_offset)
+    }
+
+    /// Assign the hash rows of values and the given sorted observations
+    ///
+    /// # Returns
+    /// `Ok(cells)` with the cell of each value, `Err(Error::Other)` if a
+    /// value can't be hashed, or the observations are not sorted, miss a
+    /// bucket's sentinel, or have a rho outside [0, 2^RHO_BITS)
+    fn assign_observations(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[Field],
+        observations: &[(usize, u8)],
+        instance_offset: usize,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        let hashes = values
+            .iter()
+            .map(|value| {
+                if SqlValue::is_null_field(value) {
+                    return Ok(None);
+                }
+                FieldUtils::to_u64(value).map(Some).ok_or_else(|| {
+                    ErrorFront::Other(format!("sketch value {:?} exceeds 64 bits", value))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let m = self.num_buckets();
+        let sorted = observations.windows(2).all(|pair| pair[0] <= pair[1]);
+        if !sorted || !(0..m).all(|j| observations.binary_search(&(j, 0)).is_ok()) {
+            return Err(ErrorFront::Other(
+                "sketch observations must be sorted and include a sentinel per bucket".to_string(),
+            ));
+        }
+        if let Some(&(bucket, rho)) = observations
+            .iter()
+            .find(|(bucket, rho)| *bucket >= m || *rho >= 1 << RHO_BITS)
+        {
+            return Err(ErrorFront::Other(format!(
+                "sketch observation ({}, {}) out of range",
+                bucket, rho
+            )));
+        }
+
+        let input_cells = layouter.assign_region(
+            || "hll hash",
+            |mut region| {
+                let mut input_cells = Vec::with_capacity(values.len());
+                for (i, (&value, hash)) in values.iter().zip(&hashes).enumerate() {
+                    let row = i * HASH_ROWS;
+                    self.s_hash.enable(&mut region, row)?;
+
+                    let (words, overflows, (rho, power, full)) = match hash {
+                        Some(hash) => self.hash_witness(*hash),
+                        None => ([0; HASH_ROWS], [false; 3], (0, 0, false)),
+                    };
+                    for (j, &word) in words.iter().enumerate() {
+                        self.s_word.enable(&mut region, row + j)?;
+                        region.assign_advice(
+                            || format!("word[{}][{}]", i, j),
+                            self.word,
+                            row + j,
+                            || Value::known(Field::from(word)),
+                        )?;
+                        for (k, (&col, &byte)) in self
+                            .bytes
+                            .iter()
+                            .zip(&FieldUtils::decompose_u64(word))
+                            .enumerate()
+                        {
+                            region.assign_advice(
+                                || format!("word[{}][{}].u8_cell[{}]", i, j, k),
+                                col,
+                                row + j,
+                                || Value::known(Field::from(byte as u64)),
+                            )?;
+                        }
+                    }
+
+                    let input_cell = region.assign_advice(
+                        || format!("value[{}]", i),
+                        self.aux[0],
+                        row,
+                        || Value::known(value),
+                    )?;
+                    region.assign_advice(
+                        || format!("null[{}]", i),
+                        self.aux[1],
+                        row,
+                        || Value::known(Field::from(hash.is_none() as u64)),
+                    )?;
+                    for (r, &overflow) in overflows.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("overflow[{}][{}]", i, r),
+                            self.aux[0],
+                            row + 3 * r + 2,
+                            || Value::known(Field::from(overflow as u64)),
+                        )?;
+                    }
+                    for (col, (name, value)) in self.aux.iter().zip([
+                        ("rho", Field::from(rho as u64)),
+                        ("power", Field::from(power)),
+                        ("full", Field::from(full as u64)),
+                    ]) {
+                        region.assign_advice(
+                            || format!("{}[{}]", name, i),
+                            *col,
+                            row + 9,
+                            || Value::known(value),
+                        )?;
+                    }
+
+                    input_cells.push(input_cell);
+                }
+
+                Ok(input_cells)
+            },
+        )?;
+
+        let n = observations.len();
+        let (rho_cells, carry_cells, register_cells) = layouter.assign_region(
+            || "hll gate",
+            |mut region| {
+                let mut rho_cells = Vec::with_capacity(n);
+                let mut carry_cells = Vec::with_capacity(n);
+                let mut register_cells: Vec<AssignedCell<Field, Field>> = Vec::with_capacity(m);
+
+                for (i, &(bucket, rho)) in observations.iter().enumerate() {
+                    self.s_row.enable(&mut region, i)?;
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    }
+                    let is_last = match observations.get(i + 1) {
+                        Some(&(next_bucket, _)) => {
+                            self.s_step.enable(&mut region, i)?;
+                            next_bucket != bucket
+                        }
+                        None => {
+                            self.s_last.enable(&mut region, i)?;
+                            true
+                        }
+                    };
+
+                    region.assign_advice(
+                        || format!("bucket[{}]", i),
+                        self.bucket,
+                        i,
+                        || Value::known(Field::from(bucket as u64)),
+                    )?;
+                    let rho_cell = region.assign_advice(
+                        || format!("rho[{}]", i),
+                        self.rho,
+                        i,
+                        || Value::known(Field::from(rho as u64)),
+                    )?;
+                    // Sentinels have rho = 0, observations at least 1
+                    region.assign_advice(
+                        || format!("observed[{}]", i),
+                        self.observed,
+                        i,
+                        || Value::known(Field::from((rho > 0) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || format!("last[{}]", i),
+                        self.last,
+                        i,
+                        || Value::known(Field::from(is_last as u64)),
+                    )?;
+                    let carry = if is_last { 0 } else { rho };
+                    let carry_cell = region.assign_advice(
+                        || format!("carry[{}]", i),
+                        self.carry,
+                        i,
+                        || Value::known(Field::from(carry as u64)),
+                    )?;
+
+                    if is_last {
+                        register_cells.push(rho_cell.clone());
+                    }
+                    rho_cells.push(rho_cell);
+                    carry_cells.push(carry_cell);
+                }
+
+                Ok((rho_cells, carry_cells, register_cells))
+            },
+        )?;
+
+        // Prove c_i ≤ rho_{i+1} on copies of each adjacent pair
+        for i in 0..n.saturating_sub(1) {
+            let carry = if observations[i].0 == observations[i + 1].0 {
+                observations[i].1
+            } else {
+                0
+            };
+            let (a, b) = self.order.assign_less_equal(
+                layouter,
+                Field::from(carry as u64),
+                Field::from(observations[i + 1].1 as u64),
+            )?;
+            layouter.assign_region(
+                || format!("hll order[{}]", i),
+                |mut region| {
+                    region.constrain_equal(carry_cells[i].cell(), a.cell())?;
+                    region.constrain_equal(rho_cells[i + 1].cell(), b.cell())
+                },
+            )?;
+        }
+
+        // Register j is the rho of the last row of bucket j
+        for (j, cell) in register_cells.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), self.instance, instance_offset + j)?;
+        }
+
+        Ok(input_cells)
+    }
+
+    /// Compute the hash rows of a value
+    ///
+    /// # Returns
+    /// The word of each row, the overflow bit of each round, and
+    /// (rho, 2^(rho-1), rho = w + 1)
+    fn hash_witness(&self, value: u64) -> ([u64; HASH_ROWS], [bool; 3], (u8, u64, bool)) {
+        let rounds = HyperLogLog::hash_rounds(value);
+        let rest_bits = 64 - self.precision;
+        let hash = rounds[2].2;
+        let rest = hash & ((1 << rest_bits) - 1);
+        let rho = rest_bits - (64 - rest.leading_zeros()) + 1;
+        let power = 1u64 << (rho - 1);
+        let full = rho == rest_bits + 1;
+        let p = rest * power;
+        let t = if full {
+            2 * p
+        } else {
+            2 * p - (1 << rest_bits)
+        };
+
+        let mut words = [0; HASH_ROWS];
+        let mut overflows = [false; 3];
+        for (r, &(hi, lo, x)) in rounds.iter().enumerate() {
+            words[3 * r..3 * r + 3].copy_from_slice(&[hi, lo, x]);
+            overflows[r] = hi.checked_add(lo).is_none();
+        }
+        words[9] = p;
+        words[10] = t;
+
+        (words, overflows, (rho as u8, power, full))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::range_check::BitwiseRangeCheckConfig;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+
+    #[test]
+    fn test_hll_estimate_within_error() {
+        let precision = HLL_PRECISION;
+        // Standard error 1.04 / sqrt(m); allow 3 standard errors
+        let bound = 3.0 * 1.04 / ((1u64 << precision) as f64).sqrt();
+
+        for distinct in [100u64, 1_000, 20_000] {
+            let mut sketch = HyperLogLog::new(precision);
+            // Every value appears 3 times
+            for i in 0..3 * distinct {
+                sketch.insert(&Field::from(i % distinct));
+            }
+            let error = (sketch.estimate() - distinct as f64).abs() / distinct as f64;
+            assert!(
+                error < bound,
+                "Estimate {} too far from {}",
+                sketch.estimate(),
+                distinct
+            );
+        }
+    }
+
+    #[test]
+    fn test_hll_from_registers() {
+        let mut sketch = HyperLogLog::new(4);
+        let values: Vec<Field> = (0..50u64).map(Field::from).collect();
+        values.iter().for_each(|v| sketch.insert(v));
+
+        let registers = HllConfig::registers(&HyperLogLog::observations(&values, 4), 4);
+        assert_eq!(HyperLogLog::from_registers(4, &registers), Some(sketch));
+        assert_eq!(HyperLogLog::from_registers(5, &registers), None);
+    }
+
+    /// Test circuit for the sketch gate
+    #[derive(Default)]
+    struct TestCircuit {
+        values: Vec<Field>,
+        /// Observations assigned instead of the values' own
+        observations: Option<Vec<(usize, u8)>>,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = (HllConfig, BitwiseRangeCheckConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..29).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let instance = meta.instance_column();
+            let range_check = BitwiseRangeCheckConfig::configure(meta, &advice[20..], &[]);
+            let config = HllConfig::configure(
+                meta,
+                &advice[..20],
+                range_check.u8_table,
+                instance,
+                HLL_PRECISION,
+            );
+            (config, range_check)
+        }
+
+        fn synthesize(
+            &self,
+            (config, range_check): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            range_check.load_lookup_table(&mut layouter)?;
+            config.load_rho_table(&mut layouter)?;
+            match &self.observations {
+                Some(observations) => {
+                    config.assign_observations(&mut layouter, &self.values, observations, 0)?
+                }
+                None => config.assign(&mut layouter, &self.values, 0)?,
+            };
+            Ok(())
+        }
+    }
+
+    /// Values with repeats and a NULL, and the registers of their sketch
+    fn sketched_values() -> (Vec<Field>, Vec<(usize, u8)>, Vec<Field>) {
+        let mut values: Vec<Field> = (0..40u64).map(|i| Field::from(i % 20)).collect();
+        values.push(SqlValue::null_field());
+        let observations = HyperLogLog::observations(&values[..40], HLL_PRECISION);
+        let registers = HllConfig::registers(&observations, HLL_PRECISION);
+        (values, observations, registers)
+    }
+
+    #[test]
+    fn test_hll_circuit() {
+        let (values, _, registers) = sketched_values();
+
+        let circuit = TestCircuit {
+            values,
+            observations: None,
+        };
+        let prover = MockProver::run(12, &circuit, vec![registers.clone()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // Overstating a register is rejected
+        let mut forged = registers;
+        let j = forged.iter().position(|r| *r != Field::zero()).unwrap();
+        forged[j] += Field::one();
+        let prover = MockProver::run(12, &circuit, vec![forged]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hll_circuit_rejects_forged_observation() {
+        let (values, mut observations, _) = sketched_values();
+
+        // Raising an observation's rho raises its register, but it is no
+        // longer the observation of a hashed value
+        let i = observations.iter().position(|&(_, rho)| rho > 0).unwrap();
+        observations[i].1 = 40;
+        observations.sort_unstable();
+        let registers = HllConfig::registers(&observations, HLL_PRECISION);
+
+        let circuit = TestCircuit {
+            values,
+            observations: Some(observations),
+        };
+        let prover = MockProver::run(12, &circuit, vec![registers]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hll_circuit_rejects_missing_sentinel() {
+        let values = vec![Field::from(7u64)];
+        let mut observations = HyperLogLog::observations(&values, HLL_PRECISION);
+        observations.retain(|&o| o != (3, 0));
+
+        let circuit = TestCircuit {
+            values,
+            observations: Some(observations),
+        };
+        assert!(MockProver::run(12, &circuit, vec![vec![Field::zero(); 256]]).is_err());
+    }
+
+    #[test]
+    fn test_hll_circuit_rejects_wide_values() {
+        let circuit = TestCircuit {
+            values: vec![Field::from_u128(1 << 64)],
+            observations: None,
+        };
+        assert!(MockProver::run(12, &circuit, vec![vec![Field::zero(); 256]]).is_err());
+    }
+}
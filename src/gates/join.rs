@@ -4,21 +4,34 @@
 //! 1. Deduplication property (each element appears exactly once)
 //! 2. Disjointness property (non-contributing sets are disjoint)
 //! 3. Join predicate validation (join attributes match)
-//! 4. Sortedness constraint (for union verification)
 //!
 //! # Method
 //!
 //! 1. Deduplication: ∀x ∈ T: x appears in T_de exactly once
 //! 2. Disjointness: T1_non-p ∩ T2_non-p = ∅
 //! 3. Join Predicate: r.attr1 - r.attr2 = 0 for each joined record
-//! 4. Sortedness: Si < Si+1 for all i (for union verification)
 //!
 //! # Constraints
 //!
 //! - Deduplication constraint: via lookup table check
-//! - Disjointness constraint: via sort and permutation check
+//! - Disjointness constraint: via the shared permutation argument, proving
+//!   S is a permutation of T1_de ‖ T2_de
 //! - Join predicate constraint: 1 per joined record
-//! - Sortedness constraint: 1 per adjacent pair
+//!
+//! # String Join Keys
+//!
+//! The gate compares field elements, so string columns (e.g., `c_mktsegment`)
+//! are joined on hashed keys built with [`JoinConfig::join_key`]. A string key
+//! is SHA-512 of its UTF-8 bytes reduced modulo the field order, so hashed
+//! equality implies string equality under the collision resistance of SHA-512
+//! (a collision in the ~254-bit key space has negligible probability).
+//!
+//! The keys are not Poseidon-hashed in-circuit: the dependency tree has no
+//! Poseidon gadget, so the SHA-512 hash is computed outside the circuit and
+//! the gate only sees the resulting field elements. Nothing in the circuit
+//! constrains a key to be the hash of its string, so the prover chooses the key
+//! cells freely. Hashed equality only implies string equality once the key
+//! columns are bound to the committed data through the column commitments.
 //!
 //! # Example
 //!
@@ -33,11 +46,16 @@
 //! let config = JoinConfig::configure(&mut meta, &advice);
 //! ```
 
+use super::permutation::PermutationArgument;
+use super::ValidationMode;
+use crate::field::FieldUtils;
+use crate::types::keys::{self, KeyError};
+use crate::types::Value as DbValue;
 use ff::Field as _;
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
     circuit::{Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, ErrorFront},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Selector},
     poly::Rotation,
 };
 
@@ -62,8 +80,11 @@ pub struct JoinConfig {
     /// Column for sorted union S (for disjointness verification)
     pub sorted_union_col: Column<Advice>,
 
-    /// Column for permutation accumulator Z (for union verification)
-    pub z_col: Column<Advice>,
+    /// Permutation argument between T1_de ‖ T2_de and the sorted union S
+    pub permutation: PermutationArgument,
+
+    /// Selector for the join result rows
+    pub s_result: Selector,
 }
 
 impl JoinConfig {
@@ -82,7 +103,7 @@ impl JoinConfig {
         // Validate input
         assert!(
             advice.len() >= 6,
-            "Need at least 6 advice columns (t1_join, t2_join, result_t1_join, result_t2_join, sorted_union, z)"
+            "Need at least 6 advice columns (t1_join, t2_join, result_t1_join, result_t2_join, union, sorted_union)"
         );
 
         // Assign columns
@@ -90,56 +111,29 @@ impl JoinConfig {
         let t2_join_col = advice[1];
         let result_t1_join_col = advice[2];
         let result_t2_join_col = advice[3];
-        let sorted_union_col = advice[4];
-        let z_col = advice[5];
 
         // Enable equality on all advice columns
         meta.enable_equality(t1_join_col);
         meta.enable_equality(t2_join_col);
         meta.enable_equality(result_t1_join_col);
         meta.enable_equality(result_t2_join_col);
-        meta.enable_equality(sorted_union_col);
-        meta.enable_equality(z_col);
+
+        // Union T1_de ‖ T2_de and sorted union S
+        let permutation = PermutationArgument::configure(meta, &advice[4..6]);
+        let sorted_union_col = permutation.output_col;
+
+        let s_result = meta.selector();
 
         // Constraint 1: Join predicate validation
         // For each joined record r: r.attr1 - r.attr2 = 0
         // This ensures that join attributes match
         meta.create_gate("join_predicate", |meta| {
+            let s = meta.query_selector(s_result);
             let attr1 = meta.query_advice(result_t1_join_col, Rotation::cur());
             let attr2 = meta.query_advice(result_t2_join_col, Rotation::cur());
 
             // attr1 - attr2 = 0 (join predicate satisfaction)
-            vec![attr1 - attr2]
-        });
-
-        // Constraint 2: Sortedness constraint (for union verification)
-        // For sorted union S: Si < Si+1 for all i
-        // This ensures disjointness: if Si = Si+1, sets are not disjoint
-        meta.create_gate("sortedness", |meta| {
-            let s_cur = meta.query_advice(sorted_union_col, Rotation::cur());
-            let s_next = meta.query_advice(sorted_union_col, Rotation::next());
-
-            // s_next - s_cur > 0 (strict inequality for disjointness)
-            // Note: In practice, we use range check or ensure s_next > s_cur
-            // For now, we use a simple constraint (can be refined with selector)
-            vec![s_next - s_cur]
-        });
-
-        // Constraint 3: Permutation check (for union verification)
-        // Verify that sorted union S is a permutation of T1_de ∪ T2_de
-        // This is used to verify disjointness and completeness
-        // Note: This is a simplified version - full implementation would use
-        // the same permutation check as SortGate
-        meta.create_gate("union_permutation", |meta| {
-            let z_cur = meta.query_advice(z_col, Rotation::cur());
-            let z_next = meta.query_advice(z_col, Rotation::next());
-            let s_cur = meta.query_advice(sorted_union_col, Rotation::cur());
-
-            // Simplified permutation check - full implementation would compare
-            // with original union set T1_de ∪ T2_de
-            // For now, we just ensure z accumulator is properly maintained
-            // In production, this would use the full permutation check formula
-            vec![z_next - z_cur * s_cur]
+            vec![s * (attr1 - attr2)]
         });
 
         Self {
@@ -148,23 +142,37 @@ impl JoinConfig {
             result_t1_join_col,
             result_t2_join_col,
             sorted_union_col,
-            z_col,
+            permutation,
+            s_result,
         }
     }
 
+    /// Number of constraints for joining `input_len` records
+    ///
+    /// Each joined record has 1 predicate constraint, and the union has the
+    /// permutation argument's constraints (taking the union as long as the
+    /// result).
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of joined records
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        input_len + self.permutation.num_constraints(input_len)
+    }
+
     /// Assign values for join gate
     ///
     /// This method:
     /// 1. Assigns table T1 and T2 join attribute values
     /// 2. Assigns join result (where join attributes match)
     /// 3. Computes and assigns sorted union S
-    /// 4. Computes and assigns permutation accumulator Z
+    /// 4. Proves S is a permutation of T1_de ‖ T2_de
     ///
     /// # Arguments
     /// * `layouter` - Layouter for assigning values
     /// * `t1_join_values` - Join attribute values from table T1
     /// * `t2_join_values` - Join attribute values from table T2
     /// * `join_results` - Join results (pairs of matching join attributes)
+    /// * `mode` - Validation mode of the permutation argument
     ///
     /// # Returns
     /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
@@ -174,45 +182,24 @@ impl JoinConfig {
         t1_join_values: &[Field],
         t2_join_values: &[Field],
         join_results: &[(Field, Field)],
+        mode: ValidationMode,
     ) -> Result<(), ErrorFront> {
         // Deduplicate T1 and T2 join values
         let t1_de = Self::deduplicate(t1_join_values);
         let t2_de = Self::deduplicate(t2_join_values);
 
-        // Create sorted union S = sort(T1_de ∪ T2_de)
-        let mut union: Vec<Field> = t1_de.iter().chain(t2_de.iter()).cloned().collect();
-        union.sort_by(|a, b| {
-            // Sort by converting to u64 for comparison
-            let a_u64 = Self::field_to_u64(*a);
-            let b_u64 = Self::field_to_u64(*b);
-            a_u64.cmp(&b_u64)
-        });
-
-        // Remove duplicates from sorted union (for disjointness verification)
-        let mut sorted_union = Vec::new();
-        if !union.is_empty() {
-            sorted_union.push(union[0]);
-            for i in 1..union.len() {
-                if union[i] != union[i - 1] {
-                    sorted_union.push(union[i]);
-                }
-            }
-        }
-
-        // Compute permutation accumulator Z (simplified)
-        // In production, this would use the full permutation check formula
-        let mut z_values = Vec::with_capacity(sorted_union.len() + 1);
-        z_values.push(Field::one()); // Z0 = 1
-
-        for &value in &sorted_union {
-            let z_next = z_values.last().unwrap() * value;
-            z_values.push(z_next);
-        }
+        // Create sorted union S = sort(T1_de ‖ T2_de)
+        let union: Vec<Field> = t1_de.iter().chain(&t2_de).cloned().collect();
+        let sorted_union = Self::sorted_union(&t1_de, &t2_de);
 
         // Assign all values in a region
         layouter.assign_region(
             || "join gate",
             |mut region| {
+                // Assign the union and sorted union S
+                self.permutation
+                    .assign(&mut region, &union, &sorted_union, 1, mode)?;
+
                 // Assign T1 join values
                 for (i, &value) in t1_join_values.iter().enumerate() {
                     region.assign_advice(
@@ -235,6 +222,7 @@ impl JoinConfig {
 
                 // Assign join results
                 for (i, &(attr1, attr2)) in join_results.iter().enumerate() {
+                    self.s_result.enable(&mut region, i)?;
                     region.assign_advice(
                         || format!("result_t1_join[{}]", i),
                         self.result_t1_join_col,
@@ -249,26 +237,6 @@ impl JoinConfig {
                     )?;
                 }
 
-                // Assign sorted union
-                for (i, &value) in sorted_union.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("sorted_union[{}]", i),
-                        self.sorted_union_col,
-                        i,
-                        || Value::known(value),
-                    )?;
-                }
-
-                // Assign permutation accumulator Z
-                for (i, &value) in z_values.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("z[{}]", i),
-                        self.z_col,
-                        i,
-                        || Value::known(value),
-                    )?;
-                }
-
                 Ok(())
             },
         )
@@ -288,9 +256,8 @@ impl JoinConfig {
         let mut seen = std::collections::HashSet::new();
 
         for &value in values {
-            let value_u64 = Self::field_to_u64(value);
-            if !seen.contains(&value_u64) {
-                seen.insert(value_u64);
+            // Compare full field bytes: hashed keys can share their low 8 bytes
+            if seen.insert(value.to_bytes()) {
                 deduped.push(value);
             }
         }
@@ -298,21 +265,77 @@ impl JoinConfig {
         deduped
     }
 
-    /// Convert field value to u64 for comparison
+    /// Create the sorted union of two deduplicated value sets
+    ///
+    /// Values are ordered by their full 256-bit representation, so values
+    /// sharing low bytes (e.g., hashed string keys) stay distinct. Values in
+    /// both sets appear twice, keeping S a permutation of T1_de ‖ T2_de; the
+    /// repeats are exactly the keys that join.
+    ///
+    /// # Arguments
+    /// * `t1_de` - Deduplicated values from table T1
+    /// * `t2_de` - Deduplicated values from table T2
+    ///
+    /// # Returns
+    /// Sorted multiset union
+    fn sorted_union(t1_de: &[Field], t2_de: &[Field]) -> Vec<Field> {
+        let mut union: Vec<Field> = t1_de.iter().chain(t2_de.iter()).cloned().collect();
+        union.sort_by(FieldUtils::cmp);
+        union
+    }
+
+    /// Build a join key from a database value
+    ///
+    /// Values use their regular field encoding, under which strings are
+    /// hashed with SHA-512 and reduced modulo the field order. Every string
+    /// therefore maps to a valid element and equal keys imply equal strings
+    /// (see the module docs for the collision assumption). The hash is
+    /// computed here, not in the circuit. `NULL` never joins and yields `None`.
     ///
     /// # Arguments
-    /// * `value` - Field value
+    /// * `value` - Join attribute value
     ///
     /// # Returns
-    /// u64 representation
-    fn field_to_u64(value: Field) -> u64 {
-        // Convert field to bytes and extract first 8 bytes as u64
-        let bytes = value.to_bytes();
-        let mut u64_bytes = [0u8; 8];
-        for i in 0..8.min(bytes.len()) {
-            u64_bytes[i] = bytes[i];
+    /// `Some(Field)` join key, or `None` for `NULL`
+    pub fn join_key(value: &DbValue) -> Option<Field> {
+        match value {
+            DbValue::Null => None,
+            other => Some(other.to_field()),
         }
-        u64::from_le_bytes(u64_bytes)
+    }
+
+    /// Build a join key from several join attributes
+    ///
+    /// Joins on more than one column compare the attributes packed into a
+    /// composite key (see [`keys::composite`]), with the given bit width per
+    /// attribute. A `NULL` in any attribute never joins and yields `None`.
+    ///
+    /// # Arguments
+    /// * `values` - Join attribute values, in join column order
+    /// * `widths` - Bit width of each attribute
+    ///
+    /// # Returns
+    /// `Ok(Some(Field))` join key, `Ok(None)` if an attribute is `NULL`,
+    /// `Err(KeyError)` if the attributes don't pack into the widths
+    pub fn composite_join_key(
+        values: &[DbValue],
+        widths: &[usize],
+    ) -> Result<Option<Field>, KeyError> {
+        if values.contains(&DbValue::Null) {
+            return Ok(None);
+        }
+        keys::composite(values, widths).map(Some)
+    }
+
+    /// Build join keys for a column of values, skipping `NULL`s
+    ///
+    /// # Arguments
+    /// * `values` - Join attribute values
+    ///
+    /// # Returns
+    /// Join keys for all non-`NULL` values, in input order
+    pub fn join_keys(values: &[DbValue]) -> Vec<Field> {
+        values.iter().filter_map(Self::join_key).collect()
     }
 
     /// Verify join predicate for a pair of join attributes
@@ -330,6 +353,7 @@ impl JoinConfig {
     /// Get join results from two tables
     ///
     /// Performs an inner join on two tables based on join attributes.
+    /// `NULL` keys (see [`DbValue::null_field`]) never match.
     ///
     /// # Arguments
     /// * `t1_join_values` - Join attribute values from table T1
@@ -344,6 +368,10 @@ impl JoinConfig {
         let mut results = Vec::new();
 
         for &attr1 in t1_join_values {
+            // NULL never joins, not even with another NULL
+            if DbValue::is_null_field(&attr1) {
+                continue;
+            }
             for &attr2 in t2_join_values {
                 if Self::verify_join_predicate(attr1, attr2) {
                     results.push((attr1, attr2));
@@ -393,6 +421,73 @@ mod tests {
         assert_eq!(results[1], (Field::from(3u64), Field::from(3u64)));
     }
 
+    #[test]
+    fn test_string_join_keys() {
+        // customer.c_mktsegment joined with a segment lookup table
+        let customer_segments = vec![
+            DbValue::String("BUILDING".to_string()),
+            DbValue::String("MACHINERY".to_string()),
+            DbValue::String("BUILDINGS".to_string()),
+            DbValue::Null,
+            DbValue::String("AUTOMOBILE".to_string()),
+        ];
+        let segment_names = vec![
+            DbValue::String("BUILDING".to_string()),
+            DbValue::String("AUTOMOBILE".to_string()),
+            DbValue::String("building".to_string()),
+            DbValue::Null,
+        ];
+
+        let t1_join = JoinConfig::join_keys(&customer_segments);
+        let t2_join = JoinConfig::join_keys(&segment_names);
+        assert_eq!(t1_join.len(), 4, "NULL keys should be skipped");
+        assert_eq!(t2_join.len(), 3, "NULL keys should be skipped");
+
+        let results = JoinConfig::get_join_results(&t1_join, &t2_join);
+        let building = JoinConfig::join_key(&DbValue::String("BUILDING".to_string())).unwrap();
+        let automobile = JoinConfig::join_key(&DbValue::String("AUTOMOBILE".to_string())).unwrap();
+        assert_eq!(
+            results,
+            vec![(building, building), (automobile, automobile)]
+        );
+
+        // All distinct strings map to distinct, non-zero keys
+        let mut distinct: Vec<Field> = t1_join.iter().chain(t2_join.iter()).cloned().collect();
+        distinct = JoinConfig::deduplicate(&distinct);
+        assert_eq!(distinct.len(), 5);
+        assert!(distinct.iter().all(|k| !bool::from(k.is_zero())));
+    }
+
+    #[test]
+    fn test_composite_join_keys() {
+        // lineitem (l_orderkey, l_linenumber) joined on two columns
+        let key = |order: i64, line: i32| {
+            JoinConfig::composite_join_key(
+                &[DbValue::BigInt(order), DbValue::Integer(line)],
+                &[64, 32],
+            )
+        };
+        assert_eq!(key(7, 1), key(7, 1));
+        assert_ne!(key(7, 1), key(7, 2));
+        assert_ne!(key(7, 1), key(1, 7));
+
+        assert_eq!(
+            JoinConfig::composite_join_key(&[DbValue::BigInt(7), DbValue::Null], &[64, 32]),
+            Ok(None)
+        );
+        assert!(key(7, -1).is_err());
+    }
+
+    #[test]
+    fn test_get_join_results_null_never_matches() {
+        let null = DbValue::Null.to_field();
+        let t1_join = vec![null, Field::from(0u64)];
+        let t2_join = vec![null, Field::from(0u64)];
+
+        let results = JoinConfig::get_join_results(&t1_join, &t2_join);
+        assert_eq!(results, vec![(Field::from(0u64), Field::from(0u64))]);
+    }
+
     #[test]
     fn test_deduplicate() {
         // Test deduplication
@@ -411,6 +506,18 @@ mod tests {
         assert!(deduped.contains(&Field::from(3u64)));
     }
 
+    #[test]
+    fn test_sorted_union_full_width() {
+        // Values sharing their low 64 bits must not collide
+        let shift = Field::from(2u64).pow_vartime([64u64]);
+        let low = Field::from(5u64) + shift * Field::from(2u64);
+        let high = Field::from(5u64) + shift * Field::from(3u64);
+        let small = Field::from(9u64);
+
+        let sorted = JoinConfig::sorted_union(&[high, small], &[low, high]);
+        assert_eq!(sorted, vec![small, low, high, high]);
+    }
+
     #[test]
     fn test_deduplicate_empty() {
         // Test deduplication with empty input
@@ -430,6 +537,7 @@ mod tests {
     impl Circuit<Field> for TestCircuit {
         type Config = JoinConfig;
         type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
 
         fn without_witnesses(&self) -> Self {
             Self::default()
@@ -451,6 +559,7 @@ mod tests {
                     &self.t1_join_values,
                     &self.t2_join_values,
                     &self.join_results,
+                    ValidationMode::Fast,
                 )?;
             }
             Ok(())
@@ -502,4 +611,17 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()), "Empty circuit should verify");
     }
+
+    #[test]
+    fn test_join_circuit_rejects_mismatched_result() {
+        // The claimed result pairs 2 with 3
+        let circuit = TestCircuit {
+            t1_join_values: vec![Field::from(2u64)],
+            t2_join_values: vec![Field::from(3u64)],
+            join_results: vec![(Field::from(2u64), Field::from(3u64))],
+        };
+
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
 }
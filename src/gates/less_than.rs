@@ -0,0 +1,360 @@
+//! Less-than gate for comparing field elements
+//!
+//! This module provides a less-than gate that verifies `a < b` (or `a ≤ b`)
+//! for values bounded by a known bit-width `n`, without u8 decomposition or
+//! lookups.
+//!
+//! # Method
+//!
+//! 1. Difference: d = b - a - 1 (d = b - a for `a ≤ b`)
+//! 2. Range check d ∈ [0, 2^n) with a running sum over n rows:
+//!    - z_0 = d
+//!    - z_i = 2 · z_{i+1} + bit_i, with bit_i ∈ {0, 1}
+//!    - z_n = 0
+//!
+//! If `a >= b`, d wraps around the field to a value ≥ 2^n, so z_n ≠ 0.
+//! Both `a` and `b` must be less than 2^n for the comparison to be sound.
+//!
+//! # Constraints
+//!
+//! - Difference constraint: 1 per comparison
+//! - Boolean constraint: n per comparison (one per bit)
+//! - Final constraint: 1 per comparison (z_n = 0)
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::less_than::LessThanConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 3];
+//!
+//! let config = LessThanConfig::configure(&mut meta, &advice, 64);
+//! ```
+
+use ff::{Field as _, PrimeField};
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for less-than gate
+///
+/// This gate verifies that `a < b` or `a ≤ b` for values below 2^n.
+#[derive(Debug, Clone)]
+pub struct LessThanConfig {
+    /// Column for the left operand a
+    pub a: Column<Advice>,
+
+    /// Column for the right operand b
+    pub b: Column<Advice>,
+
+    /// Column for the running sum z
+    pub z: Column<Advice>,
+
+    /// Selector for the difference row (z_0 = b - a - 1)
+    pub s_diff: Selector,
+
+    /// Selector for the non-strict difference row (z_0 = b - a)
+    pub s_diff_le: Selector,
+
+    /// Selector for the bit rows (z_i - 2 · z_{i+1} is boolean)
+    pub s_bit: Selector,
+
+    /// Selector for the final row (z_n = 0)
+    pub s_last: Selector,
+
+    /// Bit-width bound of the compared values
+    pub n: usize,
+}
+
+impl LessThanConfig {
+    /// Configure the less-than gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 3: a, b, z)
+    /// * `n` - Bit-width bound of the compared values
+    ///
+    /// # Returns
+    /// `LessThanConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided or `n` is not below the field size
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        advice: &[Column<Advice>],
+        n: usize,
+    ) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 3,
+            "Need at least 3 advice columns (a, b, z)"
+        );
+        assert!(
+            n > 0 && n < Field::NUM_BITS as usize - 1,
+            "Bit-width must be in [1, {})",
+            Field::NUM_BITS - 1
+        );
+
+        // Assign columns
+        let a = advice[0];
+        let b = advice[1];
+        let z = advice[2];
+
+        // Enable equality for copy constraints from other gates
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+
+        let s_diff = meta.selector();
+        let s_diff_le = meta.selector();
+        let s_bit = meta.selector();
+        let s_last = meta.selector();
+
+        // Constraint 1: running sum starts at the difference
+        // z_0 = b - a - 1
+        meta.create_gate("less_than_diff", |meta| {
+            let q = meta.query_selector(s_diff);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let z = meta.query_advice(z, Rotation::cur());
+
+            vec![q * (z - (b - a - Expression::Constant(Field::one())))]
+        });
+
+        // Constraint 1b: non-strict comparisons start at the plain difference
+        // z_0 = b - a
+        meta.create_gate("less_equal_diff", |meta| {
+            let q = meta.query_selector(s_diff_le);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let z = meta.query_advice(z, Rotation::cur());
+
+            vec![q * (z - (b - a))]
+        });
+
+        // Constraint 2: each step removes one boolean bit
+        // bit = z_i - 2 · z_{i+1}, bit · (1 - bit) = 0
+        meta.create_gate("less_than_bit", |meta| {
+            let q = meta.query_selector(s_bit);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let bit = z_cur - z_next * Field::from(2u64);
+
+            vec![q * bit.clone() * (Expression::Constant(Field::one()) - bit)]
+        });
+
+        // Constraint 3: nothing remains after n bits
+        // z_n = 0
+        meta.create_gate("less_than_last", |meta| {
+            let q = meta.query_selector(s_last);
+            let z = meta.query_advice(z, Rotation::cur());
+
+            vec![q * z]
+        });
+
+        Self {
+            a,
+            b,
+            z,
+            s_diff,
+            s_diff_le,
+            s_bit,
+            s_last,
+            n,
+        }
+    }
+
+    /// Number of constraints for `input_len` comparisons
+    ///
+    /// Each comparison has 1 difference constraint, n bit constraints and
+    /// 1 final constraint.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of comparisons
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        input_len * (self.n + 2)
+    }
+
+    /// Assign a comparison `a < b`
+    ///
+    /// The witness is always assigned; if `a >= b` (or either value is not
+    /// below 2^n) the constraints are unsatisfied.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `a` - Left operand
+    /// * `b` - Right operand
+    ///
+    /// # Returns
+    /// The assigned cells for `a` and `b`, for copy constraints
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        a: Field,
+        b: Field,
+    ) -> Result<(AssignedCell<Field, Field>, AssignedCell<Field, Field>), ErrorFront> {
+        self.assign_comparison(layouter, a, b, true)
+    }
+
+    /// Assign a comparison `a ≤ b`
+    ///
+    /// The witness is always assigned; if `a > b` (or either value is not
+    /// below 2^n) the constraints are unsatisfied.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `a` - Left operand
+    /// * `b` - Right operand
+    ///
+    /// # Returns
+    /// The assigned cells for `a` and `b`, for copy constraints
+    pub fn assign_less_equal(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        a: Field,
+        b: Field,
+    ) -> Result<(AssignedCell<Field, Field>, AssignedCell<Field, Field>), ErrorFront> {
+        self.assign_comparison(layouter, a, b, false)
+    }
+
+    /// Assign a strict or non-strict comparison of `a` and `b`
+    fn assign_comparison(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        a: Field,
+        b: Field,
+        strict: bool,
+    ) -> Result<(AssignedCell<Field, Field>, AssignedCell<Field, Field>), ErrorFront> {
+        let diff = if strict { b - a - Field::one() } else { b - a };
+        let repr = diff.to_repr();
+        let two_inv = Field::from(2u64).invert().unwrap();
+
+        layouter.assign_region(
+            || if strict { "less than" } else { "less equal" },
+            |mut region| {
+                if strict {
+                    self.s_diff.enable(&mut region, 0)?;
+                } else {
+                    self.s_diff_le.enable(&mut region, 0)?;
+                }
+
+                let a_cell = region.assign_advice(|| "a", self.a, 0, || Value::known(a))?;
+                let b_cell = region.assign_advice(|| "b", self.b, 0, || Value::known(b))?;
+
+                // z_{i+1} = (z_i - bit_i) / 2, where bit_i is bit i of the difference
+                let mut z = diff;
+                for i in 0..self.n {
+                    self.s_bit.enable(&mut region, i)?;
+                    region.assign_advice(|| format!("z[{}]", i), self.z, i, || Value::known(z))?;
+
+                    let bit = (repr.as_ref()[i / 8] >> (i % 8)) & 1;
+                    z = (z - Field::from(bit as u64)) * two_inv;
+                }
+
+                self.s_last.enable(&mut region, self.n)?;
+                region.assign_advice(
+                    || format!("z[{}]", self.n),
+                    self.z,
+                    self.n,
+                    || Value::known(z),
+                )?;
+
+                Ok((a_cell, b_cell))
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+
+    /// Test circuit for less-than gate
+    #[derive(Default)]
+    struct TestCircuit {
+        a: Field,
+        b: Field,
+        strict: bool,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = LessThanConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..3).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            LessThanConfig::configure(meta, &advice, 8)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            if self.strict {
+                config.assign(&mut layouter, self.a, self.b)?;
+            } else {
+                config.assign_less_equal(&mut layouter, self.a, self.b)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn run(a: u64, b: u64) -> bool {
+        run_comparison(a, b, true)
+    }
+
+    fn run_comparison(a: u64, b: u64, strict: bool) -> bool {
+        let circuit = TestCircuit {
+            a: Field::from(a),
+            b: Field::from(b),
+            strict,
+        };
+        let k = 5;
+        MockProver::run(k, &circuit, vec![])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn test_less_than_accepts() {
+        assert!(run(3, 5), "3 < 5 should verify");
+        assert!(run(0, 1), "0 < 1 should verify");
+        assert!(run(0, 255), "0 < 255 should verify");
+    }
+
+    #[test]
+    fn test_less_than_rejects() {
+        assert!(!run(5, 3), "5 < 3 should be rejected");
+        assert!(!run(5, 5), "5 < 5 should be rejected");
+    }
+
+    #[test]
+    fn test_less_than_rejects_out_of_range() {
+        // b - a - 1 = 256 needs more than 8 bits
+        assert!(!run(0, 257));
+    }
+
+    #[test]
+    fn test_less_equal() {
+        assert!(run_comparison(3, 5, false));
+        assert!(run_comparison(5, 5, false), "5 <= 5 should verify");
+        assert!(!run_comparison(6, 5, false), "6 <= 5 should be rejected");
+    }
+}
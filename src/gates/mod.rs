@@ -1,21 +1,74 @@
 //! Custom gates for SQL operations
 //!
 //! This module provides custom gates for various SQL operations:
-//! - Range check: Verify values are within a specified range
+//! - Range check: Verify values are within a specified range (including strict
+//!   positivity)
 //! - Sort: Prove sorting correctness
 //! - Group-by: Prove grouping correctness
 //! - Join: Prove join correctness
 //! - Aggregation: Prove aggregation function correctness
+//! - Arithmetic: Prove row-level computed values (add, sub, mul)
+//! - Count: Prove COUNT(*) over selected rows as a public output
+//! - Distinct count: Prove COUNT(DISTINCT) per group of the group-by gate
+//! - HyperLogLog: Prove sketch registers for APPROX_COUNT_DISTINCT as public outputs
+//! - Less-than: Prove a < b for bounded field elements
+//! - Permutation: Grand-product argument shared by sort and join
+//! - Selection: Prove a filter's output is exactly the rows satisfying it
+//! - Weighted sum: Prove sum-of-products aggregates (Σ a_i · b_i)
+//!
+//! Gates deriving witnesses from their inputs take a [`ValidationMode`];
+//! `Strict` re-checks the derived witness before assigning it.
 
 pub mod aggregation;
+pub mod arithmetic;
+pub mod count;
+pub mod distinct_count;
 pub mod group_by;
+pub mod hll;
 pub mod join;
+pub mod less_than;
+pub mod permutation;
 pub mod range_check;
+pub mod selection;
 pub mod sort;
+pub mod weighted_sum;
 
 // Re-export main types for convenience
-pub use aggregation::AggregationConfig;
-pub use group_by::GroupByConfig;
+pub use aggregation::{AggregationCells, AggregationConfig, AvgRemainderConfig, MinMaxConfig};
+pub use arithmetic::ArithmeticConfig;
+pub use count::CountConfig;
+pub use distinct_count::{DistinctCountCells, DistinctCountConfig};
+pub use group_by::{GroupByCells, GroupByConfig};
+pub use hll::{HllConfig, HyperLogLog};
 pub use join::JoinConfig;
-pub use range_check::BitwiseRangeCheckConfig;
+pub use less_than::LessThanConfig;
+pub use permutation::PermutationArgument;
+pub use range_check::{BitwiseRangeCheckConfig, NBitRangeCheckConfig, StrictlyPositiveConfig};
+pub use selection::SelectionConfig;
 pub use sort::SortConfig;
+pub use weighted_sum::WeightedSumConfig;
+
+/// How much a gate checks its witness before assigning it
+///
+/// Gates deriving witnesses (permutations, group boundaries,
+/// aggregation accumulators, distinct counts) assume their inputs are
+/// consistent. If they aren't, the circuit is still assigned and only fails
+/// at verification, or worse, proves something other than intended. `Strict`
+/// re-derives the gate's invariants and fails synthesis with
+/// `ErrorFront::Other` instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Re-check internal invariants before assigning (for debugging)
+    Strict,
+
+    /// Assign without extra checks
+    #[default]
+    Fast,
+}
+
+impl ValidationMode {
+    /// Check whether invariants are re-checked
+    pub fn is_strict(self) -> bool {
+        self == ValidationMode::Strict
+    }
+}
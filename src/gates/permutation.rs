@@ -0,0 +1,328 @@
+//! Permutation argument shared by the sort, join and group-by gates
+//!
+//! This module provides a shuffle argument proving that an output column R
+//! is a permutation (as a multiset) of an input column D.
+//!
+//! # Method
+//!
+//! halo2's shuffle argument proves that the multiset of tuples
+//! `(q · tag, q · D)` over all rows equals the multiset of `(q · tag, q · R)`,
+//! where `q` enables the permuted rows and `tag` is a fixed column. The
+//! argument draws its challenges from the transcript after D and R are
+//! committed, so the prover can't choose them.
+//!
+//! Each use of the argument assigns its rows under its own tag, so several
+//! permutations can share the columns without a row of one standing in for
+//! a row of another.
+//!
+//! # Constraints
+//!
+//! - Shuffle constraint: 1 per element
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::permutation::PermutationArgument;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 2];
+//!
+//! let permutation = PermutationArgument::configure(&mut meta, &advice);
+//! ```
+
+use super::ValidationMode;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// Permutation argument between an input and an output column
+///
+/// Gates embed it in their own region with [`PermutationArgument::assign`],
+/// so the permuted cells can be copied into their other constraints.
+#[derive(Debug, Clone)]
+pub struct PermutationArgument {
+    /// Column for input values D
+    pub input_col: Column<Advice>,
+
+    /// Column for permuted output values R
+    pub output_col: Column<Advice>,
+
+    /// Fixed column separating the uses of the argument
+    pub tag: Column<Fixed>,
+
+    /// Selector for the permutation rows (one per element)
+    pub s_perm: Selector,
+}
+
+impl PermutationArgument {
+    /// Configure the permutation argument
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 2: input, output)
+    ///
+    /// # Returns
+    /// `PermutationArgument` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
+        assert!(
+            advice.len() >= 2,
+            "Need at least 2 advice columns (input, output)"
+        );
+
+        let input_col = advice[0];
+        let output_col = advice[1];
+        let tag = meta.fixed_column();
+
+        meta.enable_equality(input_col);
+        meta.enable_equality(output_col);
+
+        // Shuffles can't use simple selectors
+        let s_perm = meta.complex_selector();
+
+        // Constraint: {(q · tag, q · D)} = {(q · tag, q · R)} as multisets
+        meta.shuffle("permutation", |meta| {
+            let q = meta.query_selector(s_perm);
+            let tag = meta.query_fixed(tag, Rotation::cur());
+            let d = meta.query_advice(input_col, Rotation::cur());
+            let r = meta.query_advice(output_col, Rotation::cur());
+
+            vec![
+                (q.clone() * tag.clone(), q.clone() * tag),
+                (q.clone() * d, q * r),
+            ]
+        });
+
+        Self {
+            input_col,
+            output_col,
+            tag,
+            s_perm,
+        }
+    }
+
+    /// Number of constraints for permuting `len` values
+    ///
+    /// Each element has 1 shuffle constraint.
+    ///
+    /// # Arguments
+    /// * `len` - Number of permuted values
+    pub fn num_constraints(&self, len: usize) -> usize {
+        len
+    }
+
+    /// Check whether R is a permutation of D
+    ///
+    /// # Arguments
+    /// * `input_values` - The input values D
+    /// * `output_values` - The output values R
+    ///
+    /// # Returns
+    /// `true` if both hold the same values with the same multiplicities
+    pub fn is_permutation(input_values: &[Field], output_values: &[Field]) -> bool {
+        let mut input = input_values.to_vec();
+        let mut output = output_values.to_vec();
+        input.sort();
+        output.sort();
+        input == output
+    }
+
+    /// Assign the permutation argument at the start of a region
+    ///
+    /// Uses rows `0..len(D)` of the region. In `Fast` mode the witness is
+    /// always assigned, and if R is not a permutation of D the shuffle is
+    /// unsatisfied; `Strict` mode rejects it before assigning.
+    ///
+    /// # Arguments
+    /// * `region` - Region of the consuming gate
+    /// * `input_values` - The input values D
+    /// * `output_values` - The output values R
+    /// * `tag` - Nonzero tag, distinct for each use of these columns
+    /// * `mode` - Whether to check that R is a permutation of D
+    ///
+    /// # Returns
+    /// `Ok((input_cells, output_cells))` if assignment succeeds,
+    /// `Err(Error::Synthesis)` if the lengths differ or the tag is zero,
+    /// `Err(Error::Other)` in `Strict` mode if R is not a permutation of D
+    pub fn assign(
+        &self,
+        region: &mut Region<'_, Field>,
+        input_values: &[Field],
+        output_values: &[Field],
+        tag: u64,
+        mode: ValidationMode,
+    ) -> Result<
+        (
+            Vec<AssignedCell<Field, Field>>,
+            Vec<AssignedCell<Field, Field>>,
+        ),
+        ErrorFront,
+    > {
+        if input_values.len() != output_values.len() || tag == 0 {
+            return Err(ErrorFront::Synthesis);
+        }
+        if mode.is_strict() && !Self::is_permutation(input_values, output_values) {
+            return Err(ErrorFront::Other(
+                "permutation output is not a permutation of its input".to_string(),
+            ));
+        }
+
+        let n = input_values.len();
+        let mut input_cells = Vec::with_capacity(n);
+        let mut output_cells = Vec::with_capacity(n);
+        for i in 0..n {
+            self.s_perm.enable(region, i)?;
+            region.assign_fixed(
+                || format!("tag[{}]", i),
+                self.tag,
+                i,
+                || Value::known(Field::from(tag)),
+            )?;
+            input_cells.push(region.assign_advice(
+                || format!("input[{}]", i),
+                self.input_col,
+                i,
+                || Value::known(input_values[i]),
+            )?);
+            output_cells.push(region.assign_advice(
+                || format!("output[{}]", i),
+                self.output_col,
+                i,
+                || Value::known(output_values[i]),
+            )?);
+        }
+
+        Ok((input_cells, output_cells))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fields, run_mock};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+
+    /// Test circuit for the permutation argument, with one region per use
+    #[derive(Clone, Default)]
+    struct TestCircuit {
+        uses: Vec<(Vec<Field>, Vec<Field>, u64)>,
+        mode: ValidationMode,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = PermutationArgument;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..2).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            PermutationArgument::configure(meta, &advice)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            for (input, output, tag) in &self.uses {
+                layouter.assign_region(
+                    || "permutation",
+                    |mut region| {
+                        config.assign(&mut region, input, output, *tag, self.mode)?;
+                        Ok(())
+                    },
+                )?;
+            }
+            Ok(())
+        }
+    }
+
+    fn single(input: &[u64], output: &[u64], mode: ValidationMode) -> TestCircuit {
+        TestCircuit {
+            uses: vec![(fields(input), fields(output), 1)],
+            mode,
+        }
+    }
+
+    #[test]
+    fn test_is_permutation() {
+        let input = fields(&[5, 2, 8, 2]);
+        assert!(PermutationArgument::is_permutation(
+            &input,
+            &fields(&[2, 8, 2, 5])
+        ));
+        assert!(!PermutationArgument::is_permutation(
+            &input,
+            &fields(&[2, 8, 5, 5])
+        ));
+        assert!(!PermutationArgument::is_permutation(
+            &input,
+            &fields(&[2, 8])
+        ));
+    }
+
+    #[test]
+    fn test_permutation_circuit() {
+        let circuit = single(&[5, 2, 8, 2, 1], &[2, 1, 5, 8, 2], ValidationMode::Fast);
+        assert_eq!(run_mock(&circuit, 8), Ok(()));
+
+        let empty = TestCircuit::default();
+        assert_eq!(run_mock(&empty, 8), Ok(()));
+    }
+
+    #[test]
+    fn test_permutation_circuit_rejects_non_permutation() {
+        // Same length, but 5 is duplicated in place of 8
+        let circuit = single(&[5, 2, 8], &[2, 5, 5], ValidationMode::Fast);
+        assert!(run_mock(&circuit, 8).is_err());
+    }
+
+    #[test]
+    fn test_permutation_tags_separate_uses() {
+        // Each use is a permutation of its own input
+        let circuit = TestCircuit {
+            uses: vec![
+                (fields(&[1, 2]), fields(&[2, 1]), 1),
+                (fields(&[3, 4]), fields(&[4, 3]), 2),
+            ],
+            mode: ValidationMode::Fast,
+        };
+        assert_eq!(run_mock(&circuit, 8), Ok(()));
+
+        // Swapping values between the uses keeps the union a permutation,
+        // but not each use
+        let circuit = TestCircuit {
+            uses: vec![
+                (fields(&[1, 2]), fields(&[3, 1]), 1),
+                (fields(&[3, 4]), fields(&[4, 2]), 2),
+            ],
+            mode: ValidationMode::Fast,
+        };
+        assert!(run_mock(&circuit, 8).is_err());
+    }
+
+    #[test]
+    fn test_permutation_strict_mode() {
+        // Strict mode fails synthesis instead of leaving it to the verifier
+        let circuit = single(&[5, 2, 8], &[2, 5, 5], ValidationMode::Strict);
+        assert!(MockProver::run(8, &circuit, vec![]).is_err());
+
+        let circuit = single(&[5, 2, 8], &[8, 5, 2], ValidationMode::Strict);
+        assert_eq!(run_mock(&circuit, 8), Ok(()));
+    }
+}
@@ -14,6 +14,21 @@
 //! - Decomposition constraint: 1 per integer
 //! - Lookup constraints: 8 per integer (one per u8 cell)
 //!
+//! # Narrow widths
+//!
+//! `NBitRangeCheckConfig` proves `value < 2^n` for a width `n` fixed at
+//! configuration time, using only `ceil(n / 8)` u8 cells. When `n` is not a
+//! multiple of 8, the top cell is also looked up shifted left by
+//! `8 - n mod 8` bits, which only stays in `[0, 255]` if its high bits are
+//! zero. Values known to be narrow (e.g., booleans or 32-bit integers) then
+//! need fewer lookups than the 64-bit check.
+//!
+//! # Strict positivity
+//!
+//! `StrictlyPositiveConfig` proves `1 <= value <= 2^n` by range checking
+//! `value - 1` and `2^n - value`, i.e. `value - 1` lies in `[0, 2^n)`. The
+//! bound `2^n` is a fixed cell, so the prover cannot choose it.
+//!
 //! # Example
 //!
 //! ```rust
@@ -29,10 +44,13 @@
 //! ```
 
 use crate::field::FieldUtils;
+use ff::{Field as _, PrimeField};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
-    circuit::{Layouter, Value},
-    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Fixed, TableColumn},
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, ErrorFront, Expression, Fixed, Selector, TableColumn,
+    },
     poly::Rotation,
 };
 
@@ -111,17 +129,14 @@ impl BitwiseRangeCheckConfig {
         });
 
         // Constraint 2: Lookup constraints for each u8 cell
-        // Each u8 cell must be in [0, 255] via lookup table
-        // In Halo2, lookup takes (input_expr, TableColumn) pairs
-        meta.lookup("u8_range", |meta| {
-            u8_cells
-                .iter()
-                .map(|&col| {
-                    let cell = meta.query_advice(col, Rotation::cur());
-                    (cell, u8_table)
-                })
-                .collect()
-        });
+        // Each u8 cell must be in [0, 255] via lookup table, one lookup per
+        // cell (pairs in a single lookup would be looked up as one tuple)
+        for &col in &u8_cells {
+            meta.lookup("u8_range", |meta| {
+                let cell = meta.query_advice(col, Rotation::cur());
+                vec![(cell, u8_table)]
+            });
+        }
 
         Self {
             value,
@@ -130,6 +145,16 @@ impl BitwiseRangeCheckConfig {
         }
     }
 
+    /// Number of constraints for range checking `input_len` values
+    ///
+    /// Each value has 1 decomposition constraint and 8 lookups (one per u8 cell).
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of range-checked values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        input_len * (1 + self.u8_cells.len())
+    }
+
     /// Assign a 64-bit value with decomposition
     ///
     /// This method:
@@ -178,6 +203,49 @@ impl BitwiseRangeCheckConfig {
         )
     }
 
+    /// Assign several 64-bit values with decomposition
+    ///
+    /// Each value is assigned to its own row of a single region, so all of
+    /// them are checked against the same loaded lookup table.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `values` - The 64-bit integers to decompose and assign
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
+    pub fn assign_many(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[u64],
+    ) -> Result<(), ErrorFront> {
+        layouter.assign_region(
+            || "bitwise range checks",
+            |mut region| {
+                for (row, &value) in values.iter().enumerate() {
+                    region.assign_advice(
+                        || format!("value[{}]", row),
+                        self.value,
+                        row,
+                        || Value::known(Field::from(value)),
+                    )?;
+
+                    let cells = FieldUtils::decompose_u64(value);
+                    for (i, &cell) in cells.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("value[{}].u8_cell[{}]", row, i),
+                            self.u8_cells[i],
+                            row,
+                            || Value::known(Field::from(cell as u64)),
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
     /// Load the u8 lookup table into the fixed column
     ///
     /// This method assigns values [0..255] to the fixed column
@@ -215,6 +283,342 @@ impl BitwiseRangeCheckConfig {
     }
 }
 
+/// Configuration for n-bit range check gate
+///
+/// This gate verifies that a value is below `2^bits` by decomposing it into
+/// `ceil(bits / 8)` u8 cells, with the top cell bounded to the remaining bits.
+/// Rows are enabled by a selector, so columns can be shared between
+/// configurations of different widths. The u8 lookup table is shared with
+/// (and loaded by) a `BitwiseRangeCheckConfig`.
+#[derive(Debug, Clone)]
+pub struct NBitRangeCheckConfig {
+    /// Bit width of the checked values (1 to 64)
+    pub bits: usize,
+
+    /// Column for the checked value
+    pub value: Column<Advice>,
+
+    /// Columns for the u8 cells, least significant first
+    pub u8_cells: Vec<Column<Advice>>,
+
+    /// TableColumn for the lookup table [0..255]
+    pub u8_table: TableColumn,
+
+    /// Selector for the decomposition and lookups
+    pub s_range: Selector,
+}
+
+impl NBitRangeCheckConfig {
+    /// Configure the n-bit range check gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least `1 + ceil(bits / 8)`:
+    ///   value and u8 cells)
+    /// * `u8_table` - u8 lookup table, e.g. `BitwiseRangeCheckConfig::u8_table`
+    /// * `bits` - Bit width of the checked values (1 to 64)
+    ///
+    /// # Returns
+    /// `NBitRangeCheckConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if `bits` is out of range or not enough columns are provided
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        advice: &[Column<Advice>],
+        u8_table: TableColumn,
+        bits: usize,
+    ) -> Self {
+        // Validate input
+        assert!((1..=64).contains(&bits), "Bit width must be in 1..=64");
+        let num_cells = bits.div_ceil(8);
+        assert!(
+            advice.len() > num_cells,
+            "Need at least {} advice columns (1 value + {} u8 cells)",
+            num_cells + 1,
+            num_cells
+        );
+
+        // Assign columns
+        let value = advice[0];
+        let u8_cells = advice[1..=num_cells].to_vec();
+
+        meta.enable_equality(value);
+
+        let s_range = meta.selector();
+
+        // Constraint 1: Decomposition constraint
+        // value = Σ(i < num_cells) u8_cells[i] * 2^(8i)
+        meta.create_gate("n_bit_decomposition", |meta| {
+            let s = meta.query_selector(s_range);
+            let value = meta.query_advice(value, Rotation::cur());
+
+            let mut recomposed = Expression::Constant(Field::zero());
+            for (i, &col) in u8_cells.iter().enumerate() {
+                let cell = meta.query_advice(col, Rotation::cur());
+                recomposed = recomposed + cell * Field::from_u128(1u128 << (8 * i));
+            }
+
+            vec![s * (value - recomposed)]
+        });
+
+        // Constraint 2: Each u8 cell is in [0, 255], one lookup per cell
+        for &col in &u8_cells {
+            meta.lookup("n_bit_u8_range", |meta| {
+                let s = meta.query_selector(s_range);
+                let cell = meta.query_advice(col, Rotation::cur());
+                vec![(s * cell, u8_table)]
+            });
+        }
+
+        // Constraint 3: The top cell fits in the remaining bits
+        // top * 2^(8 - top_bits) is in [0, 255] iff top < 2^top_bits
+        let top_bits = bits - 8 * (num_cells - 1);
+        if top_bits < 8 {
+            let top = u8_cells[num_cells - 1];
+            meta.lookup("n_bit_top_cell", |meta| {
+                let s = meta.query_selector(s_range);
+                let cell = meta.query_advice(top, Rotation::cur());
+                vec![(s * cell * Field::from(1u64 << (8 - top_bits)), u8_table)]
+            });
+        }
+
+        Self {
+            bits,
+            value,
+            u8_cells,
+            u8_table,
+            s_range,
+        }
+    }
+
+    /// Number of constraints for range checking `input_len` values
+    ///
+    /// Each value has 1 decomposition constraint, one lookup per u8 cell, and
+    /// one more lookup for the top cell when `bits` is not a multiple of 8.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of range-checked values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        let top_lookup = usize::from(self.bits % 8 != 0);
+        input_len * (1 + self.u8_cells.len() + top_lookup)
+    }
+
+    /// Assign several values with decomposition
+    ///
+    /// Each value is assigned to its own row of a single region. The u8
+    /// lookup table must be loaded separately via
+    /// `BitwiseRangeCheckConfig::load_lookup_table`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `values` - The values to decompose and assign
+    ///
+    /// # Returns
+    /// `Ok(())` if assignment succeeds,
+    /// `Err(Error::Synthesis)` if a value doesn't fit in `bits` bits
+    pub fn assign_many(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[u64],
+    ) -> Result<(), ErrorFront> {
+        if self.bits < 64 && values.iter().any(|&value| value >> self.bits != 0) {
+            return Err(ErrorFront::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "n-bit range checks",
+            |mut region| {
+                for (row, &value) in values.iter().enumerate() {
+                    self.s_range.enable(&mut region, row)?;
+
+                    region.assign_advice(
+                        || format!("value[{}]", row),
+                        self.value,
+                        row,
+                        || Value::known(Field::from(value)),
+                    )?;
+
+                    let cells = FieldUtils::decompose_u64(value);
+                    for (i, &col) in self.u8_cells.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("value[{}].u8_cell[{}]", row, i),
+                            col,
+                            row,
+                            || Value::known(Field::from(cells[i] as u64)),
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Configuration for strict positivity check gate
+///
+/// This gate verifies `value - 1` is in `[0, 2^n)` by constraining two rows
+/// of the bitwise range check: `value - 1` on the first row and
+/// `2^n - value` on the next, with `2^n` taken from a fixed column.
+#[derive(Debug, Clone)]
+pub struct StrictlyPositiveConfig {
+    /// Bitwise range check used for both differences
+    pub range_check: BitwiseRangeCheckConfig,
+
+    /// Column for the checked value
+    pub value: Column<Advice>,
+
+    /// Fixed column for the bound 2^n
+    pub bound: Column<Fixed>,
+
+    /// Selector for the positivity constraint
+    pub s_positive: Selector,
+}
+
+impl StrictlyPositiveConfig {
+    /// Configure the strict positivity check gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `range_check` - Bitwise range check to decompose the differences with
+    /// * `advice` - Slice of advice columns (needs at least 1: value)
+    /// * `fixed` - Slice of fixed columns (needs at least 1: bound)
+    ///
+    /// # Returns
+    /// `StrictlyPositiveConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        range_check: BitwiseRangeCheckConfig,
+        advice: &[Column<Advice>],
+        fixed: &[Column<Fixed>],
+    ) -> Self {
+        // Validate input
+        assert!(!advice.is_empty(), "Need at least 1 advice column (value)");
+        assert!(!fixed.is_empty(), "Need at least 1 fixed column (bound)");
+
+        // Assign columns
+        let value = advice[0];
+        let bound = fixed[0];
+
+        meta.enable_equality(value);
+
+        let s_positive = meta.selector();
+
+        // Constraint: differences are the range-checked values
+        // diff_cur = value - 1
+        // diff_next = bound - value
+        meta.create_gate("strictly_positive", |meta| {
+            let s = meta.query_selector(s_positive);
+            let value = meta.query_advice(value, Rotation::cur());
+            let bound = meta.query_fixed(bound, Rotation::cur());
+            let diff_low = meta.query_advice(range_check.value, Rotation::cur());
+            let diff_high = meta.query_advice(range_check.value, Rotation::next());
+
+            vec![
+                s.clone() * (diff_low - (value.clone() - Expression::Constant(Field::one()))),
+                s * (diff_high - (bound - value)),
+            ]
+        });
+
+        Self {
+            range_check,
+            value,
+            bound,
+            s_positive,
+        }
+    }
+
+    /// Number of constraints for `input_len` positivity checks
+    ///
+    /// Each check has 2 difference constraints and range checks both differences.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of positivity checks
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        2 * input_len + self.range_check.num_constraints(2 * input_len)
+    }
+
+    /// Assign a strict positivity check
+    ///
+    /// The u8 lookup table must be loaded separately via
+    /// `BitwiseRangeCheckConfig::load_lookup_table`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `value` - The value to check
+    /// * `n` - Bit width of `value - 1` (1 to 64)
+    ///
+    /// # Returns
+    /// The assigned value cell if assignment succeeds,
+    /// `Err(Error::Synthesis)` if `n` is out of range
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        value: u64,
+        n: u32,
+    ) -> Result<AssignedCell<Field, Field>, ErrorFront> {
+        if n == 0 || n > 64 {
+            return Err(ErrorFront::Synthesis);
+        }
+        let bound = 1u128 << n;
+
+        // Field differences are constrained; the decompositions only match
+        // them when the differences are non-negative
+        let diffs = [
+            (Field::from(value) - Field::one(), value.wrapping_sub(1)),
+            (
+                Field::from_u128(bound) - Field::from(value),
+                bound.wrapping_sub(value as u128) as u64,
+            ),
+        ];
+
+        layouter.assign_region(
+            || "strictly positive check",
+            |mut region| {
+                self.s_positive.enable(&mut region, 0)?;
+
+                region.assign_fixed(
+                    || "bound",
+                    self.bound,
+                    0,
+                    || Value::known(Field::from_u128(bound)),
+                )?;
+                let cell = region.assign_advice(
+                    || "value",
+                    self.value,
+                    0,
+                    || Value::known(Field::from(value)),
+                )?;
+
+                for (row, &(diff, diff_u64)) in diffs.iter().enumerate() {
+                    region.assign_advice(
+                        || format!("diff[{}]", row),
+                        self.range_check.value,
+                        row,
+                        || Value::known(diff),
+                    )?;
+
+                    let cells = FieldUtils::decompose_u64(diff_u64);
+                    for (i, &cell) in cells.iter().enumerate() {
+                        region.assign_advice(
+                            || format!("diff[{}].u8_cell[{}]", row, i),
+                            self.range_check.u8_cells[i],
+                            row,
+                            || Value::known(Field::from(cell as u64)),
+                        )?;
+                    }
+                }
+
+                Ok(cell)
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +652,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_range_check_num_constraints() {
+        let mut meta = ConstraintSystem::<Field>::default();
+        let advice = (0..9).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let config = BitwiseRangeCheckConfig::configure(&mut meta, &advice, &[]);
+
+        // 1 decomposition constraint plus 8 u8 lookups per value
+        assert_eq!(config.num_constraints(1), 1 + 8);
+        assert_eq!(config.num_constraints(4), 4 * 9);
+        assert_eq!(config.num_constraints(0), 0);
+    }
+
     #[test]
     fn test_u8_lookup_table() {
         // Test lookup table creation
@@ -272,6 +688,7 @@ mod tests {
     impl Circuit<Field> for TestCircuit {
         type Config = BitwiseRangeCheckConfig;
         type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
 
         fn without_witnesses(&self) -> Self {
             Self::default()
@@ -317,6 +734,101 @@ mod tests {
         }
     }
 
+    /// Test circuit for several range checks sharing one lookup table
+    #[derive(Default)]
+    struct ManyTestCircuit {
+        values: Vec<u64>,
+    }
+
+    impl Circuit<Field> for ManyTestCircuit {
+        type Config = BitwiseRangeCheckConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            TestCircuit::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            config.load_lookup_table(&mut layouter)?;
+            config.assign_many(&mut layouter, &self.values)
+        }
+    }
+
+    #[test]
+    fn test_range_check_many_shared_table() {
+        let circuit = ManyTestCircuit {
+            values: vec![7u64, 65536u64, u64::MAX],
+        };
+        let k = 10;
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+
+    /// Test circuit for strict positivity check gate
+    #[derive(Default)]
+    struct PositiveTestCircuit {
+        value: u64,
+        n: u32,
+    }
+
+    impl Circuit<Field> for PositiveTestCircuit {
+        type Config = StrictlyPositiveConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..10).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let fixed = vec![meta.fixed_column()];
+            let range_check = BitwiseRangeCheckConfig::configure(meta, &advice[..9], &[]);
+
+            StrictlyPositiveConfig::configure(meta, range_check, &advice[9..], &fixed)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            config.range_check.load_lookup_table(&mut layouter)?;
+            config.assign(&mut layouter, self.value, self.n)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_strictly_positive_accepts() {
+        for value in [1u64, 255] {
+            let circuit = PositiveTestCircuit { value, n: 8 };
+            let k = 10;
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "{} should be positive", value);
+        }
+    }
+
+    #[test]
+    fn test_strictly_positive_rejects() {
+        // 0 is not positive; 257 - 1 does not fit in 8 bits
+        for value in [0u64, 257] {
+            let circuit = PositiveTestCircuit { value, n: 8 };
+            let k = 10;
+            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+            assert!(prover.verify().is_err(), "{} should be rejected", value);
+        }
+    }
+
     #[test]
     fn test_range_check_circuit_max_value() {
         // Test with maximum u64 value
@@ -329,4 +841,74 @@ mod tests {
             "Circuit verification failed for u64::MAX"
         );
     }
+
+    /// Test circuit for n-bit range checks sharing the u8 lookup table
+    #[derive(Default)]
+    struct NBitTestCircuit {
+        values: Vec<u64>,
+    }
+
+    impl Circuit<Field> for NBitTestCircuit {
+        type Config = (
+            BitwiseRangeCheckConfig,
+            NBitRangeCheckConfig,
+            NBitRangeCheckConfig,
+        );
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..12).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let range_check = BitwiseRangeCheckConfig::configure(meta, &advice[..9], &[]);
+            // Both widths share the same columns
+            let one_bit =
+                NBitRangeCheckConfig::configure(meta, &advice[9..], range_check.u8_table, 1);
+            let twelve_bits =
+                NBitRangeCheckConfig::configure(meta, &advice[9..], range_check.u8_table, 12);
+            (range_check, one_bit, twelve_bits)
+        }
+
+        fn synthesize(
+            &self,
+            (range_check, one_bit, twelve_bits): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            range_check.load_lookup_table(&mut layouter)?;
+            one_bit.assign_many(&mut layouter, &self.values)?;
+            twelve_bits.assign_many(&mut layouter, &[4095, 256])
+        }
+    }
+
+    #[test]
+    fn test_n_bit_range_check() {
+        let circuit = NBitTestCircuit {
+            values: vec![0, 1, 1],
+        };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // 2 does not fit in 1 bit
+        let circuit = NBitTestCircuit { values: vec![0, 2] };
+        assert!(MockProver::run(10, &circuit, vec![]).is_err());
+    }
+
+    #[test]
+    fn test_n_bit_range_check_num_constraints() {
+        let mut meta = ConstraintSystem::<Field>::default();
+        let advice = (0..9).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let table = meta.lookup_table_column();
+
+        // 1 decomposition constraint, 1 u8 lookup, 1 top-cell lookup
+        let config = NBitRangeCheckConfig::configure(&mut meta, &advice, table, 1);
+        assert_eq!(config.num_constraints(2), 2 * 3);
+
+        // 4 whole u8 cells, no top-cell lookup
+        let config = NBitRangeCheckConfig::configure(&mut meta, &advice, table, 32);
+        assert_eq!(config.u8_cells.len(), 4);
+        assert_eq!(config.num_constraints(1), 1 + 4);
+    }
 }
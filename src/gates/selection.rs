@@ -0,0 +1,1118 @@
+//! Selection gate for proving which table rows a filter selects
+//!
+//! This module provides a selection gate that verifies, over the cells of
+//! the table columns a query reads:
+//! 1. Each comparison flag is set exactly when its row satisfies
+//!    `value > rhs`, `value < rhs`, `value = rhs` (or the negation), where
+//!    the right-hand side is a constant or another column of the row
+//! 2. Flags of several comparisons combine with AND and OR into one flag
+//!    per row
+//! 3. The selected cells of a column are exactly the cells whose row flag is
+//!    set, in table order
+//!
+//! Table cells are assigned once and copied into every gate reading them,
+//! so the flags (and the COUNT over them) and the selected cells (and the
+//! aggregates over them) are bound to the same table. A prover can neither
+//! drop a row satisfying the predicate nor add one that doesn't.
+//!
+//! # Method
+//!
+//! Comparison rows, for value v, right-hand side t, comparison bit r and
+//! negation n (fixed):
+//! 1. Boolean check: r · (1 - r) = 0
+//! 2. `>`: d = r · (v - t - 1) + (1 - r) · (t - v), with d range-checked to
+//!    64 bits (d is only in range if the bit is right); `<` swaps v and t
+//! 3. `=`: r = 1 - (v - t) · e and (v - t) · r = 0, with inverse e
+//! 4. NULL: w = (v + 1) · (t + 1), z = 1 - w · i and w · z = 0, so z = 1
+//!    exactly when an operand is the NULL encoding -1
+//! 5. Flag: f = (r + n - 2 · n · r) · (1 - z), so NULL never satisfies a
+//!    comparison, negated or not
+//!
+//! Combination rows: a · (1 - a) = 0, b · (1 - b) = 0 and `out = a · b`
+//! (AND) or `out = a + b - a · b` (OR).
+//!
+//! Compaction of a column by row flags, with a fixed tag per use:
+//! 1. Positions: c_0 = f_0, c_i = c_{i-1} + f_i
+//! 2. Subset: each (f_i · tag, f_i · c_i, f_i · v_i) is looked up in the
+//!    output table of (tag, position, output) rows, where unselected rows
+//!    map to (0, 0, 0)
+//! 3. Count: the last position equals the number of outputs (copy constraint)
+//!
+//! Positions 1..count each occur once among the selected rows, so the
+//! lookup puts the j-th selected value at output position j, and the count
+//! constraint leaves no output position unmatched.
+//!
+//! # Constraints
+//!
+//! - Comparison: 7 per row, plus a range check of its difference
+//! - Combination: 3 per row
+//! - Compaction: 2 per row, 1 lookup per row, and 1 copy constraint
+//!
+//! # Limitations
+//!
+//! Operands are compared as unsigned integers below 2^64, so callers must
+//! reject negative values (whose encodings are two's complement). A value
+//! equal to the NULL encoding is treated as NULL.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::range_check::BitwiseRangeCheckConfig;
+//! use nzengi_db::gates::selection::SelectionConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let range_advice = (0..9).map(|_| meta.advice_column()).collect::<Vec<_>>();
+//! let advice = (0..10).map(|_| meta.advice_column()).collect::<Vec<_>>();
+//!
+//! let range_check = BitwiseRangeCheckConfig::configure(&mut meta, &range_advice, &[]);
+//! let config = SelectionConfig::configure(&mut meta, range_check, &advice);
+//! ```
+
+use super::range_check::BitwiseRangeCheckConfig;
+use crate::field::FieldUtils;
+use crate::types::Value as SqlValue;
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+/// Comparison of a row value with its right-hand side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// value > rhs
+    Greater,
+
+    /// value < rhs
+    Less,
+
+    /// value = rhs
+    Equal,
+
+    /// value is not NULL (the right-hand side is ignored)
+    NotNull,
+}
+
+/// Right-hand side of a comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    /// The same constant on every row
+    Constant(Field),
+
+    /// Another column of the row, by index into the circuit's table columns
+    Column(usize),
+}
+
+/// Predicate over the rows of a table
+///
+/// Columns are indices into the table columns of the circuit (see
+/// `NzengiCircuit::with_table`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Predicate {
+    /// `column <comparison> rhs`, or its negation; NULL operands never
+    /// satisfy either
+    Compare {
+        /// Column on the left-hand side
+        column: usize,
+
+        /// Comparison of the column with the right-hand side
+        comparison: Comparison,
+
+        /// Right-hand side
+        rhs: Operand,
+
+        /// Whether the comparison is negated (`<=` is a negated `>`)
+        negated: bool,
+    },
+
+    /// Every child holds (true without children)
+    And(Vec<Predicate>),
+
+    /// Some child holds (false without children)
+    Or(Vec<Predicate>),
+}
+
+impl Predicate {
+    /// Predicate holding on the rows where a column is not NULL
+    pub fn not_null(column: usize) -> Self {
+        Predicate::Compare {
+            column,
+            comparison: Comparison::NotNull,
+            rhs: Operand::Constant(Field::zero()),
+            negated: false,
+        }
+    }
+
+    /// Check whether the predicate holds on a row of the table columns
+    ///
+    /// Mirrors the flags the selection gate proves: operands compare as
+    /// unsigned integers, and NULL operands satisfy no comparison.
+    pub fn holds(&self, columns: &[Vec<Field>], row: usize) -> bool {
+        match self {
+            Predicate::Compare {
+                column,
+                comparison,
+                rhs,
+                negated,
+            } => {
+                let value = columns[*column][row];
+                let rhs = match rhs {
+                    Operand::Constant(c) => *c,
+                    Operand::Column(idx) => columns[*idx][row],
+                };
+                if SqlValue::is_null_field(&value) || SqlValue::is_null_field(&rhs) {
+                    return false;
+                }
+                SelectionConfig::comparison_bit(*comparison, value, rhs) != *negated
+            }
+            Predicate::And(children) => children.iter().all(|c| c.holds(columns, row)),
+            Predicate::Or(children) => children.iter().any(|c| c.holds(columns, row)),
+        }
+    }
+
+    /// Get the flag of every row of the table columns
+    ///
+    /// # Arguments
+    /// * `columns` - Table columns, one value per row each
+    /// * `num_rows` - Number of table rows
+    pub fn flags(&self, columns: &[Vec<Field>], num_rows: usize) -> Vec<bool> {
+        (0..num_rows).map(|row| self.holds(columns, row)).collect()
+    }
+}
+
+/// Boolean operator combining two flag columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    /// out = a · b
+    And,
+
+    /// out = a + b - a · b
+    Or,
+}
+
+/// Cells of the right-hand side of a comparison
+#[derive(Debug, Clone, Copy)]
+pub enum RhsCells<'a> {
+    /// The same constant on every row
+    Constant(Field),
+
+    /// One cell per row
+    Column(&'a [AssignedCell<Field, Field>]),
+}
+
+/// Constraints on the comparison bit of a comparison row, given the value,
+/// the right-hand side, the bit, the difference and the equality inverse
+type BitConstraints = fn(
+    Expression<Field>,
+    Expression<Field>,
+    Expression<Field>,
+    Expression<Field>,
+    Expression<Field>,
+) -> Vec<Expression<Field>>;
+
+/// Configuration for selection gate
+///
+/// This gate verifies per-row comparison flags over table cells, their
+/// boolean combination, and the cells of the rows they select.
+#[derive(Debug, Clone)]
+pub struct SelectionConfig {
+    /// Bitwise range check for the comparison differences
+    pub range_check: BitwiseRangeCheckConfig,
+
+    /// Column for the table cells the other columns copy
+    pub column: Column<Advice>,
+
+    /// Column for compared values (and the left flag of combination rows,
+    /// and the compacted values)
+    pub value: Column<Advice>,
+
+    /// Column for the right-hand side (and the right flag of combination rows)
+    pub rhs: Column<Advice>,
+
+    /// Column for the comparison bit, before negation and NULL handling
+    pub raw: Column<Advice>,
+
+    /// Column for row flags (1 = row satisfies the predicate)
+    pub flag: Column<Advice>,
+
+    /// Column for the NULL bit (1 = an operand is NULL)
+    pub null: Column<Advice>,
+
+    /// Column for the inverse of (v + 1) · (t + 1), or 0
+    pub null_inverse: Column<Advice>,
+
+    /// Column for the inverse of v - t on equality rows, or 0
+    pub eq_inverse: Column<Advice>,
+
+    /// Column for the running position of selected rows
+    pub running: Column<Advice>,
+
+    /// Column for output values (the selected cells, in order)
+    pub output: Column<Advice>,
+
+    /// Fixed column for the negation of comparison rows
+    pub negated: Column<Fixed>,
+
+    /// Fixed column for output positions (1-based)
+    pub position: Column<Fixed>,
+
+    /// Fixed column for the tag of a compaction, on input and output rows
+    pub tag: Column<Fixed>,
+
+    /// Fixed column for constants (right-hand sides, NOT NULL operands)
+    pub constant: Column<Fixed>,
+
+    /// Selector for `>` comparison rows
+    pub s_greater: Selector,
+
+    /// Selector for `<` comparison rows
+    pub s_less: Selector,
+
+    /// Selector for `=` comparison rows
+    pub s_equal: Selector,
+
+    /// Selector for NOT NULL rows
+    pub s_not_null: Selector,
+
+    /// Selector for AND combination rows
+    pub s_and: Selector,
+
+    /// Selector for OR combination rows
+    pub s_or: Selector,
+
+    /// Selector for compaction input rows (flag and subset lookup)
+    pub s_input: Selector,
+
+    /// Selector for the first compaction input row (c_0 = f_0)
+    pub s_first: Selector,
+
+    /// Selector for subsequent compaction input rows (c_i = c_{i-1} + f_i)
+    pub s_acc: Selector,
+
+    /// Selector for output rows of the lookup table
+    pub s_output: Selector,
+}
+
+impl SelectionConfig {
+    /// Configure the selection gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `range_check` - Bitwise range check to decompose the differences with
+    /// * `advice` - Slice of advice columns (needs at least 10: column,
+    ///   value, rhs, raw, flag, null, null_inverse, eq_inverse, running,
+    ///   output)
+    ///
+    /// # Returns
+    /// `SelectionConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(
+        meta: &mut ConstraintSystem<Field>,
+        range_check: BitwiseRangeCheckConfig,
+        advice: &[Column<Advice>],
+    ) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 10,
+            "Need at least 10 advice columns (column, value, rhs, raw, flag, null, \
+             null_inverse, eq_inverse, running, output)"
+        );
+
+        // Assign columns
+        let column = advice[0];
+        let value = advice[1];
+        let rhs = advice[2];
+        let raw = advice[3];
+        let flag = advice[4];
+        let null = advice[5];
+        let null_inverse = advice[6];
+        let eq_inverse = advice[7];
+        let running = advice[8];
+        let output = advice[9];
+        let negated = meta.fixed_column();
+        let position = meta.fixed_column();
+        let tag = meta.fixed_column();
+        let constant = meta.fixed_column();
+
+        // Enable equality for copied cells and the count constraint
+        for col in [column, value, rhs, flag, running, output] {
+            meta.enable_equality(col);
+        }
+        meta.enable_equality(position);
+        meta.enable_constant(constant);
+
+        let s_greater = meta.selector();
+        let s_less = meta.selector();
+        let s_equal = meta.selector();
+        let s_not_null = meta.selector();
+        let s_and = meta.selector();
+        let s_or = meta.selector();
+        // Selectors used in the lookup must be complex selectors
+        let s_input = meta.complex_selector();
+        let s_first = meta.selector();
+        let s_acc = meta.selector();
+        let s_output = meta.complex_selector();
+
+        // Constraints 1-5 for each comparison; `bit` constrains the
+        // comparison bit r of its rows
+        let comparison_gate = |meta: &mut ConstraintSystem<Field>,
+                               name: &'static str,
+                               selector: Selector,
+                               bit: BitConstraints| {
+            meta.create_gate(name, |meta| {
+                let q = meta.query_selector(selector);
+                let v = meta.query_advice(value, Rotation::cur());
+                let t = meta.query_advice(rhs, Rotation::cur());
+                let r = meta.query_advice(raw, Rotation::cur());
+                let f = meta.query_advice(flag, Rotation::cur());
+                let z = meta.query_advice(null, Rotation::cur());
+                let i = meta.query_advice(null_inverse, Rotation::cur());
+                let e = meta.query_advice(eq_inverse, Rotation::cur());
+                let d = meta.query_advice(range_check.value, Rotation::cur());
+                let n = meta.query_fixed(negated, Rotation::cur());
+                let one = Expression::Constant(Field::one());
+                let two = Expression::Constant(Field::from(2));
+
+                let w = (v.clone() + one.clone()) * (t.clone() + one.clone());
+                let negated_bit = r.clone() + n.clone() - two * n * r.clone();
+
+                let mut constraints = vec![
+                    r.clone() * (one.clone() - r.clone()),
+                    z.clone() - one.clone() + w.clone() * i,
+                    w * z.clone(),
+                    f - negated_bit * (one - z),
+                ];
+                constraints.extend(bit(v, t, r, d, e));
+                constraints
+                    .into_iter()
+                    .map(|c| q.clone() * c)
+                    .collect::<Vec<_>>()
+            });
+        };
+
+        // `>`: d = r · (v - t - 1) + (1 - r) · (t - v)
+        comparison_gate(meta, "selection_greater", s_greater, |v, t, r, d, _| {
+            let one = Expression::Constant(Field::one());
+            let expected = r.clone() * (v.clone() - t.clone() - one.clone()) + (one - r) * (t - v);
+            vec![d - expected]
+        });
+        // `<`: d = r · (t - v - 1) + (1 - r) · (v - t)
+        comparison_gate(meta, "selection_less", s_less, |v, t, r, d, _| {
+            let one = Expression::Constant(Field::one());
+            let expected = r.clone() * (t.clone() - v.clone() - one.clone()) + (one - r) * (v - t);
+            vec![d - expected]
+        });
+        // `=`: r = 1 - (v - t) · e, (v - t) · r = 0
+        comparison_gate(meta, "selection_equal", s_equal, |v, t, r, _, e| {
+            let one = Expression::Constant(Field::one());
+            let delta = v - t;
+            vec![r.clone() - one + delta.clone() * e, delta * r]
+        });
+        // NOT NULL: r = 1, so the flag is 1 - z
+        comparison_gate(meta, "selection_not_null", s_not_null, |_, _, r, _, _| {
+            vec![r - Expression::Constant(Field::one())]
+        });
+
+        // Combination rows: boolean inputs, out = a · b or a + b - a · b
+        meta.create_gate("selection_combine", |meta| {
+            let q_and = meta.query_selector(s_and);
+            let q_or = meta.query_selector(s_or);
+            let a = meta.query_advice(value, Rotation::cur());
+            let b = meta.query_advice(rhs, Rotation::cur());
+            let out = meta.query_advice(flag, Rotation::cur());
+            let one = Expression::Constant(Field::one());
+
+            let boolean = |x: Expression<Field>| x.clone() * (one.clone() - x);
+            let product = a.clone() * b.clone();
+            vec![
+                (q_and.clone() + q_or.clone()) * boolean(a.clone()),
+                (q_and.clone() + q_or.clone()) * boolean(b.clone()),
+                q_and * (out.clone() - product.clone()),
+                q_or * (out - (a + b - product)),
+            ]
+        });
+
+        // Compaction: flags are boolean, positions count the selected rows
+        // c_0 = f_0, c_i = c_{i-1} + f_i
+        meta.create_gate("selection_flag", |meta| {
+            let q = meta.query_selector(s_input);
+            let f = meta.query_advice(flag, Rotation::cur());
+
+            vec![q * f.clone() * (Expression::Constant(Field::one()) - f)]
+        });
+        meta.create_gate("selection_first", |meta| {
+            let q = meta.query_selector(s_first);
+            let f = meta.query_advice(flag, Rotation::cur());
+            let c = meta.query_advice(running, Rotation::cur());
+
+            vec![q * (c - f)]
+        });
+        meta.create_gate("selection_accumulate", |meta| {
+            let q = meta.query_selector(s_acc);
+            let f = meta.query_advice(flag, Rotation::cur());
+            let c_cur = meta.query_advice(running, Rotation::cur());
+            let c_prev = meta.query_advice(running, Rotation::prev());
+
+            vec![q * (c_cur - c_prev - f)]
+        });
+
+        // Each selected (tag, position, value) is an output row
+        meta.lookup_any("selection_subset", |meta| {
+            let q_in = meta.query_selector(s_input);
+            let q_out = meta.query_selector(s_output);
+            let f = meta.query_advice(flag, Rotation::cur());
+            let c = meta.query_advice(running, Rotation::cur());
+            let v = meta.query_advice(value, Rotation::cur());
+            let tag = meta.query_fixed(tag, Rotation::cur());
+            let p = meta.query_fixed(position, Rotation::cur());
+            let o = meta.query_advice(output, Rotation::cur());
+            let selected = q_in * f;
+
+            vec![
+                (selected.clone() * tag.clone(), q_out.clone() * tag),
+                (selected.clone() * c, q_out.clone() * p),
+                (selected * v, q_out * o),
+            ]
+        });
+
+        Self {
+            range_check,
+            column,
+            value,
+            rhs,
+            raw,
+            flag,
+            null,
+            null_inverse,
+            eq_inverse,
+            running,
+            output,
+            negated,
+            position,
+            tag,
+            constant,
+            s_greater,
+            s_less,
+            s_equal,
+            s_not_null,
+            s_and,
+            s_or,
+            s_input,
+            s_first,
+            s_acc,
+            s_output,
+        }
+    }
+
+    /// Number of constraints for comparing and compacting `input_len` rows
+    ///
+    /// Each row has 7 comparison constraints and a range check of its
+    /// difference, plus 2 compaction constraints and 1 lookup, plus 1 copy
+    /// constraint for the count.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of input rows
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        10 * input_len + self.range_check.num_constraints(input_len) + usize::from(input_len > 0)
+    }
+
+    /// Compute the comparison bit of `value <comparison> rhs`
+    ///
+    /// Operands compare as unsigned integers: for `>` the bit is set when
+    /// `rhs - value` doesn't fit 64 bits, which for operands below 2^64 is
+    /// exactly `value > rhs`. This is also the bit that keeps the gate's
+    /// difference in range for NULL operands.
+    pub fn comparison_bit(comparison: Comparison, value: Field, rhs: Field) -> bool {
+        match comparison {
+            Comparison::Greater => FieldUtils::to_u64(&(rhs - value)).is_none(),
+            Comparison::Less => FieldUtils::to_u64(&(value - rhs)).is_none(),
+            Comparison::Equal => value == rhs,
+            Comparison::NotNull => true,
+        }
+    }
+
+    /// Assign table cells, to be copied by the gates reading them
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `values` - Cell values, one per table row
+    ///
+    /// # Returns
+    /// The assigned cells, in row order
+    pub fn assign_column(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[Field],
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        layouter.assign_region(
+            || "table column",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &value)| {
+                        region.assign_advice(
+                            || format!("cell[{}]", i),
+                            self.column,
+                            i,
+                            || Value::known(value),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assign the comparison flag of every row
+    ///
+    /// The u8 lookup table must be loaded separately via
+    /// `BitwiseRangeCheckConfig::load_lookup_table`.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `values` - Cells of the left-hand column
+    /// * `rhs` - Right-hand side: a constant, or one cell per row
+    /// * `comparison` - Comparison of each value with its right-hand side
+    /// * `negated` - Whether the flag is the negated comparison
+    ///
+    /// # Returns
+    /// `Ok(flags)` with one flag cell per row,
+    /// `Err(Error::Synthesis)` if `rhs` cells and `values` differ in length
+    pub fn assign_compare(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        values: &[AssignedCell<Field, Field>],
+        rhs: RhsCells<'_>,
+        comparison: Comparison,
+        negated: bool,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        if matches!(rhs, RhsCells::Column(cells) if cells.len() != values.len()) {
+            return Err(ErrorFront::Synthesis);
+        }
+        let selector = match comparison {
+            Comparison::Greater => self.s_greater,
+            Comparison::Less => self.s_less,
+            Comparison::Equal => self.s_equal,
+            Comparison::NotNull => self.s_not_null,
+        };
+
+        layouter.assign_region(
+            || "selection compare",
+            |mut region| {
+                let mut flags = vec![];
+                for (i, value_cell) in values.iter().enumerate() {
+                    selector.enable(&mut region, i)?;
+                    region.assign_fixed(
+                        || format!("negated[{}]", i),
+                        self.negated,
+                        i,
+                        || Value::known(Field::from(negated as u64)),
+                    )?;
+
+                    let v = value_cell.copy_advice(
+                        || format!("value[{}]", i),
+                        &mut region,
+                        self.value,
+                        i,
+                    )?;
+                    let t = match rhs {
+                        RhsCells::Constant(c) => region.assign_advice_from_constant(
+                            || format!("rhs[{}]", i),
+                            self.rhs,
+                            i,
+                            c,
+                        )?,
+                        RhsCells::Column(cells) => cells[i].copy_advice(
+                            || format!("rhs[{}]", i),
+                            &mut region,
+                            self.rhs,
+                            i,
+                        )?,
+                    };
+                    let (v, t) = (v.value().copied(), t.value().copied());
+
+                    // NULL bit and the inverse proving it is 0
+                    let w = v
+                        .zip(t)
+                        .map(|(v, t)| (v + Field::one()) * (t + Field::one()));
+                    let z = w.map(|w| Field::from(bool::from(w.is_zero()) as u64));
+                    region.assign_advice(|| format!("null[{}]", i), self.null, i, || z)?;
+                    region.assign_advice(
+                        || format!("null_inverse[{}]", i),
+                        self.null_inverse,
+                        i,
+                        || w.map(|w| w.invert().unwrap_or(Field::zero())),
+                    )?;
+
+                    let r = v
+                        .zip(t)
+                        .map(|(v, t)| Self::comparison_bit(comparison, v, t));
+                    region.assign_advice(
+                        || format!("raw[{}]", i),
+                        self.raw,
+                        i,
+                        || r.map(|r| Field::from(r as u64)),
+                    )?;
+                    let f = r.zip(z).map(|(r, z)| (r != negated) && z == Field::zero());
+                    flags.push(region.assign_advice(
+                        || format!("flag[{}]", i),
+                        self.flag,
+                        i,
+                        || f.map(|f| Field::from(f as u64)),
+                    )?);
+
+                    let e = v.zip(t).map(|(v, t)| match comparison {
+                        Comparison::Equal => (v - t).invert().unwrap_or(Field::zero()),
+                        _ => Field::zero(),
+                    });
+                    region.assign_advice(
+                        || format!("eq_inverse[{}]", i),
+                        self.eq_inverse,
+                        i,
+                        || e,
+                    )?;
+
+                    // Field differences are constrained; the decomposition
+                    // only matches them when the bit is right
+                    let d = v.zip(t).zip(r).map(|((v, t), r)| match (comparison, r) {
+                        (Comparison::Greater, true) => v - t - Field::one(),
+                        (Comparison::Greater, false) => t - v,
+                        (Comparison::Less, true) => t - v - Field::one(),
+                        (Comparison::Less, false) => v - t,
+                        _ => Field::zero(),
+                    });
+                    region.assign_advice(
+                        || format!("diff[{}]", i),
+                        self.range_check.value,
+                        i,
+                        || d,
+                    )?;
+                    let cells =
+                        d.map(|d| FieldUtils::to_u64(&d).map_or([0; 8], FieldUtils::decompose_u64));
+                    for j in 0..8 {
+                        region.assign_advice(
+                            || format!("diff[{}].u8_cell[{}]", i, j),
+                            self.range_check.u8_cells[j],
+                            i,
+                            || cells.map(|cells| Field::from(cells[j] as u64)),
+                        )?;
+                    }
+                }
+                Ok(flags)
+            },
+        )
+    }
+
+    /// Assign the AND or OR of two flag columns, row by row
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `a` - Left flags
+    /// * `b` - Right flags
+    /// * `op` - Boolean operator
+    ///
+    /// # Returns
+    /// `Ok(flags)` with one combined flag cell per row,
+    /// `Err(Error::Synthesis)` if `a` and `b` differ in length
+    pub fn assign_combine(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        a: &[AssignedCell<Field, Field>],
+        b: &[AssignedCell<Field, Field>],
+        op: Combine,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        if a.len() != b.len() {
+            return Err(ErrorFront::Synthesis);
+        }
+        let selector = match op {
+            Combine::And => self.s_and,
+            Combine::Or => self.s_or,
+        };
+
+        layouter.assign_region(
+            || "selection combine",
+            |mut region| {
+                a.iter()
+                    .zip(b)
+                    .enumerate()
+                    .map(|(i, (a_cell, b_cell))| {
+                        selector.enable(&mut region, i)?;
+                        let a = a_cell.copy_advice(
+                            || format!("a[{}]", i),
+                            &mut region,
+                            self.value,
+                            i,
+                        )?;
+                        let b =
+                            b_cell.copy_advice(|| format!("b[{}]", i), &mut region, self.rhs, i)?;
+                        let out = a.value().zip(b.value()).map(|(&a, &b)| match op {
+                            Combine::And => a * b,
+                            Combine::Or => a + b - a * b,
+                        });
+                        region.assign_advice(|| format!("out[{}]", i), self.flag, i, || out)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assign the same flag on every row, for a predicate without children
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `num_rows` - Number of rows
+    /// * `flag` - Flag of every row
+    ///
+    /// # Returns
+    /// One flag cell per row, each constrained to the constant flag
+    pub fn assign_constant(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        num_rows: usize,
+        flag: bool,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        layouter.assign_region(
+            || "selection constant",
+            |mut region| {
+                (0..num_rows)
+                    .map(|i| {
+                        region.assign_advice_from_constant(
+                            || format!("flag[{}]", i),
+                            self.flag,
+                            i,
+                            Field::from(flag as u64),
+                        )
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Assign the flag of every row under a predicate
+    ///
+    /// Comparisons are assigned with [`assign_compare`](Self::assign_compare)
+    /// and combined child by child with
+    /// [`assign_combine`](Self::assign_combine).
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `predicate` - Predicate over the columns
+    /// * `columns` - Cells of each table column, one per row
+    /// * `num_rows` - Number of table rows
+    ///
+    /// # Returns
+    /// `Ok(flags)` with one flag cell per row, `Err(Error::Synthesis)` if
+    /// the predicate reads a missing column or a column has another length
+    pub fn assign_predicate(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        predicate: &Predicate,
+        columns: &[Vec<AssignedCell<Field, Field>>],
+        num_rows: usize,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        let column = |idx: usize| match columns.get(idx) {
+            Some(cells) if cells.len() == num_rows => Ok(&cells[..]),
+            _ => Err(ErrorFront::Synthesis),
+        };
+
+        let (children, op) = match predicate {
+            Predicate::Compare {
+                column: idx,
+                comparison,
+                rhs,
+                negated,
+            } => {
+                let rhs = match rhs {
+                    Operand::Constant(c) => RhsCells::Constant(*c),
+                    Operand::Column(other) => RhsCells::Column(column(*other)?),
+                };
+                return self.assign_compare(layouter, column(*idx)?, rhs, *comparison, *negated);
+            }
+            Predicate::And(children) => (children, Combine::And),
+            Predicate::Or(children) => (children, Combine::Or),
+        };
+
+        let Some((first, rest)) = children.split_first() else {
+            return self.assign_constant(layouter, num_rows, op == Combine::And);
+        };
+        let mut flags = self.assign_predicate(layouter, first, columns, num_rows)?;
+        for child in rest {
+            let child_flags = self.assign_predicate(layouter, child, columns, num_rows)?;
+            flags = self.assign_combine(layouter, &flags, &child_flags, op)?;
+        }
+        Ok(flags)
+    }
+
+    /// Constrain the cells of two gates to be equal, pairwise
+    ///
+    /// # Returns
+    /// `Ok(())` if the constraints are added, `Err(Error::Synthesis)` if the
+    /// cell lists differ in length
+    pub fn constrain_cells(
+        layouter: &mut impl Layouter<Field>,
+        cells: &[AssignedCell<Field, Field>],
+        others: &[AssignedCell<Field, Field>],
+    ) -> Result<(), ErrorFront> {
+        if cells.len() != others.len() {
+            return Err(ErrorFront::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "selected cells",
+            |mut region| {
+                for (cell, other) in cells.iter().zip(others) {
+                    region.constrain_equal(cell.cell(), other.cell())?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign the cells of the rows whose flag is set, in row order
+    ///
+    /// Each compaction needs its own non-zero tag so that its output rows
+    /// can't satisfy the lookups of another. Flags and values are copied
+    /// from the given cells; `selected` lays out the output rows and must
+    /// match the flags, or the constraints reject the compaction.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `flags` - Flag cell of each row
+    /// * `values` - Cell of each row to compact
+    /// * `selected` - Flag of each row
+    /// * `tag` - Tag of this compaction (non-zero)
+    ///
+    /// # Returns
+    /// `Ok(outputs)` with the selected cells, in row order,
+    /// `Err(Error::Synthesis)` if the inputs differ in length or the tag is 0
+    pub fn assign_compact(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        flags: &[AssignedCell<Field, Field>],
+        values: &[AssignedCell<Field, Field>],
+        selected: &[bool],
+        tag: u64,
+    ) -> Result<Vec<AssignedCell<Field, Field>>, ErrorFront> {
+        if flags.len() != values.len() || selected.len() != values.len() || tag == 0 {
+            return Err(ErrorFront::Synthesis);
+        }
+        if values.is_empty() {
+            return Ok(vec![]);
+        }
+
+        layouter.assign_region(
+            || "selection compact",
+            |mut region| {
+                let mut count = Value::known(Field::zero());
+                let mut count_cell = None;
+                let mut outputs = vec![];
+
+                for (i, (flag_cell, value_cell)) in flags.iter().zip(values).enumerate() {
+                    self.s_input.enable(&mut region, i)?;
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_acc.enable(&mut region, i)?;
+                    }
+                    region.assign_fixed(
+                        || format!("tag[{}]", i),
+                        self.tag,
+                        i,
+                        || Value::known(Field::from(tag)),
+                    )?;
+
+                    let flag = flag_cell.copy_advice(
+                        || format!("flag[{}]", i),
+                        &mut region,
+                        self.flag,
+                        i,
+                    )?;
+                    value_cell.copy_advice(
+                        || format!("value[{}]", i),
+                        &mut region,
+                        self.value,
+                        i,
+                    )?;
+
+                    count = count + flag.value().copied();
+                    count_cell = Some(region.assign_advice(
+                        || format!("running[{}]", i),
+                        self.running,
+                        i,
+                        || count,
+                    )?);
+                }
+
+                let mut position_cell = None;
+                let selected_values = values.iter().zip(selected).filter(|(_, s)| **s);
+                for (j, (value_cell, _)) in selected_values.enumerate() {
+                    self.s_output.enable(&mut region, j)?;
+                    outputs.push(region.assign_advice(
+                        || format!("output[{}]", j),
+                        self.output,
+                        j,
+                        || value_cell.value().copied(),
+                    )?);
+                    position_cell = Some(region.assign_fixed(
+                        || format!("position[{}]", j),
+                        self.position,
+                        j,
+                        || Value::known(Field::from(j as u64 + 1)),
+                    )?);
+                }
+
+                // With no outputs, any selected row fails the lookup
+                let count_cell = count_cell.expect("values are non-empty");
+                if let Some(position_cell) = position_cell {
+                    region.constrain_equal(count_cell.cell(), position_cell.cell())?;
+                }
+
+                Ok(outputs)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fields, run_mock};
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        plonk::Circuit,
+    };
+
+    /// Test circuit comparing a column with a constant and compacting it
+    #[derive(Default)]
+    struct TestCircuit {
+        values: Vec<Field>,
+        comparison: Option<(Comparison, Field, bool)>,
+        /// Overrides the honest flags, to forge a selection
+        selected: Option<Vec<bool>>,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = SelectionConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let range_advice = (0..9).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let advice = (0..10).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let range_check = BitwiseRangeCheckConfig::configure(meta, &range_advice, &[]);
+            SelectionConfig::configure(meta, range_check, &advice)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            config.range_check.load_lookup_table(&mut layouter)?;
+            let cells = config.assign_column(&mut layouter, &self.values)?;
+            let (comparison, constant, negated) = self.comparison.ok_or(ErrorFront::Synthesis)?;
+            let flags = config.assign_compare(
+                &mut layouter,
+                &cells,
+                RhsCells::Constant(constant),
+                comparison,
+                negated,
+            )?;
+
+            let predicate = Predicate::Compare {
+                column: 0,
+                comparison,
+                rhs: Operand::Constant(constant),
+                negated,
+            };
+            let selected = self.selected.clone().unwrap_or_else(|| {
+                predicate.flags(std::slice::from_ref(&self.values), self.values.len())
+            });
+            config.assign_compact(&mut layouter, &flags, &cells, &selected, 1)?;
+            Ok(())
+        }
+    }
+
+    /// `l_quantity <comparison> 10` over a small column with a NULL
+    fn circuit(comparison: Comparison, negated: bool) -> TestCircuit {
+        let mut values = fields(&[5, 12, 10, 30, 11, 2]);
+        values.push(SqlValue::null_field());
+        TestCircuit {
+            values,
+            comparison: Some((comparison, Field::from(10), negated)),
+            selected: None,
+        }
+    }
+
+    #[test]
+    fn test_selection_predicate_flags() {
+        let columns = vec![circuit(Comparison::Greater, false).values];
+        let flags = |comparison, negated| {
+            Predicate::Compare {
+                column: 0,
+                comparison,
+                rhs: Operand::Constant(Field::from(10)),
+                negated,
+            }
+            .flags(&columns, 7)
+        };
+
+        // NULL satisfies neither a comparison nor its negation
+        let (t, f) = (true, false);
+        assert_eq!(flags(Comparison::Greater, false), [f, t, f, t, t, f, f]);
+        assert_eq!(flags(Comparison::Greater, true), [t, f, t, f, f, t, f]);
+        assert_eq!(flags(Comparison::Less, false), [t, f, f, f, f, t, f]);
+        assert_eq!(flags(Comparison::Equal, false), [f, f, t, f, f, f, f]);
+        assert_eq!(flags(Comparison::Equal, true), [t, t, f, t, t, t, f]);
+        assert_eq!(flags(Comparison::NotNull, false), [t, t, t, t, t, t, f]);
+    }
+
+    #[test]
+    fn test_selection_faithful_subset() {
+        for comparison in [
+            Comparison::Greater,
+            Comparison::Less,
+            Comparison::Equal,
+            Comparison::NotNull,
+        ] {
+            for negated in [false, true] {
+                assert_eq!(run_mock(&circuit(comparison, negated), 9), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_selection_omitted_row_rejected() {
+        // 30 satisfies `> 10` but is left out of the output
+        let mut circuit = circuit(Comparison::Greater, false);
+        circuit.selected = Some(vec![false, true, false, false, true, false, false]);
+        assert!(run_mock(&circuit, 9).is_err());
+
+        // 10 satisfies `= 10` but is left out of the output
+        let mut circuit = self::circuit(Comparison::Equal, false);
+        circuit.selected = Some(vec![false; 7]);
+        assert!(run_mock(&circuit, 9).is_err());
+    }
+
+    #[test]
+    fn test_selection_wrong_output_rejected() {
+        // A row failing `< 10` selected, and the NULL row selected by `<= 10`
+        let mut circuit = circuit(Comparison::Less, false);
+        circuit.selected = Some(vec![true, false, true, false, false, true, false]);
+        assert!(run_mock(&circuit, 9).is_err());
+
+        let mut circuit = self::circuit(Comparison::Greater, true);
+        circuit.selected = Some(vec![true, false, true, false, false, true, true]);
+        assert!(run_mock(&circuit, 9).is_err());
+    }
+}
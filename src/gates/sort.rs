@@ -6,16 +6,18 @@
 //!
 //! # Method
 //!
-//! 1. Permutation Check: Zi+1 = Zi · (Ri + α) / (Di + α)
-//!    - Z0 = 1
-//!    - Zlen(D) = 1
+//! 1. Permutation Check: R is a permutation of D, proven by the shared
+//!    [`PermutationArgument`]
 //!
-//! 2. Sortedness Check: Ri+1 - Ri ≥ 0 for all i ∈ [0, len(R)-2]
+//! 2. Sortedness Check: Ri ≤ Ri+1 for all i ∈ [0, len(R)-2], proven with the
+//!    less-than gate over 64-bit values
+//!
+//! The compared values must be below 2^64 for the sortedness check to hold.
 //!
 //! # Constraints
 //!
-//! - Permutation constraint: 1 per element (recursive accumulator)
-//! - Sortedness constraint: 1 per adjacent pair
+//! - Permutation constraint: 1 per element (shuffle)
+//! - Sortedness: one 64-bit less-or-equal comparison per adjacent pair
 //!
 //! # Example
 //!
@@ -25,35 +27,34 @@
 //! use halo2_proofs::halo2curves::bn256::Fr as Field;
 //!
 //! let mut meta = ConstraintSystem::<Field>::default();
-//! let advice = vec![meta.advice_column(); 4];
+//! let advice = vec![meta.advice_column(); 3];
 //!
 //! let config = SortConfig::configure(&mut meta, &advice);
 //! ```
 
-use ff::Field as _;
+use super::less_than::LessThanConfig;
+use super::permutation::PermutationArgument;
+use super::ValidationMode;
+use crate::types::keys::{self, KeyError};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
 use halo2_proofs::{
-    circuit::{Layouter, Value},
+    circuit::{AssignedCell, Layouter},
     plonk::{Advice, Column, ConstraintSystem, ErrorFront},
-    poly::Rotation,
 };
 
+/// Bit-width of values the sortedness check supports
+const SORT_VALUE_BITS: usize = 64;
+
 /// Configuration for sort gate
 ///
 /// This gate verifies that output R is a sorted permutation of input D.
 #[derive(Debug, Clone)]
 pub struct SortConfig {
-    /// Column for input values D
-    pub input_col: Column<Advice>,
-
-    /// Column for sorted output values R
-    pub output_col: Column<Advice>,
-
-    /// Column for permutation accumulator Z
-    pub z_col: Column<Advice>,
+    /// Permutation argument between input D and sorted output R
+    pub permutation: PermutationArgument,
 
-    /// Column for random challenge α (blinding factor)
-    pub alpha_col: Column<Advice>,
+    /// Less-or-equal comparisons between adjacent outputs
+    pub less_than: LessThanConfig,
 }
 
 impl SortConfig {
@@ -61,7 +62,8 @@ impl SortConfig {
     ///
     /// # Arguments
     /// * `meta` - Constraint system metadata
-    /// * `advice` - Slice of advice columns (needs at least 4: input, output, z, alpha)
+    /// * `advice` - Slice of advice columns (needs at least 3: input, output,
+    ///   and the less-than difference)
     ///
     /// # Returns
     /// `SortConfig` with configured columns
@@ -69,179 +71,105 @@ impl SortConfig {
     /// # Panics
     /// Panics if not enough columns are provided
     pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
-        // Validate input
         assert!(
-            advice.len() >= 4,
-            "Need at least 4 advice columns (input, output, z, alpha)"
+            advice.len() >= 3,
+            "Need at least 3 advice columns (input, output, difference)"
         );
 
-        // Assign columns
-        let input_col = advice[0];
-        let output_col = advice[1];
-        let z_col = advice[2];
-        let alpha_col = advice[3];
-
-        // Enable equality on all advice columns
-        meta.enable_equality(input_col);
-        meta.enable_equality(output_col);
-        meta.enable_equality(z_col);
-        meta.enable_equality(alpha_col);
-
-        // Constraint 1: Permutation check (recursive form)
-        // Zi+1 = Zi · (Ri + α) / (Di + α)
-        // Rearranged: Zi+1 · (Di + α) = Zi · (Ri + α)
-        meta.create_gate("sort_permutation", |meta| {
-            let z_cur = meta.query_advice(z_col, Rotation::cur());
-            let z_next = meta.query_advice(z_col, Rotation::next());
-            let d_cur = meta.query_advice(input_col, Rotation::cur());
-            let r_cur = meta.query_advice(output_col, Rotation::cur());
-            let alpha_cur = meta.query_advice(alpha_col, Rotation::cur());
-
-            // Zi+1 · (Di + α) - Zi · (Ri + α) = 0
-            let left = z_next.clone() * (d_cur.clone() + alpha_cur.clone());
-            let right = z_cur.clone() * (r_cur.clone() + alpha_cur);
-            vec![left - right]
-        });
+        // Constraint 1: R is a permutation of D
+        let permutation = PermutationArgument::configure(meta, &advice[..2]);
 
         // Constraint 2: Sortedness check (ascending order)
-        // Ri+1 - Ri ≥ 0 for all i ∈ [0, len(R)-2]
-        // This is enforced by ensuring Ri+1 - Ri is non-negative
-        // (In practice, we use a range check or direct constraint)
-        meta.create_gate("sort_order", |meta| {
-            let r_cur = meta.query_advice(output_col, Rotation::cur());
-            let r_next = meta.query_advice(output_col, Rotation::next());
-
-            // For sortedness: r_next - r_cur >= 0
-            // We can use a selector to enable this only for non-last rows
-            // For now, we'll use a simple constraint (can be refined with selector)
-            // Note: This assumes values are non-negative
-            // In production, use range check gate for proper validation
-            vec![r_next - r_cur]
-        });
+        // Ri ≤ Ri+1, copied into the less-than gate
+        let less_than = LessThanConfig::configure(meta, &advice[..3], SORT_VALUE_BITS);
 
         Self {
-            input_col,
-            output_col,
-            z_col,
-            alpha_col,
+            permutation,
+            less_than,
         }
     }
 
+    /// Number of constraints for sorting `input_len` values
+    ///
+    /// The permutation argument's constraints plus one less-or-equal
+    /// comparison per adjacent pair.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of sorted values
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        let pairs = input_len.saturating_sub(1);
+        self.permutation.num_constraints(input_len) + self.less_than.num_constraints(pairs)
+    }
+
     /// Assign values for sort gate
     ///
     /// This method:
-    /// 1. Assigns input values D to input column
-    /// 2. Assigns sorted output values R to output column
-    /// 3. Computes and assigns permutation accumulator Z
-    /// 4. Assigns random challenge α
+    /// 1. Assigns input values D and sorted output values R under `tag`
+    /// 2. Proves each adjacent output pair is in ascending order
+    ///
+    /// The witness is always assigned; if R is not a sorted permutation of D
+    /// the constraints are unsatisfied.
     ///
     /// # Arguments
     /// * `layouter` - Layouter for assigning values
     /// * `input_values` - The input values D (unsorted)
     /// * `sorted_values` - The sorted output values R (must be sorted version of D)
-    /// * `alpha` - Random challenge α (blinding factor)
+    /// * `tag` - Permutation tag, distinct for each sort in the circuit
+    /// * `mode` - Validation mode of the permutation argument
     ///
     /// # Returns
-    /// `Ok(())` if assignment succeeds, `Err(Error)` otherwise
-    ///
-    /// # Panics
-    /// Panics if input_values and sorted_values are not the same length
+    /// `Ok((input_cells, output_cells))` if assignment succeeds,
+    /// `Err(Error::Synthesis)` if the lengths differ or the tag is zero,
+    /// `Err(Error::Other)` in `Strict` mode if R is not a permutation of D
     pub fn assign(
         &self,
         layouter: &mut impl Layouter<Field>,
         input_values: &[Field],
         sorted_values: &[Field],
-        alpha: Field,
-    ) -> Result<(), ErrorFront> {
+        tag: u64,
+        mode: ValidationMode,
+    ) -> Result<
+        (
+            Vec<AssignedCell<Field, Field>>,
+            Vec<AssignedCell<Field, Field>>,
+        ),
+        ErrorFront,
+    > {
         // Validate inputs
-        assert_eq!(
-            input_values.len(),
-            sorted_values.len(),
-            "Input and sorted values must have the same length"
-        );
+        if input_values.len() != sorted_values.len() {
+            return Err(ErrorFront::Synthesis);
+        }
 
         let n = input_values.len();
         if n == 0 {
-            return Ok(()); // Empty input, nothing to do
-        }
-
-        // Verify sorted_values is actually sorted (ascending order)
-        // Note: Field comparison may not work directly, so we skip this check
-        // In production, this should be verified externally or via constraints
-        // for i in 0..n - 1 {
-        //     assert!(
-        //         sorted_values[i] <= sorted_values[i + 1],
-        //         "Sorted values must be in ascending order"
-        //     );
-        // }
-
-        // Compute permutation accumulator Z
-        // Z0 = 1
-        // Zi+1 = Zi · (Ri + α) / (Di + α)
-        let mut z_values = Vec::with_capacity(n + 1);
-        z_values.push(Field::one()); // Z0 = 1
-
-        for i in 0..n {
-            let numerator = sorted_values[i] + alpha;
-            let denominator = input_values[i] + alpha;
-            let zi = z_values[i];
-            let zi_next = zi * numerator * denominator.invert().unwrap();
-            z_values.push(zi_next);
+            return Ok((vec![], vec![])); // Empty input, nothing to do
         }
 
-        // Verify final Z value is 1 (permutation integrity)
-        assert!(
-            z_values[n] == Field::one(),
-            "Final Z value must be 1 (permutation integrity check)"
-        );
-
-        // Assign all values in a region
-        layouter.assign_region(
+        let (input_cells, output_cells) = layouter.assign_region(
             || "sort gate",
             |mut region| {
-                // Assign input values D
-                for (i, &value) in input_values.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("input[{}]", i),
-                        self.input_col,
-                        i,
-                        || Value::known(value),
-                    )?;
-                }
-
-                // Assign sorted output values R
-                for (i, &value) in sorted_values.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("output[{}]", i),
-                        self.output_col,
-                        i,
-                        || Value::known(value),
-                    )?;
-                }
-
-                // Assign permutation accumulator Z
-                for (i, &value) in z_values.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("z[{}]", i),
-                        self.z_col,
-                        i,
-                        || Value::known(value),
-                    )?;
-                }
-
-                // Assign random challenge α (same value for all rows)
-                for i in 0..n {
-                    region.assign_advice(
-                        || format!("alpha[{}]", i),
-                        self.alpha_col,
-                        i,
-                        || Value::known(alpha),
-                    )?;
-                }
-
-                Ok(())
+                self.permutation
+                    .assign(&mut region, input_values, sorted_values, tag, mode)
             },
-        )
+        )?;
+
+        // Ri ≤ Ri+1, bound to the assigned outputs
+        for i in 0..n - 1 {
+            let (a, b) = self.less_than.assign_less_equal(
+                layouter,
+                sorted_values[i],
+                sorted_values[i + 1],
+            )?;
+            layouter.assign_region(
+                || "sort order",
+                |mut region| {
+                    region.constrain_equal(a.cell(), output_cells[i].cell())?;
+                    region.constrain_equal(b.cell(), output_cells[i + 1].cell())
+                },
+            )?;
+        }
+
+        Ok((input_cells, output_cells))
     }
 
     /// Create composite value for multi-attribute sort
@@ -258,20 +186,10 @@ impl SortConfig {
     /// * `attributes` - Slice of attribute values (each as u64)
     ///
     /// # Returns
-    /// Composite field value for sorting
-    pub fn create_composite_value(attributes: &[u64]) -> Field {
-        let mut composite = Field::zero();
-        let k = attributes.len();
-
-        for (i, &attr) in attributes.iter().enumerate() {
-            let shift = 64 * (k - 1 - i) as u32;
-            let mut exp = [0u64; 4];
-            exp[0] = shift as u64;
-            let multiplier = Field::from(2u64).pow_vartime(exp);
-            composite = composite + Field::from(attr) * multiplier;
-        }
-
-        composite
+    /// `Ok(Field)` composite value for sorting, `Err(KeyError::TooWide)` for
+    /// more than 3 attributes, which would wrap around the field modulus
+    pub fn create_composite_value(attributes: &[u64]) -> Result<Field, KeyError> {
+        keys::pack(attributes, &vec![64; attributes.len()])
     }
 
     /// Extract attribute from composite value
@@ -286,64 +204,34 @@ impl SortConfig {
     /// # Returns
     /// Extracted attribute value (as u64)
     pub fn extract_attribute(composite: Field, index: usize, total_attrs: usize) -> u64 {
-        // Extract the attribute by shifting and masking
-        // This is a simplified extraction (may need refinement for production)
-        let shift = 64 * (total_attrs - 1 - index) as u32;
-        let mask = u64::MAX;
+        // Each attribute occupies its own 64-bit limb of the full representation
+        let limb = total_attrs - 1 - index;
+        if limb >= 4 {
+            return 0;
+        }
 
-        // Convert to u64 and extract
         let bytes = composite.to_bytes();
-        let value = u64::from_le_bytes([
-            bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
-        ]);
-
-        if shift >= 64 {
-            0
-        } else {
-            (value >> shift) & mask
-        }
+        let start = limb * 8;
+        let mut limb_bytes = [0u8; 8];
+        limb_bytes.copy_from_slice(&bytes[start..start + 8]);
+        u64::from_le_bytes(limb_bytes)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{fields, run_mock, sorted_fields};
     use halo2_proofs::{
-        circuit::{Layouter, SimpleFloorPlanner, Value},
-        dev::MockProver,
+        circuit::{Layouter, SimpleFloorPlanner},
         plonk::Circuit,
     };
-    use rand_core::OsRng;
 
     #[test]
     fn test_sort_permutation_integrity() {
-        // Test that permutation accumulator Z works correctly
-        let input = vec![
-            Field::from(5u64),
-            Field::from(2u64),
-            Field::from(8u64),
-            Field::from(1u64),
-            Field::from(9u64),
-        ];
-        let mut sorted = input.clone();
-        sorted.sort(); // Sort in ascending order
-
-        let alpha = Field::random(&mut OsRng);
-
-        // Compute Z values
-        let mut z = Field::one();
-        for i in 0..input.len() {
-            let numerator = sorted[i] + alpha;
-            let denominator = input[i] + alpha;
-            z = z * numerator * denominator.invert().unwrap();
-        }
-
-        // Z_final should be 1 (permutation integrity)
-        assert_eq!(
-            z,
-            Field::one(),
-            "Final Z value should be 1 for valid permutation"
-        );
+        // The sorted values are a permutation of the input
+        let (input, sorted) = sorted_fields(&[5, 2, 8, 1, 9]);
+        assert!(PermutationArgument::is_permutation(&input, &sorted));
     }
 
     #[test]
@@ -367,35 +255,45 @@ mod tests {
     fn test_composite_value() {
         // Test composite value creation
         let attrs = vec![1u64, 2u64, 3u64];
-        let composite = SortConfig::create_composite_value(&attrs);
+        let composite = SortConfig::create_composite_value(&attrs).unwrap();
 
         // Extract attributes
         let attr0 = SortConfig::extract_attribute(composite, 0, 3);
         let attr1 = SortConfig::extract_attribute(composite, 1, 3);
         let attr2 = SortConfig::extract_attribute(composite, 2, 3);
 
-        // Verify extraction (may need refinement for exact matching)
+        // Verify extraction
+        assert_eq!(attr0, 1, "First attribute should be 1");
+        assert_eq!(attr1, 2, "Second attribute should be 2");
         assert_eq!(attr2, 3, "Last attribute should be 3");
+
+        // Four 64-bit attributes don't fit in the field
+        assert_eq!(
+            SortConfig::create_composite_value(&[1, 2, 3, 4]),
+            Err(KeyError::TooWide { bits: 256 })
+        );
     }
 
     /// Test circuit for sort gate
-    #[derive(Default)]
+    #[derive(Clone, Default)]
     struct TestCircuit {
         input_values: Vec<Field>,
         sorted_values: Vec<Field>,
-        alpha: Field,
     }
 
     impl Circuit<Field> for TestCircuit {
         type Config = SortConfig;
         type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
 
         fn without_witnesses(&self) -> Self {
             Self::default()
         }
 
         fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
-            let advice = (0..4).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let advice = (0..3).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            // Unused instance column, matching the single column the prover supplies
+            meta.instance_column();
             SortConfig::configure(meta, &advice)
         }
 
@@ -410,7 +308,8 @@ mod tests {
                         &mut layouter,
                         &self.input_values,
                         &self.sorted_values,
-                        self.alpha,
+                        1,
+                        ValidationMode::Fast,
                     )
                     .map_err(|_| ErrorFront::Other(String::from("Unknown error")))?;
             }
@@ -429,24 +328,14 @@ mod tests {
         ];
 
         for input_u64 in test_cases {
-            let input: Vec<Field> = input_u64.iter().map(|&v| Field::from(v)).collect();
-            // Sort by converting to u64, sorting, then converting back
-            let mut sorted_u64 = input_u64.clone();
-            sorted_u64.sort();
-            let sorted: Vec<Field> = sorted_u64.iter().map(|&v| Field::from(v)).collect();
-
-            let alpha = Field::random(&mut OsRng);
-
+            let (input, sorted) = sorted_fields(&input_u64);
             let circuit = TestCircuit {
                 input_values: input,
                 sorted_values: sorted,
-                alpha,
             };
 
-            let k = 10; // 2^10 = 1024 rows
-            let prover = MockProver::run(k, &circuit, vec![]).unwrap();
             assert_eq!(
-                prover.verify(),
+                run_mock(&circuit, 10),
                 Ok(()),
                 "Circuit verification failed for input: {:?}",
                 input_u64
@@ -460,11 +349,57 @@ mod tests {
         let circuit = TestCircuit {
             input_values: vec![],
             sorted_values: vec![],
-            alpha: Field::zero(),
         };
 
-        let k = 10;
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-        assert_eq!(prover.verify(), Ok(()), "Empty circuit should verify");
+        assert_eq!(
+            run_mock(&circuit, 10),
+            Ok(()),
+            "Empty circuit should verify"
+        );
+    }
+
+    #[test]
+    fn test_sort_circuit_rejects_invalid_output() {
+        let input = fields(&[5, 2, 8]);
+
+        // Sorted, but not a permutation of the input
+        let circuit = TestCircuit {
+            input_values: input.clone(),
+            sorted_values: fields(&[2, 5, 9]),
+        };
+        assert!(run_mock(&circuit, 10).is_err());
+
+        // A permutation of the input, but not sorted
+        let circuit = TestCircuit {
+            input_values: input,
+            sorted_values: fields(&[5, 2, 8]),
+        };
+        assert!(run_mock(&circuit, 10).is_err());
+    }
+
+    #[test]
+    fn test_sort_proof_rejects_non_permutation() {
+        use crate::commitment::IPAParams;
+        use crate::proof::{Prover, Verifier};
+
+        let params = IPAParams::new(10);
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+
+        let honest = TestCircuit {
+            input_values: fields(&[30, 10, 20]),
+            sorted_values: fields(&[10, 20, 30]),
+        };
+        let (pk, vk) = prover.generate_keys(&honest).unwrap();
+        let proof = prover.create_proof(&pk, &honest, &[]).unwrap();
+        assert!(verifier.verify(&vk, &proof, &[]).unwrap());
+
+        // The claimed output drops 30 and duplicates 20
+        let forged = TestCircuit {
+            sorted_values: fields(&[10, 20, 20]),
+            ..honest
+        };
+        let proof = prover.create_proof(&pk, &forged, &[]).unwrap();
+        assert!(!verifier.verify(&vk, &proof, &[]).unwrap());
     }
 }
@@ -0,0 +1,280 @@
+//! Weighted sum gate for sum-of-products aggregation
+//!
+//! This module provides a gate that proves `Σ a_i · b_i` over per-row factors,
+//! as needed by revenue-style aggregates such as
+//! `SUM(l_extendedprice * (1 - l_discount))`.
+//!
+//! # Method
+//!
+//! A running accumulator is kept alongside the factors:
+//! - S_0 = a_0 · b_0
+//! - S_i = S_{i-1} + a_i · b_i
+//!
+//! S_{n-1} (the last row) is the result.
+//!
+//! # Constraints
+//!
+//! - First-row constraint: 1 per sum
+//! - Accumulation constraint: 1 per remaining row
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::gates::weighted_sum::WeightedSumConfig;
+//! use halo2_proofs::plonk::ConstraintSystem;
+//! use halo2_proofs::halo2curves::bn256::Fr as Field;
+//!
+//! let mut meta = ConstraintSystem::<Field>::default();
+//! let advice = vec![meta.advice_column(); 3];
+//!
+//! let config = WeightedSumConfig::configure(&mut meta, &advice);
+//! ```
+
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, ErrorFront, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for weighted sum gate
+///
+/// This gate verifies a running sum of per-row products `a_i · b_i`.
+#[derive(Debug, Clone)]
+pub struct WeightedSumConfig {
+    /// Column for the first factor a_i
+    pub a: Column<Advice>,
+
+    /// Column for the second factor b_i
+    pub b: Column<Advice>,
+
+    /// Column for the accumulator S_i
+    pub acc: Column<Advice>,
+
+    /// Selector for the first row (S_0 = a_0 · b_0)
+    pub s_first: Selector,
+
+    /// Selector for the remaining rows (S_i = S_{i-1} + a_i · b_i)
+    pub s_step: Selector,
+}
+
+impl WeightedSumConfig {
+    /// Configure the weighted sum gate
+    ///
+    /// # Arguments
+    /// * `meta` - Constraint system metadata
+    /// * `advice` - Slice of advice columns (needs at least 3: a, b, acc)
+    ///
+    /// # Returns
+    /// `WeightedSumConfig` with configured columns
+    ///
+    /// # Panics
+    /// Panics if not enough columns are provided
+    pub fn configure(meta: &mut ConstraintSystem<Field>, advice: &[Column<Advice>]) -> Self {
+        // Validate input
+        assert!(
+            advice.len() >= 3,
+            "Need at least 3 advice columns (a, b, acc)"
+        );
+
+        // Assign columns
+        let a = advice[0];
+        let b = advice[1];
+        let acc = advice[2];
+
+        // Enable equality so the result can be copied into other gates or instances
+        meta.enable_equality(acc);
+
+        let s_first = meta.selector();
+        let s_step = meta.selector();
+
+        // Constraint 1: accumulator starts at the first product
+        // S_0 = a_0 · b_0
+        meta.create_gate("weighted_sum_first", |meta| {
+            let q = meta.query_selector(s_first);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+
+            vec![q * (acc - a * b)]
+        });
+
+        // Constraint 2: each row adds its product
+        // S_i = S_{i-1} + a_i · b_i
+        meta.create_gate("weighted_sum_step", |meta| {
+            let q = meta.query_selector(s_step);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+
+            vec![q * (acc_cur - acc_prev - a * b)]
+        });
+
+        Self {
+            a,
+            b,
+            acc,
+            s_first,
+            s_step,
+        }
+    }
+
+    /// Number of constraints for summing `input_len` products
+    ///
+    /// The first row has 1 constraint and every other row 1 accumulation
+    /// constraint; an empty input is assigned as one `0 · 0` row.
+    ///
+    /// # Arguments
+    /// * `input_len` - Number of factor pairs
+    pub fn num_constraints(&self, input_len: usize) -> usize {
+        input_len.max(1)
+    }
+
+    /// Assign the factors and running sum
+    ///
+    /// An empty input is assigned as a single `0 · 0` row, so the result is 0.
+    ///
+    /// # Arguments
+    /// * `layouter` - Layouter for assigning values
+    /// * `a` - First factor per row
+    /// * `b` - Second factor per row
+    ///
+    /// # Returns
+    /// The assigned cell holding `Σ a_i · b_i`, `Err(Error::Synthesis)` if
+    /// the factor lengths differ
+    pub fn assign(
+        &self,
+        layouter: &mut impl Layouter<Field>,
+        a: &[Field],
+        b: &[Field],
+    ) -> Result<AssignedCell<Field, Field>, ErrorFront> {
+        if a.len() != b.len() {
+            return Err(ErrorFront::Synthesis);
+        }
+
+        let rows: Vec<(Field, Field)> = if a.is_empty() {
+            vec![(Field::zero(), Field::zero())]
+        } else {
+            a.iter().copied().zip(b.iter().copied()).collect()
+        };
+
+        layouter.assign_region(
+            || "weighted sum",
+            |mut region| {
+                let mut sum = Field::zero();
+                let mut result = None;
+
+                for (i, &(a_i, b_i)) in rows.iter().enumerate() {
+                    if i == 0 {
+                        self.s_first.enable(&mut region, i)?;
+                    } else {
+                        self.s_step.enable(&mut region, i)?;
+                    }
+
+                    region.assign_advice(
+                        || format!("a[{}]", i),
+                        self.a,
+                        i,
+                        || Value::known(a_i),
+                    )?;
+                    region.assign_advice(
+                        || format!("b[{}]", i),
+                        self.b,
+                        i,
+                        || Value::known(b_i),
+                    )?;
+
+                    sum += a_i * b_i;
+                    result = Some(region.assign_advice(
+                        || format!("acc[{}]", i),
+                        self.acc,
+                        i,
+                        || Value::known(sum),
+                    )?);
+                }
+
+                result.ok_or(ErrorFront::Synthesis)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        plonk::{Circuit, Instance},
+    };
+
+    /// Test circuit for weighted sum gate, exposing the result publicly
+    #[derive(Default)]
+    struct TestCircuit {
+        a: Vec<Field>,
+        b: Vec<Field>,
+    }
+
+    impl Circuit<Field> for TestCircuit {
+        type Config = (WeightedSumConfig, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = (0..3).map(|_| meta.advice_column()).collect::<Vec<_>>();
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+
+            (WeightedSumConfig::configure(meta, &advice), instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let result = config.assign(&mut layouter, &self.a, &self.b)?;
+            layouter.constrain_instance(result.cell(), instance, 0)
+        }
+    }
+
+    fn run(pairs: &[(u64, u64)], total: u64) -> bool {
+        let circuit = TestCircuit {
+            a: pairs.iter().map(|&(a, _)| Field::from(a)).collect(),
+            b: pairs.iter().map(|&(_, b)| Field::from(b)).collect(),
+        };
+        let k = 4;
+        MockProver::run(k, &circuit, vec![vec![Field::from(total)]])
+            .unwrap()
+            .verify()
+            .is_ok()
+    }
+
+    #[test]
+    fn test_weighted_sum() {
+        assert!(run(&[(2, 3), (4, 5)], 26), "2·3 + 4·5 should be 26");
+        assert!(run(&[(7, 6)], 42));
+        assert!(run(&[], 0));
+    }
+
+    #[test]
+    fn test_weighted_sum_rejects_wrong_total() {
+        assert!(!run(&[(2, 3), (4, 5)], 27));
+        assert!(!run(&[(2, 3), (4, 5)], 14));
+    }
+
+    #[test]
+    fn test_weighted_sum_length_mismatch() {
+        let circuit = TestCircuit {
+            a: vec![Field::from(1u64)],
+            b: vec![],
+        };
+        assert!(MockProver::run(4, &circuit, vec![vec![Field::zero()]]).is_err());
+    }
+}
@@ -41,5 +41,8 @@ pub mod proof;
 pub mod query;
 pub mod utils;
 
+#[cfg(test)]
+mod test_util;
+
 #[cfg(feature = "api")]
 pub mod api;
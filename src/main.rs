@@ -3,6 +3,17 @@
 //! Command-line interface for NzengiDB zero-knowledge database system.
 
 use clap::{Parser, Subcommand};
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use nzengi_db::circuit::NzengiCircuit;
+use nzengi_db::commitment::{DatabaseCommitment, IPAParams};
+use nzengi_db::database::DatabaseStorage;
+use nzengi_db::gates::ValidationMode;
+use nzengi_db::proof::Verifier;
+use nzengi_db::query::{QueryExecutor, QueryParser, QueryPlanner};
+use nzengi_db::types::Proof;
+use nzengi_db::utils::{benchmark_commitment, benchmark_ipa, Helpers};
+use std::time::Instant;
 
 #[derive(Parser)]
 #[command(name = "nzengi_db")]
@@ -23,6 +34,14 @@ enum Commands {
         /// Output file path
         #[arg(short, long)]
         output: String,
+
+        /// SQL query to recommend k for (with --database)
+        #[arg(short, long, requires = "database")]
+        query: Option<String>,
+
+        /// Database file path, to size the query's tables
+        #[arg(short, long)]
+        database: Option<String>,
     },
 
     /// Commit to database
@@ -57,21 +76,35 @@ enum Commands {
         /// Commitment file path
         #[arg(short, long)]
         commitment: String,
+
+        /// Re-check the circuit's derived witnesses while proving, failing
+        /// with a descriptive error if one is inconsistent (for debugging)
+        #[arg(long)]
+        strict: bool,
     },
 
     /// Verify proof
     Verify {
-        /// Proof file path
+        /// Proof file path (JSON)
         #[arg(short, long)]
         proof: String,
 
         /// Parameters file path
-        #[arg(short, long)]
+        #[arg(long)]
         params: String,
 
-        /// Commitment file path
+        /// Commitment file path (binary)
         #[arg(short, long)]
         commitment: String,
+
+        /// Verifying key file path
+        #[arg(short, long)]
+        vk: String,
+
+        /// Expected public inputs (comma-separated), e.g. the query result;
+        /// trailing zero inputs may be omitted
+        #[arg(long, value_delimiter = ',', required = true)]
+        public_inputs: Vec<u64>,
     },
 
     /// Run benchmarks
@@ -83,6 +116,14 @@ enum Commands {
         /// Queries to run (comma-separated)
         #[arg(short, long)]
         queries: Option<String>,
+
+        /// k values for the IPA benchmark (comma-separated)
+        #[arg(short, long, value_delimiter = ',', default_value = "10,12")]
+        k: Vec<u32>,
+
+        /// Row counts for the database commitment benchmark (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        commit_rows: Vec<usize>,
     },
 }
 
@@ -90,7 +131,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Setup { k, output } => {
+        Commands::Setup {
+            k,
+            output,
+            query,
+            database,
+        } => {
+            if let (Some(query), Some(database)) = (query, database) {
+                let database = DatabaseStorage::new().load(&database)?;
+                let table_sizes = database
+                    .tables_ref()
+                    .iter()
+                    .map(|(name, table)| (name.clone(), table.rows.len()))
+                    .collect();
+                let plan = QueryPlanner::new().plan(&QueryParser::new().parse(&query)?)?;
+                let recommended = Helpers::recommend_k(&plan, &table_sizes);
+                println!("💡 Recommended k for this query: {}", recommended);
+                if k < recommended {
+                    println!("⚠️  k={} is likely too small for this query", k);
+                }
+            }
+
             println!("🚀 Generating public parameters with k={}...", k);
             println!("📁 Output file: {}", output);
             println!("⏳ This may take a few minutes...");
@@ -116,38 +177,147 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             database,
             params,
             commitment,
+            strict,
         } => {
             println!("🔍 Executing query: {}", query);
             println!("📂 Database: {}", database);
             println!("📂 Parameters: {}", params);
             println!("📂 Commitment: {}", commitment);
-            // TODO: Implement query
-            println!("⚠️  Query execution not yet implemented");
-            println!("✅ Query execution with proof generation will be available in future implementation");
+
+            let database = DatabaseStorage::new().load(&database)?;
+            let params = IPAParams::load(&params)?;
+            let plan = QueryPlanner::new().plan(&QueryParser::new().parse(&query)?)?;
+            let validation = if strict {
+                println!("🐞 Strict witness validation enabled");
+                ValidationMode::Strict
+            } else {
+                ValidationMode::Fast
+            };
+            let executor = QueryExecutor::new(&params).with_validation(validation);
+            let (result, proof) = executor.execute(&plan, database.tables_ref())?;
+
+            println!("{}", result.columns.join(" | "));
+            for row in &result.rows {
+                let values: Vec<String> = row.values.iter().map(ToString::to_string).collect();
+                println!("{}", values.join(" | "));
+            }
+            println!(
+                "📏 Proof size: {} bytes ({})",
+                proof.size(),
+                Helpers::format_bytes(proof.size() as u64)
+            );
+            // TODO: Bind the proof to the commitment (needs its blinds)
+            println!("⚠️  Proof is not yet bound to the commitment");
         }
         Commands::Verify {
             proof,
             params,
             commitment,
+            vk,
+            public_inputs,
         } => {
             println!("✅ Verifying proof...");
             println!("📂 Proof: {}", proof);
             println!("📂 Parameters: {}", params);
             println!("📂 Commitment: {}", commitment);
-            // TODO: Implement verify
-            println!("⚠️  Verification not yet implemented");
-            println!("✅ Proof verification will be available in future implementation");
+            println!("📂 Verifying key: {}", vk);
+
+            let params = IPAParams::load(&params)?;
+            let commitment = DatabaseCommitment::from_bytes(&std::fs::read(&commitment)?)?;
+            let proof = Proof::from_json(&std::fs::read_to_string(&proof)?)?;
+            let verifier = Verifier::new(&params);
+            let vk = Verifier::read_vk::<NzengiCircuit>(&vk, verifier.params())?;
+
+            println!(
+                "📏 Proof size: {} bytes ({})",
+                proof.size(),
+                Helpers::format_bytes(proof.size() as u64)
+            );
+            println!("🔢 Public inputs: {}", proof.num_public_inputs);
+            println!(
+                "🔐 Commitment hash: {} (version {})",
+                commitment.commitment_hash, commitment.version
+            );
+
+            // The verifier supplies the public inputs it expects; the ones
+            // stored in the proof are the prover's claim and prove nothing.
+            // The last public inputs are the opened cells, checked against
+            // the openings, and the commitment hash.
+            let opened: usize = proof.openings.iter().map(|o| o.values.len()).sum();
+            let num_inputs = proof.num_public_inputs.saturating_sub(opened + 1);
+            if public_inputs.len() > num_inputs {
+                return Err(format!(
+                    "Expected {} public inputs, the proof has {} before the opened cells \
+                     and the commitment hash",
+                    public_inputs.len(),
+                    num_inputs
+                )
+                .into());
+            }
+            let mut expected: Vec<Field> = public_inputs.into_iter().map(Field::from).collect();
+            expected.resize(num_inputs, Field::zero());
+
+            let start = Instant::now();
+            let valid =
+                verifier.verify_with_commitment(&vk, &proof, &expected, &commitment, None)?;
+            println!(
+                "⏱️  Verification time: {}",
+                Helpers::format_duration_from(start.elapsed())
+            );
+
+            if !valid {
+                return Err("Proof verification failed".into());
+            }
+            println!("✅ Proof is valid");
         }
-        Commands::Benchmark { scale, queries } => {
+        Commands::Benchmark {
+            scale,
+            queries,
+            k,
+            commit_rows,
+        } => {
             println!("📊 Running benchmarks with scale factor {}...", scale);
             if let Some(q) = queries {
                 println!("📋 Queries: {}", q);
             } else {
                 println!("📋 Running all TPC-H queries");
             }
-            // TODO: Implement benchmark
-            println!("⚠️  Benchmarks not yet implemented");
-            println!("✅ TPC-H benchmark suite will be available in future implementation");
+
+            println!("⏱️  IPA benchmark for k = {:?}", k);
+            let results = benchmark_ipa(&k)?;
+            for result in &results {
+                println!(
+                    "k={:<3} rows={:<8} commit={:<10} keygen={:<10} prove={:<10} verify={:<10} proof={:<10} verified={}",
+                    result.k,
+                    result.num_rows,
+                    Helpers::format_duration_from(result.commit_time),
+                    Helpers::format_duration_from(result.keygen_time),
+                    Helpers::format_duration_from(result.prove_time),
+                    Helpers::format_duration_from(result.verify_time),
+                    Helpers::format_bytes(result.proof_size as u64),
+                    result.verified,
+                );
+                for (gate, constraints) in &result.constraint_profile {
+                    println!("      {:<12} constraints={}", gate, constraints);
+                }
+            }
+
+            if !commit_rows.is_empty() {
+                // Smallest k that fits the largest table
+                let max_rows = commit_rows.iter().copied().max().unwrap_or(1);
+                let commit_k = max_rows.next_power_of_two().trailing_zeros().max(1);
+                println!("⏱️  Commitment benchmark for rows = {:?}", commit_rows);
+                for (rows, time) in benchmark_commitment(&commit_rows, commit_k)? {
+                    println!(
+                        "rows={:<8} commit={}",
+                        rows,
+                        Helpers::format_duration_from(time)
+                    );
+                }
+            }
+
+            // TODO: Implement TPC-H benchmark
+            println!("⚠️  TPC-H benchmarks not yet implemented");
         }
     }
 
@@ -0,0 +1,55 @@
+//! Proof error type
+//!
+//! This module provides the error type returned when creating proofs, so
+//! callers (and `Prover::create_proof_with_retry`) can tell transient
+//! failures from ones that fail the same way every time.
+
+use std::io;
+use thiserror::Error;
+
+/// Error returned by proof creation
+#[derive(Debug, Error)]
+pub enum ProofError {
+    /// halo2 rejected the circuit, its witness or its public inputs
+    #[error("Failed to create proof: {0}")]
+    Proving(String),
+
+    /// Writing the proof bytes failed
+    #[error("Failed to write proof: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl ProofError {
+    /// Check whether retrying the proof may succeed
+    ///
+    /// Only I/O failures caused by resource pressure or interruption are
+    /// transient; a proving failure repeats with the same inputs.
+    ///
+    /// # Returns
+    /// `true` for out-of-memory, interrupted, would-block and timed-out
+    /// writes, `false` otherwise
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProofError::Io(e) => matches!(
+                e.kind(),
+                io::ErrorKind::OutOfMemory
+                    | io::ErrorKind::Interrupted
+                    | io::ErrorKind::WouldBlock
+                    | io::ErrorKind::TimedOut
+            ),
+            ProofError::Proving(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proof_error_retryable() {
+        assert!(ProofError::Io(io::ErrorKind::OutOfMemory.into()).is_retryable());
+        assert!(!ProofError::Io(io::ErrorKind::PermissionDenied.into()).is_retryable());
+        assert!(!ProofError::Proving("ConstraintSystemFailure".to_string()).is_retryable());
+    }
+}
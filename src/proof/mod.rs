@@ -6,6 +6,7 @@
 //! The proof system consists of:
 //! - `prover`: Proof generation from circuits
 //! - `verifier`: Proof verification
+//! - `error`: Error type for proof creation
 //!
 //! # Overview
 //!
@@ -46,11 +47,13 @@
 //! assert!(verifier.verify(&vk, &proof, &[])?);
 //! ```
 
+pub mod error;
 pub mod prover;
 pub mod recursive;
 pub mod verifier;
 
 // Re-export main types for convenience
-pub use prover::Prover;
+pub use error::ProofError;
+pub use prover::{KeyCircuit, Prover};
 pub use recursive::{ComposedProof, CompositionMetadata, RecursiveProver, RecursiveVerifier};
-pub use verifier::Verifier;
+pub use verifier::{Verifier, VerifyDiagnostics, VerifyStage};
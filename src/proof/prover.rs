@@ -21,43 +21,129 @@
 //!
 //! let (pk, vk) = prover.generate_keys(&circuit)?;
 //! let proof = prover.create_proof(&pk, &circuit, &[])?;
+//!
+//! // Stream a large proof to a file instead of buffering it
+//! let file = std::fs::File::create("proof.bin")?;
+//! prover.create_proof_to_writer(&pk, &circuit, &[], std::io::BufWriter::new(file))?;
+//!
+//! // Retry transient (e.g., allocation) failures up to 3 times
+//! let proof = prover.create_proof_with_retry(&pk, &circuit, &[], 3)?;
+//!
+//! // Save the verifying key for verifiers (see `Verifier::read_vk`)
+//! prover.write_vk(&vk, &circuit, "query.vk")?;
 //! ```
 
-use crate::commitment::IPAParams;
+use crate::commitment::{CommitmentScheme, IPAParams, IPAScheme};
+use crate::proof::ProofError;
 use crate::types::Proof;
 use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine};
 use halo2_proofs::{
-    plonk::{create_proof, keygen_pk, keygen_vk, Circuit},
-    poly::ipa::{commitment::IPACommitmentScheme, multiopen::ProverIPA},
+    plonk::{create_proof, keygen_pk_custom, keygen_vk_custom, Circuit, VerifyingKey},
+    SerdeFormat,
 };
 use rand_core::OsRng;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Magic bytes identifying a verifying key file
+pub(crate) const VK_MAGIC: &[u8; 4] = b"NZVK";
+
+/// Verifying key file format version
+pub(crate) const VK_VERSION: u8 = 2;
+
+/// Whether key generation compresses selectors into shared fixed columns
+///
+/// Compression depends on the rows each selector is enabled on, so reading
+/// a compressed key would need the circuit's witness layout. Uncompressed,
+/// the key's constraint system depends on the circuit's configuration alone.
+pub(crate) const COMPRESS_SELECTORS: bool = false;
+
+/// Circuit whose verifying keys can be written to files
+///
+/// Key files store the circuit's configuration parameters (e.g., the
+/// `GateSet` of `NzengiCircuit`), so `Verifier::read_vk` configures the
+/// circuit as it was when the key was generated.
+pub trait KeyCircuit: Circuit<Field> {
+    /// Encode the circuit's configuration parameters
+    fn params_bytes(&self) -> Vec<u8>;
+
+    /// Build a circuit without witnesses, configured by encoded parameters
+    ///
+    /// # Returns
+    /// `Some(circuit)`, `None` if the bytes don't decode
+    fn from_params_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+/// Delay before the first retry of `create_proof_with_retry`, doubled after
+/// each further failure
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Writer recording the first I/O error of the writer it wraps
+///
+/// The transcript reports write failures to halo2, whose error keeps only
+/// their message; the recorded error keeps the kind retries depend on.
+struct TrackedWriter<W> {
+    /// Writer the proof bytes go to
+    inner: W,
+
+    /// First error returned by `inner`
+    error: Option<io::Error>,
+}
+
+impl<W: Write> TrackedWriter<W> {
+    /// Record an error of the inner writer, passing it on to the transcript
+    fn record(&mut self, e: io::Error) -> io::Error {
+        if self.error.is_none() {
+            self.error = Some(io::Error::new(e.kind(), e.to_string()));
+        }
+        e
+    }
+}
+
+impl<W: Write> Write for TrackedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf).map_err(|e| self.record(e))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush().map_err(|e| self.record(e))
+    }
+}
 
 /// Prover for generating zero-knowledge proofs
 ///
 /// This struct provides methods for generating proving keys and creating proofs
-/// from circuits using Halo2's PLONKish proving system.
+/// from circuits using Halo2's PLONKish proving system. It is generic over
+/// the commitment backend, `IPAScheme` by default.
 #[derive(Debug, Clone)]
-pub struct Prover {
+pub struct Prover<S: CommitmentScheme = IPAScheme> {
     /// Public parameters for proof generation
-    params: IPAParams,
+    params: S::Params,
 }
 
-impl Prover {
-    /// Create a new prover with the given parameters
+impl<S: CommitmentScheme> Prover<S> {
+    /// Create a prover from parameters of its commitment backend
     ///
     /// # Arguments
-    /// * `params` - IPA parameters for proof generation
+    /// * `params` - Backend parameters for proof generation
     ///
     /// # Returns
     /// New `Prover` instance
-    pub fn new(params: &IPAParams) -> Self {
-        Self {
-            params: params.clone(),
-        }
+    pub fn with_scheme_params(params: S::Params) -> Self {
+        Self { params }
+    }
+
+    /// Get the parameters used by this prover
+    pub fn params(&self) -> &S::Params {
+        &self.params
     }
 
     /// Generate verifying key from circuit
     ///
+    /// Selectors are not compressed, so the key can be read back from its
+    /// configuration alone (see `Verifier::read_vk`).
+    ///
     /// # Arguments
     /// * `circuit` - The circuit to generate verifying key for
     ///
@@ -67,7 +153,7 @@ impl Prover {
         &self,
         circuit: &C,
     ) -> Result<halo2_proofs::plonk::VerifyingKey<G1Affine>, Box<dyn std::error::Error>> {
-        let vk = keygen_vk(&self.params.params, circuit)
+        let vk = keygen_vk_custom(S::prover_params(&self.params), circuit, COMPRESS_SELECTORS)
             .map_err(|e| format!("Failed to generate verifying key: {:?}", e))?;
         Ok(vk)
     }
@@ -87,8 +173,13 @@ impl Prover {
         let vk = self.generate_vk(circuit)?;
 
         // Generate proving key from verifying key
-        let pk = keygen_pk(&self.params.params, vk, circuit)
-            .map_err(|e| format!("Failed to generate proving key: {:?}", e))?;
+        let pk = keygen_pk_custom(
+            S::prover_params(&self.params),
+            vk,
+            circuit,
+            COMPRESS_SELECTORS,
+        )
+        .map_err(|e| format!("Failed to generate proving key: {:?}", e))?;
 
         Ok(pk)
     }
@@ -136,36 +227,194 @@ impl Prover {
         circuit: &C,
         public_inputs: &[Field],
     ) -> Result<Proof, Box<dyn std::error::Error>> {
+        let mut proof_bytes = vec![];
+        self.create_proof_to_writer(pk, circuit, public_inputs, &mut proof_bytes)?;
+
+        Ok(Proof::new(proof_bytes, public_inputs.to_vec()))
+    }
+
+    /// Create a proof, streaming the proof bytes to a writer
+    ///
+    /// The transcript writes each proof element as it is produced, so the
+    /// proof is never buffered in memory. The bytes written are the same as
+    /// `Proof::proof_bytes` from `create_proof`; the public inputs are not
+    /// written.
+    ///
+    /// # Arguments
+    /// * `pk` - Proving key generated from the circuit
+    /// * `circuit` - The circuit to prove
+    /// * `public_inputs` - Public inputs (instance column values)
+    /// * `writer` - Destination of the proof bytes (e.g., a file)
+    ///
+    /// # Returns
+    /// `Ok(())` if the proof was created and written, `Err` with a
+    /// [`ProofError`] otherwise
+    pub fn create_proof_to_writer<C: Circuit<Field> + Clone, W: Write>(
+        &self,
+        pk: &halo2_proofs::plonk::ProvingKey<G1Affine>,
+        circuit: &C,
+        public_inputs: &[Field],
+        writer: W,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Generate proof using Halo2's create_proof function
         // Note: In Halo2 v2023_04_20, create_proof needs proper transcript
         use halo2_proofs::transcript::{Blake2bWrite, Challenge255, TranscriptWriterBuffer};
 
-        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        let writer = TrackedWriter {
+            inner: writer,
+            error: None,
+        };
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(writer);
         let rng = OsRng;
 
         // Prepare circuit and instance data in the shape expected by Halo2
         let circuits = vec![circuit.clone()];
         let instances = vec![vec![public_inputs.to_vec()]];
 
-        create_proof::<IPACommitmentScheme<G1Affine>, ProverIPA<G1Affine>, _, _, _, _>(
-            &self.params.params,
+        let result = create_proof::<S::Halo2, S::Halo2Prover<'_>, _, _, _, _>(
+            S::prover_params(&self.params),
             pk,
             &circuits,
             &instances,
             rng,
             &mut transcript,
-        )
-        .map_err(|e| format!("Failed to create proof: {:?}", e))?;
+        );
 
-        // Extract proof bytes from transcript
-        let proof_bytes = transcript.finalize();
+        // A failed write is reported as such, whatever halo2 made of it
+        let mut writer = transcript.finalize();
+        if let Some(e) = writer.error.take() {
+            return Err(ProofError::Io(e).into());
+        }
+        result.map_err(|e| ProofError::Proving(format!("{:?}", e)))?;
+        writer.flush().map_err(ProofError::Io)?;
 
-        Ok(Proof::new(proof_bytes, public_inputs.to_vec()))
+        Ok(())
     }
 
-    /// Get the parameters used by this prover
-    pub fn params(&self) -> &IPAParams {
-        &self.params
+    /// Create a proof, retrying transient failures with exponential backoff
+    ///
+    /// Transient failures (see [`ProofError::is_retryable`]) are retried
+    /// after a delay that starts at 100 ms and doubles with each attempt.
+    /// Other failures, such as invalid public inputs, are returned
+    /// immediately.
+    ///
+    /// # Arguments
+    /// * `pk` - Proving key generated from the circuit
+    /// * `circuit` - The circuit to prove
+    /// * `public_inputs` - Public inputs (instance column values)
+    /// * `max_attempts` - Maximum number of attempts (at least 1)
+    ///
+    /// # Returns
+    /// `Ok(Proof)` once an attempt succeeds, `Err` with the last error if
+    /// every attempt failed or the error is not retryable
+    pub fn create_proof_with_retry<C: Circuit<Field> + Clone>(
+        &self,
+        pk: &halo2_proofs::plonk::ProvingKey<G1Affine>,
+        circuit: &C,
+        public_inputs: &[Field],
+        max_attempts: u32,
+    ) -> Result<Proof, Box<dyn std::error::Error>> {
+        Prover::retry_with_backoff(max_attempts, INITIAL_BACKOFF, || {
+            self.create_proof(pk, circuit, public_inputs)
+        })
+    }
+
+    /// Write a verifying key to a file
+    ///
+    /// Verifiers can load the key with `Verifier::read_vk` instead of
+    /// generating it from the circuit. Layout (little-endian):
+    ///
+    /// ```text
+    /// "NZVK" | format: u8 | k: u32 | params length: u16 | circuit params
+    ///        | verifying key (halo2, processed)
+    /// ```
+    ///
+    /// # Arguments
+    /// * `vk` - Verifying key generated with this prover's parameters
+    /// * `circuit` - Circuit the key was generated from, whose configuration
+    ///   parameters are stored with it
+    /// * `path` - Destination file, created or truncated
+    ///
+    /// # Returns
+    /// `Ok(())` if the key was written, `Err` otherwise
+    pub fn write_vk<C: KeyCircuit>(
+        &self,
+        vk: &VerifyingKey<G1Affine>,
+        circuit: &C,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let circuit_params = circuit.params_bytes();
+        let params_len = u16::try_from(circuit_params.len())
+            .map_err(|_| "Circuit parameters too large for a verifying key file")?;
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writer.write_all(VK_MAGIC)?;
+        writer.write_all(&[VK_VERSION])?;
+        writer.write_all(&S::k(&self.params).to_le_bytes())?;
+        writer.write_all(&params_len.to_le_bytes())?;
+        writer.write_all(&circuit_params)?;
+        vk.write(&mut writer, SerdeFormat::Processed)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Prover {
+    /// Create a new prover with the given parameters
+    ///
+    /// # Arguments
+    /// * `params` - IPA parameters for proof generation
+    ///
+    /// # Returns
+    /// New `Prover` instance
+    pub fn new(params: &IPAParams) -> Self {
+        Self {
+            params: params.clone(),
+        }
+    }
+
+    /// Run an operation until it succeeds, fails with a non-retryable error,
+    /// or `max_attempts` attempts have been made
+    ///
+    /// # Arguments
+    /// * `max_attempts` - Maximum number of attempts (at least 1)
+    /// * `backoff` - Delay before the first retry, doubled after each retry
+    /// * `attempt` - The operation to run
+    ///
+    /// # Returns
+    /// The first successful result, or the last error
+    fn retry_with_backoff<T>(
+        max_attempts: u32,
+        mut backoff: Duration,
+        mut attempt: impl FnMut() -> Result<T, Box<dyn std::error::Error>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let mut attempts = 1;
+        loop {
+            match attempt() {
+                Err(e) if attempts < max_attempts && Self::is_retryable(e.as_ref()) => {
+                    log::warn!(
+                        "Proof attempt {} of {} failed, retrying in {:?}: {}",
+                        attempts,
+                        max_attempts,
+                        backoff,
+                        e
+                    );
+                    std::thread::sleep(backoff);
+                    backoff *= 2;
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Check whether a proving error is transient
+    ///
+    /// # Returns
+    /// `true` for a retryable [`ProofError`], `false` for any other error
+    fn is_retryable(error: &(dyn std::error::Error + 'static)) -> bool {
+        error
+            .downcast_ref::<ProofError>()
+            .is_some_and(ProofError::is_retryable)
     }
 }
 
@@ -173,6 +422,7 @@ impl Prover {
 mod tests {
     use super::*;
     use crate::circuit::NzengiCircuit;
+    use crate::test_util::count_circuit;
 
     #[test]
     fn test_prover_new() {
@@ -180,6 +430,10 @@ mod tests {
         let params = IPAParams::new(10);
         let prover = Prover::new(&params);
         assert_eq!(prover.params().k(), 10);
+
+        // The default backend is IPA
+        let generic = Prover::<IPAScheme>::with_scheme_params(IPAScheme::setup(10));
+        assert_eq!(generic.params().k(), prover.params().k());
     }
 
     #[test]
@@ -226,4 +480,147 @@ mod tests {
             println!("Proof creation failed (expected for test): {}", e);
         }
     }
+
+    #[test]
+    fn test_prover_create_proof_to_writer() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(9);
+        let circuit = count_circuit(&[2, 0, 3, 5]);
+        let public_inputs = circuit.public_inputs();
+
+        let prover = Prover::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("proof.bin");
+        let file = std::fs::File::create(&path).unwrap();
+        prover
+            .create_proof_to_writer(&pk, &circuit, &public_inputs, std::io::BufWriter::new(file))
+            .unwrap();
+
+        // The streamed proof loads and verifies
+        let proof = Proof::new(std::fs::read(&path).unwrap(), public_inputs.clone());
+        assert!(!proof.proof_bytes.is_empty());
+        let verifier = Verifier::new(&params);
+        assert!(verifier.verify(&vk, &proof, &public_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_prover_generic_over_scheme() {
+        use crate::proof::Verifier;
+
+        fn prove<S: CommitmentScheme>(
+            params: S::Params,
+            circuit: &NzengiCircuit,
+            public_inputs: &[Field],
+        ) -> (Proof, VerifyingKey<G1Affine>) {
+            let prover = Prover::<S>::with_scheme_params(params);
+            let (pk, vk) = prover.generate_keys(circuit).unwrap();
+            (
+                prover.create_proof(&pk, circuit, public_inputs).unwrap(),
+                vk,
+            )
+        }
+
+        let params = IPAParams::new(9);
+        let circuit = count_circuit(&[2, 0, 3]);
+        let public_inputs = circuit.public_inputs();
+
+        // A proof through the generic path verifies like one from `Prover::new`
+        let (proof, vk) = prove::<IPAScheme>(params.clone(), &circuit, &public_inputs);
+        let verifier = Verifier::new(&params);
+        assert!(verifier.verify(&vk, &proof, &public_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_prover_retry_with_backoff() {
+        let out_of_memory = || -> Box<dyn std::error::Error> {
+            Box::new(ProofError::Io(io::ErrorKind::OutOfMemory.into()))
+        };
+
+        // Engine failing with an out-of-memory write on the first attempt
+        let mut calls = 0;
+        let result = Prover::retry_with_backoff(3, Duration::ZERO, || {
+            calls += 1;
+            if calls == 1 {
+                Err(out_of_memory())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        // Proving failures are not retried, whatever their message says
+        let mut calls = 0;
+        let result: Result<(), _> = Prover::retry_with_backoff(3, Duration::ZERO, || {
+            calls += 1;
+            Err(ProofError::Proving("out of memory".to_string()).into())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        // Retryable failures stop after max_attempts
+        let mut calls = 0;
+        let result: Result<(), _> = Prover::retry_with_backoff(3, Duration::ZERO, || {
+            calls += 1;
+            Err(out_of_memory())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_prover_create_proof_errors() {
+        /// Writer failing every write with the given kind
+        struct FailingWriter(io::ErrorKind);
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(self.0.into())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let params = IPAParams::new(9);
+        let circuit = count_circuit(&[2]);
+        let public_inputs = circuit.public_inputs();
+        let prover = Prover::new(&params);
+        let (pk, _) = prover.generate_keys(&circuit).unwrap();
+
+        // A write failing for lack of memory is transient
+        let err = prover
+            .create_proof_to_writer(
+                &pk,
+                &circuit,
+                &public_inputs,
+                FailingWriter(io::ErrorKind::OutOfMemory),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofError>(),
+            Some(ProofError::Io(e)) if e.kind() == io::ErrorKind::OutOfMemory
+        ));
+        assert!(Prover::is_retryable(err.as_ref()));
+
+        // More public inputs than the circuit has rows fails every time
+        let too_many = vec![Field::from(1u64); params.max_rows() + 1];
+        let err = prover.create_proof(&pk, &circuit, &too_many).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofError>(),
+            Some(ProofError::Proving(_))
+        ));
+        assert!(!Prover::is_retryable(err.as_ref()));
+        assert!(prover
+            .create_proof_with_retry(&pk, &circuit, &too_many, 3)
+            .is_err());
+
+        // ...while valid inputs prove
+        assert!(prover
+            .create_proof_with_retry(&pk, &circuit, &public_inputs, 3)
+            .is_ok());
+    }
 }
@@ -50,6 +50,7 @@ use crate::commitment::IPAParams;
 use crate::types::Proof;
 use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine};
 use halo2_proofs::plonk::VerifyingKey;
+use std::collections::HashMap;
 
 /// Recursive prover for composing multiple proofs
 ///
@@ -73,9 +74,11 @@ pub struct RecursiveVerifier {
 /// Composed proof structure
 ///
 /// Contains a single proof that represents the composition of multiple sub-proofs.
+/// Byte-identical sub-proofs are stored once; `metadata.proof_index` maps each
+/// sub-proof to its stored copy.
 #[derive(Debug, Clone)]
 pub struct ComposedProof {
-    /// The composed proof bytes
+    /// The composed proof bytes (each unique sub-proof once)
     pub proof_bytes: Vec<u8>,
     /// Public inputs from all sub-proofs
     pub public_inputs: Vec<Field>,
@@ -90,6 +93,12 @@ pub struct CompositionMetadata {
     pub num_proofs: usize,
     /// Sub-proof identifiers
     pub proof_ids: Vec<String>,
+    /// Index of each sub-proof in the unique proof store
+    pub proof_index: Vec<usize>,
+    /// Size in bytes of each unique proof stored in `proof_bytes`
+    pub unique_proof_sizes: Vec<usize>,
+    /// Number of public inputs of each sub-proof
+    pub public_input_counts: Vec<usize>,
     /// Composition timestamp (optional)
     pub timestamp: Option<u64>,
 }
@@ -143,14 +152,28 @@ impl RecursiveProver {
         let mut composed_proof_bytes = Vec::new();
         let mut composed_public_inputs = Vec::new();
         let mut proof_ids = Vec::new();
+        let mut proof_index = Vec::new();
+        let mut unique_proofs: HashMap<&[u8], usize> = HashMap::new();
+        let mut unique_proof_sizes = Vec::new();
+        let mut public_input_counts = Vec::new();
 
         for (i, proof) in proofs.iter().enumerate() {
-            // Combine proof bytes
-            composed_proof_bytes.extend_from_slice(&proof.proof_bytes);
-            
-            // Combine public inputs
-            composed_public_inputs.extend_from_slice(&proof.public_inputs);
-            
+            // Store proof bytes once per unique proof
+            let next_index = unique_proofs.len();
+            let index = *unique_proofs
+                .entry(proof.proof_bytes.as_slice())
+                .or_insert_with(|| {
+                    composed_proof_bytes.extend_from_slice(&proof.proof_bytes);
+                    unique_proof_sizes.push(proof.proof_bytes.len());
+                    next_index
+                });
+            proof_index.push(index);
+
+            // Combine public inputs, restoring any stripped trailing zeros
+            let public_inputs = proof.padded_public_inputs();
+            public_input_counts.push(public_inputs.len());
+            composed_public_inputs.extend(public_inputs);
+
             // Generate proof ID
             proof_ids.push(format!("proof_{}", i));
         }
@@ -158,6 +181,9 @@ impl RecursiveProver {
         let metadata = CompositionMetadata {
             num_proofs: proofs.len(),
             proof_ids,
+            proof_index,
+            unique_proof_sizes,
+            public_input_counts,
             timestamp: None, // TODO: Add timestamp if needed
         };
 
@@ -264,6 +290,17 @@ impl RecursiveVerifier {
             return Ok(false);
         }
 
+        // Reconstruct every sub-proof from the deduplicated store
+        let sub_proofs = match composed_proof.sub_proofs() {
+            Ok(sub_proofs) => sub_proofs,
+            Err(_) => return Ok(false),
+        };
+        if sub_proofs.len() != verifying_keys.len()
+            || sub_proofs.iter().any(|proof| proof.proof_bytes.is_empty())
+        {
+            return Ok(false);
+        }
+
         // TODO: Implement actual recursive proof verification
         // This would involve:
         // 1. Deserializing composed proof
@@ -309,6 +346,60 @@ impl ComposedProof {
     pub fn size(&self) -> usize {
         self.proof_bytes.len()
     }
+
+    /// Get the number of unique sub-proofs stored
+    pub fn num_unique_proofs(&self) -> usize {
+        self.metadata.unique_proof_sizes.len()
+    }
+
+    /// Reconstruct the sub-proofs from the deduplicated store
+    ///
+    /// # Returns
+    /// `Ok(Vec<Proof>)` with one proof per composed sub-proof, in order,
+    /// `Err` if the metadata doesn't match the stored bytes or inputs
+    pub fn sub_proofs(&self) -> Result<Vec<Proof>, Box<dyn std::error::Error>> {
+        let metadata = &self.metadata;
+        if metadata.proof_index.len() != metadata.num_proofs
+            || metadata.public_input_counts.len() != metadata.num_proofs
+        {
+            return Err("Composition metadata does not describe every sub-proof".into());
+        }
+
+        // Split the store into the unique proofs
+        let mut unique_proofs = Vec::with_capacity(metadata.unique_proof_sizes.len());
+        let mut offset = 0;
+        for &size in &metadata.unique_proof_sizes {
+            let end = offset
+                .checked_add(size)
+                .filter(|&end| end <= self.proof_bytes.len())
+                .ok_or("Unique proof sizes exceed the stored proof bytes")?;
+            unique_proofs.push(&self.proof_bytes[offset..end]);
+            offset = end;
+        }
+        if offset != self.proof_bytes.len() {
+            return Err("Stored proof bytes do not match the unique proof sizes".into());
+        }
+
+        let mut public_inputs = self.public_inputs.as_slice();
+        let mut sub_proofs = Vec::with_capacity(metadata.num_proofs);
+        for (&index, &count) in metadata.proof_index.iter().zip(&metadata.public_input_counts) {
+            let bytes = unique_proofs
+                .get(index)
+                .ok_or_else(|| format!("Sub-proof index {} is out of range", index))?;
+            if count > public_inputs.len() {
+                return Err("Public input counts exceed the stored public inputs".into());
+            }
+            let (inputs, rest) = public_inputs.split_at(count);
+            public_inputs = rest;
+
+            sub_proofs.push(Proof::new(bytes.to_vec(), inputs.to_vec()));
+        }
+        if !public_inputs.is_empty() {
+            return Err("Stored public inputs do not match the public input counts".into());
+        }
+
+        Ok(sub_proofs)
+    }
 }
 
 impl CompositionMetadata {
@@ -321,6 +412,9 @@ impl CompositionMetadata {
         Self {
             num_proofs,
             proof_ids,
+            proof_index: Vec::new(),
+            unique_proof_sizes: Vec::new(),
+            public_input_counts: Vec::new(),
             timestamp: None,
         }
     }
@@ -335,6 +429,11 @@ impl CompositionMetadata {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::proof::Prover;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        plonk::{Circuit, ConstraintSystem, ErrorFront},
+    };
 
     #[test]
     fn test_recursive_prover_new() {
@@ -400,5 +499,72 @@ mod tests {
         let result = recursive_prover.compose_proofs(&proofs, &vks);
         assert!(result.is_err()); // Should fail for mismatch
     }
-}
 
+    /// Circuit without constraints, used only to obtain verifying keys
+    #[derive(Clone, Default)]
+    struct EmptyCircuit;
+
+    impl Circuit<Field> for EmptyCircuit {
+        type Config = ();
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            meta.advice_column();
+        }
+
+        fn synthesize(
+            &self,
+            _config: Self::Config,
+            _layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_compose_proofs_deduplicates() {
+        let params = IPAParams::new(4);
+        let vk = Prover::new(&params).generate_vk(&EmptyCircuit).unwrap();
+        let vks = vec![vk.clone(), vk.clone(), vk];
+
+        let p1 = Proof::new(vec![1, 2, 3, 4], vec![Field::from(7u64)]);
+        let p2 = Proof::new(vec![5, 6], vec![Field::from(8u64), Field::from(9u64)]);
+        let proofs = vec![p1.clone(), p1.clone(), p2.clone()];
+
+        let recursive_prover = RecursiveProver::new(params.clone());
+        let composed = recursive_prover.compose_proofs(&proofs, &vks).unwrap();
+
+        assert_eq!(composed.num_unique_proofs(), 2);
+        assert_eq!(composed.num_proofs(), 3);
+        assert_eq!(composed.size(), 6);
+        assert_eq!(composed.metadata.proof_index, vec![0, 0, 1]);
+
+        let sub_proofs = composed.sub_proofs().unwrap();
+        assert_eq!(sub_proofs.len(), 3);
+        for (sub_proof, proof) in sub_proofs.iter().zip(&proofs) {
+            assert_eq!(sub_proof.proof_bytes, proof.proof_bytes);
+            assert_eq!(sub_proof.public_inputs, proof.public_inputs);
+        }
+
+        let recursive_verifier = RecursiveVerifier::new(params);
+        assert!(recursive_verifier.verify_composed(&composed, &vks).unwrap());
+
+        // A corrupted index map no longer reconstructs the sub-proofs
+        let mut corrupted = composed.clone();
+        corrupted.metadata.proof_index[2] = 5;
+        assert!(corrupted.sub_proofs().is_err());
+        assert!(!recursive_verifier.verify_composed(&corrupted, &vks).unwrap());
+
+        // Nor do sizes overflowing or running past the stored bytes
+        for sizes in [vec![4, usize::MAX], vec![4, 3]] {
+            let mut corrupted = composed.clone();
+            corrupted.metadata.unique_proof_sizes = sizes;
+            assert!(corrupted.sub_proofs().is_err());
+        }
+    }
+}
@@ -20,12 +20,76 @@
 //! // Verify proof
 //! let result = verifier.verify(&vk, &proof, &[])?;
 //! assert!(result);
+//!
+//! // Or find out why it fails
+//! let diagnostics = verifier.verify_with_diagnostics(&vk, &proof, &[]);
+//! assert_eq!(diagnostics.failed_stage, None);
+//!
+//! // Load a verifying key written by `Prover::write_vk`
+//! let vk = Verifier::read_vk::<NzengiCircuit>("query.vk", verifier.params())?;
 //! ```
 
-use crate::commitment::IPAParams;
+use super::prover::{KeyCircuit, COMPRESS_SELECTORS, VK_MAGIC, VK_VERSION};
+use crate::commitment::{DatabaseCommitment, IPAParams, VerifierParams};
 use crate::types::Proof;
 use halo2_proofs::halo2curves::bn256::{Fr as Field, G1Affine};
-use halo2_proofs::plonk::VerifyingKey;
+use halo2_proofs::{
+    dev::MockProver,
+    plonk::{verify_proof, vk_read, Circuit, VerifyingKey},
+    poly::ipa::{
+        commitment::IPACommitmentScheme, multiopen::VerifierIPA, strategy::SingleStrategy,
+    },
+    transcript::{Blake2bRead, Challenge255, TranscriptReadBuffer},
+    SerdeFormat,
+};
+use std::io::Read;
+use std::path::Path;
+
+/// Stage at which verification failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyStage {
+    /// The public inputs differ from the ones the proof was created with
+    PublicInputs,
+    /// The proof bytes are empty, truncated or hold an invalid encoding
+    Deserialization,
+    /// The replayed transcript's evaluations don't satisfy the constraints
+    Transcript,
+    /// The final multi-opening check failed
+    FinalCheck,
+    /// The circuit failed to synthesize in a dry run
+    Synthesis,
+    /// Constraints failed in a dry run
+    Constraints,
+}
+
+/// Explanation of a verification result
+///
+/// Produced by [`Verifier::verify_with_diagnostics`] for proofs and by
+/// [`Verifier::dry_run`] for circuits checked with `MockProver`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyDiagnostics {
+    /// Stage that failed, or `None` if verification succeeded
+    pub failed_stage: Option<VerifyStage>,
+
+    /// Details of the failure, e.g. the halo2 error or the failing
+    /// constraints and regions of a dry run
+    pub details: Vec<String>,
+}
+
+impl VerifyDiagnostics {
+    /// Diagnostics for a failure at `stage`
+    fn failed(stage: VerifyStage, details: Vec<String>) -> Self {
+        Self {
+            failed_stage: Some(stage),
+            details,
+        }
+    }
+
+    /// Whether verification succeeded
+    pub fn is_valid(&self) -> bool {
+        self.failed_stage.is_none()
+    }
+}
 
 /// Verifier for verifying zero-knowledge proofs
 ///
@@ -34,21 +98,32 @@ use halo2_proofs::plonk::VerifyingKey;
 #[derive(Debug, Clone)]
 pub struct Verifier {
     /// Public parameters for proof verification
-    params: IPAParams,
+    params: VerifierParams,
 }
 
 impl Verifier {
     /// Create a new verifier with the given parameters
     ///
+    /// Only the verification part of the parameters is kept.
+    ///
     /// # Arguments
     /// * `params` - IPA parameters for proof verification
     ///
     /// # Returns
     /// New `Verifier` instance
     pub fn new(params: &IPAParams) -> Self {
-        Self {
-            params: params.clone(),
-        }
+        Self::new_from_verifier_params(params.verifier_params())
+    }
+
+    /// Create a new verifier from verification-only parameters
+    ///
+    /// # Arguments
+    /// * `params` - Verifier parameters, from `IPAParams::verifier_params`
+    ///
+    /// # Returns
+    /// New `Verifier` instance
+    pub fn new_from_verifier_params(params: VerifierParams) -> Self {
+        Self { params }
     }
 
     /// Verify a proof using a verifying key
@@ -65,32 +140,120 @@ impl Verifier {
     /// `Ok(true)` if proof is valid, `Ok(false)` if proof is invalid, `Err` on error
     pub fn verify(
         &self,
-        _vk: &VerifyingKey<G1Affine>,
+        vk: &VerifyingKey<G1Affine>,
         proof: &Proof,
         public_inputs: &[Field],
     ) -> Result<bool, Box<dyn std::error::Error>> {
-        // Verify that public inputs match
-        if proof.public_inputs != public_inputs {
-            return Ok(false);
+        Ok(self
+            .verify_with_diagnostics(vk, proof, public_inputs)
+            .is_valid())
+    }
+
+    /// Verify a proof, reporting the stage that failed
+    ///
+    /// # Arguments
+    /// * `vk` - Verifying key generated from the circuit
+    /// * `proof` - The proof to verify
+    /// * `public_inputs` - Public inputs (instance column values)
+    ///
+    /// # Returns
+    /// `VerifyDiagnostics` with no failed stage if the proof is valid
+    pub fn verify_with_diagnostics(
+        &self,
+        vk: &VerifyingKey<G1Affine>,
+        proof: &Proof,
+        public_inputs: &[Field],
+    ) -> VerifyDiagnostics {
+        // Verify that public inputs match, restoring stripped trailing zeros
+        let proof_inputs = proof.padded_public_inputs();
+        if proof_inputs != public_inputs {
+            let mismatch = match proof_inputs
+                .iter()
+                .zip(public_inputs)
+                .position(|(a, b)| a != b)
+            {
+                Some(i) => format!("public input {} differs from the proof's", i),
+                None => format!(
+                    "expected {} public inputs, the proof has {}",
+                    public_inputs.len(),
+                    proof_inputs.len()
+                ),
+            };
+            return VerifyDiagnostics::failed(VerifyStage::PublicInputs, vec![mismatch]);
         }
 
-        // Deserialize proof from bytes
-        // Note: In Halo2 v2023_04_20, Proof type is returned directly from create_proof
-        // We need to deserialize it properly. For now, we'll use a simplified approach
-        // In production, you'd need proper serialization/deserialization of the proof structure
-        // For now, we'll skip the actual verification and just check that proof bytes are not empty
         if proof.proof_bytes.is_empty() {
-            return Ok(false);
+            return VerifyDiagnostics::failed(
+                VerifyStage::Deserialization,
+                vec!["proof bytes are empty".to_string()],
+            );
         }
 
-        // TODO: Properly deserialize and verify proof using Halo2's verify_proof function
-        // This requires understanding the exact proof structure in Halo2 v2023_04_20
-        // The signature is: verify_proof(params, vk, instances, proof)
-        // For now, we'll return true if proof bytes are not empty
-        // In production, you would:
-        // 1. Deserialize proof bytes to Halo2 proof structure
-        // 2. Call verify_proof(params, vk, &[&[public_inputs]], &halo2_proof)
-        Ok(true)
+        let mut transcript =
+            Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof.proof_bytes.as_slice());
+        let strategy = SingleStrategy::new(&self.params.params);
+        let instances = vec![vec![public_inputs.to_vec()]];
+
+        let result = verify_proof::<IPACommitmentScheme<G1Affine>, VerifierIPA<G1Affine>, _, _, _>(
+            &self.params.params,
+            vk,
+            strategy,
+            &instances,
+            &mut transcript,
+        );
+
+        match result {
+            Ok(_) => VerifyDiagnostics::default(),
+            Err(e) => {
+                let details = format!("{:?}", e);
+                VerifyDiagnostics::failed(Self::failed_stage(&details), vec![details])
+            }
+        }
+    }
+
+    /// Classify a halo2 verification error by the stage raising it
+    fn failed_stage(error: &str) -> VerifyStage {
+        if error.starts_with("Transcript") {
+            // Reading a point or scalar from the proof bytes failed
+            VerifyStage::Deserialization
+        } else if error.starts_with("Opening") {
+            VerifyStage::FinalCheck
+        } else {
+            VerifyStage::Transcript
+        }
+    }
+
+    /// Check a circuit with `MockProver` at this verifier's size
+    ///
+    /// Unlike proof verification, a dry run reports which constraints and
+    /// regions fail, which helps find why a proof doesn't verify.
+    ///
+    /// # Arguments
+    /// * `circuit` - Circuit with its witness
+    /// * `public_inputs` - Public inputs (instance column values)
+    ///
+    /// # Returns
+    /// `VerifyDiagnostics` listing every failing constraint, or no failed
+    /// stage if the circuit is satisfied
+    pub fn dry_run<C: Circuit<Field>>(
+        &self,
+        circuit: &C,
+        public_inputs: &[Field],
+    ) -> VerifyDiagnostics {
+        let prover = match MockProver::run(self.params.k(), circuit, vec![public_inputs.to_vec()]) {
+            Ok(prover) => prover,
+            Err(e) => {
+                return VerifyDiagnostics::failed(VerifyStage::Synthesis, vec![format!("{:?}", e)])
+            }
+        };
+
+        match prover.verify() {
+            Ok(()) => VerifyDiagnostics::default(),
+            Err(failures) => VerifyDiagnostics::failed(
+                VerifyStage::Constraints,
+                failures.iter().map(|f| f.to_string()).collect(),
+            ),
+        }
     }
 
     /// Verify a proof with automatic public input extraction
@@ -108,11 +271,159 @@ impl Verifier {
         vk: &VerifyingKey<G1Affine>,
         proof: &Proof,
     ) -> Result<bool, Box<dyn std::error::Error>> {
-        self.verify(vk, proof, &proof.public_inputs)
+        self.verify(vk, proof, &proof.padded_public_inputs())
+    }
+
+    /// Verify a proof against the database commitment it is bound to
+    ///
+    /// The proof must carry the commitment hash as its last public input
+    /// (see `NzengiCircuit::with_commitment_hash`); it is appended to
+    /// `public_inputs` here, so a proof created against another commitment
+    /// fails. The values of the cell openings bundled with the proof are
+    /// appended before it, in order, as the circuit's opened cells (see
+    /// `NzengiCircuit::with_opened_column`). Rejects the proof if the
+    /// commitment hash does not match its contents, if the commitment is
+    /// older than `min_version`, or if a cell opening doesn't open the
+    /// commitment.
+    ///
+    /// # Arguments
+    /// * `vk` - Verifying key generated from the circuit
+    /// * `proof` - The proof to verify
+    /// * `public_inputs` - Public inputs before the opened cells and the
+    ///   commitment hash
+    /// * `commitment` - Database commitment the proof was generated against
+    /// * `min_version` - Minimum accepted commitment version, if any
+    ///
+    /// # Returns
+    /// `Ok(true)` if the commitment is fresh and the proof is valid,
+    /// `Ok(false)` otherwise, `Err` on error
+    pub fn verify_with_commitment(
+        &self,
+        vk: &VerifyingKey<G1Affine>,
+        proof: &Proof,
+        public_inputs: &[Field],
+        commitment: &DatabaseCommitment,
+        min_version: Option<u64>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        if min_version.is_some_and(|min| commitment.version < min) {
+            return Ok(false);
+        }
+        if !commitment.hash_matches() {
+            return Ok(false);
+        }
+        if !proof
+            .openings
+            .iter()
+            .all(|opening| opening.verify(commitment, &self.params))
+        {
+            return Ok(false);
+        }
+
+        let mut bound_inputs = public_inputs.to_vec();
+        for opening in &proof.openings {
+            bound_inputs.extend(&opening.values);
+        }
+        bound_inputs.push(commitment.hash_field());
+        self.verify(vk, proof, &bound_inputs)
+    }
+
+    /// Verify an unfiltered `COUNT(*)` answered from a committed row count
+    ///
+    /// `QueryExecutor` answers such counts with a proof that only binds the
+    /// commitment (see `QueryExecutor::execute_with_cancel`). The count is
+    /// accepted if it is the committed `num_rows` of every column of the
+    /// table and the proof verifies against the commitment.
+    ///
+    /// # Arguments
+    /// * `vk` - Verifying key generated from the circuit
+    /// * `proof` - The proof to verify
+    /// * `commitment` - Database commitment the proof was generated against
+    /// * `table_name` - Counted table
+    /// * `count` - Claimed row count
+    /// * `min_version` - Minimum accepted commitment version, if any
+    ///
+    /// # Returns
+    /// `Ok(true)` if the table has `count` committed rows and the proof is
+    /// valid, `Ok(false)` otherwise, `Err` on error
+    pub fn verify_committed_count(
+        &self,
+        vk: &VerifyingKey<G1Affine>,
+        proof: &Proof,
+        commitment: &DatabaseCommitment,
+        table_name: &str,
+        count: usize,
+        min_version: Option<u64>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(table) = commitment.get_table_commitment(table_name) else {
+            return Ok(false);
+        };
+        if table.column_commitments.is_empty()
+            || table.column_commitments.iter().any(|c| c.num_rows != count)
+        {
+            return Ok(false);
+        }
+
+        self.verify_with_commitment(vk, proof, &[], commitment, min_version)
+    }
+
+    /// Read a verifying key written by `Prover::write_vk`
+    ///
+    /// The circuit of type `C` is rebuilt from the configuration parameters
+    /// stored with the key, and only provides the constraint system the key
+    /// belongs to, so verifiers need neither the circuit's witness, the
+    /// proving key nor the full parameters.
+    ///
+    /// # Arguments
+    /// * `path` - Verifying key file
+    /// * `params` - Verifier parameters the key was generated with
+    ///
+    /// # Returns
+    /// `Ok(VerifyingKey)` if the file holds a verifying key for `params`,
+    /// `Err` if it is malformed, of another format version, generated with
+    /// a different `k`, or its circuit parameters don't decode as `C`'s
+    pub fn read_vk<C: KeyCircuit>(
+        path: impl AsRef<Path>,
+        params: &VerifierParams,
+    ) -> Result<VerifyingKey<G1Affine>, Box<dyn std::error::Error>> {
+        let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+
+        let mut header = [0u8; 9];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != VK_MAGIC {
+            return Err("Not a verifying key".into());
+        }
+        if header[4] != VK_VERSION {
+            return Err(format!("Unsupported verifying key format version {}", header[4]).into());
+        }
+        let k = u32::from_le_bytes([header[5], header[6], header[7], header[8]]);
+        if k != params.k() {
+            return Err(format!(
+                "Verifying key was generated for k = {}, parameters have k = {}",
+                k,
+                params.k()
+            )
+            .into());
+        }
+
+        let mut params_len = [0u8; 2];
+        reader.read_exact(&mut params_len)?;
+        let mut circuit_params = vec![0u8; usize::from(u16::from_le_bytes(params_len))];
+        reader.read_exact(&mut circuit_params)?;
+        let circuit = C::from_params_bytes(&circuit_params)
+            .ok_or("Verifying key holds parameters of another circuit")?;
+
+        let vk = vk_read::<G1Affine, _, _>(
+            &mut reader,
+            SerdeFormat::Processed,
+            k,
+            &circuit,
+            COMPRESS_SELECTORS,
+        )?;
+        Ok(vk)
     }
 
     /// Get the parameters used by this verifier
-    pub fn params(&self) -> &IPAParams {
+    pub fn params(&self) -> &VerifierParams {
         &self.params
     }
 }
@@ -122,6 +433,10 @@ mod tests {
     use super::*;
     use crate::circuit::NzengiCircuit;
     use crate::proof::Prover;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Advice, Circuit, Column, ConstraintSystem, ErrorFront, Instance},
+    };
 
     #[test]
     fn test_verifier_new() {
@@ -131,6 +446,240 @@ mod tests {
         assert_eq!(verifier.params().k(), 10);
     }
 
+    /// Minimal circuit exposing a private value as its public input
+    #[derive(Clone, Default)]
+    struct ExposeCircuit {
+        value: Field,
+    }
+
+    impl Circuit<Field> for ExposeCircuit {
+        type Config = (Column<Advice>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Field>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            (advice, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (advice, instance): Self::Config,
+            mut layouter: impl Layouter<Field>,
+        ) -> Result<(), ErrorFront> {
+            let cell = layouter.assign_region(
+                || "value",
+                |mut region| {
+                    region.assign_advice(|| "value", advice, 0, || Value::known(self.value))
+                },
+            )?;
+            layouter.constrain_instance(cell.cell(), instance, 0)
+        }
+    }
+
+    impl KeyCircuit for ExposeCircuit {
+        fn params_bytes(&self) -> Vec<u8> {
+            vec![]
+        }
+
+        fn from_params_bytes(bytes: &[u8]) -> Option<Self> {
+            bytes.is_empty().then(Self::default)
+        }
+    }
+
+    #[test]
+    fn test_verifier_from_verifier_params() {
+        let params = IPAParams::new(4);
+        let circuit = ExposeCircuit {
+            value: Field::from(42u64),
+        };
+        let prover = Prover::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let public_inputs = vec![Field::from(42u64)];
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+
+        let full = Verifier::new(&params);
+        let light = Verifier::new_from_verifier_params(params.verifier_params());
+        assert_eq!(light.params().k(), 4);
+
+        let full_result = full.verify(&vk, &proof, &public_inputs).unwrap();
+        let light_result = light.verify(&vk, &proof, &public_inputs).unwrap();
+        assert!(full_result);
+        assert_eq!(full_result, light_result);
+
+        // A proof checked against the wrong public input fails on both
+        let mut forged = proof.clone();
+        forged.public_inputs = vec![Field::from(43u64)];
+        let wrong_inputs = forged.public_inputs.clone();
+        assert!(!full.verify(&vk, &forged, &wrong_inputs).unwrap());
+        assert!(!light.verify(&vk, &forged, &wrong_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_verifier_read_vk_round_trip() {
+        let params = IPAParams::new(4);
+        let circuit = ExposeCircuit {
+            value: Field::from(42u64),
+        };
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("expose.vk");
+        prover.write_vk(&vk, &circuit, &path).unwrap();
+        let read = Verifier::read_vk::<ExposeCircuit>(&path, verifier.params()).unwrap();
+        assert_eq!(read.transcript_repr(), vk.transcript_repr());
+
+        // The read key accepts and rejects the same proofs as the original
+        let public_inputs = vec![Field::from(42u64)];
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+        let wrong_inputs = vec![Field::from(43u64)];
+        for inputs in [&public_inputs, &wrong_inputs] {
+            assert_eq!(
+                verifier.verify(&read, &proof, inputs).unwrap(),
+                verifier.verify(&vk, &proof, inputs).unwrap()
+            );
+        }
+        assert!(verifier.verify(&read, &proof, &public_inputs).unwrap());
+
+        // Keys for other parameters, other circuits or files of another
+        // kind are rejected
+        assert!(Verifier::read_vk::<NzengiCircuit>(&path, verifier.params()).is_err());
+        let other = IPAParams::new(5).verifier_params();
+        assert!(Verifier::read_vk::<ExposeCircuit>(&path, &other).is_err());
+        let not_vk = dir.path().join("proof.bin");
+        std::fs::write(&not_vk, &proof.proof_bytes).unwrap();
+        assert!(Verifier::read_vk::<ExposeCircuit>(&not_vk, verifier.params()).is_err());
+    }
+
+    #[test]
+    fn test_verifier_diagnostics() {
+        let params = IPAParams::new(4);
+        let circuit = ExposeCircuit {
+            value: Field::from(42u64),
+        };
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let public_inputs = vec![Field::from(42u64)];
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+        let diagnostics = verifier.verify_with_diagnostics(&vk, &proof, &public_inputs);
+        assert!(diagnostics.is_valid());
+        assert!(diagnostics.details.is_empty());
+
+        let diagnostics = verifier.verify_with_diagnostics(&vk, &proof, &[Field::from(43u64)]);
+        assert_eq!(diagnostics.failed_stage, Some(VerifyStage::PublicInputs));
+
+        // The proof ends with the opening argument's final scalar; flipping
+        // its low byte keeps it canonical, so only the final check fails
+        let mut tampered = proof.clone();
+        let last_scalar = tampered.proof_bytes.len() - 32;
+        tampered.proof_bytes[last_scalar] ^= 1;
+        let diagnostics = verifier.verify_with_diagnostics(&vk, &tampered, &public_inputs);
+        assert_eq!(diagnostics.failed_stage, Some(VerifyStage::FinalCheck));
+        assert!(!verifier.verify(&vk, &tampered, &public_inputs).unwrap());
+
+        let mut truncated = proof.clone();
+        truncated.proof_bytes.truncate(16);
+        let diagnostics = verifier.verify_with_diagnostics(&vk, &truncated, &public_inputs);
+        assert_eq!(diagnostics.failed_stage, Some(VerifyStage::Deserialization));
+
+        // A dry run points at the failing copy constraint
+        assert!(verifier.dry_run(&circuit, &public_inputs).is_valid());
+        let diagnostics = verifier.dry_run(&circuit, &[Field::from(43u64)]);
+        assert_eq!(diagnostics.failed_stage, Some(VerifyStage::Constraints));
+        assert!(!diagnostics.details.is_empty());
+    }
+
+    #[test]
+    fn test_verifier_stripped_trailing_zero_inputs() {
+        let params = IPAParams::new(4);
+        let circuit = ExposeCircuit {
+            value: Field::from(42u64),
+        };
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let public_inputs = vec![Field::from(42u64), Field::zero(), Field::zero()];
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+
+        let mut stripped = proof.clone();
+        assert_eq!(stripped.strip_trailing_zero_inputs(), 2);
+        assert_eq!(stripped.public_inputs, vec![Field::from(42u64)]);
+        assert_eq!(stripped.padded_public_inputs(), public_inputs);
+        assert!(stripped.compressed_size() < proof.compressed_size());
+
+        assert!(verifier.verify_with_proof_inputs(&vk, &stripped).unwrap());
+        assert!(verifier.verify(&vk, &stripped, &public_inputs).unwrap());
+    }
+
+    #[test]
+    fn test_verifier_rejects_stale_commitment_version() {
+        use crate::types::{Column, DataType, Row, Table, Value};
+
+        let params = IPAParams::new(4);
+        let circuit = ExposeCircuit {
+            value: Field::from(42u64),
+        };
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+
+        let table = Table {
+            name: "lineitem".to_string(),
+            columns: vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+            rows: vec![Row::new(vec![Value::Integer(42)])],
+        };
+        let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
+        assert_eq!(commitment.version, 1);
+
+        // The proof carries the commitment hash after its own public inputs
+        let public_inputs = vec![Field::from(42u64)];
+        let proof = prover
+            .create_proof(&pk, &circuit, &[public_inputs[0], commitment.hash_field()])
+            .unwrap();
+
+        assert!(verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &commitment, None)
+            .unwrap());
+        assert!(verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &commitment, Some(1))
+            .unwrap());
+        assert!(!verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &commitment, Some(2))
+            .unwrap());
+
+        // Bumping the version without recomputing the hash is detected
+        let mut forged = commitment.clone();
+        forged.version = 2;
+        assert!(!verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &forged, Some(2))
+            .unwrap());
+
+        // A proof bound to version 1 doesn't verify against version 2
+        let newer = commitment.clone().with_version(2);
+        assert!(!verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, &newer, Some(2))
+            .unwrap());
+
+        // ...nor does a proof not bound to any commitment
+        let unbound = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+        assert!(!verifier
+            .verify_with_commitment(&vk, &unbound, &public_inputs, &commitment, None)
+            .unwrap());
+    }
+
     #[test]
     fn test_verifier_verify() {
         // Test proof verification
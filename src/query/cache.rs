@@ -0,0 +1,167 @@
+//! Proof cache
+//!
+//! This module provides a cache of query results and their proofs, so that
+//! repeating a query against an unchanged database doesn't prove it again.
+//!
+//! Entries are keyed by the plan's `stable_hash` and the hash of the database
+//! commitment the query ran against. Any change to the database yields a new
+//! commitment hash, so stale proofs are never returned; entries for earlier
+//! commitments are dropped the first time a new one is seen.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use nzengi_db::query::ProofCache;
+//!
+//! let mut cache = ProofCache::new();
+//! let (result, proof) = db.query(&executor, &plan, &mut cache)?;
+//!
+//! // The same query against the same commitment is served from the cache
+//! db.query(&executor, &plan, &mut cache)?;
+//! assert_eq!(cache.hits(), 1);
+//! ```
+
+use crate::query::planner::ExecutionPlan;
+use crate::types::{Proof, QueryResult};
+use std::collections::HashMap;
+
+/// Cache of query results and proofs
+///
+/// Unlike proving key caching, which only skips key generation, a hit here
+/// skips execution and proving entirely. A cache must only be shared by
+/// executors with the same configuration (parameters, commitment, and
+/// aggregate-only mode), since the key doesn't cover it.
+#[derive(Debug, Clone, Default)]
+pub struct ProofCache {
+    /// Cached results keyed by (plan hash, commitment hash)
+    entries: HashMap<(String, String), (QueryResult, Proof)>,
+
+    /// Commitment hash of the cached entries (optional)
+    commitment_hash: Option<String>,
+
+    /// Number of lookups served from the cache
+    hits: usize,
+
+    /// Number of lookups not in the cache
+    misses: usize,
+}
+
+impl ProofCache {
+    /// Create a new empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up the result and proof of a plan
+    ///
+    /// Drops all entries if `commitment_hash` differs from the commitment
+    /// they were cached against.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan of the query
+    /// * `commitment_hash` - Hash of the database commitment queried
+    ///
+    /// # Returns
+    /// `Some((QueryResult, Proof))` on a hit, `None` otherwise
+    pub fn get(
+        &mut self,
+        plan: &ExecutionPlan,
+        commitment_hash: &str,
+    ) -> Option<(QueryResult, Proof)> {
+        self.invalidate_other(commitment_hash);
+
+        let key = (plan.stable_hash(), commitment_hash.to_string());
+        match self.entries.get(&key) {
+            Some(entry) => {
+                self.hits += 1;
+                Some(entry.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Cache the result and proof of a plan
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan of the query
+    /// * `commitment_hash` - Hash of the database commitment queried
+    /// * `result` - Query result
+    /// * `proof` - Proof of the result
+    pub fn insert(
+        &mut self,
+        plan: &ExecutionPlan,
+        commitment_hash: &str,
+        result: QueryResult,
+        proof: Proof,
+    ) {
+        self.invalidate_other(commitment_hash);
+        self.entries.insert(
+            (plan.stable_hash(), commitment_hash.to_string()),
+            (result, proof),
+        );
+    }
+
+    /// Drop all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.commitment_hash = None;
+    }
+
+    /// Get the number of cached entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the number of lookups served from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of lookups not in the cache
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Drop the entries of other commitments than `commitment_hash`
+    fn invalidate_other(&mut self, commitment_hash: &str) {
+        if self.commitment_hash.as_deref() != Some(commitment_hash) {
+            self.entries.clear();
+            self.commitment_hash = Some(commitment_hash.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::plan;
+
+    #[test]
+    fn test_proof_cache() {
+        let mut cache = ProofCache::new();
+        let count = plan("SELECT COUNT(*) FROM lineitem");
+        let sum = plan("SELECT SUM(l_quantity) FROM lineitem");
+        let result = QueryResult::new(vec!["COUNT(*)".to_string()]);
+        let proof = Proof::new(vec![1, 2, 3], vec![]);
+
+        assert!(cache.get(&count, "a").is_none());
+        cache.insert(&count, "a", result, proof);
+        let (_, cached) = cache.get(&count, "a").unwrap();
+        assert_eq!(cached.proof_bytes, vec![1, 2, 3]);
+        assert!(cache.get(&sum, "a").is_none());
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        // A new commitment drops the entries of the old one
+        assert!(cache.get(&count, "b").is_none());
+        assert!(cache.is_empty());
+        assert!(cache.get(&count, "a").is_none());
+    }
+}
@@ -0,0 +1,104 @@
+//! Query cancellation
+//!
+//! This module provides a cancellation token for stopping query execution,
+//! e.g. when a client disconnects or a query exceeds its time budget.
+//!
+//! The executor checks the token between pipeline stages (filter, group-by,
+//! aggregate, key generation, proving). A running stage is not interrupted,
+//! so cancellation takes effect at the next stage boundary.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::query::CancellationToken;
+//! use std::time::Duration;
+//!
+//! let token = CancellationToken::with_timeout(Duration::from_secs(30));
+//! let handle = token.clone();
+//!
+//! // From another thread, e.g. on client disconnect
+//! handle.cancel();
+//! assert!(token.is_cancelled());
+//! ```
+
+use crate::query::error::QueryError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Cancellation token shared between a query and its caller
+///
+/// Clones share the same cancellation state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Set once the token is cancelled
+    cancelled: Arc<AtomicBool>,
+
+    /// Time after which the token counts as cancelled (optional)
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Create a new token that is cancelled only explicitly
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new token that is also cancelled once `timeout` elapses
+    ///
+    /// # Arguments
+    /// * `timeout` - Time budget, starting now
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            deadline: Instant::now().checked_add(timeout),
+        }
+    }
+
+    /// Cancel the token and every clone of it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether the token was cancelled or its deadline passed
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Fail if the token was cancelled
+    ///
+    /// # Returns
+    /// `Ok(())` if execution may continue, `Err(QueryError::Cancelled)` otherwise
+    pub fn check(&self) -> Result<(), QueryError> {
+        if self.is_cancelled() {
+            Err(QueryError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let handle = token.clone();
+        assert!(token.check().is_ok());
+
+        handle.cancel();
+        assert!(token.is_cancelled());
+        assert!(matches!(token.check(), Err(QueryError::Cancelled)));
+    }
+
+    #[test]
+    fn test_cancellation_token_timeout() {
+        assert!(CancellationToken::with_timeout(Duration::ZERO).is_cancelled());
+        assert!(!CancellationToken::with_timeout(Duration::from_secs(3600)).is_cancelled());
+    }
+}
@@ -0,0 +1,87 @@
+//! Query error type
+//!
+//! This module provides the error type returned by the query parser,
+//! planner, optimizer, and executor, so callers can match on error kinds.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::query::{QueryError, QueryExecutor};
+//!
+//! match executor.execute(&plan, &tables) {
+//!     Err(QueryError::TableNotFound(table)) => println!("No table {}", table),
+//!     Err(e) => println!("Query failed: {}", e),
+//!     Ok((result, proof)) => println!("{} rows", result.rows.len()),
+//! }
+//! ```
+
+use thiserror::Error;
+
+/// Error returned by query processing
+#[derive(Debug, Error)]
+pub enum QueryError {
+    /// SQL text could not be parsed
+    #[error("Failed to parse SQL query: {0}")]
+    ParseError(String),
+
+    /// Table referenced by the query does not exist
+    #[error("Table {0} not found")]
+    TableNotFound(String),
+
+    /// Column referenced by the query does not exist
+    #[error("Column {0} not found")]
+    ColumnNotFound(String),
+
+    /// Unqualified column name matches columns of several joined tables
+    #[error("Column {0} is ambiguous")]
+    AmbiguousColumn(String),
+
+    /// Results combined by UNION ALL have different columns
+    #[error("Schema mismatch: {0}")]
+    SchemaMismatch(String),
+
+    /// Query uses SQL that is not supported
+    #[error("Unsupported feature: {0}")]
+    UnsupportedFeature(String),
+
+    /// Value could not be evaluated (e.g., overflow, division by zero)
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+
+    /// Circuit could not be built
+    #[error("Circuit error: {0}")]
+    CircuitError(String),
+
+    /// Key generation or proving failed
+    #[error("Proof error: {0}")]
+    ProofError(String),
+
+    /// The executor's commitment doesn't match the queried tables
+    #[error("Stale commitment: {0}")]
+    StaleCommitment(String),
+
+    /// Execution was cancelled or timed out
+    #[error("Query cancelled")]
+    Cancelled,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_error_display() {
+        let err = QueryError::TableNotFound("lineitem".to_string());
+        assert_eq!(err.to_string(), "Table lineitem not found");
+
+        let err = QueryError::ColumnNotFound("l_quantity".to_string());
+        assert_eq!(err.to_string(), "Column l_quantity not found");
+    }
+
+    #[test]
+    fn test_query_error_is_std_error() {
+        let err: Box<dyn std::error::Error> =
+            Box::new(QueryError::UnsupportedFeature("HAVING".to_string()));
+        assert_eq!(err.to_string(), "Unsupported feature: HAVING");
+    }
+}
@@ -19,14 +19,22 @@
 //! let (result, proof) = executor.execute(&plan, &database)?;
 //! ```
 
-use crate::circuit::NzengiCircuit;
-use crate::commitment::IPAParams;
+use crate::circuit::{CircuitBuilder, NzengiCircuit};
+use crate::commitment::{CellOpening, CommitmentBlinds, DatabaseCommitment, IPAParams};
+use crate::field::FieldUtils;
+use crate::gates::hll::{HyperLogLog, HLL_PRECISION};
+use crate::gates::{JoinConfig, ValidationMode};
 use crate::proof::Prover;
+use crate::query::cancel::CancellationToken;
+use crate::query::error::QueryError;
 use crate::query::planner::{
-    AggregationOperation, ExecutionPlan, FilterOperation, GroupByOperation, SortOperation,
+    AggregationFunction, AggregationOperation, ArithmeticOp, ExecutionPlan, FilterCondition,
+    FilterOperation, FilterTree, GroupByOperation, ProjectionExpr, SortOperation,
 };
-use crate::types::{QueryResult, Row, Table, Value};
+use crate::types::{Column, DataType, QueryResult, Row, Table, Value, DECIMAL_SCALE};
 use halo2_proofs::halo2curves::bn256::Fr as Field;
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 /// Query executor
@@ -36,6 +44,15 @@ use std::collections::HashMap;
 pub struct QueryExecutor {
     /// Public parameters for proof generation
     params: IPAParams,
+
+    /// Database commitment to open read cells against, with its blinds
+    commitment: Option<(DatabaseCommitment, CommitmentBlinds)>,
+
+    /// Whether results reveal only the public aggregate outputs
+    aggregate_only: bool,
+
+    /// How thoroughly the circuits' derived-witness gates check their witness
+    validation: ValidationMode,
 }
 
 impl QueryExecutor {
@@ -46,9 +63,75 @@ impl QueryExecutor {
     pub fn new(params: &IPAParams) -> Self {
         Self {
             params: params.clone(),
+            commitment: None,
+            aggregate_only: false,
+            validation: ValidationMode::Fast,
         }
     }
 
+    /// Execute queries against a database commitment
+    ///
+    /// Proofs then include openings of the committed cells the query reads:
+    /// for every column the plan references, the rows passing the filters.
+    /// Other cells are not revealed.
+    ///
+    /// # Arguments
+    /// * `commitment` - Commitment to the queried tables
+    /// * `blinds` - Blinds from `DatabaseCommitment::commit_database_with_blinds`
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_commitment(
+        mut self,
+        commitment: DatabaseCommitment,
+        blinds: CommitmentBlinds,
+    ) -> Self {
+        self.commitment = Some((commitment, blinds));
+        self
+    }
+
+    /// Get the database commitment queries are executed against, if any
+    pub fn commitment(&self) -> Option<&DatabaseCommitment> {
+        self.commitment.as_ref().map(|(commitment, _)| commitment)
+    }
+
+    /// Return only the public aggregate outputs of queries
+    ///
+    /// Queries must aggregate over the whole input (no GROUP BY or HAVING),
+    /// with every aggregate bound to a public output of the proof: COUNT,
+    /// a single SUM column, and MIN/MAX of a single column. The result is
+    /// [`QueryResult::aggregate_only`] and no committed cells are opened, so
+    /// the proof attests the aggregates without revealing any row.
+    ///
+    /// # Arguments
+    /// * `aggregate_only` - Whether to return aggregate-only results
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_aggregate_only(mut self, aggregate_only: bool) -> Self {
+        self.aggregate_only = aggregate_only;
+        self
+    }
+
+    /// Set how thoroughly the circuits' derived-witness gates check their
+    /// witness, for debugging
+    ///
+    /// With `Strict`, a circuit whose sort, join, group-by, aggregation, or
+    /// distinct-count witness is inconsistent fails to prove with a
+    /// descriptive error instead of producing a proof that doesn't verify
+    /// (see `NzengiCircuit::with_validation`). `Fast` (the default) skips
+    /// those checks.
+    ///
+    /// # Arguments
+    /// * `mode` - Validation mode of the circuits
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_validation(mut self, mode: ValidationMode) -> Self {
+        self.validation = mode;
+        self
+    }
+
     /// Execute a query plan and generate a proof
     ///
     /// # Arguments
@@ -61,311 +144,3176 @@ impl QueryExecutor {
         &self,
         plan: &ExecutionPlan,
         tables: &HashMap<String, Table>,
-    ) -> Result<(QueryResult, crate::types::Proof), Box<dyn std::error::Error>> {
-        // Get the first table (for now, we only support single-table queries)
-        let table_name = plan.tables.first().ok_or("No tables specified in query")?;
-        let table = tables
-            .get(table_name)
-            .ok_or_else(|| format!("Table {} not found", table_name))?;
+    ) -> Result<(QueryResult, crate::types::Proof), QueryError> {
+        self.execute_with_cancel(plan, tables, &CancellationToken::new())
+    }
 
-        // Apply filters
-        let mut filtered_rows = table.rows.clone();
-        for filter in &plan.filters {
-            filtered_rows = self.apply_filter(&filtered_rows, filter, table)?;
+    /// Execute a query plan and generate a proof, unless cancelled
+    ///
+    /// The token is checked between pipeline stages (filter, group-by,
+    /// aggregate, key generation, proving); a running stage completes before
+    /// cancellation takes effect.
+    ///
+    /// With a database commitment, an unfiltered `COUNT(*)` over a committed
+    /// table is answered by its committed row count without scanning rows.
+    /// The proof then only binds the commitment; the count is checked
+    /// against the committed `num_rows` with `Verifier::verify_committed_count`.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `tables` - Map of table names to tables
+    /// * `token` - Cancellation token for this query
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, Proof))` if execution succeeds,
+    /// `Err(QueryError::Cancelled)` if the token was cancelled,
+    /// `Err(QueryError::StaleCommitment)` if a committed row count doesn't
+    /// match the table, `Err` otherwise
+    pub fn execute_with_cancel(
+        &self,
+        plan: &ExecutionPlan,
+        tables: &HashMap<String, Table>,
+        token: &CancellationToken,
+    ) -> Result<(QueryResult, crate::types::Proof), QueryError> {
+        if !plan.union_all.is_empty() {
+            return Err(QueryError::UnsupportedFeature(
+                "UNION ALL in a single proof (use execute_union_all)".to_string(),
+            ));
         }
-        // Clone filtered_rows for circuit building (it may be used later)
-        let filtered_rows_for_circuit = filtered_rows.clone();
 
-        // Apply group-by (if any)
-        let grouped_data = if !plan.group_by.is_empty() {
-            self.apply_group_by(&filtered_rows, &plan.group_by[0], table)?
+        if let Some(num_rows) = self.committed_count(plan, tables)? {
+            return self.execute_committed_count(plan, num_rows, token);
+        }
+
+        // Materialize derived tables before the outer plan reads them
+        token.check()?;
+        let materialized = self.materialize_derived(plan, tables)?;
+        let table = Self::input_table(plan, &materialized)?;
+
+        let (result, filtered_rows) = self.evaluate_with_cancel(plan, &table, token)?;
+        let result = if self.aggregate_only {
+            Self::check_aggregate_only(plan, &table, &filtered_rows)?;
+            let values = result
+                .rows
+                .into_iter()
+                .next()
+                .map(|row| row.values)
+                .unwrap_or_default();
+            QueryResult::aggregate_only(result.columns, values)
         } else {
-            vec![filtered_rows]
+            result
         };
 
-        // Apply aggregations
-        let mut result_rows = vec![];
-        for group in &grouped_data {
-            let mut row_values = vec![];
-            for agg in &plan.aggregations {
-                let value = self.apply_aggregation(group, agg, table)?;
-                row_values.push(value);
+        // Build circuit over the filtered rows
+        let circuit = self.build_circuit(plan, &table, &filtered_rows)?;
+        let proof = self.prove(circuit, token)?;
+
+        // Open the committed cells the query read, unless only the
+        // aggregates are revealed
+        let proof = match &self.commitment {
+            Some((commitment, blinds)) if !self.aggregate_only => {
+                proof.with_openings(self.open_read_cells(plan, tables, commitment, blinds)?)
             }
-            result_rows.push(Row::new(row_values));
-        }
+            _ => proof,
+        };
 
-        // Apply sort (if any)
-        if !plan.sort.is_empty() {
-            result_rows = self.apply_sort(&result_rows, &plan.sort[0], table)?;
-        }
+        Ok((result, proof))
+    }
 
-        // Build circuit (use cloned filtered_rows)
-        let circuit = self.build_circuit(plan, table, &filtered_rows_for_circuit)?;
+    /// Get the committed row count answering an unfiltered `COUNT(*)`
+    ///
+    /// Applies to plans counting every row of a single table: no filters,
+    /// joins, grouping, HAVING, or derived tables. The count is the
+    /// `num_rows` of the table's column commitments, which must still
+    /// describe the queried table: same columns and row count.
+    ///
+    /// # Returns
+    /// `Ok(Some(num_rows))` if the plan is an unfiltered `COUNT(*)` over a
+    /// table in the executor's commitment, `Ok(None)` otherwise,
+    /// `Err(QueryError::StaleCommitment)` if the commitment doesn't match
+    /// the table
+    fn committed_count(
+        &self,
+        plan: &ExecutionPlan,
+        tables: &HashMap<String, Table>,
+    ) -> Result<Option<usize>, QueryError> {
+        let Some((commitment, _)) = self.commitment.as_ref() else {
+            return Ok(None);
+        };
+        let unfiltered_count = matches!(
+            plan.aggregations.as_slice(),
+            [AggregationOperation {
+                function: AggregationFunction::Count,
+                column: None,
+                ..
+            }]
+        ) && plan.filters.is_empty()
+            && plan.filter_tree.is_none()
+            && !plan.is_empty_result()
+            && plan.joins.is_empty()
+            && plan.group_by.is_empty()
+            && plan.having.is_none()
+            && plan.derived.is_empty()
+            && plan.union_all.is_empty();
+        let Some(table_name) = plan.tables.first().filter(|_| unfiltered_count) else {
+            return Ok(None);
+        };
+        let Some(table_commitment) = commitment.get_table_commitment(table_name) else {
+            return Ok(None);
+        };
 
-        // Generate proof
-        let prover = Prover::new(&self.params);
-        let (pk, _vk) = prover
-            .generate_keys(&circuit)
-            .map_err(|e| format!("Failed to generate keys: {}", e))?;
-        let proof = prover
-            .create_proof(&pk, &circuit, &[])
-            .map_err(|e| format!("Failed to create proof: {}", e))?;
+        let stale = |reason: String| {
+            Err(QueryError::StaleCommitment(format!(
+                "table {}: {}",
+                table_name, reason
+            )))
+        };
+        if !commitment.hash_matches() {
+            return stale("commitment hash doesn't match its contents".to_string());
+        }
+        let table = tables
+            .get(table_name)
+            .ok_or_else(|| QueryError::TableNotFound(table_name.clone()))?;
+        if table_commitment.column_commitments.len() != table.columns.len() {
+            return stale(format!(
+                "{} committed columns, the table has {}",
+                table_commitment.column_commitments.len(),
+                table.columns.len()
+            ));
+        }
+        for column in &table_commitment.column_commitments {
+            if table.get_column(&column.column_name).is_none() {
+                return stale(format!("column {} is not in the table", column.column_name));
+            }
+            if column.num_rows != table.rows.len() {
+                return stale(format!(
+                    "{} committed rows, the table has {}",
+                    column.num_rows,
+                    table.rows.len()
+                ));
+            }
+        }
+        Ok(Some(table.rows.len()))
+    }
 
-        // Create query result
-        let columns: Vec<String> = plan
+    /// Answer an unfiltered `COUNT(*)` from the committed row count
+    ///
+    /// The count is the committed `num_rows`, which the commitment hash
+    /// covers, so the proof only binds the commitment: its sole public input
+    /// is the commitment hash, and its size doesn't depend on the table. A
+    /// verifier checks the count with `Verifier::verify_committed_count`.
+    /// No committed cells are read or opened.
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, Proof))` with the single count row,
+    /// `Err(QueryError::Cancelled)` if the token was cancelled, `Err` otherwise
+    fn execute_committed_count(
+        &self,
+        plan: &ExecutionPlan,
+        num_rows: usize,
+        token: &CancellationToken,
+    ) -> Result<(QueryResult, crate::types::Proof), QueryError> {
+        let columns = plan
             .aggregations
             .iter()
-            .map(|agg| {
-                agg.alias
-                    .clone()
-                    .unwrap_or_else(|| format!("{:?}", agg.function))
-            })
+            .map(Self::aggregation_column)
             .collect();
-        let result = QueryResult {
-            columns,
-            rows: result_rows,
+        let values = vec![Value::BigInt(num_rows as i64)];
+        let result = if self.aggregate_only {
+            QueryResult::aggregate_only(columns, values)
+        } else {
+            QueryResult {
+                columns,
+                rows: vec![Row::new(values)],
+                aggregate_only: false,
+            }
         };
 
+        let proof = self.prove(NzengiCircuit::new(), token)?;
         Ok((result, proof))
     }
 
-    /// Apply a filter operation
-    fn apply_filter(
+    /// Generate keys for a circuit and prove it, unless cancelled
+    ///
+    /// With a database commitment, the proof is bound to it: the commitment
+    /// hash is its last public input (see `Verifier::verify_with_commitment`).
+    ///
+    /// # Returns
+    /// `Ok(Proof)` over the circuit's public inputs,
+    /// `Err(QueryError::Cancelled)` if the token was cancelled, `Err` otherwise
+    fn prove(
         &self,
-        rows: &[Row],
-        filter: &FilterOperation,
-        table: &Table,
-    ) -> Result<Vec<Row>, Box<dyn std::error::Error>> {
-        let column_idx = table
-            .columns
-            .iter()
-            .position(|c| c.name == filter.column)
-            .ok_or_else(|| format!("Column {} not found", filter.column))?;
-
-        let mut filtered = vec![];
-        for row in rows {
-            if let Some(value) = row.values.get(column_idx) {
-                if self.evaluate_filter_condition(value, &filter.condition) {
-                    filtered.push(row.clone());
-                }
-            }
-        }
-
-        Ok(filtered)
+        circuit: NzengiCircuit,
+        token: &CancellationToken,
+    ) -> Result<crate::types::Proof, QueryError> {
+        let circuit = match self.commitment() {
+            Some(commitment) => circuit.with_commitment_hash(commitment.hash_field()),
+            None => circuit,
+        };
+        token.check()?;
+        let prover = Prover::new(&self.params);
+        let (pk, _vk) = prover
+            .generate_keys(&circuit)
+            .map_err(|e| QueryError::ProofError(format!("Failed to generate keys: {}", e)))?;
+        token.check()?;
+        let proof = prover
+            .create_proof(&pk, &circuit, &circuit.public_inputs())
+            .map_err(|e| QueryError::ProofError(format!("Failed to create proof: {}", e)))?;
+        token.check()?;
+        Ok(proof)
     }
 
-    /// Evaluate a filter condition
-    fn evaluate_filter_condition(
+    /// Execute a UNION ALL plan, proving each branch separately
+    ///
+    /// The root plan and each plan in its `union_all` are executed on their
+    /// own and their rows are concatenated in order. Branches must return the
+    /// same number of columns, and columns must hold values of the same type
+    /// (NULLs match any type). Column names come from the first branch.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan with UNION ALL branches
+    /// * `tables` - Map of table names to tables
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, proofs))` with one proof per branch, in order,
+    /// `Err(QueryError::SchemaMismatch)` if branch results don't line up,
+    /// `Err` otherwise
+    pub fn execute_union_all(
         &self,
-        value: &Value,
-        condition: &crate::query::planner::FilterCondition,
-    ) -> bool {
-        match condition {
-            crate::query::planner::FilterCondition::GreaterThan(threshold) => {
-                // Simplified comparison - in production, you'd parse the threshold properly
-                match value {
-                    Value::Integer(v) => *v > threshold.parse::<i32>().unwrap_or(0),
-                    Value::BigInt(v) => *v > threshold.parse::<i64>().unwrap_or(0),
-                    _ => false,
+        plan: &ExecutionPlan,
+        tables: &HashMap<String, Table>,
+    ) -> Result<(QueryResult, Vec<crate::types::Proof>), QueryError> {
+        let head = ExecutionPlan {
+            union_all: vec![],
+            ..plan.clone()
+        };
+
+        let mut output: Option<(QueryResult, Vec<Option<DataType>>)> = None;
+        let mut proofs = Vec::with_capacity(plan.union_all.len() + 1);
+        for branch in std::iter::once(&head).chain(&plan.union_all) {
+            let (result, proof) = self.execute(branch, tables)?;
+            proofs.push(proof);
+            let types = Self::column_types(&result);
+
+            let Some((combined, combined_types)) = output.as_mut() else {
+                output = Some((result, types));
+                continue;
+            };
+            if result.columns.len() != combined.columns.len() {
+                return Err(QueryError::SchemaMismatch(format!(
+                    "UNION ALL branches return {} and {} columns",
+                    combined.columns.len(),
+                    result.columns.len()
+                )));
+            }
+            for (i, (expected, actual)) in combined_types.iter_mut().zip(types).enumerate() {
+                if let (Some(e), Some(a)) = (expected.as_ref(), actual.as_ref()) {
+                    if e != a {
+                        return Err(QueryError::SchemaMismatch(format!(
+                            "UNION ALL column {} is {:?} and {:?}",
+                            combined.columns[i], e, a
+                        )));
+                    }
+                }
+                if expected.is_none() {
+                    *expected = actual;
                 }
             }
-            crate::query::planner::FilterCondition::LessThan(threshold) => match value {
-                Value::Integer(v) => *v < threshold.parse::<i32>().unwrap_or(0),
-                Value::BigInt(v) => *v < threshold.parse::<i64>().unwrap_or(0),
-                _ => false,
-            },
-            crate::query::planner::FilterCondition::Equal(threshold) => match value {
-                Value::Integer(v) => *v == threshold.parse::<i32>().unwrap_or(0),
-                Value::BigInt(v) => *v == threshold.parse::<i64>().unwrap_or(0),
-                _ => false,
-            },
-            _ => false, // Other conditions not implemented yet
+            combined.rows.extend(result.rows);
         }
-    }
 
-    /// Apply a group-by operation
-    fn apply_group_by(
-        &self,
-        rows: &[Row],
-        _group_by: &GroupByOperation,
-        _table: &Table,
-    ) -> Result<Vec<Vec<Row>>, Box<dyn std::error::Error>> {
-        // Simplified group-by implementation
-        // In production, you'd properly group by the specified columns
-        Ok(vec![rows.to_vec()])
+        let (result, _) = output.expect("UNION ALL has at least one branch");
+        Ok((result, proofs))
     }
 
-    /// Apply an aggregation operation
-    fn apply_aggregation(
-        &self,
-        rows: &[Row],
-        agg: &AggregationOperation,
+    /// Check that every aggregate of a plan is a public output of its proof
+    ///
+    /// # Returns
+    /// `Ok(())` if the plan aggregates the whole input and the circuit
+    /// exposes each aggregate, `Err(QueryError::UnsupportedFeature)` otherwise
+    fn check_aggregate_only(
+        plan: &ExecutionPlan,
         table: &Table,
-    ) -> Result<Value, Box<dyn std::error::Error>> {
-        let column_idx = agg
-            .column
-            .as_ref()
-            .and_then(|col| table.columns.iter().position(|c| c.name == *col));
+        filtered_rows: &[Row],
+    ) -> Result<(), QueryError> {
+        if plan.aggregations.is_empty() || !plan.group_by.is_empty() || plan.having.is_some() {
+            return Err(QueryError::UnsupportedFeature(
+                "Aggregate-only results for queries other than whole-input aggregates".to_string(),
+            ));
+        }
 
-        match agg.function {
-            crate::query::planner::AggregationFunction::Count => {
-                Ok(Value::Integer(rows.len() as i32))
-            }
-            crate::query::planner::AggregationFunction::Sum => {
-                if let Some(idx) = column_idx {
-                    let sum: i64 = rows
-                        .iter()
-                        .filter_map(|r| r.values.get(idx))
-                        .filter_map(|v| match v {
-                            Value::Integer(i) => Some(*i as i64),
-                            Value::BigInt(b) => Some(*b),
-                            _ => None,
-                        })
-                        .sum();
-                    Ok(Value::BigInt(sum))
-                } else {
-                    Ok(Value::BigInt(0))
+        for agg in &plan.aggregations {
+            let proven = match agg.function {
+                AggregationFunction::Count => true,
+                AggregationFunction::Sum => {
+                    CircuitBuilder::sum_values(plan, table, filtered_rows)?.is_some()
                 }
-            }
-            crate::query::planner::AggregationFunction::Avg => {
-                if let Some(idx) = column_idx {
-                    let sum: i64 = rows
-                        .iter()
-                        .filter_map(|r| r.values.get(idx))
-                        .filter_map(|v| match v {
-                            Value::Integer(i) => Some(*i as i64),
-                            Value::BigInt(b) => Some(*b),
-                            _ => None,
-                        })
-                        .sum();
-                    let count = rows.len() as i64;
-                    Ok(Value::BigInt(if count > 0 { sum / count } else { 0 }))
-                } else {
-                    Ok(Value::BigInt(0))
+                AggregationFunction::Min | AggregationFunction::Max => {
+                    CircuitBuilder::minmax_values(plan, table, filtered_rows)?.is_some()
                 }
+                _ => false,
+            };
+            if !proven {
+                return Err(QueryError::UnsupportedFeature(format!(
+                    "{}({}) is not a public output of the proof",
+                    agg.function,
+                    agg.column.as_deref().unwrap_or("*")
+                )));
             }
-            _ => Ok(Value::Integer(0)), // Other aggregations not implemented yet
         }
+        Ok(())
     }
 
-    /// Apply a sort operation
-    fn apply_sort(
-        &self,
-        rows: &[Row],
-        _sort: &SortOperation,
-        _table: &Table,
-    ) -> Result<Vec<Row>, Box<dyn std::error::Error>> {
-        // Simplified sort implementation
-        // In production, you'd properly sort by the specified columns
-        Ok(rows.to_vec())
+    /// Type of each result column, from its first non-NULL value
+    fn column_types(result: &QueryResult) -> Vec<Option<DataType>> {
+        (0..result.columns.len())
+            .map(|i| {
+                result
+                    .rows
+                    .iter()
+                    .filter_map(|r| r.values.get(i))
+                    .find_map(Self::value_type)
+            })
+            .collect()
     }
 
-    /// Build a circuit from an execution plan
-    fn build_circuit(
+    /// Data type of a value (`None` for NULL)
+    fn value_type(value: &Value) -> Option<DataType> {
+        match value {
+            Value::Integer(_) => Some(DataType::Integer),
+            Value::BigInt(_) => Some(DataType::BigInt),
+            Value::Decimal(_) => Some(DataType::Decimal),
+            Value::String(_) => Some(DataType::Varchar(usize::MAX)),
+            Value::Date(_) => Some(DataType::Date),
+            Value::Boolean(_) => Some(DataType::Boolean),
+            Value::Null => None,
+        }
+    }
+
+    /// Open the committed cells a plan reads
+    ///
+    /// # Returns
+    /// `Ok(openings)` with one opening per read column, covering the rows
+    /// that pass the filters (none for joined or derived tables, whose rows
+    /// aren't committed cells), `Err` if the table doesn't match its
+    /// commitment
+    fn open_read_cells(
         &self,
         plan: &ExecutionPlan,
-        table: &Table,
-        filtered_rows: &[Row],
-    ) -> Result<NzengiCircuit, Box<dyn std::error::Error>> {
-        let mut circuit = NzengiCircuit::new();
+        tables: &HashMap<String, Table>,
+        commitment: &DatabaseCommitment,
+        blinds: &CommitmentBlinds,
+    ) -> Result<Vec<CellOpening>, QueryError> {
+        if !plan.joins.is_empty() || !plan.derived.is_empty() {
+            return Ok(vec![]);
+        }
+        let table = Self::plan_table(plan, tables)?;
+        if table.rows.is_empty() {
+            return Ok(vec![]);
+        }
 
-        // Add range check gates for filters
-        for filter in &plan.filters {
-            if let Some(value) = self.extract_filter_value(filter, table, filtered_rows) {
-                let u8_cells = crate::field::FieldUtils::decompose_u64(value);
-                circuit = circuit.with_range_check(value, u8_cells.to_vec());
+        // Rows passing every filter and the filter tree
+        let filter_indices = plan
+            .filters
+            .iter()
+            .map(|filter| Self::filter_column_indices(table, filter))
+            .collect::<Result<Vec<_>, _>>()?;
+        for leaf in plan.filter_tree.iter().flat_map(FilterTree::leaves) {
+            Self::filter_column_indices(table, leaf)?;
+        }
+        let rows: Vec<usize> = table
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| {
+                plan.filters
+                    .iter()
+                    .zip(&filter_indices)
+                    .all(|(filter, &indices)| {
+                        Self::evaluate_filter_row(row, indices, &filter.condition)
+                    })
+                    && plan.filter_tree.as_ref().is_none_or(|tree| {
+                        tree.evaluate(&mut |leaf| Self::filter_holds(table, leaf, row))
+                    })
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+
+        Self::read_columns(plan, table)
+            .into_iter()
+            .map(|col_idx| {
+                let column = &table.columns[col_idx];
+                let committed = commitment
+                    .get_table_commitment(&table.name)
+                    .and_then(|t| t.get_column_commitment(&column.name))
+                    .filter(|c| c.num_rows == table.rows.len());
+                let blind = committed
+                    .and_then(|_| blinds.get(&table.name, &column.name))
+                    .ok_or_else(|| {
+                        QueryError::ProofError(format!(
+                            "Column {} does not match its commitment",
+                            column.name
+                        ))
+                    })?;
+
+                let values = table.column_fields(&column.name).unwrap_or_default();
+                CellOpening::create(
+                    &table.name,
+                    &column.name,
+                    &values,
+                    blind,
+                    &rows,
+                    &self.params,
+                )
+                .map_err(|e| {
+                    QueryError::ProofError(format!("Failed to open committed cells: {}", e))
+                })
+            })
+            .collect()
+    }
+
+    /// Get the indices of the table columns whose cells are opened with the
+    /// proof: the columns the plan reads, with a commitment and unless only
+    /// the aggregates are revealed (see `open_read_cells`)
+    fn opened_columns(&self, plan: &ExecutionPlan, table: &Table) -> Vec<usize> {
+        match &self.commitment {
+            Some(_)
+                if !self.aggregate_only
+                    && plan.joins.is_empty()
+                    && plan.derived.is_empty()
+                    && !table.rows.is_empty() =>
+            {
+                Self::read_columns(plan, table)
             }
+            _ => vec![],
         }
+    }
 
-        // Add aggregation gates
-        if !plan.aggregations.is_empty() {
-            let values: Vec<Field> = filtered_rows
-                .iter()
-                .flat_map(|r| r.values.iter().map(|v| v.to_field()))
-                .collect();
-            let binary_markers = vec![Field::from(1u64); values.len()];
-            let start_indices = vec![Field::zero()];
-            let end_indices = vec![Field::from(values.len() as u64)];
-            circuit = circuit.with_aggregation(values, binary_markers, start_indices, end_indices);
+    /// Get the indices of the table columns a plan reads
+    ///
+    /// Names that aren't table columns (computed columns, aliases) are skipped.
+    fn read_columns(plan: &ExecutionPlan, table: &Table) -> Vec<usize> {
+        if plan.projection.iter().any(|column| column == "*") {
+            return (0..table.columns.len()).collect();
         }
 
-        // Add sort gates
-        if !plan.sort.is_empty() {
-            let input_values: Vec<Field> = filtered_rows
-                .iter()
-                .flat_map(|r| r.values.iter().map(|v| v.to_field()))
-                .collect();
-            let mut sorted_values = input_values.clone();
-            sorted_values.sort(); // Simplified sort
-            let alpha = Field::from(42u64); // Random alpha
-            circuit = circuit.with_sort(input_values, sorted_values, alpha);
+        let computed: Vec<String> = plan
+            .computed
+            .iter()
+            .flat_map(|c| c.expr.columns())
+            .collect();
+        let mut names: Vec<&str> = vec![];
+        let tree_leaves = plan.filter_tree.iter().flat_map(FilterTree::leaves);
+        for filter in plan.filters.iter().chain(tree_leaves) {
+            names.push(&filter.column);
+            if let FilterCondition::ColumnCompare { other_column, .. } = &filter.condition {
+                names.push(other_column);
+            }
         }
+        names.extend(
+            plan.group_by
+                .iter()
+                .flat_map(|g| g.columns.iter().map(String::as_str)),
+        );
+        names.extend(plan.aggregations.iter().filter_map(|a| a.column.as_deref()));
+        names.extend(
+            plan.sort
+                .iter()
+                .flat_map(|s| s.columns.iter().map(String::as_str)),
+        );
+        names.extend(computed.iter().map(String::as_str));
+        names.extend(plan.projection.iter().map(String::as_str));
 
-        Ok(circuit)
+        let mut indices: Vec<usize> = names
+            .into_iter()
+            .filter_map(|name| Self::column_index(table, name).ok())
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        indices
     }
 
-    /// Extract filter value from a filter operation
-    fn extract_filter_value(
-        &self,
-        _filter: &FilterOperation,
-        _table: &Table,
-        _rows: &[Row],
-    ) -> Option<u64> {
-        // Simplified - in production, you'd properly extract the value
-        Some(10u64)
+    /// Get the table a plan reads from
+    ///
+    /// This is the first table, which is the left side of any joins.
+    fn plan_table<'a>(
+        plan: &ExecutionPlan,
+        tables: &'a HashMap<String, Table>,
+    ) -> Result<&'a Table, QueryError> {
+        let table_name = plan
+            .tables
+            .first()
+            .ok_or_else(|| QueryError::UnsupportedFeature("Query without tables".to_string()))?;
+        tables
+            .get(table_name)
+            .ok_or_else(|| QueryError::TableNotFound(table_name.clone()))
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::types::Column;
 
-    #[test]
-    fn test_executor_new() {
-        let params = IPAParams::new(10);
-        let executor = QueryExecutor::new(&params);
-        assert!(true); // Executor created successfully
+    /// Get the rows a plan operates on
+    ///
+    /// Without joins this is the plan's table. With joins, the tables are
+    /// joined and every later stage (filters, aggregations, projection) runs
+    /// over the joined rows.
+    fn input_table<'a>(
+        plan: &ExecutionPlan,
+        tables: &'a HashMap<String, Table>,
+    ) -> Result<Cow<'a, Table>, QueryError> {
+        if plan.joins.is_empty() {
+            Self::plan_table(plan, tables).map(Cow::Borrowed)
+        } else {
+            Self::join_tables(plan, tables).map(Cow::Owned)
+        }
     }
 
-    #[test]
-    fn test_executor_execute_simple() {
-        let params = IPAParams::new(10);
-        let executor = QueryExecutor::new(&params);
+    /// Join the tables of a plan
+    ///
+    /// Columns of the joined table are qualified by their table name
+    /// (`orders.o_custkey`). Rows are matched on their join keys (see
+    /// [`QueryExecutor::join_key`]), so rows with a NULL join key never match.
+    ///
+    /// # Returns
+    /// `Ok(Table)` with one row per matching tuple, `Err` if a table or join
+    /// column doesn't exist or a join value has no join key
+    fn join_tables(
+        plan: &ExecutionPlan,
+        tables: &HashMap<String, Table>,
+    ) -> Result<Table, QueryError> {
+        let base = Self::plan_table(plan, tables)?;
+        let mut joined = Table {
+            name: base.name.clone(),
+            columns: Self::qualified_columns(base),
+            rows: base.rows.clone(),
+        };
 
-        // Create a simple table
-        let mut table = Table::new(
+        for join in &plan.joins {
+            let right = tables
+                .get(&join.right_table)
+                .ok_or_else(|| QueryError::TableNotFound(join.right_table.clone()))?;
+            let left_idx = Self::column_index(
+                &joined,
+                &format!("{}.{}", join.left_table, join.left_column),
+            )?;
+            let right_idx = Self::column_index(right, &join.right_column)?;
+            let left_type = &joined.columns[left_idx].data_type;
+            let right_type = &right.columns[right_idx].data_type;
+
+            let right_keys = right
+                .rows
+                .iter()
+                .map(|row| Self::join_key(row.values.get(right_idx), right_type))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut rows = vec![];
+            for left_row in &joined.rows {
+                let key = match Self::join_key(left_row.values.get(left_idx), left_type)? {
+                    Some(key) => key,
+                    None => continue,
+                };
+                for (right_row, _) in right
+                    .rows
+                    .iter()
+                    .zip(&right_keys)
+                    .filter(|(_, right_key)| **right_key == Some(key))
+                {
+                    let mut values = left_row.values.clone();
+                    values.extend(right_row.values.iter().cloned());
+                    rows.push(Row::new(values));
+                }
+            }
+
+            joined.name = format!("{}_{}", joined.name, right.name);
+            joined.columns.extend(Self::qualified_columns(right));
+            joined.rows = rows;
+        }
+
+        Ok(joined)
+    }
+
+    /// Get the join key of a value
+    ///
+    /// Columns with an integer encoding go through the checked composite
+    /// key of the join gate ([`JoinConfig::composite_join_key`]) with the bit
+    /// width of the column type, so `INTEGER` and `BIGINT` keys of equal
+    /// value match. Strings use their hashed field encoding.
+    ///
+    /// # Returns
+    /// `Ok(Some(key))`, `Ok(None)` for a `NULL` or missing value, `Err` if
+    /// an integer value is negative or doesn't fit the key width of its
+    /// column type
+    fn join_key(value: Option<&Value>, data_type: &DataType) -> Result<Option<Field>, QueryError> {
+        let value = match value {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        match data_type.range_bits() {
+            Some(bits) => JoinConfig::composite_join_key(std::slice::from_ref(value), &[bits])
+                .map_err(|e| QueryError::InvalidValue(format!("Join key: {}", e))),
+            None => Ok(JoinConfig::join_key(value)),
+        }
+    }
+
+    /// Columns of a table qualified by the table name
+    fn qualified_columns(table: &Table) -> Vec<Column> {
+        table
+            .columns
+            .iter()
+            .map(|c| Column::new(format!("{}.{}", table.name, c.name), c.data_type.clone()))
+            .collect()
+    }
+
+    /// Evaluate a plan against a table without generating a proof
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, filtered_rows))` with the query result and the rows
+    /// that passed the filters
+    fn evaluate(
+        &self,
+        plan: &ExecutionPlan,
+        table: &Table,
+    ) -> Result<(QueryResult, Vec<Row>), QueryError> {
+        self.evaluate_with_cancel(plan, table, &CancellationToken::new())
+    }
+
+    /// Evaluate a plan, checking the token between stages
+    ///
+    /// # Returns
+    /// `Ok((QueryResult, filtered_rows))` with the query result and the rows
+    /// that passed the filters, `Err(QueryError::Cancelled)` if the token was
+    /// cancelled
+    fn evaluate_with_cancel(
+        &self,
+        plan: &ExecutionPlan,
+        table: &Table,
+        token: &CancellationToken,
+    ) -> Result<(QueryResult, Vec<Row>), QueryError> {
+        // Apply filters (none of the rows pass a false constant filter)
+        let mut filtered_rows = if plan.is_empty_result() {
+            vec![]
+        } else {
+            table.rows.clone()
+        };
+        for filter in &plan.filters {
+            token.check()?;
+            filtered_rows = self.apply_filter(&filtered_rows, filter, table)?;
+        }
+        if let Some(tree) = &plan.filter_tree {
+            token.check()?;
+            filtered_rows = Self::apply_filter_tree(&filtered_rows, tree, table)?;
+        }
+        token.check()?;
+        // Keep filtered_rows for circuit building
+        let filtered_rows_for_circuit = filtered_rows.clone();
+
+        // HAVING needs the groups even without aggregations
+        let projection_only =
+            plan.aggregations.is_empty() && !plan.projection.is_empty() && plan.having.is_none();
+        let (columns, result_rows) = if projection_only {
+            // Queries without aggregations return projected rows in sort order
+            let sorted_rows = match plan.sort.first() {
+                Some(sort) => self.apply_sort(&filtered_rows, sort, table)?,
+                None => filtered_rows,
+            };
+            self.apply_projection(&sorted_rows, plan, table)?
+        } else {
+            // Apply group-by (if any); each result row starts with its group key
+            let (key_columns, grouped_data) = match plan.group_by.first() {
+                Some(group_by) => (
+                    group_by.columns.clone(),
+                    self.apply_group_by(&filtered_rows, group_by, table)?,
+                ),
+                None => (vec![], vec![(vec![], filtered_rows)]),
+            };
+
+            // Drop the groups failing HAVING
+            let grouped_data: Vec<_> = match &plan.having {
+                Some(having) => grouped_data
+                    .into_iter()
+                    .filter(|(_, group)| having.holds(group.len() as u64))
+                    .collect(),
+                None => grouped_data,
+            };
+
+            // Apply aggregations
+            let mut result_rows = vec![];
+            for (key, group) in &grouped_data {
+                token.check()?;
+                let mut row_values = key.clone();
+                for agg in &plan.aggregations {
+                    let value = self.apply_aggregation(group, agg, table)?;
+                    row_values.push(value);
+                }
+                result_rows.push(Row::new(row_values));
+            }
+
+            let columns: Vec<String> = key_columns
+                .into_iter()
+                .chain(plan.aggregations.iter().map(Self::aggregation_column))
+                .collect();
+
+            // Sort aggregated rows by the result columns named in ORDER BY
+            let result_rows = match plan.sort.first() {
+                Some(sort) => {
+                    let keys = sort
+                        .columns
+                        .iter()
+                        .zip(&sort.ascending)
+                        .filter_map(|(name, &ascending)| {
+                            columns
+                                .iter()
+                                .position(|c| c == name)
+                                .map(|i| (i, ascending))
+                        })
+                        .collect::<Vec<_>>();
+                    Self::sort_rows(&result_rows, &keys)
+                }
+                None => result_rows,
+            };
+            (columns, result_rows)
+        };
+
+        // Create query result
+        let result = QueryResult {
+            columns,
+            rows: result_rows,
+            aggregate_only: false,
+        };
+
+        token.check()?;
+        Ok((result, filtered_rows_for_circuit))
+    }
+
+    /// Result column name of an aggregation: its alias, or e.g. `COUNT(*)`
+    fn aggregation_column(agg: &AggregationOperation) -> String {
+        agg.alias.clone().unwrap_or_else(|| match &agg.column {
+            Some(column) => format!("{}({})", agg.function, column),
+            None => format!("{}(*)", agg.function),
+        })
+    }
+
+    /// Materialize the derived tables of a plan
+    ///
+    /// Each subquery is run first (after materializing its own derived
+    /// tables), and its result is registered as a temporary table named by
+    /// its alias, which the outer plan reads. Only the outer query is
+    /// proven, over the subquery's rows.
+    ///
+    /// # Returns
+    /// `Ok(tables)` with the derived tables added (borrowed without any),
+    /// `Err(QueryError::UnsupportedFeature)` for UNION ALL in a subquery,
+    /// `Err` if a subquery fails
+    fn materialize_derived<'a>(
+        &self,
+        plan: &ExecutionPlan,
+        tables: &'a HashMap<String, Table>,
+    ) -> Result<Cow<'a, HashMap<String, Table>>, QueryError> {
+        if plan.derived.is_empty() {
+            return Ok(Cow::Borrowed(tables));
+        }
+
+        let mut materialized = tables.clone();
+        for derived in &plan.derived {
+            if !derived.plan.union_all.is_empty() {
+                return Err(QueryError::UnsupportedFeature(
+                    "UNION ALL in a subquery".to_string(),
+                ));
+            }
+            let inner_tables = self.materialize_derived(&derived.plan, tables)?;
+            let source = Self::input_table(&derived.plan, &inner_tables)?;
+            let (result, _) = self.evaluate(&derived.plan, &source)?;
+            let table = Self::result_table(&derived.alias, result, &source);
+            materialized.insert(derived.alias.clone(), table);
+        }
+
+        Ok(Cow::Owned(materialized))
+    }
+
+    /// Build a temporary table from a query result
+    ///
+    /// Column types come from the source table when the names match,
+    /// otherwise from the first non-NULL value (defaulting to `BigInt`).
+    fn result_table(name: &str, result: QueryResult, source: &Table) -> Table {
+        let types = Self::column_types(&result);
+        let columns = result
+            .columns
+            .iter()
+            .zip(types)
+            .map(|(column_name, value_type)| {
+                let data_type = source
+                    .columns
+                    .iter()
+                    .find(|c| c.name == *column_name)
+                    .map(|c| c.data_type.clone())
+                    .or(value_type)
+                    .unwrap_or(DataType::BigInt);
+                Column::new(column_name.clone(), data_type)
+            })
+            .collect();
+
+        let mut table = Table::new(name.to_string(), columns);
+        table.rows = result.rows;
+        table
+    }
+
+    /// Get the index of a column in a table
+    ///
+    /// Besides exact matches, a name qualified by the table's own name
+    /// (`lineitem.l_quantity`) resolves to the unqualified column, and an
+    /// unqualified name resolves to a qualified column of a joined table.
+    ///
+    /// # Returns
+    /// `Ok(index)` of the column, `Err(ColumnNotFound)` if no column matches
+    /// (including a qualifier naming another table), `Err(AmbiguousColumn)`
+    /// if an unqualified name matches columns of several joined tables
+    pub(crate) fn column_index(table: &Table, name: &str) -> Result<usize, QueryError> {
+        if let Some(idx) = table.columns.iter().position(|c| c.name == name) {
+            return Ok(idx);
+        }
+
+        if let Some((qualifier, bare)) = name.rsplit_once('.') {
+            return table
+                .columns
+                .iter()
+                .position(|c| qualifier == table.name && c.name == bare)
+                .ok_or_else(|| QueryError::ColumnNotFound(name.to_string()));
+        }
+
+        let suffix = format!(".{}", name);
+        let mut matches = table
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.name.ends_with(&suffix));
+
+        match (matches.next(), matches.next()) {
+            (Some((idx, _)), None) => Ok(idx),
+            (Some(_), Some(_)) => Err(QueryError::AmbiguousColumn(name.to_string())),
+            _ => Err(QueryError::ColumnNotFound(name.to_string())),
+        }
+    }
+
+    /// Apply a filter operation
+    fn apply_filter(
+        &self,
+        rows: &[Row],
+        filter: &FilterOperation,
+        table: &Table,
+    ) -> Result<Vec<Row>, QueryError> {
+        let indices = Self::filter_column_indices(table, filter)?;
+
+        Ok(rows
+            .iter()
+            .filter(|row| Self::evaluate_filter_row(row, indices, &filter.condition))
+            .cloned()
+            .collect())
+    }
+
+    /// Apply a filter tree to rows
+    ///
+    /// # Returns
+    /// `Ok(Vec<Row>)` with the rows satisfying the tree, `Err` if a leaf
+    /// reads a missing column
+    fn apply_filter_tree(
+        rows: &[Row],
+        tree: &FilterTree,
+        table: &Table,
+    ) -> Result<Vec<Row>, QueryError> {
+        for leaf in tree.leaves() {
+            Self::filter_column_indices(table, leaf)?;
+        }
+
+        Ok(rows
+            .iter()
+            .filter(|row| tree.evaluate(&mut |leaf| Self::filter_holds(table, leaf, row)))
+            .cloned()
+            .collect())
+    }
+
+    /// Evaluate a single filter against a row
+    ///
+    /// # Returns
+    /// `true` if the row satisfies the filter, `false` otherwise or if the
+    /// filter reads a missing column
+    pub(crate) fn filter_holds(table: &Table, filter: &FilterOperation, row: &Row) -> bool {
+        Self::filter_column_indices(table, filter)
+            .is_ok_and(|indices| Self::evaluate_filter_row(row, indices, &filter.condition))
+    }
+
+    /// Get the indices of the columns a filter reads
+    ///
+    /// # Returns
+    /// `Ok((column, other))` where `other` is the right-hand column of a
+    /// column-to-column comparison, `Err` if a column doesn't exist
+    pub(crate) fn filter_column_indices(
+        table: &Table,
+        filter: &FilterOperation,
+    ) -> Result<(usize, Option<usize>), QueryError> {
+        let column_idx = Self::column_index(table, &filter.column)?;
+        let other_idx = match &filter.condition {
+            FilterCondition::ColumnCompare { other_column, .. } => {
+                Some(Self::column_index(table, other_column)?)
+            }
+            _ => None,
+        };
+        Ok((column_idx, other_idx))
+    }
+
+    /// Evaluate a filter against a row
+    ///
+    /// # Arguments
+    /// * `row` - Row to evaluate
+    /// * `indices` - Column indices from `filter_column_indices`
+    /// * `condition` - Filter condition
+    ///
+    /// # Returns
+    /// `true` if the row satisfies the condition
+    fn evaluate_filter_row(
+        row: &Row,
+        (column_idx, other_idx): (usize, Option<usize>),
+        condition: &FilterCondition,
+    ) -> bool {
+        let Some(value) = row.values.get(column_idx) else {
+            return false;
+        };
+
+        match (condition, other_idx.and_then(|idx| row.values.get(idx))) {
+            (FilterCondition::ColumnCompare { op, .. }, Some(other)) => {
+                Self::compare_values(value, other).is_some_and(|ordering| op.holds(ordering))
+            }
+            (FilterCondition::ColumnCompare { .. }, None) => false,
+            _ => Self::evaluate_filter_condition(value, condition),
+        }
+    }
+
+    /// Compare two values of compatible types
+    ///
+    /// Numeric values of different types are compared after promotion to
+    /// the wider type (see [`QueryExecutor::promote`]).
+    ///
+    /// # Returns
+    /// `Some(Ordering)` for comparable values, `None` if either is NULL or the
+    /// types differ
+    fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+        match Self::promote(a, b) {
+            (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(&b)),
+            (Value::BigInt(a), Value::BigInt(b)) | (Value::Decimal(a), Value::Decimal(b)) => {
+                Some(a.cmp(&b))
+            }
+            (Value::Date(a), Value::Date(b)) => Some(a.cmp(&b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(&b)),
+            (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(&b)),
+            _ => None,
+        }
+    }
+
+    /// Promote two values to a common numeric type
+    ///
+    /// The narrower of two numeric values is cast to the type of the other
+    /// with [`Value::cast`]; other pairs are returned unchanged.
+    ///
+    /// # Returns
+    /// The two values, in order, after promotion
+    fn promote(a: &Value, b: &Value) -> (Value, Value) {
+        let cast_to = |from: &Value, to: &Value| {
+            Self::value_type(to).and_then(|data_type| from.cast(&data_type))
+        };
+        match (cast_to(a, b), cast_to(b, a)) {
+            (Some(a), _) => (a, b.clone()),
+            (None, Some(b)) => (a.clone(), b),
+            (None, None) => (a.clone(), b.clone()),
+        }
+    }
+
+    /// Compare a date value against a filter literal
+    ///
+    /// The literal is parsed as a date (ISO `YYYY-MM-DD` or Unix seconds,
+    /// as the planner emits for `DATE '...'` literals).
+    ///
+    /// # Returns
+    /// `Some(Ordering)` of the value relative to the literal, `None` if the
+    /// literal isn't a date
+    fn compare_date(value: &Value, literal: &str) -> Option<Ordering> {
+        let literal = Value::parse(literal, &DataType::Date).ok()?;
+        Self::compare_values(value, &literal)
+    }
+
+    /// Evaluate a filter condition
+    fn evaluate_filter_condition(
+        value: &Value,
+        condition: &crate::query::planner::FilterCondition,
+    ) -> bool {
+        match condition {
+            crate::query::planner::FilterCondition::GreaterThan(threshold) => {
+                // Simplified comparison - in production, you'd parse the threshold properly
+                match value {
+                    Value::Integer(v) => *v > threshold.parse::<i32>().unwrap_or(0),
+                    Value::BigInt(v) => *v > threshold.parse::<i64>().unwrap_or(0),
+                    Value::Date(_) => {
+                        Self::compare_date(value, threshold) == Some(Ordering::Greater)
+                    }
+                    _ => false,
+                }
+            }
+            crate::query::planner::FilterCondition::LessThan(threshold) => match value {
+                Value::Integer(v) => *v < threshold.parse::<i32>().unwrap_or(0),
+                Value::BigInt(v) => *v < threshold.parse::<i64>().unwrap_or(0),
+                Value::Date(_) => Self::compare_date(value, threshold) == Some(Ordering::Less),
+                _ => false,
+            },
+            crate::query::planner::FilterCondition::GreaterOrEqual(threshold) => match value {
+                Value::Integer(v) => *v >= threshold.parse::<i32>().unwrap_or(0),
+                Value::BigInt(v) => *v >= threshold.parse::<i64>().unwrap_or(0),
+                Value::Date(_) => Self::compare_date(value, threshold).is_some_and(Ordering::is_ge),
+                _ => false,
+            },
+            crate::query::planner::FilterCondition::LessOrEqual(threshold) => match value {
+                Value::Integer(v) => *v <= threshold.parse::<i32>().unwrap_or(0),
+                Value::BigInt(v) => *v <= threshold.parse::<i64>().unwrap_or(0),
+                Value::Date(_) => Self::compare_date(value, threshold).is_some_and(Ordering::is_le),
+                _ => false,
+            },
+            crate::query::planner::FilterCondition::Equal(threshold) => match value {
+                Value::Integer(v) => *v == threshold.parse::<i32>().unwrap_or(0),
+                Value::BigInt(v) => *v == threshold.parse::<i64>().unwrap_or(0),
+                Value::Date(_) => Self::compare_date(value, threshold) == Some(Ordering::Equal),
+                _ => false,
+            },
+            crate::query::planner::FilterCondition::Between(low, high) => {
+                if let Value::Date(_) = value {
+                    return Self::compare_date(value, low).is_some_and(Ordering::is_ge)
+                        && Self::compare_date(value, high).is_some_and(Ordering::is_le);
+                }
+                let (low, high) = (
+                    low.parse::<i64>().unwrap_or(0),
+                    high.parse::<i64>().unwrap_or(0),
+                );
+                match value {
+                    Value::Integer(v) => (low..=high).contains(&(*v as i64)),
+                    Value::BigInt(v) => (low..=high).contains(v),
+                    _ => false,
+                }
+            }
+            crate::query::planner::FilterCondition::Like(pattern) => match value {
+                Value::String(s) => Self::like_matches(s, pattern),
+                _ => false,
+            },
+            _ => false, // Other conditions not implemented yet
+        }
+    }
+
+    /// Match a string against a LIKE pattern
+    ///
+    /// `%` matches any sequence of characters and `_` any single character;
+    /// escapes are not supported.
+    ///
+    /// # Returns
+    /// `true` if the whole string matches the pattern
+    fn like_matches(value: &str, pattern: &str) -> bool {
+        let value: Vec<char> = value.chars().collect();
+        let pattern: Vec<char> = pattern.chars().collect();
+
+        // Greedy match, backtracking to the last `%` on a mismatch
+        let (mut v, mut p) = (0, 0);
+        let mut last_wildcard: Option<(usize, usize)> = None;
+        while v < value.len() {
+            match pattern.get(p) {
+                Some('%') => {
+                    last_wildcard = Some((p, v));
+                    p += 1;
+                }
+                Some(&c) if c == '_' || c == value[v] => {
+                    v += 1;
+                    p += 1;
+                }
+                _ => match last_wildcard {
+                    // Let the `%` absorb one more character and retry
+                    Some((wildcard_p, wildcard_v)) => {
+                        last_wildcard = Some((wildcard_p, wildcard_v + 1));
+                        p = wildcard_p + 1;
+                        v = wildcard_v + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+        pattern[p..].iter().all(|&c| c == '%')
+    }
+
+    /// Apply a group-by operation
+    ///
+    /// Rows are grouped by the values of the group-by columns. Groups are
+    /// ordered by key (NULLs first), as in the sorted input of the group-by
+    /// gate.
+    ///
+    /// # Returns
+    /// `Ok(groups)` as (key values, rows) pairs, `Err` if a group-by column
+    /// doesn't exist
+    fn apply_group_by(
+        &self,
+        rows: &[Row],
+        group_by: &GroupByOperation,
+        table: &Table,
+    ) -> Result<Vec<(Vec<Value>, Vec<Row>)>, QueryError> {
+        let indices = group_by
+            .columns
+            .iter()
+            .map(|column| Self::column_index(table, column))
+            .collect::<Result<Vec<_>, _>>()?;
+        let keys: Vec<(usize, bool)> = indices.iter().map(|&idx| (idx, true)).collect();
+
+        let mut groups: Vec<(Vec<Value>, Vec<Row>)> = vec![];
+        for row in Self::sort_rows(rows, &keys) {
+            let key: Vec<Value> = indices
+                .iter()
+                .map(|&idx| row.values.get(idx).cloned().unwrap_or(Value::Null))
+                .collect();
+            match groups.last_mut() {
+                Some((last, members)) if *last == key => members.push(row),
+                _ => groups.push((key, vec![row])),
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// Apply an aggregation operation
+    ///
+    /// Results are typed by the aggregated column: COUNT is BIGINT, SUM, MIN
+    /// and MAX have the column's type (see `Value::from_aggregate`), and AVG
+    /// is DECIMAL.
+    fn apply_aggregation(
+        &self,
+        rows: &[Row],
+        agg: &AggregationOperation,
+        table: &Table,
+    ) -> Result<Value, QueryError> {
+        let column_idx = agg
+            .column
+            .as_ref()
+            .and_then(|col| Self::column_index(table, col).ok());
+        let data_type = column_idx.and_then(|idx| table.columns.get(idx).map(|c| &c.data_type));
+
+        match agg.function {
+            crate::query::planner::AggregationFunction::Count => {
+                // COUNT(*) counts all rows; COUNT(column) skips NULLs
+                let count = match column_idx {
+                    Some(idx) => rows
+                        .iter()
+                        .filter(|r| !matches!(r.values.get(idx), Some(Value::Null) | None))
+                        .count(),
+                    None => rows.len(),
+                };
+                Ok(Value::BigInt(count as i64))
+            }
+            crate::query::planner::AggregationFunction::Sum => {
+                if let (Some(idx), Some(data_type)) = (column_idx, data_type) {
+                    let sum: i64 = rows
+                        .iter()
+                        .filter_map(|r| r.values.get(idx))
+                        .filter_map(Self::numeric_value)
+                        .sum();
+                    Ok(Value::from_aggregate(sum, data_type))
+                } else {
+                    Ok(Value::BigInt(0))
+                }
+            }
+            crate::query::planner::AggregationFunction::Avg => {
+                if let Some(idx) = column_idx {
+                    // NULLs are excluded from both the sum and the count
+                    let values: Vec<i64> = rows
+                        .iter()
+                        .filter_map(|r| r.values.get(idx))
+                        .filter_map(Self::numeric_value)
+                        .collect();
+                    let sum: i128 = values.iter().map(|&v| v as i128).sum();
+                    let count = values.len() as i128;
+
+                    // Decimals are already fixed-point; integers are scaled
+                    let scale = match data_type {
+                        Some(DataType::Decimal) => 1,
+                        _ => 10i128.pow(DECIMAL_SCALE),
+                    };
+                    let avg = if count > 0 { sum * scale / count } else { 0 };
+                    Ok(Value::Decimal(avg as i64))
+                } else {
+                    Ok(Value::Decimal(0))
+                }
+            }
+            crate::query::planner::AggregationFunction::Min
+            | crate::query::planner::AggregationFunction::Max => {
+                // NULLs are skipped; without non-NULL values the result is NULL
+                let Some(idx) = column_idx else {
+                    return Ok(Value::Null);
+                };
+                let wanted = if agg.function == crate::query::planner::AggregationFunction::Min {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+                let best = rows
+                    .iter()
+                    .filter_map(|r| r.values.get(idx))
+                    .filter(|v| **v != Value::Null)
+                    .fold(None, |best: Option<&Value>, v| match best {
+                        Some(b) if Self::compare_values(v, b) != Some(wanted) => Some(b),
+                        _ => Some(v),
+                    });
+                Ok(best.cloned().unwrap_or(Value::Null))
+            }
+            crate::query::planner::AggregationFunction::ApproxCountDistinct => {
+                // NULLs are not counted as distinct values; rows have no
+                // value to hash
+                let Some(idx) = column_idx else {
+                    return Err(QueryError::UnsupportedFeature(
+                        "APPROX_COUNT_DISTINCT(*)".to_string(),
+                    ));
+                };
+                let mut sketch = HyperLogLog::new(HLL_PRECISION);
+                rows.iter()
+                    .filter_map(|r| r.values.get(idx))
+                    .filter(|v| **v != Value::Null)
+                    .for_each(|v| sketch.insert(&v.to_field()));
+                Ok(Value::BigInt(sketch.estimate().round() as i64))
+            }
+            crate::query::planner::AggregationFunction::CountDistinct => {
+                // NULLs are not counted as distinct values
+                let Some(idx) = column_idx else {
+                    return Ok(Value::BigInt(0));
+                };
+                let mut values: Vec<Field> = rows
+                    .iter()
+                    .filter_map(|r| r.values.get(idx))
+                    .filter(|v| **v != Value::Null)
+                    .map(Value::to_field)
+                    .collect();
+                values.sort_by(FieldUtils::cmp);
+                values.dedup();
+                Ok(Value::BigInt(values.len() as i64))
+            }
+        }
+    }
+
+    /// Integer encoding of a numeric value (decimals as fixed-point)
+    fn numeric_value(value: &Value) -> Option<i64> {
+        match value {
+            Value::Integer(i) => Some(*i as i64),
+            Value::BigInt(b) | Value::Decimal(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Apply the projection of a plan to each row
+    ///
+    /// Plain columns are copied from the row and computed columns are
+    /// evaluated per-row.
+    ///
+    /// # Returns
+    /// `Ok((columns, rows))` with the output column names and projected rows
+    fn apply_projection(
+        &self,
+        rows: &[Row],
+        plan: &ExecutionPlan,
+        table: &Table,
+    ) -> Result<(Vec<String>, Vec<Row>), QueryError> {
+        let mut columns = vec![];
+        for name in &plan.projection {
+            if name == "*" {
+                columns.extend(table.columns.iter().map(|c| c.name.clone()));
+            } else {
+                columns.push(name.clone());
+            }
+        }
+
+        // Plain columns are resolved by the table; computed ones are evaluated per-row
+        let plain_columns: Vec<String> = columns
+            .iter()
+            .filter(|name| !plan.computed.iter().any(|c| c.name == **name))
+            .cloned()
+            .collect();
+        let filtered = Table {
+            name: table.name.clone(),
+            columns: table.columns.clone(),
+            rows: rows.to_vec(),
+        };
+        let resolved_columns = plain_columns
+            .iter()
+            .map(|name| Self::column_index(table, name).map(|idx| table.columns[idx].name.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let plain_rows = filtered
+            .project(&resolved_columns)
+            .map_err(|e| QueryError::ColumnNotFound(e.to_string()))?;
+
+        let mut projected = Vec::with_capacity(rows.len());
+        for (row, plain_row) in rows.iter().zip(plain_rows) {
+            let mut plain_values = plain_row.values.into_iter();
+            let mut values = Vec::with_capacity(columns.len());
+            for name in &columns {
+                let value = match plan.computed.iter().find(|c| c.name == *name) {
+                    Some(computed) => self.evaluate_projection_expr(&computed.expr, row, table)?,
+                    None => plain_values.next().unwrap_or(Value::Null),
+                };
+                values.push(value);
+            }
+            projected.push(Row::new(values));
+        }
+
+        Ok((columns, projected))
+    }
+
+    /// Evaluate a projection expression against a row
+    ///
+    /// Operands are promoted to a common type first (see
+    /// [`QueryExecutor::promote`]). Integer operands produce `BigInt`
+    /// results; if either operand is a `Decimal`, the other is scaled to
+    /// fixed-point and the result is a `Decimal`, with products and quotients
+    /// rescaled to [`DECIMAL_SCALE`] digits. `NULL` operands yield `NULL`.
+    fn evaluate_projection_expr(
+        &self,
+        expr: &ProjectionExpr,
+        row: &Row,
+        table: &Table,
+    ) -> Result<Value, QueryError> {
+        match expr {
+            ProjectionExpr::Column(name) => {
+                let idx = Self::column_index(table, name)?;
+                Ok(row.values.get(idx).cloned().unwrap_or(Value::Null))
+            }
+            ProjectionExpr::Literal(value) => Ok(Value::BigInt(*value)),
+            ProjectionExpr::BinaryOp { left, op, right } => {
+                let (left, right) = Self::promote(
+                    &self.evaluate_projection_expr(left, row, table)?,
+                    &self.evaluate_projection_expr(right, row, table)?,
+                );
+
+                let as_i64 = |v: &Value| -> Result<Option<i64>, QueryError> {
+                    match v {
+                        Value::Integer(i) => Ok(Some(*i as i64)),
+                        Value::BigInt(b) | Value::Decimal(b) => Ok(Some(*b)),
+                        Value::Null => Ok(None),
+                        other => Err(QueryError::UnsupportedFeature(format!(
+                            "Cannot apply {} to {}",
+                            op, other
+                        ))),
+                    }
+                };
+                let (a, b) = match (as_i64(&left)?, as_i64(&right)?) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return Ok(Value::Null),
+                };
+
+                // Both operands are decimals after promotion, if either is
+                let decimal = matches!(left, Value::Decimal(_));
+                let scale = 10i64.pow(DECIMAL_SCALE);
+                let result = match op {
+                    ArithmeticOp::Add => a.checked_add(b),
+                    ArithmeticOp::Sub => a.checked_sub(b),
+                    ArithmeticOp::Mul if decimal => a.checked_mul(b).map(|p| p / scale),
+                    ArithmeticOp::Mul => a.checked_mul(b),
+                    ArithmeticOp::Div => {
+                        if b == 0 {
+                            return Err(QueryError::InvalidValue(
+                                "Division by zero in projection".to_string(),
+                            ));
+                        }
+                        if decimal {
+                            a.checked_mul(scale).and_then(|a| a.checked_div(b))
+                        } else {
+                            a.checked_div(b)
+                        }
+                    }
+                    ArithmeticOp::Mod => {
+                        if b == 0 {
+                            return Err(QueryError::InvalidValue(
+                                "Modulo by zero in projection".to_string(),
+                            ));
+                        }
+                        a.checked_rem(b)
+                    }
+                }
+                .ok_or_else(|| {
+                    QueryError::InvalidValue(format!("Overflow evaluating {} {} {}", a, op, b))
+                })?;
+
+                if decimal {
+                    Ok(Value::Decimal(result))
+                } else {
+                    Ok(Value::BigInt(result))
+                }
+            }
+        }
+    }
+
+    /// Apply a sort operation
+    ///
+    /// Rows are ordered by the sort columns in turn; the sort is stable, so
+    /// ties keep their input order.
+    ///
+    /// # Arguments
+    /// * `rows` - Rows of `table` to sort
+    /// * `sort` - Sort columns and directions
+    /// * `table` - Table the sort columns are resolved against
+    ///
+    /// # Returns
+    /// `Ok(sorted_rows)`, `Err` if a sort column doesn't exist
+    fn apply_sort(
+        &self,
+        rows: &[Row],
+        sort: &SortOperation,
+        table: &Table,
+    ) -> Result<Vec<Row>, QueryError> {
+        let keys = sort
+            .columns
+            .iter()
+            .zip(&sort.ascending)
+            .map(|(name, &ascending)| Ok((Self::column_index(table, name)?, ascending)))
+            .collect::<Result<Vec<_>, QueryError>>()?;
+
+        Ok(Self::sort_rows(rows, &keys))
+    }
+
+    /// Stable sort of rows by `(column index, ascending)` keys
+    ///
+    /// NULLs sort before every other value; values of incomparable types
+    /// are treated as equal.
+    fn sort_rows(rows: &[Row], keys: &[(usize, bool)]) -> Vec<Row> {
+        let mut sorted = rows.to_vec();
+        sorted.sort_by(|a, b| {
+            keys.iter()
+                .map(|&(idx, ascending)| {
+                    let ordering = match (a.values.get(idx), b.values.get(idx)) {
+                        (Some(Value::Null), Some(Value::Null)) => Ordering::Equal,
+                        (Some(Value::Null), _) => Ordering::Less,
+                        (_, Some(Value::Null)) => Ordering::Greater,
+                        (Some(a), Some(b)) => Self::compare_values(a, b).unwrap_or(Ordering::Equal),
+                        _ => Ordering::Equal,
+                    };
+                    if ascending {
+                        ordering
+                    } else {
+                        ordering.reverse()
+                    }
+                })
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        });
+        sorted
+    }
+
+    /// Build a circuit from an execution plan
+    ///
+    /// Gate data is derived by [`CircuitBuilder::from_plan`]. When the plan
+    /// sorts filtered rows, the sort gate proves that the rows in result
+    /// order are a sorted permutation of the filtered rows, and their sort
+    /// keys are public outputs. ORDER BY over aggregated rows sorts result
+    /// columns (which may be aggregate aliases), not table rows, so it isn't
+    /// proved. For HAVING, the circuit proves that the count of every group
+    /// kept in the result is its proved group's and passes the threshold.
+    /// The selected cells of the columns opened with the proof are public,
+    /// so the verifier can check them against the openings.
+    fn build_circuit(
+        &self,
+        plan: &ExecutionPlan,
+        table: &Table,
+        filtered_rows: &[Row],
+    ) -> Result<NzengiCircuit, QueryError> {
+        let mut circuit =
+            CircuitBuilder::from_plan(plan, table, filtered_rows)?.with_validation(self.validation);
+
+        // Only projections return filtered rows in sort order
+        let projection_only =
+            plan.aggregations.is_empty() && !plan.projection.is_empty() && plan.having.is_none();
+        if let (Some(sort), true) = (plan.sort.first(), projection_only) {
+            let sorted_rows = self.apply_sort(filtered_rows, sort, table)?;
+            let input = CircuitBuilder::sort_keys(sort, table, filtered_rows)?;
+            let output = CircuitBuilder::sort_keys(sort, table, &sorted_rows)?;
+
+            if let (Some(input), Some(mut output)) = (input, output) {
+                // The gate proves ascending order; descending output is checked reversed
+                if !sort.ascending.first().copied().unwrap_or(true) {
+                    output.reverse();
+                }
+                circuit = circuit.with_sort_output(input, output);
+            }
+        }
+
+        for column_idx in self.opened_columns(plan, table) {
+            circuit = CircuitBuilder::open_column(circuit, table, column_idx);
+        }
+
+        Ok(circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fields, plan, run_mock_with_instance, small_lineitem, tables};
+
+    #[test]
+    fn test_executor_new() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+        assert!(true); // Executor created successfully
+    }
+
+    #[test]
+    fn test_executor_execute_simple() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        // Create a simple table
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new(
+                "l_quantity".to_string(),
+                crate::types::DataType::Integer,
+            )],
+        );
+        table.rows.push(Row::new(vec![Value::Integer(10)]));
+
+        let mut tables = HashMap::new();
+        tables.insert("lineitem".to_string(), table);
+
+        // Create a simple plan
+        let plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            filters: vec![],
+            filter_tree: None,
+            constant_filters: vec![],
+            empty_result: false,
+            joins: vec![],
+            group_by: vec![],
+            having: None,
+            aggregations: vec![AggregationOperation {
+                function: crate::query::planner::AggregationFunction::Count,
+                column: None,
+                alias: Some("count".to_string()),
+            }],
+            sort: vec![],
+            projection: vec![],
+            computed: vec![],
+            required_columns: None,
+            derived: vec![],
+            union_all: vec![],
+        };
+
+        // Note: This test may fail if circuit generation fails
+        // The actual execution depends on proper circuit configuration
+        let result = executor.execute(&plan, &tables);
+        if let Err(e) = result {
+            println!("Execution failed (expected for test): {}", e);
+        }
+    }
+
+    #[test]
+    fn test_executor_opens_read_cells() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_comment".to_string(), DataType::Varchar(20)),
+            ],
+        );
+        for q in [5, 12, 10, 30] {
+            table.rows.push(Row::new(vec![
+                Value::Integer(q),
+                Value::String("secret".to_string()),
+            ]));
+        }
+        let (commitment, blinds) =
+            DatabaseCommitment::commit_database_with_blinds(&[table.clone()], &params).unwrap();
+        let executor = QueryExecutor::new(&params).with_commitment(commitment.clone(), blinds);
+
+        let plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            filters: vec![FilterOperation {
+                column: "l_quantity".to_string(),
+                condition: FilterCondition::GreaterThan("10".to_string()),
+            }],
+            aggregations: vec![AggregationOperation {
+                function: AggregationFunction::Count,
+                column: None,
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        let mut tables = HashMap::new();
+        tables.insert("lineitem".to_string(), table);
+
+        let (_, proof) = executor.execute(&plan, &tables).unwrap();
+
+        // Only the filtered column is opened, at the rows passing the filter
+        assert_eq!(proof.openings.len(), 1);
+        assert_eq!(proof.openings[0].column_name, "l_quantity");
+        assert_eq!(proof.openings[0].rows, vec![1, 3]);
+
+        // The circuit exposes the selected cells of the opened column after
+        // the count
+        let table = &tables["lineitem"];
+        let (_, filtered) = executor.evaluate(&plan, table).unwrap();
+        let circuit = executor.build_circuit(&plan, table, &filtered).unwrap();
+        assert_eq!(
+            circuit.public_inputs(),
+            vec![Field::from(2u64), Field::from(12u64), Field::from(30u64)]
+        );
+
+        // The verifier checks the openings with the proof, and supplies the
+        // opened values as the circuit's, also after a JSON round trip
+        let vk = Prover::new(&params).generate_vk(&circuit).unwrap();
+        let verifier = Verifier::new(&params);
+        let public_inputs = [Field::from(2u64)];
+        let verify = |proof: &crate::types::Proof| {
+            verifier
+                .verify_with_commitment(&vk, proof, &public_inputs, &commitment, None)
+                .unwrap()
+        };
+        assert!(verify(&proof));
+        let restored = crate::types::Proof::from_json(&proof.to_json().unwrap()).unwrap();
+        assert_eq!(restored.openings, proof.openings);
+        assert!(verify(&restored));
+
+        // A fabricated cell value fails opening verification
+        let mut forged = proof.clone();
+        forged.openings[0].values[0] = Value::Integer(11).to_field();
+        assert!(!verify(&forged));
+
+        // So does a valid opening of other cells than the circuit's
+        let (_, blinds) = executor.commitment.as_ref().unwrap();
+        let mut other = proof.clone();
+        other.openings[0] = CellOpening::create(
+            "lineitem",
+            "l_quantity",
+            &table.column_fields("l_quantity").unwrap(),
+            blinds.get("lineitem", "l_quantity").unwrap(),
+            &[1, 2],
+            &params,
+        )
+        .unwrap();
+        assert!(other.openings[0].verify(&commitment, &params));
+        assert!(!verify(&other));
+    }
+
+    #[test]
+    fn test_executor_strict_validation() {
+        use halo2_proofs::dev::MockProver;
+
+        let params = IPAParams::new(10);
+        let table = small_lineitem(&[5, 12, 30]);
+        let plan = plan("SELECT SUM(l_quantity) FROM lineitem WHERE l_quantity > 10");
+        let (_, filtered) = QueryExecutor::new(&params).evaluate(&plan, &table).unwrap();
+
+        // Circuits skip the witness checks by default
+        let circuit = QueryExecutor::new(&params)
+            .build_circuit(&plan, &table, &filtered)
+            .unwrap();
+        assert_eq!(circuit.validation(), ValidationMode::Fast);
+
+        // The executor's mode reaches its circuits, whose consistent
+        // witnesses pass the checks
+        let executor = QueryExecutor::new(&params).with_validation(ValidationMode::Strict);
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        assert_eq!(circuit.validation(), ValidationMode::Strict);
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+        let (result, _) = executor.execute(&plan, &tables([table])).unwrap();
+        assert_eq!(result.rows, vec![Row::new(vec![Value::Integer(42)])]);
+
+        // A sort whose output isn't a permutation of its input fails
+        // synthesis under Strict, and only verification under Fast
+        let forged = circuit.with_sort(fields(&[12, 30]), fields(&[12, 31]));
+        assert!(MockProver::run(10, &forged, vec![forged.public_inputs()]).is_err());
+        let fast = forged.with_validation(ValidationMode::Fast);
+        let prover = MockProver::run(10, &fast, vec![fast.public_inputs()]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_executor_materialize_derived_table() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+        let plan = plan(
+            "SELECT COUNT(*) FROM \
+             (SELECT l_quantity FROM lineitem WHERE l_quantity > 10) t \
+             WHERE l_quantity < 20",
+        );
+        assert_eq!(plan.derived.len(), 1);
+        let tables = tables([small_lineitem(&[5, 12, 30])]);
+
+        // The subquery's result is a temporary table named by its alias
+        let materialized = executor.materialize_derived(&plan, &tables).unwrap();
+        let derived = &materialized["t"];
+        assert_eq!(derived.columns.len(), 1);
+        assert_eq!(derived.columns[0].name, "l_quantity");
+        assert_eq!(derived.columns[0].data_type, DataType::Integer);
+        assert_eq!(derived.rows.len(), 2);
+
+        // The outer query counts the subquery's rows below 20
+        let (result, proof) = executor.execute(&plan, &tables).unwrap();
+        assert_eq!(result.rows, vec![Row::new(vec![Value::BigInt(1)])]);
+
+        // The proof is of the outer query over the subquery's rows
+        let (_, filtered) = executor.evaluate(&plan, derived).unwrap();
+        let circuit = executor.build_circuit(&plan, derived, &filtered).unwrap();
+        let vk = Prover::new(&params).generate_vk(&circuit).unwrap();
+        let verifier = Verifier::new(&params);
+        assert!(verifier.verify(&vk, &proof, &[Field::from(1u64)]).unwrap());
+        assert!(!verifier.verify(&vk, &proof, &[Field::from(2u64)]).unwrap());
+    }
+
+    #[test]
+    fn test_executor_count_over_join() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut orders = Table::new(
+            "orders".to_string(),
+            vec![
+                Column::new("o_orderkey".to_string(), DataType::Integer),
+                Column::new("o_custkey".to_string(), DataType::Integer),
+            ],
+        );
+        let order_customers = [
+            Value::Integer(1),
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3),
+            Value::Null,
+        ];
+        for (order, customer) in order_customers.into_iter().enumerate() {
+            orders
+                .rows
+                .push(Row::new(vec![Value::Integer(order as i32 + 1), customer]));
+        }
+
+        let mut customer = Table::new(
+            "customer".to_string(),
+            vec![
+                Column::new("c_custkey".to_string(), DataType::Integer),
+                Column::new("c_acctbal".to_string(), DataType::Integer),
+            ],
+        );
+        for (key, balance) in [(1, 10), (2, 20), (2, 30), (4, 40)] {
+            customer
+                .rows
+                .push(Row::new(vec![Value::Integer(key), Value::Integer(balance)]));
+        }
+
+        let mut tables = HashMap::new();
+        tables.insert("orders".to_string(), orders);
+        tables.insert("customer".to_string(), customer);
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT COUNT(*), SUM(c.c_acctbal) FROM orders o \
+                 JOIN customer c ON o.o_custkey = c.c_custkey",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let joined = QueryExecutor::join_tables(&plan, &tables).unwrap();
+        assert_eq!(joined.columns.len(), 4);
+        assert_eq!(joined.columns[3].name, "customer.c_acctbal");
+
+        // Orders 1 and 2 match customer 1; order 3 matches both customer 2 rows
+        let (result, filtered) = executor.evaluate(&plan, &joined).unwrap();
+        assert_eq!(filtered.len(), 4);
+        assert_eq!(
+            result.rows[0].values,
+            vec![Value::BigInt(4), Value::Integer(10 + 10 + 20 + 30)]
+        );
+    }
+
+    #[test]
+    fn test_executor_missing_table() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser.parse("SELECT COUNT(*) FROM orders").unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let result = executor.execute(&plan, &HashMap::new());
+        assert!(matches!(result, Err(QueryError::TableNotFound(table)) if table == "orders"));
+    }
+
+    #[test]
+    fn test_executor_missing_column() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new(
+                "l_quantity".to_string(),
+                crate::types::DataType::Integer,
+            )],
+        );
+        let filter = FilterOperation {
+            column: "l_discount".to_string(),
+            condition: crate::query::planner::FilterCondition::GreaterThan("1".to_string()),
+        };
+
+        let result = executor.apply_filter(&table.rows, &filter, &table);
+        assert!(
+            matches!(result, Err(QueryError::ColumnNotFound(column)) if column == "l_discount")
+        );
+    }
+
+    #[test]
+    fn test_executor_count_public_output() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let table = small_lineitem(&[5, 12, 10, 30, 11]);
+        let plan = plan("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10");
+
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        let selection = CircuitBuilder::selection_flags(&table, &filtered);
+        assert_eq!(
+            selection,
+            vec![
+                Field::zero(),
+                Field::one(),
+                Field::zero(),
+                Field::one(),
+                Field::one()
+            ]
+        );
+
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        assert_eq!(circuit.public_inputs(), vec![Field::from(3u64)]);
+
+        // The count is proved from the comparisons of the table cells
+        assert!(circuit.predicate().is_some());
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+
+        // A forged count is rejected, whether or not the instance agrees
+        let forged = circuit.clone().with_count_output(Field::from(4u64));
+        assert!(run_mock_with_instance(&forged, 10, vec![forged.public_inputs()]).is_err());
+        assert!(run_mock_with_instance(&circuit, 10, vec![fields(&[4])]).is_err());
+    }
+
+    #[test]
+    fn test_executor_count_column_skips_null() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new(
+                "l_quantity".to_string(),
+                crate::types::DataType::Integer,
+            )],
+        );
+        table.rows.push(Row::new(vec![Value::Integer(0)]));
+        table.rows.push(Row::new(vec![Value::Null]));
+
+        let count_column = AggregationOperation {
+            function: AggregationFunction::Count,
+            column: Some("l_quantity".to_string()),
+            alias: None,
+        };
+        let count_star = AggregationOperation {
+            function: AggregationFunction::Count,
+            column: None,
+            alias: None,
+        };
+
+        assert_eq!(
+            executor
+                .apply_aggregation(&table.rows, &count_column, &table)
+                .unwrap(),
+            Value::BigInt(1)
+        );
+        assert_eq!(
+            executor
+                .apply_aggregation(&table.rows, &count_star, &table)
+                .unwrap(),
+            Value::BigInt(2)
+        );
+
+        let plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            aggregations: vec![count_column],
+            ..Default::default()
+        };
+        let circuit = executor.build_circuit(&plan, &table, &table.rows).unwrap();
+        assert_eq!(circuit.public_inputs(), vec![Field::one()]);
+    }
+
+    #[test]
+    fn test_executor_aggregates_typed_by_column() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_extendedprice".to_string(), DataType::Decimal),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+            ],
+        );
+        for (price, q) in [("10.50", "5"), ("2.25", "12"), ("", "30")] {
+            table.rows.push(Row::new(vec![
+                Value::parse(price, &DataType::Decimal).unwrap(),
+                Value::parse(q, &DataType::Integer).unwrap(),
+            ]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT SUM(l_extendedprice), COUNT(*), MAX(l_extendedprice), \
+                 AVG(l_extendedprice), SUM(l_quantity), AVG(l_quantity) FROM lineitem",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (result, _) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            result.rows[0].values,
+            vec![
+                Value::Decimal(1275),
+                Value::BigInt(3),
+                Value::Decimal(1050),
+                Value::Decimal(637),
+                Value::Integer(47),
+                Value::Decimal(1566),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_executor_approx_count_distinct() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        // 1000 distinct values, each 3 times, plus a NULL
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new(
+                "l_partkey".to_string(),
+                crate::types::DataType::Integer,
+            )],
+        );
+        for i in 0..3000 {
+            table.rows.push(Row::new(vec![Value::Integer(i % 1000)]));
+        }
+        table.rows.push(Row::new(vec![Value::Null]));
+
+        let approx = AggregationOperation {
+            function: AggregationFunction::ApproxCountDistinct,
+            column: Some("l_partkey".to_string()),
+            alias: None,
+        };
+        let Value::BigInt(estimate) = executor
+            .apply_aggregation(&table.rows, &approx, &table)
+            .unwrap()
+        else {
+            panic!("APPROX_COUNT_DISTINCT should return a BigInt");
+        };
+        // 3 standard errors of 1.04 / sqrt(256)
+        assert!((estimate - 1000).abs() < 195, "Estimate {}", estimate);
+
+        // The public registers reproduce the estimate
+        let plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            aggregations: vec![approx],
+            ..Default::default()
+        };
+        let circuit = executor.build_circuit(&plan, &table, &table.rows).unwrap();
+        let sketch = HyperLogLog::from_registers(HLL_PRECISION, &circuit.public_inputs()).unwrap();
+        assert_eq!(sketch.estimate().round() as i64, estimate);
+
+        // The registers are proved over the selected cells of the column
+        let mut small = table.clone();
+        small.rows = table.rows[2995..].to_vec();
+        let circuit = executor.build_circuit(&plan, &small, &small.rows).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(
+            run_mock_with_instance(&circuit, 12, vec![public_inputs.clone()]),
+            Ok(())
+        );
+        let mut tampered = public_inputs;
+        let j = tampered.iter().position(|r| *r != Field::zero()).unwrap();
+        tampered[j] += Field::one();
+        assert!(run_mock_with_instance(&circuit, 12, vec![tampered]).is_err());
+
+        // Values the gate can't hash are rejected instead of left unproved
+        small.rows[0].values[0] = Value::String("MAIL".to_string());
+        assert!(matches!(
+            executor.build_circuit(&plan, &small, &small.rows),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+
+        // Rows have no value to hash
+        let approx_all = AggregationOperation {
+            column: None,
+            ..plan.aggregations[0].clone()
+        };
+        assert!(matches!(
+            executor.apply_aggregation(&table.rows, &approx_all, &table),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_circuit_columns_pruned() {
+        let table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_orderkey".to_string(), crate::types::DataType::BigInt),
+                Column::new("l_quantity".to_string(), crate::types::DataType::Integer),
+                Column::new("l_discount".to_string(), crate::types::DataType::Integer),
+            ],
+        );
+
+        let mut plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            CircuitBuilder::circuit_column_indices(&plan, &table),
+            vec![0, 1, 2]
+        );
+
+        plan.required_columns = Some(vec!["l_quantity".to_string()]);
+        assert_eq!(
+            CircuitBuilder::circuit_column_indices(&plan, &table),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_executor_between_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new(
+                "l_quantity".to_string(),
+                crate::types::DataType::Integer,
+            )],
+        );
+        for q in [5, 10, 15, 20, 25] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity BETWEEN 10 AND 20")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered.len(), 3);
+
+        assert!(executor.build_circuit(&plan, &table, &filtered).is_ok());
+    }
+
+    #[test]
+    fn test_executor_qualified_column_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE lineitem.l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.filters[0].column, "lineitem.l_quantity");
+
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered.len(), 2);
+
+        // A qualifier naming another table doesn't resolve
+        assert!(matches!(
+            QueryExecutor::column_index(&table, "orders.l_quantity"),
+            Err(QueryError::ColumnNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_join_keys_across_types() {
+        let mut orders = Table::new(
+            "orders".to_string(),
+            vec![Column::new("o_custkey".to_string(), DataType::Integer)],
+        );
+        for key in [1, 2, 2] {
+            orders.rows.push(Row::new(vec![Value::Integer(key)]));
+        }
+        let mut customer = Table::new(
+            "customer".to_string(),
+            vec![Column::new("c_custkey".to_string(), DataType::BigInt)],
+        );
+        for key in [2, 3] {
+            customer.rows.push(Row::new(vec![Value::BigInt(key)]));
+        }
+
+        let mut tables = HashMap::new();
+        tables.insert("orders".to_string(), orders);
+        tables.insert("customer".to_string(), customer);
+
+        let ast = crate::query::QueryParser::new()
+            .parse("SELECT COUNT(*) FROM orders o JOIN customer c ON o.o_custkey = c.c_custkey")
+            .unwrap();
+        let plan = crate::query::QueryPlanner::new().plan(&ast).unwrap();
+
+        // INTEGER 2 and BIGINT 2 share a join key
+        let joined = QueryExecutor::join_tables(&plan, &tables).unwrap();
+        assert_eq!(joined.rows.len(), 2);
+
+        // Negative keys have no checked join key
+        tables
+            .get_mut("orders")
+            .unwrap()
+            .rows
+            .push(Row::new(vec![Value::Integer(-1)]));
+        assert!(matches!(
+            QueryExecutor::join_tables(&plan, &tables),
+            Err(QueryError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_ambiguous_column_in_join() {
+        let joined = Table::new(
+            "orders_customer".to_string(),
+            vec![
+                Column::new("orders.comment".to_string(), DataType::Integer),
+                Column::new("customer.comment".to_string(), DataType::Integer),
+                Column::new("customer.c_acctbal".to_string(), DataType::Integer),
+            ],
+        );
+
+        assert_eq!(
+            QueryExecutor::column_index(&joined, "c_acctbal").unwrap(),
+            2
+        );
+        assert_eq!(
+            QueryExecutor::column_index(&joined, "customer.comment").unwrap(),
+            1
+        );
+        assert!(matches!(
+            QueryExecutor::column_index(&joined, "comment"),
+            Err(QueryError::AmbiguousColumn(name)) if name == "comment"
+        ));
+    }
+
+    #[test]
+    fn test_executor_computed_projection() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new(
+                    "l_extendedprice".to_string(),
+                    crate::types::DataType::BigInt,
+                ),
+                Column::new("l_discount".to_string(), crate::types::DataType::Integer),
+            ],
+        );
+        table
+            .rows
+            .push(Row::new(vec![Value::BigInt(100), Value::Integer(0)]));
+        table
+            .rows
+            .push(Row::new(vec![Value::BigInt(50), Value::Integer(3)]));
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT l_extendedprice * (1 - l_discount) FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (columns, rows) = executor
+            .apply_projection(&table.rows, &plan, &table)
+            .unwrap();
+        assert_eq!(
+            columns,
+            vec!["(l_extendedprice * (1 - l_discount))".to_string()]
+        );
+        assert_eq!(rows[0].values, vec![Value::BigInt(100)]);
+        assert_eq!(rows[1].values, vec![Value::BigInt(-100)]);
+    }
+
+    #[test]
+    fn test_executor_modulo_projection() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_orderkey".to_string(), DataType::BigInt)],
+        );
+        table.rows.push(Row::new(vec![Value::BigInt(17)]));
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT l_orderkey % 5, l_orderkey / 5 FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (_, rows) = executor
+            .apply_projection(&table.rows, &plan, &table)
+            .unwrap();
+        assert_eq!(rows[0].values, vec![Value::BigInt(2), Value::BigInt(3)]);
+
+        // The circuit proves the same values from the selected cell
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[2, 3]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+        assert!(run_mock_with_instance(&circuit, 10, vec![fields(&[2, 4])]).is_err());
+
+        // Dividing by zero can't be proved
+        let mut zero = table.clone();
+        zero.rows[0] = Row::new(vec![Value::BigInt(0)]);
+        let plan = self::plan("SELECT 5 / l_orderkey FROM lineitem");
+        assert!(matches!(
+            CircuitBuilder::from_plan(&plan, &zero, &zero.rows),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_date_literal_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_shipdate".to_string(), DataType::Date)],
+        );
+        for date in ["1993-12-31", "1994-01-01", "1994-06-15", "1995-01-01"] {
+            table
+                .rows
+                .push(Row::new(vec![Value::parse(date, &DataType::Date).unwrap()]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_shipdate > DATE '1994-01-01'")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert!(matches!(
+            &plan.filters[0].condition,
+            FilterCondition::GreaterThan(t) if t == "757382400"
+        ));
+
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, table.rows[2..].to_vec());
+        assert!(executor.build_circuit(&plan, &table, &filtered).is_ok());
+
+        let ast = parser
+            .parse(
+                "SELECT COUNT(*) FROM lineitem \
+                 WHERE l_shipdate BETWEEN DATE '1994-01-01' AND TIMESTAMP '1994-12-31 23:59:59'",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, table.rows[1..3].to_vec());
+
+        // `>=` and `<=` keep the rows on the bound
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_shipdate >= DATE '1994-01-01'")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert!(matches!(
+            &plan.filters[0].condition,
+            FilterCondition::GreaterOrEqual(t) if t == "757382400"
+        ));
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, table.rows[1..].to_vec());
+        assert!(executor.build_circuit(&plan, &table, &filtered).is_ok());
+
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_shipdate <= DATE '1994-01-01'")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, table.rows[..2].to_vec());
+
+        // A malformed date is an error, not a dropped filter
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_shipdate >= DATE '1994-13-01'")
+            .unwrap();
+        assert!(matches!(
+            planner.plan(&ast),
+            Err(QueryError::InvalidValue(msg)) if msg.contains("1994-13-01")
+        ));
+    }
+
+    #[test]
+    fn test_executor_column_comparison_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_commitdate".to_string(), DataType::Date),
+                Column::new("l_receiptdate".to_string(), DataType::Date),
+            ],
+        );
+        for (commit, receipt) in [(100, 120), (130, 110), (140, 140), (150, 151)] {
+            table
+                .rows
+                .push(Row::new(vec![Value::Date(commit), Value::Date(receipt)]));
+        }
+        table
+            .rows
+            .push(Row::new(vec![Value::Null, Value::Date(200)]));
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_commitdate < l_receiptdate")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, vec![table.rows[0].clone(), table.rows[3].clone()]);
+
+        let selection = CircuitBuilder::selection_flags(&table, &filtered);
+        let selected: Vec<bool> = selection.iter().map(|f| *f == Field::one()).collect();
+        assert_eq!(selected, vec![true, false, false, true, false]);
+
+        assert!(executor.build_circuit(&plan, &table, &filtered).is_ok());
+    }
+
+    #[test]
+    fn test_executor_mixed_numeric_types() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_quantity".to_string(), DataType::Integer),
+                Column::new("l_limit".to_string(), DataType::BigInt),
+                Column::new("l_extendedprice".to_string(), DataType::Decimal),
+                Column::new("l_discount".to_string(), DataType::Decimal),
+            ],
+        );
+        for (quantity, limit, price, discount) in
+            [(5, 10, "100.00", "0.05"), (20, 10, "10.50", "0.10")]
+        {
+            table.rows.push(Row::new(vec![
+                Value::Integer(quantity),
+                Value::BigInt(limit),
+                Value::parse(price, &DataType::Decimal).unwrap(),
+                Value::parse(discount, &DataType::Decimal).unwrap(),
+            ]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+
+        // INTEGER compared with BIGINT
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity < l_limit")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        assert_eq!(filtered, vec![table.rows[0].clone()]);
+
+        // Integer literals and columns are promoted to fixed-point decimals
+        let ast = parser
+            .parse(
+                "SELECT l_extendedprice * (1 - l_discount), l_quantity * l_extendedprice \
+                 FROM lineitem",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let (_, rows) = executor
+            .apply_projection(&table.rows, &plan, &table)
+            .unwrap();
+        assert_eq!(
+            rows[0].values,
+            vec![Value::Decimal(9500), Value::Decimal(50000)]
+        );
+        assert_eq!(
+            rows[1].values,
+            vec![Value::Decimal(945), Value::Decimal(21000)]
+        );
+
+        // The arithmetic rows prove the same fixed-point results
+        let circuit = CircuitBuilder::from_plan(&plan, &table, &table.rows).unwrap();
+        assert_eq!(circuit.public_inputs(), fields(&[9500, 50000, 945, 21000]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![circuit.public_inputs()]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_executor_nested_and_or_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "t".to_string(),
+            vec![
+                Column::new("a".to_string(), DataType::Integer),
+                Column::new("b".to_string(), DataType::Integer),
+                Column::new("c".to_string(), DataType::Integer),
+            ],
+        );
+        for (a, b, c) in [(2, 1, 0), (2, 5, 0), (0, 0, 3), (0, 0, 4), (5, 1, 3)] {
+            table.rows.push(Row::new(vec![
+                Value::Integer(a),
+                Value::Integer(b),
+                Value::Integer(c),
+            ]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM t WHERE (a > 1 AND b < 2) OR c = 3")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        // The disjunction is kept as a tree, not flattened into filters
+        assert!(plan.filters.is_empty());
+        let tree = plan.filter_tree.as_ref().unwrap();
+        let FilterTree::Or(children) = tree else {
+            panic!("expected OR at the root, got {:?}", tree);
+        };
+        assert_eq!(children.len(), 2);
+        assert!(matches!(&children[0], FilterTree::And(and) if and.len() == 2));
+        assert!(matches!(
+            &children[1],
+            FilterTree::Leaf(FilterOperation { column, condition: FilterCondition::Equal(v) })
+                if column == "c" && v == "3"
+        ));
+        let columns: Vec<&str> = tree.leaves().iter().map(|f| f.column.as_str()).collect();
+        assert_eq!(columns, vec!["a", "b", "c"]);
+
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            filtered,
+            vec![
+                table.rows[0].clone(),
+                table.rows[2].clone(),
+                table.rows[4].clone()
+            ]
+        );
+        assert_eq!(result.rows[0].values[0], Value::BigInt(3));
+
+        assert!(executor.build_circuit(&plan, &table, &filtered).is_ok());
+    }
+
+    #[test]
+    fn test_executor_order_by_proves_sort() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+            ],
+        );
+        for (key, q) in [(1, 30), (2, 10), (3, 20), (4, 5)] {
+            table
+                .rows
+                .push(Row::new(vec![Value::BigInt(key), Value::Integer(q)]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT l_orderkey FROM lineitem WHERE l_quantity > 6 ORDER BY l_quantity DESC")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        let keys: Vec<Value> = result.rows.iter().map(|r| r.values[0].clone()).collect();
+        assert_eq!(
+            keys,
+            vec![Value::BigInt(1), Value::BigInt(3), Value::BigInt(2)]
+        );
+
+        // The sort gate gets the filtered keys and the result-order keys
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let (input, sorted, public) = &circuit.sort_data()[0];
+        assert_eq!(*input, fields(&[30, 10, 20]));
+        assert_eq!(*sorted, fields(&[10, 20, 30]));
+        assert!(public);
+    }
+
+    #[test]
+    fn test_executor_group_by_order_by_aggregate_alias() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_orderkey".to_string(), DataType::BigInt)],
+        );
+        for key in [1, 2, 2, 3, 3, 3] {
+            table.rows.push(Row::new(vec![Value::BigInt(key)]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT l_orderkey, COUNT(*) AS cnt FROM lineitem \
+                 GROUP BY l_orderkey ORDER BY cnt DESC",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        // Groups are ordered by the aggregate alias
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        let keys: Vec<Value> = result.rows.iter().map(|r| r.values[0].clone()).collect();
+        assert_eq!(
+            keys,
+            vec![Value::BigInt(3), Value::BigInt(2), Value::BigInt(1)]
+        );
+
+        // The alias isn't a table column, so the table-row sort isn't proved
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        assert!(circuit.sort_data().is_empty());
+    }
+
+    #[test]
+    fn test_executor_table_alias() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT l.l_quantity, l.l_quantity * 2 FROM lineitem l WHERE l.l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (result, _) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                Row::new(vec![Value::Integer(12), Value::BigInt(24)]),
+                Row::new(vec![Value::Integer(30), Value::BigInt(60)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_executor_cancelled_before_keygen() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        let tables = HashMap::from([("lineitem".to_string(), table)]);
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = executor.execute_with_cancel(&plan, &tables, &token);
+        assert!(matches!(result, Err(QueryError::Cancelled)));
+
+        // An expired timeout cancels as well
+        let token = CancellationToken::with_timeout(std::time::Duration::ZERO);
+        let result = executor.execute_with_cancel(&plan, &tables, &token);
+        assert!(matches!(result, Err(QueryError::Cancelled)));
+    }
+
+    #[test]
+    fn test_executor_min_max_proves_public_outputs() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 10, 30, 11] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        table.rows.push(Row::new(vec![Value::Null]));
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT MIN(l_quantity), MAX(l_quantity) FROM lineitem WHERE l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![Row::new(vec![Value::Integer(11), Value::Integer(30)])]
+        );
+
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, vec![Field::from(11u64), Field::from(30u64)]);
+
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs).unwrap();
+        assert!(verifier.verify(&vk, &proof, &public_inputs).unwrap());
+
+        // A proof claiming a MIN below the smallest filtered value is rejected
+        let claimed = vec![Field::from(10u64), Field::from(30u64)];
+        let proof = prover.create_proof(&pk, &circuit, &claimed).unwrap();
+        assert!(!verifier.verify(&vk, &proof, &claimed).unwrap());
+
+        // So is a MIN over values other than the filtered cells
+        let mut forged_rows = filtered.clone();
+        forged_rows[2] = Row::new(vec![Value::Integer(10)]);
+        let forged = executor.build_circuit(&plan, &table, &forged_rows).unwrap();
+        let claimed = forged.public_inputs();
+        assert_eq!(claimed, vec![Field::from(10u64), Field::from(30u64)]);
+        let proof = prover.create_proof(&pk, &forged, &claimed).unwrap();
+        assert!(!verifier.verify(&vk, &proof, &claimed).unwrap());
+
+        // Negative values can't be proved
+        table.rows.push(Row::new(vec![Value::Integer(-20)]));
+        let ast = parser
+            .parse("SELECT MIN(l_quantity) FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let (_, filtered) = executor.evaluate(&plan, &table).unwrap();
+        assert!(matches!(
+            executor.build_circuit(&plan, &table, &filtered),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_union_all() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 10, 30, 2] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        let mut tables = HashMap::new();
+        tables.insert("lineitem".to_string(), table.clone());
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT l_quantity FROM lineitem WHERE l_quantity > 10 \
+                 UNION ALL SELECT l_quantity FROM lineitem WHERE l_quantity < 6",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.union_all.len(), 1);
+
+        // A single proof can't cover both branches
+        assert!(matches!(
+            executor.execute(&plan, &tables),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+
+        let (result, proofs) = executor.execute_union_all(&plan, &tables).unwrap();
+        assert_eq!(result.columns, vec!["l_quantity"]);
+        assert_eq!(result.rows.len(), 4);
+        assert_eq!(proofs.len(), 2);
+
+        // Each branch proof verifies against its own circuit
+        let head = ExecutionPlan {
+            union_all: vec![],
+            ..plan.clone()
+        };
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        for (branch, proof) in std::iter::once(&head).chain(&plan.union_all).zip(&proofs) {
+            let (_, filtered) = executor.evaluate(branch, &table).unwrap();
+            let circuit = executor.build_circuit(branch, &table, &filtered).unwrap();
+            let vk = prover.generate_vk(&circuit).unwrap();
+            assert!(verifier
+                .verify(&vk, proof, &circuit.public_inputs())
+                .unwrap());
+        }
+
+        // Branches with different column counts are rejected
+        let ast = parser
+            .parse(
+                "SELECT l_quantity FROM lineitem \
+                 UNION ALL SELECT l_quantity, l_quantity FROM lineitem",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert!(matches!(
+            executor.execute_union_all(&plan, &tables),
+            Err(QueryError::SchemaMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_group_by_returns_keys() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_returnflag".to_string(), DataType::Varchar(1)),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+            ],
+        );
+        for (flag, q) in [
+            ("R", 5),
+            ("A", 12),
+            ("R", 10),
+            ("N", 30),
+            ("A", 11),
+            ("R", 2),
+        ] {
+            table.rows.push(Row::new(vec![
+                Value::String(flag.to_string()),
+                Value::Integer(q),
+            ]));
+        }
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT l_returnflag, COUNT(*) FROM lineitem GROUP BY l_returnflag")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (result, _) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(result.columns, vec!["l_returnflag", "COUNT(*)"]);
+        let group = |flag: &str, count: i64| {
+            Row::new(vec![Value::String(flag.to_string()), Value::BigInt(count)])
+        };
+        assert_eq!(
+            result.rows,
+            vec![group("A", 2), group("N", 1), group("R", 3)]
+        );
+    }
+
+    #[test]
+    fn test_executor_having_count_proves_threshold() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+                Column::new("l_quantity".to_string(), DataType::Integer),
+            ],
+        );
+        for (key, q) in [(1, 5), (2, 12), (1, 10), (1, 30), (2, 11)] {
+            table
+                .rows
+                .push(Row::new(vec![Value::BigInt(key), Value::Integer(q)]));
+        }
+        let mut tables = HashMap::new();
+        tables.insert("lineitem".to_string(), table.clone());
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT l_orderkey, COUNT(*) FROM lineitem \
+                 GROUP BY l_orderkey HAVING COUNT(*) > 2",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        // Order 2 has only two rows and is dropped
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![Row::new(vec![Value::BigInt(1), Value::BigInt(3)])]
+        );
+
+        // The threshold and the kept group's count are public
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, vec![Field::from(2u64), Field::from(3u64)]);
+
+        let (executed, proof) = executor.execute(&plan, &tables).unwrap();
+        assert_eq!(executed.rows, result.rows);
+        let prover = Prover::new(&params);
+        let verifier = Verifier::new(&params);
+        let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+        assert!(verifier.verify(&vk, &proof, &public_inputs).unwrap());
+
+        // A proof claiming order 2's count of 2 passed is rejected, and so
+        // is one claiming order 1 has 4 rows
+        for (row, count) in [(3, 2u64), (0, 4)] {
+            let forged = circuit
+                .clone()
+                .with_having(vec![(row, Field::from(count))], Field::from(2u64));
+            let claimed = forged.public_inputs();
+            assert_eq!(claimed, vec![Field::from(2u64), Field::from(count)]);
+            let proof = prover.create_proof(&pk, &forged, &claimed).unwrap();
+            assert!(!verifier.verify(&vk, &proof, &claimed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_executor_aggregate_only_hides_rows() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let mut table = Table::new(
             "lineitem".to_string(),
+            vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+        );
+        for q in [5, 12, 10, 30] {
+            table.rows.push(Row::new(vec![Value::Integer(q)]));
+        }
+        let (commitment, blinds) =
+            DatabaseCommitment::commit_database_with_blinds(&[table.clone()], &params).unwrap();
+        let executor = QueryExecutor::new(&params)
+            .with_commitment(commitment, blinds)
+            .with_aggregate_only(true);
+        let mut tables = HashMap::new();
+        tables.insert("lineitem".to_string(), table.clone());
+
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*), SUM(l_quantity) FROM lineitem WHERE l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        // Only the aggregates are returned, and no committed cell is opened
+        let (result, proof) = executor.execute(&plan, &tables).unwrap();
+        assert!(result.aggregate_only);
+        assert_eq!(
+            result.rows,
+            vec![Row::new(vec![Value::BigInt(2), Value::Integer(42)])]
+        );
+        assert!(proof.openings.is_empty());
+
+        // The proof attests COUNT and SUM as its public inputs, bound to
+        // the commitment
+        let public_inputs = vec![Field::from(2u64), Field::from(42u64)];
+        let (_, filtered) = executor.evaluate(&plan, &table).unwrap();
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        assert_eq!(circuit.public_inputs(), public_inputs);
+        let vk = Prover::new(&params).generate_vk(&circuit).unwrap();
+        let verifier = Verifier::new(&params);
+        let commitment = executor.commitment().unwrap();
+        assert!(verifier
+            .verify_with_commitment(&vk, &proof, &public_inputs, commitment, None)
+            .unwrap());
+        let wrong_sum = vec![Field::from(2u64), Field::from(43u64)];
+        assert!(!verifier
+            .verify_with_commitment(&vk, &proof, &wrong_sum, commitment, None)
+            .unwrap());
+
+        // Aggregates that aren't public outputs can't be returned
+        let ast = parser
+            .parse("SELECT AVG(l_quantity) FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert!(matches!(
+            executor.execute(&plan, &tables),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_like_filter() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+
+        let mut table = Table::new(
+            "customer".to_string(),
             vec![Column::new(
-                "l_quantity".to_string(),
-                crate::types::DataType::Integer,
+                "c_name".to_string(),
+                crate::types::DataType::Varchar(25),
             )],
         );
-        table.rows.push(Row::new(vec![Value::Integer(10)]));
+        for name in [
+            "Customer#001",
+            "Supplier#002",
+            "Customer#003",
+            "customer#004",
+        ] {
+            table
+                .rows
+                .push(Row::new(vec![Value::String(name.to_string())]));
+        }
 
-        let mut tables = HashMap::new();
-        tables.insert("lineitem".to_string(), table);
+        let parser = crate::query::QueryParser::new();
+        let planner = crate::query::QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM customer WHERE c_name LIKE 'Customer%'")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert!(matches!(&plan.filters[0].condition, FilterCondition::Like(p) if p == "Customer%"));
 
-        // Create a simple plan
-        let plan = ExecutionPlan {
-            tables: vec!["lineitem".to_string()],
-            filters: vec![],
-            joins: vec![],
-            group_by: vec![],
-            aggregations: vec![AggregationOperation {
-                function: crate::query::planner::AggregationFunction::Count,
-                column: None,
-                alias: Some("count".to_string()),
-            }],
-            sort: vec![],
-            projection: vec![],
+        let filtered = executor
+            .apply_filter(&table.rows, &plan.filters[0], &table)
+            .unwrap();
+        let names: Vec<&Value> = filtered.iter().map(|row| &row.values[0]).collect();
+        assert_eq!(
+            names,
+            vec![
+                &Value::String("Customer#001".to_string()),
+                &Value::String("Customer#003".to_string()),
+            ]
+        );
+        // Strings are committed as hashes, so wildcard patterns aren't proven
+        assert!(matches!(
+            executor.build_circuit(&plan, &table, &filtered),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+
+        // Other patterns are evaluated too
+        for (pattern, expected) in [
+            ("%#00_", 4),
+            ("_ustomer%", 3),
+            ("%plier%", 1),
+            ("Customer", 0),
+        ] {
+            let filter = FilterOperation {
+                column: "c_name".to_string(),
+                condition: FilterCondition::Like(pattern.to_string()),
+            };
+            let filtered = executor.apply_filter(&table.rows, &filter, &table).unwrap();
+            assert_eq!(filtered.len(), expected, "pattern {}", pattern);
+        }
+    }
+
+    #[test]
+    fn test_executor_unfiltered_count_uses_committed_rows() {
+        use crate::proof::Verifier;
+
+        let params = IPAParams::new(10);
+        let plan = plan("SELECT COUNT(*) FROM lineitem");
+        let verifier = Verifier::new(&params);
+        let vk = Prover::new(&params)
+            .generate_vk(&NzengiCircuit::new())
+            .unwrap();
+
+        let committed = |n: i32| {
+            let table = small_lineitem(&(0..n).collect::<Vec<_>>());
+            let (commitment, blinds) =
+                DatabaseCommitment::commit_database_with_blinds(&[table.clone()], &params).unwrap();
+            (
+                QueryExecutor::new(&params).with_commitment(commitment, blinds),
+                table,
+            )
         };
 
-        // Note: This test may fail if circuit generation fails
-        // The actual execution depends on proper circuit configuration
-        let result = executor.execute(&plan, &tables);
-        if let Err(e) = result {
-            println!("Execution failed (expected for test): {}", e);
+        let (executor, table) = committed(5);
+        let (result, proof) = executor.execute(&plan, &tables([table.clone()])).unwrap();
+        let commitment = executor.commitment().unwrap();
+        assert_eq!(result.columns, vec!["COUNT(*)".to_string()]);
+        assert_eq!(result.rows[0].values, vec![Value::BigInt(5)]);
+        assert_eq!(proof.public_inputs, vec![commitment.hash_field()]);
+        assert!(proof.openings.is_empty());
+
+        // The count is checked against the commitment the proof is bound to
+        let verify = |commitment: &DatabaseCommitment, count: usize| {
+            verifier
+                .verify_committed_count(&vk, &proof, commitment, "lineitem", count, None)
+                .unwrap()
+        };
+        assert!(verify(commitment, 5));
+        assert!(!verify(commitment, 6));
+
+        // The proof size doesn't depend on the number of rows, and a proof
+        // doesn't verify against another commitment
+        let (larger_executor, larger_table) = committed(40);
+        let (result, larger) = larger_executor
+            .execute(&plan, &tables([larger_table]))
+            .unwrap();
+        assert_eq!(result.rows[0].values, vec![Value::BigInt(40)]);
+        assert_eq!(larger.size(), proof.size());
+        assert!(!verify(larger_executor.commitment().unwrap(), 40));
+
+        // A table changed since it was committed is rejected
+        let mut changed = table;
+        changed.rows.pop();
+        assert!(matches!(
+            executor.execute(&plan, &tables([changed])),
+            Err(QueryError::StaleCommitment(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_grouped_count_distinct() {
+        let params = IPAParams::new(10);
+        let executor = QueryExecutor::new(&params);
+        let mut table = Table::new(
+            "lineitem".to_string(),
+            vec![
+                Column::new("l_orderkey".to_string(), DataType::BigInt),
+                Column::new("l_suppkey".to_string(), DataType::Integer),
+            ],
+        );
+        let rows = [
+            (1, Value::Integer(20)),
+            (2, Value::Integer(30)),
+            (1, Value::Integer(10)),
+            (1, Value::Null),
+            (2, Value::Integer(30)),
+            (1, Value::Integer(20)),
+        ];
+        for (key, supplier) in rows {
+            table
+                .rows
+                .push(Row::new(vec![Value::BigInt(key), supplier]));
+        }
+        let plan =
+            plan("SELECT l_orderkey, COUNT(DISTINCT l_suppkey) FROM lineitem GROUP BY l_orderkey");
+
+        // NULL suppliers are not counted
+        let (result, filtered) = executor.evaluate(&plan, &table).unwrap();
+        assert_eq!(
+            result.rows,
+            vec![
+                Row::new(vec![Value::BigInt(1), Value::BigInt(2)]),
+                Row::new(vec![Value::BigInt(2), Value::BigInt(1)]),
+            ]
+        );
+        let (executed, _) = executor.execute(&plan, &tables([table.clone()])).unwrap();
+        assert_eq!(executed.rows, result.rows);
+
+        // Each group's key and distinct count are public
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[1, 2, 2, 1]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![public_inputs]),
+            Ok(())
+        );
+
+        // A tampered per-group count is rejected
+        let tampered = fields(&[1, 3, 2, 1]);
+        assert!(run_mock_with_instance(&circuit, 10, vec![tampered]).is_err());
+
+        // Every COUNT(DISTINCT) is proved, each over its column
+        let plan = crate::test_util::plan(
+            "SELECT l_orderkey, COUNT(DISTINCT l_suppkey), COUNT(DISTINCT l_orderkey) \
+             FROM lineitem GROUP BY l_orderkey",
+        );
+        let (_, filtered) = executor.evaluate(&plan, &table).unwrap();
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[1, 2, 2, 1, 1, 1, 2, 1]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![public_inputs]),
+            Ok(())
+        );
+
+        // Without GROUP BY, all rows are one group
+        let plan = crate::test_util::plan("SELECT COUNT(DISTINCT l_suppkey) FROM lineitem");
+        let (_, filtered) = executor.evaluate(&plan, &table).unwrap();
+        let circuit = executor.build_circuit(&plan, &table, &filtered).unwrap();
+        let public_inputs = circuit.public_inputs();
+        assert_eq!(public_inputs, fields(&[0, 3]));
+        assert_eq!(
+            run_mock_with_instance(&circuit, 10, vec![public_inputs]),
+            Ok(())
+        );
+
+        // Values the gate can't order are rejected instead of left unproved
+        for value in [
+            Value::String("MAIL".to_string()),
+            Value::Integer(-3),
+            Value::Decimal(-150),
+        ] {
+            let mut unsupported = table.clone();
+            unsupported.rows[1].values[1] = value;
+            let plan = crate::test_util::plan(
+                "SELECT l_orderkey, COUNT(DISTINCT l_suppkey) FROM lineitem GROUP BY l_orderkey",
+            );
+            assert!(matches!(
+                executor.build_circuit(&plan, &unsupported, &unsupported.rows),
+                Err(QueryError::UnsupportedFeature(_))
+            ));
         }
     }
 }
@@ -7,6 +7,8 @@
 //! - `parser`: SQL query parsing into AST
 //! - `planner`: Query execution planning (gate selection)
 //! - `executor`: Query execution with circuit building
+//! - `cache`: Cached results and proofs of repeated queries
+//! - `error`: `QueryError` returned by all of the above
 //!
 //! # Overview
 //!
@@ -50,13 +52,21 @@
 //! let (result, proof) = executor.execute(&optimized_plan, &database)?;
 //! ```
 
+pub mod cache;
+pub mod cancel;
+pub mod error;
 pub mod executor;
 pub mod optimizer;
 pub mod parser;
 pub mod planner;
 
 // Re-export main types for convenience
+pub use cache::ProofCache;
+pub use cancel::CancellationToken;
+pub use error::QueryError;
 pub use executor::QueryExecutor;
 pub use optimizer::{OptimizationStats, QueryOptimizer};
-pub use parser::QueryParser;
-pub use planner::{ExecutionPlan, QueryPlanner};
+pub use parser::{Capabilities, QueryParser};
+pub use planner::{
+    ConstantFilter, ExecutionPlan, FilterTree, HavingCondition, QueryPlanner, SupportReport,
+};
@@ -20,6 +20,9 @@
 //! 3. **Gate Reordering**: Reorder gates to minimize circuit size
 //! 4. **Aggregation Optimization**: Combine multiple aggregations when possible
 //! 5. **Sort Optimization**: Apply sort after filtering to reduce data size
+//! 6. **Sort/GroupBy Fusion**: Drop a sort already implied by the group-by's sorted input
+//! 7. **Projection Pruning**: Commit and prove only the columns the query reads
+//! 8. **Constant Folding**: Evaluate literal-only predicates such as `1 = 1` at plan time
 //!
 //! # Example
 //!
@@ -43,7 +46,8 @@
 //! 4. **Sort Last**: Apply sort after all other operations
 //! 5. **Projection**: Keep only necessary columns throughout the pipeline
 
-use crate::query::planner::{ExecutionPlan, FilterOperation, JoinOperation};
+use crate::query::error::QueryError;
+use crate::query::planner::{ExecutionPlan, FilterOperation, FilterTree, JoinOperation};
 
 /// Query optimizer
 ///
@@ -98,6 +102,8 @@ impl QueryOptimizer {
     ///
     /// This method applies various optimization strategies to the execution plan,
     /// including filter pushdown, join reordering, and gate reordering.
+    /// At level 0 the plan is returned unchanged, with no optimizations and
+    /// zero reductions reported.
     ///
     /// # Arguments
     /// * `plan` - The execution plan to optimize
@@ -115,12 +121,25 @@ impl QueryOptimizer {
     pub fn optimize(
         &self,
         plan: &ExecutionPlan,
-    ) -> Result<(ExecutionPlan, OptimizationStats), Box<dyn std::error::Error>> {
+    ) -> Result<(ExecutionPlan, OptimizationStats), QueryError> {
         let original_size = Self::plan_size(plan);
+
+        // Level 0 is a pass-through: the plan is returned unchanged
+        if self.level == 0 {
+            let stats = OptimizationStats::new(original_size, original_size, 0.0, 0.0, vec![]);
+            return Ok((plan.clone(), stats));
+        }
+
         let mut optimized_plan = plan.clone();
         let mut optimizations_applied = Vec::new();
 
         // Apply optimizations based on level
+        if self.level >= 1 && !optimized_plan.constant_filters.is_empty() {
+            // Constant folding: Drop always-true predicates, short-circuit always-false ones
+            optimized_plan = Self::apply_constant_folding(optimized_plan);
+            optimizations_applied.push("Constant Folding".to_string());
+        }
+
         if self.level >= 1 {
             // Filter pushdown: Apply filters as early as possible
             optimized_plan = Self::apply_filter_pushdown(optimized_plan);
@@ -133,6 +152,20 @@ impl QueryOptimizer {
             optimizations_applied.push("Sort Optimization".to_string());
         }
 
+        if self.level >= 1 && Self::can_fuse_sort_into_group_by(&optimized_plan) {
+            // Sort/GroupBy fusion: The group-by gate already sorts its input
+            optimized_plan = Self::apply_sort_group_by_fusion(optimized_plan);
+            optimizations_applied.push("Sort/GroupBy Fusion".to_string());
+        }
+
+        if self.level >= 1 {
+            // Projection pruning: Narrow the circuit input to referenced columns
+            optimized_plan = Self::apply_projection_pruning(optimized_plan);
+            if optimized_plan.required_columns.is_some() {
+                optimizations_applied.push("Projection Pruning".to_string());
+            }
+        }
+
         if self.level >= 2 {
             // Join reordering: Optimize join order based on table sizes
             optimized_plan = Self::apply_join_reordering(optimized_plan);
@@ -148,12 +181,17 @@ impl QueryOptimizer {
         let optimized_size = Self::plan_size(&optimized_plan);
 
         // Calculate estimated reductions (simplified estimates)
-        let circuit_size_reduction = if original_size > 0 {
+        let plan_size_reduction = if original_size > 0 {
             ((original_size - optimized_size) as f64 / original_size as f64) * 100.0
         } else {
             0.0
         };
 
+        // Pruned columns shrink the circuit independently of the plan size
+        let column_reduction = Self::estimate_column_reduction(&optimized_plan);
+        let circuit_size_reduction =
+            100.0 - (100.0 - plan_size_reduction) * (100.0 - column_reduction) / 100.0;
+
         let proof_time_reduction = circuit_size_reduction * 0.8; // Rough estimate
 
         let stats = OptimizationStats {
@@ -167,6 +205,21 @@ impl QueryOptimizer {
         Ok((optimized_plan, stats))
     }
 
+    /// Apply constant folding
+    ///
+    /// Literal-only predicates are evaluated at plan time. Always-true ones
+    /// are dropped; an always-false one marks the plan as an empty result
+    /// and removes the remaining filters, whose gates would prove nothing.
+    fn apply_constant_folding(mut plan: ExecutionPlan) -> ExecutionPlan {
+        if plan.is_empty_result() {
+            plan.empty_result = true;
+            plan.filters.clear();
+            plan.filter_tree = None;
+        }
+        plan.constant_filters.clear();
+        plan
+    }
+
     /// Apply filter pushdown optimization
     ///
     /// This optimization moves filters as early as possible in the execution plan
@@ -207,6 +260,97 @@ impl QueryOptimizer {
         plan
     }
 
+    /// Check whether the sort is redundant with the group-by
+    ///
+    /// The group-by gate requires its input sorted ascending by the group
+    /// columns, so an ascending sort whose columns are a prefix of (or equal
+    /// to) the group-by columns is already satisfied.
+    fn can_fuse_sort_into_group_by(plan: &ExecutionPlan) -> bool {
+        let group_columns: Vec<&String> = plan
+            .group_by
+            .iter()
+            .flat_map(|group_by| group_by.columns.iter())
+            .collect();
+
+        !group_columns.is_empty()
+            && !plan.sort.is_empty()
+            && plan.sort.iter().all(|sort| {
+                !sort.columns.is_empty()
+                    && sort.columns.len() <= group_columns.len()
+                    && sort
+                        .columns
+                        .iter()
+                        .zip(group_columns.iter())
+                        .all(|(sort_col, group_col)| sort_col == *group_col)
+                    && sort.ascending.iter().all(|&asc| asc)
+            })
+    }
+
+    /// Apply sort/group-by fusion
+    ///
+    /// This optimization removes sort operations that are redundant with the
+    /// group-by, saving a separate sort gate.
+    fn apply_sort_group_by_fusion(mut plan: ExecutionPlan) -> ExecutionPlan {
+        plan.sort.clear();
+        plan
+    }
+
+    /// Apply projection pruning optimization
+    ///
+    /// This optimization computes the columns referenced by filters, joins,
+    /// group-by, aggregations, sorts, and projections, so the executor only
+    /// feeds those columns into the circuit. Queries selecting `*` read every
+    /// column and are left unpruned.
+    fn apply_projection_pruning(mut plan: ExecutionPlan) -> ExecutionPlan {
+        if plan.projection.iter().any(|col| col == "*") {
+            return plan;
+        }
+
+        let mut required: Vec<String> = Vec::new();
+        let mut require = |column: &String| {
+            if !required.contains(column) {
+                required.push(column.clone());
+            }
+        };
+
+        let tree_leaves = plan.filter_tree.iter().flat_map(FilterTree::leaves);
+        for filter in plan.filters.iter().chain(tree_leaves) {
+            require(&filter.column);
+        }
+        for join in &plan.joins {
+            require(&join.left_column);
+            require(&join.right_column);
+        }
+        for group_by in &plan.group_by {
+            group_by.columns.iter().for_each(&mut require);
+        }
+        for agg in &plan.aggregations {
+            if let Some(column) = &agg.column {
+                require(column);
+            }
+        }
+        for sort in &plan.sort {
+            sort.columns.iter().for_each(&mut require);
+        }
+        for computed in &plan.computed {
+            computed.expr.columns().iter().for_each(&mut require);
+        }
+        for column in &plan.projection {
+            // Computed columns and aggregation aliases are not table columns
+            let is_computed = plan.computed.iter().any(|c| &c.name == column);
+            let is_alias = plan
+                .aggregations
+                .iter()
+                .any(|agg| agg.alias.as_ref() == Some(column));
+            if !is_computed && !is_alias {
+                require(column);
+            }
+        }
+
+        plan.required_columns = Some(required);
+        plan
+    }
+
     /// Apply join reordering optimization
     ///
     /// This optimization reorders joins to minimize intermediate result sizes.
@@ -260,8 +404,12 @@ impl QueryOptimizer {
             crate::query::planner::FilterCondition::Equal(_) => 0.1, // Highly selective
             crate::query::planner::FilterCondition::GreaterThan(_) => 0.3,
             crate::query::planner::FilterCondition::LessThan(_) => 0.3,
+            crate::query::planner::FilterCondition::GreaterOrEqual(_) => 0.3,
+            crate::query::planner::FilterCondition::LessOrEqual(_) => 0.3,
             crate::query::planner::FilterCondition::Between(_, _) => 0.2,
             crate::query::planner::FilterCondition::In(_) => 0.15,
+            crate::query::planner::FilterCondition::Like(_) => 0.25,
+            crate::query::planner::FilterCondition::ColumnCompare { .. } => 0.3,
         }
     }
 
@@ -299,9 +447,47 @@ impl QueryOptimizer {
         }
     }
 
+    /// Estimate table width
+    ///
+    /// Returns an estimated number of columns for a TPC-H table (simplified).
+    fn estimate_table_width(table_name: &str) -> usize {
+        if table_name.contains("lineitem") {
+            16
+        } else if table_name.contains("partsupp") {
+            5
+        } else if table_name.contains("order") || table_name.contains("part") {
+            9
+        } else if table_name.contains("customer") {
+            8
+        } else if table_name.contains("supplier") {
+            7
+        } else {
+            4
+        }
+    }
+
+    /// Estimate circuit size reduction from projection pruning (percentage)
+    fn estimate_column_reduction(plan: &ExecutionPlan) -> f64 {
+        let (Some(required), Some(table)) = (&plan.required_columns, plan.tables.first()) else {
+            return 0.0;
+        };
+
+        let width = Self::estimate_table_width(table);
+        if required.len() >= width {
+            0.0
+        } else {
+            (1.0 - required.len() as f64 / width as f64) * 100.0
+        }
+    }
+
     /// Calculate plan size (number of operations)
     fn plan_size(plan: &ExecutionPlan) -> usize {
         plan.filters.len()
+            + plan.constant_filters.len()
+            + plan
+                .filter_tree
+                .as_ref()
+                .map_or(0, |tree| tree.leaves().len())
             + plan.joins.len()
             + plan.group_by.len()
             + plan.aggregations.len()
@@ -348,6 +534,7 @@ impl OptimizationStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::planner::FilterCondition;
 
     #[test]
     fn test_query_optimizer_new() {
@@ -377,17 +564,66 @@ mod tests {
         assert!(stats.is_optimized());
     }
 
+    #[test]
+    fn test_optimize_level_zero_pass_through() {
+        let optimizer = QueryOptimizer::with_level(0);
+        let filter = |column: &str, condition: FilterCondition| FilterOperation {
+            column: column.to_string(),
+            condition,
+        };
+        let plan = ExecutionPlan {
+            tables: vec!["lineitem".to_string()],
+            filters: vec![
+                filter("l_quantity", FilterCondition::GreaterThan("10".to_string())),
+                filter(
+                    "l_discount",
+                    FilterCondition::Between("1".to_string(), "5".to_string()),
+                ),
+                filter("l_tax", FilterCondition::Equal("2".to_string())),
+            ],
+            projection: vec!["l_quantity".to_string()],
+            ..Default::default()
+        };
+
+        let (optimized, stats) = optimizer.optimize(&plan).unwrap();
+
+        let columns = |p: &ExecutionPlan| {
+            p.filters
+                .iter()
+                .map(|f| f.column.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(&optimized), columns(&plan));
+        assert!(optimized.required_columns.is_none());
+        assert!(stats.optimizations_applied.is_empty());
+        assert_eq!(stats.original_size, stats.optimized_size);
+        assert_eq!(stats.circuit_size_reduction, 0.0);
+        assert_eq!(stats.proof_time_reduction, 0.0);
+
+        // Level 1 reorders the same filters by selectivity
+        let (reordered, _) = QueryOptimizer::with_level(1).optimize(&plan).unwrap();
+        assert_ne!(columns(&reordered), columns(&plan));
+    }
+
     #[test]
     fn test_optimize_empty_plan() {
         let optimizer = QueryOptimizer::new();
         let plan = ExecutionPlan {
             tables: vec![],
             filters: vec![],
+            filter_tree: None,
+            constant_filters: vec![],
+            empty_result: false,
             joins: vec![],
             group_by: vec![],
+            having: None,
             aggregations: vec![],
             sort: vec![],
             projection: vec![],
+            computed: vec![],
+            required_columns: None,
+            derived: vec![],
+            union_all: vec![],
         };
 
         let result = optimizer.optimize(&plan);
@@ -413,16 +649,158 @@ mod tests {
         assert!(size > 0.0);
     }
 
+    #[test]
+    fn test_sort_group_by_fusion() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT l_returnflag, COUNT(*) FROM lineitem \
+                 GROUP BY l_returnflag ORDER BY l_returnflag",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.sort.len(), 1);
+
+        let optimizer = QueryOptimizer::new();
+        let (optimized, stats) = optimizer.optimize(&plan).unwrap();
+
+        assert!(optimized.sort.is_empty(), "Sort should be eliminated");
+        assert_eq!(optimized.group_by.len(), 1);
+        assert!(stats
+            .optimizations_applied
+            .contains(&"Sort/GroupBy Fusion".to_string()));
+        assert_eq!(stats.optimized_size, stats.original_size - 1);
+        assert!(stats.is_optimized());
+    }
+
+    #[test]
+    fn test_sort_group_by_no_fusion_on_different_key() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser
+            .parse(
+                "SELECT l_returnflag, COUNT(*) FROM lineitem \
+                 GROUP BY l_returnflag ORDER BY l_linestatus",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let optimizer = QueryOptimizer::new();
+        let (optimized, stats) = optimizer.optimize(&plan).unwrap();
+
+        assert_eq!(optimized.sort.len(), 1);
+        assert!(!stats
+            .optimizations_applied
+            .contains(&"Sort/GroupBy Fusion".to_string()));
+    }
+
+    #[test]
+    fn test_projection_pruning() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT SUM(l_extendedprice) FROM lineitem WHERE l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let optimizer = QueryOptimizer::new();
+        let (optimized, stats) = optimizer.optimize(&plan).unwrap();
+
+        let required = optimized.required_columns.unwrap();
+        assert_eq!(required.len(), 2);
+        assert!(required.contains(&"l_quantity".to_string()));
+        assert!(required.contains(&"l_extendedprice".to_string()));
+        assert!(stats
+            .optimizations_applied
+            .contains(&"Projection Pruning".to_string()));
+        // 2 of 16 lineitem columns remain
+        assert!((stats.circuit_size_reduction - 87.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_projection_pruning_skips_wildcard() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser.parse("SELECT * FROM lineitem").unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let optimizer = QueryOptimizer::new();
+        let (optimized, stats) = optimizer.optimize(&plan).unwrap();
+
+        assert!(optimized.required_columns.is_none());
+        assert!(!stats
+            .optimizations_applied
+            .contains(&"Projection Pruning".to_string()));
+    }
+
+    #[test]
+    fn test_constant_folding_always_true() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE 1 = 1 AND l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.constant_filters.len(), 1);
+
+        let (optimized, stats) = QueryOptimizer::new().optimize(&plan).unwrap();
+        assert!(optimized.constant_filters.is_empty());
+        assert!(!optimized.is_empty_result());
+        assert_eq!(optimized.filters.len(), 1);
+        assert!(stats
+            .optimizations_applied
+            .contains(&"Constant Folding".to_string()));
+    }
+
+    #[test]
+    fn test_constant_folding_always_false() {
+        use crate::query::{QueryParser, QueryPlanner};
+
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE 0 = 1 AND l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let (optimized, stats) = QueryOptimizer::new().optimize(&plan).unwrap();
+        assert!(optimized.empty_result);
+        assert!(optimized.filters.is_empty());
+        assert!(optimized.constant_filters.is_empty());
+        assert!(stats
+            .optimizations_applied
+            .contains(&"Constant Folding".to_string()));
+    }
+
     #[test]
     fn test_plan_size() {
         let plan = ExecutionPlan {
             tables: vec!["table1".to_string()],
             filters: vec![],
+            filter_tree: None,
+            constant_filters: vec![],
+            empty_result: false,
             joins: vec![],
             group_by: vec![],
+            having: None,
             aggregations: vec![],
             sort: vec![],
             projection: vec!["col1".to_string()],
+            computed: vec![],
+            required_columns: None,
+            derived: vec![],
+            union_all: vec![],
         };
 
         let size = QueryOptimizer::plan_size(&plan);
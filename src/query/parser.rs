@@ -12,10 +12,34 @@
 //! let ast = parser.parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10")?;
 //! ```
 
-use sqlparser::ast::{Expr, GroupByExpr, Query, SelectItem, SetExpr, Statement};
+use crate::query::error::QueryError;
+use crate::query::planner::{AggregationFunction, ArithmeticOp, CompareOp};
+use sqlparser::ast::{Expr, GroupByExpr, Join, Query, SelectItem, SetExpr, Statement, TableFactor};
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
 
+/// SQL features supported by the query engine
+///
+/// Describes the engine as a whole, as opposed to
+/// `QueryPlanner::supported`, which reports on a single query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Aggregate functions, e.g. `COUNT`
+    pub aggregate_functions: Vec<String>,
+
+    /// Operators usable in WHERE, e.g. `>` or `BETWEEN`
+    pub filter_operators: Vec<String>,
+
+    /// Arithmetic operators usable in projections, e.g. `*`
+    pub arithmetic_operators: Vec<String>,
+
+    /// Join types, e.g. `INNER`
+    pub join_types: Vec<String>,
+
+    /// Clauses, e.g. `GROUP BY`
+    pub clauses: Vec<String>,
+}
+
 /// SQL query parser
 ///
 /// This struct provides methods for parsing SQL queries into ASTs.
@@ -33,6 +57,36 @@ impl QueryParser {
         }
     }
 
+    /// List the SQL features supported by the query engine
+    ///
+    /// # Returns
+    /// `Capabilities` enumerating supported functions, operators, joins and clauses
+    pub fn capabilities() -> Capabilities {
+        let names = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let mut filter_operators: Vec<String> =
+            CompareOp::ALL.iter().map(|op| op.to_string()).collect();
+        filter_operators.extend(names(&["BETWEEN", "IN", "LIKE", "AND", "OR"]));
+
+        Capabilities {
+            aggregate_functions: AggregationFunction::ALL
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+            filter_operators,
+            arithmetic_operators: ArithmeticOp::ALL.iter().map(|op| op.to_string()).collect(),
+            join_types: names(&["INNER"]),
+            clauses: names(&[
+                "WHERE",
+                "JOIN",
+                "GROUP BY",
+                "ORDER BY",
+                "FROM subquery",
+                "UNION ALL",
+            ]),
+        }
+    }
+
     /// Parse a SQL query string into an AST
     ///
     /// # Arguments
@@ -48,14 +102,16 @@ impl QueryParser {
     /// let parser = QueryParser::new();
     /// let ast = parser.parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10")?;
     /// ```
-    pub fn parse(&self, query: &str) -> Result<Statement, Box<dyn std::error::Error>> {
+    pub fn parse(&self, query: &str) -> Result<Statement, QueryError> {
         // In sqlparser 0.59, use Parser::parse_sql static method
         // See: https://docs.rs/sqlparser/0.59.0/sqlparser/index.html
         let ast = Parser::parse_sql(&self.dialect, query)
-            .map_err(|e| format!("Failed to parse SQL query: {}", e))?;
+            .map_err(|e| QueryError::ParseError(e.to_string()))?;
 
         if ast.len() != 1 {
-            return Err("Expected exactly one SQL statement".into());
+            return Err(QueryError::ParseError(
+                "Expected exactly one SQL statement".to_string(),
+            ));
         }
 
         Ok(ast.into_iter().next().unwrap())
@@ -68,12 +124,14 @@ impl QueryParser {
     ///
     /// # Returns
     /// `Ok(Query)` if parsing succeeds, `Err` otherwise
-    pub fn parse_select(&self, query: &str) -> Result<Query, Box<dyn std::error::Error>> {
+    pub fn parse_select(&self, query: &str) -> Result<Query, QueryError> {
         let statement = self.parse(query)?;
 
         match statement {
             Statement::Query(query) => Ok(*query),
-            _ => Err("Expected SELECT query".into()),
+            _ => Err(QueryError::UnsupportedFeature(
+                "Only SELECT queries are supported".to_string(),
+            )),
         }
     }
 
@@ -93,6 +151,8 @@ impl QueryParser {
 
     /// Extract FROM clause from a query
     ///
+    /// Derived tables (`FROM (SELECT ...) t`) are named by their alias.
+    ///
     /// # Arguments
     /// * `query` - SQL query AST
     ///
@@ -103,12 +163,112 @@ impl QueryParser {
             SetExpr::Select(select) => select
                 .from
                 .iter()
-                .map(|table| table.relation.to_string())
+                .flat_map(|table| {
+                    std::iter::once(&table.relation)
+                        .chain(table.joins.iter().map(|join| &join.relation))
+                })
+                .map(Self::relation_name)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Extract JOIN clauses from the FROM clause
+    ///
+    /// # Arguments
+    /// * `query` - SQL query AST
+    ///
+    /// # Returns
+    /// Vector of joins, in FROM order
+    pub fn extract_joins(&self, query: &Query) -> Vec<Join> {
+        match &*query.body {
+            SetExpr::Select(select) => select
+                .from
+                .iter()
+                .flat_map(|table| table.joins.iter().cloned())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Extract table aliases from the FROM clause
+    ///
+    /// # Arguments
+    /// * `query` - SQL query AST
+    ///
+    /// # Returns
+    /// Vector of (alias, table name) for each aliased table
+    pub fn extract_table_aliases(&self, query: &Query) -> Vec<(String, String)> {
+        match &*query.body {
+            SetExpr::Select(select) => select
+                .from
+                .iter()
+                .flat_map(|table| {
+                    std::iter::once(&table.relation)
+                        .chain(table.joins.iter().map(|join| &join.relation))
+                })
+                .filter_map(|relation| match relation {
+                    TableFactor::Table {
+                        name,
+                        alias: Some(alias),
+                        ..
+                    } => Some((alias.name.value.clone(), name.to_string())),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Name of a table in the FROM clause
+    ///
+    /// Tables are named without their alias; derived tables by their alias.
+    pub fn relation_name(relation: &TableFactor) -> String {
+        match relation {
+            TableFactor::Table { name, .. } => name.to_string(),
+            TableFactor::Derived { alias, .. } => Self::derived_table_name(alias),
+            relation => relation.to_string(),
+        }
+    }
+
+    /// Extract derived tables (subqueries) from the FROM clause
+    ///
+    /// # Arguments
+    /// * `query` - SQL query AST
+    ///
+    /// # Returns
+    /// Vector of (alias, subquery, lateral) for each derived table
+    pub fn extract_derived_tables(&self, query: &Query) -> Vec<(String, Query, bool)> {
+        match &*query.body {
+            SetExpr::Select(select) => select
+                .from
+                .iter()
+                .filter_map(|table| match &table.relation {
+                    TableFactor::Derived {
+                        lateral,
+                        subquery,
+                        alias,
+                        ..
+                    } => Some((
+                        Self::derived_table_name(alias),
+                        (**subquery).clone(),
+                        *lateral,
+                    )),
+                    _ => None,
+                })
                 .collect(),
             _ => vec![],
         }
     }
 
+    /// Name a derived table by its alias
+    fn derived_table_name(alias: &Option<sqlparser::ast::TableAlias>) -> String {
+        alias
+            .as_ref()
+            .map(|alias| alias.name.value.clone())
+            .unwrap_or_else(|| "subquery".to_string())
+    }
+
     /// Extract WHERE clause from a query
     ///
     /// # Arguments
@@ -123,6 +283,20 @@ impl QueryParser {
         }
     }
 
+    /// Extract HAVING clause from a query
+    ///
+    /// # Arguments
+    /// * `query` - SQL query AST
+    ///
+    /// # Returns
+    /// Optional HAVING expression
+    pub fn extract_having(&self, query: &Query) -> Option<Expr> {
+        match &*query.body {
+            SetExpr::Select(select) => select.having.clone(),
+            _ => None,
+        }
+    }
+
     /// Extract GROUP BY clause from a query
     ///
     /// # Arguments
@@ -183,6 +357,46 @@ mod tests {
         assert!(true); // Parser created successfully
     }
 
+    #[test]
+    fn test_parser_capabilities() {
+        use crate::query::planner::QueryPlanner;
+
+        let capabilities = QueryParser::capabilities();
+        for function in ["COUNT", "SUM", "AVG", "MIN", "MAX"] {
+            assert!(capabilities
+                .aggregate_functions
+                .iter()
+                .any(|f| f == function));
+        }
+        for op in [">", "<", "="] {
+            assert!(capabilities.filter_operators.iter().any(|o| o == op));
+        }
+
+        // Every listed aggregate and comparison is planned
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+        for function in &capabilities.aggregate_functions {
+            let ast = parser
+                .parse(&format!("SELECT {}(l_quantity) FROM lineitem", function))
+                .unwrap();
+            assert_eq!(planner.plan(&ast).unwrap().aggregations.len(), 1);
+        }
+        for op in CompareOp::ALL {
+            let ast = parser
+                .parse(&format!(
+                    "SELECT * FROM lineitem WHERE l_quantity {} 10",
+                    op
+                ))
+                .unwrap();
+            assert_eq!(planner.plan(&ast).unwrap().filters.len(), 1);
+        }
+
+        // Rejected clauses are not listed
+        let ast = parser.parse("SELECT * FROM lineitem LIMIT 1").unwrap();
+        assert!(!planner.supported(&ast).is_fully_supported());
+        assert!(!capabilities.clauses.iter().any(|c| c == "LIMIT"));
+    }
+
     #[test]
     fn test_parser_parse_simple() {
         let parser = QueryParser::new();
@@ -190,6 +404,13 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_parser_parse_invalid() {
+        let parser = QueryParser::new();
+        let result = parser.parse("SELEC * FORM lineitem");
+        assert!(matches!(result, Err(QueryError::ParseError(_))));
+    }
+
     #[test]
     fn test_parser_parse_select() {
         let parser = QueryParser::new();
@@ -206,6 +427,40 @@ mod tests {
         assert!(tables[0].contains("lineitem"));
     }
 
+    #[test]
+    fn test_parser_extract_derived_tables() {
+        let parser = QueryParser::new();
+        let query = parser
+            .parse_select("SELECT COUNT(*) FROM (SELECT l_quantity FROM lineitem) t")
+            .unwrap();
+
+        assert_eq!(parser.extract_tables(&query), vec!["t"]);
+        let derived = parser.extract_derived_tables(&query);
+        assert_eq!(derived.len(), 1);
+        assert_eq!(derived[0].0, "t");
+        assert_eq!(parser.extract_tables(&derived[0].1), vec!["lineitem"]);
+    }
+
+    #[test]
+    fn test_parser_extract_joins() {
+        let parser = QueryParser::new();
+        let query = parser
+            .parse_select(
+                "SELECT COUNT(*) FROM orders o JOIN customer c ON o.o_custkey = c.c_custkey",
+            )
+            .unwrap();
+
+        assert_eq!(parser.extract_tables(&query), vec!["orders", "customer"]);
+        assert_eq!(parser.extract_joins(&query).len(), 1);
+        assert_eq!(
+            parser.extract_table_aliases(&query),
+            vec![
+                ("o".to_string(), "orders".to_string()),
+                ("c".to_string(), "customer".to_string())
+            ]
+        );
+    }
+
     #[test]
     fn test_parser_extract_where() {
         let parser = QueryParser::new();
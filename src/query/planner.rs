@@ -15,14 +15,21 @@
 //! let plan = planner.plan(&ast)?;
 //! ```
 
+use crate::query::error::QueryError;
 use crate::query::parser::QueryParser;
-use sqlparser::ast::{Expr, Query, SelectItem, Statement};
+use crate::types::{DataType, Value};
+use sqlparser::ast::{
+    BinaryOperator, DataType as SqlDataType, Expr, Join, JoinConstraint, JoinOperator, Query,
+    SelectItem, SetExpr, SetOperator, SetQuantifier, Statement,
+};
+use std::collections::HashMap;
+use std::fmt;
 
 /// Query execution plan
 ///
 /// This struct represents an execution plan for a SQL query,
 /// specifying which gates to use for each operation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ExecutionPlan {
     /// Tables to query
     pub tables: Vec<String>,
@@ -30,12 +37,27 @@ pub struct ExecutionPlan {
     /// Filter operations (Range Check Gates)
     pub filters: Vec<FilterOperation>,
 
+    /// Boolean filter combining leaves with OR (`None` = no disjunction)
+    ///
+    /// Rows must satisfy both `filters` and this tree. Top-level AND leaves
+    /// are kept in `filters` so they can be reordered by the optimizer.
+    pub filter_tree: Option<FilterTree>,
+
+    /// Predicates comparing two literals, e.g. `1 = 1` (folded by the optimizer)
+    pub constant_filters: Vec<ConstantFilter>,
+
+    /// Whether the filters can never hold, so the query selects no rows
+    pub empty_result: bool,
+
     /// Join operations (Join Gates)
     pub joins: Vec<JoinOperation>,
 
     /// Group-by operations (Group-By Gates)
     pub group_by: Vec<GroupByOperation>,
 
+    /// Predicate on each group's aggregates (HAVING), `None` = keep all groups
+    pub having: Option<HavingCondition>,
+
     /// Aggregation operations (Aggregation Gates)
     pub aggregations: Vec<AggregationOperation>,
 
@@ -44,6 +66,286 @@ pub struct ExecutionPlan {
 
     /// Projection operations (column selection)
     pub projection: Vec<String>,
+
+    /// Computed projections (Arithmetic Gates)
+    pub computed: Vec<ComputedColumn>,
+
+    /// Columns read by the query (set by projection pruning, `None` = all columns)
+    pub required_columns: Option<Vec<String>>,
+
+    /// Derived tables (subqueries in FROM), materialized before this plan
+    pub derived: Vec<DerivedTable>,
+
+    /// Plans whose results are appended to this plan's result (UNION ALL)
+    pub union_all: Vec<ExecutionPlan>,
+}
+
+impl ExecutionPlan {
+    /// Check whether the query selects no rows regardless of the data
+    ///
+    /// # Returns
+    /// `true` if the plan was marked empty or a constant filter is false
+    pub fn is_empty_result(&self) -> bool {
+        self.empty_result || self.constant_filters.iter().any(|f| !f.evaluate())
+    }
+
+    /// Compute a stable identifier for the plan
+    ///
+    /// The plan is encoded canonically section by section (tables, filters,
+    /// joins, group-by, HAVING, aggregations, sort, projection, computed
+    /// columns, derived tables, and UNION ALL branches) and hashed with SHA-256.
+    /// Filters are a conjunction, so their order does not affect the hash;
+    /// neither does the order of the children of an AND/OR in the filter tree. `required_columns` is derived
+    /// by the optimizer from the other sections and is not included.
+    ///
+    /// # Returns
+    /// Hex-encoded SHA-256 hash string
+    pub fn stable_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for (section, items) in self.canonical_sections() {
+            hasher.update(section.as_bytes());
+            hasher.update((items.len() as u64).to_le_bytes());
+            for item in items {
+                hasher.update((item.len() as u64).to_le_bytes());
+                hasher.update(item.as_bytes());
+            }
+        }
+
+        hex::encode(hasher.finalize())
+    }
+
+    /// Render the plan as normalized SQL
+    ///
+    /// The SELECT list keeps the order of `projection`, with aggregations
+    /// placed by their aliases and unaliased aggregations at the end; WHERE
+    /// lists the filters, then the filter tree, then literal comparisons.
+    /// Keywords are upper case and every sort column has an explicit
+    /// direction. Planning the SQL again gives a plan with the same
+    /// `stable_hash`, except that a plan the optimizer marked empty renders
+    /// its empty result as `0 = 1`. Plans have no LIMIT, so none is rendered.
+    ///
+    /// # Returns
+    /// SQL text of the query
+    pub fn to_sql(&self) -> String {
+        let mut sql = format!("SELECT {}", self.select_list().join(", "));
+
+        let mut from = String::new();
+        for (i, table) in self.tables.iter().enumerate() {
+            let relation = match self.derived.iter().find(|d| d.alias == *table) {
+                Some(derived) => format!("({}) AS {}", derived.plan.to_sql(), table),
+                None => table.clone(),
+            };
+            match self.joins.iter().find(|j| j.right_table == *table) {
+                Some(join) if i > 0 => from.push_str(&format!(
+                    " JOIN {} ON {}.{} = {}.{}",
+                    relation,
+                    join.left_table,
+                    join.left_column,
+                    join.right_table,
+                    join.right_column
+                )),
+                _ if i > 0 => from.push_str(&format!(", {}", relation)),
+                _ => from.push_str(&relation),
+            }
+        }
+        if !from.is_empty() {
+            sql.push_str(&format!(" FROM {}", from));
+        }
+
+        let mut conjuncts: Vec<String> = self.filters.iter().map(|f| f.to_sql()).collect();
+        match &self.filter_tree {
+            Some(tree @ FilterTree::Or(_)) => conjuncts.push(format!("({})", tree.to_sql())),
+            Some(tree) => conjuncts.push(tree.to_sql()),
+            None => {}
+        }
+        conjuncts.extend(self.constant_filters.iter().map(|c| {
+            format!(
+                "{} {} {}",
+                sql_literal(&c.left),
+                c.op,
+                sql_literal(&c.right)
+            )
+        }));
+        if self.empty_result {
+            conjuncts.push("0 = 1".to_string());
+        }
+        if !conjuncts.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conjuncts.join(" AND ")));
+        }
+
+        let group_by: Vec<&str> = self
+            .group_by
+            .iter()
+            .flat_map(|g| g.columns.iter().map(String::as_str))
+            .collect();
+        if !group_by.is_empty() {
+            sql.push_str(&format!(" GROUP BY {}", group_by.join(", ")));
+        }
+        if let Some(having) = &self.having {
+            sql.push_str(&format!(" HAVING {}", having));
+        }
+
+        let order_by: Vec<String> = self
+            .sort
+            .iter()
+            .flat_map(|s| {
+                s.columns.iter().zip(&s.ascending).map(|(column, asc)| {
+                    format!("{} {}", column, if *asc { "ASC" } else { "DESC" })
+                })
+            })
+            .collect();
+        if !order_by.is_empty() {
+            sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+
+        for branch in &self.union_all {
+            sql.push_str(&format!(" UNION ALL {}", branch.to_sql()));
+        }
+
+        sql
+    }
+
+    /// Items of the SELECT list rendered by `to_sql`
+    fn select_list(&self) -> Vec<String> {
+        let aggregation = |a: &AggregationOperation| {
+            let call = format!("{}({})", a.function, a.column.as_deref().unwrap_or("*"));
+            match &a.alias {
+                Some(alias) => format!("{} AS {}", call, alias),
+                None => call,
+            }
+        };
+
+        let mut items = vec![];
+        let mut next = 0;
+        for name in &self.projection {
+            let aliased = self.aggregations[next..]
+                .iter()
+                .position(|a| a.alias.as_deref() == Some(name.as_str()));
+            if let Some(offset) = aliased {
+                // Unaliased aggregations before it keep their relative order
+                items.extend(
+                    self.aggregations[next..=next + offset]
+                        .iter()
+                        .map(aggregation),
+                );
+                next += offset + 1;
+            } else if let Some(computed) = self.computed.iter().find(|c| c.name == *name) {
+                let expr = computed.expr.to_string();
+                if computed.name == expr {
+                    items.push(expr);
+                } else {
+                    items.push(format!("{} AS {}", expr, computed.name));
+                }
+            } else {
+                items.push(name.clone());
+            }
+        }
+        items.extend(self.aggregations[next..].iter().map(aggregation));
+        items
+    }
+
+    /// Canonical encoding of each plan section, used by `stable_hash`
+    fn canonical_sections(&self) -> Vec<(&'static str, Vec<String>)> {
+        let mut filters: Vec<String> = self.filters.iter().map(|f| f.to_string()).collect();
+        filters.sort();
+        if let Some(tree) = &self.filter_tree {
+            filters.push(tree.canonical());
+        }
+        let mut constants: Vec<String> = self
+            .constant_filters
+            .iter()
+            .map(|f| f.to_string())
+            .collect();
+        constants.sort();
+        filters.extend(constants);
+        if self.empty_result {
+            filters.push("FALSE".to_string());
+        }
+
+        vec![
+            ("tables", self.tables.clone()),
+            ("filters", filters),
+            (
+                "joins",
+                self.joins
+                    .iter()
+                    .map(|j| {
+                        format!(
+                            "{}.{}={}.{}",
+                            j.left_table, j.left_column, j.right_table, j.right_column
+                        )
+                    })
+                    .collect(),
+            ),
+            (
+                "group_by",
+                self.group_by.iter().map(|g| g.columns.join(",")).collect(),
+            ),
+            (
+                "having",
+                self.having.iter().map(|h| h.to_string()).collect(),
+            ),
+            (
+                "aggregations",
+                self.aggregations
+                    .iter()
+                    .map(|a| {
+                        format!(
+                            "{}({}) as {}",
+                            a.function,
+                            a.column.as_deref().unwrap_or("*"),
+                            a.alias.as_deref().unwrap_or("")
+                        )
+                    })
+                    .collect(),
+            ),
+            (
+                "sort",
+                self.sort
+                    .iter()
+                    .flat_map(|s| {
+                        s.columns.iter().zip(&s.ascending).map(|(column, asc)| {
+                            format!("{} {}", column, if *asc { "ASC" } else { "DESC" })
+                        })
+                    })
+                    .collect(),
+            ),
+            ("projection", self.projection.clone()),
+            (
+                "computed",
+                self.computed
+                    .iter()
+                    .map(|c| format!("{} = {}", c.name, c.expr))
+                    .collect(),
+            ),
+            (
+                "derived",
+                self.derived
+                    .iter()
+                    .map(|d| format!("{} = {}", d.alias, d.plan.stable_hash()))
+                    .collect(),
+            ),
+            (
+                "union_all",
+                self.union_all.iter().map(|p| p.stable_hash()).collect(),
+            ),
+        ]
+    }
+}
+
+/// Derived table (subquery in FROM)
+///
+/// The subquery is planned on its own and its output is used as an
+/// intermediate table named by `alias` in the outer plan.
+#[derive(Debug, Clone)]
+pub struct DerivedTable {
+    /// Name of the intermediate table in the outer plan
+    pub alias: String,
+
+    /// Execution plan of the subquery
+    pub plan: ExecutionPlan,
 }
 
 /// Filter operation
@@ -56,6 +358,226 @@ pub struct FilterOperation {
     pub condition: FilterCondition,
 }
 
+impl fmt::Display for FilterOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let condition = match &self.condition {
+            FilterCondition::GreaterThan(v) => format!(">{}", v),
+            FilterCondition::LessThan(v) => format!("<{}", v),
+            FilterCondition::GreaterOrEqual(v) => format!(">={}", v),
+            FilterCondition::LessOrEqual(v) => format!("<={}", v),
+            FilterCondition::Equal(v) => format!("={}", v),
+            FilterCondition::Between(low, high) => format!("between {},{}", low, high),
+            FilterCondition::In(values) => format!("in {}", values.join(",")),
+            FilterCondition::Like(pattern) => format!("like {}", pattern),
+            FilterCondition::ColumnCompare { other_column, op } => {
+                format!("{} column {}", op, other_column)
+            }
+        };
+        write!(f, "{} {}", self.column, condition)
+    }
+}
+
+impl FilterOperation {
+    /// Render the comparison as SQL, used by `ExecutionPlan::to_sql`
+    fn to_sql(&self) -> String {
+        match &self.condition {
+            FilterCondition::GreaterThan(v) => format!("{} > {}", self.column, sql_literal(v)),
+            FilterCondition::LessThan(v) => format!("{} < {}", self.column, sql_literal(v)),
+            FilterCondition::GreaterOrEqual(v) => {
+                format!("{} >= {}", self.column, sql_literal(v))
+            }
+            FilterCondition::LessOrEqual(v) => format!("{} <= {}", self.column, sql_literal(v)),
+            FilterCondition::Equal(v) => format!("{} = {}", self.column, sql_literal(v)),
+            FilterCondition::Between(low, high) => format!(
+                "{} BETWEEN {} AND {}",
+                self.column,
+                sql_literal(low),
+                sql_literal(high)
+            ),
+            FilterCondition::In(values) => format!(
+                "{} IN ({})",
+                self.column,
+                values
+                    .iter()
+                    .map(|v| sql_literal(v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            FilterCondition::Like(pattern) => {
+                format!("{} LIKE '{}'", self.column, pattern.replace('\'', "''"))
+            }
+            FilterCondition::ColumnCompare { other_column, op } => {
+                format!("{} {} {}", self.column, op, other_column)
+            }
+        }
+    }
+}
+
+/// Render a filter value as a SQL literal
+///
+/// Numbers are left bare; other values are single-quoted.
+fn sql_literal(value: &str) -> String {
+    let numeric = value
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'));
+    if numeric && value.parse::<f64>().is_ok() {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// Comparison between two literals, e.g. `0 = 1`
+#[derive(Debug, Clone)]
+pub struct ConstantFilter {
+    /// Left-hand literal
+    pub left: String,
+
+    /// Comparison operator
+    pub op: CompareOp,
+
+    /// Right-hand literal
+    pub right: String,
+}
+
+impl ConstantFilter {
+    /// Evaluate the comparison
+    ///
+    /// Literals are compared as numbers when both parse as numbers, and as
+    /// strings otherwise.
+    ///
+    /// # Returns
+    /// `true` if the comparison holds
+    pub fn evaluate(&self) -> bool {
+        let ordering = match (self.left.parse::<i128>(), self.right.parse::<i128>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => match (self.left.parse::<f64>(), self.right.parse::<f64>()) {
+                (Ok(a), Ok(b)) => a
+                    .partial_cmp(&b)
+                    .unwrap_or_else(|| self.left.cmp(&self.right)),
+                _ => self.left.cmp(&self.right),
+            },
+        };
+        self.op.holds(ordering)
+    }
+}
+
+impl fmt::Display for ConstantFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.left, self.op, self.right)
+    }
+}
+
+/// Boolean predicate tree of a WHERE clause
+///
+/// Leaves are single comparisons; inner nodes combine their children with
+/// AND or OR. Parentheses in the SQL only shape the tree.
+#[derive(Debug, Clone)]
+pub enum FilterTree {
+    /// Single comparison
+    Leaf(FilterOperation),
+
+    /// Every child holds
+    And(Vec<FilterTree>),
+
+    /// At least one child holds
+    Or(Vec<FilterTree>),
+}
+
+impl FilterTree {
+    /// Get the leaves of the tree, left to right
+    pub fn leaves(&self) -> Vec<&FilterOperation> {
+        match self {
+            FilterTree::Leaf(filter) => vec![filter],
+            FilterTree::And(children) | FilterTree::Or(children) => {
+                children.iter().flat_map(FilterTree::leaves).collect()
+            }
+        }
+    }
+
+    /// Get mutable references to the leaves of the tree, left to right
+    pub fn leaves_mut(&mut self) -> Vec<&mut FilterOperation> {
+        match self {
+            FilterTree::Leaf(filter) => vec![filter],
+            FilterTree::And(children) | FilterTree::Or(children) => children
+                .iter_mut()
+                .flat_map(FilterTree::leaves_mut)
+                .collect(),
+        }
+    }
+
+    /// Evaluate the tree
+    ///
+    /// # Arguments
+    /// * `leaf` - Evaluates a single comparison
+    ///
+    /// # Returns
+    /// `true` if the boolean combination of the leaves holds
+    pub fn evaluate(&self, leaf: &mut impl FnMut(&FilterOperation) -> bool) -> bool {
+        match self {
+            FilterTree::Leaf(filter) => leaf(filter),
+            FilterTree::And(children) => children.iter().all(|child| child.evaluate(leaf)),
+            FilterTree::Or(children) => children.iter().any(|child| child.evaluate(leaf)),
+        }
+    }
+
+    /// Split the top-level conjunction into leaves and the remaining tree
+    ///
+    /// # Returns
+    /// `(leaves, rest)` where the tree holds iff every leaf and `rest` hold
+    pub fn split_conjuncts(self) -> (Vec<FilterOperation>, Option<FilterTree>) {
+        match self {
+            FilterTree::Leaf(filter) => (vec![filter], None),
+            FilterTree::And(children) => {
+                let mut leaves = vec![];
+                let mut rest = vec![];
+                for child in children {
+                    match child {
+                        FilterTree::Leaf(filter) => leaves.push(filter),
+                        other => rest.push(other),
+                    }
+                }
+                let rest = match rest.len() {
+                    0 => None,
+                    1 => rest.pop(),
+                    _ => Some(FilterTree::And(rest)),
+                };
+                (leaves, rest)
+            }
+            or => (vec![], Some(or)),
+        }
+    }
+
+    /// Canonical encoding with children sorted, used by `stable_hash`
+    fn canonical(&self) -> String {
+        let (name, children) = match self {
+            FilterTree::Leaf(filter) => return filter.to_string(),
+            FilterTree::And(children) => ("and", children),
+            FilterTree::Or(children) => ("or", children),
+        };
+        let mut children: Vec<String> = children.iter().map(FilterTree::canonical).collect();
+        children.sort();
+        format!("{}({})", name, children.join(";"))
+    }
+
+    /// Render the tree as SQL, parenthesizing nested AND/OR nodes
+    fn to_sql(&self) -> String {
+        let (separator, children) = match self {
+            FilterTree::Leaf(filter) => return filter.to_sql(),
+            FilterTree::And(children) => (" AND ", children),
+            FilterTree::Or(children) => (" OR ", children),
+        };
+        children
+            .iter()
+            .map(|child| match child {
+                FilterTree::Leaf(filter) => filter.to_sql(),
+                nested => format!("({})", nested.to_sql()),
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
 /// Filter condition type
 #[derive(Debug, Clone)]
 pub enum FilterCondition {
@@ -65,6 +587,12 @@ pub enum FilterCondition {
     /// Less than
     LessThan(String),
 
+    /// Greater than or equal to
+    GreaterOrEqual(String),
+
+    /// Less than or equal to
+    LessOrEqual(String),
+
     /// Equal to
     Equal(String),
 
@@ -73,6 +601,65 @@ pub enum FilterCondition {
 
     /// In
     In(Vec<String>),
+
+    /// Matches a LIKE pattern (`%` matches any sequence, `_` any character)
+    ///
+    /// Strings are committed as hashes, so only patterns without wildcards
+    /// (matching one string) are proven; other patterns are evaluated, and
+    /// proving them is unsupported.
+    Like(String),
+
+    /// Comparison against another column of the same row
+    ColumnCompare {
+        /// Column on the right-hand side
+        other_column: String,
+
+        /// Comparison operator
+        op: CompareOp,
+    },
+}
+
+/// Comparison operator between two columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// Less than
+    LessThan,
+
+    /// Greater than
+    GreaterThan,
+
+    /// Equal to
+    Equal,
+}
+
+impl CompareOp {
+    /// All comparison operators, in declaration order
+    pub const ALL: [CompareOp; 3] = [
+        CompareOp::LessThan,
+        CompareOp::GreaterThan,
+        CompareOp::Equal,
+    ];
+
+    /// Check whether the ordering of the left value relative to the right
+    /// value satisfies the operator
+    pub fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        match self {
+            CompareOp::LessThan => ordering.is_lt(),
+            CompareOp::GreaterThan => ordering.is_gt(),
+            CompareOp::Equal => ordering.is_eq(),
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            CompareOp::LessThan => "<",
+            CompareOp::GreaterThan => ">",
+            CompareOp::Equal => "=",
+        };
+        write!(f, "{}", symbol)
+    }
 }
 
 /// Join operation
@@ -98,6 +685,33 @@ pub struct GroupByOperation {
     pub columns: Vec<String>,
 }
 
+/// HAVING predicate on the groups of a query
+///
+/// Groups failing the predicate are dropped from the result, and the
+/// circuit proves that every remaining group satisfies it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HavingCondition {
+    /// `COUNT(*) > k`
+    CountGreaterThan(u64),
+}
+
+impl HavingCondition {
+    /// Check whether a group with `count` rows satisfies the predicate
+    pub fn holds(&self, count: u64) -> bool {
+        match self {
+            HavingCondition::CountGreaterThan(k) => count > *k,
+        }
+    }
+}
+
+impl fmt::Display for HavingCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HavingCondition::CountGreaterThan(k) => write!(f, "COUNT(*) > {}", k),
+        }
+    }
+}
+
 /// Aggregation operation
 #[derive(Debug, Clone)]
 pub struct AggregationOperation {
@@ -129,6 +743,40 @@ pub enum AggregationFunction {
 
     /// Maximum
     Max,
+
+    /// Approximate number of distinct values (HyperLogLog)
+    ApproxCountDistinct,
+
+    /// Exact number of distinct values (`COUNT(DISTINCT column)`)
+    CountDistinct,
+}
+
+impl AggregationFunction {
+    /// All aggregation functions, in declaration order
+    pub const ALL: [AggregationFunction; 7] = [
+        AggregationFunction::Sum,
+        AggregationFunction::Count,
+        AggregationFunction::Avg,
+        AggregationFunction::Min,
+        AggregationFunction::Max,
+        AggregationFunction::ApproxCountDistinct,
+        AggregationFunction::CountDistinct,
+    ];
+}
+
+impl fmt::Display for AggregationFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AggregationFunction::Sum => "SUM",
+            AggregationFunction::Count => "COUNT",
+            AggregationFunction::Avg => "AVG",
+            AggregationFunction::Min => "MIN",
+            AggregationFunction::Max => "MAX",
+            AggregationFunction::ApproxCountDistinct => "APPROX_COUNT_DISTINCT",
+            AggregationFunction::CountDistinct => "COUNT_DISTINCT",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 /// Sort operation
@@ -141,6 +789,151 @@ pub struct SortOperation {
     pub ascending: Vec<bool>,
 }
 
+/// Arithmetic operator in a projection expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticOp {
+    /// Addition
+    Add,
+
+    /// Subtraction
+    Sub,
+
+    /// Multiplication
+    Mul,
+
+    /// Integer division
+    Div,
+
+    /// Integer remainder
+    Mod,
+}
+
+impl ArithmeticOp {
+    /// All arithmetic operators, in declaration order
+    pub const ALL: [ArithmeticOp; 5] = [
+        ArithmeticOp::Add,
+        ArithmeticOp::Sub,
+        ArithmeticOp::Mul,
+        ArithmeticOp::Div,
+        ArithmeticOp::Mod,
+    ];
+}
+
+impl fmt::Display for ArithmeticOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            ArithmeticOp::Add => "+",
+            ArithmeticOp::Sub => "-",
+            ArithmeticOp::Mul => "*",
+            ArithmeticOp::Div => "/",
+            ArithmeticOp::Mod => "%",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Projection expression
+///
+/// Captures column references, integer constants, and binary arithmetic
+/// over them, e.g. `l_extendedprice * (1 - l_discount)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectionExpr {
+    /// Column reference
+    Column(String),
+
+    /// Integer constant
+    Literal(i64),
+
+    /// Binary arithmetic operation
+    BinaryOp {
+        /// Left operand
+        left: Box<ProjectionExpr>,
+
+        /// Operator
+        op: ArithmeticOp,
+
+        /// Right operand
+        right: Box<ProjectionExpr>,
+    },
+}
+
+impl ProjectionExpr {
+    /// Get all column names referenced by this expression
+    pub fn columns(&self) -> Vec<String> {
+        match self {
+            ProjectionExpr::Column(name) => vec![name.clone()],
+            ProjectionExpr::Literal(_) => vec![],
+            ProjectionExpr::BinaryOp { left, right, .. } => {
+                let mut columns = left.columns();
+                columns.extend(right.columns());
+                columns
+            }
+        }
+    }
+}
+
+impl fmt::Display for ProjectionExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectionExpr::Column(name) => write!(f, "{}", name),
+            ProjectionExpr::Literal(value) => write!(f, "{}", value),
+            ProjectionExpr::BinaryOp { left, op, right } => {
+                write!(f, "({} {} {})", left, op, right)
+            }
+        }
+    }
+}
+
+/// Computed projection column
+#[derive(Debug, Clone)]
+pub struct ComputedColumn {
+    /// Output column name (alias, or the rendered expression)
+    pub name: String,
+
+    /// Expression computing the column value
+    pub expr: ProjectionExpr,
+}
+
+/// Report of the SQL features used by a query
+///
+/// Produced by `QueryPlanner::supported`. Clauses are named as the plan
+/// sections they map to (e.g. `filters`, `joins`, `group_by`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SupportReport {
+    /// Clauses present in the query that the planner supports
+    pub recognized: Vec<String>,
+
+    /// Features present in the query that the planner does not support
+    pub unsupported: Vec<String>,
+}
+
+impl SupportReport {
+    /// Check whether every feature used by the query is supported
+    pub fn is_fully_supported(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    /// Record a supported clause (once)
+    fn recognize(&mut self, clause: &str) {
+        if !self.recognized.iter().any(|c| c == clause) {
+            self.recognized.push(clause.to_string());
+        }
+    }
+
+    /// Record an unsupported feature (once)
+    fn reject(&mut self, feature: &str) {
+        if !self.unsupported.iter().any(|f| f == feature) {
+            self.unsupported.push(feature.to_string());
+        }
+    }
+
+    /// Merge the report of a nested query
+    fn extend(&mut self, other: SupportReport) {
+        other.recognized.iter().for_each(|c| self.recognize(c));
+        other.unsupported.iter().for_each(|f| self.reject(f));
+    }
+}
+
 /// Query planner
 ///
 /// This struct provides methods for planning SQL query execution.
@@ -165,10 +958,254 @@ impl QueryPlanner {
     ///
     /// # Returns
     /// `Ok(ExecutionPlan)` if planning succeeds, `Err` otherwise
-    pub fn plan(&self, statement: &Statement) -> Result<ExecutionPlan, Box<dyn std::error::Error>> {
+    pub fn plan(&self, statement: &Statement) -> Result<ExecutionPlan, QueryError> {
         match statement {
             Statement::Query(query) => self.plan_query(query),
-            _ => Err("Only SELECT queries are supported".into()),
+            _ => Err(QueryError::UnsupportedFeature(
+                "Only SELECT queries are supported".to_string(),
+            )),
+        }
+    }
+
+    /// Report which features of a SQL statement are supported
+    ///
+    /// Unlike `plan`, this never fails: every clause present in the
+    /// statement is listed as either recognized or unsupported, so callers
+    /// can decide whether to run the query before planning it.
+    ///
+    /// # Arguments
+    /// * `statement` - SQL statement AST
+    ///
+    /// # Returns
+    /// `SupportReport` listing recognized and unsupported features
+    pub fn supported(&self, statement: &Statement) -> SupportReport {
+        match statement {
+            Statement::Query(query) => self.query_support(query),
+            _ => SupportReport {
+                recognized: vec![],
+                unsupported: vec!["non-SELECT statements".to_string()],
+            },
+        }
+    }
+
+    /// Report which features of a query are supported
+    fn query_support(&self, query: &Query) -> SupportReport {
+        let mut report = SupportReport::default();
+
+        let select = match &*query.body {
+            SetExpr::Select(select) => select,
+            SetExpr::SetOperation { .. } => {
+                let mut branches = vec![];
+                if Self::union_all_branches(&query.body, &mut branches).is_err() {
+                    report.reject("set operations");
+                    return report;
+                }
+                report.recognize("union_all");
+                if query.order_by.is_some() || query.limit_clause.is_some() {
+                    report.reject("ORDER BY or LIMIT on UNION ALL");
+                }
+                for body in branches {
+                    report.extend(self.query_support(&Self::branch_query(query, body)));
+                }
+                return report;
+            }
+            _ => {
+                report.reject("set operations");
+                return report;
+            }
+        };
+
+        for (_, subquery, lateral) in self.parser.extract_derived_tables(query) {
+            if lateral {
+                report.reject("correlated subqueries");
+            } else {
+                report.recognize("derived");
+                report.extend(self.query_support(&subquery));
+            }
+        }
+
+        if let Some(where_expr) = &select.selection {
+            report.recognize("filters");
+            Self::expr_support(where_expr, &mut report);
+        }
+
+        let aliases: HashMap<String, String> = self
+            .parser
+            .extract_table_aliases(query)
+            .into_iter()
+            .collect();
+        for join in self.parser.extract_joins(query) {
+            match self.extract_join(&join, &aliases) {
+                Ok(_) => report.recognize("joins"),
+                Err(_) => report.reject("non-equi or outer joins"),
+            }
+        }
+
+        if !self.parser.extract_group_by(query).is_empty() {
+            report.recognize("group_by");
+        }
+
+        for item in &select.projection {
+            let expr = match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => expr,
+                _ => continue,
+            };
+            let windowed = matches!(expr, Expr::Function(func) if func.over.is_some());
+            if !windowed && self.extract_aggregation(item).is_some() {
+                report.recognize("aggregations");
+            } else if !windowed && self.extract_computed_column(item).is_some() {
+                report.recognize("computed");
+            }
+            Self::expr_support(expr, &mut report);
+        }
+
+        if let Some(having) = &select.having {
+            match self.extract_having(having) {
+                Ok(_) => report.recognize("having"),
+                Err(_) => report.reject("HAVING other than COUNT(*) > k"),
+            }
+        }
+        if !self.parser.extract_order_by(query).is_empty() {
+            report.recognize("sort");
+        }
+        if query.limit_clause.is_some() {
+            report.reject("LIMIT");
+        }
+
+        report
+    }
+
+    /// Record unsupported features (subqueries, window functions) used
+    /// inside an expression
+    fn expr_support(expr: &Expr, report: &mut SupportReport) {
+        match expr {
+            Expr::Subquery(_) | Expr::InSubquery { .. } | Expr::Exists { .. } => {
+                report.reject("subqueries");
+            }
+            Expr::Function(func) => {
+                if func.over.is_some() {
+                    report.reject("window functions");
+                }
+                if let sqlparser::ast::FunctionArguments::List(list) = &func.args {
+                    for arg in &list.args {
+                        if let sqlparser::ast::FunctionArg::Unnamed(
+                            sqlparser::ast::FunctionArgExpr::Expr(e),
+                        ) = arg
+                        {
+                            Self::expr_support(e, report);
+                        }
+                    }
+                } else if let sqlparser::ast::FunctionArguments::Subquery(_) = &func.args {
+                    report.reject("subqueries");
+                }
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                Self::expr_support(left, report);
+                Self::expr_support(right, report);
+            }
+            Expr::UnaryOp { expr, .. } | Expr::Nested(expr) => Self::expr_support(expr, report),
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                Self::expr_support(expr, report);
+                Self::expr_support(low, report);
+                Self::expr_support(high, report);
+            }
+            Expr::InList { expr, list, .. } => {
+                Self::expr_support(expr, report);
+                list.iter().for_each(|e| Self::expr_support(e, report));
+            }
+            Expr::Like {
+                negated,
+                expr,
+                pattern,
+                escape_char,
+                ..
+            } => {
+                if *negated {
+                    report.reject("NOT LIKE");
+                }
+                if escape_char.is_some() {
+                    report.reject("LIKE ... ESCAPE");
+                }
+                Self::expr_support(expr, report);
+                Self::expr_support(pattern, report);
+            }
+            _ => {}
+        }
+    }
+
+    /// Plan a UNION ALL of SELECT queries
+    ///
+    /// Each branch is planned on its own. The left-most branch becomes the
+    /// root plan and the other branches are listed, in order, in its
+    /// `union_all`; nested unions are flattened.
+    ///
+    /// # Arguments
+    /// * `query` - SQL query AST whose body is a set operation
+    ///
+    /// # Returns
+    /// `Ok(ExecutionPlan)` if planning succeeds, `Err` for set operations
+    /// other than UNION ALL or ORDER BY/LIMIT applied to the union
+    fn plan_union_all(&self, query: &Query) -> Result<ExecutionPlan, QueryError> {
+        if query.order_by.is_some() || query.limit_clause.is_some() {
+            return Err(QueryError::UnsupportedFeature(
+                "ORDER BY or LIMIT on UNION ALL".to_string(),
+            ));
+        }
+
+        let mut branches = vec![];
+        Self::union_all_branches(&query.body, &mut branches)?;
+        let mut plans = branches
+            .into_iter()
+            .map(|body| self.plan_query(&Self::branch_query(query, body)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut plan = plans.remove(0);
+        plan.union_all = plans;
+        Ok(plan)
+    }
+
+    /// Collect the SELECT branches of a UNION ALL, left to right
+    fn union_all_branches<'a>(
+        body: &'a SetExpr,
+        branches: &mut Vec<&'a SetExpr>,
+    ) -> Result<(), QueryError> {
+        match body {
+            SetExpr::SetOperation {
+                op: SetOperator::Union,
+                set_quantifier: SetQuantifier::All,
+                left,
+                right,
+            } => {
+                Self::union_all_branches(left, branches)?;
+                Self::union_all_branches(right, branches)
+            }
+            SetExpr::SetOperation { .. } => Err(QueryError::UnsupportedFeature(
+                "Set operations other than UNION ALL".to_string(),
+            )),
+            SetExpr::Query(inner) => {
+                if inner.order_by.is_some() || inner.limit_clause.is_some() {
+                    return Err(QueryError::UnsupportedFeature(
+                        "ORDER BY or LIMIT in a UNION ALL branch".to_string(),
+                    ));
+                }
+                Self::union_all_branches(&inner.body, branches)
+            }
+            _ => {
+                branches.push(body);
+                Ok(())
+            }
+        }
+    }
+
+    /// Build the query for one branch of a UNION ALL
+    fn branch_query(query: &Query, body: &SetExpr) -> Query {
+        Query {
+            body: Box::new(body.clone()),
+            order_by: None,
+            limit_clause: None,
+            ..query.clone()
         }
     }
 
@@ -179,25 +1216,61 @@ impl QueryPlanner {
     ///
     /// # Returns
     /// `Ok(ExecutionPlan)` if planning succeeds, `Err` otherwise
-    fn plan_query(&self, query: &Query) -> Result<ExecutionPlan, Box<dyn std::error::Error>> {
+    fn plan_query(&self, query: &Query) -> Result<ExecutionPlan, QueryError> {
+        if let SetExpr::SetOperation { .. } = &*query.body {
+            return self.plan_union_all(query);
+        }
+
         let mut plan = ExecutionPlan {
             tables: self.parser.extract_tables(query),
             filters: vec![],
+            filter_tree: None,
+            constant_filters: vec![],
+            empty_result: false,
             joins: vec![],
             group_by: vec![],
+            having: None,
             aggregations: vec![],
             sort: vec![],
             projection: vec![],
+            computed: vec![],
+            required_columns: None,
+            derived: vec![],
+            union_all: vec![],
         };
 
+        // Plan derived tables (non-correlated subqueries in FROM)
+        for (alias, subquery, lateral) in self.parser.extract_derived_tables(query) {
+            if lateral {
+                return Err(QueryError::UnsupportedFeature(
+                    "Correlated (LATERAL) subqueries".to_string(),
+                ));
+            }
+            plan.derived.push(DerivedTable {
+                alias,
+                plan: self.plan_query(&subquery)?,
+            });
+        }
+
         // Extract WHERE clause (filters)
         if let Some(where_expr) = self.parser.extract_where(query) {
-            plan.filters.extend(self.extract_filters(&where_expr)?);
+            if let Some(tree) = self.extract_filters(&where_expr)? {
+                let (filters, rest) = tree.split_conjuncts();
+                plan.filters = filters;
+                plan.filter_tree = rest;
+            }
+            self.extract_constant_filters(&where_expr, &mut plan.constant_filters);
         }
 
-        // Extract JOINs (from FROM clause)
-        // Note: This is a simplified version - in production, you'd parse JOIN syntax properly
-        // For now, we assume joins are specified in WHERE clause (e.g., table1.col = table2.col)
+        // Extract JOINs (FROM ... JOIN ... ON a.col = b.col)
+        let aliases: HashMap<String, String> = self
+            .parser
+            .extract_table_aliases(query)
+            .into_iter()
+            .collect();
+        for join in self.parser.extract_joins(query) {
+            plan.joins.push(self.extract_join(&join, &aliases)?);
+        }
 
         // Extract GROUP BY clause
         let group_by_exprs = self.parser.extract_group_by(query);
@@ -207,55 +1280,238 @@ impl QueryPlanner {
             });
         }
 
+        // Extract HAVING clause
+        if let Some(having_expr) = self.parser.extract_having(query) {
+            plan.having = Some(self.extract_having(&having_expr)?);
+        }
+
         // Extract aggregations from SELECT clause
         let select_items = self.parser.extract_select_items(query);
         for item in &select_items {
             if let Some(agg) = self.extract_aggregation(item) {
                 plan.aggregations.push(agg);
             }
+            if let Some(computed) = self.extract_computed_column(item) {
+                plan.projection.push(computed.name.clone());
+                plan.computed.push(computed);
+                continue;
+            }
             if let Some(col) = self.extract_column_name(item) {
                 plan.projection.push(col);
             }
         }
 
-        // Extract ORDER BY clause
-        let order_by_exprs = self.parser.extract_order_by(query);
-        if !order_by_exprs.is_empty() {
-            plan.sort.push(SortOperation {
-                columns: order_by_exprs.iter().map(|e| e.expr.to_string()).collect(),
-                ascending: order_by_exprs
-                    .iter()
-                    .map(|e| {
-                        // OrderByExpr in sqlparser 0.59 has options field (OrderByOptions struct)
-                        // options is NOT Option<OrderByOptions>, it's directly OrderByOptions
-                        // Check if options.asc is Some(false) for descending
-                        // Default to ascending if not specified
-                        !matches!(e.options.asc, Some(false))
-                    })
-                    .collect(),
-            });
+        // Extract ORDER BY clause
+        let order_by_exprs = self.parser.extract_order_by(query);
+        if !order_by_exprs.is_empty() {
+            plan.sort.push(SortOperation {
+                columns: order_by_exprs.iter().map(|e| e.expr.to_string()).collect(),
+                ascending: order_by_exprs
+                    .iter()
+                    .map(|e| {
+                        // OrderByExpr in sqlparser 0.59 has options field (OrderByOptions struct)
+                        // options is NOT Option<OrderByOptions>, it's directly OrderByOptions
+                        // Check if options.asc is Some(false) for descending
+                        // Default to ascending if not specified
+                        !matches!(e.options.asc, Some(false))
+                    })
+                    .collect(),
+            });
+        }
+
+        if !aliases.is_empty() {
+            Self::resolve_aliases(&mut plan, &aliases);
+        }
+
+        Ok(plan)
+    }
+
+    /// Extract an equi-join from a JOIN clause
+    ///
+    /// The join column of the joined relation is always the right side,
+    /// regardless of the order in the ON condition.
+    ///
+    /// # Arguments
+    /// * `join` - JOIN clause
+    /// * `aliases` - Table aliases (alias to table name)
+    ///
+    /// # Returns
+    /// `Ok(JoinOperation)` for `[INNER] JOIN ... ON a.col = b.col`, `Err` otherwise
+    fn extract_join(
+        &self,
+        join: &Join,
+        aliases: &HashMap<String, String>,
+    ) -> Result<JoinOperation, QueryError> {
+        let constraint = match &join.join_operator {
+            JoinOperator::Join(constraint) | JoinOperator::Inner(constraint) => constraint,
+            _ => {
+                return Err(QueryError::UnsupportedFeature(
+                    "Only inner joins are supported".to_string(),
+                ))
+            }
+        };
+
+        let (left, right) = match constraint {
+            JoinConstraint::On(Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Eq,
+                right,
+            }) => (left, right),
+            _ => {
+                return Err(QueryError::UnsupportedFeature(
+                    "Join conditions other than ON a.col = b.col".to_string(),
+                ))
+            }
+        };
+
+        let qualified = |expr: &Expr| match expr {
+            Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                let table = &idents[0].value;
+                Ok((
+                    aliases.get(table).cloned().unwrap_or_else(|| table.clone()),
+                    idents[1].value.clone(),
+                ))
+            }
+            other => Err(QueryError::UnsupportedFeature(format!(
+                "Unqualified join column {}",
+                other
+            ))),
+        };
+        let (mut left_table, mut left_column) = qualified(left)?;
+        let (mut right_table, mut right_column) = qualified(right)?;
+
+        let joined_table = QueryParser::relation_name(&join.relation);
+        if left_table == joined_table {
+            std::mem::swap(&mut left_table, &mut right_table);
+            std::mem::swap(&mut left_column, &mut right_column);
+        }
+
+        Ok(JoinOperation {
+            left_table,
+            right_table,
+            left_column,
+            right_column,
+        })
+    }
+
+    /// Replace table aliases in column references with table names
+    ///
+    /// `o.o_custkey` becomes `orders.o_custkey` for `FROM orders o`. Output
+    /// names of computed projections keep the alias as written.
+    fn resolve_aliases(plan: &mut ExecutionPlan, aliases: &HashMap<String, String>) {
+        let resolve = |name: &mut String| {
+            if let Some((qualifier, column)) = name.split_once('.') {
+                if let Some(table) = aliases.get(qualifier) {
+                    *name = format!("{}.{}", table, column);
+                }
+            }
+        };
+
+        fn resolve_expr(expr: &mut ProjectionExpr, resolve: &dyn Fn(&mut String)) {
+            match expr {
+                ProjectionExpr::Column(name) => resolve(name),
+                ProjectionExpr::Literal(_) => {}
+                ProjectionExpr::BinaryOp { left, right, .. } => {
+                    resolve_expr(left, resolve);
+                    resolve_expr(right, resolve);
+                }
+            }
+        }
+
+        let tree_leaves = plan.filter_tree.iter_mut().flat_map(FilterTree::leaves_mut);
+        for filter in plan.filters.iter_mut().chain(tree_leaves) {
+            resolve(&mut filter.column);
+            if let FilterCondition::ColumnCompare { other_column, .. } = &mut filter.condition {
+                resolve(other_column);
+            }
+        }
+        plan.group_by
+            .iter_mut()
+            .flat_map(|g| g.columns.iter_mut())
+            .for_each(resolve);
+        plan.aggregations
+            .iter_mut()
+            .filter_map(|a| a.column.as_mut())
+            .for_each(resolve);
+        plan.sort
+            .iter_mut()
+            .flat_map(|s| s.columns.iter_mut())
+            .for_each(resolve);
+        // Computed projections are labelled by their expression text, not resolved
+        let computed_names: Vec<String> = plan.computed.iter().map(|c| c.name.clone()).collect();
+        plan.projection
+            .iter_mut()
+            .filter(|name| !computed_names.contains(name))
+            .for_each(resolve);
+        for computed in &mut plan.computed {
+            resolve_expr(&mut computed.expr, &resolve);
         }
-
-        Ok(plan)
     }
 
-    /// Extract filters from a WHERE expression
-    fn extract_filters(
-        &self,
-        expr: &Expr,
-    ) -> Result<Vec<FilterOperation>, Box<dyn std::error::Error>> {
-        let mut filters = vec![];
+    /// Extract the filter tree of a WHERE expression
+    ///
+    /// AND/OR chains are flattened into one node per operator and
+    /// parentheses are followed. Unsupported predicates are not filtered on:
+    /// they are dropped from an AND, and make an enclosing OR always true.
+    ///
+    /// # Returns
+    /// `Ok(Some(FilterTree))` for the supported predicates, `Ok(None)` if none
+    /// restrict the rows
+    fn extract_filters(&self, expr: &Expr) -> Result<Option<FilterTree>, QueryError> {
+        match expr {
+            Expr::Nested(inner) => return self.extract_filters(inner),
+            Expr::BinaryOp {
+                left,
+                op: op @ (BinaryOperator::And | BinaryOperator::Or),
+                right,
+            } => {
+                let is_and = *op == BinaryOperator::And;
+                let mut children = vec![];
+                for side in [left, right] {
+                    match self.extract_filters(side)? {
+                        // Merge nested chains of the same operator
+                        Some(FilterTree::And(nested)) if is_and => children.extend(nested),
+                        Some(FilterTree::Or(nested)) if !is_and => children.extend(nested),
+                        Some(child) => children.push(child),
+                        None if is_and => {}
+                        None => return Ok(None),
+                    }
+                }
+                return Ok(match children.len() {
+                    0 => None,
+                    1 => children.pop(),
+                    _ if is_and => Some(FilterTree::And(children)),
+                    _ => Some(FilterTree::Or(children)),
+                });
+            }
+            _ => {}
+        }
 
+        let mut filters = vec![];
         match expr {
             Expr::BinaryOp { left, op, right } => {
                 // Handle comparison operators
                 if let Some(column) = self.extract_column_from_expr(left) {
-                    if let Some(value) = self.extract_value_from_expr(right) {
-                        let condition = match op.to_string().as_str() {
-                            ">" => FilterCondition::GreaterThan(value),
-                            "<" => FilterCondition::LessThan(value),
-                            "=" | "==" => FilterCondition::Equal(value),
-                            _ => return Ok(vec![]), // Unsupported operator
+                    // Column-to-column comparison
+                    if let Some(other_column) = self.extract_column_from_expr(right) {
+                        let op = match op {
+                            BinaryOperator::Lt => CompareOp::LessThan,
+                            BinaryOperator::Gt => CompareOp::GreaterThan,
+                            BinaryOperator::Eq => CompareOp::Equal,
+                            _ => return Ok(None), // Unsupported operator
+                        };
+                        filters.push(FilterOperation {
+                            column,
+                            condition: FilterCondition::ColumnCompare { other_column, op },
+                        });
+                    } else if let Some(value) = self.extract_value_from_expr(right)? {
+                        let condition = match op {
+                            BinaryOperator::Gt => FilterCondition::GreaterThan(value),
+                            BinaryOperator::Lt => FilterCondition::LessThan(value),
+                            BinaryOperator::GtEq => FilterCondition::GreaterOrEqual(value),
+                            BinaryOperator::LtEq => FilterCondition::LessOrEqual(value),
+                            BinaryOperator::Eq => FilterCondition::Equal(value),
+                            _ => return Ok(None), // Unsupported operator
                         };
                         filters.push(FilterOperation { column, condition });
                     }
@@ -269,8 +1525,8 @@ impl QueryPlanner {
             } => {
                 if let Some(column) = self.extract_column_from_expr(expr) {
                     if let (Some(low_val), Some(high_val)) = (
-                        self.extract_value_from_expr(low),
-                        self.extract_value_from_expr(high),
+                        self.extract_value_from_expr(low)?,
+                        self.extract_value_from_expr(high)?,
                     ) {
                         filters.push(FilterOperation {
                             column,
@@ -287,7 +1543,10 @@ impl QueryPlanner {
                 if let Some(column) = self.extract_column_from_expr(expr) {
                     let values: Vec<String> = list
                         .iter()
-                        .filter_map(|e| self.extract_value_from_expr(e))
+                        .map(|e| self.extract_value_from_expr(e))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .flatten()
                         .collect();
                     if !values.is_empty() {
                         filters.push(FilterOperation {
@@ -297,12 +1556,119 @@ impl QueryPlanner {
                     }
                 }
             }
-            // Note: Expr::And/Or don't exist in sqlparser 0.59, use BinaryOp instead
-            // This case is already handled in BinaryOp above
+            Expr::Like {
+                negated: false,
+                expr,
+                pattern,
+                escape_char: None,
+                ..
+            } => {
+                if let (Some(column), Some(pattern)) = (
+                    self.extract_column_from_expr(expr),
+                    self.extract_value_from_expr(pattern)?,
+                ) {
+                    filters.push(FilterOperation {
+                        column,
+                        condition: FilterCondition::Like(pattern),
+                    });
+                }
+            }
+            // Dropping these would select rows they reject
+            Expr::Like { negated: true, .. } => {
+                return Err(QueryError::UnsupportedFeature("NOT LIKE".to_string()));
+            }
+            Expr::Like { .. } => {
+                return Err(QueryError::UnsupportedFeature(
+                    "LIKE ... ESCAPE".to_string(),
+                ));
+            }
             _ => {} // Other expression types not supported yet
         }
 
-        Ok(filters)
+        Ok(filters.pop().map(FilterTree::Leaf))
+    }
+
+    /// Extract the literal-only comparisons among the top-level conjuncts of
+    /// a WHERE clause
+    ///
+    /// `extract_filters` drops these from an AND since they reference no
+    /// column; inside an OR they remain unsupported.
+    ///
+    /// # Arguments
+    /// * `expr` - WHERE clause expression
+    /// * `constants` - Output list of constant comparisons
+    fn extract_constant_filters(&self, expr: &Expr, constants: &mut Vec<ConstantFilter>) {
+        match expr {
+            Expr::Nested(inner) => self.extract_constant_filters(inner, constants),
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::And,
+                right,
+            } => {
+                self.extract_constant_filters(left, constants);
+                self.extract_constant_filters(right, constants);
+            }
+            Expr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    BinaryOperator::Lt => CompareOp::LessThan,
+                    BinaryOperator::Gt => CompareOp::GreaterThan,
+                    BinaryOperator::Eq => CompareOp::Equal,
+                    _ => return,
+                };
+                // Identifiers are values elsewhere; here both sides must be literals
+                if !matches!(**left, Expr::Value(_)) || !matches!(**right, Expr::Value(_)) {
+                    return;
+                }
+                if let (Ok(Some(left)), Ok(Some(right))) = (
+                    self.extract_value_from_expr(left),
+                    self.extract_value_from_expr(right),
+                ) {
+                    constants.push(ConstantFilter { left, op, right });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Extract a HAVING predicate
+    ///
+    /// # Arguments
+    /// * `expr` - HAVING expression
+    ///
+    /// # Returns
+    /// `Ok(HavingCondition)` for `COUNT(*) > k` with a non-negative integer
+    /// `k`, `Err` otherwise
+    fn extract_having(&self, expr: &Expr) -> Result<HavingCondition, QueryError> {
+        let unsupported =
+            || QueryError::UnsupportedFeature("HAVING other than COUNT(*) > k".to_string());
+
+        let (count, threshold) = match expr {
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Gt,
+                right,
+            } => (left, right),
+            _ => return Err(unsupported()),
+        };
+
+        // COUNT(*) is the only aggregation without a column
+        match self.extract_aggregation_from_expr(count, None) {
+            Some(AggregationOperation {
+                function: AggregationFunction::Count,
+                column: None,
+                ..
+            }) => {}
+            _ => return Err(unsupported()),
+        }
+
+        match &**threshold {
+            Expr::Value(v) => match &v.value {
+                sqlparser::ast::Value::Number(n, _) => n.parse().map_err(|_| unsupported()),
+                _ => Err(unsupported()),
+            },
+            _ => Err(unsupported()),
+        }
+        .map(HavingCondition::CountGreaterThan)
     }
 
     /// Extract column name from an expression
@@ -321,8 +1687,12 @@ impl QueryPlanner {
     }
 
     /// Extract value from an expression
-    fn extract_value_from_expr(&self, expr: &Expr) -> Option<String> {
-        match expr {
+    ///
+    /// # Returns
+    /// `Ok(Some(value))` for literals and identifiers, `Ok(None)` for other
+    /// expressions, `Err` for a malformed `DATE` or `TIMESTAMP` literal
+    fn extract_value_from_expr(&self, expr: &Expr) -> Result<Option<String>, QueryError> {
+        Ok(match expr {
             Expr::Value(v) => {
                 // Value is ValueWithSpan in sqlparser 0.59
                 match &v.value {
@@ -332,9 +1702,62 @@ impl QueryPlanner {
                     _ => Some(format!("{}", v)),
                 }
             }
+            Expr::TypedString {
+                data_type: data_type @ (SqlDataType::Date | SqlDataType::Timestamp(..)),
+                value,
+                ..
+            } => match &value.value {
+                sqlparser::ast::Value::SingleQuotedString(s) => {
+                    let epoch = Self::date_literal_epoch(data_type, s).ok_or_else(|| {
+                        QueryError::InvalidValue(format!("{} literal '{}'", data_type, s))
+                    })?;
+                    Some(epoch.to_string())
+                }
+                _ => None,
+            },
             Expr::Identifier(ident) => Some(ident.value.clone()),
             _ => None,
+        })
+    }
+
+    /// Convert a `DATE '...'` or `TIMESTAMP '...'` literal to Unix seconds
+    ///
+    /// Dates are ISO `YYYY-MM-DD`; timestamps may add an `HH:MM:SS` time of
+    /// day, separated by a space or `T`.
+    ///
+    /// # Returns
+    /// `Some(seconds)` since 1970-01-01, `None` if the literal is malformed
+    fn date_literal_epoch(data_type: &SqlDataType, literal: &str) -> Option<u64> {
+        let (date, time) = match data_type {
+            SqlDataType::Timestamp(..) => match literal.split_once([' ', 'T']) {
+                Some((date, time)) => (date, Some(time)),
+                None => (literal, None),
+            },
+            _ => (literal, None),
+        };
+        // Reject bare Unix seconds, which `Value::parse` would also accept
+        if !date.contains('-') {
+            return None;
         }
+        let midnight = match Value::parse(date, &DataType::Date).ok()? {
+            Value::Date(epoch) => epoch,
+            _ => return None,
+        };
+
+        let seconds = match time {
+            Some(time) => {
+                let mut parts = time.splitn(3, ':');
+                let hours: u64 = parts.next()?.parse().ok()?;
+                let minutes: u64 = parts.next()?.parse().ok()?;
+                let seconds: u64 = parts.next().unwrap_or("0").parse().ok()?;
+                if hours > 23 || minutes > 59 || seconds > 59 {
+                    return None;
+                }
+                hours * 3600 + minutes * 60 + seconds
+            }
+            None => 0,
+        };
+        midnight.checked_add(seconds)
     }
 
     /// Extract aggregation from a SELECT item
@@ -363,9 +1786,23 @@ impl QueryPlanner {
                     "AVG" | "AVERAGE" => AggregationFunction::Avg,
                     "MIN" => AggregationFunction::Min,
                     "MAX" => AggregationFunction::Max,
+                    "APPROX_COUNT_DISTINCT" => AggregationFunction::ApproxCountDistinct,
+                    "COUNT_DISTINCT" => AggregationFunction::CountDistinct,
                     _ => return None,
                 };
 
+                // COUNT(DISTINCT column) counts each value once
+                let agg_func = match &func.args {
+                    sqlparser::ast::FunctionArguments::List(list)
+                        if agg_func == AggregationFunction::Count
+                            && list.duplicate_treatment
+                                == Some(sqlparser::ast::DuplicateTreatment::Distinct) =>
+                    {
+                        AggregationFunction::CountDistinct
+                    }
+                    _ => agg_func,
+                };
+
                 // Function arguments handling for sqlparser 0.59
                 // In sqlparser 0.59, func.args is FunctionArguments enum with variants:
                 // - None: Special functions without parentheses (e.g., CURRENT_TIMESTAMP)
@@ -395,6 +1832,62 @@ impl QueryPlanner {
         }
     }
 
+    /// Extract a computed column from a SELECT item
+    ///
+    /// Only arithmetic expressions produce a computed column; plain column
+    /// references are handled as regular projections.
+    fn extract_computed_column(&self, item: &SelectItem) -> Option<ComputedColumn> {
+        let (expr, alias) = match item {
+            SelectItem::UnnamedExpr(expr) => (expr, None),
+            SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.value.clone())),
+            _ => return None,
+        };
+
+        let projection_expr = self.extract_projection_expr(expr)?;
+        if !matches!(projection_expr, ProjectionExpr::BinaryOp { .. }) {
+            return None;
+        }
+
+        Some(ComputedColumn {
+            name: alias.unwrap_or_else(|| projection_expr.to_string()),
+            expr: projection_expr,
+        })
+    }
+
+    /// Extract a projection expression from a SQL expression
+    fn extract_projection_expr(&self, expr: &Expr) -> Option<ProjectionExpr> {
+        match expr {
+            Expr::Identifier(_) | Expr::CompoundIdentifier(_) => self
+                .extract_column_from_expr(expr)
+                .map(ProjectionExpr::Column),
+            Expr::Value(v) => match &v.value {
+                sqlparser::ast::Value::Number(n, _) => {
+                    n.parse::<i64>().ok().map(ProjectionExpr::Literal)
+                }
+                _ => None,
+            },
+            Expr::Nested(inner) => self.extract_projection_expr(inner),
+            Expr::BinaryOp { left, op, right } => {
+                let op = match op {
+                    BinaryOperator::Plus => ArithmeticOp::Add,
+                    BinaryOperator::Minus => ArithmeticOp::Sub,
+                    BinaryOperator::Multiply => ArithmeticOp::Mul,
+                    BinaryOperator::Divide
+                    | BinaryOperator::MyIntegerDivide
+                    | BinaryOperator::DuckIntegerDivide => ArithmeticOp::Div,
+                    BinaryOperator::Modulo => ArithmeticOp::Mod,
+                    _ => return None,
+                };
+                Some(ProjectionExpr::BinaryOp {
+                    left: Box::new(self.extract_projection_expr(left)?),
+                    op,
+                    right: Box::new(self.extract_projection_expr(right)?),
+                })
+            }
+            _ => None,
+        }
+    }
+
     /// Extract column name from a SELECT item
     fn extract_column_name(&self, item: &SelectItem) -> Option<String> {
         match item {
@@ -431,6 +1924,260 @@ mod tests {
         assert!(plan.is_ok());
     }
 
+    #[test]
+    fn test_plan_stable_hash() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let plan_for = |sql: &str| planner.plan(&parser.parse(sql).unwrap()).unwrap();
+
+        let plan = plan_for("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10");
+        let same = plan_for("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10");
+        assert_eq!(plan.stable_hash(), same.stable_hash());
+        assert_eq!(plan.stable_hash().len(), 64);
+
+        // Filter order does not matter
+        let filter = |column: &str, value: &str| FilterOperation {
+            column: column.to_string(),
+            condition: FilterCondition::LessThan(value.to_string()),
+        };
+        let mut ab = plan.clone();
+        ab.filters.push(filter("l_discount", "5"));
+        let mut ba = plan.clone();
+        ba.filters.insert(0, filter("l_discount", "5"));
+        assert_eq!(ab.stable_hash(), ba.stable_hash());
+
+        // An extra filter changes the hash
+        assert_ne!(plan.stable_hash(), ab.stable_hash());
+    }
+
+    #[test]
+    fn test_planner_plan_join() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "SELECT COUNT(*) FROM orders o JOIN customer c ON c.c_custkey = o.o_custkey \
+                 WHERE o.o_totalprice > 100",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.tables, vec!["orders", "customer"]);
+        assert_eq!(plan.joins.len(), 1);
+        assert_eq!(plan.joins[0].left_table, "orders");
+        assert_eq!(plan.joins[0].left_column, "o_custkey");
+        assert_eq!(plan.joins[0].right_table, "customer");
+        assert_eq!(plan.joins[0].right_column, "c_custkey");
+        assert_eq!(plan.filters[0].column, "orders.o_totalprice");
+    }
+
+    #[test]
+    fn test_planner_resolves_table_alias() {
+        let parser = QueryParser::new();
+        let planner = QueryPlanner::new();
+
+        let ast = parser
+            .parse("SELECT l.l_quantity FROM lineitem l WHERE l.l_quantity > 10")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.tables, vec!["lineitem".to_string()]);
+        assert_eq!(plan.filters.len(), 1);
+        let (table, column) = plan.filters[0].column.split_once('.').unwrap();
+        assert_eq!((table, column), ("lineitem", "l_quantity"));
+        assert!(matches!(
+            &plan.filters[0].condition,
+            FilterCondition::GreaterThan(v) if v == "10"
+        ));
+        assert_eq!(plan.projection, vec!["lineitem.l_quantity".to_string()]);
+
+        // Computed projections keep their written name
+        let ast = parser
+            .parse("SELECT l.l_quantity * 2 FROM lineitem l")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.projection, vec![plan.computed[0].name.clone()]);
+        assert_eq!(
+            plan.computed[0].expr,
+            ProjectionExpr::BinaryOp {
+                left: Box::new(ProjectionExpr::Column("lineitem.l_quantity".to_string())),
+                op: ArithmeticOp::Mul,
+                right: Box::new(ProjectionExpr::Literal(2)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_planner_rejects_outer_join() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse("SELECT * FROM orders o LEFT JOIN customer c ON o.o_custkey = c.c_custkey")
+            .unwrap();
+        assert!(matches!(
+            planner.plan(&ast),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+    }
+
+    #[test]
+    fn test_planner_rejects_negated_and_escaped_like() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        for (sql, feature) in [
+            (
+                "SELECT COUNT(*) FROM customer WHERE c_name NOT LIKE 'Customer%'",
+                "NOT LIKE",
+            ),
+            (
+                "SELECT COUNT(*) FROM customer WHERE c_name LIKE 'a!%%' ESCAPE '!'",
+                "LIKE ... ESCAPE",
+            ),
+        ] {
+            let ast = parser.parse(sql).unwrap();
+            assert!(matches!(
+                planner.plan(&ast),
+                Err(QueryError::UnsupportedFeature(f)) if f == feature
+            ));
+            assert_eq!(
+                planner.supported(&ast).unsupported,
+                vec![feature.to_string()]
+            );
+        }
+    }
+
+    #[test]
+    fn test_planner_support_report() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+
+        let ast = parser
+            .parse(
+                "SELECT o.o_orderkey, SUM(o.o_totalprice) OVER (PARTITION BY o.o_custkey) \
+                 FROM orders o JOIN customer c ON o.o_custkey = c.c_custkey \
+                 WHERE o.o_totalprice > 100 ORDER BY o.o_orderkey",
+            )
+            .unwrap();
+        let report = planner.supported(&ast);
+
+        assert_eq!(report.unsupported, vec!["window functions".to_string()]);
+        assert!(!report.is_fully_supported());
+        for clause in ["filters", "joins", "sort"] {
+            assert!(report.recognized.iter().any(|c| c == clause), "{}", clause);
+        }
+        assert!(!report.recognized.iter().any(|c| c == "aggregations"));
+
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem WHERE l_quantity > 10 GROUP BY l_orderkey")
+            .unwrap();
+        let report = planner.supported(&ast);
+        assert!(report.is_fully_supported());
+        assert_eq!(
+            report.recognized,
+            vec!["filters", "group_by", "aggregations"]
+        );
+    }
+
+    #[test]
+    fn test_planner_rejects_non_select() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser.parse("DELETE FROM lineitem").unwrap();
+        let result = planner.plan(&ast);
+        assert!(matches!(result, Err(QueryError::UnsupportedFeature(_))));
+    }
+
+    #[test]
+    fn test_planner_plan_with_derived_table() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "SELECT COUNT(*) FROM \
+                 (SELECT l_quantity FROM lineitem WHERE l_quantity > 10) t \
+                 WHERE l_quantity < 30",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.tables, vec!["t"]);
+        assert_eq!(plan.filters.len(), 1);
+        assert_eq!(plan.aggregations.len(), 1);
+
+        assert_eq!(plan.derived.len(), 1);
+        let derived = &plan.derived[0];
+        assert_eq!(derived.alias, "t");
+        assert_eq!(derived.plan.tables, vec!["lineitem"]);
+        assert_eq!(derived.plan.projection, vec!["l_quantity"]);
+        assert_eq!(derived.plan.filters.len(), 1);
+        assert!(derived.plan.aggregations.is_empty());
+        assert!(derived.plan.derived.is_empty());
+    }
+
+    #[test]
+    fn test_plan_to_sql_round_trip() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "select l_returnflag, count(*), sum(l_quantity) as total, \
+                 l_extendedprice * (1 - l_discount) as revenue \
+                 from lineitem join orders on lineitem.l_orderkey = orders.o_orderkey \
+                 where l_quantity > 10 and (l_shipmode = 'MAIL' or l_discount between 1 and 5) \
+                 and o_orderstatus in ('F', 'O') and 1 = 1 \
+                 group by l_returnflag order by l_returnflag desc",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+        let sql = plan.to_sql();
+        assert_eq!(
+            sql,
+            "SELECT l_returnflag, COUNT(*), SUM(l_quantity) AS total, \
+             (l_extendedprice * (1 - l_discount)) AS revenue \
+             FROM lineitem JOIN orders ON lineitem.l_orderkey = orders.o_orderkey \
+             WHERE l_quantity > 10 AND o_orderstatus IN ('F', 'O') \
+             AND (l_shipmode = 'MAIL' OR l_discount BETWEEN 1 AND 5) AND 1 = 1 \
+             GROUP BY l_returnflag ORDER BY l_returnflag DESC"
+        );
+
+        let replanned = planner.plan(&parser.parse(&sql).unwrap()).unwrap();
+        assert_eq!(replanned.stable_hash(), plan.stable_hash());
+        assert_eq!(replanned.to_sql(), sql);
+    }
+
+    #[test]
+    fn test_planner_plan_union_all() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "SELECT l_quantity FROM lineitem WHERE l_quantity > 10 \
+                 UNION ALL SELECT l_quantity FROM lineitem WHERE l_quantity < 5 \
+                 UNION ALL SELECT l_quantity FROM orders",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        // Branches are flattened left to right
+        assert_eq!(plan.tables, vec!["lineitem"]);
+        assert_eq!(plan.filters.len(), 1);
+        assert_eq!(plan.union_all.len(), 2);
+        assert_eq!(plan.union_all[0].filters.len(), 1);
+        assert_eq!(plan.union_all[1].tables, vec!["orders"]);
+        assert!(plan.union_all.iter().all(|p| p.union_all.is_empty()));
+        assert!(planner.supported(&ast).is_fully_supported());
+
+        // UNION (distinct) is not supported
+        let ast = parser
+            .parse("SELECT l_quantity FROM lineitem UNION SELECT l_quantity FROM orders")
+            .unwrap();
+        assert!(matches!(
+            planner.plan(&ast),
+            Err(QueryError::UnsupportedFeature(_))
+        ));
+        assert!(!planner.supported(&ast).is_fully_supported());
+    }
+
     #[test]
     fn test_planner_plan_with_where() {
         let planner = QueryPlanner::new();
@@ -453,6 +2200,54 @@ mod tests {
         assert!(!plan.group_by.is_empty());
     }
 
+    #[test]
+    fn test_planner_plan_with_having() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "SELECT l_returnflag, COUNT(*) FROM lineitem \
+                 GROUP BY l_returnflag HAVING COUNT(*) > 2",
+            )
+            .unwrap();
+        assert!(planner
+            .supported(&ast)
+            .recognized
+            .iter()
+            .any(|c| c == "having"));
+
+        let plan = planner.plan(&ast).unwrap();
+        assert_eq!(plan.having, Some(HavingCondition::CountGreaterThan(2)));
+        assert!(plan.having.unwrap().holds(3));
+        assert!(!plan.having.unwrap().holds(2));
+
+        // HAVING is rendered and hashed
+        let sql = plan.to_sql();
+        assert!(sql.ends_with("GROUP BY l_returnflag HAVING COUNT(*) > 2"));
+        let replanned = planner.plan(&parser.parse(&sql).unwrap()).unwrap();
+        assert_eq!(replanned.stable_hash(), plan.stable_hash());
+        let without = ExecutionPlan {
+            having: None,
+            ..plan.clone()
+        };
+        assert_ne!(without.stable_hash(), plan.stable_hash());
+
+        // Other HAVING predicates are not supported
+        for having in ["SUM(l_quantity) > 2", "COUNT(*) < 2", "COUNT(l_tax) > 2"] {
+            let ast = parser
+                .parse(&format!(
+                    "SELECT l_returnflag FROM lineitem GROUP BY l_returnflag HAVING {}",
+                    having
+                ))
+                .unwrap();
+            assert!(matches!(
+                planner.plan(&ast),
+                Err(QueryError::UnsupportedFeature(_))
+            ));
+            assert!(!planner.supported(&ast).is_fully_supported());
+        }
+    }
+
     #[test]
     fn test_planner_plan_with_aggregation() {
         let planner = QueryPlanner::new();
@@ -461,4 +2256,100 @@ mod tests {
         let plan = planner.plan(&ast).unwrap();
         assert!(!plan.aggregations.is_empty());
     }
+
+    #[test]
+    fn test_planner_plan_with_count_distinct() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse(
+                "SELECT l_orderkey, COUNT(DISTINCT l_suppkey), COUNT(l_suppkey) \
+                 FROM lineitem GROUP BY l_orderkey",
+            )
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        let functions: Vec<_> = plan.aggregations.iter().map(|a| &a.function).collect();
+        assert_eq!(
+            functions,
+            vec![
+                &AggregationFunction::CountDistinct,
+                &AggregationFunction::Count
+            ]
+        );
+        assert_eq!(plan.aggregations[0].column.as_deref(), Some("l_suppkey"));
+    }
+
+    #[test]
+    fn test_planner_plan_with_computed_projection() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse("SELECT l_extendedprice * (1 - l_discount) AS disc_price FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.projection, vec!["disc_price".to_string()]);
+        assert_eq!(plan.computed.len(), 1);
+        assert_eq!(plan.computed[0].name, "disc_price");
+        assert_eq!(
+            plan.computed[0].expr,
+            ProjectionExpr::BinaryOp {
+                left: Box::new(ProjectionExpr::Column("l_extendedprice".to_string())),
+                op: ArithmeticOp::Mul,
+                right: Box::new(ProjectionExpr::BinaryOp {
+                    left: Box::new(ProjectionExpr::Literal(1)),
+                    op: ArithmeticOp::Sub,
+                    right: Box::new(ProjectionExpr::Column("l_discount".to_string())),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_planner_plan_with_modulo_projection() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse("SELECT l_orderkey % 10 AS bucket, l_orderkey / 10 FROM lineitem")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.computed.len(), 2);
+        assert_eq!(
+            plan.computed[0].expr,
+            ProjectionExpr::BinaryOp {
+                left: Box::new(ProjectionExpr::Column("l_orderkey".to_string())),
+                op: ArithmeticOp::Mod,
+                right: Box::new(ProjectionExpr::Literal(10)),
+            }
+        );
+        assert!(matches!(
+            plan.computed[1].expr,
+            ProjectionExpr::BinaryOp {
+                op: ArithmeticOp::Div,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_planner_plan_with_column_comparison() {
+        let planner = QueryPlanner::new();
+        let parser = QueryParser::new();
+        let ast = parser
+            .parse("SELECT COUNT(*) FROM lineitem l WHERE l.l_commitdate < l.l_receiptdate")
+            .unwrap();
+        let plan = planner.plan(&ast).unwrap();
+
+        assert_eq!(plan.filters.len(), 1);
+        assert_eq!(plan.filters[0].column, "lineitem.l_commitdate");
+        match &plan.filters[0].condition {
+            FilterCondition::ColumnCompare { other_column, op } => {
+                assert_eq!(other_column, "lineitem.l_receiptdate");
+                assert_eq!(*op, CompareOp::LessThan);
+            }
+            other => panic!("Expected a column comparison, got {:?}", other),
+        }
+    }
 }
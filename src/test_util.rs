@@ -0,0 +1,127 @@
+//! Shared fixtures for unit tests
+//!
+//! Helpers for building gate inputs, small tables, and query plans, and for
+//! running circuits on the mock prover, so tests don't have to rebuild them.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use crate::test_util::{fields, run_mock, small_lineitem};
+//!
+//! let table = small_lineitem(&[5, 12, 30]);
+//! let values = fields(&[1, 2, 3]);
+//! assert_eq!(run_mock(&circuit, 10), Ok(()));
+//! ```
+
+use crate::circuit::builder::CircuitBuilder;
+use crate::circuit::NzengiCircuit;
+use crate::gates::selection::{Comparison, Operand, Predicate};
+use crate::query::planner::ExecutionPlan;
+use crate::query::{QueryParser, QueryPlanner};
+use crate::types::{Column, DataType, Row, Table, Value};
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    plonk::{Circuit, ConstraintSystem},
+};
+use std::collections::HashMap;
+
+/// Convert integers to field elements
+pub(crate) fn fields(values: &[u64]) -> Vec<Field> {
+    values.iter().map(|&v| Field::from(v)).collect()
+}
+
+/// Build a circuit proving COUNT(*) of the values above 1 of a table column
+///
+/// The comparison loads the u8 lookup table, so the circuit needs k >= 9.
+pub(crate) fn count_circuit(values: &[u64]) -> NzengiCircuit {
+    let count = values.iter().filter(|&&v| v > 1).count();
+    NzengiCircuit::new()
+        .with_table(values.len(), vec![fields(values)])
+        .with_predicate(Predicate::Compare {
+            column: 0,
+            comparison: Comparison::Greater,
+            rhs: Operand::Constant(Field::from(1)),
+            negated: false,
+        })
+        .with_count_output(Field::from(count as u64))
+}
+
+/// Build a `lineitem` table with a single INTEGER `l_quantity` column
+///
+/// # Arguments
+/// * `quantities` - `l_quantity` of each row, in order
+pub(crate) fn small_lineitem(quantities: &[i32]) -> Table {
+    let mut table = Table::new(
+        "lineitem".to_string(),
+        vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+    );
+    for &quantity in quantities {
+        table.rows.push(Row::new(vec![Value::Integer(quantity)]));
+    }
+    table
+}
+
+/// Map tables by name, as the executor expects them
+pub(crate) fn tables(tables: impl IntoIterator<Item = Table>) -> HashMap<String, Table> {
+    tables
+        .into_iter()
+        .map(|table| (table.name.clone(), table))
+        .collect()
+}
+
+/// Parse and plan a SQL query
+///
+/// # Panics
+/// If the query doesn't parse or plan
+pub(crate) fn plan(sql: &str) -> ExecutionPlan {
+    let ast = QueryParser::new().parse(sql).unwrap();
+    QueryPlanner::new().plan(&ast).unwrap()
+}
+
+/// Build sort gate inputs for values
+///
+/// # Returns
+/// `(input, sorted)`: the values, and the values in ascending order
+pub(crate) fn sorted_fields(values: &[u64]) -> (Vec<Field>, Vec<Field>) {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    (fields(values), fields(&sorted))
+}
+
+/// Build aggregation gate group witnesses for sorted keys
+///
+/// # Returns
+/// `(binary_markers, start_indices, end_indices)`, with groups of equal keys
+/// as the group-by gate derives them
+pub(crate) fn group_witness(sorted_keys: &[u64]) -> (Vec<Field>, Vec<Field>, Vec<Field>) {
+    CircuitBuilder::group_witness(&fields(sorted_keys))
+}
+
+/// Run a circuit on the mock prover with empty instance columns
+///
+/// # Panics
+/// If synthesis fails
+///
+/// # Returns
+/// `Ok(())` if every constraint holds, the failures otherwise
+pub(crate) fn run_mock<C: Circuit<Field>>(circuit: &C, k: u32) -> Result<(), Vec<VerifyFailure>> {
+    let mut meta = ConstraintSystem::<Field>::default();
+    C::configure(&mut meta);
+    run_mock_with_instance(circuit, k, vec![vec![]; meta.num_instance_columns()])
+}
+
+/// Run a circuit on the mock prover with the given instance columns
+///
+/// # Panics
+/// If synthesis fails
+///
+/// # Returns
+/// `Ok(())` if every constraint holds, the failures otherwise
+pub(crate) fn run_mock_with_instance<C: Circuit<Field>>(
+    circuit: &C,
+    k: u32,
+    instance: Vec<Vec<Field>>,
+) -> Result<(), Vec<VerifyFailure>> {
+    MockProver::run(k, circuit, instance).unwrap().verify()
+}
@@ -4,12 +4,88 @@
 //! - Tables, columns, and rows
 //! - SQL data types and values
 //! - Query results and proofs
+//! - Composite keys (see the `keys` module)
 
+pub mod keys;
+
+use crate::commitment::CellOpening;
 use halo2_proofs::halo2curves::bn256::Fr as Field;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+
+/// Number of fractional digits stored in a `Decimal` value
+///
+/// `Decimal(1234)` represents `12.34`.
+pub const DECIMAL_SCALE: u32 = 2;
+
+/// Error parsing a string into a typed `Value`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    /// The input is not a valid value of the expected type
+    #[error("invalid {data_type} value: {input:?}")]
+    Invalid {
+        /// Input string
+        input: String,
+
+        /// Expected data type
+        data_type: DataType,
+    },
+
+    /// The input is longer than the VARCHAR length
+    #[error("value {input:?} exceeds VARCHAR({max_len})")]
+    TooLong {
+        /// Input string
+        input: String,
+
+        /// Maximum length
+        max_len: usize,
+    },
+}
+
+/// Error building a table with `TableBuilder`
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TableBuildError {
+    /// A row has a different number of values than declared columns
+    #[error("row {row} has {found} values, expected {expected}")]
+    ArityMismatch {
+        /// Row index
+        row: usize,
+
+        /// Number of declared columns
+        expected: usize,
+
+        /// Number of values in the row
+        found: usize,
+    },
+
+    /// A value does not match its column's data type
+    #[error("row {row}, column {column}: {value} is not a {data_type} value")]
+    TypeMismatch {
+        /// Row index
+        row: usize,
+
+        /// Column name
+        column: String,
+
+        /// Declared data type
+        data_type: DataType,
+
+        /// Offending value
+        value: Value,
+    },
+
+    /// A column was declared after rows were added
+    #[error("column {0} declared after rows were added")]
+    ColumnAfterRows(String),
+}
 
 /// Database table representation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Tables compare structurally: name, columns, and rows in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Table {
     /// Table name
     pub name: String,
@@ -45,10 +121,228 @@ impl Table {
     pub fn get_column(&self, name: &str) -> Option<&Column> {
         self.columns.iter().find(|c| c.name == name)
     }
+
+    /// Insert a row, validating it against the declared columns
+    ///
+    /// Strings must fit the VARCHAR length of their column.
+    ///
+    /// # Arguments
+    /// * `row` - Row to append
+    ///
+    /// # Returns
+    /// `Ok(())` if the row was appended, `Err(TableBuildError)` if its value
+    /// count or a value doesn't match the columns (the table is unchanged)
+    pub fn insert_row(&mut self, row: Row) -> Result<(), TableBuildError> {
+        self.validate_row(&row.values)?;
+        self.rows.push(row);
+        Ok(())
+    }
+
+    /// Validate the values of the next row against the declared columns
+    fn validate_row(&self, values: &[Value]) -> Result<(), TableBuildError> {
+        let row = self.rows.len();
+        if values.len() != self.columns.len() {
+            return Err(TableBuildError::ArityMismatch {
+                row,
+                expected: self.columns.len(),
+                found: values.len(),
+            });
+        }
+
+        for (column, value) in self.columns.iter().zip(values) {
+            if !value.is_compatible(&column.data_type) {
+                return Err(TableBuildError::TypeMismatch {
+                    row,
+                    column: column.name.clone(),
+                    data_type: column.data_type.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the values of a column
+    ///
+    /// Rows missing the column yield `NULL`.
+    ///
+    /// # Arguments
+    /// * `column_name` - Name of the column
+    ///
+    /// # Returns
+    /// `Some(values)` in row order, `None` if the column doesn't exist
+    pub fn column_values(&self, column_name: &str) -> Option<Vec<Value>> {
+        let idx = self.columns.iter().position(|c| c.name == column_name)?;
+        Some(
+            self.rows
+                .iter()
+                .map(|row| row.values.get(idx).cloned().unwrap_or(Value::Null))
+                .collect(),
+        )
+    }
+
+    /// Get the field encodings of a column's values
+    ///
+    /// Rows missing the column yield the `NULL` encoding, as committed.
+    ///
+    /// # Arguments
+    /// * `column_name` - Name of the column
+    ///
+    /// # Returns
+    /// `Some(fields)` in row order, `None` if the column doesn't exist
+    pub fn column_fields(&self, column_name: &str) -> Option<Vec<Field>> {
+        let idx = self.columns.iter().position(|c| c.name == column_name)?;
+        Some(
+            self.rows
+                .iter()
+                .map(|row| row.column_field(idx).unwrap_or_else(Value::null_field))
+                .collect(),
+        )
+    }
+
+    /// Hash the table contents
+    ///
+    /// SHA-256 over the serialized name, columns, and rows, for cache keys
+    /// and change detection. Equal tables have equal hashes.
+    ///
+    /// # Returns
+    /// Hex-encoded SHA-256 hash string
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+
+        let serialized = serde_json::to_vec(self).expect("tables contain only serializable values");
+
+        let mut hasher = Sha256::new();
+        hasher.update(&serialized);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Project rows onto a subset of columns
+    ///
+    /// # Arguments
+    /// * `column_names` - Columns to keep, in output order
+    ///
+    /// # Returns
+    /// `Ok(Vec<Row>)` with only the named columns, `Err` if a column doesn't exist
+    pub fn project(&self, column_names: &[String]) -> Result<Vec<Row>, Box<dyn std::error::Error>> {
+        let indices = column_names
+            .iter()
+            .map(|name| {
+                self.columns
+                    .iter()
+                    .position(|c| c.name == *name)
+                    .ok_or_else(|| format!("Column {} not found in table {}", name, self.name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self
+            .rows
+            .iter()
+            .map(|row| {
+                Row::new(
+                    indices
+                        .iter()
+                        .map(|&idx| row.values.get(idx).cloned().unwrap_or(Value::Null))
+                        .collect(),
+                )
+            })
+            .collect())
+    }
+
+    /// Deterministically sample rows
+    ///
+    /// Sampled rows keep their original order, and the same seed always
+    /// yields the same sample.
+    ///
+    /// # Arguments
+    /// * `n` - Maximum number of rows to sample
+    /// * `seed` - Seed for the sampling RNG
+    ///
+    /// # Returns
+    /// A new table with the same schema and `min(n, num_rows)` rows
+    pub fn sample(&self, n: usize, seed: u64) -> Table {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut indices =
+            rand::seq::index::sample(&mut rng, self.rows.len(), n.min(self.rows.len())).into_vec();
+        indices.sort_unstable();
+
+        Table {
+            name: self.name.clone(),
+            columns: self.columns.clone(),
+            rows: indices.into_iter().map(|i| self.rows[i].clone()).collect(),
+        }
+    }
+}
+
+/// Builder for constructing tables with validated rows
+///
+/// Rows are checked against the declared columns as they are added; the
+/// first error is reported by `build`.
+///
+/// # Example
+/// ```
+/// use nzengi_db::types::{DataType, TableBuilder, Value};
+///
+/// let table = TableBuilder::new("users")
+///     .column("id", DataType::Integer)
+///     .column("name", DataType::Varchar(100))
+///     .row(vec![Value::Integer(1), Value::String("Alice".to_string())])
+///     .build()?;
+/// assert_eq!(table.rows.len(), 1);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableBuilder {
+    table: Table,
+    error: Option<TableBuildError>,
+}
+
+impl TableBuilder {
+    /// Create a new table builder
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            table: Table::new(name.into(), Vec::new()),
+            error: None,
+        }
+    }
+
+    /// Declare a column
+    ///
+    /// Columns must be declared before any rows are added.
+    pub fn column(mut self, name: impl Into<String>, data_type: DataType) -> Self {
+        let name = name.into();
+        if self.error.is_none() && !self.table.rows.is_empty() {
+            self.error = Some(TableBuildError::ColumnAfterRows(name.clone()));
+        }
+        self.table.columns.push(Column::new(name, data_type));
+        self
+    }
+
+    /// Add a row, validating value count and types against declared columns
+    pub fn row(mut self, values: Vec<Value>) -> Self {
+        if self.error.is_none() {
+            self.error = self.table.validate_row(&values).err();
+        }
+        self.table.rows.push(Row::new(values));
+        self
+    }
+
+    /// Finalize the table
+    ///
+    /// # Returns
+    /// `Ok(Table)` if every row matched the declared columns, otherwise the
+    /// first `TableBuildError` encountered
+    pub fn build(self) -> Result<Table, TableBuildError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.table),
+        }
+    }
 }
 
 /// Column definition
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Column {
     /// Column name
     pub name: String,
@@ -86,8 +380,38 @@ pub enum DataType {
     Boolean,
 }
 
+impl DataType {
+    /// Number of bits a circuit needs to range-check values of this type
+    ///
+    /// # Returns
+    /// `Some(bits)` for types encoded as unsigned integers (BOOLEAN 1,
+    /// INTEGER 32, DATE 48, BIGINT and DECIMAL 64), `None` for strings
+    pub fn range_bits(&self) -> Option<usize> {
+        match self {
+            DataType::Boolean => Some(1),
+            DataType::Integer => Some(32),
+            DataType::Date => Some(48),
+            DataType::BigInt | DataType::Decimal => Some(64),
+            DataType::Varchar(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataType::Integer => write!(f, "INTEGER"),
+            DataType::BigInt => write!(f, "BIGINT"),
+            DataType::Decimal => write!(f, "DECIMAL"),
+            DataType::Varchar(len) => write!(f, "VARCHAR({})", len),
+            DataType::Date => write!(f, "DATE"),
+            DataType::Boolean => write!(f, "BOOLEAN"),
+        }
+    }
+}
+
 /// Database row
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Row {
     /// Row values
     pub values: Vec<Value>,
@@ -109,6 +433,22 @@ impl Row {
         let col_index = table.columns.iter().position(|c| c.name == column_name)?;
         self.values.get(col_index)
     }
+
+    /// Convert all values to field elements
+    ///
+    /// # Returns
+    /// The field encoding of each value, in column order
+    pub fn to_fields(&self) -> Vec<Field> {
+        self.values.iter().map(Value::to_field).collect()
+    }
+
+    /// Get the field encoding of the value at column index
+    ///
+    /// # Returns
+    /// `Some(Field)` if the row has the column, `None` otherwise
+    pub fn column_field(&self, index: usize) -> Option<Field> {
+        self.values.get(index).map(Value::to_field)
+    }
 }
 
 /// SQL value types
@@ -137,6 +477,183 @@ pub enum Value {
 }
 
 impl Value {
+    /// Parse a string into a typed value
+    ///
+    /// Empty strings parse as `NULL`. Decimals are stored with
+    /// [`DECIMAL_SCALE`] fractional digits, dates accept ISO `YYYY-MM-DD` or
+    /// Unix seconds, and booleans accept `true`/`false`/`1`/`0`.
+    ///
+    /// # Arguments
+    /// * `s` - Input string
+    /// * `data_type` - Target data type
+    ///
+    /// # Returns
+    /// `Ok(Value)` if parsing succeeds, `Err(ParseError)` otherwise
+    pub fn parse(s: &str, data_type: &DataType) -> Result<Value, ParseError> {
+        if s.is_empty() {
+            return Ok(Value::Null);
+        }
+
+        let invalid = || ParseError::Invalid {
+            input: s.to_string(),
+            data_type: data_type.clone(),
+        };
+
+        match data_type {
+            DataType::Integer => s.parse::<i32>().map(Value::Integer).map_err(|_| invalid()),
+            DataType::BigInt => s.parse::<i64>().map(Value::BigInt).map_err(|_| invalid()),
+            DataType::Decimal => Self::parse_decimal(s)
+                .map(Value::Decimal)
+                .ok_or_else(invalid),
+            DataType::Varchar(max_len) => {
+                if s.chars().count() > *max_len {
+                    Err(ParseError::TooLong {
+                        input: s.to_string(),
+                        max_len: *max_len,
+                    })
+                } else {
+                    Ok(Value::String(s.to_string()))
+                }
+            }
+            DataType::Date => Self::parse_date(s).map(Value::Date).ok_or_else(invalid),
+            DataType::Boolean => match s.to_ascii_lowercase().as_str() {
+                "true" | "1" => Ok(Value::Boolean(true)),
+                "false" | "0" => Ok(Value::Boolean(false)),
+                _ => Err(invalid()),
+            },
+        }
+    }
+
+    /// Parse a string into a typed value, truncating over-long strings
+    ///
+    /// Same as `parse`, but a string longer than its VARCHAR length is cut
+    /// to the first `n` characters instead of rejected.
+    ///
+    /// # Arguments
+    /// * `s` - Input string
+    /// * `data_type` - Target data type
+    ///
+    /// # Returns
+    /// `Ok(Value)` if parsing succeeds, `Err(ParseError)` otherwise
+    pub fn parse_truncating(s: &str, data_type: &DataType) -> Result<Value, ParseError> {
+        match data_type {
+            DataType::Varchar(max_len) if !s.is_empty() => {
+                Ok(Value::String(s.chars().take(*max_len).collect()))
+            }
+            _ => Self::parse(s, data_type),
+        }
+    }
+
+    /// Check whether the value can be stored in a column of the given type
+    ///
+    /// `NULL` is compatible with every type, and strings must fit the
+    /// VARCHAR length.
+    pub fn is_compatible(&self, data_type: &DataType) -> bool {
+        match (self, data_type) {
+            (Value::Null, _) => true,
+            (Value::Integer(_), DataType::Integer)
+            | (Value::BigInt(_), DataType::BigInt)
+            | (Value::Decimal(_), DataType::Decimal)
+            | (Value::Date(_), DataType::Date)
+            | (Value::Boolean(_), DataType::Boolean) => true,
+            (Value::String(s), DataType::Varchar(max_len)) => s.chars().count() <= *max_len,
+            _ => false,
+        }
+    }
+
+    /// Convert the value to another type by safe numeric promotion
+    ///
+    /// Integers widen along INTEGER → BIGINT → DECIMAL, with decimals scaled
+    /// by [`DECIMAL_SCALE`]. A value casts to its own type unchanged and
+    /// `NULL` casts to every type. Narrowing (even of values that would fit)
+    /// and other conversions are rejected, so a cast never loses information.
+    ///
+    /// # Arguments
+    /// * `to` - Target type
+    ///
+    /// # Returns
+    /// `Some(Value)` of the target type, `None` if the cast isn't a promotion
+    /// or the promoted decimal would overflow
+    pub fn cast(&self, to: &DataType) -> Option<Value> {
+        let scale = 10i64.pow(DECIMAL_SCALE);
+        match (self, to) {
+            _ if self.is_compatible(to) => Some(self.clone()),
+            (Value::Integer(v), DataType::BigInt) => Some(Value::BigInt(*v as i64)),
+            (Value::Integer(v), DataType::Decimal) => Some(Value::Decimal(*v as i64 * scale)),
+            (Value::BigInt(v), DataType::Decimal) => v.checked_mul(scale).map(Value::Decimal),
+            _ => None,
+        }
+    }
+
+    /// Parse a decimal string into a fixed-point integer with `DECIMAL_SCALE` digits
+    fn parse_decimal(s: &str) -> Option<i64> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !all_digits(int_part)
+            || !all_digits(frac_part)
+            || frac_part.len() > DECIMAL_SCALE as usize
+        {
+            return None;
+        }
+
+        let scale = 10i64.pow(DECIMAL_SCALE);
+        let int_value = if int_part.is_empty() {
+            0
+        } else {
+            int_part.parse::<i64>().ok()?
+        };
+        let frac_value = if frac_part.is_empty() {
+            0
+        } else {
+            frac_part.parse::<i64>().ok()? * 10i64.pow(DECIMAL_SCALE - frac_part.len() as u32)
+        };
+
+        let magnitude = int_value.checked_mul(scale)?.checked_add(frac_value)?;
+        Some(if negative { -magnitude } else { magnitude })
+    }
+
+    /// Parse a date as ISO `YYYY-MM-DD` or Unix seconds
+    fn parse_date(s: &str) -> Option<u64> {
+        if let Ok(epoch) = s.parse::<u64>() {
+            return Some(epoch);
+        }
+
+        let mut parts = s.splitn(3, '-');
+        let year: i64 = parts.next()?.parse().ok()?;
+        let month: i64 = parts.next()?.parse().ok()?;
+        let day: i64 = parts.next()?.parse().ok()?;
+        if !(1..=12).contains(&month) || day < 1 || day > Self::days_in_month(year, month) {
+            return None;
+        }
+
+        // Days since 1970-01-01 (proleptic Gregorian calendar)
+        let y = if month <= 2 { year - 1 } else { year };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        u64::try_from(days * 86400).ok()
+    }
+
+    /// Number of days in a month of the given year
+    fn days_in_month(year: i64, month: i64) -> i64 {
+        match month {
+            4 | 6 | 9 | 11 => 30,
+            2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+            2 => 28,
+            _ => 31,
+        }
+    }
+
     /// Convert value to field element
     ///
     /// This is used when converting SQL values to field elements for ZKP circuits.
@@ -166,29 +683,36 @@ impl Value {
                 // Hash string to field element
                 Self::string_to_field(s)
             }
-            Value::Null => Field::zero(),
+            Value::Null => Self::null_field(),
         }
     }
 
-    /// Hash string to field element
+    /// Field encoding of `NULL`
     ///
-    /// Strings are hashed using SHA-256 to fit within the field size.
-    /// Only the first 31 bytes of the hash are used to ensure it fits in the field.
-    fn string_to_field(s: &str) -> Field {
-        use sha2::{Digest, Sha256};
+    /// `NULL` maps to the reserved sentinel `-1` (the field modulus minus one),
+    /// which no integer, decimal, date, or boolean encodes to. This keeps it
+    /// distinct from `Integer(0)` in commitments and gates.
+    pub fn null_field() -> Field {
+        -Field::one()
+    }
 
-        let mut hasher = Sha256::new();
-        hasher.update(s.as_bytes());
-        let hash = hasher.finalize();
+    /// Check whether a field element is the `NULL` sentinel
+    pub fn is_null_field(field: &Field) -> bool {
+        *field == Self::null_field()
+    }
 
-        // Take first 31 bytes to fit in field (254-bit field, 31 bytes = 248 bits)
-        let mut bytes = [0u8; 32];
-        bytes[1..32].copy_from_slice(&hash[..31]);
+    /// Hash string to field element
+    ///
+    /// Strings are hashed with SHA-512 and the 64 hash bytes are reduced
+    /// modulo the field order, so every string maps to a valid, uniformly
+    /// distributed element.
+    fn string_to_field(s: &str) -> Field {
+        use ff::FromUniformBytes;
+        use sha2::{Digest, Sha512};
 
-        // Convert to field element
-        // Note: This may fail if the bytes don't form a valid field element
-        // In production, we might want to handle this more gracefully
-        Field::from_bytes(&bytes).unwrap_or(Field::zero())
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&Sha512::digest(s.as_bytes()));
+        Field::from_uniform_bytes(&wide)
     }
 
     /// Convert from field element (for integer types only)
@@ -196,6 +720,10 @@ impl Value {
     /// This is a helper for converting field elements back to integer values.
     /// Note: This only works for integer types, not for strings (which are hashed).
     pub fn from_field(field: &Field, data_type: &DataType) -> Option<Self> {
+        if Self::is_null_field(field) {
+            return Some(Value::Null);
+        }
+
         // Convert field to bytes
         let bytes = field.to_bytes();
 
@@ -213,6 +741,57 @@ impl Value {
             DataType::Varchar(_) => None, // Cannot recover string from hash
         }
     }
+
+    /// Decode an aggregate over a column back to a typed value
+    ///
+    /// `raw` is computed over the integer encodings of the column's values
+    /// (decimals as fixed-point), so it decodes to the column type: INTEGER
+    /// stays INTEGER unless it overflows 32 bits (then BIGINT), DECIMAL stays
+    /// DECIMAL, and every other type decodes as BIGINT.
+    ///
+    /// # Arguments
+    /// * `raw` - Aggregate of the encoded values
+    /// * `data_type` - Type of the aggregated column
+    ///
+    /// # Returns
+    /// The aggregate as a `Value` of the column's type
+    pub fn from_aggregate(raw: i64, data_type: &DataType) -> Value {
+        match data_type {
+            DataType::Integer => i32::try_from(raw).map_or(Value::BigInt(raw), Value::Integer),
+            DataType::Decimal => Value::Decimal(raw),
+            _ => Value::BigInt(raw),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    /// Render the value as it would appear in SQL
+    ///
+    /// Strings are single-quoted, decimals use `DECIMAL_SCALE` fractional
+    /// digits, and `NULL` renders as `NULL`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(v) => write!(f, "{}", v),
+            Value::BigInt(v) => write!(f, "{}", v),
+            Value::Decimal(v) => {
+                let scale = 10u64.pow(DECIMAL_SCALE);
+                let sign = if *v < 0 { "-" } else { "" };
+                let magnitude = v.unsigned_abs();
+                write!(
+                    f,
+                    "{}{}.{:0width$}",
+                    sign,
+                    magnitude / scale,
+                    magnitude % scale,
+                    width = DECIMAL_SCALE as usize
+                )
+            }
+            Value::String(s) => write!(f, "'{}'", s.replace('\'', "''")),
+            Value::Date(v) => write!(f, "{}", v),
+            Value::Boolean(b) => write!(f, "{}", if *b { "TRUE" } else { "FALSE" }),
+            Value::Null => write!(f, "NULL"),
+        }
+    }
 }
 
 /// Query result
@@ -225,6 +804,13 @@ pub struct QueryResult {
 
     /// Result rows
     pub rows: Vec<Row>,
+
+    /// Whether the result holds only public aggregate outputs
+    ///
+    /// Aggregate-only results have a single row of aggregates proven as
+    /// public outputs; no source rows or committed cells are revealed.
+    #[serde(default)]
+    pub aggregate_only: bool,
 }
 
 impl QueryResult {
@@ -233,6 +819,23 @@ impl QueryResult {
         Self {
             columns,
             rows: Vec::new(),
+            aggregate_only: false,
+        }
+    }
+
+    /// Create an aggregate-only query result
+    ///
+    /// # Arguments
+    /// * `columns` - Aggregate column names
+    /// * `values` - Aggregate values, one per column
+    ///
+    /// # Returns
+    /// Result with a single row of aggregates
+    pub fn aggregate_only(columns: Vec<String>, values: Vec<Value>) -> Self {
+        Self {
+            columns,
+            rows: vec![Row::new(values)],
+            aggregate_only: true,
         }
     }
 
@@ -250,6 +853,52 @@ impl QueryResult {
     pub fn num_columns(&self) -> usize {
         self.columns.len()
     }
+
+    /// Render the result as an aligned text table
+    ///
+    /// Values are rendered with their `Display` implementation.
+    ///
+    /// # Returns
+    /// Table with a header row, a separator, and one line per result row
+    pub fn to_table_string(&self) -> String {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.values.iter().map(|v| v.to_string()).collect())
+            .collect();
+
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &cells {
+            for (i, cell) in row.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(cell.chars().count());
+                }
+            }
+        }
+
+        let format_line = |values: &[String]| {
+            values
+                .iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{:<width$}", value, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![format_line(&self.columns)];
+        lines.push(
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("-+-"),
+        );
+        lines.extend(cells.iter().map(|row| format_line(row)));
+
+        lines.join("\n")
+    }
 }
 
 /// Zero-knowledge proof
@@ -261,7 +910,17 @@ pub struct Proof {
     pub proof_bytes: Vec<u8>,
 
     /// Public inputs (for verification)
+    ///
+    /// May be shorter than `num_public_inputs` after trailing zeros were
+    /// stripped; see [`Proof::padded_public_inputs`].
     pub public_inputs: Vec<Field>,
+
+    /// Number of public inputs the proof was created with
+    pub num_public_inputs: usize,
+
+    /// Openings of the committed cells the query read (empty unless the
+    /// query was executed against a database commitment)
+    pub openings: Vec<CellOpening>,
 }
 
 impl Proof {
@@ -269,19 +928,83 @@ impl Proof {
     pub fn new(proof_bytes: Vec<u8>, public_inputs: Vec<Field>) -> Self {
         Self {
             proof_bytes,
+            num_public_inputs: public_inputs.len(),
             public_inputs,
+            openings: Vec::new(),
         }
     }
 
+    /// Bundle cell openings with the proof
+    ///
+    /// # Arguments
+    /// * `openings` - Openings of the committed cells the query read
+    ///
+    /// # Returns
+    /// Self for method chaining
+    pub fn with_openings(mut self, openings: Vec<CellOpening>) -> Self {
+        self.openings = openings;
+        self
+    }
+
     /// Get proof size in bytes
     pub fn size(&self) -> usize {
         self.proof_bytes.len()
     }
 
-    /// Serialize proof to JSON (for debugging)
+    /// Get the stored size of the proof in bytes
+    ///
+    /// Counts the proof bytes, 32 bytes per stored public input and 8 bytes
+    /// for the original public input count.
+    pub fn compressed_size(&self) -> usize {
+        self.proof_bytes.len() + self.public_inputs.len() * 32 + 8
+    }
+
+    /// Remove trailing zero public inputs
+    ///
+    /// Circuits pad their instance column with zeros; those are restored by
+    /// [`Proof::padded_public_inputs`] before verification.
+    ///
+    /// # Returns
+    /// Number of public inputs removed
+    pub fn strip_trailing_zero_inputs(&mut self) -> usize {
+        let kept = self
+            .public_inputs
+            .iter()
+            .rposition(|input| *input != Field::zero())
+            .map_or(0, |idx| idx + 1);
+        let removed = self.public_inputs.len() - kept;
+        self.public_inputs.truncate(kept);
+        removed
+    }
+
+    /// Get the public inputs padded back to their original length
+    ///
+    /// # Returns
+    /// The stored public inputs followed by zeros up to `num_public_inputs`
+    pub fn padded_public_inputs(&self) -> Vec<Field> {
+        let mut inputs = self.public_inputs.clone();
+        if inputs.len() < self.num_public_inputs {
+            inputs.resize(self.num_public_inputs, Field::zero());
+        }
+        inputs
+    }
+
+    /// Serialize proof to JSON, including its cell openings
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
         serde_json::to_string(self)
     }
+
+    /// Deserialize a proof from the JSON produced by `to_json`
+    ///
+    /// # Arguments
+    /// * `json` - Serialized proof
+    ///
+    /// # Returns
+    /// `Ok(Proof)`, `Err` if the JSON is malformed or a public input or
+    /// opening scalar is not a canonical field element
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
 }
 
 // Serialization for Proof (for JSON export)
@@ -291,7 +1014,7 @@ impl Serialize for Proof {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("Proof", 2)?;
+        let mut state = serializer.serialize_struct("Proof", 4)?;
         state.serialize_field("proof_bytes", &hex::encode(&self.proof_bytes))?;
         state.serialize_field(
             "public_inputs",
@@ -301,10 +1024,52 @@ impl Serialize for Proof {
                 .map(|f| hex::encode(f.to_bytes()))
                 .collect::<Vec<_>>(),
         )?;
+        state.serialize_field("num_public_inputs", &self.num_public_inputs)?;
+        state.serialize_field("openings", &self.openings)?;
         state.end()
     }
 }
 
+impl<'de> Deserialize<'de> for Proof {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        #[derive(Deserialize)]
+        struct Encoded {
+            proof_bytes: String,
+            public_inputs: Vec<String>,
+            num_public_inputs: usize,
+            #[serde(default)]
+            openings: Vec<CellOpening>,
+        }
+
+        let encoded = Encoded::deserialize(deserializer)?;
+        let proof_bytes = hex::decode(&encoded.proof_bytes).map_err(D::Error::custom)?;
+        let public_inputs = encoded
+            .public_inputs
+            .iter()
+            .map(|input| {
+                let bytes: [u8; 32] = hex::decode(input)
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| D::Error::custom("public input is not 32 hex bytes"))?;
+                Option::from(Field::from_bytes(&bytes))
+                    .ok_or_else(|| D::Error::custom("public input is not a field element"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            proof_bytes,
+            public_inputs,
+            num_public_inputs: encoded.num_public_inputs,
+            openings: encoded.openings,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,6 +1087,347 @@ mod tests {
         assert_eq!(table.num_rows(), 0);
     }
 
+    #[test]
+    fn test_value_display() {
+        assert_eq!(Value::Integer(42).to_string(), "42");
+        assert_eq!(Value::BigInt(-7).to_string(), "-7");
+        assert_eq!(Value::Decimal(1234).to_string(), "12.34");
+        assert_eq!(Value::Decimal(-5).to_string(), "-0.05");
+        assert_eq!(Value::String("x".to_string()).to_string(), "'x'");
+        assert_eq!(Value::String("it's".to_string()).to_string(), "'it''s'");
+        assert_eq!(Value::Boolean(true).to_string(), "TRUE");
+        assert_eq!(Value::Null.to_string(), "NULL");
+    }
+
+    #[test]
+    fn test_data_type_display() {
+        assert_eq!(DataType::Integer.to_string(), "INTEGER");
+        assert_eq!(DataType::Varchar(50).to_string(), "VARCHAR(50)");
+        assert_eq!(DataType::Decimal.to_string(), "DECIMAL");
+    }
+
+    #[test]
+    fn test_query_result_to_table_string() {
+        let mut result = QueryResult::new(vec!["id".to_string(), "name".to_string()]);
+        result.add_row(Row::new(vec![
+            Value::Integer(1),
+            Value::String("Alice".to_string()),
+        ]));
+        result.add_row(Row::new(vec![Value::Integer(20), Value::Null]));
+
+        assert_eq!(
+            result.to_table_string(),
+            "id | name\n---+--------\n1  | 'Alice'\n20 | NULL"
+        );
+    }
+
+    #[test]
+    fn test_table_equality_and_hash() {
+        let build = || {
+            TableBuilder::new("users")
+                .column("id", DataType::Integer)
+                .column("name", DataType::Varchar(10))
+                .row(vec![Value::Integer(1), Value::String("Alice".to_string())])
+                .row(vec![Value::Integer(2), Value::String("Bob".to_string())])
+                .build()
+                .unwrap()
+        };
+
+        let table = build();
+        let same = build();
+        assert_eq!(table, same);
+        assert_eq!(table.content_hash(), same.content_hash());
+        assert_eq!(table.content_hash().len(), 64);
+
+        let mut modified = build();
+        modified.rows[1].values[1] = Value::String("Carol".to_string());
+        assert_ne!(table, modified);
+        assert_ne!(table.content_hash(), modified.content_hash());
+    }
+
+    #[test]
+    fn test_table_builder() {
+        let table = TableBuilder::new("users")
+            .column("id", DataType::Integer)
+            .column("name", DataType::Varchar(5))
+            .row(vec![Value::Integer(1), Value::String("Alice".to_string())])
+            .row(vec![Value::Integer(2), Value::Null])
+            .build()
+            .unwrap();
+
+        assert_eq!(table.name, "users");
+        assert_eq!(table.num_columns(), 2);
+        assert_eq!(table.columns[1].data_type, DataType::Varchar(5));
+        assert_eq!(table.num_rows(), 2);
+        assert_eq!(table.rows[1].values, vec![Value::Integer(2), Value::Null]);
+    }
+
+    #[test]
+    fn test_table_builder_rejects_invalid_rows() {
+        let err = TableBuilder::new("users")
+            .column("id", DataType::Integer)
+            .column("name", DataType::Varchar(100))
+            .row(vec![Value::Integer(1)])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            TableBuildError::ArityMismatch {
+                row: 0,
+                expected: 2,
+                found: 1
+            }
+        );
+
+        let err = TableBuilder::new("users")
+            .column("id", DataType::Integer)
+            .row(vec![Value::Integer(1)])
+            .row(vec![Value::String("two".to_string())])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, TableBuildError::TypeMismatch { row: 1, .. }));
+
+        let err = TableBuilder::new("users")
+            .column("id", DataType::Integer)
+            .row(vec![Value::Integer(1)])
+            .column("name", DataType::Varchar(100))
+            .build()
+            .unwrap_err();
+        assert_eq!(err, TableBuildError::ColumnAfterRows("name".to_string()));
+    }
+
+    #[test]
+    fn test_table_sample() {
+        let columns = vec![Column::new("id".to_string(), DataType::Integer)];
+        let mut table = Table::new("users".to_string(), columns);
+        for i in 0..100 {
+            table.rows.push(Row::new(vec![Value::Integer(i)]));
+        }
+
+        let sample = table.sample(10, 42);
+        assert_eq!(sample.num_rows(), 10);
+        assert_eq!(sample.num_columns(), 1);
+        assert_eq!(sample.name, "users");
+
+        // Same seed, same sample
+        let again = table.sample(10, 42);
+        let ids = |t: &Table| t.rows.iter().map(|r| r.values.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&sample), ids(&again));
+
+        // Sample larger than the table returns every row
+        assert_eq!(table.sample(1000, 42).num_rows(), 100);
+        assert_eq!(table.sample(0, 42).num_rows(), 0);
+    }
+
+    #[test]
+    fn test_table_project() {
+        let columns = vec![
+            Column::new("id".to_string(), DataType::Integer),
+            Column::new("name".to_string(), DataType::Varchar(100)),
+            Column::new("active".to_string(), DataType::Boolean),
+        ];
+        let mut table = Table::new("users".to_string(), columns);
+        table.rows.push(Row::new(vec![
+            Value::Integer(1),
+            Value::String("alice".to_string()),
+            Value::Boolean(true),
+        ]));
+        table.rows.push(Row::new(vec![
+            Value::Integer(2),
+            Value::String("bob".to_string()),
+            Value::Boolean(false),
+        ]));
+
+        let projected = table
+            .project(&["active".to_string(), "id".to_string()])
+            .unwrap();
+        assert_eq!(projected.len(), 2);
+        assert_eq!(
+            projected[0].values,
+            vec![Value::Boolean(true), Value::Integer(1)]
+        );
+        assert_eq!(
+            projected[1].values,
+            vec![Value::Boolean(false), Value::Integer(2)]
+        );
+
+        assert!(table.project(&["missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_table_column_values() {
+        let columns = vec![
+            Column::new("id".to_string(), DataType::Integer),
+            Column::new("name".to_string(), DataType::Varchar(100)),
+        ];
+        let mut table = Table::new("users".to_string(), columns);
+        table.rows.push(Row::new(vec![
+            Value::Integer(1),
+            Value::String("alice".to_string()),
+        ]));
+        // A short row has no value for the second column
+        table.rows.push(Row::new(vec![Value::Integer(2)]));
+
+        assert_eq!(
+            table.column_values("name"),
+            Some(vec![Value::String("alice".to_string()), Value::Null])
+        );
+        assert_eq!(
+            table.column_fields("id"),
+            Some(vec![
+                Value::Integer(1).to_field(),
+                Value::Integer(2).to_field()
+            ])
+        );
+        assert_eq!(table.column_fields("name").unwrap()[1], Value::null_field());
+
+        assert_eq!(table.column_values("missing"), None);
+        assert_eq!(table.column_fields("missing"), None);
+    }
+
+    #[test]
+    fn test_value_parse_integer() {
+        assert_eq!(
+            Value::parse("42", &DataType::Integer),
+            Ok(Value::Integer(42))
+        );
+        assert_eq!(
+            Value::parse("-7", &DataType::Integer),
+            Ok(Value::Integer(-7))
+        );
+        assert!(matches!(
+            Value::parse("abc", &DataType::Integer),
+            Err(ParseError::Invalid { .. })
+        ));
+        assert!(Value::parse("3000000000", &DataType::Integer).is_err());
+    }
+
+    #[test]
+    fn test_value_parse_bigint() {
+        assert_eq!(
+            Value::parse("3000000000", &DataType::BigInt),
+            Ok(Value::BigInt(3_000_000_000))
+        );
+        assert!(Value::parse("1.5", &DataType::BigInt).is_err());
+    }
+
+    #[test]
+    fn test_value_parse_decimal() {
+        assert_eq!(
+            Value::parse("12.34", &DataType::Decimal),
+            Ok(Value::Decimal(1234))
+        );
+        assert_eq!(
+            Value::parse("0.05", &DataType::Decimal),
+            Ok(Value::Decimal(5))
+        );
+        assert_eq!(
+            Value::parse("7", &DataType::Decimal),
+            Ok(Value::Decimal(700))
+        );
+        assert_eq!(
+            Value::parse("-1.5", &DataType::Decimal),
+            Ok(Value::Decimal(-150))
+        );
+        assert!(Value::parse("1.234", &DataType::Decimal).is_err());
+        assert!(Value::parse("1.2.3", &DataType::Decimal).is_err());
+        assert!(Value::parse(".", &DataType::Decimal).is_err());
+    }
+
+    #[test]
+    fn test_value_parse_varchar() {
+        assert_eq!(
+            Value::parse("BUILDING", &DataType::Varchar(10)),
+            Ok(Value::String("BUILDING".to_string()))
+        );
+        assert!(matches!(
+            Value::parse("AUTOMOBILE!", &DataType::Varchar(10)),
+            Err(ParseError::TooLong { max_len: 10, .. })
+        ));
+
+        // Truncation is opt-in
+        assert_eq!(
+            Value::parse_truncating("AUTOMOBILE!", &DataType::Varchar(5)),
+            Ok(Value::String("AUTOM".to_string()))
+        );
+        assert_eq!(
+            Value::parse_truncating("", &DataType::Varchar(5)),
+            Ok(Value::Null)
+        );
+    }
+
+    #[test]
+    fn test_table_insert_row_varchar_length() {
+        let mut table = Table::new(
+            "nation".to_string(),
+            vec![Column::new("n_name".to_string(), DataType::Varchar(5))],
+        );
+
+        table
+            .insert_row(Row::new(vec![Value::String("PERU".to_string())]))
+            .unwrap();
+        assert_eq!(
+            table.insert_row(Row::new(vec![Value::String("GERMANY".to_string())])),
+            Err(TableBuildError::TypeMismatch {
+                row: 1,
+                column: "n_name".to_string(),
+                data_type: DataType::Varchar(5),
+                value: Value::String("GERMANY".to_string()),
+            })
+        );
+        assert!(matches!(
+            table.insert_row(Row::new(vec![])),
+            Err(TableBuildError::ArityMismatch { .. })
+        ));
+        assert_eq!(table.num_rows(), 1);
+    }
+
+    #[test]
+    fn test_value_parse_date() {
+        assert_eq!(
+            Value::parse("1970-01-01", &DataType::Date),
+            Ok(Value::Date(0))
+        );
+        assert_eq!(
+            Value::parse("1998-12-01", &DataType::Date),
+            Ok(Value::Date(912_470_400))
+        );
+        assert_eq!(
+            Value::parse("2000-02-29", &DataType::Date),
+            Ok(Value::Date(951_782_400))
+        );
+        assert_eq!(
+            Value::parse("800000000", &DataType::Date),
+            Ok(Value::Date(800_000_000))
+        );
+        assert!(Value::parse("1999-02-29", &DataType::Date).is_err());
+        assert!(Value::parse("1998-13-01", &DataType::Date).is_err());
+        assert!(Value::parse("yesterday", &DataType::Date).is_err());
+    }
+
+    #[test]
+    fn test_value_parse_boolean() {
+        assert_eq!(
+            Value::parse("true", &DataType::Boolean),
+            Ok(Value::Boolean(true))
+        );
+        assert_eq!(
+            Value::parse("0", &DataType::Boolean),
+            Ok(Value::Boolean(false))
+        );
+        assert_eq!(
+            Value::parse("1", &DataType::Boolean),
+            Ok(Value::Boolean(true))
+        );
+        assert!(Value::parse("yes", &DataType::Boolean).is_err());
+    }
+
+    #[test]
+    fn test_value_parse_empty_is_null() {
+        assert_eq!(Value::parse("", &DataType::Integer), Ok(Value::Null));
+        assert_eq!(Value::parse("", &DataType::Varchar(5)), Ok(Value::Null));
+    }
+
     #[test]
     fn test_value_to_field() {
         // Test Integer
@@ -343,10 +1449,37 @@ mod tests {
         let field = bool_val.to_field();
         assert_eq!(field, Field::from(0u64));
 
-        // Test Null
+        // Test Null (sentinel, distinct from zero)
         let null_val = Value::Null;
         let field = null_val.to_field();
-        assert_eq!(field, Field::zero());
+        assert_eq!(field, Value::null_field());
+        assert_ne!(field, Value::Integer(0).to_field());
+        assert!(Value::is_null_field(&field));
+    }
+
+    #[test]
+    fn test_value_string_to_field_distribution() {
+        let fields: Vec<Field> = (0..1000)
+            .map(|i| Value::String(format!("customer#{:09}", i)).to_field())
+            .collect();
+
+        // No string collapses to zero or NULL, and no two strings collide
+        assert!(fields.iter().all(|f| *f != Field::zero()));
+        assert!(fields.iter().all(|f| !Value::is_null_field(f)));
+        let distinct: std::collections::HashSet<[u8; 32]> =
+            fields.iter().map(|f| f.to_bytes()).collect();
+        assert_eq!(distinct.len(), fields.len());
+
+        // Low bits are roughly balanced and high bytes take many values
+        let odd = fields.iter().filter(|f| f.to_bytes()[0] & 1 == 1).count();
+        assert!((400..600).contains(&odd), "odd count {}", odd);
+        let top_bytes: std::collections::HashSet<u8> =
+            fields.iter().map(|f| f.to_bytes()[31]).collect();
+        assert!(
+            top_bytes.len() > 40,
+            "{} distinct top bytes",
+            top_bytes.len()
+        );
     }
 
     #[test]
@@ -366,6 +1499,61 @@ mod tests {
         assert_eq!(value, Value::Boolean(false));
     }
 
+    #[test]
+    fn test_value_from_aggregate() {
+        assert_eq!(
+            Value::from_aggregate(1275, &DataType::Decimal),
+            Value::Decimal(1275)
+        );
+        assert_eq!(
+            Value::from_aggregate(42, &DataType::Integer),
+            Value::Integer(42)
+        );
+
+        // INTEGER sums overflowing 32 bits widen to BIGINT
+        let big = i32::MAX as i64 + 1;
+        assert_eq!(
+            Value::from_aggregate(big, &DataType::Integer),
+            Value::BigInt(big)
+        );
+        assert_eq!(Value::from_aggregate(7, &DataType::Date), Value::BigInt(7));
+    }
+
+    #[test]
+    fn test_value_cast() {
+        // Promotion along INTEGER → BIGINT → DECIMAL
+        assert_eq!(
+            Value::Integer(5).cast(&DataType::BigInt),
+            Some(Value::BigInt(5))
+        );
+        assert_eq!(
+            Value::Integer(5).cast(&DataType::Decimal),
+            Some(Value::Decimal(500))
+        );
+        assert_eq!(
+            Value::BigInt(-7).cast(&DataType::Decimal),
+            Some(Value::Decimal(-700))
+        );
+        assert_eq!(
+            Value::Integer(5).cast(&DataType::Integer),
+            Some(Value::Integer(5))
+        );
+        assert_eq!(Value::Null.cast(&DataType::Decimal), Some(Value::Null));
+
+        // Narrowing is rejected, even for values that fit
+        assert_eq!(Value::BigInt(5).cast(&DataType::Integer), None);
+        assert_eq!(Value::Decimal(500).cast(&DataType::BigInt), None);
+        assert_eq!(Value::Decimal(500).cast(&DataType::Integer), None);
+        assert_eq!(Value::BigInt(i64::MAX).cast(&DataType::Decimal), None);
+
+        // Non-numeric conversions are rejected
+        assert_eq!(Value::Integer(1).cast(&DataType::Boolean), None);
+        assert_eq!(
+            Value::String("5".to_string()).cast(&DataType::Integer),
+            None
+        );
+    }
+
     #[test]
     fn test_query_result() {
         let mut result = QueryResult::new(vec!["id".to_string(), "name".to_string()]);
@@ -384,6 +1572,26 @@ mod tests {
         assert_eq!(result.num_rows(), 2);
     }
 
+    #[test]
+    fn test_row_to_fields() {
+        let row = Row::new(vec![
+            Value::Integer(-3),
+            Value::BigInt(1 << 40),
+            Value::Decimal(1250),
+            Value::String("BUILDING".to_string()),
+            Value::Date(19000),
+            Value::Boolean(true),
+            Value::Null,
+        ]);
+
+        let expected: Vec<Field> = row.values.iter().map(|v| v.to_field()).collect();
+        assert_eq!(row.to_fields(), expected);
+        for (i, field) in expected.iter().enumerate() {
+            assert_eq!(row.column_field(i), Some(*field));
+        }
+        assert_eq!(row.column_field(row.values.len()), None);
+    }
+
     #[test]
     fn test_row_get_value() {
         let table = Table::new(
@@ -410,4 +1618,22 @@ mod tests {
             Some(&Value::String("Alice".to_string()))
         );
     }
+
+    #[test]
+    fn test_proof_json_round_trip() {
+        let mut proof = Proof::new(vec![1, 2, 3], vec![Field::from(7u64), Field::zero()]);
+        proof.strip_trailing_zero_inputs();
+
+        let read = Proof::from_json(&proof.to_json().unwrap()).unwrap();
+        assert_eq!(read.proof_bytes, proof.proof_bytes);
+        assert_eq!(read.padded_public_inputs(), proof.padded_public_inputs());
+        assert_eq!(read.size(), 3);
+
+        // Public inputs must be canonical field elements
+        let json = proof
+            .to_json()
+            .unwrap()
+            .replace(&hex::encode(Field::from(7u64).to_bytes()), &"ff".repeat(32));
+        assert!(Proof::from_json(&json).is_err());
+    }
 }
@@ -0,0 +1,223 @@
+//! Composite keys
+//!
+//! This module packs several key values into a single field element, for
+//! gates comparing rows by more than one column (multi-column sort, group-by
+//! and join keys).
+//!
+//! # Layout
+//!
+//! Each value gets an explicit bit width, and the first value is the most
+//! significant:
+//!
+//! ```text
+//! composite = v1 · 2^(w2 + ... + wk) + v2 · 2^(w3 + ... + wk) + ... + vk
+//! ```
+//!
+//! Composites compare like the tuples they pack, as long as every value fits
+//! its width and the widths sum to at most [`MAX_COMPOSITE_BITS`]. Values or
+//! widths that would break this are rejected instead of wrapping around the
+//! field modulus.
+//!
+//! # Example
+//!
+//! ```rust
+//! use nzengi_db::types::keys;
+//! use nzengi_db::types::Value;
+//!
+//! let key = keys::composite(&[Value::Integer(7), Value::Boolean(true)], &[32, 1])?;
+//! assert_eq!(key, keys::pack(&[7, 1], &[32, 1])?);
+//! # Ok::<(), keys::KeyError>(())
+//! ```
+
+use super::Value;
+use ff::Field as _;
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use thiserror::Error;
+
+/// Maximum total bit width of a composite key
+///
+/// The BN256 scalar field holds every 253-bit integer, so composites up to
+/// this width never wrap around the modulus.
+pub const MAX_COMPOSITE_BITS: usize = 253;
+
+/// Error packing values into a composite key
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum KeyError {
+    /// The number of values and widths differ
+    #[error("{values} key values but {widths} widths")]
+    ArityMismatch {
+        /// Number of values
+        values: usize,
+
+        /// Number of widths
+        widths: usize,
+    },
+
+    /// The widths add up to more than `MAX_COMPOSITE_BITS`
+    #[error("composite key of {bits} bits exceeds {MAX_COMPOSITE_BITS} bits")]
+    TooWide {
+        /// Total bit width
+        bits: usize,
+    },
+
+    /// A value can't be encoded as an unsigned integer
+    #[error("key value {index} ({value}) is not a non-negative integer")]
+    Unsupported {
+        /// Position of the value
+        index: usize,
+
+        /// The value
+        value: Value,
+    },
+
+    /// A value doesn't fit its bit width
+    #[error("key value {index} ({value}) overflows {width} bits")]
+    Overflow {
+        /// Position of the value
+        index: usize,
+
+        /// The value
+        value: u64,
+
+        /// Bit width of the value
+        width: usize,
+    },
+}
+
+/// Pack typed values into a composite key
+///
+/// Integers, big integers and decimals must be non-negative; dates use their
+/// timestamp and booleans are 0 or 1. Strings and `NULL` have no order
+/// preserving integer encoding and are rejected.
+///
+/// # Arguments
+/// * `values` - Key values, most significant first
+/// * `widths` - Bit width of each value
+///
+/// # Returns
+/// `Ok(Field)` composite key, `Err(KeyError)` if a value is unsupported or
+/// overflows its width, or the widths don't match the values
+pub fn composite(values: &[Value], widths: &[usize]) -> Result<Field, KeyError> {
+    let encoded = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            unsigned(value).ok_or_else(|| KeyError::Unsupported {
+                index,
+                value: value.clone(),
+            })
+        })
+        .collect::<Result<Vec<u64>, _>>()?;
+    pack(&encoded, widths)
+}
+
+/// Pack unsigned integers into a composite key
+///
+/// # Arguments
+/// * `values` - Key values, most significant first
+/// * `widths` - Bit width of each value
+///
+/// # Returns
+/// `Ok(Field)` composite key, `Err(KeyError)` if a value overflows its width
+/// or the widths don't match the values
+pub fn pack(values: &[u64], widths: &[usize]) -> Result<Field, KeyError> {
+    if values.len() != widths.len() {
+        return Err(KeyError::ArityMismatch {
+            values: values.len(),
+            widths: widths.len(),
+        });
+    }
+    let bits: usize = widths.iter().sum();
+    if bits > MAX_COMPOSITE_BITS {
+        return Err(KeyError::TooWide { bits });
+    }
+
+    let mut composite = Field::zero();
+    for (index, (&value, &width)) in values.iter().zip(widths).enumerate() {
+        if width < 64 && value >> width != 0 {
+            return Err(KeyError::Overflow {
+                index,
+                value,
+                width,
+            });
+        }
+        // Shift by the width, at most 63 bits at a time
+        let mut shift = width;
+        while shift > 0 {
+            let step = shift.min(63);
+            composite *= Field::from(1u64 << step);
+            shift -= step;
+        }
+        composite += Field::from(value);
+    }
+
+    Ok(composite)
+}
+
+/// Unsigned integer encoding of a key value
+fn unsigned(value: &Value) -> Option<u64> {
+    match value {
+        Value::Integer(v) => u64::try_from(*v).ok(),
+        Value::BigInt(v) | Value::Decimal(v) => u64::try_from(*v).ok(),
+        Value::Date(v) => Some(*v),
+        Value::Boolean(v) => Some(*v as u64),
+        Value::String(_) | Value::Null => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_three_keys() {
+        let key = composite(
+            &[Value::Integer(3), Value::Boolean(true), Value::Date(20)],
+            &[32, 1, 48],
+        )
+        .unwrap();
+        let expected = Field::from(3u64) * Field::from(1u64 << 49)
+            + Field::from(1u64 << 48)
+            + Field::from(20u64);
+        assert_eq!(key, expected);
+
+        // Composites order like the tuples they pack
+        let smaller = composite(
+            &[
+                Value::Integer(2),
+                Value::Boolean(true),
+                Value::Date(u32::MAX as u64),
+            ],
+            &[32, 1, 48],
+        )
+        .unwrap();
+        assert_eq!(
+            crate::field::FieldUtils::cmp(&smaller, &key),
+            std::cmp::Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_composite_overflow() {
+        assert_eq!(
+            composite(&[Value::Integer(1), Value::BigInt(256)], &[32, 8]),
+            Err(KeyError::Overflow {
+                index: 1,
+                value: 256,
+                width: 8
+            })
+        );
+        assert_eq!(
+            pack(&[1, 2, 3, 4], &[64; 4]),
+            Err(KeyError::TooWide { bits: 256 })
+        );
+        assert!(matches!(
+            composite(&[Value::Integer(-1)], &[32]),
+            Err(KeyError::Unsupported { index: 0, .. })
+        ));
+        assert!(matches!(
+            pack(&[1], &[8, 8]),
+            Err(KeyError::ArityMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,234 @@
+//! Benchmark harness
+//!
+//! This module provides a programmatic harness for measuring the core
+//! performance claims of nzengiDB: commitment time, proving time, and
+//! O(log n) proof size and verification time over the IPA scheme.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use nzengi_db::utils::bench::{benchmark_commitment, benchmark_ipa};
+//!
+//! let results = benchmark_ipa(&[10, 12])?;
+//! for result in &results {
+//!     println!("k={}: proof {} bytes", result.k, result.proof_size);
+//! }
+//!
+//! // Database commitment time by table size
+//! for (rows, time) in benchmark_commitment(&[60_000, 120_000], 17)? {
+//!     println!("{} rows: {:?}", rows, time);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::circuit::NzengiCircuit;
+use crate::commitment::{DatabaseCommitment, IPAParams, VectorCommitment};
+use crate::gates::selection::{Comparison, Operand, Predicate};
+use crate::proof::{Prover, Verifier};
+use crate::types::{Column, DataType, Row, Table, Value};
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Benchmark result for a single k value
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Log2 of the maximum number of rows
+    pub k: u32,
+
+    /// Number of rows committed and proven
+    pub num_rows: usize,
+
+    /// Time to commit to a column of `num_rows` values
+    pub commit_time: Duration,
+
+    /// Time to generate proving and verifying keys
+    pub keygen_time: Duration,
+
+    /// Time to create the proof
+    pub prove_time: Duration,
+
+    /// Time to verify the proof
+    pub verify_time: Duration,
+
+    /// Proof size in bytes
+    pub proof_size: usize,
+
+    /// Whether the proof verified
+    pub verified: bool,
+
+    /// Constraints contributed by each enabled gate, as (gate name, count)
+    pub constraint_profile: Vec<(String, usize)>,
+}
+
+/// Benchmark commitment, proving, and verification for each k
+///
+/// For each k, a column of `2^(k-1)` values is committed, and a COUNT(*)
+/// proof of a comparison over the same rows is created and verified. The
+/// comparison's lookup table needs k of at least 9.
+///
+/// # Arguments
+/// * `k_values` - Log2 of the maximum number of rows to benchmark
+///
+/// # Returns
+/// `Ok(Vec<BenchResult>)` with one result per k, `Err` if committing or
+/// proving fails
+pub fn benchmark_ipa(k_values: &[u32]) -> Result<Vec<BenchResult>, Box<dyn std::error::Error>> {
+    let mut results = Vec::with_capacity(k_values.len());
+
+    for &k in k_values {
+        let params = IPAParams::new(k);
+        let num_rows = 1usize << (k - 1);
+
+        // Commitment
+        let values: Vec<Field> = (0..num_rows as u64).map(Field::from).collect();
+        let start = Instant::now();
+        let _commitment = VectorCommitment::commit(values.clone(), &params)?;
+        let commit_time = start.elapsed();
+
+        // Circuit: COUNT(*) of the values below half the row count
+        let count = Field::from((num_rows / 2) as u64);
+        let circuit = NzengiCircuit::new()
+            .with_table(num_rows, vec![values])
+            .with_predicate(Predicate::Compare {
+                column: 0,
+                comparison: Comparison::Less,
+                rhs: Operand::Constant(count),
+                negated: false,
+            })
+            .with_count_output(count);
+        let public_inputs = circuit.public_inputs();
+
+        // Constraint profile of the circuit's gates
+        let config = <NzengiCircuit as Circuit<Field>>::configure(&mut ConstraintSystem::default());
+        let constraint_profile = config.constraint_profile(&HashMap::from([
+            ("selection", num_rows),
+            ("count", num_rows),
+        ]));
+
+        // Key generation
+        let prover = Prover::new(&params);
+        let start = Instant::now();
+        let (pk, vk) = prover.generate_keys(&circuit)?;
+        let keygen_time = start.elapsed();
+
+        // Proving
+        let start = Instant::now();
+        let proof = prover.create_proof(&pk, &circuit, &public_inputs)?;
+        let prove_time = start.elapsed();
+
+        // Verification
+        let verifier = Verifier::new(&params);
+        let start = Instant::now();
+        let verified = verifier.verify(&vk, &proof, &public_inputs)?;
+        let verify_time = start.elapsed();
+
+        results.push(BenchResult {
+            k,
+            num_rows,
+            commit_time,
+            keygen_time,
+            prove_time,
+            verify_time,
+            proof_size: proof.size(),
+            verified,
+            constraint_profile,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Benchmark database commitment time for each table size
+///
+/// For each row count, a synthetic lineitem-like table (quantity, price,
+/// discount, and ship date columns) is committed with `commit_database`,
+/// reproducing the commitment performance table in the `commitment` module.
+///
+/// # Arguments
+/// * `row_counts` - Number of rows of each table to commit
+/// * `k` - Log2 of the maximum number of rows
+///
+/// # Returns
+/// `Ok(Vec<(usize, Duration)>)` with the commitment time per row count, in
+/// order, `Err` if a row count exceeds `2^k`
+pub fn benchmark_commitment(
+    row_counts: &[usize],
+    k: u32,
+) -> Result<Vec<(usize, Duration)>, Box<dyn std::error::Error>> {
+    let params = IPAParams::new(k);
+    let mut results = Vec::with_capacity(row_counts.len());
+
+    for &num_rows in row_counts {
+        let table = synthetic_table(num_rows);
+
+        let start = Instant::now();
+        let _commitment = DatabaseCommitment::commit_database(&[table], &params)?;
+        results.push((num_rows, start.elapsed()));
+    }
+
+    Ok(results)
+}
+
+/// Build a lineitem-like table with deterministic values
+fn synthetic_table(num_rows: usize) -> Table {
+    let mut table = Table::new(
+        "lineitem".to_string(),
+        vec![
+            Column::new("l_quantity".to_string(), DataType::Integer),
+            Column::new("l_extendedprice".to_string(), DataType::Decimal),
+            Column::new("l_discount".to_string(), DataType::Decimal),
+            Column::new("l_shipdate".to_string(), DataType::Date),
+        ],
+    );
+    table.rows = (0..num_rows as i64)
+        .map(|i| {
+            Row::new(vec![
+                Value::Integer((i % 50 + 1) as i32),
+                Value::Decimal(100_000 + i * 37 % 10_000_000),
+                Value::Decimal(i % 11),
+                Value::Date(694_224_000 + (i as u64 % 2_500) * 86_400),
+            ])
+        })
+        .collect();
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmark_ipa() {
+        let results = benchmark_ipa(&[9, 10]).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].k, 9);
+        assert_eq!(results[1].k, 10);
+        for result in &results {
+            assert_eq!(result.num_rows, 1 << (result.k - 1));
+            assert!(result.proof_size > 0);
+            assert!(result.verify_time > Duration::ZERO);
+
+            let count = result
+                .constraint_profile
+                .iter()
+                .find(|(gate, _)| gate == "count")
+                .map(|(_, constraints)| *constraints);
+            assert_eq!(count, Some(2 * result.num_rows + 1));
+        }
+    }
+
+    #[test]
+    fn test_benchmark_commitment() {
+        let results = benchmark_commitment(&[4, 16, 64], 7).unwrap();
+
+        let rows: Vec<usize> = results.iter().map(|(rows, _)| *rows).collect();
+        assert_eq!(rows, vec![4, 16, 64]);
+        assert!(results.iter().all(|(_, time)| *time > Duration::ZERO));
+
+        // Tables larger than 2^k can't be committed
+        assert!(benchmark_commitment(&[129], 7).is_err());
+    }
+}
@@ -16,6 +16,9 @@
 //! println!("Time: {}", formatted); // "1.23s"
 //! ```
 
+use crate::circuit::builder::CircuitBuilder;
+use crate::query::planner::ExecutionPlan;
+use std::collections::HashMap;
 use std::time::Duration;
 
 /// Helper functions
@@ -163,12 +166,54 @@ impl Helpers {
         }
         Some(log)
     }
+
+    /// Recommend the smallest parameter size `k` for a query
+    ///
+    /// `2^k` must exceed the worst-case rows of the query circuit
+    /// (`CircuitBuilder::estimate_rows`) and hold every column of the
+    /// queried tables for the database commitment.
+    ///
+    /// # Arguments
+    /// * `plan` - Execution plan for the query
+    /// * `table_sizes` - Number of rows of each table
+    ///
+    /// # Returns
+    /// The recommended k
+    ///
+    /// # Example
+    /// ```
+    /// use nzengi_db::query::{QueryParser, QueryPlanner};
+    /// use nzengi_db::utils::Helpers;
+    /// use std::collections::HashMap;
+    ///
+    /// let ast = QueryParser::new().parse("SELECT COUNT(*) FROM lineitem")?;
+    /// let plan = QueryPlanner::new().plan(&ast)?;
+    /// let sizes = HashMap::from([("lineitem".to_string(), 60_000)]);
+    /// let k = Helpers::recommend_k(&plan, &sizes);
+    /// assert!(1usize << k >= 60_000);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn recommend_k(plan: &ExecutionPlan, table_sizes: &HashMap<String, usize>) -> u32 {
+        let circuit_rows = CircuitBuilder::estimate_rows(plan, table_sizes);
+        let k_circuit = (circuit_rows + 1).next_power_of_two().trailing_zeros();
+
+        let max_table_rows = plan
+            .tables
+            .iter()
+            .filter_map(|table| table_sizes.get(table))
+            .copied()
+            .max()
+            .unwrap_or(0);
+        let k_commitment = max_table_rows.next_power_of_two().trailing_zeros();
+
+        k_circuit.max(k_commitment)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
+    use crate::test_util::plan;
 
     #[test]
     fn test_format_bytes() {
@@ -221,6 +266,32 @@ mod tests {
         assert!(!Helpers::is_power_of_2(6));
     }
 
+    #[test]
+    fn test_recommend_k() {
+        let count = plan("SELECT COUNT(*) FROM lineitem");
+        let scan =
+            plan("SELECT l_quantity FROM lineitem WHERE l_quantity > 10 ORDER BY l_quantity");
+
+        let tiny = HashMap::from([("lineitem".to_string(), 4)]);
+        let large = HashMap::from([("lineitem".to_string(), 60_000)]);
+        let small_k = Helpers::recommend_k(&count, &tiny);
+        let large_k = Helpers::recommend_k(&scan, &large);
+        assert!(small_k <= 6, "tiny query needs k = {}", small_k);
+        assert!(large_k > small_k);
+
+        // 2^k exceeds the worst-case rows, and 2^(k - 1) doesn't
+        let rows = CircuitBuilder::estimate_rows(&scan, &large);
+        assert!(1usize << large_k > rows);
+        assert!(1usize << (large_k - 1) <= rows);
+
+        // Tables the query doesn't read don't count
+        let other = HashMap::from([("orders".to_string(), 60_000)]);
+        assert_eq!(
+            Helpers::recommend_k(&count, &other),
+            Helpers::recommend_k(&count, &HashMap::new())
+        );
+    }
+
     #[test]
     fn test_log2() {
         assert_eq!(Helpers::log2(1), Some(0));
@@ -1,6 +1,7 @@
 //! Utilities module
 //!
 //! This module provides utility functions for common operations:
+//! - Benchmark harness
 //! - Helper functions
 //! - Logging utilities
 //! - Common utilities
@@ -20,9 +21,11 @@
 //! let formatted = Helpers::format_bytes(1024);
 //! ```
 
+pub mod bench;
 pub mod helpers;
 pub mod logger;
 
 // Re-export main types for convenience
+pub use bench::{benchmark_commitment, benchmark_ipa, BenchResult};
 pub use helpers::Helpers;
 pub use logger::Logger;
@@ -0,0 +1,66 @@
+//! Integration tests for the `query` CLI command
+
+#![cfg(feature = "cli")]
+
+use nzengi_db::commitment::IPAParams;
+use nzengi_db::database::{Database, DatabaseStorage, Schema};
+use nzengi_db::types::{Column, DataType, Row, Table, Value};
+use std::process::Command;
+
+#[test]
+fn test_cli_query_strict_validation() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let params = IPAParams::new(10);
+    params.save(&path("params.bin")).unwrap();
+
+    let mut table = Table::new(
+        "lineitem".to_string(),
+        vec![
+            Column::new("l_orderkey".to_string(), DataType::BigInt),
+            Column::new("l_quantity".to_string(), DataType::Integer),
+        ],
+    );
+    for (key, quantity) in [(1, 5), (2, 12), (1, 30)] {
+        table
+            .rows
+            .push(Row::new(vec![Value::BigInt(key), Value::Integer(quantity)]));
+    }
+    let mut schema = Schema::new("tpch".to_string());
+    schema.add_table(table).unwrap();
+    DatabaseStorage::new()
+        .save(&Database::new(schema), &path("db.json"))
+        .unwrap();
+
+    let query = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_nzengi_db"))
+            .args(["query", "--query"])
+            .arg("SELECT l_orderkey, SUM(l_quantity) FROM lineitem GROUP BY l_orderkey")
+            .args(["--database", &path("db.json")])
+            .args(["--params", &path("params.bin")])
+            .args(["--commitment", &path("commitment.bin")])
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    // The group-by and aggregation witnesses are re-checked while proving
+    let output = query(&["--strict"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Strict witness validation enabled"));
+    assert!(stdout.contains("1 | 35"));
+    assert!(stdout.contains("2 | 12"));
+    assert!(stdout.contains("Proof size:"));
+
+    // Without the flag, the checks are skipped
+    let output = query(&[]);
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Strict witness validation"));
+}
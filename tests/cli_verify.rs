@@ -0,0 +1,137 @@
+//! Integration tests for the `verify` CLI command
+
+#![cfg(feature = "cli")]
+
+use halo2_proofs::halo2curves::bn256::Fr as Field;
+use nzengi_db::circuit::NzengiCircuit;
+use nzengi_db::commitment::{DatabaseCommitment, IPAParams};
+use nzengi_db::gates::selection::{Comparison, Operand, Predicate};
+use nzengi_db::proof::Prover;
+use nzengi_db::types::{Column, DataType, Row, Table, Value};
+use std::process::Command;
+
+#[test]
+fn test_cli_verify_reports_proof_and_commitment() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let params = IPAParams::new(10);
+    params.save(&path("params.bin")).unwrap();
+
+    let mut table = Table::new(
+        "lineitem".to_string(),
+        vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+    );
+    for q in [5, 12, 30] {
+        table.rows.push(Row::new(vec![Value::Integer(q)]));
+    }
+    let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
+    std::fs::write(path("commitment.bin"), commitment.to_bytes()).unwrap();
+
+    // COUNT(*) WHERE l_quantity > 1
+    let quantities = [5u64, 12, 30].map(Field::from).to_vec();
+    let circuit = NzengiCircuit::new()
+        .with_table(quantities.len(), vec![quantities])
+        .with_predicate(Predicate::Compare {
+            column: 0,
+            comparison: Comparison::Greater,
+            rhs: Operand::Constant(Field::from(1u64)),
+            negated: false,
+        })
+        .with_count_output(Field::from(3u64))
+        .with_commitment_hash(commitment.hash_field());
+    let prover = Prover::new(&params);
+    let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+    prover.write_vk(&vk, &circuit, path("query.vk")).unwrap();
+    let proof = prover
+        .create_proof(&pk, &circuit, &circuit.public_inputs())
+        .unwrap();
+    std::fs::write(path("proof.json"), proof.to_json().unwrap()).unwrap();
+
+    let verify = |public_inputs: &str| {
+        Command::new(env!("CARGO_BIN_EXE_nzengi_db"))
+            .args(["verify", "--proof", &path("proof.json")])
+            .args(["--params", &path("params.bin")])
+            .args(["--commitment", &path("commitment.bin")])
+            .args(["--vk", &path("query.vk")])
+            .args(["--public-inputs", public_inputs])
+            .output()
+            .unwrap()
+    };
+
+    let output = verify("3");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains(&format!("Proof size: {} bytes", proof.size())));
+    assert!(stdout.contains("Public inputs: 2"));
+    assert!(stdout.contains(&commitment.commitment_hash));
+    assert!(stdout.contains("Verification time:"));
+    assert!(stdout.contains("Proof is valid"));
+
+    // The proof doesn't verify against a result other than the proven one
+    let output = verify("4");
+    assert!(!output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Proof is valid"));
+}
+
+#[test]
+fn test_cli_verify_avg_proof() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+    let params = IPAParams::new(11);
+    params.save(&path("params.bin")).unwrap();
+
+    let mut table = Table::new(
+        "lineitem".to_string(),
+        vec![Column::new("l_quantity".to_string(), DataType::Integer)],
+    );
+    for q in [5, 12, 30] {
+        table.rows.push(Row::new(vec![Value::Integer(q)]));
+    }
+    let commitment = DatabaseCommitment::commit_database(&[table], &params).unwrap();
+    std::fs::write(path("commitment.bin"), commitment.to_bytes()).unwrap();
+
+    // COUNT(*) and the truncated AVG of one group: 47 / 3 = 15, remainder 2
+    let quantities = [5u64, 12, 30].map(Field::from).to_vec();
+    let circuit = NzengiCircuit::new()
+        .with_table(quantities.len(), vec![quantities.clone()])
+        .with_count_output(Field::from(3u64))
+        .with_aggregation(
+            quantities,
+            [1u64, 1, 0].map(Field::from).to_vec(),
+            vec![Field::from(0u64); 3],
+            vec![Field::from(2u64); 3],
+        )
+        .with_truncated_avg()
+        .with_commitment_hash(commitment.hash_field());
+    assert!(circuit.gates().avg_remainder);
+
+    // The key file records the AVG remainder gate, which an empty circuit
+    // doesn't configure
+    let prover = Prover::new(&params);
+    let (pk, vk) = prover.generate_keys(&circuit).unwrap();
+    prover.write_vk(&vk, &circuit, path("avg.vk")).unwrap();
+    let proof = prover
+        .create_proof(&pk, &circuit, &circuit.public_inputs())
+        .unwrap();
+    std::fs::write(path("proof.json"), proof.to_json().unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_nzengi_db"))
+        .args(["verify", "--proof", &path("proof.json")])
+        .args(["--params", &path("params.bin")])
+        .args(["--commitment", &path("commitment.bin")])
+        .args(["--vk", &path("avg.vk")])
+        .args(["--public-inputs", "3"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Proof is valid"));
+}